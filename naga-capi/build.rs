@@ -0,0 +1,22 @@
+fn main() {
+    // Compile the C test program that `tests/c_api.rs` runs, only when that
+    // test is enabled. It is never linked into the library.
+    #[cfg(feature = "c-test")]
+    compile_c_test();
+}
+
+#[cfg(feature = "c-test")]
+fn compile_c_test() {
+    println!("cargo:rerun-if-changed=include/naga.h");
+    println!("cargo:rerun-if-changed=tests/c/api.c");
+    cc::Build::new()
+        .file("tests/c/api.c")
+        .include("include")
+        .warnings_into_errors(true)
+        .cargo_metadata(false)
+        .compile("naga_capi_c_test");
+    println!(
+        "cargo:rustc-link-search=native={}",
+        std::env::var("OUT_DIR").unwrap()
+    );
+}
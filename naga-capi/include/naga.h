@@ -0,0 +1,114 @@
+/*
+ * C API for the Naga shader translator.
+ *
+ * Every function returns a `NagaStatus`. Functions that can fail also take a
+ * `NagaBuffer **diagnostics` argument, which may be NULL; on failure, it
+ * receives a UTF-8 JSON array of the problems found. Each element has the
+ * schema of `naga::error::Diagnostic::emit_to_json`:
+ *
+ *     [
+ *       {
+ *         "severity": "error",
+ *         "code": "wgsl::parse",
+ *         "message": "expected ';', found '}'",
+ *         "labels": [
+ *           { "start": 72, "end": 73, "line": 5, "column": 1,
+ *             "message": "expected ';'" }
+ *         ],
+ *         "notes": []
+ *       }
+ *     ]
+ *
+ * `start` and `end` are byte offsets. `line` and `column` are 1-based, and
+ * only present when the source text of the module is known. Problems with
+ * the arguments have the code "invalid-argument", and panics "panic".
+ *
+ * Stages, flags and capabilities are passed as integers. Values this API
+ * doesn't know are rejected with NAGA_STATUS_INVALID_ARGUMENT.
+ *
+ * Every handle this API returns must be released with its `_free` function.
+ * Passing NULL to a `_free` function does nothing.
+ */
+
+#ifndef NAGA_H
+#define NAGA_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef enum NagaStatus {
+    NAGA_STATUS_OK = 0,
+    /* A required pointer was NULL, or a string was not valid UTF-8. */
+    NAGA_STATUS_INVALID_ARGUMENT = 1,
+    NAGA_STATUS_PARSE_ERROR = 2,
+    NAGA_STATUS_VALIDATION_ERROR = 3,
+    NAGA_STATUS_TRANSLATION_ERROR = 4,
+    /* Naga panicked. The handles passed in are still usable. */
+    NAGA_STATUS_PANIC = 5,
+} NagaStatus;
+
+/* Values for `stage` arguments. */
+#define NAGA_SHADER_STAGE_VERTEX 0u
+#define NAGA_SHADER_STAGE_FRAGMENT 1u
+#define NAGA_SHADER_STAGE_COMPUTE 2u
+
+/* Bits of `naga::valid::ValidationFlags`. */
+#define NAGA_VALIDATION_FLAGS_ALL 0x7Fu
+
+/* Bits of `naga::valid::Capabilities`. */
+#define NAGA_CAPABILITIES_NONE 0u
+#define NAGA_CAPABILITIES_ALL 0x1FFFFu
+
+/* A parsed shader module. */
+typedef struct NagaModule NagaModule;
+
+/* The result of validating a module, needed by the backends. */
+typedef struct NagaModuleInfo NagaModuleInfo;
+
+/* An owned byte buffer. */
+typedef struct NagaBuffer NagaBuffer;
+
+NagaStatus naga_parse_wgsl(const uint8_t *source, size_t length, NagaModule **module,
+                           NagaBuffer **diagnostics);
+NagaStatus naga_parse_glsl(uint32_t stage, const uint8_t *source, size_t length,
+                           NagaModule **module, NagaBuffer **diagnostics);
+/* `length` is in bytes, and must be a multiple of four. */
+NagaStatus naga_parse_spirv(const uint8_t *words, size_t length, NagaModule **module,
+                            NagaBuffer **diagnostics);
+void naga_module_free(NagaModule *module);
+
+NagaStatus naga_validate(const NagaModule *module, uint32_t flags, uint32_t capabilities,
+                         NagaModuleInfo **info, NagaBuffer **diagnostics);
+void naga_module_info_free(NagaModuleInfo *info);
+
+NagaStatus naga_write_wgsl(const NagaModule *module, const NagaModuleInfo *info,
+                           NagaBuffer **output, NagaBuffer **diagnostics);
+/* The output is SPIR-V words in native byte order. */
+NagaStatus naga_write_spirv(const NagaModule *module, const NagaModuleInfo *info,
+                            NagaBuffer **output, NagaBuffer **diagnostics);
+NagaStatus naga_write_msl(const NagaModule *module, const NagaModuleInfo *info,
+                          NagaBuffer **output, NagaBuffer **diagnostics);
+NagaStatus naga_write_hlsl(const NagaModule *module, const NagaModuleInfo *info,
+                           NagaBuffer **output, NagaBuffer **diagnostics);
+/* GLSL output holds a single entry point, named by the NUL-terminated
+ * `entry_point`. `version` is a GLSL version like 450, or an ES version like
+ * 310 if `es` is true. */
+NagaStatus naga_write_glsl(const NagaModule *module, const NagaModuleInfo *info,
+                           uint32_t stage, const char *entry_point, uint16_t version,
+                           bool es, NagaBuffer **output, NagaBuffer **diagnostics);
+
+/* Text output is not NUL-terminated. */
+const uint8_t *naga_buffer_data(const NagaBuffer *buffer);
+size_t naga_buffer_length(const NagaBuffer *buffer);
+void naga_buffer_free(NagaBuffer *buffer);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* NAGA_H */
@@ -0,0 +1,445 @@
+/*!
+A C API for Naga.
+
+This exposes parsing, validation, and translation to C and C++ programs. The
+declarations are in `include/naga.h`, which also describes the conventions
+shared by all the functions here.
+
+No panic unwinds out of this library: each function catches them and returns
+[`NagaStatus::Panic`] instead.
+*/
+
+// The requirements for calling these functions safely are in `naga.h`.
+#![allow(clippy::missing_safety_doc)]
+
+use std::{
+    ffi::{c_char, CStr},
+    panic::{self, AssertUnwindSafe},
+    ptr, slice,
+};
+
+use naga::{back, error::Diagnostic, front, valid};
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NagaStatus {
+    Ok = 0,
+    InvalidArgument = 1,
+    ParseError = 2,
+    ValidationError = 3,
+    TranslationError = 4,
+    Panic = 5,
+}
+
+/// Map a `NAGA_SHADER_STAGE_*` value from `naga.h` to a stage.
+fn shader_stage(stage: u32) -> Result<naga::ShaderStage, Failure> {
+    match stage {
+        0 => Ok(naga::ShaderStage::Vertex),
+        1 => Ok(naga::ShaderStage::Fragment),
+        2 => Ok(naga::ShaderStage::Compute),
+        _ => Err(Failure::invalid_argument("stage")),
+    }
+}
+
+/// A parsed module, and the source it came from, if it was text.
+pub struct NagaModule {
+    module: naga::Module,
+    source: Option<String>,
+}
+
+pub struct NagaModuleInfo {
+    info: valid::ModuleInfo,
+}
+
+pub struct NagaBuffer {
+    data: Box<[u8]>,
+}
+
+impl NagaBuffer {
+    fn into_raw(data: impl Into<Vec<u8>>) -> *mut Self {
+        Box::into_raw(Box::new(Self {
+            data: data.into().into_boxed_slice(),
+        }))
+    }
+}
+
+/// Why a call failed, with its diagnostics already rendered as JSON.
+struct Failure {
+    status: NagaStatus,
+    errors: Vec<String>,
+}
+
+impl Failure {
+    /// Report `diagnostics`, locating their labels in `source`, if known.
+    fn new(
+        status: NagaStatus,
+        diagnostics: impl IntoIterator<Item = Diagnostic>,
+        source: Option<&str>,
+    ) -> Self {
+        Self {
+            status,
+            errors: diagnostics
+                .into_iter()
+                .map(|diagnostic| diagnostic.emit_to_json(source))
+                .collect(),
+        }
+    }
+
+    /// Report a problem outside Naga itself, with the diagnostic code `code`.
+    fn message(status: NagaStatus, code: &'static str, message: impl ToString) -> Self {
+        Self::new(status, [Diagnostic::error(code, message)], None)
+    }
+
+    fn invalid_argument(name: &str) -> Self {
+        Self::message(
+            NagaStatus::InvalidArgument,
+            "invalid-argument",
+            format!("`{name}` is invalid"),
+        )
+    }
+
+    fn translation<E>(error: E) -> Self
+    where
+        for<'a> Diagnostic: From<&'a E>,
+    {
+        Self::new(
+            NagaStatus::TranslationError,
+            [Diagnostic::from(&error)],
+            None,
+        )
+    }
+}
+
+/// Run `body`, reporting its failure or panic through `diagnostics`.
+unsafe fn guard(
+    diagnostics: *mut *mut NagaBuffer,
+    body: impl FnOnce() -> Result<(), Failure>,
+) -> NagaStatus {
+    if !diagnostics.is_null() {
+        *diagnostics = ptr::null_mut();
+    }
+
+    let failure = match panic::catch_unwind(AssertUnwindSafe(body)) {
+        Ok(Ok(())) => return NagaStatus::Ok,
+        Ok(Err(failure)) => failure,
+        Err(payload) => {
+            let message = payload
+                .downcast_ref::<&str>()
+                .copied()
+                .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+                .unwrap_or("unknown panic");
+            Failure::message(NagaStatus::Panic, "panic", message)
+        }
+    };
+
+    if !diagnostics.is_null() {
+        let json = format!("[{}]", failure.errors.join(","));
+        *diagnostics = NagaBuffer::into_raw(json);
+    }
+    failure.status
+}
+
+/// Borrow `length` bytes at `data`, allowing a null pointer if `length` is zero.
+unsafe fn bytes<'a>(data: *const u8, length: usize) -> Result<&'a [u8], Failure> {
+    if length == 0 {
+        Ok(&[])
+    } else if data.is_null() {
+        Err(Failure::invalid_argument("source"))
+    } else {
+        Ok(slice::from_raw_parts(data, length))
+    }
+}
+
+unsafe fn text<'a>(data: *const u8, length: usize) -> Result<&'a str, Failure> {
+    std::str::from_utf8(bytes(data, length)?).map_err(|_| Failure::invalid_argument("source"))
+}
+
+unsafe fn reference<'a, T>(pointer: *const T, name: &str) -> Result<&'a T, Failure> {
+    pointer
+        .as_ref()
+        .ok_or_else(|| Failure::invalid_argument(name))
+}
+
+unsafe fn give_module(out: *mut *mut NagaModule, module: naga::Module, source: Option<&str>) {
+    *out = Box::into_raw(Box::new(NagaModule {
+        module,
+        source: source.map(str::to_string),
+    }));
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn naga_parse_wgsl(
+    source: *const u8,
+    length: usize,
+    module: *mut *mut NagaModule,
+    diagnostics: *mut *mut NagaBuffer,
+) -> NagaStatus {
+    guard(diagnostics, || {
+        if module.is_null() {
+            return Err(Failure::invalid_argument("module"));
+        }
+        let source = text(source, length)?;
+        let parsed = front::wgsl::parse_str(source).map_err(|error| {
+            Failure::new(
+                NagaStatus::ParseError,
+                [Diagnostic::from(&error)],
+                Some(source),
+            )
+        })?;
+        give_module(module, parsed, Some(source));
+        Ok(())
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn naga_parse_glsl(
+    stage: u32,
+    source: *const u8,
+    length: usize,
+    module: *mut *mut NagaModule,
+    diagnostics: *mut *mut NagaBuffer,
+) -> NagaStatus {
+    guard(diagnostics, || {
+        if module.is_null() {
+            return Err(Failure::invalid_argument("module"));
+        }
+        let source = text(source, length)?;
+        let options = front::glsl::Options::from(shader_stage(stage)?);
+        let parsed = front::glsl::Frontend::default()
+            .parse(&options, source)
+            .map_err(|error| {
+                Failure::new(
+                    NagaStatus::ParseError,
+                    error.errors.iter().map(Diagnostic::from),
+                    Some(source),
+                )
+            })?;
+        give_module(module, parsed, Some(source));
+        Ok(())
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn naga_parse_spirv(
+    words: *const u8,
+    length: usize,
+    module: *mut *mut NagaModule,
+    diagnostics: *mut *mut NagaBuffer,
+) -> NagaStatus {
+    guard(diagnostics, || {
+        if module.is_null() {
+            return Err(Failure::invalid_argument("module"));
+        }
+        let words = bytes(words, length)?;
+        let parsed = front::spv::parse_u8_slice(words, &front::spv::Options::default()).map_err(
+            |error| Failure::new(NagaStatus::ParseError, [Diagnostic::from(&error)], None),
+        )?;
+        give_module(module, parsed, None);
+        Ok(())
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn naga_module_free(module: *mut NagaModule) {
+    if !module.is_null() {
+        drop(Box::from_raw(module));
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn naga_validate(
+    module: *const NagaModule,
+    flags: u32,
+    capabilities: u32,
+    info: *mut *mut NagaModuleInfo,
+    diagnostics: *mut *mut NagaBuffer,
+) -> NagaStatus {
+    guard(diagnostics, || {
+        let module = reference(module, "module")?;
+        if info.is_null() {
+            return Err(Failure::invalid_argument("info"));
+        }
+        let flags = u8::try_from(flags)
+            .ok()
+            .and_then(valid::ValidationFlags::from_bits)
+            .ok_or_else(|| Failure::invalid_argument("flags"))?;
+        let capabilities = valid::Capabilities::from_bits(capabilities)
+            .ok_or_else(|| Failure::invalid_argument("capabilities"))?;
+        let mut validator = valid::Validator::new(flags, capabilities);
+        let validated = validator.validate(&module.module).map_err(|error| {
+            Failure::new(
+                NagaStatus::ValidationError,
+                [Diagnostic::from(&error)],
+                module.source.as_deref(),
+            )
+        })?;
+        *info = Box::into_raw(Box::new(NagaModuleInfo { info: validated }));
+        Ok(())
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn naga_module_info_free(info: *mut NagaModuleInfo) {
+    if !info.is_null() {
+        drop(Box::from_raw(info));
+    }
+}
+
+/// Run a backend on `module` and `info`, storing what it returns in `output`.
+unsafe fn write(
+    module: *const NagaModule,
+    info: *const NagaModuleInfo,
+    output: *mut *mut NagaBuffer,
+    diagnostics: *mut *mut NagaBuffer,
+    backend: impl FnOnce(&naga::Module, &valid::ModuleInfo) -> Result<Vec<u8>, Failure>,
+) -> NagaStatus {
+    guard(diagnostics, || {
+        let module = reference(module, "module")?;
+        let info = reference(info, "info")?;
+        if output.is_null() {
+            return Err(Failure::invalid_argument("output"));
+        }
+        let data = backend(&module.module, &info.info)?;
+        *output = NagaBuffer::into_raw(data);
+        Ok(())
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn naga_write_wgsl(
+    module: *const NagaModule,
+    info: *const NagaModuleInfo,
+    output: *mut *mut NagaBuffer,
+    diagnostics: *mut *mut NagaBuffer,
+) -> NagaStatus {
+    write(module, info, output, diagnostics, |module, info| {
+        back::wgsl::write_string(module, info, back::wgsl::WriterFlags::empty())
+            .map(String::into_bytes)
+            .map_err(Failure::translation)
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn naga_write_spirv(
+    module: *const NagaModule,
+    info: *const NagaModuleInfo,
+    output: *mut *mut NagaBuffer,
+    diagnostics: *mut *mut NagaBuffer,
+) -> NagaStatus {
+    write(module, info, output, diagnostics, |module, info| {
+        let words = back::spv::write_vec(module, info, &back::spv::Options::default(), None)
+            .map_err(Failure::translation)?;
+        Ok(words.iter().flat_map(|word| word.to_ne_bytes()).collect())
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn naga_write_msl(
+    module: *const NagaModule,
+    info: *const NagaModuleInfo,
+    output: *mut *mut NagaBuffer,
+    diagnostics: *mut *mut NagaBuffer,
+) -> NagaStatus {
+    write(module, info, output, diagnostics, |module, info| {
+        back::msl::write_string(
+            module,
+            info,
+            &back::msl::Options::default(),
+            &back::msl::PipelineOptions::default(),
+        )
+        .map(|(source, _)| source.into_bytes())
+        .map_err(Failure::translation)
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn naga_write_hlsl(
+    module: *const NagaModule,
+    info: *const NagaModuleInfo,
+    output: *mut *mut NagaBuffer,
+    diagnostics: *mut *mut NagaBuffer,
+) -> NagaStatus {
+    write(module, info, output, diagnostics, |module, info| {
+        let mut source = String::new();
+        let options = back::hlsl::Options::default();
+        back::hlsl::Writer::new(&mut source, &options)
+            .write(module, info)
+            .map_err(Failure::translation)?;
+        Ok(source.into_bytes())
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn naga_write_glsl(
+    module: *const NagaModule,
+    info: *const NagaModuleInfo,
+    stage: u32,
+    entry_point: *const c_char,
+    version: u16,
+    es: bool,
+    output: *mut *mut NagaBuffer,
+    diagnostics: *mut *mut NagaBuffer,
+) -> NagaStatus {
+    let entry_point = (!entry_point.is_null()).then(|| CStr::from_ptr(entry_point));
+    write(module, info, output, diagnostics, |module, info| {
+        let entry_point = entry_point
+            .and_then(|name| name.to_str().ok())
+            .ok_or_else(|| Failure::invalid_argument("entry_point"))?;
+        let options = back::glsl::Options {
+            version: if es {
+                back::glsl::Version::new_gles(version)
+            } else {
+                back::glsl::Version::Desktop(version)
+            },
+            ..Default::default()
+        };
+        let pipeline_options = back::glsl::PipelineOptions {
+            shader_stage: shader_stage(stage)?,
+            entry_point: entry_point.to_string(),
+            multiview: None,
+        };
+        let mut source = String::new();
+        back::glsl::Writer::new(
+            &mut source,
+            module,
+            info,
+            &options,
+            &pipeline_options,
+            naga::proc::BoundsCheckPolicies::default(),
+        )
+        .and_then(|mut writer| writer.write())
+        .map_err(Failure::translation)?;
+        Ok(source.into_bytes())
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn naga_buffer_data(buffer: *const NagaBuffer) -> *const u8 {
+    buffer
+        .as_ref()
+        .map_or(ptr::null(), |buffer| buffer.data.as_ptr())
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn naga_buffer_length(buffer: *const NagaBuffer) -> usize {
+    buffer.as_ref().map_or(0, |buffer| buffer.data.len())
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn naga_buffer_free(buffer: *mut NagaBuffer) {
+    if !buffer.is_null() {
+        drop(Box::from_raw(buffer));
+    }
+}
+
+#[test]
+fn panics_are_caught() {
+    let mut diagnostics = ptr::null_mut();
+    let status = unsafe { guard(&mut diagnostics, || panic!("oh no")) };
+    assert_eq!(status, NagaStatus::Panic);
+
+    let json: serde_json::Value = serde_json::from_slice(unsafe { &(*diagnostics).data }).unwrap();
+    assert_eq!(json[0]["code"], "panic");
+    assert_eq!(json[0]["message"], "oh no");
+    unsafe { naga_buffer_free(diagnostics) };
+}
@@ -0,0 +1,196 @@
+/*
+ * Exercise the C API from C. `tests/c_api.rs` calls `naga_capi_c_test`,
+ * which returns zero on success, or the line of the first failed check.
+ */
+
+#include <stdio.h>
+#include <string.h>
+
+#include "naga.h"
+
+#define CHECK(condition)                                                                      \
+    do {                                                                                      \
+        if (!(condition)) {                                                                   \
+            fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__, __LINE__, #condition);    \
+            return __LINE__;                                                                  \
+        }                                                                                     \
+    } while (0)
+
+static const char WGSL[] = "@fragment\n"
+                           "fn main() -> @location(0) vec4<f32> {\n"
+                           "    return vec4(1.0, 0.0, 0.0, 1.0);\n"
+                           "}\n";
+
+static const char BROKEN_WGSL[] = "@fragment\n"
+                                  "fn main() -> @location(0) vec4<f32> {\n"
+                                  "    return vec4(1.0, 0.0, 0.0, 1.0)\n"
+                                  "}\n";
+
+static const char INVALID_WGSL[] = "fn f() -> f32 {\n"
+                                   "    return 1u;\n"
+                                   "}\n";
+
+static const char GLSL[] = "#version 450\n"
+                           "layout(location = 0) out vec4 color;\n"
+                           "void main() { color = vec4(1.0); }\n";
+
+/* Return true if `buffer` holds text containing `needle`. */
+static int contains(const NagaBuffer *buffer, const char *needle) {
+    size_t length = naga_buffer_length(buffer);
+    size_t needle_length = strlen(needle);
+    const uint8_t *data = naga_buffer_data(buffer);
+    for (size_t i = 0; i + needle_length <= length; i++) {
+        if (memcmp(data + i, needle, needle_length) == 0) {
+            return 1;
+        }
+    }
+    return 0;
+}
+
+static int translate_wgsl(void) {
+    NagaModule *module = NULL;
+    NagaModuleInfo *info = NULL;
+    NagaBuffer *output = NULL;
+    NagaBuffer *diagnostics = NULL;
+
+    CHECK(naga_parse_wgsl((const uint8_t *)WGSL, strlen(WGSL), &module, &diagnostics) ==
+          NAGA_STATUS_OK);
+    CHECK(module != NULL && diagnostics == NULL);
+    CHECK(naga_validate(module, NAGA_VALIDATION_FLAGS_ALL, NAGA_CAPABILITIES_NONE, &info,
+                        &diagnostics) == NAGA_STATUS_OK);
+
+    CHECK(naga_write_wgsl(module, info, &output, &diagnostics) == NAGA_STATUS_OK);
+    CHECK(contains(output, "vec4<f32>(1f, 0f, 0f, 1f)"));
+    naga_buffer_free(output);
+
+    CHECK(naga_write_spirv(module, info, &output, &diagnostics) == NAGA_STATUS_OK);
+    CHECK(naga_buffer_length(output) % 4 == 0);
+    uint32_t magic;
+    memcpy(&magic, naga_buffer_data(output), sizeof(magic));
+    CHECK(magic == 0x07230203);
+    naga_buffer_free(output);
+
+    CHECK(naga_write_msl(module, info, &output, &diagnostics) == NAGA_STATUS_OK);
+    CHECK(contains(output, "fragment main_Output main_("));
+    naga_buffer_free(output);
+
+    CHECK(naga_write_hlsl(module, info, &output, &diagnostics) == NAGA_STATUS_OK);
+    CHECK(contains(output, "float4 main() : SV_Target0"));
+    naga_buffer_free(output);
+
+    CHECK(naga_write_glsl(module, info, NAGA_SHADER_STAGE_FRAGMENT, "main", 310, true, &output,
+                          &diagnostics) == NAGA_STATUS_OK);
+    CHECK(contains(output, "#version 310 es"));
+    naga_buffer_free(output);
+
+    /* GLSL output needs an existing entry point. */
+    output = NULL;
+    CHECK(naga_write_glsl(module, info, NAGA_SHADER_STAGE_VERTEX, "main", 450, false, &output,
+                          &diagnostics) == NAGA_STATUS_TRANSLATION_ERROR);
+    CHECK(output == NULL);
+    CHECK(contains(diagnostics, "\"code\":\"glsl::write\""));
+    naga_buffer_free(diagnostics);
+
+    naga_module_info_free(info);
+    naga_module_free(module);
+    return 0;
+}
+
+static int parse_glsl(void) {
+    NagaModule *module = NULL;
+    NagaModuleInfo *info = NULL;
+
+    CHECK(naga_parse_glsl(NAGA_SHADER_STAGE_FRAGMENT, (const uint8_t *)GLSL, strlen(GLSL),
+                          &module, NULL) == NAGA_STATUS_OK);
+    CHECK(naga_validate(module, NAGA_VALIDATION_FLAGS_ALL, NAGA_CAPABILITIES_ALL, &info, NULL) ==
+          NAGA_STATUS_OK);
+
+    naga_module_info_free(info);
+    naga_module_free(module);
+    return 0;
+}
+
+static int round_trip_spirv(void) {
+    NagaModule *module = NULL;
+    NagaModuleInfo *info = NULL;
+    NagaBuffer *spirv = NULL;
+
+    CHECK(naga_parse_wgsl((const uint8_t *)WGSL, strlen(WGSL), &module, NULL) == NAGA_STATUS_OK);
+    CHECK(naga_validate(module, NAGA_VALIDATION_FLAGS_ALL, NAGA_CAPABILITIES_NONE, &info, NULL) ==
+          NAGA_STATUS_OK);
+    CHECK(naga_write_spirv(module, info, &spirv, NULL) == NAGA_STATUS_OK);
+    naga_module_info_free(info);
+    naga_module_free(module);
+
+    module = NULL;
+    CHECK(naga_parse_spirv(naga_buffer_data(spirv), naga_buffer_length(spirv), &module, NULL) ==
+          NAGA_STATUS_OK);
+    CHECK(module != NULL);
+    naga_module_free(module);
+
+    /* SPIR-V must be whole words. */
+    module = NULL;
+    CHECK(naga_parse_spirv(naga_buffer_data(spirv), 3, &module, NULL) ==
+          NAGA_STATUS_PARSE_ERROR);
+    CHECK(module == NULL);
+
+    naga_buffer_free(spirv);
+    return 0;
+}
+
+static int diagnostics(void) {
+    NagaModule *module = NULL;
+    NagaModuleInfo *info = NULL;
+    NagaBuffer *diagnostics = NULL;
+
+    CHECK(naga_parse_wgsl((const uint8_t *)BROKEN_WGSL, strlen(BROKEN_WGSL), &module,
+                          &diagnostics) == NAGA_STATUS_PARSE_ERROR);
+    CHECK(module == NULL);
+    CHECK(contains(diagnostics, "\"code\":\"wgsl::parse\""));
+    CHECK(contains(diagnostics, "\"message\":\"expected ';', found '}'\""));
+    CHECK(contains(diagnostics, "\"line\":4"));
+    naga_buffer_free(diagnostics);
+
+    CHECK(naga_parse_wgsl((const uint8_t *)INVALID_WGSL, strlen(INVALID_WGSL), &module,
+                          &diagnostics) == NAGA_STATUS_OK);
+    CHECK(naga_validate(module, NAGA_VALIDATION_FLAGS_ALL, NAGA_CAPABILITIES_NONE, &info,
+                        &diagnostics) == NAGA_STATUS_VALIDATION_ERROR);
+    CHECK(info == NULL);
+    CHECK(contains(diagnostics, "\"code\":\"validation\""));
+    CHECK(contains(diagnostics, "\"line\":2"));
+    naga_buffer_free(diagnostics);
+
+    /* Unknown flag and capability bits are rejected, not ignored. */
+    CHECK(naga_validate(module, 0x80u, NAGA_CAPABILITIES_NONE, &info, NULL) ==
+          NAGA_STATUS_INVALID_ARGUMENT);
+    CHECK(naga_validate(module, 0x101u, NAGA_CAPABILITIES_NONE, &info, NULL) ==
+          NAGA_STATUS_INVALID_ARGUMENT);
+    CHECK(naga_validate(module, NAGA_VALIDATION_FLAGS_ALL, 0x80000000u, &info, NULL) ==
+          NAGA_STATUS_INVALID_ARGUMENT);
+    naga_module_free(module);
+
+    /* Invalid arguments are reported, not dereferenced. */
+    CHECK(naga_parse_wgsl(NULL, 4, &module, &diagnostics) == NAGA_STATUS_INVALID_ARGUMENT);
+    CHECK(contains(diagnostics, "\"code\":\"invalid-argument\""));
+    naga_buffer_free(diagnostics);
+    CHECK(naga_validate(NULL, 0, 0, &info, NULL) == NAGA_STATUS_INVALID_ARGUMENT);
+    CHECK(naga_parse_glsl(7, (const uint8_t *)GLSL, strlen(GLSL), &module, NULL) ==
+          NAGA_STATUS_INVALID_ARGUMENT);
+    CHECK(naga_parse_wgsl((const uint8_t *)"\xff", 1, &module, NULL) ==
+          NAGA_STATUS_INVALID_ARGUMENT);
+
+    /* Freeing NULL does nothing. */
+    naga_module_free(NULL);
+    naga_module_info_free(NULL);
+    naga_buffer_free(NULL);
+    return 0;
+}
+
+int naga_capi_c_test(void) {
+    int failed_line;
+    if ((failed_line = translate_wgsl()) != 0 || (failed_line = parse_glsl()) != 0 ||
+        (failed_line = round_trip_spirv()) != 0 || (failed_line = diagnostics()) != 0) {
+        return failed_line;
+    }
+    return 0;
+}
@@ -0,0 +1,23 @@
+//! Run the C test program in `tests/c/api.c`, which `build.rs` compiles
+//! with the `c-test` feature.
+
+#![cfg(feature = "c-test")]
+
+use std::ffi::c_int;
+
+// The C program calls the library's functions, so it must be linked in.
+use naga_capi as _;
+
+#[link(name = "naga_capi_c_test", kind = "static")]
+extern "C" {
+    fn naga_capi_c_test() -> c_int;
+}
+
+#[test]
+fn c_program() {
+    let failed_line = unsafe { naga_capi_c_test() };
+    assert_eq!(
+        failed_line, 0,
+        "check on line {failed_line} of `api.c` failed"
+    );
+}
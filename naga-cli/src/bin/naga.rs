@@ -1,7 +1,15 @@
 #![allow(clippy::manual_strip)]
 #[allow(unused_imports)]
 use std::fs;
-use std::{error::Error, fmt, io::Read, path::Path, str::FromStr};
+use std::{
+    error::Error,
+    fmt,
+    io::Read,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
 
 /// Translate shaders to different formats.
 #[derive(argh::FromArgs, Debug, Clone)]
@@ -44,6 +52,13 @@ struct Args {
     #[argh(option)]
     image_store_bounds_check_policy: Option<BoundsCheckPolicyArg>,
 
+    /// what policy to use for bounds checking indices into binding arrays.
+    ///
+    /// Possible values are the same as for `index-bounds-check-policy`. If
+    /// omitted, defaults to the index bounds check policy.
+    #[argh(option)]
+    binding_array_bounds_check_policy: Option<BoundsCheckPolicyArg>,
+
     /// directory to dump the SPIR-V block context dump to
     #[argh(option)]
     block_ctx_dir: Option<String>,
@@ -58,10 +73,41 @@ struct Args {
 
     /// the shader model to use if targeting HLSL
     ///
-    /// May be `50`, 51`, or `60`
+    /// May be `50`, 51`, `60`, or `64`
     #[argh(option)]
     shader_model: Option<ShaderModelArg>,
 
+    /// the SPIR-V version to target, for example `1.3`.
+    ///
+    /// Defaults to `1.0`.
+    #[argh(option)]
+    spirv_version: Option<VersionArg>,
+
+    /// the SPIR-V capabilities the output may use, as a comma-separated list
+    /// of names, for example `Shader,Float64`.
+    ///
+    /// Translation fails if the module needs a capability not on the list.
+    /// If omitted, all capabilities are permitted.
+    #[argh(option)]
+    spirv_capabilities: Option<SpirvCapabilitiesArg>,
+
+    /// don't emit `OpName`s for SPIR-V inputs and outputs.
+    ///
+    /// Some drivers treat these names as significant, and reject conflicts.
+    #[argh(switch)]
+    no_label_varyings: bool,
+
+    /// the Metal Shading Language version to target, for example `2.1`.
+    ///
+    /// Defaults to `1.0`.
+    #[argh(option)]
+    metal_version: Option<VersionArg>,
+
+    /// always write the point size built-in from vertex shaders, as needed
+    /// for drawing points, if targeting SPIR-V or GLSL
+    #[argh(switch)]
+    force_point_size: bool,
+
     /// if the selected frontends/backends support coordinate space conversions,
     /// disable them
     #[argh(switch)]
@@ -71,6 +117,14 @@ struct Args {
     #[argh(switch)]
     dot_cfg_only: bool,
 
+    /// print the validator capabilities the input module requires
+    #[argh(switch)]
+    required_capabilities: bool,
+
+    /// print how long parsing, validation and SPIR-V output took
+    #[argh(switch)]
+    timing: bool,
+
     /// specify file path to process STDIN as
     #[argh(option)]
     stdin_file_path: Option<String>,
@@ -79,6 +133,15 @@ struct Args {
     #[argh(switch, short = 'g')]
     generate_debug_symbols: bool,
 
+    /// fold accesses to constant vectors and matrices in function bodies,
+    /// and revalidate.
+    #[argh(switch)]
+    fold_constants: bool,
+
+    /// inline every function call, and revalidate.
+    #[argh(switch)]
+    inline_all: bool,
+
     /// compact the module's IR and revalidate.
     ///
     /// Output files will reflect the compacted IR. If you want to see the IR as
@@ -97,6 +160,33 @@ struct Args {
     #[argh(switch)]
     bulk_validate: bool,
 
+    /// bulk mode: compile every input listed in the given manifest.
+    ///
+    /// The manifest is a RON file with a list of `inputs`, each giving an
+    /// `input` path, a list of `outputs`, and optionally an `entry_point` and
+    /// GLSL preprocessor `defines`. Paths are relative to the manifest.
+    /// Inputs are compiled in parallel, and a summary with per-file timings
+    /// is printed at the end.
+    #[argh(option)]
+    bulk: Option<String>,
+
+    /// watch mode: recompile the input whenever it changes.
+    ///
+    /// Each compile prints how long it took, and either `ok` or the
+    /// diagnostics, in the `compact` format unless `--diagnostic-format`
+    /// says otherwise. If a compile fails, the previous outputs are left
+    /// alone. Stop with Ctrl-C. Needs the `watch` feature.
+    #[argh(switch)]
+    watch: bool,
+
+    /// how to report parse and validation errors.
+    ///
+    /// May be `human` (annotated source snippets, the default), `json`
+    /// (one JSON object per diagnostic, one per line, on stderr), or
+    /// `compact` (one `path:line:column: message` line per source location).
+    #[argh(option)]
+    diagnostic_format: Option<DiagnosticFormat>,
+
     /// show version
     #[argh(switch)]
     version: bool,
@@ -150,11 +240,50 @@ impl FromStr for ShaderModelArg {
             "50" => ShaderModel::V5_0,
             "51" => ShaderModel::V5_1,
             "60" => ShaderModel::V6_0,
+            "64" => ShaderModel::V6_4,
             _ => return Err(format!("Invalid value for --shader-model: {s}")),
         }))
     }
 }
 
+/// A `major.minor` version number, for `--spirv-version` and `--metal-version`.
+#[derive(Debug, Clone, Copy)]
+struct VersionArg((u8, u8));
+
+impl FromStr for VersionArg {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse = || {
+            let (major, minor) = s.split_once('.')?;
+            Some((major.parse().ok()?, minor.parse().ok()?))
+        };
+        match parse() {
+            Some(version) => Ok(Self(version)),
+            None => Err(format!("Invalid version, expected `major.minor`: {s}")),
+        }
+    }
+}
+
+/// A comma-separated list of SPIR-V capabilities, for `--spirv-capabilities`.
+#[derive(Debug, Clone)]
+struct SpirvCapabilitiesArg(naga::FastHashSet<naga::back::spv::Capability>);
+
+impl FromStr for SpirvCapabilitiesArg {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split(',')
+            .map(|name| {
+                name.trim()
+                    .parse()
+                    .map_err(|()| format!("Unknown SPIR-V capability: {name}"))
+            })
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
+
 /// Newtype so we can implement [`FromStr`] for [`naga::back::glsl::Version`].
 #[derive(Clone, Debug)]
 struct GlslProfileArg(naga::back::glsl::Version);
@@ -174,12 +303,37 @@ impl FromStr for GlslProfileArg {
     }
 }
 
-#[derive(Default)]
+/// How the CLI should report errors. See `--diagnostic-format`.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+enum DiagnosticFormat {
+    #[default]
+    Human,
+    Json,
+    Compact,
+}
+
+impl FromStr for DiagnosticFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.to_lowercase().as_str() {
+            "human" => Self::Human,
+            "json" => Self::Json,
+            "compact" => Self::Compact,
+            _ => return Err(format!("Invalid value for --diagnostic-format: {s}")),
+        })
+    }
+}
+
+#[derive(Default, Clone)]
 struct Parameters<'a> {
+    diagnostic_format: DiagnosticFormat,
     validation_flags: naga::valid::ValidationFlags,
     bounds_check_policies: naga::proc::BoundsCheckPolicies,
     entry_point: Option<String>,
+    defines: naga::FastHashMap<String, String>,
     keep_coordinate_space: bool,
+    timing: bool,
     spv_in: naga::front::spv::Options,
     spv_out: naga::back::spv::Options<'a>,
     dot: naga::back::dot::Options,
@@ -188,38 +342,25 @@ struct Parameters<'a> {
     hlsl: naga::back::hlsl::Options,
 }
 
-trait PrettyResult {
-    type Target;
-    fn unwrap_pretty(self) -> Self::Target;
-}
-
-fn print_err(error: &dyn Error) {
-    eprint!("{error}");
+fn format_err(error: &dyn Error) -> String {
+    use std::fmt::Write;
 
+    let mut formatted = error.to_string();
     let mut e = error.source();
     if e.is_some() {
-        eprintln!(": ");
-    } else {
-        eprintln!();
+        formatted.push_str(": ");
     }
+    formatted.push('\n');
 
     while let Some(source) = e {
-        eprintln!("\t{source}");
+        writeln!(formatted, "\t{source}").unwrap();
         e = source.source();
     }
+    formatted
 }
 
-impl<T, E: Error> PrettyResult for Result<T, E> {
-    type Target = T;
-    fn unwrap_pretty(self) -> T {
-        match self {
-            Result::Ok(value) => value,
-            Result::Err(error) => {
-                print_err(&error);
-                std::process::exit(1);
-            }
-        }
-    }
+fn print_err(error: &dyn Error) {
+    eprint!("{}", format_err(error));
 }
 
 fn main() {
@@ -273,6 +414,10 @@ fn run() -> Result<(), Box<dyn std::error::Error>> {
         Some(arg) => arg.0,
         None => params.bounds_check_policies.index,
     };
+    params.bounds_check_policies.binding_array = match args.binding_array_bounds_check_policy {
+        Some(arg) => arg.0,
+        None => params.bounds_check_policies.index,
+    };
 
     params.spv_in = naga::front::spv::Options {
         adjust_coordinate_space: !args.keep_coordinate_space,
@@ -288,18 +433,60 @@ fn run() -> Result<(), Box<dyn std::error::Error>> {
         params.hlsl.shader_model = model.0;
     }
     params.keep_coordinate_space = args.keep_coordinate_space;
+    params.timing = args.timing;
 
     params.dot.cfg_only = args.dot_cfg_only;
 
+    params.diagnostic_format = match args.diagnostic_format {
+        Some(format) => format,
+        None if args.watch => DiagnosticFormat::Compact,
+        None => DiagnosticFormat::default(),
+    };
+
     params.spv_out.bounds_check_policies = params.bounds_check_policies;
+    params.hlsl.bounds_check_policies = params.bounds_check_policies;
     params.spv_out.flags.set(
         naga::back::spv::WriterFlags::ADJUST_COORDINATE_SPACE,
         !params.keep_coordinate_space,
     );
+    params.spv_out.flags.set(
+        naga::back::spv::WriterFlags::LABEL_VARYINGS,
+        !args.no_label_varyings,
+    );
+    params.spv_out.flags.set(
+        naga::back::spv::WriterFlags::FORCE_POINT_SIZE,
+        args.force_point_size,
+    );
+    if let Some(version) = args.spirv_version {
+        params.spv_out.lang_version = version.0;
+    }
+    if let Some(ref capabilities) = args.spirv_capabilities {
+        params.spv_out.capabilities = Some(capabilities.0.clone());
+    }
+    params.glsl.writer_flags.set(
+        naga::back::glsl::WriterFlags::ADJUST_COORDINATE_SPACE,
+        !params.keep_coordinate_space,
+    );
+    params.glsl.writer_flags.set(
+        naga::back::glsl::WriterFlags::FORCE_POINT_SIZE,
+        args.force_point_size,
+    );
+    if let Some(version) = args.metal_version {
+        params.msl.lang_version = version.0;
+    }
 
     if args.bulk_validate {
         return bulk_validate(args, &params);
     }
+    if let Some(ref manifest) = args.bulk {
+        return bulk(Path::new(manifest), &params);
+    }
+    if args.watch {
+        #[cfg(feature = "watch")]
+        return watch(&args.files, &params);
+        #[cfg(not(feature = "watch"))]
+        return Err(CliError("`--watch` needs naga-cli's `watch` feature").into());
+    }
 
     let (input_path, input) = if let Some(path) = args.files.first() {
         let path = Path::new(path);
@@ -312,10 +499,14 @@ fn run() -> Result<(), Box<dyn std::error::Error>> {
         return Err(CliError("Input file path is not specified").into());
     };
 
+    let start = Instant::now();
     let Parsed {
         mut module,
         input_text,
     } = parse_input(input_path, input, &params)?;
+    if params.timing {
+        println!("Parsing: {}", format_duration(start.elapsed()));
+    }
 
     // Include debugging information if requested.
     if args.generate_debug_symbols {
@@ -340,33 +531,76 @@ fn run() -> Result<(), Box<dyn std::error::Error>> {
     let output_paths = args.files.get(1..).unwrap_or(&[]);
 
     // Decide which capabilities our output formats can support.
-    let validation_caps =
-        output_paths
-            .iter()
-            .fold(naga::valid::Capabilities::all(), |caps, path| {
-                use naga::valid::Capabilities as C;
-                let missing = match Path::new(path).extension().and_then(|ex| ex.to_str()) {
-                    Some("wgsl") => C::CLIP_DISTANCE | C::CULL_DISTANCE,
-                    Some("metal") => C::CULL_DISTANCE,
-                    _ => C::empty(),
-                };
-                caps & !missing
-            });
+    let validation_caps = validation_capabilities(output_paths.iter().map(Path::new));
 
     // Validate the IR before compaction.
-    let info = match naga::valid::Validator::new(params.validation_flags, validation_caps)
-        .validate(&module)
-    {
+    let mut validator = naga::valid::Validator::new(params.validation_flags, validation_caps);
+    let info = match validator.validate(&module) {
         Ok(info) => Some(info),
         Err(error) => {
             // Validation failure is not fatal. Just report the error.
-            if let Some(input) = &input_text {
-                let filename = input_path.file_name().and_then(std::ffi::OsStr::to_str);
-                emit_annotated_error(&error, filename.unwrap_or("input"), input);
+            emit_validation_error(&error, input_path, input_text.as_deref(), &params);
+            None
+        }
+    };
+    if params.timing && info.is_some() {
+        let stats = validator.statistics();
+        println!("Validation: {}", format_duration(stats.total));
+        for (phase, duration) in [
+            ("types", stats.types),
+            ("constants", stats.constants),
+            ("global variables", stats.global_variables),
+            ("functions", stats.functions),
+            ("entry points", stats.entry_points),
+        ] {
+            println!("  {phase}: {}", format_duration(duration));
+        }
+    }
+
+    // Fold accesses to constants, if requested.
+    let info = if args.fold_constants {
+        // Fold only if validation succeeded, like compaction below.
+        if info.is_some() {
+            naga::proc::fold_constants(&mut module);
+            match naga::valid::Validator::new(params.validation_flags, validation_caps)
+                .validate(&module)
+            {
+                Ok(info) => Some(info),
+                Err(error) => {
+                    eprintln!("Error validating folded module:");
+                    emit_validation_error(&error, input_path, input_text.as_deref(), &params);
+                    None
+                }
             }
-            print_err(&error);
+        } else {
+            eprintln!("Skipping constant folding due to validation failure.");
             None
         }
+    } else {
+        info
+    };
+
+    // Inline function calls, if requested.
+    let info = if args.inline_all {
+        // Inline only if validation succeeded, like compaction below.
+        if info.is_some() {
+            naga::proc::inline_all_calls(&mut module);
+            match naga::valid::Validator::new(params.validation_flags, validation_caps)
+                .validate(&module)
+            {
+                Ok(info) => Some(info),
+                Err(error) => {
+                    eprintln!("Error validating inlined module:");
+                    emit_validation_error(&error, input_path, input_text.as_deref(), &params);
+                    None
+                }
+            }
+        } else {
+            eprintln!("Skipping inlining due to validation failure.");
+            None
+        }
+    } else {
+        info
     };
 
     // Compact the module, if requested.
@@ -375,7 +609,13 @@ fn run() -> Result<(), Box<dyn std::error::Error>> {
         if info.is_some() {
             // Write out the module state before compaction, if requested.
             if let Some(ref before_compaction) = args.before_compaction {
-                write_output(&module, &info, &params, before_compaction)?;
+                write_output(
+                    &module,
+                    &info,
+                    &params,
+                    before_compaction,
+                    &mut Writers::default(),
+                )?;
             }
 
             naga::compact::compact(&mut module);
@@ -388,11 +628,7 @@ fn run() -> Result<(), Box<dyn std::error::Error>> {
                 Err(error) => {
                     // Validation failure is not fatal. Just report the error.
                     eprintln!("Error validating compacted module:");
-                    if let Some(input) = &input_text {
-                        let filename = input_path.file_name().and_then(std::ffi::OsStr::to_str);
-                        emit_annotated_error(&error, filename.unwrap_or("input"), input);
-                    }
-                    print_err(&error);
+                    emit_validation_error(&error, input_path, input_text.as_deref(), &params);
                     None
                 }
             }
@@ -404,6 +640,16 @@ fn run() -> Result<(), Box<dyn std::error::Error>> {
         info
     };
 
+    if args.required_capabilities && info.is_some() {
+        let required = naga::valid::required_capabilities(&module)?;
+        if required.is_empty() {
+            println!("Required capabilities: none");
+        } else {
+            let names: Vec<_> = required.iter_names().map(|(name, _)| name).collect();
+            println!("Required capabilities: {}", names.join(" | "));
+        }
+    }
+
     // If no output was requested, then report validation results and stop here.
     //
     // If the user asked for output, don't stop: some output formats (".txt",
@@ -417,13 +663,33 @@ fn run() -> Result<(), Box<dyn std::error::Error>> {
         }
     }
 
+    let mut writers = Writers::default();
     for output_path in output_paths {
-        write_output(&module, &info, &params, output_path)?;
+        write_output(&module, &info, &params, output_path, &mut writers)?;
     }
 
     Ok(())
 }
 
+/// Return the validator capabilities supported by all of the given output
+/// formats.
+fn validation_capabilities<'a>(
+    output_paths: impl IntoIterator<Item = &'a Path>,
+) -> naga::valid::Capabilities {
+    output_paths
+        .into_iter()
+        .fold(naga::valid::Capabilities::all(), |caps, path| {
+            use naga::valid::Capabilities as C;
+            let missing = match path.extension().and_then(|ex| ex.to_str()) {
+                Some("wgsl") => C::CLIP_DISTANCE | C::CULL_DISTANCE,
+                Some("metal") => C::CULL_DISTANCE | C::MESH_SHADER,
+                Some("hlsl" | "vert" | "frag" | "comp") => C::MESH_SHADER,
+                _ => C::empty(),
+            };
+            caps & !missing
+        })
+}
+
 struct Parsed {
     module: naga::Module,
     input_text: Option<String>,
@@ -441,12 +707,23 @@ fn parse_input(
         .ok_or(CliError("Input filename not valid unicode"))?
     {
         "bin" => (bincode::deserialize(&input)?, None),
-        "spv" => naga::front::spv::parse_u8_slice(&input, &params.spv_in).map(|m| (m, None))?,
+        "spv" => match naga::front::spv::parse_u8_slice(&input, &params.spv_in) {
+            Ok(module) => (module, None),
+            Err(ref e) if params.diagnostic_format != DiagnosticFormat::Human => {
+                emit_diagnostic(&e.into(), input_path, None, params);
+                return Err(CliError("Could not parse SPIR-V").into());
+            }
+            Err(e) => return Err(e.into()),
+        },
         "wgsl" => {
             let input = String::from_utf8(input)?;
             let result = naga::front::wgsl::parse_str(&input);
             match result {
                 Ok(v) => (v, Some(input)),
+                Err(ref e) if params.diagnostic_format != DiagnosticFormat::Human => {
+                    emit_diagnostic(&e.into(), input_path, Some(&input), params);
+                    return Err(CliError("Could not parse WGSL").into());
+                }
                 Err(ref e) => {
                     let message = format!(
                         "Could not parse WGSL:\n{}",
@@ -484,16 +761,22 @@ fn parse_input(
                                 },
                                 _ => unreachable!(),
                             },
-                            defines: Default::default(),
+                            defines: params.defines.clone(),
                         },
                         &input,
                     )
-                    .unwrap_or_else(|error| {
-                        let filename = input_path.file_name().and_then(std::ffi::OsStr::to_str).unwrap_or("glsl");
-                        let mut writer = StandardStream::stderr(ColorChoice::Auto);
-                        error.emit_to_writer_with_path(&mut writer, &input, filename);
-                        std::process::exit(1);
-                    }),
+                    .map_err(|error| {
+                        if params.diagnostic_format != DiagnosticFormat::Human {
+                            for error in &error.errors {
+                                emit_diagnostic(&error.into(), input_path, Some(&input), params);
+                            }
+                        } else {
+                            let filename = input_path.file_name().and_then(std::ffi::OsStr::to_str).unwrap_or("glsl");
+                            let mut writer = StandardStream::stderr(ColorChoice::Auto);
+                            error.emit_to_writer_with_path(&mut writer, &input, filename);
+                        }
+                        CliError("Could not parse GLSL")
+                    })?,
                 Some(input),
             )
         }
@@ -503,11 +786,18 @@ fn parse_input(
     Ok(Parsed { module, input_text })
 }
 
+/// Back end writers that can be reused from one module to the next.
+#[derive(Default)]
+struct Writers {
+    spv: Option<naga::back::spv::Writer>,
+}
+
 fn write_output(
     module: &naga::Module,
     info: &Option<naga::valid::ModuleInfo>,
     params: &Parameters,
     output_path: &str,
+    writers: &mut Writers,
 ) -> Result<(), Box<dyn std::error::Error>> {
     match Path::new(&output_path)
         .extension()
@@ -544,8 +834,7 @@ fn write_output(
                 ))?,
                 &options,
                 &pipeline_options,
-            )
-            .unwrap_pretty();
+            )?;
             fs::write(output_path, msl)?;
         }
         "spv" => {
@@ -558,7 +847,7 @@ fn write_output(
                         .entry_points
                         .iter()
                         .position(|ep| ep.name == *name)
-                        .expect("Unable to find the entry point");
+                        .ok_or(CliError("Unable to find the entry point"))?;
                     pipeline_options_owned = spv::PipelineOptions {
                         entry_point: name.clone(),
                         shader_stage: module.entry_points[ep_index].stage,
@@ -568,16 +857,41 @@ fn write_output(
                 None => None,
             };
 
-            let spv = spv::write_vec(
+            let writer = match writers.spv {
+                Some(ref mut writer) => writer,
+                None => writers.spv.insert(spv::Writer::new(&params.spv_out)?),
+            };
+            let mut spv = Vec::new();
+            writer.write(
                 module,
                 info.as_ref().ok_or(CliError(
                     "Generating SPIR-V output requires validation to \
                      succeed, and it failed in a previous step",
                 ))?,
-                &params.spv_out,
                 pipeline_options,
-            )
-            .unwrap_pretty();
+                &params.spv_out.debug_info,
+                &mut spv,
+            )?;
+            if params.timing {
+                let stats = writer.statistics();
+                println!("SPIR-V output: {}", format_duration(stats.total));
+                for (phase, duration) in [
+                    ("types", stats.types),
+                    ("constants", stats.constants),
+                    ("global variables", stats.global_variables),
+                    ("functions", stats.functions),
+                    ("entry points", stats.entry_points),
+                ] {
+                    println!("  {phase}: {}", format_duration(duration));
+                }
+                println!(
+                    "  {} instructions, {} ids, {} constants ({} cached)",
+                    stats.instructions,
+                    stats.ids_allocated,
+                    stats.constants_created,
+                    stats.constants_cached,
+                );
+            }
             let bytes = spv
                 .iter()
                 .fold(Vec::with_capacity(spv.len() * 4), |mut v, w| {
@@ -615,8 +929,7 @@ fn write_output(
                 &params.glsl,
                 &pipeline_options,
                 params.bounds_check_policies,
-            )
-            .unwrap_pretty();
+            )?;
             writer.write()?;
             fs::write(output_path, buffer)?;
         }
@@ -630,15 +943,13 @@ fn write_output(
             use naga::back::hlsl;
             let mut buffer = String::new();
             let mut writer = hlsl::Writer::new(&mut buffer, &params.hlsl);
-            writer
-                .write(
-                    module,
-                    info.as_ref().ok_or(CliError(
-                        "Generating hlsl output requires validation to \
+            writer.write(
+                module,
+                info.as_ref().ok_or(CliError(
+                    "Generating hlsl output requires validation to \
                          succeed, and it failed in a previous step",
-                    ))?,
-                )
-                .unwrap_pretty();
+                ))?,
+            )?;
             fs::write(output_path, buffer)?;
         }
         "wgsl" => {
@@ -651,8 +962,7 @@ fn write_output(
                      succeed, and it failed in a previous step",
                 ))?,
                 wgsl::WriterFlags::empty(),
-            )
-            .unwrap_pretty();
+            )?;
             fs::write(output_path, wgsl)?;
         }
         other => {
@@ -685,11 +995,7 @@ fn bulk_validate(args: Args, params: &Parameters) -> Result<(), Box<dyn std::err
         if let Err(error) = validator.validate(&module) {
             invalid.push(input_path.clone());
             eprintln!("Error validating {}:", input_path);
-            if let Some(input) = &input_text {
-                let filename = path.file_name().and_then(std::ffi::OsStr::to_str);
-                emit_annotated_error(&error, filename.unwrap_or("input"), input);
-            }
-            print_err(&error);
+            emit_validation_error(&error, path, input_text.as_deref(), params);
         }
     }
 
@@ -710,6 +1016,299 @@ fn bulk_validate(args: Args, params: &Parameters) -> Result<(), Box<dyn std::err
     Ok(())
 }
 
+/// The manifest read by `--bulk`.
+#[derive(serde::Deserialize)]
+struct BulkManifest {
+    inputs: Vec<BulkInput>,
+}
+
+/// One shader to compile in bulk mode.
+#[derive(serde::Deserialize)]
+struct BulkInput {
+    /// The shader to read, relative to the manifest.
+    input: PathBuf,
+    /// The files to write, relative to the manifest. As on the command
+    /// line, the extension selects the output format.
+    outputs: Vec<PathBuf>,
+    /// The entry point to use for SPIR-V and GLSL output.
+    #[serde(default)]
+    entry_point: Option<String>,
+    /// Preprocessor definitions for GLSL input.
+    #[serde(default)]
+    defines: naga::FastHashMap<String, String>,
+}
+
+fn bulk(manifest_path: &Path, params: &Parameters) -> Result<(), Box<dyn std::error::Error>> {
+    let manifest: BulkManifest = ron::de::from_str(&fs::read_to_string(manifest_path)?)?;
+    let base = manifest_path.parent().unwrap_or(Path::new(""));
+
+    // Every worker shares one set of capabilities, so use the ones that all
+    // of the manifest's output formats support.
+    let validation_caps = validation_capabilities(
+        manifest
+            .inputs
+            .iter()
+            .flat_map(|input| input.outputs.iter().map(PathBuf::as_path)),
+    );
+
+    let worker_count = std::thread::available_parallelism()
+        .map_or(1, |count| count.get())
+        .min(manifest.inputs.len());
+    let next_input = AtomicUsize::new(0);
+    let start = Instant::now();
+
+    // Each worker keeps its own validator and writers, and reuses them for
+    // every input it picks up.
+    let mut results = std::thread::scope(|scope| {
+        let workers = (0..worker_count)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut validator =
+                        naga::valid::Validator::new(params.validation_flags, validation_caps);
+                    let mut writers = Writers::default();
+                    let mut results = vec![];
+                    loop {
+                        let index = next_input.fetch_add(1, Ordering::Relaxed);
+                        let Some(input) = manifest.inputs.get(index) else {
+                            break;
+                        };
+                        let start = Instant::now();
+                        let result =
+                            compile_bulk_input(input, base, params, &mut validator, &mut writers);
+                        results.push((index, start.elapsed(), result));
+                    }
+                    results
+                })
+            })
+            .collect::<Vec<_>>();
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().unwrap())
+            .collect::<Vec<_>>()
+    });
+    let elapsed = start.elapsed();
+
+    // Report in manifest order, regardless of which worker finished first.
+    results.sort_by_key(|&(index, ..)| index);
+    let mut failed = 0;
+    for (index, time, result) in results {
+        let path = base.join(&manifest.inputs[index].input);
+        match result {
+            Ok(()) => println!("{:>12}  {}", format_duration(time), path.display()),
+            Err(message) => {
+                failed += 1;
+                println!("{:>12}  {}", "FAILED", path.display());
+                eprintln!("Error compiling {}:\n{message}", path.display());
+            }
+        }
+    }
+    println!(
+        "Compiled {} of {} inputs in {}",
+        manifest.inputs.len() - failed,
+        manifest.inputs.len(),
+        format_duration(elapsed)
+    );
+
+    if failed != 0 {
+        return Err(format!("{failed} of {} inputs failed", manifest.inputs.len()).into());
+    }
+    Ok(())
+}
+
+fn compile_bulk_input(
+    input: &BulkInput,
+    base: &Path,
+    params: &Parameters,
+    validator: &mut naga::valid::Validator,
+    writers: &mut Writers,
+) -> Result<(), String> {
+    let mut params = params.clone();
+    params.entry_point = input.entry_point.clone();
+    params.defines = input.defines.clone();
+
+    let input_path = base.join(&input.input);
+    let bytes = fs::read(&input_path).map_err(|error| format_err(&error))?;
+    let Parsed { module, input_text } =
+        parse_input(&input_path, bytes, &params).map_err(|error| format_err(error.as_ref()))?;
+
+    let info = match validator.validate(&module) {
+        Ok(info) => Some(info),
+        Err(error) => {
+            let diagnostic = naga::error::Diagnostic::from(&error);
+            return Err(match (params.diagnostic_format, input_text) {
+                (DiagnosticFormat::Json, input_text) => {
+                    diagnostic.emit_to_json(input_text.as_deref())
+                }
+                (DiagnosticFormat::Compact, input_text) => {
+                    format_compact(&diagnostic, &input_path, input_text.as_deref())
+                }
+                (DiagnosticFormat::Human, Some(input_text)) => {
+                    diagnostic.emit_to_string_with_path(&input_text, &input_path.to_string_lossy())
+                }
+                (DiagnosticFormat::Human, None) => format_err(&error),
+            });
+        }
+    };
+
+    for output in input.outputs.iter() {
+        let output_path = base.join(output);
+        write_output(
+            &module,
+            &info,
+            &params,
+            &output_path.to_string_lossy(),
+            writers,
+        )
+        .map_err(|error| format_err(error.as_ref()))?;
+    }
+    Ok(())
+}
+
+/// What the `--watch` loop waits for.
+#[cfg(feature = "watch")]
+enum WatchEvent {
+    /// The input changed.
+    Changed,
+    /// The user pressed Ctrl-C.
+    Stop,
+}
+
+/// How long the input must go unchanged before `--watch` recompiles it, so
+/// that a save that touches the file several times compiles it once.
+#[cfg(feature = "watch")]
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(100);
+
+#[cfg(feature = "watch")]
+fn watch(files: &[String], params: &Parameters) -> Result<(), Box<dyn std::error::Error>> {
+    use notify::Watcher as _;
+    use std::sync::mpsc::{self, RecvTimeoutError};
+
+    let input_path = Path::new(
+        files
+            .first()
+            .ok_or(CliError("Input file path is not specified"))?,
+    );
+    let output_paths = files.get(1..).unwrap_or(&[]);
+    let file_name = input_path
+        .file_name()
+        .ok_or(CliError("Input path has no file name"))?
+        .to_owned();
+
+    let (sender, receiver) = mpsc::channel();
+
+    // Editors often save by replacing the file, which would end a watch on
+    // the file itself, so watch its directory instead.
+    let changes = sender.clone();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        let Ok(event) = event else {
+            return;
+        };
+        if (event.kind.is_create() || event.kind.is_modify())
+            && event
+                .paths
+                .iter()
+                .any(|path| path.file_name() == Some(&file_name))
+        {
+            let _ = changes.send(WatchEvent::Changed);
+        }
+    })?;
+    let directory = match input_path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    watcher.watch(directory, notify::RecursiveMode::NonRecursive)?;
+    ctrlc::set_handler(move || {
+        let _ = sender.send(WatchEvent::Stop);
+    })?;
+
+    loop {
+        let start = Instant::now();
+        let result = recompile(input_path, output_paths, params);
+        let time = format_duration(start.elapsed());
+        match result {
+            Ok(()) => println!("{}: ok in {time}", input_path.display()),
+            Err(error) => {
+                print_err(error.as_ref());
+                println!("{}: failed in {time}", input_path.display());
+            }
+        }
+
+        // Wait for a change, and then for the input to settle.
+        match receiver.recv() {
+            Ok(WatchEvent::Changed) => {}
+            Ok(WatchEvent::Stop) | Err(_) => return Ok(()),
+        }
+        loop {
+            match receiver.recv_timeout(WATCH_DEBOUNCE) {
+                Ok(WatchEvent::Changed) => {}
+                Ok(WatchEvent::Stop) | Err(RecvTimeoutError::Disconnected) => return Ok(()),
+                Err(RecvTimeoutError::Timeout) => break,
+            }
+        }
+    }
+}
+
+/// Compile `input_path` to `output_paths` once, for `--watch`.
+///
+/// Diagnostics are reported as they're found. The outputs are only replaced
+/// if parsing, validation and every backend succeed.
+#[cfg(feature = "watch")]
+fn recompile(
+    input_path: &Path,
+    output_paths: &[String],
+    params: &Parameters,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let input = fs::read(input_path)?;
+    let Parsed { module, input_text } = parse_input(input_path, input, params)?;
+
+    let validation_caps = validation_capabilities(output_paths.iter().map(Path::new));
+    let info = naga::valid::Validator::new(params.validation_flags, validation_caps)
+        .validate(&module)
+        .map_err(|error| {
+            emit_validation_error(&error, input_path, input_text.as_deref(), params);
+            CliError("Validation failed")
+        })?;
+    let info = Some(info);
+
+    // Write each output beside its destination, and move them into place only
+    // once they have all been written.
+    let mut writers = Writers::default();
+    let mut written = Vec::with_capacity(output_paths.len());
+    for output_path in output_paths {
+        let output_path = Path::new(output_path);
+        // Keep the whole file name, since its extensions select the format.
+        let mut temp_name = std::ffi::OsString::from(".naga-watch-");
+        temp_name.push(
+            output_path
+                .file_name()
+                .ok_or(CliError("Output path has no file name"))?,
+        );
+        let temp_path = output_path.with_file_name(temp_name);
+        let result = write_output(
+            &module,
+            &info,
+            params,
+            &temp_path.to_string_lossy(),
+            &mut writers,
+        );
+        written.push((temp_path, output_path));
+        if let Err(error) = result {
+            for (temp_path, _) in written {
+                let _ = fs::remove_file(temp_path);
+            }
+            return Err(error);
+        }
+    }
+    for (temp_path, output_path) in written {
+        fs::rename(temp_path, output_path)?;
+    }
+    Ok(())
+}
+
+fn format_duration(duration: Duration) -> String {
+    format!("{:.2}ms", duration.as_secs_f64() * 1000.0)
+}
+
 use codespan_reporting::{
     diagnostic::{Diagnostic, Label},
     files::SimpleFile,
@@ -720,6 +1319,92 @@ use codespan_reporting::{
 };
 use naga::WithSpan;
 
+/// Report a validation error in the format selected by `--diagnostic-format`.
+fn emit_validation_error(
+    error: &WithSpan<naga::valid::ValidationError>,
+    input_path: &Path,
+    input_text: Option<&str>,
+    params: &Parameters,
+) {
+    match params.diagnostic_format {
+        DiagnosticFormat::Json | DiagnosticFormat::Compact => {
+            emit_diagnostic(&error.into(), input_path, input_text, params);
+        }
+        DiagnosticFormat::Human => {
+            if let Some(input) = input_text {
+                let filename = input_path.file_name().and_then(std::ffi::OsStr::to_str);
+                emit_annotated_error(error, filename.unwrap_or("input"), input);
+            }
+            print_err(error);
+        }
+    }
+}
+
+/// Report `diagnostic` on stderr in the JSON or compact format.
+///
+/// The human format has its own reporting in each caller.
+fn emit_diagnostic(
+    diagnostic: &naga::error::Diagnostic,
+    input_path: &Path,
+    input_text: Option<&str>,
+    params: &Parameters,
+) {
+    match params.diagnostic_format {
+        DiagnosticFormat::Json => eprintln!("{}", diagnostic.emit_to_json(input_text)),
+        DiagnosticFormat::Compact => {
+            eprint!("{}", format_compact(diagnostic, input_path, input_text))
+        }
+        DiagnosticFormat::Human => eprintln!("{}", diagnostic.message),
+    }
+}
+
+/// Format `diagnostic` for `--diagnostic-format compact`.
+///
+/// The first line is `path:line:column: severity[code]: message`, located at
+/// the first label. Further labels and notes follow on their own lines.
+fn format_compact(
+    diagnostic: &naga::error::Diagnostic,
+    input_path: &Path,
+    input_text: Option<&str>,
+) -> String {
+    use std::fmt::Write;
+
+    let path = input_path.display();
+    let locate = |span: naga::Span| match input_text {
+        Some(text) if span.is_defined() => {
+            let location = span.location(text);
+            format!("{path}:{}:{}", location.line_number, location.line_position)
+        }
+        _ => path.to_string(),
+    };
+
+    let mut labels = diagnostic.labels.iter();
+    let mut out = String::new();
+    let first = labels.next();
+    write!(
+        out,
+        "{}: {}[{}]: {}",
+        locate(first.map_or(naga::Span::UNDEFINED, |label| label.span)),
+        diagnostic.severity.as_str(),
+        diagnostic.code,
+        diagnostic.message
+    )
+    .unwrap();
+    match first {
+        Some(label) if !label.message.is_empty() && label.message != diagnostic.message => {
+            writeln!(out, " ({})", label.message).unwrap()
+        }
+        _ => out.push('\n'),
+    }
+    for label in labels {
+        writeln!(out, "{}: note: {}", locate(label.span), label.message).unwrap();
+    }
+    for note in diagnostic.notes.iter() {
+        writeln!(out, "{path}: note: {note}").unwrap();
+    }
+    out
+}
+
 pub fn emit_annotated_error<E: Error>(ann_err: &WithSpan<E>, filename: &str, source: &str) {
     let files = SimpleFile::new(filename, source);
     let config = codespan_reporting::term::Config::default();
@@ -736,3 +1421,80 @@ pub fn emit_annotated_error<E: Error>(ann_err: &WithSpan<E>, filename: &str, sou
 
     term::emit(&mut writer.lock(), &config, &files, &diagnostic).expect("cannot write error");
 }
+
+#[cfg(all(test, feature = "watch"))]
+mod tests {
+    use super::{format_compact, recompile, DiagnosticFormat, Parameters};
+    use std::{fs, path::Path};
+
+    const RED: &str = "
+@fragment
+fn main() -> @location(0) vec4<f32> {
+    return vec4(1.0, 0.0, 0.0, 1.0);
+}
+";
+
+    const BROKEN: &str = "
+@fragment
+fn main() -> @location(0) vec4<f32> {
+    return vec4(0.0, 1.0, 0.0, 1.0)
+}
+";
+
+    const BLUE: &str = "
+@fragment
+fn main() -> @location(0) vec4<f32> {
+    return vec4(0.0, 0.0, 1.0, 1.0);
+}
+";
+
+    /// Each change to the input recompiles it, and a failed compile leaves
+    /// the previous output alone.
+    #[test]
+    fn recompile_changed_input() {
+        let dir = std::env::temp_dir().join(format!("naga-watch-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let input = dir.join("shader.wgsl");
+        let output = dir.join("out.wgsl");
+        let outputs = [output.to_string_lossy().into_owned()];
+        let params = Parameters {
+            diagnostic_format: DiagnosticFormat::Compact,
+            ..Default::default()
+        };
+
+        fs::write(&input, RED).unwrap();
+        recompile(&input, &outputs, &params).unwrap();
+        assert!(fs::read_to_string(&output)
+            .unwrap()
+            .contains("1f, 0f, 0f, 1f"));
+
+        fs::write(&input, BROKEN).unwrap();
+        recompile(&input, &outputs, &params).unwrap_err();
+        assert!(fs::read_to_string(&output)
+            .unwrap()
+            .contains("1f, 0f, 0f, 1f"));
+
+        fs::write(&input, BLUE).unwrap();
+        recompile(&input, &outputs, &params).unwrap();
+        assert!(fs::read_to_string(&output)
+            .unwrap()
+            .contains("0f, 0f, 1f, 1f"));
+
+        // Only the output is left behind, not the files it was written to.
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 2);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn compact_diagnostics() {
+        let error = naga::front::wgsl::parse_str(BROKEN).unwrap_err();
+        let diagnostic = (&error).into();
+        let formatted = format_compact(&diagnostic, Path::new("shader.wgsl"), Some(BROKEN));
+        assert!(
+            formatted.starts_with("shader.wgsl:5:1: error[wgsl::parse]: expected ';'"),
+            "{formatted}"
+        );
+    }
+}
@@ -0,0 +1,134 @@
+//! Tests for `naga --bulk`.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+const COMPUTE: &str = "
+@group(0) @binding(0) var<storage, read_write> data: array<u32>;
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    data[id.x] *= 2u;
+}
+";
+
+const TRIANGLE: &str = "
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> @builtin(position) vec4<f32> {
+    let x = f32(i32(index) - 1);
+    let y = f32(i32(index & 1u) * 2 - 1);
+    return vec4(x, y, 0.0, 1.0);
+}
+
+@fragment
+fn fs_main() -> @location(0) vec4<f32> {
+    return vec4(1.0, 0.0, 0.0, 1.0);
+}
+";
+
+// `COLOR` is only defined by the manifest.
+const FRAGMENT: &str = "
+#version 450
+layout(location = 0) out vec4 o_color;
+void main() {
+    o_color = COLOR;
+}
+";
+
+const MANIFEST: &str = r#"(
+    inputs: [
+        (input: "compute.wgsl", outputs: ["out/compute.spv", "out/compute.metal"]),
+        (
+            input: "triangle.wgsl",
+            outputs: ["out/triangle.spv", "out/triangle.metal"],
+            entry_point: Some("fs_main"),
+        ),
+        (
+            input: "color.frag",
+            outputs: ["out/color.spv", "out/color.metal"],
+            defines: {"COLOR": "vec4(0.5)"},
+        ),
+    ],
+)"#;
+
+/// Write `files` into a fresh directory for the test called `name`.
+fn setup(name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("naga-bulk-{}-{name}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(dir.join("out")).unwrap();
+    for &(file, contents) in files {
+        fs::write(dir.join(file), contents).unwrap();
+    }
+    dir
+}
+
+fn run_bulk(dir: &Path) -> std::process::Output {
+    Command::new(env!("CARGO_BIN_EXE_naga"))
+        .arg("--bulk")
+        .arg(dir.join("manifest.ron"))
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn compiles_manifest() {
+    let dir = setup(
+        "compiles",
+        &[
+            ("compute.wgsl", COMPUTE),
+            ("triangle.wgsl", TRIANGLE),
+            ("color.frag", FRAGMENT),
+            ("manifest.ron", MANIFEST),
+        ],
+    );
+
+    let output = run_bulk(&dir);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "{stdout}\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(stdout.contains("Compiled 3 of 3 inputs"), "{stdout}");
+
+    for name in ["compute", "triangle", "color"] {
+        let spv = fs::read(dir.join("out").join(format!("{name}.spv"))).unwrap();
+        assert_eq!(spv[..4], 0x0723_0203u32.to_le_bytes());
+        let msl = fs::read_to_string(dir.join("out").join(format!("{name}.metal"))).unwrap();
+        assert!(msl.contains("#include <metal_stdlib>"));
+    }
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn reports_failures() {
+    let manifest = r#"(
+        inputs: [
+            (input: "broken.wgsl", outputs: ["out/broken.spv"]),
+            (input: "compute.wgsl", outputs: ["out/compute.metal"]),
+        ],
+    )"#;
+    let dir = setup(
+        "failures",
+        &[
+            ("broken.wgsl", "fn main() -> f32 { return 1u; }"),
+            ("compute.wgsl", COMPUTE),
+            ("manifest.ron", manifest),
+        ],
+    );
+
+    let output = run_bulk(&dir);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!output.status.success());
+    assert!(stdout.contains("FAILED"), "{stdout}");
+    assert!(stdout.contains("Compiled 1 of 2 inputs"), "{stdout}");
+    // A failure doesn't stop the other inputs from being compiled.
+    assert!(dir.join("out/compute.metal").exists());
+    assert!(!dir.join("out/broken.spv").exists());
+
+    fs::remove_dir_all(dir).unwrap();
+}
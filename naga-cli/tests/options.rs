@@ -0,0 +1,105 @@
+//! Tests that command-line options reach the backends.
+
+use std::{fs, path::PathBuf, process::Command};
+
+const TRIANGLE: &str = "
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> @builtin(position) vec4<f32> {
+    let x = f32(i32(index) - 1);
+    let y = f32(i32(index & 1u) * 2 - 1);
+    return vec4(x, y, 0.0, 1.0);
+}
+";
+
+// Needs the `ImageQuery` SPIR-V capability.
+const QUERY: &str = "
+@group(0) @binding(0) var image: texture_2d<f32>;
+
+@fragment
+fn main() -> @location(0) vec4<f32> {
+    return vec4(vec2<f32>(textureDimensions(image)), 0.0, 1.0);
+}
+";
+
+/// Translate `TRIANGLE` to `output` with the given extra arguments, and
+/// return the path of the output file, or the CLI's output if it failed.
+fn translate(name: &str, output: &str, args: &[&str]) -> Result<PathBuf, String> {
+    translate_source(name, TRIANGLE, output, args)
+}
+
+/// Like `translate`, but for the given WGSL `source`.
+fn translate_source(
+    name: &str,
+    source: &str,
+    output: &str,
+    args: &[&str],
+) -> Result<PathBuf, String> {
+    let dir = std::env::temp_dir().join(format!("naga-options-{}-{name}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    let input = dir.join("triangle.wgsl");
+    fs::write(&input, source).unwrap();
+
+    let output = dir.join(output);
+    let result = Command::new(env!("CARGO_BIN_EXE_naga"))
+        .args(args)
+        .arg(&input)
+        .arg(&output)
+        .output()
+        .unwrap();
+    if result.status.success() {
+        Ok(output)
+    } else {
+        // `argh` reports bad arguments on stdout.
+        Err(format!(
+            "{}{}",
+            String::from_utf8_lossy(&result.stdout),
+            String::from_utf8_lossy(&result.stderr)
+        ))
+    }
+}
+
+#[test]
+fn spirv_version() {
+    let output = translate("spirv-version", "out.spv", &["--spirv-version", "1.3"]).unwrap();
+    let spv = fs::read(output).unwrap();
+    // The version word follows the magic number.
+    assert_eq!(spv[4..8], 0x0001_0300u32.to_le_bytes());
+}
+
+#[test]
+fn spirv_capabilities() {
+    let args = ["--spirv-capabilities", "Shader,ImageQuery"];
+    translate_source("spirv-caps", QUERY, "out.spv", &args).unwrap();
+
+    let args = ["--spirv-capabilities", "Shader"];
+    let error = translate_source("spirv-no-caps", QUERY, "out.spv", &args).unwrap_err();
+    assert!(error.contains("[ImageQuery]"), "{error}");
+
+    let args = ["--spirv-capabilities", "Teleport"];
+    let error = translate("spirv-bad-caps", "out.spv", &args).unwrap_err();
+    assert!(
+        error.contains("Unknown SPIR-V capability: Teleport"),
+        "{error}"
+    );
+}
+
+#[test]
+fn metal_version() {
+    let output = translate("metal-version", "out.metal", &["--metal-version", "2.1"]).unwrap();
+    let msl = fs::read_to_string(output).unwrap();
+    assert!(msl.starts_with("// language: metal2.1"), "{msl}");
+}
+
+#[test]
+fn force_point_size() {
+    let args = ["--entry-point", "vs_main"];
+    let output = translate("no-point-size", "out.vert", &args).unwrap();
+    let glsl = fs::read_to_string(output).unwrap();
+    assert!(!glsl.contains("gl_PointSize"), "{glsl}");
+
+    let args = ["--entry-point", "vs_main", "--force-point-size"];
+    let output = translate("point-size", "out.vert", &args).unwrap();
+    let glsl = fs::read_to_string(output).unwrap();
+    assert!(glsl.contains("gl_PointSize = 1.0;"), "{glsl}");
+}
@@ -143,6 +143,101 @@ fn validation(c: &mut Criterion) {
     });
 }
 
+/// Build a module with `count` functions, each calling the one before it.
+#[cfg(feature = "wgsl-in")]
+fn many_functions(count: usize) -> naga::Module {
+    use std::fmt::Write as _;
+
+    let mut source = String::from("fn fun0(x: f32) -> f32 { return x; }\n");
+    for i in 1..count {
+        writeln!(
+            source,
+            "fn fun{i}(x: f32) -> f32 {{
+                var sum = 0.0;
+                for (var j = 0; j < {i}; j++) {{
+                    sum += sin(x * f32(j)) + fun{}(x);
+                }}
+                return sum;
+            }}",
+            i - 1
+        )
+        .unwrap();
+    }
+    naga::front::wgsl::parse_str(&source).unwrap()
+}
+
+/// Build a module with `count` functions, each calling only the first.
+///
+/// With the `parallel` feature, all but the first can be validated at once.
+#[cfg(feature = "wgsl-in")]
+fn independent_functions(count: usize) -> naga::Module {
+    use std::fmt::Write as _;
+
+    let mut source = String::from("fn fun0(x: f32) -> f32 { return x; }\n");
+    for i in 1..count {
+        writeln!(
+            source,
+            "fn fun{i}(x: f32) -> f32 {{
+                var sum = 0.0;
+                for (var j = 0; j < {i}; j++) {{
+                    sum += sin(x * f32(j)) + fun0(x);
+                }}
+                return sum;
+            }}"
+        )
+        .unwrap();
+    }
+    naga::front::wgsl::parse_str(&source).unwrap()
+}
+
+#[cfg(feature = "wgsl-in")]
+fn many_functions_validation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("valid-many-functions");
+    for (name, module) in [
+        ("chained", many_functions(500)),
+        ("independent", independent_functions(500)),
+    ] {
+        group.bench_function(name, |b| {
+            let mut validator = naga::valid::Validator::new(
+                naga::valid::ValidationFlags::all(),
+                naga::valid::Capabilities::all(),
+            );
+            b.iter(|| validator.validate(&module).unwrap());
+        });
+    }
+}
+
+#[cfg(not(feature = "wgsl-in"))]
+fn many_functions_validation(_: &mut Criterion) {}
+
+#[cfg(feature = "wgsl-in")]
+fn incremental_validation(c: &mut Criterion) {
+    let module = many_functions(100);
+    let handle = module.functions.iter().nth(50).unwrap().0;
+    let mut group = c.benchmark_group("valid-incremental");
+    group.bench_function("full", |b| {
+        let mut validator = naga::valid::Validator::new(
+            naga::valid::ValidationFlags::all(),
+            naga::valid::Capabilities::all(),
+        );
+        b.iter(|| validator.validate(&module).unwrap());
+    });
+    group.bench_function("one-function", |b| {
+        let mut validator = naga::valid::Validator::new(
+            naga::valid::ValidationFlags::all(),
+            naga::valid::Capabilities::all(),
+        );
+        let mut info = validator.validate(&module).unwrap();
+        b.iter(|| {
+            let function_info = validator.validate_function(&module, handle, &info).unwrap();
+            info.replace_function(handle, function_info);
+        });
+    });
+}
+
+#[cfg(not(feature = "wgsl-in"))]
+fn incremental_validation(_: &mut Criterion) {}
+
 fn backends(c: &mut Criterion) {
     let inputs = {
         let mut validator = naga::valid::Validator::new(
@@ -241,6 +336,9 @@ fn backends(c: &mut Criterion) {
                 writer_flags: naga::back::glsl::WriterFlags::empty(),
                 binding_map: Default::default(),
                 zero_initialize_workgroup_memory: true,
+                max_loop_iterations: 1 << 31,
+                extensions: Vec::new(),
+                prologue: None,
             };
             for &(ref module, ref info) in inputs.iter() {
                 for ep in module.entry_points.iter() {
@@ -269,5 +367,12 @@ fn backends(c: &mut Criterion) {
     });
 }
 
-criterion_group!(criterion, frontends, validation, backends,);
+criterion_group!(
+    criterion,
+    frontends,
+    validation,
+    many_functions_validation,
+    incremental_validation,
+    backends,
+);
 criterion_main!(criterion);
@@ -4,11 +4,41 @@ mod fuzz {
     use libfuzzer_sys::fuzz_target;
 
     fuzz_target!(|module: naga::Module| {
-        use naga::valid as v;
+        use naga::{back, valid as v};
         // Check if the module validates without errors.
         //TODO: may also fuzz the flags and capabilities
-        let mut validator =
-            v::Validator::new(v::ValidationFlags::all(), v::Capabilities::default());
-        let _result = validator.validate(&module);
+        let mut validator = v::Validator::new(v::ValidationFlags::all(), v::Capabilities::all());
+        let Ok(info) = validator.validate(&module) else {
+            return;
+        };
+
+        // Backends may reject a valid module, but must never panic on one.
+        let _ = back::spv::write_vec(&module, &info, &Default::default(), None);
+        let _ = back::msl::write_string(&module, &info, &Default::default(), &Default::default());
+        let _ = back::wgsl::write_string(&module, &info, back::wgsl::WriterFlags::empty());
+
+        let hlsl_options = Default::default();
+        let mut hlsl = String::new();
+        let _ = back::hlsl::Writer::new(&mut hlsl, &hlsl_options).write(&module, &info);
+
+        let glsl_options = Default::default();
+        for ep in module.entry_points.iter() {
+            let pipeline_options = back::glsl::PipelineOptions {
+                shader_stage: ep.stage,
+                entry_point: ep.name.clone(),
+                multiview: None,
+            };
+            let mut glsl = String::new();
+            if let Ok(mut writer) = back::glsl::Writer::new(
+                &mut glsl,
+                &module,
+                &info,
+                &glsl_options,
+                &pipeline_options,
+                Default::default(),
+            ) {
+                let _ = writer.write();
+            }
+        }
     });
 }
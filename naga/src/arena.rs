@@ -32,7 +32,6 @@ impl BadHandle {
     any(feature = "serialize", feature = "deserialize"),
     serde(transparent)
 )]
-#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct Handle<T> {
     index: Index,
     #[cfg_attr(any(feature = "serialize", feature = "deserialize"), serde(skip))]
@@ -100,7 +99,7 @@ impl<T> Handle<T> {
     }
 
     /// Convert a `usize` index into a `Handle<T>`.
-    fn from_usize(index: usize) -> Self {
+    pub(crate) fn from_usize(index: usize) -> Self {
         let handle_index = u32::try_from(index + 1)
             .ok()
             .and_then(Index::new)
@@ -121,7 +120,6 @@ impl<T> Handle<T> {
     any(feature = "serialize", feature = "deserialize"),
     serde(transparent)
 )]
-#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct Range<T> {
     inner: ops::Range<u32>,
     #[cfg_attr(any(feature = "serialize", feature = "deserialize"), serde(skip))]
@@ -166,6 +164,14 @@ impl<T> Clone for Range<T> {
     }
 }
 
+impl<T> PartialEq for Range<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.inner == other.inner
+    }
+}
+
+impl<T> Eq for Range<T> {}
+
 impl<T> fmt::Debug for Range<T> {
     fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
         write!(formatter, "[{}..{}]", self.inner.start + 1, self.inner.end)
@@ -233,6 +239,35 @@ impl<T> Range<T> {
     }
 }
 
+/// The length of an [`Arena`] at some point, to which it can be truncated.
+///
+/// See [`Arena::checkpoint`].
+pub struct Checkpoint<T> {
+    len: usize,
+    marker: PhantomData<T>,
+}
+
+impl<T> Clone for Checkpoint<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Checkpoint<T> {}
+
+impl<T> fmt::Debug for Checkpoint<T> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "Checkpoint({})", self.len)
+    }
+}
+
+impl<T> Checkpoint<T> {
+    /// Return the number of elements the arena held at this checkpoint.
+    pub(crate) const fn len(&self) -> usize {
+        self.len
+    }
+}
+
 /// An arena holding some kind of component (e.g., type, constant,
 /// instruction, etc.) that can be referenced.
 ///
@@ -242,7 +277,6 @@ impl<T> Range<T> {
 #[cfg_attr(feature = "clone", derive(Clone))]
 #[cfg_attr(feature = "serialize", derive(serde::Serialize))]
 #[cfg_attr(feature = "serialize", serde(transparent))]
-#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(test, derive(PartialEq))]
 pub struct Arena<T> {
     /// Values of this arena.
@@ -366,6 +400,42 @@ impl<T> Arena<T> {
         }
     }
 
+    /// Return a checkpoint recording the arena's current length.
+    ///
+    /// Passing the checkpoint to [`truncate`] later drops every element
+    /// appended since then. This lets front ends build IR speculatively, and
+    /// throw it away if they don't need it.
+    ///
+    /// [`truncate`]: Arena::truncate
+    pub fn checkpoint(&self) -> Checkpoint<T> {
+        Checkpoint {
+            len: self.data.len(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Drop every element appended since `checkpoint` was taken.
+    ///
+    /// This invalidates the handles to those elements, so the caller must
+    /// ensure that only the code that appended them holds any. If the arena
+    /// has already been truncated past `checkpoint`, this does nothing.
+    pub fn truncate(&mut self, checkpoint: Checkpoint<T>) {
+        self.data.truncate(checkpoint.len);
+        self.span_info.truncate(checkpoint.len);
+    }
+
+    /// Start appending elements speculatively.
+    ///
+    /// The returned guard derefs to this arena. Unless it is
+    /// [committed](Speculation::commit), dropping it truncates the arena
+    /// back to its length at this call.
+    pub fn speculate(&mut self) -> Speculation<'_, T> {
+        Speculation {
+            checkpoint: Some(self.checkpoint()),
+            arena: self,
+        }
+    }
+
     /// Clears the arena keeping all allocations
     pub fn clear(&mut self) {
         self.data.clear()
@@ -438,6 +508,43 @@ impl<T> Arena<T> {
     }
 }
 
+/// A guard that rolls an [`Arena`] back when dropped, unless committed.
+///
+/// See [`Arena::speculate`].
+pub struct Speculation<'a, T> {
+    arena: &'a mut Arena<T>,
+    /// The checkpoint to roll back to, or `None` once committed.
+    checkpoint: Option<Checkpoint<T>>,
+}
+
+impl<T> Speculation<'_, T> {
+    /// Keep the elements appended since this speculation started.
+    pub fn commit(mut self) {
+        self.checkpoint = None;
+    }
+}
+
+impl<T> ops::Deref for Speculation<'_, T> {
+    type Target = Arena<T>;
+    fn deref(&self) -> &Arena<T> {
+        self.arena
+    }
+}
+
+impl<T> ops::DerefMut for Speculation<'_, T> {
+    fn deref_mut(&mut self) -> &mut Arena<T> {
+        self.arena
+    }
+}
+
+impl<T> Drop for Speculation<'_, T> {
+    fn drop(&mut self) {
+        if let Some(checkpoint) = self.checkpoint {
+            self.arena.truncate(checkpoint);
+        }
+    }
+}
+
 #[cfg(feature = "deserialize")]
 impl<'de, T> serde::Deserialize<'de> for Arena<T>
 where
@@ -515,6 +622,59 @@ mod tests {
         assert!(t1 != t2);
         assert!(arena[t1] != arena[t2]);
     }
+
+    #[test]
+    fn checkpoint_truncate() {
+        let mut arena: Arena<u8> = Arena::new();
+        let t1 = arena.append(1, Span::new(0, 1));
+        let outer = arena.checkpoint();
+        arena.append(2, Span::new(1, 2));
+        let inner = arena.checkpoint();
+        arena.append(3, Span::new(2, 3));
+
+        arena.truncate(inner);
+        assert_eq!(arena.len(), 2);
+        arena.truncate(outer);
+        assert_eq!(arena.len(), 1);
+        // Truncating to a checkpoint past the end does nothing.
+        arena.truncate(inner);
+        assert_eq!(arena.len(), 1);
+
+        // Spans are truncated along with the elements.
+        let t2 = arena.append(4, Span::new(3, 4));
+        assert_eq!(arena[t1], 1);
+        assert_eq!(arena[t2], 4);
+        assert_eq!(arena.get_span(t2), Span::new(3, 4));
+    }
+
+    #[test]
+    fn speculate() {
+        let mut arena: Arena<u8> = Arena::new();
+        arena.append(1, Default::default());
+
+        {
+            let mut outer = arena.speculate();
+            outer.append(2, Default::default());
+            {
+                let mut inner = outer.speculate();
+                inner.append(3, Default::default());
+                assert_eq!(inner.len(), 3);
+            }
+            assert_eq!(outer.len(), 2);
+            {
+                let mut inner = outer.speculate();
+                inner.append(4, Default::default());
+                inner.commit();
+            }
+            assert_eq!(outer.len(), 3);
+        }
+        assert_eq!(arena.len(), 1);
+
+        let mut committed = arena.speculate();
+        committed.append(5, Default::default());
+        committed.commit();
+        assert_eq!(arena.into_inner(), [1, 5]);
+    }
 }
 
 /// An arena whose elements are guaranteed to be unique.
@@ -645,6 +805,12 @@ impl<T: Eq + hash::Hash> UniqueArena<T> {
         if added {
             debug_assert!(index == self.span_info.len());
             self.span_info.push(span);
+        } else {
+            log::debug!(
+                "deduplicated {} {:?}",
+                std::any::type_name::<T>(),
+                Handle::<T>::from_usize(index)
+            );
         }
 
         debug_assert!(self.set.len() == self.span_info.len());
@@ -740,33 +906,121 @@ where
     }
 }
 
-//Note: largely borrowed from `HashSet` implementation
+/// Support for generating structurally sound IR with [`arbitrary`].
+///
+/// A [`Handle`] generated from nothing but random bytes almost never refers
+/// to anything, so the validator would reject nearly every arbitrary module
+/// before it got to anything interesting. Instead, as each [`Arena`] or
+/// [`UniqueArena`] is generated, we record how many elements it holds so
+/// far, and handles of that element type are chosen from among those.
+///
+/// Since an element can only refer to elements generated before it, this
+/// also produces arenas in the dependency order the validator expects.
+/// Handles into arenas that haven't been generated yet can have their
+/// length reserved in advance with [`set_len`]; see [`Module`]'s
+/// implementation of `Arbitrary`.
+///
+/// [`arbitrary`]: https://docs.rs/arbitrary
+/// [`Module`]: crate::Module
 #[cfg(feature = "arbitrary")]
-impl<'a, T> arbitrary::Arbitrary<'a> for UniqueArena<T>
-where
-    T: Eq + hash::Hash + arbitrary::Arbitrary<'a>,
-{
-    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
-        let mut arena = Self::default();
-        for elem in u.arbitrary_iter()? {
-            arena.set.insert(elem?);
-            arena.span_info.push(Span::UNDEFINED);
+pub(crate) mod arbitrary_handles {
+    use super::{Arena, Handle, Index, Range, Span, UniqueArena};
+    use arbitrary::{Arbitrary, Result, Unstructured};
+    use std::{any::TypeId, cell::RefCell, hash::Hash};
+
+    thread_local! {
+        /// The number of elements handles of each type may refer to.
+        static LENGTHS: RefCell<crate::FastHashMap<TypeId, usize>> = RefCell::default();
+    }
+
+    /// Forget all arena lengths, to start generating a new module.
+    pub fn reset() {
+        LENGTHS.with(|lengths| lengths.borrow_mut().clear());
+    }
+
+    /// Let handles to `T` refer to the first `len` elements of its arena.
+    pub fn set_len<T: 'static>(len: usize) {
+        LENGTHS.with(|lengths| lengths.borrow_mut().insert(TypeId::of::<T>(), len));
+    }
+
+    fn len<T: 'static>() -> Option<usize> {
+        LENGTHS.with(|lengths| lengths.borrow().get(&TypeId::of::<T>()).copied())
+    }
+
+    /// Generate an arena element, retrying if it turns out to need a handle
+    /// into an arena with nothing in it yet.
+    fn element<'a, T: Arbitrary<'a>>(u: &mut Unstructured<'a>) -> Result<T> {
+        loop {
+            match T::arbitrary(u) {
+                Err(arbitrary::Error::IncorrectFormat) if !u.is_empty() => continue,
+                result => return result,
+            }
+        }
+    }
+
+    impl<T> Arena<T> {
+        /// Generate an arena with exactly `len` elements.
+        ///
+        /// Use this to fill an arena whose length was reserved with
+        /// [`set_len`] before other items that refer to it were generated.
+        pub(crate) fn arbitrary_with_len<'a>(u: &mut Unstructured<'a>, len: usize) -> Result<Self>
+        where
+            T: Arbitrary<'a> + 'static,
+        {
+            let mut arena = Self::new();
+            for index in 0..len {
+                set_len::<T>(index);
+                arena.append(element(u)?, Span::UNDEFINED);
+            }
+            set_len::<T>(len);
+            Ok(arena)
         }
-        Ok(arena)
     }
 
-    fn arbitrary_take_rest(u: arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
-        let mut arena = Self::default();
-        for elem in u.arbitrary_take_rest_iter()? {
-            arena.set.insert(elem?);
-            arena.span_info.push(Span::UNDEFINED);
+    impl<'a, T: Arbitrary<'a> + 'static> Arbitrary<'a> for Arena<T> {
+        fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+            let len = u.arbitrary_len::<T>()?;
+            Self::arbitrary_with_len(u, len)
         }
-        Ok(arena)
     }
 
-    #[inline]
-    fn size_hint(depth: usize) -> (usize, Option<usize>) {
-        let depth_hint = <usize as arbitrary::Arbitrary>::size_hint(depth);
-        arbitrary::size_hint::and(depth_hint, (0, None))
+    impl<'a, T> Arbitrary<'a> for UniqueArena<T>
+    where
+        T: Eq + Hash + Arbitrary<'a> + 'static,
+    {
+        fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+            let mut arena = Self::new();
+            for _ in 0..u.arbitrary_len::<T>()? {
+                set_len::<T>(arena.len());
+                arena.insert(element(u)?, Span::UNDEFINED);
+            }
+            set_len::<T>(arena.len());
+            Ok(arena)
+        }
+    }
+
+    impl<'a, T: 'static> Arbitrary<'a> for Handle<T> {
+        fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+            match len::<T>() {
+                // Nothing generated so far could refer to this, so there's
+                // no way to make this module sound.
+                Some(0) => Err(arbitrary::Error::IncorrectFormat),
+                Some(len) => Ok(Handle::from_usize(u.choose_index(len)?)),
+                // This arena's length is unknown, so any handle will do.
+                None => Ok(Handle::new(Index::arbitrary(u)?)),
+            }
+        }
+    }
+
+    impl<'a, T: 'static> Arbitrary<'a> for Range<T> {
+        fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+            let len = len::<T>().unwrap_or(0) as u32;
+            let start = u.int_in_range(0..=len)?;
+            let end = u.int_in_range(start..=len)?;
+            Ok(Range {
+                inner: start..end,
+                marker: Default::default(),
+            })
+        }
     }
 }
@@ -110,6 +110,28 @@ impl StatementGraph {
                     "Continue"
                 }
                 S::Barrier(_flags) => "Barrier",
+                S::MeshFunction(ref fun) => match *fun {
+                    crate::MeshFunction::SetMeshOutputs {
+                        vertex_count,
+                        primitive_count,
+                    } => {
+                        self.dependencies.push((id, vertex_count, "vertex_count"));
+                        self.dependencies
+                            .push((id, primitive_count, "primitive_count"));
+                        "SetMeshOutputs"
+                    }
+                    crate::MeshFunction::SetVertex { index, value } => {
+                        self.dependencies.push((id, index, "index"));
+                        self.dependencies.push((id, value, "value"));
+                        "SetVertex"
+                    }
+                    crate::MeshFunction::SetPrimitive { index, value } => {
+                        self.dependencies.push((id, index, "index"));
+                        self.dependencies.push((id, value, "value"));
+                        "SetPrimitive"
+                    }
+                },
+                S::DebugMarker(_) => "DebugMarker",
                 S::Block(ref b) => {
                     let (other, last) = self.add(b, targets);
                     self.flow.push((id, other, ""));
@@ -122,6 +144,7 @@ impl StatementGraph {
                     condition,
                     ref accept,
                     ref reject,
+                    hint: _,
                 } => {
                     self.dependencies.push((id, condition, "condition"));
                     let (accept_id, accept_last) = self.add(accept, targets);
@@ -170,6 +193,7 @@ impl StatementGraph {
                     ref body,
                     ref continuing,
                     break_if,
+                    hint: _,
                 } => {
                     // Create a new targets structure and set the break target
                     // to the merge node, this must happen before generating the
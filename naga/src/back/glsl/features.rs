@@ -1,6 +1,6 @@
 use super::{BackendResult, Error, Version, Writer};
 use crate::{
-    back::glsl::{Options, WriterFlags},
+    back::glsl::{InstanceIndexSource, Options, WriterFlags},
     AddressSpace, Binding, Expression, Handle, ImageClass, ImageDimension, Interpolation,
     SampleLevel, Sampling, Scalar, ScalarKind, ShaderStage, StorageFormat, Type, TypeInner,
 };
@@ -31,7 +31,7 @@ bitflags::bitflags! {
         const SAMPLE_QUALIFIER = 1 << 12;
         const CLIP_DISTANCE = 1 << 13;
         const CULL_DISTANCE = 1 << 14;
-        /// Sample ID.
+        /// Sample ID and sample masks.
         const SAMPLE_VARIABLES = 1 << 15;
         /// Arrays with a dynamic length.
         const DYNAMIC_ARRAY_SIZE = 1 << 16;
@@ -50,6 +50,8 @@ bitflags::bitflags! {
         const INSTANCE_INDEX = 1 << 22;
         /// Sample specific LODs of cube / array shadow textures
         const TEXTURE_SHADOW_LOD = 1 << 23;
+        /// 16-bit floating point types
+        const HALF_FLOAT = 1 << 24;
     }
 }
 
@@ -111,10 +113,10 @@ impl FeaturesManager {
         check_feature!(IMAGE_LOAD_STORE, 130, 310);
         check_feature!(CONSERVATIVE_DEPTH, 130, 300);
         check_feature!(NOPERSPECTIVE_QUALIFIER, 130);
-        check_feature!(SAMPLE_QUALIFIER, 400, 320);
+        check_feature!(SAMPLE_QUALIFIER, 400, 300 /* with extension */);
         check_feature!(CLIP_DISTANCE, 130, 300 /* with extension */);
         check_feature!(CULL_DISTANCE, 450, 300 /* with extension */);
-        check_feature!(SAMPLE_VARIABLES, 400, 300);
+        check_feature!(SAMPLE_VARIABLES, 400, 300 /* with extension */);
         check_feature!(DYNAMIC_ARRAY_SIZE, 430, 310);
         check_feature!(DUAL_SOURCE_BLENDING, 330, 300 /* with extension */);
         match version {
@@ -128,6 +130,7 @@ impl FeaturesManager {
         check_feature!(TEXTURE_LEVELS, 130);
         check_feature!(IMAGE_SIZE, 430, 310);
         check_feature!(TEXTURE_SHADOW_LOD, 200, 300);
+        check_feature!(HALF_FLOAT, 450);
 
         // Return an error if there are missing features
         if missing.is_empty() {
@@ -137,74 +140,71 @@ impl FeaturesManager {
         }
     }
 
-    /// Helper method used to write all needed extensions
+    /// Helper method used to write all needed extensions, followed by the
+    /// ones requested in [`Options::extensions`]
     ///
     /// # Notes
     /// This won't check for feature availability so it might output extensions that aren't even
     /// supported.[`check_availability`](Self::check_availability) will check feature availability
     pub fn write(&self, options: &Options, mut out: impl Write) -> BackendResult {
+        let mut extensions = Vec::new();
+
         if self.0.contains(Features::COMPUTE_SHADER) && !options.version.is_es() {
             // https://www.khronos.org/registry/OpenGL/extensions/ARB/ARB_compute_shader.txt
-            writeln!(out, "#extension GL_ARB_compute_shader : require")?;
+            extensions.push("GL_ARB_compute_shader");
         }
 
         if self.0.contains(Features::BUFFER_STORAGE) && !options.version.is_es() {
             // https://www.khronos.org/registry/OpenGL/extensions/ARB/ARB_shader_storage_buffer_object.txt
-            writeln!(
-                out,
-                "#extension GL_ARB_shader_storage_buffer_object : require"
-            )?;
+            extensions.push("GL_ARB_shader_storage_buffer_object");
         }
 
         if self.0.contains(Features::DOUBLE_TYPE) && options.version < Version::Desktop(400) {
             // https://www.khronos.org/registry/OpenGL/extensions/ARB/ARB_gpu_shader_fp64.txt
-            writeln!(out, "#extension GL_ARB_gpu_shader_fp64 : require")?;
+            extensions.push("GL_ARB_gpu_shader_fp64");
         }
 
         if self.0.contains(Features::CUBE_TEXTURES_ARRAY) {
             if options.version.is_es() {
                 // https://www.khronos.org/registry/OpenGL/extensions/EXT/EXT_texture_cube_map_array.txt
-                writeln!(out, "#extension GL_EXT_texture_cube_map_array : require")?;
+                extensions.push("GL_EXT_texture_cube_map_array");
             } else if options.version < Version::Desktop(400) {
                 // https://www.khronos.org/registry/OpenGL/extensions/ARB/ARB_texture_cube_map_array.txt
-                writeln!(out, "#extension GL_ARB_texture_cube_map_array : require")?;
+                extensions.push("GL_ARB_texture_cube_map_array");
             }
         }
 
         if self.0.contains(Features::MULTISAMPLED_TEXTURE_ARRAYS) && options.version.is_es() {
             // https://www.khronos.org/registry/OpenGL/extensions/OES/OES_texture_storage_multisample_2d_array.txt
-            writeln!(
-                out,
-                "#extension GL_OES_texture_storage_multisample_2d_array : require"
-            )?;
+            extensions.push("GL_OES_texture_storage_multisample_2d_array");
         }
 
         if self.0.contains(Features::ARRAY_OF_ARRAYS) && options.version < Version::Desktop(430) {
             // https://www.khronos.org/registry/OpenGL/extensions/ARB/ARB_arrays_of_arrays.txt
-            writeln!(out, "#extension ARB_arrays_of_arrays : require")?;
+            extensions.push("ARB_arrays_of_arrays");
         }
 
         if self.0.contains(Features::IMAGE_LOAD_STORE) {
             if self.0.contains(Features::FULL_IMAGE_FORMATS) && options.version.is_es() {
                 // https://www.khronos.org/registry/OpenGL/extensions/NV/NV_image_formats.txt
-                writeln!(out, "#extension GL_NV_image_formats : require")?;
+                extensions.push("GL_NV_image_formats");
             }
 
             if options.version < Version::Desktop(420) {
                 // https://www.khronos.org/registry/OpenGL/extensions/ARB/ARB_shader_image_load_store.txt
-                writeln!(out, "#extension GL_ARB_shader_image_load_store : require")?;
+                extensions.push("GL_ARB_shader_image_load_store");
             }
         }
 
         if self.0.contains(Features::CONSERVATIVE_DEPTH) {
             if options.version.is_es() {
                 // https://www.khronos.org/registry/OpenGL/extensions/EXT/EXT_conservative_depth.txt
-                writeln!(out, "#extension GL_EXT_conservative_depth : require")?;
+                extensions.push("GL_EXT_conservative_depth");
             }
 
             if options.version < Version::Desktop(420) {
                 // https://www.khronos.org/registry/OpenGL/extensions/ARB/ARB_conservative_depth.txt
-                writeln!(out, "#extension GL_ARB_conservative_depth : require")?;
+                extensions.push("GL_ARB_conservative_depth");
             }
         }
 
@@ -212,51 +212,77 @@ impl FeaturesManager {
             && options.version.is_es()
         {
             // https://www.khronos.org/registry/OpenGL/extensions/EXT/EXT_clip_cull_distance.txt
-            writeln!(out, "#extension GL_EXT_clip_cull_distance : require")?;
+            extensions.push("GL_EXT_clip_cull_distance");
         }
 
-        if self.0.contains(Features::SAMPLE_VARIABLES) && options.version.is_es() {
+        if self.0.contains(Features::SAMPLE_VARIABLES) && options.version < Version::new_gles(320) {
             // https://www.khronos.org/registry/OpenGL/extensions/OES/OES_sample_variables.txt
-            writeln!(out, "#extension GL_OES_sample_variables : require")?;
+            extensions.push("GL_OES_sample_variables");
+        }
+
+        if self.0.contains(Features::SAMPLE_QUALIFIER) && options.version < Version::new_gles(320) {
+            // https://www.khronos.org/registry/OpenGL/extensions/OES/OES_shader_multisample_interpolation.txt
+            extensions.push("GL_OES_shader_multisample_interpolation");
         }
 
         if self.0.contains(Features::MULTI_VIEW) {
             if let Version::Embedded { is_webgl: true, .. } = options.version {
                 // https://www.khronos.org/registry/OpenGL/extensions/OVR/OVR_multiview2.txt
-                writeln!(out, "#extension GL_OVR_multiview2 : require")?;
+                extensions.push("GL_OVR_multiview2");
             } else {
                 // https://github.com/KhronosGroup/GLSL/blob/master/extensions/ext/GL_EXT_multiview.txt
-                writeln!(out, "#extension GL_EXT_multiview : require")?;
+                extensions.push("GL_EXT_multiview");
             }
         }
 
         if self.0.contains(Features::TEXTURE_SAMPLES) {
             // https://www.khronos.org/registry/OpenGL/extensions/ARB/ARB_shader_texture_image_samples.txt
-            writeln!(
-                out,
-                "#extension GL_ARB_shader_texture_image_samples : require"
-            )?;
+            extensions.push("GL_ARB_shader_texture_image_samples");
         }
 
         if self.0.contains(Features::TEXTURE_LEVELS) && options.version < Version::Desktop(430) {
             // https://www.khronos.org/registry/OpenGL/extensions/ARB/ARB_texture_query_levels.txt
-            writeln!(out, "#extension GL_ARB_texture_query_levels : require")?;
+            extensions.push("GL_ARB_texture_query_levels");
         }
         if self.0.contains(Features::DUAL_SOURCE_BLENDING) && options.version.is_es() {
             // https://registry.khronos.org/OpenGL/extensions/EXT/EXT_blend_func_extended.txt
-            writeln!(out, "#extension GL_EXT_blend_func_extended : require")?;
+            extensions.push("GL_EXT_blend_func_extended");
         }
 
         if self.0.contains(Features::INSTANCE_INDEX) {
-            if options.writer_flags.contains(WriterFlags::DRAW_PARAMETERS) {
+            if options.instance_index_source() == InstanceIndexSource::DrawParameters {
                 // https://registry.khronos.org/OpenGL/extensions/ARB/ARB_shader_draw_parameters.txt
-                writeln!(out, "#extension GL_ARB_shader_draw_parameters : require")?;
+                extensions.push("GL_ARB_shader_draw_parameters");
             }
         }
 
         if self.0.contains(Features::TEXTURE_SHADOW_LOD) {
             // https://registry.khronos.org/OpenGL/extensions/EXT/EXT_texture_shadow_lod.txt
-            writeln!(out, "#extension GL_EXT_texture_shadow_lod : require")?;
+            extensions.push("GL_EXT_texture_shadow_lod");
+        }
+
+        if self.0.contains(Features::HALF_FLOAT) {
+            // https://registry.khronos.org/OpenGL/extensions/AMD/AMD_gpu_shader_half_float.txt
+            extensions.push("GL_AMD_gpu_shader_half_float");
+        }
+
+        for name in extensions.iter() {
+            writeln!(out, "#extension {name} : require")?;
+        }
+
+        // Extensions requested in the options come after the ones we need,
+        // skipping any that are already written.
+        for extension in options.extensions.iter() {
+            if extensions.contains(&extension.name.as_str()) {
+                continue;
+            }
+            extensions.push(&extension.name);
+            writeln!(
+                out,
+                "#extension {} : {}",
+                extension.name,
+                extension.behavior.to_str()
+            )?;
         }
 
         Ok(())
@@ -270,16 +296,25 @@ impl<'a, W> Writer<'a, W> {
     /// If the version doesn't support any of the needed [`Features`] a
     /// [`Error::MissingFeatures`] will be returned
     pub(super) fn collect_required_features(&mut self) -> BackendResult {
+        if crate::external_texture::has_external_textures(self.module) {
+            return Err(Error::Custom(
+                "external textures must be lowered first".to_string(),
+            ));
+        }
+
         let ep_info = self.info.get_entry_point(self.entry_point_idx as usize);
 
         if let Some(depth_test) = self.entry_point.early_depth_test {
-            // If IMAGE_LOAD_STORE is supported for this version of GLSL
-            if self.options.version.supports_early_depth_test() {
-                self.features.request(Features::IMAGE_LOAD_STORE);
-            }
-
-            if depth_test.conservative.is_some() {
-                self.features.request(Features::CONSERVATIVE_DEPTH);
+            match depth_test.conservative {
+                // If IMAGE_LOAD_STORE is supported for this version of GLSL
+                None if self.options.version.supports_early_depth_test() => {
+                    self.features.request(Features::IMAGE_LOAD_STORE);
+                }
+                None => {}
+                Some(_) if self.writes_frag_depth() => {
+                    self.features.request(Features::CONSERVATIVE_DEPTH);
+                }
+                Some(_) => {}
             }
         }
 
@@ -390,6 +425,7 @@ impl<'a, W> Writer<'a, W> {
                         },
                         ImageClass::Sampled { multi: false, .. }
                         | ImageClass::Depth { multi: false } => {}
+                        ImageClass::External => {}
                     }
                 }
                 _ => {}
@@ -518,6 +554,12 @@ impl<'a, W> Writer<'a, W> {
                         }
                     }
                 }
+                Expression::Math { fun: crate::MathFunction::QuantizeToF16, .. } => {
+                    // Without the extension, we round trip through `packHalf2x16`.
+                    if self.options.writer_flags.contains(WriterFlags::HALF_FLOAT) {
+                        features.request(Features::HALF_FLOAT);
+                    }
+                }
                 _ => {}
             }
             }
@@ -550,7 +592,7 @@ impl<'a, W> Writer<'a, W> {
                             crate::BuiltIn::CullDistance => {
                                 self.features.request(Features::CULL_DISTANCE)
                             }
-                            crate::BuiltIn::SampleIndex => {
+                            crate::BuiltIn::SampleIndex | crate::BuiltIn::SampleMask => {
                                 self.features.request(Features::SAMPLE_VARIABLES)
                             }
                             crate::BuiltIn::ViewIndex => {
@@ -582,4 +624,18 @@ impl<'a, W> Writer<'a, W> {
             }
         }
     }
+
+    /// Return true if the entry point's result includes `FragDepth`.
+    pub(super) fn writes_frag_depth(&self) -> bool {
+        let Some(ref result) = self.entry_point.function.result else {
+            return false;
+        };
+        let is_frag_depth = |binding| binding == Some(&Binding::BuiltIn(crate::BuiltIn::FragDepth));
+        match self.module.types[result.ty].inner {
+            TypeInner::Struct { ref members, .. } => members
+                .iter()
+                .any(|member| is_frag_depth(member.binding.as_ref())),
+            _ => is_frag_depth(result.binding.as_ref()),
+        }
+    }
 }
@@ -201,9 +201,17 @@ impl Version {
         *self >= Version::Desktop(400) || *self >= Version::new_gles(310)
     }
 
+    fn supports_integer_mix(&self) -> bool {
+        *self >= Version::Desktop(450) || *self >= Version::new_gles(310)
+    }
+
     fn supports_derivative_control(&self) -> bool {
         *self >= Version::Desktop(450)
     }
+
+    fn supports_base_instance(&self) -> bool {
+        *self >= Version::Desktop(460)
+    }
 }
 
 impl PartialOrd for Version {
@@ -252,6 +260,17 @@ bitflags::bitflags! {
         /// The variable gl_PointSize is intended for a shader to write the size of the point to be rasterized. It is measured in pixels.
         /// If gl_PointSize is not written to, its value is undefined in subsequent pipe stages.
         const FORCE_POINT_SIZE = 0x20;
+        /// Emit every loop as a `for` loop with an induction counter capped at
+        /// [`Options::max_loop_iterations`], instead of `while(true)`.
+        ///
+        /// Some GLES drivers miscompile or hang on loops they can't prove are
+        /// bounded. The original break logic is left intact, so loops that
+        /// terminate within the cap behave exactly as before.
+        const FORCE_BOUNDED_LOOPS = 0x40;
+        /// Supports GL_AMD_gpu_shader_half_float on the host, which lets
+        /// `quantizeToF16` convert through `float16_t` instead of packing
+        /// values with `packHalf2x16`.
+        const HALF_FLOAT = 0x80;
     }
 }
 
@@ -268,6 +287,43 @@ pub struct Options {
     pub binding_map: BindingMap,
     /// Should workgroup variables be zero initialized (by polyfilling)?
     pub zero_initialize_workgroup_memory: bool,
+    /// The iteration cap used by [`WriterFlags::FORCE_BOUNDED_LOOPS`].
+    #[cfg_attr(
+        feature = "deserialize",
+        serde(default = "Options::default_max_loop_iterations")
+    )]
+    pub max_loop_iterations: u32,
+    /// Extra `#extension` directives, written after the ones the writer
+    /// needs itself.
+    ///
+    /// Extensions the writer already requires, or that appear earlier in
+    /// this list, are skipped.
+    #[cfg_attr(feature = "deserialize", serde(default))]
+    pub extensions: Vec<Extension>,
+    /// Source inserted verbatim after the version, extension and precision
+    /// lines, but before any declarations.
+    ///
+    /// This can hold pragmas, precision overrides or defines the generated
+    /// code doesn't know about.
+    #[cfg_attr(feature = "deserialize", serde(default))]
+    pub prologue: Option<String>,
+}
+
+impl Options {
+    const fn default_max_loop_iterations() -> u32 {
+        1 << 31
+    }
+
+    /// How `instance_index` is computed for these options.
+    fn instance_index_source(&self) -> InstanceIndexSource {
+        if self.version.supports_base_instance() {
+            InstanceIndexSource::BaseInstance
+        } else if self.writer_flags.contains(WriterFlags::DRAW_PARAMETERS) {
+            InstanceIndexSource::DrawParameters
+        } else {
+            InstanceIndexSource::Uniform
+        }
+    }
 }
 
 impl Default for Options {
@@ -277,10 +333,44 @@ impl Default for Options {
             writer_flags: WriterFlags::ADJUST_COORDINATE_SPACE,
             binding_map: BindingMap::default(),
             zero_initialize_workgroup_memory: true,
+            max_loop_iterations: Self::default_max_loop_iterations(),
+            extensions: Vec::new(),
+            prologue: None,
+        }
+    }
+}
+
+/// How an [`Extension`] requested in [`Options::extensions`] is enabled.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+pub enum ExtensionBehavior {
+    /// Fail to compile if the extension isn't supported.
+    Require,
+    /// Warn if the extension isn't supported.
+    Enable,
+}
+
+impl ExtensionBehavior {
+    const fn to_str(self) -> &'static str {
+        match self {
+            Self::Require => "require",
+            Self::Enable => "enable",
         }
     }
 }
 
+/// An `#extension` directive to write in the generated source.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+pub struct Extension {
+    /// The extension name, like `GL_EXT_shader_framebuffer_fetch`.
+    pub name: String,
+    /// What the compiler should do if the extension isn't supported.
+    pub behavior: ExtensionBehavior,
+}
+
 /// A subset of options meant to be changed per pipeline.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serialize", derive(serde::Serialize))]
@@ -306,6 +396,22 @@ pub struct VaryingLocation {
     pub index: u32,
 }
 
+/// Where a vertex shader gets the first instance of the draw from, to add
+/// to `gl_InstanceID` when computing the `instance_index` built-in.
+///
+/// Unlike WGSL's `instance_index`, `gl_InstanceID` doesn't include the
+/// draw's first instance.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InstanceIndexSource {
+    /// A `uint` uniform named [`FIRST_INSTANCE_BINDING`], which the user
+    /// must set to the first instance before each draw.
+    Uniform,
+    /// `gl_BaseInstanceARB`, from [`WriterFlags::DRAW_PARAMETERS`].
+    DrawParameters,
+    /// `gl_BaseInstance`, which is core in GLSL 4.60.
+    BaseInstance,
+}
+
 /// Reflection info for texture mappings and uniforms.
 #[derive(Debug)]
 pub struct ReflectionInfo {
@@ -317,6 +423,9 @@ pub struct ReflectionInfo {
     pub varying: crate::FastHashMap<String, VaryingLocation>,
     /// List of push constant items in the shader.
     pub push_constant_items: Vec<PushConstantItem>,
+    /// How the `instance_index` built-in is computed, if the vertex shader
+    /// uses it.
+    pub instance_index: Option<InstanceIndexSource>,
 }
 
 /// Mapping between a texture and its sampler, if it exists.
@@ -399,15 +508,15 @@ impl IdGenerator {
 struct VaryingOptions {
     output: bool,
     targeting_webgl: bool,
-    draw_parameters: bool,
+    instance_index: InstanceIndexSource,
 }
 
 impl VaryingOptions {
-    const fn from_writer_options(options: &Options, output: bool) -> Self {
+    fn from_writer_options(options: &Options, output: bool) -> Self {
         Self {
             output,
             targeting_webgl: options.version.is_webgl(),
-            draw_parameters: options.writer_flags.contains(WriterFlags::DRAW_PARAMETERS),
+            instance_index: options.instance_index_source(),
         }
     }
 }
@@ -435,7 +544,9 @@ impl fmt::Display for VaryingName<'_> {
             }
             crate::Binding::Location { location, .. } => {
                 let prefix = match (self.stage, self.options.output) {
-                    (ShaderStage::Compute, _) => unreachable!(),
+                    (ShaderStage::Compute | ShaderStage::Task | ShaderStage::Mesh, _) => {
+                        unreachable!()
+                    }
                     // pipeline to vertex
                     (ShaderStage::Vertex, false) => "p2vs",
                     // vertex to fragment
@@ -458,6 +569,8 @@ impl ShaderStage {
             ShaderStage::Compute => "cs",
             ShaderStage::Fragment => "fs",
             ShaderStage::Vertex => "vs",
+            ShaderStage::Task => "ts",
+            ShaderStage::Mesh => "ms",
         }
     }
 }
@@ -484,8 +597,24 @@ pub enum Error {
     #[error("The specified version isn't supported")]
     VersionNotSupported,
     /// The entry point couldn't be found.
-    #[error("The requested entry point couldn't be found")]
-    EntryPointNotFound,
+    #[error(
+        "The requested {stage:?} entry point `{name}` couldn't be found; \
+         the module's entry points are: {available}"
+    )]
+    EntryPointNotFound {
+        stage: ShaderStage,
+        name: String,
+        available: back::EntryPointNames,
+    },
+    /// More than one entry point matches the [`PipelineOptions`].
+    ///
+    /// Validation rejects such modules, so this can only happen if the
+    /// module changed after it was validated.
+    #[error("The module has more than one {stage:?} entry point named `{name}`")]
+    AmbiguousEntryPoint { stage: ShaderStage, name: String },
+    /// GLSL has no equivalent of the requested shader stage.
+    #[error("{0:?} shaders aren't supported")]
+    UnsupportedStage(ShaderStage),
     /// A call was made to an unsupported external.
     #[error("A call was made to an unsupported external: {0}")]
     UnsupportedExternal(String),
@@ -511,6 +640,25 @@ enum BinaryOperation {
     Other,
 }
 
+/// A [`Statement::Loop`] that GLSL can write with a native loop condition.
+///
+/// [`Statement::Loop`]: crate::Statement::Loop
+enum LoopShape<'a> {
+    /// The body starts by breaking unless `condition` holds: `while(cond) { .. }`.
+    While {
+        condition: Handle<crate::Expression>,
+        /// Whether the loop breaks when `condition` is true, rather than false.
+        negate: bool,
+        /// The rest of the body, after the breaking `if`.
+        body: &'a [crate::Statement],
+    },
+    /// The loop breaks at the end of each iteration when `condition` holds:
+    /// `do { .. } while(!cond);`.
+    DoWhile {
+        condition: Handle<crate::Expression>,
+    },
+}
+
 /// Writer responsible for all code generation.
 pub struct Writer<'a, W> {
     // Inputs
@@ -548,6 +696,12 @@ pub struct Writer<'a, W> {
     multiview: Option<std::num::NonZeroU32>,
     /// Mapping of varying variables to their location. Needed for reflections.
     varying: crate::FastHashMap<String, VaryingLocation>,
+    /// Non-multisampled depth images that are loaded from, and never sampled
+    /// with a depth comparison.
+    ///
+    /// `texelFetch` doesn't accept shadow samplers, so these are declared as
+    /// plain `sampler2D`s and friends instead.
+    loaded_depth_images: crate::FastHashSet<Handle<crate::GlobalVariable>>,
 }
 
 impl<'a, W: Write> Writer<'a, W> {
@@ -571,14 +725,30 @@ impl<'a, W: Write> Writer<'a, W> {
             return Err(Error::VersionNotSupported);
         }
 
+        if let ShaderStage::Task | ShaderStage::Mesh = pipeline_options.shader_stage {
+            return Err(Error::UnsupportedStage(pipeline_options.shader_stage));
+        }
+
         // Try to find the entry point and corresponding index
-        let ep_idx = module
-            .entry_points
-            .iter()
-            .position(|ep| {
-                pipeline_options.shader_stage == ep.stage && pipeline_options.entry_point == ep.name
-            })
-            .ok_or(Error::EntryPointNotFound)?;
+        let ep_idx = back::find_entry_point(
+            module,
+            pipeline_options.shader_stage,
+            &pipeline_options.entry_point,
+        )
+        .map_err(|error| {
+            let stage = pipeline_options.shader_stage;
+            let name = pipeline_options.entry_point.clone();
+            match error {
+                back::EntryPointLookupError::NotFound => Error::EntryPointNotFound {
+                    stage,
+                    name,
+                    available: back::EntryPointNames::new(module),
+                },
+                back::EntryPointLookupError::Ambiguous => {
+                    Error::AmbiguousEntryPoint { stage, name }
+                }
+            }
+        })?;
 
         // Generate a map with names required to write the module
         let mut names = crate::FastHashMap::default();
@@ -615,6 +785,7 @@ impl<'a, W: Write> Writer<'a, W> {
             named_expressions: Default::default(),
             need_bake_expressions: Default::default(),
             varying: Default::default(),
+            loaded_depth_images: collect_loaded_depth_images(module),
         };
 
         // Find all features required to print this module
@@ -655,7 +826,17 @@ impl<'a, W: Write> Writer<'a, W> {
             writeln!(self.out)?;
         }
 
+        if let Some(ref prologue) = self.options.prologue {
+            writeln!(self.out, "{}", prologue.trim_end())?;
+            writeln!(self.out)?;
+        }
+
         if self.entry_point.stage == ShaderStage::Compute {
+            if self.entry_point.workgroup_size_overrides.is_some() {
+                return Err(Error::Custom(
+                    "Workgroup sizes from overrides aren't supported".to_string(),
+                ));
+            }
             let workgroup_size = self.entry_point.workgroup_size;
             writeln!(
                 self.out,
@@ -666,10 +847,7 @@ impl<'a, W: Write> Writer<'a, W> {
         }
 
         if self.entry_point.stage == ShaderStage::Vertex
-            && !self
-                .options
-                .writer_flags
-                .contains(WriterFlags::DRAW_PARAMETERS)
+            && self.options.instance_index_source() == InstanceIndexSource::Uniform
             && self.features.contains(Features::INSTANCE_INDEX)
         {
             writeln!(self.out, "uniform uint {FIRST_INSTANCE_BINDING};")?;
@@ -677,28 +855,37 @@ impl<'a, W: Write> Writer<'a, W> {
         }
 
         // Enable early depth tests if needed
-        if let Some(depth_test) = self.entry_point.early_depth_test {
-            // If early depth test is supported for this version of GLSL
-            if self.options.version.supports_early_depth_test() {
-                writeln!(self.out, "layout(early_fragment_tests) in;")?;
-
-                if let Some(conservative) = depth_test.conservative {
-                    use crate::ConservativeDepth as Cd;
-
-                    let depth = match conservative {
-                        Cd::GreaterEqual => "greater",
-                        Cd::LessEqual => "less",
-                        Cd::Unchanged => "unchanged",
-                    };
-                    writeln!(self.out, "layout (depth_{depth}) out float gl_FragDepth;")?;
+        match self.entry_point.early_depth_test {
+            Some(crate::EarlyDepthTest { conservative: None }) => {
+                // If early depth test is supported for this version of GLSL
+                if self.options.version.supports_early_depth_test() {
+                    writeln!(self.out, "layout(early_fragment_tests) in;")?;
+                    writeln!(self.out)?;
+                } else {
+                    log::warn!(
+                        "Early depth testing is not supported for this version of GLSL: {}",
+                        self.options.version
+                    );
                 }
+            }
+            // The conservative modes only describe how the written depth
+            // relates to the interpolated one, so they're declared on
+            // `gl_FragDepth` itself, and only when it's written. Forcing
+            // early tests would make the driver ignore the written depth.
+            Some(crate::EarlyDepthTest {
+                conservative: Some(conservative),
+            }) if self.writes_frag_depth() => {
+                use crate::ConservativeDepth as Cd;
+
+                let depth = match conservative {
+                    Cd::GreaterEqual => "greater",
+                    Cd::LessEqual => "less",
+                    Cd::Unchanged => "unchanged",
+                };
+                writeln!(self.out, "layout(depth_{depth}) out float gl_FragDepth;")?;
                 writeln!(self.out)?;
-            } else {
-                log::warn!(
-                    "Early depth testing is not supported for this version of GLSL: {}",
-                    self.options.version
-                );
             }
+            _ => {}
         }
 
         if self.entry_point.stage == ShaderStage::Vertex && self.options.version.is_webgl() {
@@ -875,6 +1062,16 @@ impl<'a, W: Write> Writer<'a, W> {
                     // The trailing space is important
                     write!(self.out, "uniform ")?;
 
+                    // Depth images we load from can't be shadow samplers
+                    let class = if self.loaded_depth_images.contains(&handle) {
+                        crate::ImageClass::Sampled {
+                            kind: crate::ScalarKind::Float,
+                            multi: false,
+                        }
+                    } else {
+                        class
+                    };
+
                     // write the type
                     //
                     // This is way we need the leading space because `write_image_type` doesn't add
@@ -963,6 +1160,11 @@ impl<'a, W: Write> Writer<'a, W> {
             crate::ArraySize::Constant(size) => {
                 write!(self.out, "{size}")?;
             }
+            crate::ArraySize::Pending(_) => {
+                return Err(Error::Custom(
+                    "Arrays sized by an override aren't supported".to_string(),
+                ))
+            }
             crate::ArraySize::Dynamic => (),
         }
 
@@ -1088,6 +1290,7 @@ impl<'a, W: Write> Writer<'a, W> {
             Ic::Depth { multi: true } => ("sampler", crate::ScalarKind::Float, "MS", ""),
             Ic::Depth { multi: false } => ("sampler", crate::ScalarKind::Float, "", "Shadow"),
             Ic::Storage { format, .. } => ("image", format.into(), "", ""),
+            Ic::External => unreachable!(),
         };
 
         let precision = if self.options.version.is_es() {
@@ -1154,6 +1357,18 @@ impl<'a, W: Write> Writer<'a, W> {
 
         if let crate::AddressSpace::Storage { access } = global.space {
             self.write_storage_access(access)?;
+            if global
+                .memory_decorations
+                .contains(crate::MemoryDecorations::COHERENT)
+            {
+                write!(self.out, "coherent ")?;
+            }
+            if global
+                .memory_decorations
+                .contains(crate::MemoryDecorations::VOLATILE)
+            {
+                write!(self.out, "volatile ")?;
+            }
         }
 
         if let Some(storage_qualifier) = glsl_storage_qualifier(global.space) {
@@ -1290,6 +1505,22 @@ impl<'a, W: Write> Writer<'a, W> {
 
             let inner = expr_info.ty.inner_with(&self.module.types);
 
+            if let Expression::Select {
+                condition,
+                accept,
+                reject,
+            } = *expr
+            {
+                let condition_inner = info[condition].ty.inner_with(&self.module.types);
+                if let TypeInner::Vector { .. } = *condition_inner {
+                    if self.select_by_component(inner) {
+                        self.need_bake_expressions.insert(condition);
+                        self.need_bake_expressions.insert(accept);
+                        self.need_bake_expressions.insert(reject);
+                    }
+                }
+            }
+
             if let Expression::Math { fun, arg, arg1, .. } = *expr {
                 match fun {
                     crate::MathFunction::Dot => {
@@ -1305,6 +1536,10 @@ impl<'a, W: Write> Writer<'a, W> {
                             }
                         }
                     }
+                    crate::MathFunction::Dot4I8Packed | crate::MathFunction::Dot4U8Packed => {
+                        self.need_bake_expressions.insert(arg);
+                        self.need_bake_expressions.insert(arg1.unwrap());
+                    }
                     crate::MathFunction::CountLeadingZeros => {
                         if let Some(crate::ScalarKind::Sint) = inner.scalar_kind() {
                             self.need_bake_expressions.insert(arg);
@@ -1316,6 +1551,23 @@ impl<'a, W: Write> Writer<'a, W> {
         }
     }
 
+    /// Return `true` if a [`Select`] of `inner` values by a vector of
+    /// booleans must select each component separately.
+    ///
+    /// Before GLSL 4.50 and ES 3.10, `mix` only takes a vector of booleans to
+    /// select between floats.
+    ///
+    /// [`Select`]: crate::Expression::Select
+    fn select_by_component(&self, inner: &TypeInner) -> bool {
+        match *inner {
+            TypeInner::Vector { scalar, .. } => {
+                scalar.kind != crate::ScalarKind::Float
+                    && !self.options.version.supports_integer_mix()
+            }
+            _ => false,
+        }
+    }
+
     /// Helper method used to get a name for a global
     ///
     /// Globals have different naming schemes depending on their binding:
@@ -1465,78 +1717,146 @@ impl<'a, W: Write> Writer<'a, W> {
         let emit_interpolation_and_auxiliary = match self.entry_point.stage {
             ShaderStage::Vertex => output,
             ShaderStage::Fragment => !output,
-            ShaderStage::Compute => false,
+            ShaderStage::Compute | ShaderStage::Task | ShaderStage::Mesh => false,
         };
 
-        // Write the I/O locations, if allowed
-        let io_location = if self.options.version.supports_explicit_locations()
-            || !emit_interpolation_and_auxiliary
-        {
-            if self.options.version.supports_io_locations() {
-                if second_blend_source {
-                    write!(self.out, "layout(location = {location}, index = 1) ")?;
+        // Vertex inputs that take several locations get one global for each
+        // column or element, at consecutive locations.
+        let split = match (self.entry_point.stage, output) {
+            (ShaderStage::Vertex, false) => self.module.types[ty]
+                .inner
+                .vertex_input_split(&self.module.types),
+            _ => None,
+        };
+        let count = split.as_ref().map_or(1, |&(count, _)| count);
+
+        for location in location..location + count {
+            // Write the I/O locations, if allowed
+            let io_location = if self.options.version.supports_explicit_locations()
+                || !emit_interpolation_and_auxiliary
+            {
+                if self.options.version.supports_io_locations() {
+                    if second_blend_source {
+                        write!(self.out, "layout(location = {location}, index = 1) ")?;
+                    } else {
+                        write!(self.out, "layout(location = {location}) ")?;
+                    }
+                    None
                 } else {
-                    write!(self.out, "layout(location = {location}) ")?;
+                    Some(VaryingLocation {
+                        location,
+                        index: second_blend_source as u32,
+                    })
                 }
-                None
             } else {
-                Some(VaryingLocation {
-                    location,
-                    index: second_blend_source as u32,
-                })
-            }
-        } else {
-            None
-        };
+                None
+            };
 
-        // Write the interpolation qualifier.
-        if let Some(interp) = interpolation {
-            if emit_interpolation_and_auxiliary {
-                write!(self.out, "{} ", glsl_interpolation(interp))?;
+            // Write the interpolation qualifier.
+            if let Some(interp) = interpolation {
+                if emit_interpolation_and_auxiliary {
+                    write!(self.out, "{} ", glsl_interpolation(interp))?;
+                }
             }
-        }
 
-        // Write the sampling auxiliary qualifier.
-        //
-        // Before GLSL 4.2, the `centroid` and `sample` qualifiers were required to appear
-        // immediately before the `in` / `out` qualifier, so we'll just follow that rule
-        // here, regardless of the version.
-        if let Some(sampling) = sampling {
-            if emit_interpolation_and_auxiliary {
-                if let Some(qualifier) = glsl_sampling(sampling) {
-                    write!(self.out, "{qualifier} ")?;
+            // Write the sampling auxiliary qualifier.
+            //
+            // Before GLSL 4.2, the `centroid` and `sample` qualifiers were required to appear
+            // immediately before the `in` / `out` qualifier, so we'll just follow that rule
+            // here, regardless of the version.
+            if let Some(sampling) = sampling {
+                if emit_interpolation_and_auxiliary {
+                    if let Some(qualifier) = glsl_sampling(sampling) {
+                        write!(self.out, "{qualifier} ")?;
+                    }
                 }
             }
-        }
 
-        // Write the input/output qualifier.
-        write!(self.out, "{} ", if output { "out" } else { "in" })?;
+            // Write the input/output qualifier.
+            write!(self.out, "{} ", if output { "out" } else { "in" })?;
 
-        // Write the type
-        // `write_type` adds no leading or trailing spaces
-        self.write_type(ty)?;
+            // Write the type
+            // `write_type` adds no leading or trailing spaces
+            match split {
+                Some((_, ref part)) => self.write_value_type(part)?,
+                None => self.write_type(ty)?,
+            }
 
-        // Finally write the global name and end the global with a `;` and a newline
-        // Leading space is important
-        let vname = VaryingName {
-            binding: &crate::Binding::Location {
-                location,
-                interpolation: None,
-                sampling: None,
-                second_blend_source,
-            },
-            stage: self.entry_point.stage,
-            options: VaryingOptions::from_writer_options(self.options, output),
-        };
-        writeln!(self.out, " {vname};")?;
+            // Finally write the global name and end the global with a `;` and a newline
+            // Leading space is important
+            let vname = VaryingName {
+                binding: &crate::Binding::Location {
+                    location,
+                    interpolation: None,
+                    sampling: None,
+                    second_blend_source,
+                },
+                stage: self.entry_point.stage,
+                options: VaryingOptions::from_writer_options(self.options, output),
+            };
+            writeln!(self.out, " {vname};")?;
 
-        if let Some(location) = io_location {
-            self.varying.insert(vname.to_string(), location);
+            if let Some(location) = io_location {
+                self.varying.insert(vname.to_string(), location);
+            }
         }
 
         Ok(())
     }
 
+    /// Write the value of an entry point input with the given `binding`, read
+    /// from the globals declared for it by [`Self::write_varying`].
+    fn write_ep_input(
+        &mut self,
+        binding: &crate::Binding,
+        ty: Handle<crate::Type>,
+        stage: ShaderStage,
+    ) -> BackendResult {
+        let options = VaryingOptions::from_writer_options(self.options, false);
+        let split = match (stage, binding) {
+            (ShaderStage::Vertex, &crate::Binding::Location { location, .. }) => self.module.types
+                [ty]
+                .inner
+                .vertex_input_split(&self.module.types)
+                .map(|(count, _)| (location, count)),
+            _ => None,
+        };
+        let Some((first, count)) = split else {
+            let varying_name = VaryingName {
+                binding,
+                stage,
+                options,
+            };
+            write!(self.out, "{varying_name}")?;
+            return Ok(());
+        };
+
+        // Put the columns or elements back together.
+        self.write_type(ty)?;
+        if let TypeInner::Array { base, size, .. } = self.module.types[ty].inner {
+            self.write_array_size(base, size)?;
+        }
+        write!(self.out, "(")?;
+        for location in first..first + count {
+            if location != first {
+                write!(self.out, ", ")?;
+            }
+            let varying_name = VaryingName {
+                binding: &crate::Binding::Location {
+                    location,
+                    interpolation: None,
+                    sampling: None,
+                    second_blend_source: false,
+                },
+                stage,
+                options,
+            };
+            write!(self.out, "{varying_name}")?;
+        }
+        write!(self.out, ")")?;
+        Ok(())
+    }
+
     /// Helper method used to write functions (both entry points and regular functions)
     ///
     /// # Notes
@@ -1670,6 +1990,15 @@ impl<'a, W: Write> Writer<'a, W> {
             self.write_workgroup_variables_initialization(&ctx)?;
         }
 
+        // GLSL leaves the depth undefined on any path that doesn't write
+        // `gl_FragDepth`, so give such paths the depth the fragment would
+        // have had without the write.
+        if let back::FunctionType::EntryPoint(_) = ctx.ty {
+            if self.writes_frag_depth() && !returns_on_all_paths(&func.body) {
+                writeln!(self.out, "{}gl_FragDepth = gl_FragCoord.z;", back::INDENT)?;
+            }
+        }
+
         // Compose the function arguments from globals, in case of an entry point.
         if let back::FunctionType::EntryPoint(ep_index) = ctx.ty {
             let stage = self.module.entry_points[ep_index as usize].stage;
@@ -1678,31 +2007,29 @@ impl<'a, W: Write> Writer<'a, W> {
                 self.write_type(arg.ty)?;
                 let name = &self.names[&NameKey::EntryPointArgument(ep_index, index as u32)];
                 write!(self.out, " {name}")?;
+                if let TypeInner::Array { base, size, .. } = self.module.types[arg.ty].inner {
+                    self.write_array_size(base, size)?;
+                }
                 write!(self.out, " = ")?;
                 match self.module.types[arg.ty].inner {
                     crate::TypeInner::Struct { ref members, .. } => {
                         self.write_type(arg.ty)?;
                         write!(self.out, "(")?;
                         for (index, member) in members.iter().enumerate() {
-                            let varying_name = VaryingName {
-                                binding: member.binding.as_ref().unwrap(),
-                                stage,
-                                options: VaryingOptions::from_writer_options(self.options, false),
-                            };
                             if index != 0 {
                                 write!(self.out, ", ")?;
                             }
-                            write!(self.out, "{varying_name}")?;
+                            self.write_ep_input(
+                                member.binding.as_ref().unwrap(),
+                                member.ty,
+                                stage,
+                            )?;
                         }
                         writeln!(self.out, ");")?;
                     }
                     _ => {
-                        let varying_name = VaryingName {
-                            binding: arg.binding.as_ref().unwrap(),
-                            stage,
-                            options: VaryingOptions::from_writer_options(self.options, false),
-                        };
-                        writeln!(self.out, "{varying_name};")?;
+                        self.write_ep_input(arg.binding.as_ref().unwrap(), arg.ty, stage)?;
+                        writeln!(self.out, ";")?;
                     }
                 }
             }
@@ -1867,6 +2194,53 @@ impl<'a, W: Write> Writer<'a, W> {
         Ok(())
     }
 
+    /// Write `dot4I8Packed` or `dot4U8Packed` as a sum of the products of
+    /// the operands' bytes.
+    ///
+    /// In the signed case, each byte is shifted to the top of an `int` and
+    /// back down, which sign-extends it.
+    fn write_dot4_packed(
+        &mut self,
+        arg: Handle<crate::Expression>,
+        arg1: Handle<crate::Expression>,
+        signed: bool,
+        ctx: &back::FunctionCtx,
+    ) -> BackendResult {
+        write!(self.out, "(")?;
+        for byte in 0..4 {
+            // The operands are baked, so they can be written more than once.
+            if byte != 0 {
+                write!(self.out, " + ")?;
+            }
+            for (index, operand) in [arg, arg1].into_iter().enumerate() {
+                if index != 0 {
+                    write!(self.out, " * ")?;
+                }
+                if signed {
+                    write!(self.out, "(int(")?;
+                    self.write_expr(operand, ctx)?;
+                    write!(self.out, ")")?;
+                    if byte != 3 {
+                        write!(self.out, " << {}", 24 - byte * 8)?;
+                    }
+                    write!(self.out, " >> 24)")?;
+                } else {
+                    write!(self.out, "(")?;
+                    self.write_expr(operand, ctx)?;
+                    if byte != 0 {
+                        write!(self.out, " >> {}u", byte * 8)?;
+                    }
+                    if byte != 3 {
+                        write!(self.out, " & 0xFFu")?;
+                    }
+                    write!(self.out, ")")?;
+                }
+            }
+        }
+        write!(self.out, ")")?;
+        Ok(())
+    }
+
     /// Helper method used to write structs
     ///
     /// # Notes
@@ -1927,6 +2301,189 @@ impl<'a, W: Write> Writer<'a, W> {
         Ok(())
     }
 
+    /// Helper method used to write the opening line of a loop
+    ///
+    /// # Notes
+    /// If `bounded` is set, the loop gets an induction counter capped at
+    /// [`Options::max_loop_iterations`], otherwise it's a plain `while(true)`.
+    fn write_loop_header(&mut self, bounded: bool, level: back::Level) -> BackendResult {
+        if bounded {
+            let counter = self.namer.call("naga_i");
+            let max = self.options.max_loop_iterations;
+            writeln!(
+                self.out,
+                "{level}for (uint {counter} = 0u; {counter} < {max}u; ++{counter}) {{"
+            )?;
+        } else {
+            writeln!(self.out, "{level}while(true) {{")?;
+        }
+        Ok(())
+    }
+
+    /// Write a loop as `while(true)`, running `continuing` and checking
+    /// `break_if` at the top of every iteration but the first.
+    fn write_generic_loop(
+        &mut self,
+        body: &[crate::Statement],
+        continuing: &[crate::Statement],
+        break_if: Option<Handle<crate::Expression>>,
+        bounded: bool,
+        ctx: &back::FunctionCtx,
+        level: back::Level,
+    ) -> BackendResult {
+        if !continuing.is_empty() || break_if.is_some() {
+            let gate_name = self.namer.call("loop_init");
+            writeln!(self.out, "{level}bool {gate_name} = true;")?;
+            self.write_loop_header(bounded, level)?;
+            let l2 = level.next();
+            let l3 = l2.next();
+            writeln!(self.out, "{l2}if (!{gate_name}) {{")?;
+            for sta in continuing {
+                self.write_stmt(sta, ctx, l3)?;
+            }
+            if let Some(condition) = break_if {
+                write!(self.out, "{l3}if (")?;
+                self.write_expr(condition, ctx)?;
+                writeln!(self.out, ") {{")?;
+                writeln!(self.out, "{}break;", l3.next())?;
+                writeln!(self.out, "{l3}}}")?;
+            }
+            writeln!(self.out, "{l2}}}")?;
+            writeln!(self.out, "{}{} = false;", level.next(), gate_name)?;
+        } else {
+            self.write_loop_header(bounded, level)?;
+        }
+        for sta in body {
+            self.write_stmt(sta, ctx, level.next())?;
+        }
+        writeln!(self.out, "{level}}}")?;
+        Ok(())
+    }
+
+    /// Recognize loops that GLSL can write as `while(cond)` or `do { } while(cond)`.
+    ///
+    /// The condition is written inline at the point the loop tests it, so
+    /// the statements computing it must be nothing but [`Emit`]s whose
+    /// expressions feed only the condition. That way nothing is evaluated
+    /// twice, or at a different point than the IR says.
+    ///
+    /// [`Emit`]: crate::Statement::Emit
+    fn loop_shape<'b>(
+        &self,
+        body: &'b [crate::Statement],
+        continuing: &[crate::Statement],
+        break_if: Option<Handle<crate::Expression>>,
+        ctx: &back::FunctionCtx,
+    ) -> Option<LoopShape<'b>> {
+        use crate::Statement;
+
+        if let Some(condition) = break_if {
+            return self
+                .inline_condition(continuing, condition, ctx)
+                .then_some(LoopShape::DoWhile { condition });
+        }
+        if !continuing.is_empty() {
+            return None;
+        }
+
+        let split = body
+            .iter()
+            .position(|sta| !matches!(*sta, Statement::Emit(_)))?;
+        let Statement::If {
+            condition,
+            ref accept,
+            ref reject,
+            ..
+        } = body[split]
+        else {
+            return None;
+        };
+        let negate = match (&accept[..], &reject[..]) {
+            (&[], &[Statement::Break]) => false,
+            (&[Statement::Break], &[]) => true,
+            _ => return None,
+        };
+        self.inline_condition(&body[..split], condition, ctx)
+            .then_some(LoopShape::While {
+                condition,
+                negate,
+                body: &body[split + 1..],
+            })
+    }
+
+    /// Check that `condition` can be written in place of `statements`.
+    ///
+    /// Every expression `statements` emits must be used exactly once, by
+    /// `condition` or its operands, and everything else the condition refers
+    /// to must already be in scope ahead of the loop.
+    fn inline_condition(
+        &self,
+        statements: &[crate::Statement],
+        condition: Handle<crate::Expression>,
+        ctx: &back::FunctionCtx,
+    ) -> bool {
+        let mut emitted = Vec::new();
+        for sta in statements {
+            let crate::Statement::Emit(ref range) = *sta else {
+                return false;
+            };
+            for handle in range.clone() {
+                // The clamped lod of an `ImageLoad` has to be written as a
+                // statement of its own.
+                if ctx.info[handle].ref_count != 1
+                    || matches!(ctx.expressions[handle], crate::Expression::ImageLoad { .. })
+                {
+                    return false;
+                }
+                emitted.push(handle);
+            }
+        }
+
+        let mut reached = 0;
+        let mut stack = vec![condition];
+        while let Some(handle) = stack.pop() {
+            if self.named_expressions.contains_key(&handle) {
+                continue;
+            }
+            if emitted.contains(&handle) {
+                reached += 1;
+                proc::for_each_operand(&ctx.expressions[handle], |operand| stack.push(operand));
+            } else if !ctx.expressions[handle].needs_pre_emit() {
+                // Emitted in the body, or left unnamed before the loop.
+                return false;
+            }
+        }
+        reached == emitted.len()
+    }
+
+    /// Write a loop condition, which is `condition` itself, or its negation
+    /// if `negate` is set.
+    fn write_loop_condition(
+        &mut self,
+        condition: Handle<crate::Expression>,
+        negate: bool,
+        ctx: &back::FunctionCtx,
+    ) -> BackendResult {
+        if negate {
+            // Don't write `!(!(x))` for loops that break unless `x` holds.
+            if let crate::Expression::Unary {
+                op: crate::UnaryOperator::LogicalNot,
+                expr,
+            } = ctx.expressions[condition]
+            {
+                if !self.named_expressions.contains_key(&condition) {
+                    return self.write_expr(expr, ctx);
+                }
+            }
+            write!(self.out, "!(")?;
+            self.write_expr(condition, ctx)?;
+            write!(self.out, ")")?;
+        } else {
+            self.write_expr(condition, ctx)?;
+        }
+        Ok(())
+    }
+
     /// Helper method used to write statements
     ///
     /// # Notes
@@ -2012,6 +2569,7 @@ impl<'a, W: Write> Writer<'a, W> {
                 condition,
                 ref accept,
                 ref reject,
+                hint: _,
             } => {
                 write!(self.out, "{level}")?;
                 write!(self.out, "if (")?;
@@ -2107,33 +2665,44 @@ impl<'a, W: Write> Writer<'a, W> {
                 ref body,
                 ref continuing,
                 break_if,
+                hint: _,
             } => {
-                if !continuing.is_empty() || break_if.is_some() {
-                    let gate_name = self.namer.call("loop_init");
-                    writeln!(self.out, "{level}bool {gate_name} = true;")?;
-                    writeln!(self.out, "{level}while(true) {{")?;
-                    let l2 = level.next();
-                    let l3 = l2.next();
-                    writeln!(self.out, "{l2}if (!{gate_name}) {{")?;
-                    for sta in continuing {
-                        self.write_stmt(sta, ctx, l3)?;
-                    }
-                    if let Some(condition) = break_if {
-                        write!(self.out, "{l3}if (")?;
-                        self.write_expr(condition, ctx)?;
+                let bounded = self
+                    .options
+                    .writer_flags
+                    .contains(WriterFlags::FORCE_BOUNDED_LOOPS);
+                let shape = if bounded {
+                    None
+                } else {
+                    self.loop_shape(body, continuing, break_if, ctx)
+                };
+                match shape {
+                    Some(LoopShape::While {
+                        condition,
+                        negate,
+                        body,
+                    }) => {
+                        write!(self.out, "{level}while(")?;
+                        self.write_loop_condition(condition, negate, ctx)?;
                         writeln!(self.out, ") {{")?;
-                        writeln!(self.out, "{}break;", l3.next())?;
-                        writeln!(self.out, "{l3}}}")?;
+                        for sta in body {
+                            self.write_stmt(sta, ctx, level.next())?;
+                        }
+                        writeln!(self.out, "{level}}}")?
+                    }
+                    Some(LoopShape::DoWhile { condition }) => {
+                        writeln!(self.out, "{level}do {{")?;
+                        for sta in body {
+                            self.write_stmt(sta, ctx, level.next())?;
+                        }
+                        write!(self.out, "{level}}} while(")?;
+                        self.write_loop_condition(condition, true, ctx)?;
+                        writeln!(self.out, ");")?
+                    }
+                    None => {
+                        self.write_generic_loop(body, continuing, break_if, bounded, ctx, level)?
                     }
-                    writeln!(self.out, "{l2}}}")?;
-                    writeln!(self.out, "{}{} = false;", level.next(), gate_name)?;
-                } else {
-                    writeln!(self.out, "{level}while(true) {{")?;
-                }
-                for sta in body {
-                    self.write_stmt(sta, ctx, level.next())?;
                 }
-                writeln!(self.out, "{level}}}")?
             }
             // Break, continue and return as written as in C
             // `break;`
@@ -2200,6 +2769,12 @@ impl<'a, W: Write> Writer<'a, W> {
                                             ),
                                         };
                                         write!(self.out, "{varying_name} = ")?;
+                                        let conversion = glsl_built_in_output_conversion(
+                                            member.binding.as_ref().unwrap(),
+                                        );
+                                        if let Some(conversion) = conversion {
+                                            write!(self.out, "{conversion}(")?;
+                                        }
 
                                         if let Some(struct_name) = temp_struct_name {
                                             write!(self.out, "{struct_name}")?;
@@ -2208,18 +2783,23 @@ impl<'a, W: Write> Writer<'a, W> {
                                         }
 
                                         // Write field name
-                                        writeln!(
+                                        write!(
                                             self.out,
-                                            ".{};",
+                                            ".{}",
                                             &self.names
                                                 [&NameKey::StructMember(result.ty, index as u32)]
                                         )?;
+                                        if conversion.is_some() {
+                                            write!(self.out, ")")?;
+                                        }
+                                        writeln!(self.out, ";")?;
                                         write!(self.out, "{level}")?;
                                     }
                                 }
                                 _ => {
+                                    let binding = result.binding.as_ref().unwrap();
                                     let name = VaryingName {
-                                        binding: result.binding.as_ref().unwrap(),
+                                        binding,
                                         stage: ep.stage,
                                         options: VaryingOptions::from_writer_options(
                                             self.options,
@@ -2227,7 +2807,15 @@ impl<'a, W: Write> Writer<'a, W> {
                                         ),
                                     };
                                     write!(self.out, "{name} = ")?;
-                                    self.write_expr(value, ctx)?;
+                                    if let Some(conversion) =
+                                        glsl_built_in_output_conversion(binding)
+                                    {
+                                        write!(self.out, "{conversion}(")?;
+                                        self.write_expr(value, ctx)?;
+                                        write!(self.out, ")")?;
+                                    } else {
+                                        self.write_expr(value, ctx)?;
+                                    }
                                     writeln!(self.out, ";")?;
                                     write!(self.out, "{level}")?;
                                 }
@@ -2267,16 +2855,38 @@ impl<'a, W: Write> Writer<'a, W> {
             // keyword which ceases all further processing in a fragment shader, it's called OpKill
             // in spir-v that's why it's called `Statement::Kill`
             Statement::Kill => writeln!(self.out, "{level}discard;")?,
+            Statement::DebugMarker(ref text) => {
+                for line in back::c_comment_lines(text) {
+                    let space = if line.is_empty() { "" } else { " " };
+                    writeln!(self.out, "{level}//{space}{line}")?;
+                }
+            }
             Statement::Barrier(flags) => {
                 self.write_barrier(flags, level)?;
             }
+            Statement::MeshFunction(_) => {
+                return Err(Error::UnsupportedStage(ShaderStage::Mesh));
+            }
             // Stores in glsl are just variable assignments written as `pointer = value;`
             Statement::Store { pointer, value } => {
                 write!(self.out, "{level}")?;
-                self.write_expr(pointer, ctx)?;
-                write!(self.out, " = ")?;
-                self.write_expr(value, ctx)?;
-                writeln!(self.out, ";")?
+                if ctx
+                    .resolve_atomic_pointer(pointer, &self.module.types)
+                    .is_some()
+                {
+                    // GLSL has no atomic store for buffer and shared
+                    // variables, so exchange and discard the original value.
+                    write!(self.out, "atomicExchange(")?;
+                    self.write_expr(pointer, ctx)?;
+                    write!(self.out, ", ")?;
+                    self.write_expr(value, ctx)?;
+                    writeln!(self.out, ");")?
+                } else {
+                    self.write_expr(pointer, ctx)?;
+                    write!(self.out, " = ")?;
+                    self.write_expr(value, ctx)?;
+                    writeln!(self.out, ";")?
+                }
             }
             Statement::WorkGroupUniformLoad { pointer, result } => {
                 // GLSL doesn't have pointers, which means that this backend needs to ensure that
@@ -2590,7 +3200,21 @@ impl<'a, W: Write> Writer<'a, W> {
                 write!(self.out, "{}", self.names[&ctx.name_key(handle)])?
             }
             // glsl has no pointers so there's no load operation, just write the pointer expression
-            Expression::Load { pointer } => self.write_expr(pointer, ctx)?,
+            Expression::Load { pointer } => {
+                match ctx.resolve_atomic_pointer(pointer, &self.module.types) {
+                    // GLSL has no atomic load for buffer and shared
+                    // variables, so or in zero instead.
+                    Some(scalar) => {
+                        write!(self.out, "atomicOr(")?;
+                        self.write_expr(pointer, ctx)?;
+                        match scalar.kind {
+                            crate::ScalarKind::Uint => write!(self.out, ", 0u)")?,
+                            _ => write!(self.out, ", 0)")?,
+                        }
+                    }
+                    None => self.write_expr(pointer, ctx)?,
+                }
+            }
             // `ImageSample` is a bit complicated compared to the rest of the IR.
             //
             // First there are three variations depending whether the sample level is explicitly set,
@@ -2791,14 +3415,17 @@ impl<'a, W: Write> Writer<'a, W> {
                 use crate::ImageClass;
 
                 // This will only panic if the module is invalid
-                let (dim, class) = match *ctx.resolve_type(image, &self.module.types) {
+                let (dim, arrayed, class) = match *ctx.resolve_type(image, &self.module.types) {
                     TypeInner::Image {
                         dim,
-                        arrayed: _,
+                        arrayed,
                         class,
-                    } => (dim, class),
+                    } => (dim, arrayed, class),
                     _ => unreachable!(),
                 };
+                // ES has no 1D textures, so we declare them as 2D textures,
+                // whose size has an extra component.
+                let tex_1d_hack = dim == crate::ImageDimension::D1 && self.options.version.is_es();
                 let components = match dim {
                     crate::ImageDimension::D1 => 1,
                     crate::ImageDimension::D2 => 2,
@@ -2851,9 +3478,12 @@ impl<'a, W: Write> Writer<'a, W> {
                                 write!(self.out, "imageSize(")?;
                                 self.write_expr(image, ctx)?;
                             }
+                            ImageClass::External => unreachable!(),
                         }
                         write!(self.out, ")")?;
-                        if components != 1 || self.options.version.is_es() {
+                        // The layer count follows the dimensions, and WGSL
+                        // doesn't include it.
+                        if components != 1 || arrayed || tex_1d_hack {
                             write!(self.out, ".{}", &"xyz"[..components])?;
                         }
                     }
@@ -2866,18 +3496,18 @@ impl<'a, W: Write> Writer<'a, W> {
                         let fun_name = match class {
                             ImageClass::Sampled { .. } | ImageClass::Depth { .. } => "textureSize",
                             ImageClass::Storage { .. } => "imageSize",
+                            ImageClass::External => unreachable!(),
                         };
                         write!(self.out, "{fun_name}(")?;
                         self.write_expr(image, ctx)?;
-                        // All textureSize calls requires an lod argument
-                        // except for multisampled samplers
-                        if class.is_multisampled() {
+                        // Only `textureSize` on mipmapped textures takes an
+                        // lod argument.
+                        if class.is_mipmapped() {
                             write!(self.out, ", 0")?;
                         }
                         write!(self.out, ")")?;
-                        if components != 1 || self.options.version.is_es() {
-                            write!(self.out, ".{}", back::COMPONENTS[components])?;
-                        }
+                        let layer_component = components + usize::from(tex_1d_hack);
+                        write!(self.out, ".{}", back::COMPONENTS[layer_component])?;
                     }
                     crate::ImageQuery::NumSamples => {
                         let fun_name = match class {
@@ -2885,6 +3515,7 @@ impl<'a, W: Write> Writer<'a, W> {
                                 "textureSamples"
                             }
                             ImageClass::Storage { .. } => "imageSamples",
+                            ImageClass::External => unreachable!(),
                         };
                         write!(self.out, "{fun_name}(")?;
                         self.write_expr(image, ctx)?;
@@ -3060,9 +3691,29 @@ impl<'a, W: Write> Writer<'a, W> {
                 } else {
                     false
                 };
+                let object_ty = ctx.resolve_type(accept, &self.module.types);
 
-                // TODO: Boolean mix on desktop required GL_EXT_shader_integer_mix
-                if vec_select {
+                if vec_select && self.select_by_component(object_ty) {
+                    // The operands were baked by `update_expressions_to_bake`,
+                    // so refer to them once for each component.
+                    self.write_value_type(object_ty)?;
+                    write!(self.out, "(")?;
+                    let size = match *object_ty {
+                        TypeInner::Vector { size, .. } => size as usize,
+                        _ => unreachable!(),
+                    };
+                    for (index, component) in back::COMPONENTS[..size].iter().enumerate() {
+                        if index != 0 {
+                            write!(self.out, ", ")?;
+                        }
+                        self.write_expr(condition, ctx)?;
+                        write!(self.out, ".{component} ? ")?;
+                        self.write_expr(accept, ctx)?;
+                        write!(self.out, ".{component} : ")?;
+                        self.write_expr(reject, ctx)?;
+                        write!(self.out, ".{component}")?;
+                    }
+                } else if vec_select {
                     // Glsl defines that for mix when the condition is a boolean the first element
                     // is picked if condition is false and the second if condition is true
                     write!(self.out, "mix(")?;
@@ -3112,6 +3763,14 @@ impl<'a, W: Write> Writer<'a, W> {
             Expression::Relational { fun, argument } => {
                 use crate::RelationalFunction as Rf;
 
+                // GLSL's `all` and `any` only take vectors, and of a single
+                // boolean they're the identity.
+                if let (Rf::All | Rf::Any, &TypeInner::Scalar(_)) =
+                    (fun, ctx.resolve_type(argument, &self.module.types))
+                {
+                    return self.write_expr(argument, ctx);
+                }
+
                 let fun_name = match fun {
                     Rf::IsInf => "isinf",
                     Rf::IsNan => "isnan",
@@ -3207,6 +3866,10 @@ impl<'a, W: Write> Writer<'a, W> {
                             "Correct TypeInner for dot product should be already validated"
                         ),
                     },
+                    Mf::Dot4I8Packed | Mf::Dot4U8Packed => {
+                        let signed = fun == Mf::Dot4I8Packed;
+                        return self.write_dot4_packed(arg, arg1.unwrap(), signed, ctx);
+                    }
                     Mf::Outer => "outerProduct",
                     Mf::Cross => "cross",
                     Mf::Distance => "distance",
@@ -3249,6 +3912,66 @@ impl<'a, W: Write> Writer<'a, W> {
                     Mf::Inverse => "inverse",
                     Mf::Transpose => "transpose",
                     Mf::Determinant => "determinant",
+                    Mf::QuantizeToF16 => {
+                        if self.features.contains(Features::HALF_FLOAT) {
+                            // Convert to `float16_t` and back. As with
+                            // `packHalf2x16`, whether `f16` denormals are
+                            // flushed to zero is up to the driver.
+                            match *ctx.resolve_type(arg, &self.module.types) {
+                                crate::TypeInner::Vector { size, .. } => {
+                                    let s = back::vector_size_str(size);
+                                    write!(self.out, "vec{s}(f16vec{s}(")?;
+                                }
+                                _ => write!(self.out, "float(float16_t(")?,
+                            }
+                            self.write_expr(arg, ctx)?;
+                            write!(self.out, "))")?;
+
+                            return Ok(());
+                        }
+
+                        // Round trip through `packHalf2x16`, two components
+                        // at a time. Out-of-range values become infinities,
+                        // but unlike WGSL, whether `f16` denormals are
+                        // flushed to zero is up to the driver.
+                        match *ctx.resolve_type(arg, &self.module.types) {
+                            crate::TypeInner::Vector {
+                                size: crate::VectorSize::Bi,
+                                ..
+                            } => {
+                                write!(self.out, "unpackHalf2x16(packHalf2x16(")?;
+                                self.write_expr(arg, ctx)?;
+                                write!(self.out, "))")?;
+                            }
+                            crate::TypeInner::Vector {
+                                size: crate::VectorSize::Tri,
+                                ..
+                            } => {
+                                write!(self.out, "vec3(unpackHalf2x16(packHalf2x16(")?;
+                                self.write_expr(arg, ctx)?;
+                                write!(self.out, ".xy)), unpackHalf2x16(packHalf2x16(")?;
+                                self.write_expr(arg, ctx)?;
+                                write!(self.out, ".zz)).x)")?;
+                            }
+                            crate::TypeInner::Vector {
+                                size: crate::VectorSize::Quad,
+                                ..
+                            } => {
+                                write!(self.out, "vec4(unpackHalf2x16(packHalf2x16(")?;
+                                self.write_expr(arg, ctx)?;
+                                write!(self.out, ".xy)), unpackHalf2x16(packHalf2x16(")?;
+                                self.write_expr(arg, ctx)?;
+                                write!(self.out, ".zw)))")?;
+                            }
+                            _ => {
+                                write!(self.out, "unpackHalf2x16(packHalf2x16(vec2(")?;
+                                self.write_expr(arg, ctx)?;
+                                write!(self.out, ", 0.0))).x")?;
+                            }
+                        }
+
+                        return Ok(());
+                    }
                     // bits
                     Mf::CountTrailingZeros => {
                         match *ctx.resolve_type(arg, &self.module.types) {
@@ -3620,6 +4343,32 @@ impl<'a, W: Write> Writer<'a, W> {
         tex_coord_size + tex_1d_hack as u8 + arrayed as u8
     }
 
+    /// Helper method to write the sample index of a multisampled image load
+    ///
+    /// `texelFetch` and `textureSamples` only work with `int`s, so unsigned
+    /// sample indices are converted.
+    fn write_image_sample_index(
+        &mut self,
+        sample: Handle<crate::Expression>,
+        ctx: &back::FunctionCtx,
+    ) -> BackendResult {
+        let is_unsigned = matches!(
+            *ctx.resolve_type(sample, &self.module.types),
+            TypeInner::Scalar(crate::Scalar {
+                kind: crate::ScalarKind::Uint,
+                ..
+            })
+        );
+        if is_unsigned {
+            write!(self.out, "int(")?;
+        }
+        self.write_expr(sample, ctx)?;
+        if is_unsigned {
+            write!(self.out, ")")?;
+        }
+        Ok(())
+    }
+
     /// Helper method to write the coordinate vector for image operations
     fn write_texture_coord(
         &mut self,
@@ -3793,12 +4542,24 @@ impl<'a, W: Write> Writer<'a, W> {
                 };
                 ("imageLoad", policy)
             }
-            // TODO: Is there even a function for this?
-            crate::ImageClass::Depth { multi: _ } => {
-                return Err(Error::Custom(
-                    "WGSL `textureLoad` from depth textures is not supported in GLSL".to_string(),
-                ))
-            }
+            // Multisampled depth textures are plain `sampler2DMS`s
+            crate::ImageClass::Depth { multi: true } => ("texelFetch", self.policies.image_load),
+            // Other depth textures are declared as plain samplers if we can
+            crate::ImageClass::Depth { multi: false } => match ctx.expressions[image] {
+                crate::Expression::GlobalVariable(var)
+                    if self.loaded_depth_images.contains(&var) =>
+                {
+                    ("texelFetch", self.policies.image_load)
+                }
+                _ => {
+                    return Err(Error::Custom(
+                        "WGSL `textureLoad` from depth textures that are also sampled \
+                         with comparisons, or passed as arguments, is not supported in GLSL"
+                            .to_string(),
+                    ))
+                }
+            },
+            crate::ImageClass::External => unreachable!(),
         };
 
         // openGL es doesn't have 1D images so we need workaround it
@@ -3829,13 +4590,15 @@ impl<'a, W: Write> Writer<'a, W> {
                 write!(self.out, ") && ")?;
             }
 
-            // Check that the sample arguments doesn't exceed the number of samples
+            // Check that the sample arguments doesn't exceed the number of samples,
+            // comparing as unsigned so that negative indices are rejected too
             if let Some(sample_expr) = sample {
+                write!(self.out, "uint(")?;
                 self.write_expr(sample_expr, ctx)?;
-                write!(self.out, " < textureSamples(",)?;
+                write!(self.out, ") < uint(textureSamples(",)?;
                 self.write_expr(image, ctx)?;
                 // Chain the next check
-                write!(self.out, ") && ")?;
+                write!(self.out, ")) && ")?;
             }
 
             // We now need to write the size checks for the coordinates and array index
@@ -3960,47 +4723,58 @@ impl<'a, W: Write> Writer<'a, W> {
             // the number of samples the image has.
             if let Some(sample_expr) = sample {
                 write!(self.out, ", clamp(")?;
-                self.write_expr(sample_expr, ctx)?;
+                self.write_image_sample_index(sample_expr, ctx)?;
                 // Set the min value to 0 and start the call to `textureSamples`
                 write!(self.out, ", 0, textureSamples(")?;
                 self.write_expr(image, ctx)?;
                 // Close the `textureSamples` call, subtract 1 from it since the sample
                 // argument is zero based, and close the `clamp` call
-                writeln!(self.out, ") - 1)")?;
+                write!(self.out, ") - 1)")?;
             }
-        } else if let Some(sample_or_level) = sample.or(level) {
-            // If no bounds checking is need just add the sample or level argument
+        } else if let Some(sample_expr) = sample {
+            // If no bounds checking is need just add the sample argument
             // after the coordinates
             write!(self.out, ", ")?;
-            self.write_expr(sample_or_level, ctx)?;
+            self.write_image_sample_index(sample_expr, ctx)?;
+        } else if let Some(level_expr) = level {
+            // Or the level argument
+            write!(self.out, ", ")?;
+            self.write_expr(level_expr, ctx)?;
         }
 
         // Close the image load function.
         write!(self.out, ")")?;
 
+        // `texelFetch` always returns a vector, but depth loads produce a scalar.
+        if let crate::ImageClass::Depth { .. } = class {
+            write!(self.out, ".x")?;
+        }
+
         // If we were using the `ReadZeroSkipWrite` policy we need to end the first branch
         // (which is taken if the condition is `true`) with a colon (`:`) and write the
         // second branch which is just a 0 value.
         if let proc::BoundsCheckPolicy::ReadZeroSkipWrite = policy {
-            // Get the kind of the output value.
-            let kind = match class {
-                // Only sampled images can reach here since storage images
-                // don't need bounds checks and depth images aren't implemented
-                crate::ImageClass::Sampled { kind, .. } => kind,
-                _ => unreachable!(),
-            };
-
             // End the first branch
             write!(self.out, " : ")?;
             // Write the 0 value
-            write!(
-                self.out,
-                "{}vec4(",
-                glsl_scalar(crate::Scalar { kind, width: 4 })?.prefix,
-            )?;
-            self.write_zero_init_scalar(kind)?;
-            // Close the zero value constructor
-            write!(self.out, ")")?;
+            match class {
+                crate::ImageClass::Sampled { kind, .. } => {
+                    write!(
+                        self.out,
+                        "{}vec4(",
+                        glsl_scalar(crate::Scalar { kind, width: 4 })?.prefix,
+                    )?;
+                    self.write_zero_init_scalar(kind)?;
+                    // Close the zero value constructor
+                    write!(self.out, ")")?;
+                }
+                // Depth loads produce a single float
+                crate::ImageClass::Depth { .. } => {
+                    self.write_zero_init_scalar(crate::ScalarKind::Float)?
+                }
+                // Storage images don't need bounds checks
+                crate::ImageClass::Storage { .. } | crate::ImageClass::External => unreachable!(),
+            }
             // Close the parentheses surrounding our ternary
             write!(self.out, ")")?;
         }
@@ -4071,6 +4845,8 @@ impl<'a, W: Write> Writer<'a, W> {
                     .expect("Bad array size")
                 {
                     proc::IndexableLength::Known(count) => count,
+                    // Rejected by `write_array_size`.
+                    proc::IndexableLength::Pending(_) => unreachable!(),
                     proc::IndexableLength::Dynamic => return Ok(()),
                 };
                 self.write_type(base)?;
@@ -4230,11 +5006,16 @@ impl<'a, W: Write> Writer<'a, W> {
             );
         }
 
+        let instance_index = (self.entry_point.stage == ShaderStage::Vertex
+            && self.features.contains(Features::INSTANCE_INDEX))
+        .then(|| self.options.instance_index_source());
+
         Ok(ReflectionInfo {
             texture_mapping,
             uniforms,
             varying: mem::take(&mut self.varying),
             push_constant_items,
+            instance_index,
         })
     }
 
@@ -4347,6 +5128,15 @@ const fn glsl_scalar(scalar: crate::Scalar) -> Result<ScalarString<'static>, Err
     })
 }
 
+/// Helper function that returns the conversion needed to store a value into
+/// an output builtin whose GLSL type differs from the IR's
+const fn glsl_built_in_output_conversion(binding: &crate::Binding) -> Option<&'static str> {
+    match *binding {
+        crate::Binding::BuiltIn(crate::BuiltIn::SampleMask) => Some("int"),
+        _ => None,
+    }
+}
+
 /// Helper function that returns the glsl variable name for a builtin
 const fn glsl_built_in(built_in: crate::BuiltIn, options: VaryingOptions) -> &'static str {
     use crate::BuiltIn as Bi;
@@ -4366,14 +5156,14 @@ const fn glsl_built_in(built_in: crate::BuiltIn, options: VaryingOptions) -> &'s
         Bi::BaseVertex => "uint(gl_BaseVertex)",
         Bi::ClipDistance => "gl_ClipDistance",
         Bi::CullDistance => "gl_CullDistance",
-        Bi::InstanceIndex => {
-            if options.draw_parameters {
+        Bi::InstanceIndex => match options.instance_index {
+            InstanceIndexSource::BaseInstance => "(uint(gl_InstanceID) + uint(gl_BaseInstance))",
+            InstanceIndexSource::DrawParameters => {
                 "(uint(gl_InstanceID) + uint(gl_BaseInstanceARB))"
-            } else {
-                // Must match FIRST_INSTANCE_BINDING
-                "(uint(gl_InstanceID) + naga_vs_first_instance)"
             }
-        }
+            // Must match FIRST_INSTANCE_BINDING
+            InstanceIndexSource::Uniform => "(uint(gl_InstanceID) + naga_vs_first_instance)",
+        },
         Bi::PointSize => "gl_PointSize",
         Bi::VertexIndex => "uint(gl_VertexID)",
         // fragment
@@ -4381,12 +5171,13 @@ const fn glsl_built_in(built_in: crate::BuiltIn, options: VaryingOptions) -> &'s
         Bi::PointCoord => "gl_PointCoord",
         Bi::FrontFacing => "gl_FrontFacing",
         Bi::PrimitiveIndex => "uint(gl_PrimitiveID)",
-        Bi::SampleIndex => "gl_SampleID",
+        Bi::SampleIndex => "uint(gl_SampleID)",
+        // The sample masks are `int` arrays, with one element per 32 samples.
         Bi::SampleMask => {
             if options.output {
-                "gl_SampleMask"
+                "gl_SampleMask[0]"
             } else {
-                "gl_SampleMaskIn"
+                "uint(gl_SampleMaskIn[0])"
             }
         }
         // compute
@@ -4396,6 +5187,12 @@ const fn glsl_built_in(built_in: crate::BuiltIn, options: VaryingOptions) -> &'s
         Bi::WorkGroupId => "gl_WorkGroupID",
         Bi::WorkGroupSize => "gl_WorkGroupSize",
         Bi::NumWorkGroups => "gl_NumWorkGroups",
+        // mesh shading, rejected by `Writer::new`
+        Bi::MeshTaskSize
+        | Bi::TriangleIndices
+        | Bi::LineIndices
+        | Bi::PointIndex
+        | Bi::CullPrimitive => unreachable!(),
     }
 }
 
@@ -4513,3 +5310,65 @@ fn is_value_init_supported(module: &crate::Module, ty: Handle<crate::Type>) -> b
         _ => false,
     }
 }
+
+/// Return true if every path through `block` ends in a `return` or `discard`.
+///
+/// This is only meant for the top level of an entry point's body, so it doesn't
+/// look into loops and switches, where a `break` could skip a later `return`.
+/// It may return false for blocks that do always return.
+fn returns_on_all_paths(block: &crate::Block) -> bool {
+    block.iter().any(|statement| match *statement {
+        crate::Statement::Return { .. } | crate::Statement::Kill => true,
+        crate::Statement::Block(ref block) => returns_on_all_paths(block),
+        crate::Statement::If {
+            ref accept,
+            ref reject,
+            ..
+        } => returns_on_all_paths(accept) && returns_on_all_paths(reject),
+        _ => false,
+    })
+}
+
+/// Find the non-multisampled depth images in `module` that are loaded from,
+/// and never sampled with a depth comparison.
+///
+/// See [`Writer::loaded_depth_images`].
+fn collect_loaded_depth_images(
+    module: &crate::Module,
+) -> crate::FastHashSet<Handle<crate::GlobalVariable>> {
+    let mut loaded = crate::FastHashSet::default();
+    let mut compared = crate::FastHashSet::default();
+    let functions = module
+        .functions
+        .iter()
+        .map(|(_, function)| function)
+        .chain(module.entry_points.iter().map(|ep| &ep.function));
+    for function in functions {
+        for (_, expression) in function.expressions.iter() {
+            let (image, set) = match *expression {
+                crate::Expression::ImageLoad { image, .. } => (image, &mut loaded),
+                crate::Expression::ImageSample {
+                    image,
+                    depth_ref: Some(_),
+                    ..
+                } => (image, &mut compared),
+                _ => continue,
+            };
+            if let crate::Expression::GlobalVariable(var) = function.expressions[image] {
+                set.insert(var);
+            }
+        }
+    }
+
+    loaded.retain(|&var| {
+        !compared.contains(&var)
+            && matches!(
+                module.types[module.global_variables[var].ty].inner,
+                TypeInner::Image {
+                    class: crate::ImageClass::Depth { multi: false },
+                    ..
+                }
+            )
+    });
+    loaded
+}
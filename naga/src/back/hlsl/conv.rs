@@ -60,7 +60,7 @@ impl crate::TypeInner {
                 let count = match size {
                     crate::ArraySize::Constant(size) => size.get(),
                     // A dynamically-sized array has to have at least one element
-                    crate::ArraySize::Dynamic => 1,
+                    crate::ArraySize::Pending(_) | crate::ArraySize::Dynamic => 1,
                 };
                 let last_el_size = gctx.types[base].inner.size_hlsl(gctx);
                 ((count - 1) * stride) + last_el_size
@@ -145,6 +145,57 @@ impl crate::StorageFormat {
             | Self::Rgba32Sint => "int4",
         }
     }
+
+    /// Return the number of components of the type [`to_hlsl_str`] returns.
+    ///
+    /// [`to_hlsl_str`]: Self::to_hlsl_str
+    pub(super) const fn hlsl_component_count(self) -> usize {
+        match self {
+            Self::R8Unorm
+            | Self::R8Snorm
+            | Self::R8Uint
+            | Self::R8Sint
+            | Self::R16Uint
+            | Self::R16Sint
+            | Self::R16Float
+            | Self::R16Unorm
+            | Self::R16Snorm => 1,
+
+            Self::Rg8Unorm
+            | Self::Rg8Snorm
+            | Self::Rg8Uint
+            | Self::Rg8Sint
+            | Self::Rg16Uint
+            | Self::Rg16Sint
+            | Self::Rg16Float
+            | Self::Rg16Unorm
+            | Self::Rg16Snorm => 2,
+
+            Self::Rg11b10Float => 3,
+
+            Self::R32Uint
+            | Self::R32Sint
+            | Self::R32Float
+            | Self::Rgba8Unorm
+            | Self::Rgba8Snorm
+            | Self::Rgba8Uint
+            | Self::Rgba8Sint
+            | Self::Bgra8Unorm
+            | Self::Rgb10a2Uint
+            | Self::Rgb10a2Unorm
+            | Self::Rg32Uint
+            | Self::Rg32Sint
+            | Self::Rg32Float
+            | Self::Rgba16Uint
+            | Self::Rgba16Sint
+            | Self::Rgba16Float
+            | Self::Rgba32Uint
+            | Self::Rgba32Sint
+            | Self::Rgba32Float
+            | Self::Rgba16Unorm
+            | Self::Rgba16Snorm => 4,
+        }
+    }
 }
 
 impl crate::BuiltIn {
@@ -174,6 +225,11 @@ impl crate::BuiltIn {
             Self::BaseInstance | Self::BaseVertex | Self::WorkGroupSize => {
                 return Err(Error::Unimplemented(format!("builtin {self:?}")))
             }
+            Self::MeshTaskSize
+            | Self::TriangleIndices
+            | Self::LineIndices
+            | Self::PointIndex
+            | Self::CullPrimitive => return Err(Error::Unimplemented(format!("builtin {self:?}"))),
             Self::PointSize | Self::ViewIndex | Self::PointCoord => {
                 return Err(Error::Custom(format!("Unsupported builtin {self:?}")))
             }
@@ -205,6 +261,30 @@ impl crate::Sampling {
     }
 }
 
+impl crate::LoopHint {
+    /// Return the HLSL attribute for this hint.
+    ///
+    /// <https://learn.microsoft.com/en-us/windows/win32/direct3dhlsl/dx-graphics-hlsl-while>
+    pub(super) const fn to_hlsl_str(self) -> &'static str {
+        match self {
+            Self::Unroll => "[unroll]",
+            Self::DontUnroll => "[loop]",
+        }
+    }
+}
+
+impl crate::BranchHint {
+    /// Return the HLSL attribute for this hint.
+    ///
+    /// <https://learn.microsoft.com/en-us/windows/win32/direct3dhlsl/dx-graphics-hlsl-if>
+    pub(super) const fn to_hlsl_str(self) -> &'static str {
+        match self {
+            Self::Branch => "[branch]",
+            Self::Flatten => "[flatten]",
+        }
+    }
+}
+
 impl crate::AtomicFunction {
     /// Return the HLSL suffix for the `InterlockedXxx` method.
     pub(super) const fn to_hlsl_suffix(self) -> &'static str {
@@ -216,7 +296,7 @@ impl crate::AtomicFunction {
             Self::Min => "Min",
             Self::Max => "Max",
             Self::Exchange { compare: None } => "Exchange",
-            Self::Exchange { .. } => "", //TODO
+            Self::Exchange { compare: Some(_) } => "CompareExchange",
         }
     }
 }
@@ -140,6 +140,7 @@ impl<'a, W: Write> super::Writer<'a, W> {
                 let storage_format_str = format.to_hlsl_str();
                 write!(self.out, "<{storage_format_str}>")?
             }
+            crate::ImageClass::External => unreachable!(),
         }
         Ok(())
     }
@@ -209,6 +210,7 @@ impl<'a, W: Write> super::Writer<'a, W> {
             crate::ImageClass::Depth { multi: false } => "Depth",
             crate::ImageClass::Sampled { multi: false, .. } => "",
             crate::ImageClass::Storage { .. } => "RW",
+            crate::ImageClass::External => unreachable!(),
         };
         let arrayed_str = if query.arrayed { "Array" } else { "" };
         let query_str = match query.query {
@@ -229,10 +231,7 @@ impl<'a, W: Write> super::Writer<'a, W> {
     /// <https://docs.microsoft.com/en-us/windows/win32/direct3dhlsl/dx-graphics-hlsl-to-getdimensions>
     pub(super) fn write_wrapped_image_query_function(
         &mut self,
-        module: &crate::Module,
         wiq: WrappedImageQuery,
-        expr_handle: Handle<crate::Expression>,
-        func_ctx: &FunctionCtx,
     ) -> BackendResult {
         use crate::{
             back::{COMPONENTS, INDENT},
@@ -243,9 +242,41 @@ impl<'a, W: Write> super::Writer<'a, W> {
         const RETURN_VARIABLE_NAME: &str = "ret";
         const MIP_LEVEL_PARAM: &str = "mip_level";
 
+        let dim_coords = match wiq.dim {
+            IDim::D1 => 1,
+            IDim::D2 | IDim::Cube => 2,
+            IDim::D3 => 3,
+        };
+        let array_coords = usize::from(wiq.arrayed);
+        // extra parameter is the mip level count or the sample count
+        let extra_coords = match wiq.class {
+            crate::ImageClass::Storage { .. } => 0,
+            crate::ImageClass::Sampled { .. } | crate::ImageClass::Depth { .. } => 1,
+            crate::ImageClass::External => unreachable!(),
+        };
+
+        // GetDimensions Overloaded Methods
+        // https://docs.microsoft.com/en-us/windows/win32/direct3dhlsl/dx-graphics-hlsl-to-getdimensions#overloaded-methods
+        //
+        // The outputs are the size, then the number of array elements, then
+        // the number of levels or samples. For cube arrays, the number of
+        // elements counts cubes, not faces, which is what `NumLayers` wants.
+        let number_of_params = dim_coords + array_coords + extra_coords;
+        let ret_swizzle = match wiq.query {
+            ImageQuery::Size | ImageQuery::SizeLevel => &"xyz"[..dim_coords],
+            ImageQuery::NumLayers => &"xyzw"[dim_coords..dim_coords + 1],
+            ImageQuery::NumLevels | ImageQuery::NumSamples => {
+                &"xyzw"[number_of_params - 1..number_of_params]
+            }
+        };
+
         // Write function return type and name
-        let ret_ty = func_ctx.resolve_type(expr_handle, &module.types);
-        self.write_value_type(module, ret_ty)?;
+        match wiq.query {
+            ImageQuery::Size | ImageQuery::SizeLevel if dim_coords > 1 => {
+                write!(self.out, "uint{dim_coords}")?
+            }
+            _ => write!(self.out, "uint")?,
+        }
         write!(self.out, " ")?;
         self.write_wrapped_image_query_function_name(wiq)?;
 
@@ -263,34 +294,6 @@ impl<'a, W: Write> super::Writer<'a, W> {
         // Write function body
         writeln!(self.out, "{{")?;
 
-        let array_coords = usize::from(wiq.arrayed);
-        // extra parameter is the mip level count or the sample count
-        let extra_coords = match wiq.class {
-            crate::ImageClass::Storage { .. } => 0,
-            crate::ImageClass::Sampled { .. } | crate::ImageClass::Depth { .. } => 1,
-        };
-
-        // GetDimensions Overloaded Methods
-        // https://docs.microsoft.com/en-us/windows/win32/direct3dhlsl/dx-graphics-hlsl-to-getdimensions#overloaded-methods
-        let (ret_swizzle, number_of_params) = match wiq.query {
-            ImageQuery::Size | ImageQuery::SizeLevel => {
-                let ret = match wiq.dim {
-                    IDim::D1 => "x",
-                    IDim::D2 => "xy",
-                    IDim::D3 => "xyz",
-                    IDim::Cube => "xy",
-                };
-                (ret, ret.len() + array_coords + extra_coords)
-            }
-            ImageQuery::NumLevels | ImageQuery::NumSamples | ImageQuery::NumLayers => {
-                if wiq.arrayed || wiq.dim == IDim::D3 {
-                    ("w", 4)
-                } else {
-                    ("z", 3)
-                }
-            }
-        };
-
         // Write `GetDimensions` function.
         writeln!(self.out, "{INDENT}uint4 {RETURN_VARIABLE_NAME};")?;
         write!(self.out, "{INDENT}{ARGUMENT_VARIABLE_NAME}.GetDimensions(")?;
@@ -901,7 +904,33 @@ impl<'a, W: Write> super::Writer<'a, W> {
                     };
 
                     if self.wrapped.image_queries.insert(wiq) {
-                        self.write_wrapped_image_query_function(module, wiq, handle, func_ctx)?;
+                        self.write_wrapped_image_query_function(wiq)?;
+                    }
+                }
+                crate::Expression::ImageSample {
+                    image,
+                    array_index: Some(_),
+                    ..
+                } if self.options.bounds_check_policies.image_load
+                    == crate::proc::BoundsCheckPolicy::Restrict =>
+                {
+                    // The array index is clamped to the number of layers.
+                    let wiq = match *func_ctx.resolve_type(image, &module.types) {
+                        crate::TypeInner::Image {
+                            dim,
+                            arrayed,
+                            class,
+                        } => WrappedImageQuery {
+                            dim,
+                            arrayed,
+                            class,
+                            query: ImageQuery::NumLayers,
+                        },
+                        _ => unreachable!("we only sample images"),
+                    };
+
+                    if self.wrapped.image_queries.insert(wiq) {
+                        self.write_wrapped_image_query_function(wiq)?;
                     }
                 }
                 // Write `WrappedConstructor` for structs that are loaded from `AddressSpace::Storage`
@@ -996,12 +1025,18 @@ impl<'a, W: Write> super::Writer<'a, W> {
         Ok(())
     }
 
+    /// Write `coordinate`, merged with `array_index` and `mip_level`.
+    ///
+    /// If `layers_of` is `Some(image)`, clamp `array_index` to the layers of
+    /// `image`, using the wrapped `NumLayers` query for its type.
+    #[allow(clippy::too_many_arguments)]
     pub(super) fn write_texture_coordinates(
         &mut self,
         kind: &str,
         coordinate: Handle<crate::Expression>,
         array_index: Option<Handle<crate::Expression>>,
         mip_level: Option<Handle<crate::Expression>>,
+        layers_of: Option<Handle<crate::Expression>>,
         module: &crate::Module,
         func_ctx: &FunctionCtx,
     ) -> BackendResult {
@@ -1019,7 +1054,10 @@ impl<'a, W: Write> super::Writer<'a, W> {
             self.write_expr(module, coordinate, func_ctx)?;
             if let Some(expr) = array_index {
                 write!(self.out, ", ")?;
-                self.write_expr(module, expr, func_ctx)?;
+                match layers_of {
+                    Some(image) => self.write_clamped_array_index(module, expr, image, func_ctx)?,
+                    None => self.write_expr(module, expr, func_ctx)?,
+                }
             }
             if let Some(expr) = mip_level {
                 write!(self.out, ", ")?;
@@ -1030,6 +1068,57 @@ impl<'a, W: Write> super::Writer<'a, W> {
         Ok(())
     }
 
+    /// Write `array_index`, clamped to the number of layers in `image`.
+    ///
+    /// For cube arrays, HLSL counts and indexes whole cubes, so the layer
+    /// count needs no adjustment for the six faces.
+    fn write_clamped_array_index(
+        &mut self,
+        module: &crate::Module,
+        array_index: Handle<crate::Expression>,
+        image: Handle<crate::Expression>,
+        func_ctx: &FunctionCtx,
+    ) -> BackendResult {
+        let wiq = match *func_ctx.resolve_type(image, &module.types) {
+            crate::TypeInner::Image {
+                dim,
+                arrayed,
+                class,
+            } => WrappedImageQuery {
+                dim,
+                arrayed,
+                class,
+                query: ImageQuery::NumLayers,
+            },
+            _ => unreachable!("we only sample images"),
+        };
+        let signed = match *func_ctx.resolve_type(array_index, &module.types) {
+            crate::TypeInner::Scalar(scalar) => scalar.kind == crate::ScalarKind::Sint,
+            _ => unreachable!("array indices are scalars"),
+        };
+
+        // Negative indices clamp to the first layer, so we can't let
+        // HLSL convert a signed index to match the unsigned layer count.
+        if signed {
+            write!(self.out, "clamp(")?;
+            self.write_expr(module, array_index, func_ctx)?;
+            write!(self.out, ", 0, int(")?;
+        } else {
+            write!(self.out, "min(")?;
+            self.write_expr(module, array_index, func_ctx)?;
+            write!(self.out, ", ")?;
+        }
+        self.write_wrapped_image_query_function_name(wiq)?;
+        write!(self.out, "(")?;
+        self.write_expr(module, image, func_ctx)?;
+        write!(self.out, ")")?;
+        if signed {
+            write!(self.out, ")")?;
+        }
+        write!(self.out, " - 1)")?;
+        Ok(())
+    }
+
     pub(super) fn write_mat_cx2_typedef_and_functions(
         &mut self,
         WrappedMatCx2 { columns }: WrappedMatCx2,
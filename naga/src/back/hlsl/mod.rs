@@ -19,6 +19,13 @@ which we generate `Load` and `Store` method calls with explicit byte
 offsets. WGSL pipeline inputs must be scalars or vectors; they cannot
 be matrices, which is where the interesting problems arise.
 
+Matrices in `storage` buffers are loaded and stored one column at a
+time, so HLSL's packing rules never come into play. The columns are
+spaced as the [`Layouter`] says: for example, the columns of a
+`mat3x3<f32>` are 16 bytes apart, like `vec4<f32>`s.
+
+[`Layouter`]: crate::proc::Layouter
+
 ## Row- and column-major ordering for matrices
 
 WGSL specifies that matrices in uniform buffers are stored in
@@ -122,6 +129,83 @@ pub struct BindTarget {
 // Using `BTreeMap` instead of `HashMap` so that we can hash itself.
 pub type BindingMap = std::collections::BTreeMap<crate::ResourceBinding, BindTarget>;
 
+/// A class of HLSL registers. Each class is allocated separately.
+///
+/// See <https://learn.microsoft.com/en-us/windows/win32/direct3dhlsl/dx-graphics-hlsl-variable-register>.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum RegisterClass {
+    /// Constant buffers, in `b` registers.
+    ConstantBuffer,
+    /// Shader resource views, like textures and read-only storage buffers,
+    /// in `t` registers.
+    ShaderResource,
+    /// Unordered access views, like storage textures and read-write storage
+    /// buffers, in `u` registers.
+    UnorderedAccess,
+    /// Samplers, in `s` registers.
+    Sampler,
+}
+
+impl RegisterClass {
+    /// The letter that starts this class's register names, like the `s` in
+    /// `register(s0)`.
+    pub const fn prefix(self) -> &'static str {
+        match self {
+            Self::ConstantBuffer => "b",
+            Self::ShaderResource => "t",
+            Self::UnorderedAccess => "u",
+            Self::Sampler => "s",
+        }
+    }
+}
+
+/// The registers a global variable was bound to.
+///
+/// See [`ReflectionInfo::resource_bindings`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RegisterBinding {
+    pub global: crate::Handle<crate::GlobalVariable>,
+    pub class: RegisterClass,
+    /// The space and first register of the binding.
+    pub target: BindTarget,
+    /// How many consecutive registers the binding occupies, or `None` for a
+    /// binding array with no size.
+    pub count: Option<u32>,
+}
+
+/// The layout of the special constants buffer.
+///
+/// See [`Options::special_constants_binding`]. All members are 32 bits
+/// wide, so the runtime can also provide the buffer as root constants.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SpecialConstantsLayout {
+    /// Byte offset of the `int` added to `SV_VertexID`, which should hold
+    /// the draw's first vertex, or its base vertex for indexed draws.
+    ///
+    /// In compute shaders, this holds the number of workgroups along `x`.
+    pub first_vertex: u32,
+    /// Byte offset of the `int` added to `SV_InstanceID`, which should hold
+    /// the draw's first instance.
+    ///
+    /// In compute shaders, this holds the number of workgroups along `y`.
+    pub first_instance: u32,
+    /// Byte offset of a `uint` only compute shaders use, holding the number
+    /// of workgroups along `z`.
+    pub other: u32,
+    /// The size of the buffer in bytes.
+    pub size: u32,
+}
+
+impl SpecialConstantsLayout {
+    /// The layout of the buffer the backend declares.
+    pub const DEFAULT: Self = Self {
+        first_vertex: 0,
+        first_instance: 4,
+        other: 8,
+        size: 12,
+    };
+}
+
 /// A HLSL shader model version.
 #[allow(non_snake_case, non_camel_case_types)]
 #[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, PartialOrd)]
@@ -131,6 +215,7 @@ pub enum ShaderModel {
     V5_0,
     V5_1,
     V6_0,
+    V6_4,
 }
 
 impl ShaderModel {
@@ -139,6 +224,7 @@ impl ShaderModel {
             Self::V5_0 => "5_0",
             Self::V5_1 => "5_1",
             Self::V6_0 => "6_0",
+            Self::V6_4 => "6_4",
         }
     }
 }
@@ -149,6 +235,8 @@ impl crate::ShaderStage {
             Self::Vertex => "vs",
             Self::Fragment => "ps",
             Self::Compute => "cs",
+            Self::Task => "as",
+            Self::Mesh => "ms",
         }
     }
 }
@@ -173,6 +261,10 @@ type BackendResult = Result<(), Error>;
 pub enum EntryPointError {
     #[error("mapping of {0:?} is missing")]
     MissingBinding(crate::ResourceBinding),
+    #[error("{0:?} requires `Options::special_constants_binding` to be set")]
+    MissingSpecialConstants(crate::BuiltIn),
+    #[error("the workgroup size override {0:?} has no valid value")]
+    UnresolvedOverride(String),
 }
 
 /// Configuration used in the [`Writer`].
@@ -193,6 +285,31 @@ pub struct Options {
     pub push_constants_target: Option<BindTarget>,
     /// Should workgroup variables be zero initialized (by polyfilling)?
     pub zero_initialize_workgroup_memory: bool,
+    /// Write `clamp(x, 0.0, 1.0)` as `saturate(x)`, which some drivers
+    /// turn into a free modifier on the instruction producing `x`.
+    #[cfg_attr(feature = "deserialize", serde(default))]
+    pub fold_clamp_to_saturate: bool,
+    /// How to handle out-of-range indices.
+    ///
+    /// Of these, the backend currently only honors [`image_load`], and only
+    /// its [`Restrict`] policy, which it applies to the array index of
+    /// [`ImageSample`] expressions by clamping it to the image's layer count.
+    ///
+    /// [`image_load`]: proc::BoundsCheckPolicies::image_load
+    /// [`Restrict`]: proc::BoundsCheckPolicy::Restrict
+    /// [`ImageSample`]: crate::Expression::ImageSample
+    #[cfg_attr(feature = "deserialize", serde(default))]
+    pub bounds_check_policies: proc::BoundsCheckPolicies,
+    /// Values of override constants, keyed by their numeric ID written in
+    /// decimal if they have one, and by their name otherwise.
+    ///
+    /// HLSL has no specialization constants, so overrides that give an
+    /// entry point's [`workgroup_size_overrides`] are resolved when
+    /// writing. Overrides missing from this map use their initializer.
+    ///
+    /// [`workgroup_size_overrides`]: crate::EntryPoint::workgroup_size_overrides
+    #[cfg_attr(feature = "deserialize", serde(default))]
+    pub override_values: std::collections::BTreeMap<String, u32>,
 }
 
 impl Default for Options {
@@ -204,6 +321,9 @@ impl Default for Options {
             special_constants_binding: None,
             push_constants_target: None,
             zero_initialize_workgroup_memory: true,
+            fold_clamp_to_saturate: false,
+            bounds_check_policies: proc::BoundsCheckPolicies::default(),
+            override_values: Default::default(),
         }
     }
 }
@@ -223,6 +343,58 @@ impl Options {
             None => Err(EntryPointError::MissingBinding(res_binding.clone())),
         }
     }
+
+    /// Return `ep`'s workgroup size, with any overrides resolved.
+    fn resolve_workgroup_size(
+        &self,
+        module: &crate::Module,
+        ep: &crate::EntryPoint,
+    ) -> Result<[u32; 3], EntryPointError> {
+        let mut size = ep.workgroup_size;
+        for (size, handle) in size
+            .iter_mut()
+            .zip(ep.workgroup_size_overrides.unwrap_or_default())
+        {
+            if let Some(handle) = handle {
+                let value = self.resolve_override(module, handle);
+                *size = value.filter(|&value| value != 0).ok_or_else(|| {
+                    EntryPointError::UnresolvedOverride(override_key(&module.constants[handle]))
+                })?;
+            }
+        }
+        Ok(size)
+    }
+
+    /// Return the value of the constant `handle` as a `u32`, taking it from
+    /// [`override_values`] if it's an override listed there.
+    ///
+    /// [`override_values`]: Options::override_values
+    fn resolve_override(
+        &self,
+        module: &crate::Module,
+        handle: crate::Handle<crate::Constant>,
+    ) -> Option<u32> {
+        let constant = &module.constants[handle];
+        if constant.r#override != crate::Override::None {
+            if let Some(&value) = self.override_values.get(&override_key(constant)) {
+                return Some(value);
+            }
+        }
+        match module.const_expressions[constant.init] {
+            crate::Expression::Constant(other) => self.resolve_override(module, other),
+            crate::Expression::Literal(crate::Literal::U32(value)) => Some(value),
+            crate::Expression::Literal(crate::Literal::I32(value)) => value.try_into().ok(),
+            _ => None,
+        }
+    }
+}
+
+/// Return the key of the override `constant` in [`Options::override_values`].
+fn override_key(constant: &crate::Constant) -> String {
+    match constant.r#override {
+        crate::Override::ByNameOrId(id) => id.to_string(),
+        _ => constant.name.clone().unwrap_or_default(),
+    }
 }
 
 /// Reflection info for entry point names.
@@ -235,6 +407,27 @@ pub struct ReflectionInfo {
     ///
     /// Note: Some entry points may fail translation because of missing bindings.
     pub entry_point_names: Vec<Result<String, EntryPointError>>,
+    /// The binding of the special constants buffer, if the generated code
+    /// declares one. See [`Options::special_constants_binding`].
+    pub special_constants_binding: Option<BindTarget>,
+    /// The layout of the special constants buffer, if the generated code
+    /// declares one, so the runtime can fill it for each draw or dispatch.
+    pub special_constants_layout: Option<SpecialConstantsLayout>,
+    /// For each entry point, whether it reads [`BuiltIn::NumWorkGroups`].
+    ///
+    /// HLSL has no semantic for the number of workgroups, so such entry
+    /// points read it from the special constants buffer instead, and the
+    /// runtime must fill that buffer with the dispatch size.
+    ///
+    /// [`BuiltIn::NumWorkGroups`]: crate::BuiltIn::NumWorkGroups
+    pub uses_num_workgroups: Vec<bool>,
+    /// The registers of every resource global the output declares, in
+    /// declaration order.
+    ///
+    /// This includes globals given registers by [`Options::binding_map`] and
+    /// by [`Options::fake_missing_bindings`] alike, so the runtime can build
+    /// a root signature to match, for example one with static samplers.
+    pub resource_bindings: Vec<RegisterBinding>,
 }
 
 #[derive(Error, Debug)]
@@ -247,6 +440,17 @@ pub enum Error {
     Unimplemented(String), // TODO: Error used only during development
     #[error("{0}")]
     Custom(String),
+    #[error(
+        "global variables {first:?} and {second:?} both use register {}{register} in space {space}",
+        .class.prefix()
+    )]
+    RegisterCollision {
+        first: crate::Handle<crate::GlobalVariable>,
+        second: crate::Handle<crate::GlobalVariable>,
+        class: RegisterClass,
+        space: u8,
+        register: u32,
+    },
 }
 
 #[derive(Default)]
@@ -299,4 +503,7 @@ pub struct Writer<'a, W> {
     /// [`AccessIndex`]: crate::Expression::AccessIndex
     temp_access_chain: Vec<storage::SubAccess>,
     need_bake_expressions: back::NeedBakeExpressions,
+    /// The registers allocated to the globals written so far, for
+    /// [`ReflectionInfo::resource_bindings`].
+    resource_bindings: Vec<RegisterBinding>,
 }
@@ -248,8 +248,32 @@ impl<W: fmt::Write> super::Writer<'_, W> {
                 base,
                 member_index,
             } => {
-                let name = &self.names[&NameKey::StructMember(base, member_index)];
-                write!(self.out, "{STORE_TEMP_NAME}{depth}.{name}")?
+                let name = self.names[&NameKey::StructMember(base, member_index)].clone();
+                let member = match module.types[base].inner {
+                    crate::TypeInner::Struct { ref members, .. } => &members[member_index as usize],
+                    _ => unreachable!(),
+                };
+                match module.types[member.ty].inner {
+                    // Structs hold `matCx2` members as separate columns, so
+                    // reassemble the matrix. See the module-level block
+                    // comment in mod.rs for details.
+                    ref matrix @ crate::TypeInner::Matrix {
+                        columns,
+                        rows: crate::VectorSize::Bi,
+                        ..
+                    } if member.binding.is_none() => {
+                        self.write_value_type(module, matrix)?;
+                        write!(self.out, "(")?;
+                        for i in 0..columns as u8 {
+                            if i != 0 {
+                                write!(self.out, ", ")?;
+                            }
+                            write!(self.out, "{STORE_TEMP_NAME}{depth}.{name}_{i}")?;
+                        }
+                        write!(self.out, ")")?;
+                    }
+                    _ => write!(self.out, "{STORE_TEMP_NAME}{depth}.{name}")?,
+                }
             }
         }
         Ok(())
@@ -1,7 +1,7 @@
 use super::{
     help::{WrappedArrayLength, WrappedConstructor, WrappedImageQuery, WrappedStructMatrixAccess},
     storage::StoreValue,
-    BackendResult, Error, Options,
+    BackendResult, EntryPointError, Error, Options, RegisterBinding, RegisterClass, ShaderModel,
 };
 use crate::{
     back,
@@ -26,6 +26,51 @@ struct EpStructMember {
     // technically, this should always be `Some`
     binding: Option<crate::Binding>,
     index: u32,
+    /// For a vertex input split across several locations, the names of the
+    /// members holding each of them. Empty otherwise.
+    parts: Vec<String>,
+}
+
+impl EpStructMember {
+    /// If this member is the `clip_distance` built-in, return the number of
+    /// clip distances it holds.
+    fn clip_distance_count(&self, module: &Module) -> Option<u32> {
+        match (self.binding.as_ref(), &module.types[self.ty].inner) {
+            (
+                Some(&crate::Binding::BuiltIn(crate::BuiltIn::ClipDistance)),
+                &TypeInner::Array {
+                    size: crate::ArraySize::Constant(count),
+                    ..
+                },
+            ) => Some(count.get()),
+            _ => None,
+        }
+    }
+}
+
+/// Return the HLSL type holding `count` floats, for clip distance members.
+fn clip_distance_type(count: u32) -> String {
+    match count {
+        1 => "float".to_string(),
+        _ => format!("float{count}"),
+    }
+}
+
+/// Write the clip distances `value[range]`, gathered into a single vector
+/// for one of the `SV_ClipDistance` members of an output struct.
+fn write_clip_distances(
+    out: &mut impl fmt::Write,
+    value: &str,
+    range: std::ops::Range<u32>,
+) -> fmt::Result {
+    write!(out, "{}(", clip_distance_type(range.len() as u32))?;
+    for index in range.clone() {
+        if index != range.start {
+            write!(out, ", ")?;
+        }
+        write!(out, "{value}[{index}]")?;
+    }
+    write!(out, ")")
 }
 
 /// Structure contains information required for generating
@@ -87,6 +132,7 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
             wrapped: super::Wrapped::default(),
             temp_access_chain: Vec::new(),
             need_bake_expressions: Default::default(),
+            resource_bindings: Vec::new(),
         }
     }
 
@@ -104,6 +150,7 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
         self.named_expressions.clear();
         self.wrapped.clear();
         self.need_bake_expressions.clear();
+        self.resource_bindings.clear();
     }
 
     /// Helper method used to find which expressions of a given function require baking
@@ -149,6 +196,14 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                     | crate::MathFunction::Pack4x8unorm => {
                         self.need_bake_expressions.insert(arg);
                     }
+                    // Without the shader model 6.4 intrinsics, each operand
+                    // is written once per byte.
+                    crate::MathFunction::Dot4I8Packed | crate::MathFunction::Dot4U8Packed
+                        if self.options.shader_model < ShaderModel::V6_4 =>
+                    {
+                        self.need_bake_expressions.insert(arg);
+                        self.need_bake_expressions.insert(arg1.unwrap());
+                    }
                     crate::MathFunction::ExtractBits => {
                         self.need_bake_expressions.insert(arg);
                         self.need_bake_expressions.insert(arg1.unwrap());
@@ -184,6 +239,22 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
         module: &Module,
         module_info: &valid::ModuleInfo,
     ) -> Result<super::ReflectionInfo, Error> {
+        if crate::external_texture::has_external_textures(module) {
+            return Err(Error::Custom(
+                "external textures must be lowered first".to_string(),
+            ));
+        }
+        if let Some(ep) = module
+            .entry_points
+            .iter()
+            .find(|ep| matches!(ep.stage, ShaderStage::Task | ShaderStage::Mesh))
+        {
+            return Err(Error::Unimplemented(format!(
+                "{:?} shader entry point `{}`",
+                ep.stage, ep.name
+            )));
+        }
+
         self.reset(module);
 
         // Write special constants, if needed
@@ -275,10 +346,18 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
             writeln!(self.out)?;
         }
 
+        self.check_register_collisions(module, module_info)?;
+
         // Write all entry points wrapped structs
         for (index, ep) in module.entry_points.iter().enumerate() {
             let ep_name = self.names[&NameKey::EntryPoint(index as u16)].clone();
-            let ep_io = self.write_ep_interface(module, &ep.function, ep.stage, &ep_name)?;
+            let ep_io = self.write_ep_interface(
+                module,
+                &ep.function,
+                ep.stage,
+                &ep_name,
+                module_info.get_entry_point(index),
+            )?;
             self.entry_point_io.push(ep_io);
         }
 
@@ -325,11 +404,25 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
         }
 
         let mut entry_point_names = Vec::with_capacity(module.entry_points.len());
+        let uses_num_workgroups = module
+            .entry_points
+            .iter()
+            .map(|ep| uses_builtin(module, &ep.function, crate::BuiltIn::NumWorkGroups))
+            .collect::<Vec<_>>();
 
         // Write all entry points
         for (index, ep) in module.entry_points.iter().enumerate() {
             let info = module_info.get_entry_point(index);
 
+            // There is no HLSL semantic for `num_workgroups`; we emulate it
+            // with the special constants buffer, which must exist.
+            if uses_num_workgroups[index] && self.options.special_constants_binding.is_none() {
+                entry_point_names.push(Err(EntryPointError::MissingSpecialConstants(
+                    crate::BuiltIn::NumWorkGroups,
+                )));
+                continue;
+            }
+
             if !self.options.fake_missing_bindings {
                 let mut ep_error = None;
                 for (var_handle, var) in module.global_variables.iter() {
@@ -349,6 +442,14 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                 }
             }
 
+            let num_threads = match self.options.resolve_workgroup_size(module, ep) {
+                Ok(size) => size,
+                Err(err) => {
+                    entry_point_names.push(Err(err));
+                    continue;
+                }
+            };
+
             let ctx = back::FunctionCtx {
                 ty: back::FunctionType::EntryPoint(index as u16),
                 info,
@@ -360,7 +461,6 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
 
             if ep.stage == ShaderStage::Compute {
                 // HLSL is calling workgroup size "num threads"
-                let num_threads = ep.workgroup_size;
                 writeln!(
                     self.out,
                     "[numthreads({}, {}, {})]",
@@ -378,7 +478,17 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
             entry_point_names.push(Ok(name));
         }
 
-        Ok(super::ReflectionInfo { entry_point_names })
+        Ok(super::ReflectionInfo {
+            entry_point_names,
+            special_constants_binding: self.options.special_constants_binding.clone(),
+            special_constants_layout: self
+                .options
+                .special_constants_binding
+                .as_ref()
+                .map(|_| super::SpecialConstantsLayout::DEFAULT),
+            uses_num_workgroups,
+            resource_bindings: mem::take(&mut self.resource_bindings),
+        })
     }
 
     fn write_modifier(&mut self, binding: &crate::Binding) -> BackendResult {
@@ -458,6 +568,50 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
         write!(self.out, "struct {struct_name}")?;
         writeln!(self.out, " {{")?;
         for m in members.iter() {
+            if let Some(count) = m.clip_distance_count(module) {
+                // D3D packs clip distances into at most two `float4`s, so split
+                // the array across `SV_ClipDistance0` and `SV_ClipDistance1`.
+                let low = count.min(4);
+                writeln!(
+                    self.out,
+                    "{}{} {} : SV_ClipDistance0;",
+                    back::INDENT,
+                    clip_distance_type(low),
+                    m.name
+                )?;
+                if count > low {
+                    let high_name = self.namer.call(&m.name);
+                    writeln!(
+                        self.out,
+                        "{}{} {} : SV_ClipDistance1;",
+                        back::INDENT,
+                        clip_distance_type(count - low),
+                        high_name
+                    )?;
+                }
+                continue;
+            }
+            if let Some((_, part_inner)) = module.types[m.ty]
+                .inner
+                .vertex_input_split(&module.types)
+                .filter(|_| !m.parts.is_empty())
+            {
+                // Give each column or element its own semantic.
+                for (index, part) in m.parts.iter().enumerate() {
+                    write!(self.out, "{}", back::INDENT)?;
+                    self.write_value_type(module, &part_inner)?;
+                    write!(self.out, " {part}")?;
+                    if let Some(crate::Binding::Location { location, .. }) = m.binding {
+                        write!(
+                            self.out,
+                            " : {LOCATION_SEMANTIC}{}",
+                            location + index as u32
+                        )?;
+                    }
+                    writeln!(self.out, ";")?;
+                }
+                continue;
+            }
             write!(self.out, "{}", back::INDENT)?;
             if let Some(ref binding) = m.binding {
                 self.write_modifier(binding)?;
@@ -508,22 +662,38 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                     for member in members.iter() {
                         let name = self.namer.call_or(&member.name, "member");
                         let index = fake_members.len() as u32;
+                        let parts = self.split_input_parts(
+                            module,
+                            stage,
+                            &name,
+                            member.ty,
+                            member.binding.as_ref(),
+                        );
                         fake_members.push(EpStructMember {
                             name,
                             ty: member.ty,
                             binding: member.binding.clone(),
                             index,
+                            parts,
                         });
                     }
                 }
                 _ => {
                     let member_name = self.namer.call_or(&arg.name, "member");
                     let index = fake_members.len() as u32;
+                    let parts = self.split_input_parts(
+                        module,
+                        stage,
+                        &member_name,
+                        arg.ty,
+                        arg.binding.as_ref(),
+                    );
                     fake_members.push(EpStructMember {
                         name: member_name,
                         ty: arg.ty,
                         binding: arg.binding.clone(),
                         index,
+                        parts,
                     });
                 }
             }
@@ -532,6 +702,30 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
         self.write_interface_struct(module, (stage, Io::Input), struct_name, fake_members)
     }
 
+    /// If the input `name` is a vertex input that takes several locations,
+    /// return fresh names for the members holding each of them.
+    fn split_input_parts(
+        &mut self,
+        module: &Module,
+        stage: ShaderStage,
+        name: &str,
+        ty: Handle<crate::Type>,
+        binding: Option<&crate::Binding>,
+    ) -> Vec<String> {
+        let count = match (stage, binding) {
+            (ShaderStage::Vertex, Some(&crate::Binding::Location { .. })) => {
+                match module.types[ty].inner.vertex_input_split(&module.types) {
+                    Some((count, _)) => count,
+                    None => 0,
+                }
+            }
+            _ => 0,
+        };
+        (0..count)
+            .map(|index| self.namer.call(&format!("{name}_{index}")))
+            .collect()
+    }
+
     /// Flatten all entry point results into a single struct.
     /// This is needed since we need to re-order them: first placing user locations,
     /// then built-ins.
@@ -562,6 +756,7 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                 ty: member.ty,
                 binding: member.binding.clone(),
                 index,
+                parts: Vec::new(),
             });
         }
 
@@ -571,15 +766,24 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
     /// Writes special interface structures for an entry point. The special structures have
     /// all the fields flattened into them and sorted by binding. They are only needed for
     /// VS outputs and FS inputs, so that these interfaces match.
+    ///
+    /// VS inputs also need one if some of them take several locations, so
+    /// that those can be split into one member per location.
     fn write_ep_interface(
         &mut self,
         module: &Module,
         func: &crate::Function,
         stage: ShaderStage,
         ep_name: &str,
+        ep_info: &valid::FunctionInfo,
     ) -> Result<EntryPointInterface, Error> {
+        let flatten_input = match stage {
+            ShaderStage::Vertex => !ep_info.split_vertex_inputs.is_empty(),
+            ShaderStage::Fragment => true,
+            ShaderStage::Compute | ShaderStage::Task | ShaderStage::Mesh => false,
+        };
         Ok(EntryPointInterface {
-            input: if !func.arguments.is_empty() && stage == ShaderStage::Fragment {
+            input: if !func.arguments.is_empty() && flatten_input {
                 Some(self.write_ep_input_struct(module, func, stage, ep_name)?)
             } else {
                 None
@@ -614,7 +818,9 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                 TypeInner::Array { base, size, .. } => {
                     self.write_array_size(module, base, size)?;
                     let fake_member = fake_iter.next().unwrap();
-                    writeln!(self.out, " = {}.{};", ep_input.arg_name, fake_member.name)?;
+                    write!(self.out, " = ")?;
+                    self.write_ep_input_member(module, &ep_input.arg_name, fake_member)?;
+                    writeln!(self.out, ";")?;
                 }
                 TypeInner::Struct { ref members, .. } => {
                     write!(self.out, " = {{ ")?;
@@ -623,13 +829,15 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                             write!(self.out, ", ")?;
                         }
                         let fake_member = fake_iter.next().unwrap();
-                        write!(self.out, "{}.{}", ep_input.arg_name, fake_member.name)?;
+                        self.write_ep_input_member(module, &ep_input.arg_name, fake_member)?;
                     }
                     writeln!(self.out, " }};")?;
                 }
                 _ => {
                     let fake_member = fake_iter.next().unwrap();
-                    writeln!(self.out, " = {}.{};", ep_input.arg_name, fake_member.name)?;
+                    write!(self.out, " = ")?;
+                    self.write_ep_input_member(module, &ep_input.arg_name, fake_member)?;
+                    writeln!(self.out, ";")?;
                 }
             }
         }
@@ -637,6 +845,85 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
         Ok(())
     }
 
+    /// Write the value of `member` of the entry point input struct `arg_name`,
+    /// putting it back together if it was split across several locations.
+    fn write_ep_input_member(
+        &mut self,
+        module: &Module,
+        arg_name: &str,
+        member: &EpStructMember,
+    ) -> BackendResult {
+        if member.parts.is_empty() {
+            write!(self.out, "{arg_name}.{}", member.name)?;
+            return Ok(());
+        }
+        // Arrays are built with an initializer list, matrices from their rows.
+        match module.types[member.ty].inner {
+            TypeInner::Array { .. } => write!(self.out, "{{ ")?,
+            _ => {
+                self.write_type(module, member.ty)?;
+                write!(self.out, "(")?;
+            }
+        }
+        for (index, part) in member.parts.iter().enumerate() {
+            if index != 0 {
+                write!(self.out, ", ")?;
+            }
+            write!(self.out, "{arg_name}.{part}")?;
+        }
+        match module.types[member.ty].inner {
+            TypeInner::Array { .. } => write!(self.out, " }}")?,
+            _ => write!(self.out, ")")?,
+        }
+        Ok(())
+    }
+
+    /// Check that no entry point uses two globals whose registers overlap.
+    ///
+    /// Entry points may reuse each other's bindings, so globals that no
+    /// single entry point uses together are free to collide.
+    fn check_register_collisions(
+        &self,
+        module: &Module,
+        module_info: &valid::ModuleInfo,
+    ) -> BackendResult {
+        // The registers `binding` occupies, as a half-open range, where an
+        // end of `None` means the range is unbounded.
+        let range = |binding: &RegisterBinding| {
+            let start = binding.target.register;
+            (
+                start,
+                binding.count.map(|count| start.saturating_add(count)),
+            )
+        };
+
+        for (index, first) in self.resource_bindings.iter().enumerate() {
+            let (first_start, first_end) = range(first);
+            for second in self.resource_bindings[index + 1..].iter() {
+                if second.class != first.class || second.target.space != first.target.space {
+                    continue;
+                }
+                let (second_start, second_end) = range(second);
+                let overlap = first_end.map_or(true, |end| second_start < end)
+                    && second_end.map_or(true, |end| first_start < end);
+                let shared = (0..module.entry_points.len()).any(|index| {
+                    let info = module_info.get_entry_point(index);
+                    !info[first.global].is_empty() && !info[second.global].is_empty()
+                });
+                if overlap && shared {
+                    return Err(Error::RegisterCollision {
+                        first: first.global,
+                        second: second.global,
+                        class: first.class,
+                        space: first.target.space,
+                        register: first_start.max(second_start),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
     /// Helper method used to write global variables
     /// # Notes
     /// Always adds a newline
@@ -661,32 +948,39 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
         }
 
         // https://docs.microsoft.com/en-us/windows/win32/direct3dhlsl/dx-graphics-hlsl-variable-register
-        let register_ty = match global.space {
+        let register_class = match global.space {
             crate::AddressSpace::Function => unreachable!("Function address space"),
             crate::AddressSpace::Private => {
                 write!(self.out, "static ")?;
                 self.write_type(module, global.ty)?;
-                ""
+                None
             }
             crate::AddressSpace::WorkGroup => {
                 write!(self.out, "groupshared ")?;
                 self.write_type(module, global.ty)?;
-                ""
+                None
             }
             crate::AddressSpace::Uniform => {
                 // constant buffer declarations are expected to be inlined, e.g.
                 // `cbuffer foo: register(b0) { field1: type1; }`
                 write!(self.out, "cbuffer")?;
-                "b"
+                Some(RegisterClass::ConstantBuffer)
             }
             crate::AddressSpace::Storage { access } => {
-                let (prefix, register) = if access.contains(crate::StorageAccess::STORE) {
-                    ("RW", "u")
+                let (prefix, class) = if access.contains(crate::StorageAccess::STORE) {
+                    ("RW", RegisterClass::UnorderedAccess)
                 } else {
-                    ("", "t")
+                    ("", RegisterClass::ShaderResource)
                 };
+                // HLSL has nothing like `volatile` for buffers, so
+                // `globallycoherent` stands in for both decorations. Only
+                // UAVs can have it.
+                if class == RegisterClass::UnorderedAccess && !global.memory_decorations.is_empty()
+                {
+                    write!(self.out, "globallycoherent ")?;
+                }
                 write!(self.out, "{prefix}ByteAddressBuffer")?;
-                register
+                Some(class)
             }
             crate::AddressSpace::Handle => {
                 let handle_ty = match *inner {
@@ -694,22 +988,22 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                     _ => inner,
                 };
 
-                let register = match *handle_ty {
-                    TypeInner::Sampler { .. } => "s",
+                let class = match *handle_ty {
+                    TypeInner::Sampler { .. } => RegisterClass::Sampler,
                     // all storage textures are UAV, unconditionally
                     TypeInner::Image {
                         class: crate::ImageClass::Storage { .. },
                         ..
-                    } => "u",
-                    _ => "t",
+                    } => RegisterClass::UnorderedAccess,
+                    _ => RegisterClass::ShaderResource,
                 };
                 self.write_type(module, global.ty)?;
-                register
+                Some(class)
             }
             crate::AddressSpace::PushConstant => {
                 // The type of the push constants will be wrapped in `ConstantBuffer`
                 write!(self.out, "ConstantBuffer<")?;
-                "b"
+                Some(RegisterClass::ConstantBuffer)
             }
         };
 
@@ -738,6 +1032,12 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                 .push_constants_target
                 .as_ref()
                 .expect("No bind target was defined for the push constants block");
+            self.resource_bindings.push(RegisterBinding {
+                global: handle,
+                class: RegisterClass::ConstantBuffer,
+                target: target.clone(),
+                count: Some(1),
+            });
             write!(self.out, ": register(b{}", target.register)?;
             if target.space != 0 {
                 write!(self.out, ", space{}", target.space)?;
@@ -748,17 +1048,37 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
         if let Some(ref binding) = global.binding {
             // this was already resolved earlier when we started evaluating an entry point.
             let bt = self.options.resolve_resource_binding(binding).unwrap();
+            let class = register_class.expect("bound global without a register class");
 
             // need to write the binding array size if the type was emitted with `write_type`
-            if let TypeInner::BindingArray { base, size, .. } = module.types[global.ty].inner {
-                if let Some(overridden_size) = bt.binding_array_size {
-                    write!(self.out, "[{overridden_size}]")?;
+            let count =
+                if let TypeInner::BindingArray { base, size, .. } = module.types[global.ty].inner {
+                    if let Some(overridden_size) = bt.binding_array_size {
+                        write!(self.out, "[{overridden_size}]")?;
+                        Some(overridden_size)
+                    } else {
+                        self.write_array_size(module, base, size)?;
+                        match size {
+                            crate::ArraySize::Constant(size) => Some(size.get()),
+                            crate::ArraySize::Pending(_) => {
+                                return Err(Error::Unimplemented(
+                                    "binding arrays sized by an override".to_string(),
+                                ))
+                            }
+                            crate::ArraySize::Dynamic => None,
+                        }
+                    }
                 } else {
-                    self.write_array_size(module, base, size)?;
-                }
-            }
+                    Some(1)
+                };
 
-            write!(self.out, " : register({}{}", register_ty, bt.register)?;
+            self.resource_bindings.push(RegisterBinding {
+                global: handle,
+                class,
+                target: bt.clone(),
+                count,
+            });
+            write!(self.out, " : register({}{}", class.prefix(), bt.register)?;
             if bt.space != 0 {
                 write!(self.out, ", space{}", bt.space)?;
             }
@@ -838,6 +1158,11 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
             crate::ArraySize::Constant(size) => {
                 write!(self.out, "{size}")?;
             }
+            crate::ArraySize::Pending(_) => {
+                return Err(Error::Unimplemented(
+                    "arrays sized by an override".to_string(),
+                ))
+            }
             crate::ArraySize::Dynamic => unreachable!(),
         }
 
@@ -1355,8 +1680,12 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                 condition,
                 ref accept,
                 ref reject,
+                hint,
             } => {
                 write!(self.out, "{level}")?;
+                if let Some(hint) = hint {
+                    write!(self.out, "{} ", hint.to_hlsl_str())?;
+                }
                 write!(self.out, "if (")?;
                 self.write_expr(module, condition, func_ctx)?;
                 writeln!(self.out, ") {{")?;
@@ -1382,6 +1711,12 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
             }
             // TODO: copy-paste from glsl-out
             Statement::Kill => writeln!(self.out, "{level}discard;")?,
+            Statement::DebugMarker(ref text) => {
+                for line in back::c_comment_lines(text) {
+                    let space = if line.is_empty() { "" } else { " " };
+                    writeln!(self.out, "{level}//{space}{line}")?;
+                }
+            }
             Statement::Return { value: None } => {
                 writeln!(self.out, "{level}return;")?;
             }
@@ -1421,7 +1756,21 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                                     write!(self.out, ", ")?;
                                 }
                                 let member_name = &self.names[&NameKey::StructMember(ty, m.index)];
-                                write!(self.out, "{variable_name}.{member_name}")?;
+                                let value = format!("{variable_name}.{member_name}");
+                                match m.clip_distance_count(module) {
+                                    Some(count) => {
+                                        write_clip_distances(
+                                            &mut self.out,
+                                            &value,
+                                            0..count.min(4),
+                                        )?;
+                                        if count > 4 {
+                                            write!(self.out, ", ")?;
+                                            write_clip_distances(&mut self.out, &value, 4..count)?;
+                                        }
+                                    }
+                                    None => write!(self.out, "{value}")?,
+                                }
                             }
                             writeln!(self.out, " }};")?;
                             final_name
@@ -1437,7 +1786,16 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
             }
             Statement::Store { pointer, value } => {
                 let ty_inner = func_ctx.resolve_type(pointer, &module.types);
-                if let Some(crate::AddressSpace::Storage { .. }) = ty_inner.pointer_space() {
+                if let Some(scalar) = func_ctx.resolve_atomic_pointer(pointer, &module.types) {
+                    // HLSL has no atomic store, so exchange and discard the
+                    // original value instead.
+                    let old_name = self.namer.call("_atomic_old");
+                    write!(self.out, "{level}{} {old_name}; ", scalar.to_hlsl_str()?)?;
+                    self.write_interlocked_call(module, pointer, "Exchange", func_ctx)?;
+                    write!(self.out, ", ")?;
+                    self.write_expr(module, value, func_ctx)?;
+                    writeln!(self.out, ", {old_name});")?;
+                } else if let Some(crate::AddressSpace::Storage { .. }) = ty_inner.pointer_space() {
                     let var_handle = self.fill_access_chain(module, pointer, func_ctx)?;
                     self.write_storage_store(
                         module,
@@ -1742,12 +2100,17 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                 ref body,
                 ref continuing,
                 break_if,
+                hint,
             } => {
                 let l2 = level.next();
+                let attribute = match hint {
+                    Some(hint) => format!("{} ", hint.to_hlsl_str()),
+                    None => String::new(),
+                };
                 if !continuing.is_empty() || break_if.is_some() {
                     let gate_name = self.namer.call("loop_init");
                     writeln!(self.out, "{level}bool {gate_name} = true;")?;
-                    writeln!(self.out, "{level}while(true) {{")?;
+                    writeln!(self.out, "{level}{attribute}while(true) {{")?;
                     writeln!(self.out, "{l2}if (!{gate_name}) {{")?;
                     let l3 = l2.next();
                     for sta in continuing.iter() {
@@ -1763,7 +2126,7 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                     writeln!(self.out, "{l2}}}")?;
                     writeln!(self.out, "{l2}{gate_name} = false;")?;
                 } else {
-                    writeln!(self.out, "{level}while(true) {{")?;
+                    writeln!(self.out, "{level}{attribute}while(true) {{")?;
                 }
 
                 for sta in body.iter() {
@@ -1776,6 +2139,9 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
             Statement::Barrier(barrier) => {
                 self.write_barrier(barrier, level)?;
             }
+            Statement::MeshFunction(_) => {
+                return Err(Error::Unimplemented("mesh shader outputs".to_string()));
+            }
             Statement::ImageStore {
                 image,
                 coordinate,
@@ -1786,20 +2152,44 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                 self.write_expr(module, image, func_ctx)?;
 
                 write!(self.out, "[")?;
-                if let Some(index) = array_index {
-                    // Array index accepted only for texture_storage_2d_array, so we can safety use int3(coordinate, array_index) here
-                    write!(self.out, "int3(")?;
-                    self.write_expr(module, coordinate, func_ctx)?;
-                    write!(self.out, ", ")?;
-                    self.write_expr(module, index, func_ctx)?;
-                    write!(self.out, ")")?;
+                self.write_texture_coordinates(
+                    "int",
+                    coordinate,
+                    array_index,
+                    None,
+                    None,
+                    module,
+                    func_ctx,
+                )?;
+                write!(self.out, "] = ")?;
+
+                // The IR value is always a four-component vector, but the
+                // UAV may be declared with fewer components.
+                let count = match *func_ctx.resolve_type(image, &module.types) {
+                    TypeInner::Image {
+                        class: crate::ImageClass::Storage { format, .. },
+                        ..
+                    } => format.hlsl_component_count(),
+                    _ => 4,
+                };
+                if count < 4 {
+                    // Parenthesize the value, unless it's a plain name, so
+                    // the swizzle applies to all of it, as with `(float4)0`.
+                    let named = self.named_expressions.contains_key(&value);
+                    if !named {
+                        write!(self.out, "(")?;
+                    }
+                    self.write_expr(module, value, func_ctx)?;
+                    if !named {
+                        write!(self.out, ")")?;
+                    }
+                    write!(self.out, ".")?;
+                    for &c in back::COMPONENTS[..count].iter() {
+                        self.out.write_char(c)?;
+                    }
                 } else {
-                    self.write_expr(module, coordinate, func_ctx)?;
+                    self.write_expr(module, value, func_ctx)?;
                 }
-                write!(self.out, "]")?;
-
-                write!(self.out, " = ")?;
-                self.write_expr(module, value, func_ctx)?;
                 writeln!(self.out, ";")?;
             }
             Statement::Call {
@@ -1837,6 +2227,26 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                 value,
                 result,
             } => {
+                let compare_expr = match *fun {
+                    crate::AtomicFunction::Exchange { compare: Some(cmp) } => Some(cmp),
+                    _ => None,
+                };
+                // The comparand is written twice below, so bake it first,
+                // unless it's a constant.
+                if let Some(cmp) = compare_expr {
+                    let is_constant = matches!(
+                        func_ctx.expressions[cmp],
+                        crate::Expression::Literal(_)
+                            | crate::Expression::Constant(_)
+                            | crate::Expression::ZeroValue(_)
+                    );
+                    if !is_constant && !self.named_expressions.contains_key(&cmp) {
+                        write!(self.out, "{level}")?;
+                        let name = format!("{}{}", back::BAKE_PREFIX, cmp.index());
+                        self.write_named_expr(module, cmp, name, cmp, func_ctx)?;
+                    }
+                }
+
                 write!(self.out, "{level}")?;
                 let res_name = format!("{}{}", back::BAKE_PREFIX, result.index());
                 match func_ctx.info[result].ty {
@@ -1846,50 +2256,32 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                     }
                 };
 
-                // Validation ensures that `pointer` has a `Pointer` type.
-                let pointer_space = func_ctx
-                    .resolve_type(pointer, &module.types)
-                    .pointer_space()
-                    .unwrap();
-
                 let fun_str = fun.to_hlsl_suffix();
                 write!(self.out, " {res_name}; ")?;
-                match pointer_space {
-                    crate::AddressSpace::WorkGroup => {
-                        write!(self.out, "Interlocked{fun_str}(")?;
-                        self.write_expr(module, pointer, func_ctx)?;
-                    }
-                    crate::AddressSpace::Storage { .. } => {
-                        let var_handle = self.fill_access_chain(module, pointer, func_ctx)?;
-                        // The call to `self.write_storage_address` wants
-                        // mutable access to all of `self`, so temporarily take
-                        // ownership of our reusable access chain buffer.
-                        let chain = mem::take(&mut self.temp_access_chain);
-                        let var_name = &self.names[&NameKey::GlobalVariable(var_handle)];
-                        write!(self.out, "{var_name}.Interlocked{fun_str}(")?;
-                        self.write_storage_address(module, &chain, func_ctx)?;
-                        self.temp_access_chain = chain;
-                    }
-                    ref other => {
-                        return Err(Error::Custom(format!(
-                            "invalid address space {other:?} for atomic statement"
-                        )))
-                    }
+                self.write_interlocked_call(module, pointer, fun_str, func_ctx)?;
+                if let Some(cmp) = compare_expr {
+                    write!(self.out, ", ")?;
+                    self.write_expr(module, cmp, func_ctx)?;
                 }
                 write!(self.out, ", ")?;
-                // handle the special cases
-                match *fun {
-                    crate::AtomicFunction::Subtract => {
-                        // we just wrote `InterlockedAdd`, so negate the argument
-                        write!(self.out, "-")?;
-                    }
-                    crate::AtomicFunction::Exchange { compare: Some(_) } => {
-                        return Err(Error::Unimplemented("atomic CompareExchange".to_string()));
-                    }
-                    _ => {}
+                if let crate::AtomicFunction::Subtract = *fun {
+                    // we just wrote `InterlockedAdd`, so negate the argument
+                    write!(self.out, "-")?;
                 }
                 self.write_expr(module, value, func_ctx)?;
-                writeln!(self.out, ", {res_name});")?;
+                if let Some(cmp) = compare_expr {
+                    // `InterlockedCompareExchange` only gives us the original
+                    // value, so fill in `exchanged` ourselves.
+                    writeln!(self.out, ", {res_name}.old_value);")?;
+                    write!(
+                        self.out,
+                        "{level}{res_name}.exchanged = ({res_name}.old_value == "
+                    )?;
+                    self.write_expr(module, cmp, func_ctx)?;
+                    writeln!(self.out, ");")?;
+                } else {
+                    writeln!(self.out, ", {res_name});")?;
+                }
                 self.named_expressions.insert(result, res_name);
             }
             Statement::WorkGroupUniformLoad { pointer, result } => {
@@ -2390,11 +2782,16 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                 write!(self.out, ".{base_str}{cmp_str}{component_str}{level_str}(")?;
                 self.write_expr(module, sampler, func_ctx)?;
                 write!(self.out, ", ")?;
+                let layers_of = match self.options.bounds_check_policies.image_load {
+                    crate::proc::BoundsCheckPolicy::Restrict => Some(image),
+                    _ => None,
+                };
                 self.write_texture_coordinates(
                     "float",
                     coordinate,
                     array_index,
                     None,
+                    layers_of,
                     module,
                     func_ctx,
                 )?;
@@ -2473,6 +2870,7 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                     coordinate,
                     array_index,
                     level,
+                    None,
                     module,
                     func_ctx,
                 )?;
@@ -2617,11 +3015,22 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
             } => {
                 use crate::MathFunction as Mf;
 
+                let (fun, arg1, arg2) = if fun == Mf::Clamp
+                    && self.options.fold_clamp_to_saturate
+                    && back::is_saturate_clamp(module, func_ctx.expressions, arg1, arg2)
+                {
+                    (Mf::Saturate, None, None)
+                } else {
+                    (fun, arg1, arg2)
+                };
+
                 enum Function {
+                    Abs,
                     Asincosh { is_sin: bool },
                     Atanh,
                     ExtractBits,
                     InsertBits,
+                    Dot4Packed { signed: bool },
                     Pack2x16float,
                     Pack2x16snorm,
                     Pack2x16unorm,
@@ -2632,6 +3041,7 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                     Unpack2x16unorm,
                     Unpack4x8snorm,
                     Unpack4x8unorm,
+                    QuantizeToF16,
                     Regular(&'static str),
                     MissingIntOverload(&'static str),
                     MissingIntReturnType(&'static str),
@@ -2641,7 +3051,7 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
 
                 let fun = match fun {
                     // comparison
-                    Mf::Abs => Function::Regular("abs"),
+                    Mf::Abs => Function::Abs,
                     Mf::Min => Function::Regular("min"),
                     Mf::Max => Function::Regular("max"),
                     Mf::Clamp => Function::Regular("clamp"),
@@ -2665,6 +3075,8 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                     // decomposition
                     Mf::Ceil => Function::Regular("ceil"),
                     Mf::Floor => Function::Regular("floor"),
+                    // Unlike in C, HLSL's `round` rounds halfway cases to
+                    // the nearest even integer, as required by our IR
                     Mf::Round => Function::Regular("round"),
                     Mf::Fract => Function::Regular("frac"),
                     Mf::Trunc => Function::Regular("trunc"),
@@ -2698,6 +3110,7 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                     //Mf::Inverse =>,
                     Mf::Transpose => Function::Regular("transpose"),
                     Mf::Determinant => Function::Regular("determinant"),
+                    Mf::QuantizeToF16 => Function::QuantizeToF16,
                     // bits
                     Mf::CountTrailingZeros => Function::CountTrailingZeros,
                     Mf::CountLeadingZeros => Function::CountLeadingZeros,
@@ -2707,6 +3120,9 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                     Mf::FindMsb => Function::MissingIntReturnType("firstbithigh"),
                     Mf::ExtractBits => Function::ExtractBits,
                     Mf::InsertBits => Function::InsertBits,
+                    // geometry
+                    Mf::Dot4I8Packed => Function::Dot4Packed { signed: true },
+                    Mf::Dot4U8Packed => Function::Dot4Packed { signed: false },
                     // Data Packing
                     Mf::Pack2x16float => Function::Pack2x16float,
                     Mf::Pack2x16snorm => Function::Pack2x16snorm,
@@ -2723,6 +3139,18 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                 };
 
                 match fun {
+                    Function::Abs => {
+                        let scalar_kind = func_ctx.resolve_type(arg, &module.types).scalar_kind();
+                        // HLSL has no unsigned overload of `abs`, and it is
+                        // the identity on unsigned integers anyway.
+                        if let Some(ScalarKind::Uint) = scalar_kind {
+                            write!(self.out, "(")?;
+                        } else {
+                            write!(self.out, "abs(")?;
+                        }
+                        self.write_expr(module, arg, func_ctx)?;
+                        write!(self.out, ")")?;
+                    }
                     Function::Asincosh { is_sin } => {
                         write!(self.out, "log(")?;
                         self.write_expr(module, arg, func_ctx)?;
@@ -2813,6 +3241,51 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                         self.write_expr(module, arg, func_ctx)?;
                         write!(self.out, "[1]) << 16)")?;
                     }
+                    Function::Dot4Packed { signed } => {
+                        if self.options.shader_model >= ShaderModel::V6_4 {
+                            let (fun_name, zero) = match signed {
+                                true => ("dot4add_i8packed", "0"),
+                                false => ("dot4add_u8packed", "0u"),
+                            };
+                            write!(self.out, "{fun_name}(")?;
+                            self.write_expr(module, arg, func_ctx)?;
+                            write!(self.out, ", ")?;
+                            self.write_expr(module, arg1.unwrap(), func_ctx)?;
+                            write!(self.out, ", {zero})")?;
+                        } else {
+                            // Spread the bytes of each operand into a vector,
+                            // sign-extending them with an arithmetic shift
+                            // in the signed case, as `Unpack4x8snorm` does.
+                            write!(self.out, "dot(")?;
+                            for (index, operand) in [arg, arg1.unwrap()].into_iter().enumerate() {
+                                if index != 0 {
+                                    write!(self.out, ", ")?;
+                                }
+                                if signed {
+                                    write!(self.out, "int4(")?;
+                                    self.write_expr(module, operand, func_ctx)?;
+                                    write!(self.out, " << 24, ")?;
+                                    self.write_expr(module, operand, func_ctx)?;
+                                    write!(self.out, " << 16, ")?;
+                                    self.write_expr(module, operand, func_ctx)?;
+                                    write!(self.out, " << 8, ")?;
+                                    self.write_expr(module, operand, func_ctx)?;
+                                    write!(self.out, ") >> 24")?;
+                                } else {
+                                    write!(self.out, "uint4(")?;
+                                    self.write_expr(module, operand, func_ctx)?;
+                                    write!(self.out, ", ")?;
+                                    self.write_expr(module, operand, func_ctx)?;
+                                    write!(self.out, " >> 8, ")?;
+                                    self.write_expr(module, operand, func_ctx)?;
+                                    write!(self.out, " >> 16, ")?;
+                                    self.write_expr(module, operand, func_ctx)?;
+                                    write!(self.out, " >> 24) & 0xFF")?;
+                                }
+                            }
+                            write!(self.out, ")")?;
+                        }
+                    }
                     Function::Pack2x16snorm => {
                         let scale = 32767;
 
@@ -2927,6 +3400,16 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                         self.write_expr(module, arg, func_ctx)?;
                         write!(self.out, " >> 24) / {scale}.0)")?;
                     }
+                    Function::QuantizeToF16 => {
+                        // `f32tof16` works componentwise, and stores the
+                        // `half` in the low bits of a `uint`. Out-of-range
+                        // values become infinities, but unlike WGSL, whether
+                        // `f16` denormals are flushed to zero is up to the
+                        // driver.
+                        write!(self.out, "f16tof32(f32tof16(")?;
+                        self.write_expr(module, arg, func_ctx)?;
+                        write!(self.out, "))")?;
+                    }
                     Function::Regular(fun_name) => {
                         write!(self.out, "{fun_name}(")?;
                         self.write_expr(module, arg, func_ctx)?;
@@ -3199,14 +3682,69 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
         if let TypeInner::Array { base, size, .. } = *resolved {
             self.write_array_size(module, base, size)?;
         }
-        write!(self.out, " = ")?;
-        self.write_expr(module, handle, ctx)?;
-        writeln!(self.out, ";")?;
+        match ctx.expressions[handle] {
+            // HLSL has no atomic load, so or in zero instead.
+            crate::Expression::Load { pointer }
+                if ctx.resolve_atomic_pointer(pointer, &module.types).is_some() =>
+            {
+                write!(self.out, "; ")?;
+                self.write_interlocked_call(module, pointer, "Or", ctx)?;
+                writeln!(self.out, ", 0, {name});")?;
+            }
+            _ => {
+                write!(self.out, " = ")?;
+                self.write_expr(module, handle, ctx)?;
+                writeln!(self.out, ";")?;
+            }
+        }
         self.named_expressions.insert(named, name);
 
         Ok(())
     }
 
+    /// Write the start of an `Interlocked*` call on the atomic at `pointer`,
+    /// up to and including the destination argument.
+    fn write_interlocked_call(
+        &mut self,
+        module: &Module,
+        pointer: Handle<crate::Expression>,
+        fun_str: &str,
+        func_ctx: &back::FunctionCtx,
+    ) -> BackendResult {
+        // Validation ensures that `pointer` has a `Pointer` type.
+        let pointer_space = func_ctx
+            .resolve_type(pointer, &module.types)
+            .pointer_space()
+            .unwrap();
+
+        match pointer_space {
+            crate::AddressSpace::WorkGroup => {
+                write!(self.out, "Interlocked{fun_str}(")?;
+                self.write_expr(module, pointer, func_ctx)?;
+            }
+            crate::AddressSpace::Storage { .. } => {
+                // Atomics on storage buffers go through the
+                // `RWByteAddressBuffer` methods, since an element of a
+                // `ByteAddressBuffer` isn't an l-value HLSL accepts.
+                let var_handle = self.fill_access_chain(module, pointer, func_ctx)?;
+                // The call to `self.write_storage_address` wants
+                // mutable access to all of `self`, so temporarily take
+                // ownership of our reusable access chain buffer.
+                let chain = mem::take(&mut self.temp_access_chain);
+                let var_name = &self.names[&NameKey::GlobalVariable(var_handle)];
+                write!(self.out, "{var_name}.Interlocked{fun_str}(")?;
+                self.write_storage_address(module, &chain, func_ctx)?;
+                self.temp_access_chain = chain;
+            }
+            ref other => {
+                return Err(Error::Custom(format!(
+                    "invalid address space {other:?} for atomic statement"
+                )))
+            }
+        }
+        Ok(())
+    }
+
     /// Helper function that write default zero initialization
     fn write_default_init(&mut self, module: &Module, ty: Handle<crate::Type>) -> BackendResult {
         write!(self.out, "(")?;
@@ -3364,3 +3902,21 @@ fn get_inner_matrix_of_global_uniform(
     }
     None
 }
+
+/// Returns true if any argument of `function`, or any member of a struct
+/// argument, is bound to `built_in`.
+fn uses_builtin(module: &Module, function: &crate::Function, built_in: crate::BuiltIn) -> bool {
+    let is_built_in = |binding: Option<&crate::Binding>| match binding {
+        Some(&crate::Binding::BuiltIn(b)) => b == built_in,
+        _ => false,
+    };
+    function.arguments.iter().any(|arg| {
+        is_built_in(arg.binding.as_ref())
+            || match module.types[arg.ty].inner {
+                TypeInner::Struct { ref members, .. } => members
+                    .iter()
+                    .any(|member| is_built_in(member.binding.as_ref())),
+                _ => false,
+            }
+    })
+}
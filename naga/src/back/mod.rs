@@ -94,6 +94,24 @@ impl FunctionCtx<'_> {
         self.info[handle].ty.inner_with(types)
     }
 
+    /// If `pointer` points to an atomic, return the atomic's scalar type.
+    ///
+    /// Loads and stores through such pointers must be written as atomic
+    /// operations.
+    fn resolve_atomic_pointer(
+        &self,
+        pointer: crate::Handle<crate::Expression>,
+        types: &crate::UniqueArena<crate::Type>,
+    ) -> Option<crate::Scalar> {
+        match *self.resolve_type(pointer, types) {
+            crate::TypeInner::Pointer { base, .. } => match types[base].inner {
+                crate::TypeInner::Atomic(scalar) => Some(scalar),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
     /// Helper method that generates a [`NameKey`](crate::proc::NameKey) for a local in the current function
     const fn name_key(&self, local: crate::Handle<crate::LocalVariable>) -> crate::proc::NameKey {
         match self.ty {
@@ -218,6 +236,145 @@ const fn vector_size_str(size: crate::VectorSize) -> &'static str {
     }
 }
 
+/// Returns true if `low` and `high`, the bounds of a [`Clamp`], are the
+/// constants `0.0` and `1.0`, so the clamp could be written as `saturate`.
+///
+/// [`Clamp`]: crate::MathFunction::Clamp
+/// # Notes
+/// Used by `msl-out`, `hlsl-out`.
+fn is_saturate_clamp(
+    module: &crate::Module,
+    expressions: &crate::Arena<crate::Expression>,
+    low: Option<crate::Handle<crate::Expression>>,
+    high: Option<crate::Handle<crate::Expression>>,
+) -> bool {
+    match (low, high) {
+        (Some(low), Some(high)) => {
+            float_splat_value(module, expressions, low) == Some(0.0)
+                && float_splat_value(module, expressions, high) == Some(1.0)
+        }
+        _ => false,
+    }
+}
+
+/// Returns the value of `expr` if it is a floating-point constant scalar,
+/// or a vector with the same constant in every component.
+fn float_splat_value(
+    module: &crate::Module,
+    expressions: &crate::Arena<crate::Expression>,
+    expr: crate::Handle<crate::Expression>,
+) -> Option<f64> {
+    match expressions[expr] {
+        crate::Expression::Literal(crate::Literal::F32(value)) => Some(value as f64),
+        crate::Expression::Literal(crate::Literal::F64(value)) => Some(value),
+        crate::Expression::Splat { value, .. } => float_splat_value(module, expressions, value),
+        crate::Expression::Compose { ref components, .. } => {
+            let (&first, rest) = components.split_first()?;
+            let value = float_splat_value(module, expressions, first)?;
+            rest.iter()
+                .all(|&component| float_splat_value(module, expressions, component) == Some(value))
+                .then_some(value)
+        }
+        crate::Expression::Constant(handle) => {
+            let constant = &module.constants[handle];
+            match constant.r#override {
+                crate::Override::None => {
+                    float_splat_value(module, &module.const_expressions, constant.init)
+                }
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Split the text of a [`DebugMarker`] into the lines of a line comment.
+///
+/// This splits at every character WGSL treats as a line break, not just
+/// `\n`, so a lone `\r` can't end the comment early. An empty marker is a
+/// single empty line, so it isn't lost.
+///
+/// [`DebugMarker`]: crate::Statement::DebugMarker
+/// # Notes
+/// Used by `wgsl-out`, and through [`c_comment_lines`] by `glsl-out`,
+/// `msl-out`, `hlsl-out`.
+fn comment_lines(text: &str) -> impl Iterator<Item = &str> {
+    text.split('\n').flat_map(|line| {
+        line.strip_suffix('\r')
+            .unwrap_or(line)
+            .split(['\r', '\u{b}', '\u{c}', '\u{85}', '\u{2028}', '\u{2029}'])
+    })
+}
+
+/// Like [`comment_lines`], but safe to write after `//` in C-like languages.
+///
+/// A backslash at the end of a line splices the next line of output into
+/// the comment, so trailing backslashes are removed, along with any
+/// whitespace around them.
+fn c_comment_lines(text: &str) -> impl Iterator<Item = &str> {
+    comment_lines(text).map(|line| line.trim_end_matches(|c: char| c == '\\' || c.is_whitespace()))
+}
+
+/// The stages and names of a module's entry points, for error messages.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EntryPointNames(pub Vec<(crate::ShaderStage, String)>);
+
+impl EntryPointNames {
+    pub fn new(module: &crate::Module) -> Self {
+        Self(
+            module
+                .entry_points
+                .iter()
+                .map(|ep| (ep.stage, ep.name.clone()))
+                .collect(),
+        )
+    }
+}
+
+impl std::fmt::Display for EntryPointNames {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.0.is_empty() {
+            return write!(f, "none");
+        }
+        for (i, &(stage, ref name)) in self.0.iter().enumerate() {
+            if i != 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "`{name}` ({stage:?})")?;
+        }
+        Ok(())
+    }
+}
+
+/// Why [`find_entry_point`] failed.
+enum EntryPointLookupError {
+    NotFound,
+    Ambiguous,
+}
+
+/// Return the index of `module`'s entry point for `stage` named `name`.
+///
+/// Validation rejects modules with more than one entry point for the same
+/// stage and name, but a module could have changed since it was validated.
+/// Rather than silently picking the first match, report that as ambiguous.
+fn find_entry_point(
+    module: &crate::Module,
+    stage: crate::ShaderStage,
+    name: &str,
+) -> Result<usize, EntryPointLookupError> {
+    let mut matches = module
+        .entry_points
+        .iter()
+        .enumerate()
+        .filter(|&(_, ep)| ep.stage == stage && ep.name == name)
+        .map(|(index, _)| index);
+    match (matches.next(), matches.next()) {
+        (Some(index), None) => Ok(index),
+        (Some(_), Some(_)) => Err(EntryPointLookupError::Ambiguous),
+        (None, _) => Err(EntryPointLookupError::NotFound),
+    }
+}
+
 impl crate::TypeInner {
     const fn is_handle(&self) -> bool {
         match *self {
@@ -33,7 +33,7 @@ mod keywords;
 pub mod sampler;
 mod writer;
 
-pub use writer::Writer;
+pub use writer::{LineObserver, Writer};
 
 pub type Slot = u8;
 pub type InlineSamplerIndex = u8;
@@ -75,12 +75,23 @@ pub struct EntryPointResources {
     /// one for the size of each bound buffer that contains a runtime array,
     /// in order of [`crate::GlobalVariable`] declarations.
     pub sizes_buffer: Option<Slot>,
+
+    /// Override the entry point's [`EarlyDepthTest::conservative`] mode when
+    /// choosing the `[[depth(...)]]` attribute of its `FragDepth` output.
+    ///
+    /// [`EarlyDepthTest::conservative`]: crate::EarlyDepthTest::conservative
+    pub conservative_depth: Option<crate::ConservativeDepth>,
 }
 
 pub type EntryPointResourceMap = std::collections::BTreeMap<String, EntryPointResources>;
 
+/// Samplers to write as `constexpr` declarations, indexed by their binding.
+pub type ConstexprSamplerMap =
+    std::collections::BTreeMap<crate::ResourceBinding, sampler::InlineSampler>;
+
 enum ResolvedBinding {
     BuiltIn(crate::BuiltIn),
+    FragDepth(Option<crate::ConservativeDepth>),
     Attribute(u32),
     Color {
         location: u32,
@@ -92,6 +103,8 @@ enum ResolvedBinding {
         interpolation: Option<ResolvedInterpolation>,
     },
     Resource(BindTarget),
+    /// A sampler in [`Options::constexpr_samplers`].
+    ConstexprSampler(crate::ResourceBinding),
 }
 
 #[derive(Copy, Clone)]
@@ -127,22 +140,34 @@ pub enum Error {
     UnsupportedBuiltIn(crate::BuiltIn),
     #[error("capability {0:?} is not supported")]
     CapabilityNotSupported(crate::valid::Capabilities),
-    #[error("attribute '{0}' is not supported for target MSL version")]
-    UnsupportedAttribute(String),
-    #[error("function '{0}' is not supported for target MSL version")]
-    UnsupportedFunction(String),
-    #[error("can not use writeable storage buffers in fragment stage prior to MSL 1.2")]
-    UnsupportedWriteableStorageBuffer,
-    #[error("can not use writeable storage textures in {0:?} stage prior to MSL 1.2")]
-    UnsupportedWriteableStorageTexture(crate::ShaderStage),
-    #[error("can not use read-write storage textures prior to MSL 1.2")]
-    UnsupportedRWStorageTexture,
-    #[error("array of '{0}' is not supported for target MSL version")]
+    #[error(
+        "{feature} requires MSL {}.{} or later, but the target is MSL {}.{}",
+        .needed.0, .needed.1, .requested.0, .requested.1
+    )]
+    UnsupportedMslVersion {
+        feature: String,
+        needed: (u8, u8),
+        requested: (u8, u8),
+    },
+    #[error("array of '{0}' is not supported")]
     UnsupportedArrayOf(String),
     #[error("array of type '{0:?}' is not supported")]
     UnsupportedArrayOfType(Handle<crate::Type>),
-    #[error("ray tracing is not supported prior to MSL 2.3")]
-    UnsupportedRayTracing,
+    #[error("constexpr sampler for {0:?} is not supported: {1}")]
+    UnsupportedConstexprSampler(crate::ResourceBinding, &'static str),
+}
+
+/// Return an error if `requested`, the MSL version we're targeting, is older
+/// than `needed`, the first version to support `feature`.
+fn require_lang_version(requested: (u8, u8), needed: (u8, u8), feature: &str) -> Result<(), Error> {
+    if requested < needed {
+        return Err(Error::UnsupportedMslVersion {
+            feature: feature.to_string(),
+            needed,
+            requested,
+        });
+    }
+    Ok(())
 }
 
 #[derive(Clone, Debug, PartialEq, thiserror::Error)]
@@ -157,6 +182,8 @@ pub enum EntryPointError {
     MissingPushConstants,
     #[error("mapping for sizes buffer is missing")]
     MissingSizesBuffer,
+    #[error("{0:?} shaders aren't supported")]
+    UnsupportedStage(crate::ShaderStage),
 }
 
 /// Points in the MSL code where we might emit a pipeline input or output.
@@ -195,6 +222,22 @@ pub struct Options {
     pub per_entry_point_map: EntryPointResourceMap,
     /// Samplers to be inlined into the code.
     pub inline_samplers: Vec<sampler::InlineSampler>,
+    /// Samplers known to be immutable, to be written as `constexpr`
+    /// declarations in the entry points using them instead of taking a
+    /// `[[sampler(n)]]` argument.
+    ///
+    /// Unlike [`inline_samplers`], these apply to every entry point, and take
+    /// precedence over [`per_entry_point_map`]. The bindings that were
+    /// elided are reported in
+    /// [`TranslationInfo::entry_point_constexpr_samplers`].
+    ///
+    /// Metal can't express anisotropic filtering or LOD clamping this way,
+    /// so samplers using either are an error.
+    ///
+    /// [`inline_samplers`]: Options::inline_samplers
+    /// [`per_entry_point_map`]: Options::per_entry_point_map
+    #[cfg_attr(feature = "deserialize", serde(default))]
+    pub constexpr_samplers: ConstexprSamplerMap,
     /// Make it possible to link different stages via SPIRV-Cross.
     pub spirv_cross_compatibility: bool,
     /// Don't panic on missing bindings, instead generate invalid MSL.
@@ -204,6 +247,60 @@ pub struct Options {
     pub bounds_check_policies: index::BoundsCheckPolicies,
     /// Should workgroup variables be zero initialized (by polyfilling)?
     pub zero_initialize_workgroup_memory: bool,
+    /// Clamp `BuiltIn::FragDepth` output between 0 and 1.
+    ///
+    /// Metal doesn't clamp the depth written by a fragment shader, while
+    /// Vulkan clamps it to the viewport's depth range.
+    #[cfg_attr(feature = "deserialize", serde(default))]
+    pub clamp_frag_depth: bool,
+    /// Write `clamp(x, 0.0, 1.0)` as `saturate(x)`, which some drivers
+    /// turn into a free modifier on the instruction producing `x`.
+    #[cfg_attr(feature = "deserialize", serde(default))]
+    pub fold_clamp_to_saturate: bool,
+    /// Emit every loop as a `for` loop with an induction counter capped at
+    /// [`max_loop_iterations`], instead of `while(true)`.
+    ///
+    /// The Metal compiler can hang, or take minutes, on loops it can't prove
+    /// are bounded. The original break logic is left intact, so loops that
+    /// terminate within the cap behave exactly as before.
+    ///
+    /// [`max_loop_iterations`]: Options::max_loop_iterations
+    #[cfg_attr(feature = "deserialize", serde(default))]
+    pub force_bounded_loops: bool,
+    /// The iteration cap used by [`force_bounded_loops`].
+    ///
+    /// [`force_bounded_loops`]: Options::force_bounded_loops
+    #[cfg_attr(
+        feature = "deserialize",
+        serde(default = "Options::default_max_loop_iterations")
+    )]
+    pub max_loop_iterations: u32,
+    /// Write code that stays correct when the Metal compiler's fast math,
+    /// which is on by default, is enabled.
+    ///
+    /// Fast math assumes that floating-point values are never NaN or
+    /// infinite, and deletes `isnan` and `isinf` checks. With this option,
+    /// they are written as tests of the value's bits instead.
+    ///
+    /// Position outputs are marked `[[invariant]]` when the IR asks for
+    /// invariance either way, but Metal only honors that with fast math
+    /// disabled. [`TranslationInfo::entry_point_needs_precise`] reports which
+    /// entry points still need it.
+    #[cfg_attr(feature = "deserialize", serde(default))]
+    pub force_precise: bool,
+    /// Whether the target GPU family supports `[[primitive_id]]` fragment
+    /// inputs, which carry [`BuiltIn::PrimitiveIndex`].
+    ///
+    /// Besides MSL 2.2, this needs a Mac2 family GPU on macOS, or an Apple7
+    /// family GPU and MSL 2.3 on iOS. Writing a shader that uses the
+    /// built-in fails if this is `false`.
+    ///
+    /// [`BuiltIn::PrimitiveIndex`]: crate::BuiltIn::PrimitiveIndex
+    #[cfg_attr(
+        feature = "deserialize",
+        serde(default = "Options::default_supports_primitive_id")
+    )]
+    pub supports_primitive_id: bool,
 }
 
 impl Default for Options {
@@ -212,10 +309,17 @@ impl Default for Options {
             lang_version: (1, 0),
             per_entry_point_map: EntryPointResourceMap::default(),
             inline_samplers: Vec::new(),
+            constexpr_samplers: ConstexprSamplerMap::default(),
             spirv_cross_compatibility: false,
             fake_missing_bindings: true,
             bounds_check_policies: index::BoundsCheckPolicies::default(),
             zero_initialize_workgroup_memory: true,
+            clamp_frag_depth: false,
+            fold_clamp_to_saturate: false,
+            force_bounded_loops: false,
+            max_loop_iterations: Self::default_max_loop_iterations(),
+            force_precise: false,
+            supports_primitive_id: Self::default_supports_primitive_id(),
         }
     }
 }
@@ -235,6 +339,14 @@ pub struct PipelineOptions {
 }
 
 impl Options {
+    const fn default_max_loop_iterations() -> u32 {
+        1 << 31
+    }
+
+    const fn default_supports_primitive_id() -> bool {
+        true
+    }
+
     fn resolve_local_binding(
         &self,
         binding: &crate::Binding,
@@ -244,8 +356,8 @@ impl Options {
             crate::Binding::BuiltIn(mut built_in) => {
                 match built_in {
                     crate::BuiltIn::Position { ref mut invariant } => {
-                        if *invariant && self.lang_version < (2, 1) {
-                            return Err(Error::UnsupportedAttribute("invariant".to_string()));
+                        if *invariant {
+                            require_lang_version(self.lang_version, (2, 1), "`[[invariant]]`")?;
                         }
 
                         // The 'invariant' attribute may only appear on vertex
@@ -254,16 +366,19 @@ impl Options {
                             *invariant = false;
                         }
                     }
-                    crate::BuiltIn::BaseInstance if self.lang_version < (1, 2) => {
-                        return Err(Error::UnsupportedAttribute("base_instance".to_string()));
+                    crate::BuiltIn::BaseInstance => {
+                        require_lang_version(self.lang_version, (1, 2), "`[[base_instance]]`")?;
                     }
-                    crate::BuiltIn::InstanceIndex if self.lang_version < (1, 2) => {
-                        return Err(Error::UnsupportedAttribute("instance_id".to_string()));
+                    crate::BuiltIn::InstanceIndex => {
+                        require_lang_version(self.lang_version, (1, 2), "`[[instance_id]]`")?;
                     }
                     // macOS: Since Metal 2.2
                     // iOS: Since Metal 2.3 (check depends on https://github.com/gfx-rs/naga/issues/2164)
-                    crate::BuiltIn::PrimitiveIndex if self.lang_version < (2, 2) => {
-                        return Err(Error::UnsupportedAttribute("primitive_id".to_string()));
+                    crate::BuiltIn::PrimitiveIndex => {
+                        require_lang_version(self.lang_version, (2, 2), "`[[primitive_id]]`")?;
+                        if !self.supports_primitive_id {
+                            return Err(Error::UnsupportedBuiltIn(built_in));
+                        }
                     }
                     _ => {}
                 }
@@ -278,10 +393,8 @@ impl Options {
             } => match mode {
                 LocationMode::VertexInput => Ok(ResolvedBinding::Attribute(location)),
                 LocationMode::FragmentOutput => {
-                    if second_blend_source && self.lang_version < (1, 2) {
-                        return Err(Error::UnsupportedAttribute(
-                            "second_blend_source".to_string(),
-                        ));
+                    if second_blend_source {
+                        require_lang_version(self.lang_version, (1, 2), "dual-source blending")?;
                     }
                     Ok(ResolvedBinding::Color {
                         location,
@@ -289,6 +402,13 @@ impl Options {
                     })
                 }
                 LocationMode::VertexOutput | LocationMode::FragmentInput => {
+                    if sampling == Some(crate::Sampling::Sample) {
+                        require_lang_version(
+                            self.lang_version,
+                            (1, 1),
+                            "per-sample interpolation",
+                        )?;
+                    }
                     Ok(ResolvedBinding::User {
                         prefix: if self.spirv_cross_compatibility {
                             "locn"
@@ -321,6 +441,16 @@ impl Options {
         self.per_entry_point_map.get(&ep.name)
     }
 
+    /// Return the conservative depth mode to use for `ep`'s `FragDepth`
+    /// output, preferring the override in [`per_entry_point_map`].
+    ///
+    /// [`per_entry_point_map`]: Options::per_entry_point_map
+    fn get_conservative_depth(&self, ep: &crate::EntryPoint) -> Option<crate::ConservativeDepth> {
+        self.get_entry_point_resources(ep)
+            .and_then(|res| res.conservative_depth)
+            .or_else(|| ep.early_depth_test.and_then(|test| test.conservative))
+    }
+
     fn get_resource_binding_target(
         &self,
         ep: &crate::EntryPoint,
@@ -335,6 +465,9 @@ impl Options {
         ep: &crate::EntryPoint,
         res_binding: &crate::ResourceBinding,
     ) -> Result<ResolvedBinding, EntryPointError> {
+        if self.constexpr_samplers.contains_key(res_binding) {
+            return Ok(ResolvedBinding::ConstexprSampler(res_binding.clone()));
+        }
         let target = self.get_resource_binding_target(ep, res_binding);
         match target {
             Some(target) => Ok(ResolvedBinding::Resource(target.clone())),
@@ -397,6 +530,7 @@ impl ResolvedBinding {
                 sampler: Some(BindSamplerTarget::Inline(index)),
                 ..
             }) => Some(&options.inline_samplers[index as usize]),
+            Self::ConstexprSampler(ref binding) => options.constexpr_samplers.get(binding),
             _ => None,
         }
     }
@@ -437,12 +571,26 @@ impl ResolvedBinding {
                     Bi::WorkGroupId => "threadgroup_position_in_grid",
                     Bi::WorkGroupSize => "dispatch_threads_per_threadgroup",
                     Bi::NumWorkGroups => "threadgroups_per_grid",
-                    Bi::CullDistance | Bi::ViewIndex => {
-                        return Err(Error::UnsupportedBuiltIn(built_in))
-                    }
+                    Bi::CullDistance
+                    | Bi::ViewIndex
+                    | Bi::MeshTaskSize
+                    | Bi::TriangleIndices
+                    | Bi::LineIndices
+                    | Bi::PointIndex
+                    | Bi::CullPrimitive => return Err(Error::UnsupportedBuiltIn(built_in)),
                 };
                 write!(out, "{name}")?;
             }
+            Self::FragDepth(conservative) => {
+                use crate::ConservativeDepth as Cd;
+                let mode = match conservative {
+                    Some(Cd::GreaterEqual) => "greater",
+                    Some(Cd::LessEqual) => "less",
+                    // Metal has no `unchanged` mode.
+                    Some(Cd::Unchanged) | None => "any",
+                };
+                write!(out, "depth({mode})")?;
+            }
             Self::Attribute(index) => write!(out, "attribute({index})")?,
             Self::Color {
                 location,
@@ -476,6 +624,7 @@ impl ResolvedBinding {
                     return Err(Error::UnimplementedBindTarget(target.clone()));
                 }
             }
+            Self::ConstexprSampler(_) => unreachable!(),
         }
         write!(out, "]]")?;
         Ok(())
@@ -521,6 +670,17 @@ pub struct TranslationInfo {
     ///
     ///Note: Some entry points may fail translation because of missing bindings.
     pub entry_point_names: Vec<Result<String, EntryPointError>>,
+    /// For each entry point, whether its library must be compiled with fast
+    /// math disabled to behave as the IR requires.
+    ///
+    /// This is the case for vertex shaders with an `[[invariant]]` position
+    /// output, and, unless [`Options::force_precise`] is set, for entry points
+    /// that check for NaNs or infinities.
+    pub entry_point_needs_precise: Vec<bool>,
+    /// For each entry point, the bindings of the samplers it uses that were
+    /// written as `constexpr` declarations, per
+    /// [`Options::constexpr_samplers`]. These don't need to be bound.
+    pub entry_point_constexpr_samplers: Vec<Vec<crate::ResourceBinding>>,
 }
 
 pub fn write_string(
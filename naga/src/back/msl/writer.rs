@@ -10,6 +10,7 @@ use bit_set::BitSet;
 use std::{
     fmt::{Display, Error as FmtError, Formatter, Write},
     iter,
+    ops::Range,
 };
 
 /// Shorthand result used internally by the backend
@@ -192,6 +193,7 @@ impl<'a> Display for TypeContext<'a> {
                         };
                         ("texture", "", format.into(), access)
                     }
+                    crate::ImageClass::External => unreachable!(),
                 };
                 let base_name = crate::Scalar { kind, width: 4 }.to_msl_name();
                 let array_str = if arrayed { "_array" } else { "" };
@@ -287,10 +289,18 @@ impl<'a> TypedGlobalVariable<'a> {
             }
             _ => ("", "", ""),
         };
+        // Metal has no coherent buffers before MSL 3.2, so `volatile` stands
+        // in for both decorations.
+        let volatile = if self.reference && !var.memory_decorations.is_empty() {
+            "volatile "
+        } else {
+            ""
+        };
 
         Ok(write!(
             out,
-            "{}{}{}{}{}{} {}",
+            "{}{}{}{}{}{}{} {}",
+            volatile,
             space,
             if space.is_empty() { "" } else { " " },
             ty_name,
@@ -302,8 +312,27 @@ impl<'a> TypedGlobalVariable<'a> {
     }
 }
 
+/// A callback told which lines of output each statement was written to.
+///
+/// See [`Writer::set_line_observer`].
+pub type LineObserver = Box<dyn FnMut(crate::Span, Range<usize>) + Send>;
+
+/// Wraps a [`Writer`]'s output, counting the lines written to it.
+struct LineCounter<W> {
+    inner: W,
+    /// The number of newlines written so far.
+    lines: usize,
+}
+
+impl<W: Write> Write for LineCounter<W> {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        self.lines += s.bytes().filter(|&byte| byte == b'\n').count();
+        self.inner.write_str(s)
+    }
+}
+
 pub struct Writer<W> {
-    out: W,
+    out: LineCounter<W>,
     names: FastHashMap<NameKey, String>,
     named_expressions: crate::NamedExpressions,
     /// Set of expressions that need to be baked to avoid unnecessary repetition in output
@@ -316,20 +345,33 @@ pub struct Writer<W> {
     /// Set of (struct type, struct field index) denoting which fields require
     /// padding inserted **before** them (i.e. between fields at index - 1 and index)
     struct_member_pads: FastHashSet<(Handle<crate::Type>, u32)>,
+    line_observer: Option<LineObserver>,
 }
 
 impl crate::Scalar {
     const fn to_msl_name(self) -> &'static str {
         use crate::ScalarKind as Sk;
         match self {
+            Self {
+                kind: Sk::Float,
+                width: 2,
+            } => "half",
             Self {
                 kind: Sk::Float,
                 width: _,
             } => "float",
+            Self {
+                kind: Sk::Sint,
+                width: 2,
+            } => "short",
             Self {
                 kind: Sk::Sint,
                 width: _,
             } => "int",
+            Self {
+                kind: Sk::Uint,
+                width: 2,
+            } => "ushort",
             Self {
                 kind: Sk::Uint,
                 width: _,
@@ -373,12 +415,123 @@ fn should_pack_struct_member(
     match *ty_inner {
         crate::TypeInner::Vector {
             size: crate::VectorSize::Tri,
-            scalar: scalar @ crate::Scalar { width: 4, .. },
+            scalar: scalar @ crate::Scalar { width: 2 | 4, .. },
         } if is_tight => Some(scalar),
         _ => None,
     }
 }
 
+/// Return the scalar type of the packed vector to use for the elements of
+/// an array of `base` with the given `stride`, if they need packing.
+///
+/// Metal's three-component vectors are as large as four-component ones, so an
+/// array of them whose stride is only three components wide must use packed
+/// elements instead.
+fn should_pack_array_element(
+    base: Handle<crate::Type>,
+    stride: u32,
+    module: &crate::Module,
+) -> Option<crate::Scalar> {
+    match module.types[base].inner {
+        crate::TypeInner::Vector {
+            size: crate::VectorSize::Tri,
+            scalar: scalar @ crate::Scalar { width: 2 | 4, .. },
+        } if stride == 3 * scalar.width as u32 => Some(scalar),
+        _ => None,
+    }
+}
+
+/// Return an error if the global of type `ty` at `binding` can't be written
+/// as a `constexpr` declaration of `sampler`.
+fn check_constexpr_sampler(
+    binding: &crate::ResourceBinding,
+    sampler: &sm::InlineSampler,
+    ty: &crate::TypeInner,
+) -> BackendResult {
+    let reason = if !matches!(*ty, crate::TypeInner::Sampler { .. }) {
+        "the global is not a sampler"
+    } else if matches!(sampler.max_anisotropy, Some(aniso) if aniso.get() > 1) {
+        "anisotropic filtering"
+    } else if sampler.lod_clamp.is_some() {
+        "LOD clamping"
+    } else {
+        return Ok(());
+    };
+    Err(Error::UnsupportedConstexprSampler(binding.clone(), reason))
+}
+
+/// Return true if `ep` is a vertex shader with an invariant position output.
+fn has_invariant_position(ep: &crate::EntryPoint, types: &crate::UniqueArena<crate::Type>) -> bool {
+    let is_invariant = |binding: &Option<crate::Binding>| {
+        matches!(
+            *binding,
+            Some(crate::Binding::BuiltIn(crate::BuiltIn::Position {
+                invariant: true
+            }))
+        )
+    };
+    let Some(ref result) = ep.function.result else {
+        return false;
+    };
+    if ep.stage != crate::ShaderStage::Vertex {
+        return false;
+    }
+    match types[result.ty].inner {
+        crate::TypeInner::Struct { ref members, .. } => {
+            members.iter().any(|member| is_invariant(&member.binding))
+        }
+        _ => is_invariant(&result.binding),
+    }
+}
+
+/// Return, for each function in `module`, whether it or a function it calls
+/// checks for NaNs or infinities, which fast math breaks.
+fn functions_checking_nan(module: &crate::Module) -> Vec<bool> {
+    // Callees always come before their callers in the arena.
+    let mut checks_nan = Vec::with_capacity(module.functions.len());
+    for (_, fun) in module.functions.iter() {
+        let checks = function_checks_nan(fun, &checks_nan);
+        checks_nan.push(checks);
+    }
+    checks_nan
+}
+
+/// Return true if `fun` checks for NaNs or infinities, or calls a function
+/// that `checks_nan` says does.
+fn function_checks_nan(fun: &crate::Function, checks_nan: &[bool]) -> bool {
+    fn calls_checker(block: &crate::Block, checks_nan: &[bool]) -> bool {
+        block.iter().any(|statement| match *statement {
+            crate::Statement::Call { function, .. } => checks_nan[function.index()],
+            crate::Statement::Block(ref block) => calls_checker(block, checks_nan),
+            crate::Statement::If {
+                ref accept,
+                ref reject,
+                ..
+            } => calls_checker(accept, checks_nan) || calls_checker(reject, checks_nan),
+            crate::Statement::Switch { ref cases, .. } => cases
+                .iter()
+                .any(|case| calls_checker(&case.body, checks_nan)),
+            crate::Statement::Loop {
+                ref body,
+                ref continuing,
+                ..
+            } => calls_checker(body, checks_nan) || calls_checker(continuing, checks_nan),
+            _ => false,
+        })
+    }
+
+    let uses_check = fun.expressions.iter().any(|(_, expr)| {
+        matches!(
+            *expr,
+            crate::Expression::Relational {
+                fun: crate::RelationalFunction::IsNan | crate::RelationalFunction::IsInf,
+                ..
+            }
+        )
+    });
+    uses_check || calls_checker(&fun.body, checks_nan)
+}
+
 fn needs_array_length(ty: Handle<crate::Type>, arena: &crate::UniqueArena<crate::Type>) -> bool {
     match arena[ty].inner {
         crate::TypeInner::Struct { ref members, .. } => {
@@ -515,6 +668,12 @@ struct ExpressionContext<'a> {
     pipeline_options: &'a PipelineOptions,
     lang_version: (u8, u8),
     policies: index::BoundsCheckPolicies,
+    clamp_frag_depth: bool,
+    fold_clamp_to_saturate: bool,
+    /// Whether [`Options::force_precise`] is set.
+    force_precise: bool,
+    /// The iteration cap for loops, if [`Options::force_bounded_loops`] is set.
+    max_loop_iterations: Option<u32>,
 
     /// A bitset containing the `Expression` handle indexes of expressions used
     /// as indices in `ReadZeroSkipWrite`-policy accesses. These may need to be
@@ -570,6 +729,21 @@ impl<'a> ExpressionContext<'a> {
                     crate::TypeInner::Struct {
                         ref members, span, ..
                     } => should_pack_struct_member(members, span, index as usize, self.module),
+                    crate::TypeInner::Array { base, stride, .. } => {
+                        should_pack_array_element(base, stride, self.module)
+                    }
+                    _ => None,
+                }
+            }
+            crate::Expression::Access { base, .. } => {
+                let ty = match *self.resolve_type(base) {
+                    crate::TypeInner::Pointer { base, .. } => &self.module.types[base].inner,
+                    ref ty => ty,
+                };
+                match *ty {
+                    crate::TypeInner::Array { base, stride, .. } => {
+                        should_pack_array_element(base, stride, self.module)
+                    }
                     _ => None,
                 }
             }
@@ -587,7 +761,10 @@ impl<W: Write> Writer<W> {
     /// Creates a new `Writer` instance.
     pub fn new(out: W) -> Self {
         Writer {
-            out,
+            out: LineCounter {
+                inner: out,
+                lines: 0,
+            },
             names: FastHashMap::default(),
             named_expressions: Default::default(),
             need_bake_expressions: Default::default(),
@@ -597,6 +774,7 @@ impl<W: Write> Writer<W> {
             #[cfg(test)]
             put_block_stack_pointers: Default::default(),
             struct_member_pads: FastHashSet::default(),
+            line_observer: None,
         }
     }
 
@@ -604,7 +782,22 @@ impl<W: Write> Writer<W> {
     // See https://github.com/rust-lang/rust-clippy/issues/4979.
     #[allow(clippy::missing_const_for_fn)]
     pub fn finish(self) -> W {
-        self.out
+        self.out.inner
+    }
+
+    /// Call `observer` for each statement written, with its span and the
+    /// range of lines it was written to.
+    ///
+    /// Lines are numbered from zero, counting from the start of this
+    /// writer's output. Statements containing blocks, like `if` or `loop`,
+    /// are reported after the statements in those blocks, with a range that
+    /// includes theirs. Statements that write nothing, like most `Emit`s,
+    /// get an empty range.
+    ///
+    /// This is enough to build a source map from the shader's source to
+    /// the generated code, given spans from the front end.
+    pub fn set_line_observer(&mut self, observer: LineObserver) {
+        self.line_observer = Some(observer);
     }
 
     fn put_call_parameters(
@@ -1194,6 +1387,39 @@ impl<W: Write> Writer<W> {
         Ok(())
     }
 
+    /// Emit code for `dot4I8Packed` or `dot4U8Packed`.
+    ///
+    /// Metal has no packed dot product, so reinterpret each operand as a
+    /// vector of four bytes and sum the products of their components. The
+    /// components are widened before multiplying, so the signed case
+    /// sign-extends them.
+    fn put_dot4_packed(
+        &mut self,
+        arg: Handle<crate::Expression>,
+        arg1: Handle<crate::Expression>,
+        signed: bool,
+        context: &ExpressionContext,
+    ) -> BackendResult {
+        let (scalar, bytes) = match signed {
+            true => ("int", "char4"),
+            false => ("uint", "uchar4"),
+        };
+        write!(self.out, "(")?;
+        for (index, component) in back::COMPONENTS.iter().enumerate() {
+            if index != 0 {
+                write!(self.out, " + ")?;
+            }
+            // The operands are baked, so repeating them is cheap.
+            write!(self.out, "{scalar}(as_type<{bytes}>(")?;
+            self.put_expression(arg, context, true)?;
+            write!(self.out, ").{component}) * {scalar}(as_type<{bytes}>(")?;
+            self.put_expression(arg1, context, true)?;
+            write!(self.out, ").{component})")?;
+        }
+        write!(self.out, ")")?;
+        Ok(())
+    }
+
     /// Emit code for the sign(i32) expression.
     ///
     fn put_isign(
@@ -1684,6 +1910,36 @@ impl<W: Write> Writer<W> {
                 write!(self.out, "{NAMESPACE}::{op}")?;
                 self.put_call_parameters(iter::once(expr), context)?;
             }
+            crate::Expression::Relational {
+                fun: fun @ (crate::RelationalFunction::IsNan | crate::RelationalFunction::IsInf),
+                argument,
+            } if context.force_precise => {
+                // Fast math deletes `isnan` and `isinf`, so compare the bits
+                // of the value, with the sign cleared, to those of infinity.
+                let op = match fun {
+                    crate::RelationalFunction::IsNan => ">",
+                    _ => "==",
+                };
+                let size = match *context.resolve_type(argument) {
+                    crate::TypeInner::Vector { size, .. } => back::vector_size_str(size),
+                    _ => "",
+                };
+                let ty = match size {
+                    "" => "uint".to_string(),
+                    _ => format!("{NAMESPACE}::uint{size}"),
+                };
+                write!(self.out, "((as_type<{ty}>(")?;
+                self.put_expression(argument, context, true)?;
+                write!(self.out, ") & 0x7FFFFFFFu) {op} 0x7F800000u)")?;
+            }
+            // Metal's `all` and `any` only take vectors, and of a single
+            // boolean they're the identity.
+            crate::Expression::Relational {
+                fun: crate::RelationalFunction::All | crate::RelationalFunction::Any,
+                argument,
+            } if matches!(*context.resolve_type(argument), crate::TypeInner::Scalar(_)) => {
+                self.put_expression(argument, context, is_scoped)?;
+            }
             crate::Expression::Relational { fun, argument } => {
                 let op = match fun {
                     crate::RelationalFunction::Any => "any",
@@ -1703,6 +1959,19 @@ impl<W: Write> Writer<W> {
             } => {
                 use crate::MathFunction as Mf;
 
+                let (fun, arg1, arg2) = if fun == Mf::Clamp
+                    && context.fold_clamp_to_saturate
+                    && back::is_saturate_clamp(
+                        context.module,
+                        &context.function.expressions,
+                        arg1,
+                        arg2,
+                    ) {
+                    (Mf::Saturate, None, None)
+                } else {
+                    (fun, arg1, arg2)
+                };
+
                 let arg_type = context.resolve_type(arg);
                 let scalar_argument = match arg_type {
                     &crate::TypeInner::Scalar(_) => true,
@@ -1764,6 +2033,10 @@ impl<W: Write> Writer<W> {
                             "Correct TypeInner for dot product should be already validated"
                         ),
                     },
+                    Mf::Dot4I8Packed | Mf::Dot4U8Packed => {
+                        let signed = fun == Mf::Dot4I8Packed;
+                        return self.put_dot4_packed(arg, arg1.unwrap(), signed, context);
+                    }
                     Mf::Outer => return Err(Error::UnsupportedCall(format!("{fun:?}"))),
                     Mf::Cross => "cross",
                     Mf::Distance => "distance",
@@ -1789,6 +2062,7 @@ impl<W: Write> Writer<W> {
                     Mf::Inverse => return Err(Error::UnsupportedCall(format!("{fun:?}"))),
                     Mf::Transpose => "transpose",
                     Mf::Determinant => "determinant",
+                    Mf::QuantizeToF16 => "",
                     // bits
                     Mf::CountTrailingZeros => "ctz",
                     Mf::CountLeadingZeros => "clz",
@@ -1822,9 +2096,11 @@ impl<W: Write> Writer<W> {
                         // also talks about MSL 1.2 adding "New integer
                         // functions to extract, insert, and reverse bits, as
                         // described in Integer Functions."
-                        if context.lang_version < (1, 2) {
-                            return Err(Error::UnsupportedFunction(fun_name.to_string()));
-                        }
+                        super::require_lang_version(
+                            context.lang_version,
+                            (1, 2),
+                            &format!("`{NAMESPACE}::{fun_name}`"),
+                        )?;
                     }
                     _ => {}
                 }
@@ -1891,6 +2167,17 @@ impl<W: Write> Writer<W> {
                     write!(self.out, "as_type<uint>(half2(")?;
                     self.put_expression(arg, context, false)?;
                     write!(self.out, "))")?;
+                } else if fun == Mf::QuantizeToF16 {
+                    // Metal's `half` conversions round to nearest even, and
+                    // produce infinities for out-of-range values, as WGSL
+                    // requires.
+                    let size = match *arg_type {
+                        crate::TypeInner::Vector { size, .. } => back::vector_size_str(size),
+                        _ => "",
+                    };
+                    write!(self.out, "float{size}(half{size}(")?;
+                    self.put_expression(arg, context, false)?;
+                    write!(self.out, "))")?;
                 } else if fun == Mf::Radians {
                     write!(self.out, "((")?;
                     self.put_expression(arg, context, false)?;
@@ -1987,9 +2274,7 @@ impl<W: Write> Writer<W> {
                 }
             }
             crate::Expression::RayQueryGetIntersection { query, committed } => {
-                if context.lang_version < (2, 4) {
-                    return Err(Error::UnsupportedRayTracing);
-                }
+                super::require_lang_version(context.lang_version, (2, 4), "ray tracing")?;
 
                 if !committed {
                     unimplemented!()
@@ -2139,6 +2424,8 @@ impl<W: Write> Writer<W> {
                     self.out.write_str(") < ")?;
                     match length {
                         index::IndexableLength::Known(value) => write!(self.out, "{value}")?,
+                        // Rejected by `write_type_defs`.
+                        index::IndexableLength::Pending(_) => unreachable!(),
                         index::IndexableLength::Dynamic => {
                             let global = context
                                 .function
@@ -2299,6 +2586,8 @@ impl<W: Write> Writer<W> {
                 index::IndexableLength::Known(limit) => {
                     write!(self.out, "{}u", limit - 1)?;
                 }
+                // Rejected by `write_type_defs`.
+                index::IndexableLength::Pending(_) => unreachable!(),
                 index::IndexableLength::Dynamic => {
                     let global = context
                         .function
@@ -2425,6 +2714,14 @@ impl<W: Write> Writer<W> {
                                     write!(self.out, "{tmp}.{name}.{WRAPPED_ARRAY_FIELD}[{j}]")?;
                                 }
                                 write!(self.out, "}}")?;
+                            } else if context.clamp_frag_depth
+                                && member.binding
+                                    == Some(crate::Binding::BuiltIn(crate::BuiltIn::FragDepth))
+                            {
+                                write!(
+                                    self.out,
+                                    "{comma} {NAMESPACE}::clamp({tmp}.{name}, 0.0, 1.0)"
+                                )?;
                             } else {
                                 write!(self.out, "{comma} {tmp}.{name}")?;
                             }
@@ -2432,7 +2729,16 @@ impl<W: Write> Writer<W> {
                     }
                     _ => {
                         write!(self.out, "{level}return {struct_name} {{ ")?;
-                        self.put_expression(expr_handle, context, true)?;
+                        let binding = context.function.result.as_ref().unwrap().binding.as_ref();
+                        if context.clamp_frag_depth
+                            && binding == Some(&crate::Binding::BuiltIn(crate::BuiltIn::FragDepth))
+                        {
+                            write!(self.out, "{NAMESPACE}::clamp(")?;
+                            self.put_expression(expr_handle, context, true)?;
+                            write!(self.out, ", 0.0, 1.0)")?;
+                        } else {
+                            self.put_expression(expr_handle, context, true)?;
+                        }
                     }
                 }
 
@@ -2511,6 +2817,12 @@ impl<W: Write> Writer<W> {
                             }
                         }
                     }
+                    crate::MathFunction::Dot4I8Packed | crate::MathFunction::Dot4U8Packed => {
+                        // Like integer `dot`, these use each operand once per
+                        // byte (see `put_dot4_packed`).
+                        self.need_bake_expressions.insert(arg);
+                        self.need_bake_expressions.insert(arg1.unwrap());
+                    }
                     crate::MathFunction::FindMsb => {
                         self.need_bake_expressions.insert(arg);
                     }
@@ -2624,10 +2936,29 @@ impl<W: Write> Writer<W> {
         Ok(())
     }
 
+    /// Open a loop: `while(true)`, or a `for` loop capped at `max_iterations`.
+    fn put_loop_header(
+        &mut self,
+        level: back::Level,
+        max_iterations: Option<u32>,
+    ) -> BackendResult {
+        match max_iterations {
+            Some(max) => {
+                let counter = self.namer.call("naga_i");
+                writeln!(
+                    self.out,
+                    "{level}for (uint {counter} = 0u; {counter} < {max}u; ++{counter}) {{"
+                )?;
+            }
+            None => writeln!(self.out, "{level}while(true) {{")?,
+        }
+        Ok(())
+    }
+
     fn put_block(
         &mut self,
         level: back::Level,
-        statements: &[crate::Statement],
+        statements: &crate::Block,
         context: &StatementContext,
     ) -> BackendResult {
         // Add to the set in order to track the stack size.
@@ -2636,8 +2967,9 @@ impl<W: Write> Writer<W> {
         self.put_block_stack_pointers
             .insert(&level as *const _ as *const ());
 
-        for statement in statements {
+        for (statement, &span) in statements.span_iter() {
             log::trace!("statement[{}] {:?}", level.0, statement);
+            let first_line = self.out.lines;
             match *statement {
                 crate::Statement::Emit(ref range) => {
                     for handle in range.clone() {
@@ -2708,6 +3040,7 @@ impl<W: Write> Writer<W> {
                     condition,
                     ref accept,
                     ref reject,
+                    hint: _,
                 } => {
                     write!(self.out, "{level}if (")?;
                     self.put_expression(condition, &context.expression, true)?;
@@ -2764,11 +3097,12 @@ impl<W: Write> Writer<W> {
                     ref body,
                     ref continuing,
                     break_if,
+                    hint: _,
                 } => {
                     if !continuing.is_empty() || break_if.is_some() {
                         let gate_name = self.namer.call("loop_init");
                         writeln!(self.out, "{level}bool {gate_name} = true;")?;
-                        writeln!(self.out, "{level}while(true) {{")?;
+                        self.put_loop_header(level, context.expression.max_loop_iterations)?;
                         let lif = level.next();
                         let lcontinuing = lif.next();
                         writeln!(self.out, "{lif}if (!{gate_name}) {{")?;
@@ -2783,7 +3117,7 @@ impl<W: Write> Writer<W> {
                         writeln!(self.out, "{lif}}}")?;
                         writeln!(self.out, "{lif}{gate_name} = false;")?;
                     } else {
-                        writeln!(self.out, "{level}while(true) {{")?;
+                        self.put_loop_header(level, context.expression.max_loop_iterations)?;
                     }
                     self.put_block(level.next(), body, context)?;
                     writeln!(self.out, "{level}}}")?;
@@ -2810,9 +3144,20 @@ impl<W: Write> Writer<W> {
                 crate::Statement::Kill => {
                     writeln!(self.out, "{level}{NAMESPACE}::discard_fragment();")?;
                 }
+                crate::Statement::DebugMarker(ref text) => {
+                    for line in back::c_comment_lines(text) {
+                        let space = if line.is_empty() { "" } else { " " };
+                        writeln!(self.out, "{level}//{space}{line}")?;
+                    }
+                }
                 crate::Statement::Barrier(flags) => {
                     self.write_barrier(flags, level)?;
                 }
+                crate::Statement::MeshFunction(_) => {
+                    return Err(Error::FeatureNotImplemented(
+                        "mesh shader outputs".to_string(),
+                    ));
+                }
                 crate::Statement::Store { pointer, value } => {
                     self.put_store(pointer, value, level, context)?
                 }
@@ -2943,9 +3288,11 @@ impl<W: Write> Writer<W> {
                     self.write_barrier(crate::Barrier::WORK_GROUP, level)?;
                 }
                 crate::Statement::RayQuery { query, ref fun } => {
-                    if context.expression.lang_version < (2, 4) {
-                        return Err(Error::UnsupportedRayTracing);
-                    }
+                    super::require_lang_version(
+                        context.expression.lang_version,
+                        (2, 4),
+                        "ray tracing",
+                    )?;
 
                     match *fun {
                         crate::RayQueryFunction::Initialize {
@@ -3041,6 +3388,10 @@ impl<W: Write> Writer<W> {
                     }
                 }
             }
+
+            if let Some(ref mut observer) = self.line_observer {
+                observer(span, first_line..self.out.lines);
+            }
         }
 
         // un-emit expressions
@@ -3127,6 +3478,12 @@ impl<W: Write> Writer<W> {
         );
         self.struct_member_pads.clear();
 
+        if crate::external_texture::has_external_textures(module) {
+            return Err(Error::FeatureNotImplemented(
+                "external textures".to_string(),
+            ));
+        }
+
         writeln!(
             self.out,
             "// language: metal{}.{}",
@@ -3142,16 +3499,30 @@ impl<W: Write> Writer<W> {
         for (_, ty) in module.types.iter() {
             match ty.inner {
                 crate::TypeInner::AccelerationStructure => {
-                    if options.lang_version < (2, 4) {
-                        return Err(Error::UnsupportedRayTracing);
-                    }
+                    super::require_lang_version(options.lang_version, (2, 4), "ray tracing")?;
                 }
                 crate::TypeInner::RayQuery => {
-                    if options.lang_version < (2, 4) {
-                        return Err(Error::UnsupportedRayTracing);
-                    }
+                    super::require_lang_version(options.lang_version, (2, 4), "ray tracing")?;
                     uses_ray_query = true;
                 }
+                // `texture2d_ms_array` and `depth2d_ms_array` are macOS only,
+                // since Metal 2.
+                crate::TypeInner::Image {
+                    arrayed: true,
+                    class:
+                        crate::ImageClass::Sampled { multi: true, .. }
+                        | crate::ImageClass::Depth { multi: true },
+                    ..
+                } => {
+                    super::require_lang_version(
+                        options.lang_version,
+                        (2, 0),
+                        "multisampled array textures",
+                    )?;
+                }
+                // 32-bit integer atomics are available in every MSL version.
+                // The 64-bit and floating-point atomics that need MSL 2.4 and
+                // 3.0 are rejected by validation, so nothing reaches here.
                 _ => (),
             }
         }
@@ -3159,9 +3530,7 @@ impl<W: Write> Writer<W> {
         if module.special_types.ray_desc.is_some()
             || module.special_types.ray_intersection.is_some()
         {
-            if options.lang_version < (2, 4) {
-                return Err(Error::UnsupportedRayTracing);
-            }
+            super::require_lang_version(options.lang_version, (2, 4), "ray tracing")?;
         }
 
         if uses_ray_query {
@@ -3271,18 +3640,21 @@ impl<W: Write> Writer<W> {
                 //
                 // Then we carefully include `.inner` (`WRAPPED_ARRAY_FIELD`) in
                 // any expression that actually wants access to the array.
-                crate::TypeInner::Array {
-                    base,
-                    size,
-                    stride: _,
-                } => {
-                    let base_name = TypeContext {
-                        handle: base,
-                        gctx: module.to_ctx(),
-                        names: &self.names,
-                        access: crate::StorageAccess::empty(),
-                        binding: None,
-                        first_time: false,
+                //
+                // Elements that are tightly packed three-component vectors
+                // use Metal's packed vector types, as struct members do.
+                crate::TypeInner::Array { base, size, stride } => {
+                    let base_name = match should_pack_array_element(base, stride, module) {
+                        Some(scalar) => format!("{NAMESPACE}::packed_{}3", scalar.to_msl_name()),
+                        None => TypeContext {
+                            handle: base,
+                            gctx: module.to_ctx(),
+                            names: &self.names,
+                            access: crate::StorageAccess::empty(),
+                            binding: None,
+                            first_time: false,
+                        }
+                        .to_string(),
                     };
 
                     match size {
@@ -3298,6 +3670,11 @@ impl<W: Write> Writer<W> {
                             )?;
                             writeln!(self.out, "}};")?;
                         }
+                        crate::ArraySize::Pending(_) => {
+                            return Err(Error::FeatureNotImplemented(
+                                "arrays sized by an override".to_string(),
+                            ));
+                        }
                         crate::ArraySize::Dynamic => {
                             writeln!(self.out, "typedef {base_name} {name}[1];")?;
                         }
@@ -3640,6 +4017,12 @@ impl<W: Write> Writer<W> {
                     info: fun_info,
                     lang_version: options.lang_version,
                     policies: options.bounds_check_policies,
+                    clamp_frag_depth: options.clamp_frag_depth,
+                    fold_clamp_to_saturate: options.fold_clamp_to_saturate,
+                    force_precise: options.force_precise,
+                    max_loop_iterations: options
+                        .force_bounded_loops
+                        .then_some(options.max_loop_iterations),
                     guarded_indices,
                     module,
                     mod_info,
@@ -3679,12 +4062,20 @@ impl<W: Write> Writer<W> {
 
         let mut info = TranslationInfo {
             entry_point_names: Vec::with_capacity(module.entry_points.len()),
+            entry_point_needs_precise: Vec::with_capacity(module.entry_points.len()),
+            entry_point_constexpr_samplers: Vec::with_capacity(module.entry_points.len()),
         };
+        let checks_nan = functions_checking_nan(module);
         for (ep_index, ep) in module.entry_points.iter().enumerate() {
             let fun = &ep.function;
             let fun_info = mod_info.get_entry_point(ep_index);
             let mut ep_error = None;
 
+            let needs_precise = has_invariant_position(ep, &module.types)
+                || (!options.force_precise && function_checks_nan(fun, &checks_nan));
+            info.entry_point_needs_precise.push(needs_precise);
+            info.entry_point_constexpr_samplers.push(Vec::new());
+
             log::trace!(
                 "entry point {:?}, index {:?}",
                 fun.name.as_deref().unwrap_or("(anonymous)"),
@@ -3718,6 +4109,9 @@ impl<W: Write> Writer<W> {
                                     break;
                                 }
                             };
+                            if options.constexpr_samplers.contains_key(br) {
+                                continue;
+                            }
                             let target = options.get_resource_binding_target(ep, br);
                             let good = match target {
                                 Some(target) => {
@@ -3761,6 +4155,10 @@ impl<W: Write> Writer<W> {
                 }
             }
 
+            if let crate::ShaderStage::Task | crate::ShaderStage::Mesh = ep.stage {
+                ep_error = Some(super::EntryPointError::UnsupportedStage(ep.stage));
+            }
+
             if let Some(err) = ep_error {
                 info.entry_point_names.push(Err(err));
                 continue;
@@ -3784,6 +4182,7 @@ impl<W: Write> Writer<W> {
                 crate::ShaderStage::Compute { .. } => {
                     ("kernel", LocationMode::Uniform, LocationMode::Uniform)
                 }
+                crate::ShaderStage::Task | crate::ShaderStage::Mesh => unreachable!(),
             };
 
             // Since `Namer.reset` wasn't expecting struct members to be
@@ -3833,6 +4232,10 @@ impl<W: Write> Writer<W> {
             let stage_in_name = format!("{fun_name}Input");
             let varyings_member_name = self.namer.call("varyings");
             let mut has_varyings = false;
+            // Metal has no matrix or array attributes, so vertex inputs of
+            // those types are split into one member per location. This maps
+            // such inputs to the names of their parts.
+            let mut split_input_names = FastHashMap::default();
             if !flattened_arguments.is_empty() {
                 writeln!(self.out, "struct {stage_in_name} {{")?;
                 for &(ref name_key, ty, binding) in flattened_arguments.iter() {
@@ -3845,6 +4248,49 @@ impl<W: Write> Writer<W> {
                         NameKey::StructMember(..) => &flattened_member_names[name_key],
                         _ => &self.names[name_key],
                     };
+                    let split = match in_mode {
+                        LocationMode::VertexInput => {
+                            module.types[ty].inner.vertex_input_split(&module.types)
+                        }
+                        _ => None,
+                    };
+                    if let Some((count, ref inner)) = split {
+                        let mut parts = Vec::with_capacity(count as usize);
+                        for index in 0..count {
+                            let part = self.namer.call(&format!("{name}_{index}"));
+                            let binding = match **binding {
+                                crate::Binding::Location {
+                                    location,
+                                    interpolation,
+                                    sampling,
+                                    second_blend_source,
+                                } => crate::Binding::Location {
+                                    location: location + index,
+                                    interpolation,
+                                    sampling,
+                                    second_blend_source,
+                                },
+                                crate::Binding::BuiltIn(_) => unreachable!(),
+                            };
+                            let resolved = options.resolve_local_binding(&binding, in_mode)?;
+                            write!(self.out, "{}", back::INDENT)?;
+                            match *inner {
+                                crate::TypeInner::Vector { size, scalar } => {
+                                    put_numeric_type(&mut self.out, scalar, &[size])?
+                                }
+                                crate::TypeInner::Scalar(scalar) => {
+                                    put_numeric_type(&mut self.out, scalar, &[])?
+                                }
+                                _ => unreachable!(),
+                            }
+                            write!(self.out, " {part}")?;
+                            resolved.try_fmt(&mut self.out)?;
+                            writeln!(self.out, ";")?;
+                            parts.push(part);
+                        }
+                        split_input_names.insert(name_key, parts);
+                        continue;
+                    }
                     let ty_name = TypeContext {
                         handle: ty,
                         gctx: module.to_ctx(),
@@ -3913,7 +4359,14 @@ impl<W: Write> Writer<W> {
                             } => Some(size),
                             _ => None,
                         };
-                        let resolved = options.resolve_local_binding(binding, out_mode)?;
+                        let resolved = match *binding {
+                            crate::Binding::BuiltIn(crate::BuiltIn::FragDepth) => {
+                                super::ResolvedBinding::FragDepth(
+                                    options.get_conservative_depth(ep),
+                                )
+                            }
+                            _ => options.resolve_local_binding(binding, out_mode)?,
+                        };
                         write!(self.out, "{}{} {}", back::INDENT, ty_name, name)?;
                         if let Some(array_len) = array_len {
                             write!(self.out, " [{array_len}]")?;
@@ -4018,6 +4471,9 @@ impl<W: Write> Writer<W> {
                 }
 
                 if options.lang_version < (1, 2) {
+                    let require = |feature: &str| {
+                        super::require_lang_version(options.lang_version, (1, 2), feature)
+                    };
                     match var.space {
                         // This restriction is not documented in the MSL spec
                         // but validation will fail if it is not upheld.
@@ -4032,7 +4488,7 @@ impl<W: Write> Writer<W> {
                             if access.contains(crate::StorageAccess::STORE)
                                 && ep.stage == crate::ShaderStage::Fragment =>
                         {
-                            return Err(Error::UnsupportedWriteableStorageBuffer)
+                            require("writeable storage buffers in the fragment stage")?
                         }
                         crate::AddressSpace::Handle => {
                             match module.types[var.ty].inner {
@@ -4053,15 +4509,16 @@ impl<W: Write> Writer<W> {
                                         && (ep.stage == crate::ShaderStage::Vertex
                                             || ep.stage == crate::ShaderStage::Fragment)
                                     {
-                                        return Err(Error::UnsupportedWriteableStorageTexture(
-                                            ep.stage,
-                                        ));
+                                        require(&format!(
+                                            "writeable storage textures in the {:?} stage",
+                                            ep.stage
+                                        ))?;
                                     }
 
                                     if access.contains(
                                         crate::StorageAccess::LOAD | crate::StorageAccess::STORE,
                                     ) {
-                                        return Err(Error::UnsupportedRWStorageTexture);
+                                        require("read-write storage textures")?;
                                     }
                                 }
                                 _ => {}
@@ -4077,11 +4534,11 @@ impl<W: Write> Writer<W> {
                         crate::TypeInner::BindingArray { base, .. } => {
                             match module.types[base].inner {
                                 crate::TypeInner::Sampler { .. } => {
-                                    if options.lang_version < (2, 0) {
-                                        return Err(Error::UnsupportedArrayOf(
-                                            "samplers".to_string(),
-                                        ));
-                                    }
+                                    super::require_lang_version(
+                                        options.lang_version,
+                                        (2, 0),
+                                        "arrays of samplers",
+                                    )?;
                                 }
                                 crate::TypeInner::Image { class, .. } => match class {
                                     crate::ImageClass::Sampled { .. }
@@ -4094,11 +4551,11 @@ impl<W: Write> Writer<W> {
                                         // - iOS: Metal 1.2 (check depends on https://github.com/gfx-rs/naga/issues/2164)
                                         // - macOS: Metal 2
 
-                                        if options.lang_version < (2, 0) {
-                                            return Err(Error::UnsupportedArrayOf(
-                                                "textures".to_string(),
-                                            ));
-                                        }
+                                        super::require_lang_version(
+                                            options.lang_version,
+                                            (2, 0),
+                                            "arrays of textures",
+                                        )?;
                                     }
                                     crate::ImageClass::Storage {
                                         access: crate::StorageAccess::STORE,
@@ -4108,17 +4565,18 @@ impl<W: Write> Writer<W> {
                                         // - iOS: Metal 2.2 (check depends on https://github.com/gfx-rs/naga/issues/2164)
                                         // - macOS: Metal 2
 
-                                        if options.lang_version < (2, 0) {
-                                            return Err(Error::UnsupportedArrayOf(
-                                                "write-only textures".to_string(),
-                                            ));
-                                        }
+                                        super::require_lang_version(
+                                            options.lang_version,
+                                            (2, 0),
+                                            "arrays of write-only textures",
+                                        )?;
                                     }
                                     crate::ImageClass::Storage { .. } => {
                                         return Err(Error::UnsupportedArrayOf(
                                             "read-write textures".to_string(),
                                         ));
                                     }
+                                    crate::ImageClass::External => unreachable!(),
                                 },
                                 _ => {
                                     return Err(Error::UnsupportedArrayOfType(base));
@@ -4233,6 +4691,10 @@ impl<W: Write> Writer<W> {
                     // write an inline sampler
                     let resolved = options.resolve_resource_binding(ep, binding).unwrap();
                     if let Some(sampler) = resolved.as_inline_sampler(options) {
+                        if let super::ResolvedBinding::ConstexprSampler(ref binding) = resolved {
+                            check_constexpr_sampler(binding, sampler, &module.types[var.ty].inner)?;
+                            info.entry_point_constexpr_samplers[ep_index].push(binding.clone());
+                        }
                         let name = &self.names[&NameKey::GlobalVariable(handle)];
                         writeln!(
                             self.out,
@@ -4283,6 +4745,15 @@ impl<W: Write> Writer<W> {
                             {
                                 write!(self.out, "{{}}, ")?;
                             }
+                            if let Some(parts) = split_input_names.get(&key) {
+                                self.put_split_vertex_input(
+                                    module,
+                                    member.ty,
+                                    &varyings_member_name,
+                                    parts,
+                                )?;
+                                continue;
+                            }
                             if let Some(crate::Binding::Location { .. }) = member.binding {
                                 write!(self.out, "{varyings_member_name}.")?;
                             }
@@ -4291,7 +4762,17 @@ impl<W: Write> Writer<W> {
                         writeln!(self.out, " }};")?;
                     }
                     _ => {
-                        if let Some(crate::Binding::Location { .. }) = arg.binding {
+                        let key = NameKey::EntryPointArgument(ep_index as _, arg_index as u32);
+                        if let Some(parts) = split_input_names.get(&key) {
+                            write!(self.out, "{}const auto {} = ", back::INDENT, arg_name)?;
+                            self.put_split_vertex_input(
+                                module,
+                                arg.ty,
+                                &varyings_member_name,
+                                parts,
+                            )?;
+                            writeln!(self.out, ";")?;
+                        } else if let Some(crate::Binding::Location { .. }) = arg.binding {
                             writeln!(
                                 self.out,
                                 "{}const auto {} = {}.{};",
@@ -4315,6 +4796,12 @@ impl<W: Write> Writer<W> {
                     info: fun_info,
                     lang_version: options.lang_version,
                     policies: options.bounds_check_policies,
+                    clamp_frag_depth: options.clamp_frag_depth,
+                    fold_clamp_to_saturate: options.fold_clamp_to_saturate,
+                    force_precise: options.force_precise,
+                    max_loop_iterations: options
+                        .force_bounded_loops
+                        .then_some(options.max_loop_iterations),
                     guarded_indices,
                     module,
                     mod_info,
@@ -4360,6 +4847,39 @@ impl<W: Write> Writer<W> {
         Ok(info)
     }
 
+    /// Write the value of the vertex input of type `ty`, rebuilt from the
+    /// `parts` members of `varyings` holding its columns or elements.
+    fn put_split_vertex_input(
+        &mut self,
+        module: &crate::Module,
+        ty: Handle<crate::Type>,
+        varyings: &str,
+        parts: &[String],
+    ) -> BackendResult {
+        let ty_name = TypeContext {
+            handle: ty,
+            gctx: module.to_ctx(),
+            names: &self.names,
+            access: crate::StorageAccess::empty(),
+            binding: None,
+            first_time: false,
+        };
+        // Arrays are wrapped in structs, which need braces.
+        let (open, close) = match module.types[ty].inner {
+            crate::TypeInner::Array { .. } => (" {{", "}}"),
+            _ => ("(", ")"),
+        };
+        write!(self.out, "{ty_name}{open}")?;
+        for (index, part) in parts.iter().enumerate() {
+            if index != 0 {
+                write!(self.out, ", ")?;
+            }
+            write!(self.out, "{varyings}.{part}")?;
+        }
+        write!(self.out, "{close}")?;
+        Ok(())
+    }
+
     fn write_barrier(&mut self, flags: crate::Barrier, level: back::Level) -> BackendResult {
         // Note: OR-ring bitflags requires `__HAVE_MEMFLAG_OPERATORS__`,
         // so we try to avoid it here.
@@ -4538,11 +5058,12 @@ mod workgroup_mem_init {
                         writeln!(self.out, ", 0, {NAMESPACE}::memory_order_relaxed);")?;
                     }
                     crate::TypeInner::Array { base, size, .. } => {
-                        let count = match size.to_indexable_length(module).expect("Bad array size")
-                        {
-                            proc::IndexableLength::Known(count) => count,
-                            proc::IndexableLength::Dynamic => unreachable!(),
-                        };
+                        let count =
+                            match size.to_indexable_length(module).expect("Bad array size") {
+                                proc::IndexableLength::Known(count) => count,
+                                proc::IndexableLength::Pending(_)
+                                | proc::IndexableLength::Dynamic => unreachable!(),
+                            };
 
                         access_stack.enter_array(|access_stack, array_depth| {
                             writeln!(
@@ -4611,6 +5132,7 @@ fn test_stack_size() {
             condition: nested_expr,
             accept: crate::Block::new(),
             reject: crate::Block::new(),
+            hint: None,
         },
         Default::default(),
     );
@@ -4657,3 +5179,43 @@ fn test_stack_size() {
         }
     }
 }
+
+#[test]
+fn test_ray_tracing_version() {
+    use crate::valid::{Capabilities, ValidationFlags};
+    let mut module = crate::Module::default();
+    module.types.insert(
+        crate::Type {
+            name: None,
+            inner: crate::TypeInner::AccelerationStructure,
+        },
+        Default::default(),
+    );
+    let info = crate::valid::Validator::new(ValidationFlags::empty(), Capabilities::all())
+        .validate(&module)
+        .unwrap();
+
+    let options = Options {
+        lang_version: (2, 3),
+        ..Default::default()
+    };
+    let mut writer = Writer::new(String::new());
+    match writer.write(&module, &info, &options, &Default::default()) {
+        Err(Error::UnsupportedMslVersion {
+            needed: (2, 4),
+            requested: (2, 3),
+            ..
+        }) => {}
+        Err(other) => panic!("expected UnsupportedMslVersion, got {other:?}"),
+        Ok(_) => panic!("ray tracing should require MSL 2.4"),
+    }
+
+    let options = Options {
+        lang_version: (2, 4),
+        ..Default::default()
+    };
+    let mut writer = Writer::new(String::new());
+    writer
+        .write(&module, &info, &options, &Default::default())
+        .unwrap();
+}
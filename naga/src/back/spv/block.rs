@@ -8,6 +8,7 @@ use super::{
     WriterFlags,
 };
 use crate::{arena::Handle, proc::TypeResolution, Statement};
+use arrayvec::ArrayVec;
 use spirv::Word;
 
 fn get_dimension(type_inner: &crate::TypeInner) -> Dimension {
@@ -38,6 +39,40 @@ enum ExpressionPointer {
     },
 }
 
+/// A run of statements storing some components of a vector value into
+/// components of a vector in memory, like `v.xz = value`.
+///
+/// Naga IR has no swizzled stores, so front ends lower them to one `Store`
+/// per component, through an `AccessIndex` on the destination pointer. See
+/// [`BlockContext::find_swizzle_store`].
+struct SwizzleStore {
+    /// The pointer to the destination vector.
+    pointer: Handle<crate::Expression>,
+    /// The vector value whose components are being stored.
+    value: Handle<crate::Expression>,
+    /// Pairs of destination and source component indices.
+    components: ArrayVec<(u32, u32), 4>,
+    /// The `AccessIndex` expressions extracting the source components, which
+    /// we don't need to emit.
+    sources: ArrayVec<Handle<crate::Expression>, 4>,
+    /// The number of statements the run covers, including `Emit`s.
+    statement_count: usize,
+}
+
+/// A whole struct or array copied from one place in memory to another, like
+/// `a = b`.
+///
+/// Front ends lower this to an `Emit` of a `Load` of the source, followed by
+/// a `Store` of the loaded value. See [`BlockContext::find_copy_store`].
+struct CopyStore {
+    /// The pointer to the destination.
+    pointer: Handle<crate::Expression>,
+    /// The `Load` of the source, which we don't need to emit.
+    load: Handle<crate::Expression>,
+    /// The pointer to the source.
+    source: Handle<crate::Expression>,
+}
+
 /// The termination statement to be added to the end of the block
 pub enum BlockExit {
     /// Generates an OpReturn (void return)
@@ -188,6 +223,127 @@ impl Writer {
 }
 
 impl<'w> BlockContext<'w> {
+    /// Write the `OpEmitMeshTasksEXT` that ends a task shader.
+    ///
+    /// The workgroup counts come from the `MeshTaskSize` built-in, which is
+    /// either the returned value, `value_id`, or its member `index`.
+    ///
+    /// Return the terminating instruction.
+    fn write_emit_mesh_tasks(
+        &mut self,
+        value_id: Word,
+        index: u32,
+        block: &mut Block,
+    ) -> Instruction {
+        let uint_type_id = self.get_type_id(LookupType::Local(LocalType::Value {
+            vector_size: None,
+            scalar: crate::Scalar::U32,
+            pointer_space: None,
+        }));
+        let size_id = match self.ir_function.result {
+            Some(crate::FunctionResult {
+                binding: Some(_), ..
+            }) => value_id,
+            _ => {
+                let uvec3_type_id = self.get_type_id(LookupType::Local(LocalType::Value {
+                    vector_size: Some(crate::VectorSize::Tri),
+                    scalar: crate::Scalar::U32,
+                    pointer_space: None,
+                }));
+                let id = self.gen_id();
+                block.body.push(Instruction::composite_extract(
+                    uvec3_type_id,
+                    id,
+                    value_id,
+                    &[index],
+                ));
+                id
+            }
+        };
+        let mut counts = [0; 3];
+        for (component, count) in counts.iter_mut().enumerate() {
+            *count = self.gen_id();
+            block.body.push(Instruction::composite_extract(
+                uint_type_id,
+                *count,
+                size_id,
+                &[component as u32],
+            ));
+        }
+        Instruction::emit_mesh_tasks(counts[0], counts[1], counts[2])
+    }
+
+    /// Write a [`MeshFunction`] statement.
+    ///
+    /// Stores to the vertex and primitive outputs write each member of the
+    /// struct to its own array variable.
+    ///
+    /// [`MeshFunction`]: crate::MeshFunction
+    fn write_mesh_function(
+        &mut self,
+        fun: &crate::MeshFunction,
+        block: &mut Block,
+    ) -> Result<(), Error> {
+        let context =
+            self.function
+                .entry_point_context
+                .as_ref()
+                .ok_or(Error::FeatureNotImplemented(
+                    "mesh outputs outside the entry point",
+                ))?;
+        let (index, value, outputs) = match *fun {
+            crate::MeshFunction::SetMeshOutputs {
+                vertex_count,
+                primitive_count,
+            } => {
+                block.body.push(Instruction::set_mesh_outputs(
+                    self.cached[vertex_count],
+                    self.cached[primitive_count],
+                ));
+                return Ok(());
+            }
+            crate::MeshFunction::SetVertex { index, value } => {
+                (index, value, &context.mesh_vertices)
+            }
+            crate::MeshFunction::SetPrimitive { index, value } => {
+                (index, value, &context.mesh_primitives)
+            }
+        };
+
+        let index_id = self.cached[index];
+        let value_id = self.cached[value];
+        for (member_index, output) in outputs.iter().enumerate() {
+            let member_id = self.writer.id_gen.next();
+            block.body.push(Instruction::composite_extract(
+                output.type_id,
+                member_id,
+                value_id,
+                &[member_index as u32],
+            ));
+            let pointer_id = self.writer.id_gen.next();
+            block.body.push(Instruction::access_chain(
+                output.pointer_type_id,
+                pointer_id,
+                output.id,
+                &[index_id],
+            ));
+            block
+                .body
+                .push(Instruction::store(pointer_id, member_id, None));
+            if let Some(crate::BuiltIn::Position { .. }) = output.built_in {
+                if self
+                    .writer
+                    .flags
+                    .contains(WriterFlags::ADJUST_COORDINATE_SPACE)
+                {
+                    self.writer
+                        .write_epilogue_position_y_flip(pointer_id, &mut block.body)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
     /// Decide whether to put off emitting instructions for `expr_handle`.
     ///
     /// We would like to gather together chains of `Access` and `AccessIndex`
@@ -210,6 +366,11 @@ impl<'w> BlockContext<'w> {
                 let arg = &self.ir_function.arguments[index as usize];
                 self.ir_module.types[arg.ty].inner.pointer_space().is_some()
             }
+            crate::Expression::Select { accept, .. } => self.fun_info[accept]
+                .ty
+                .inner_with(&self.ir_module.types)
+                .pointer_space()
+                .is_some(),
 
             // The chain rule: if this `Access...`'s `base` operand was
             // previously omitted, then omit this one, too.
@@ -235,10 +396,13 @@ impl<'w> BlockContext<'w> {
         let result_type_id = self.get_expression_type_id(&self.fun_info[expr_handle].ty);
         let id = match self.ir_function.expressions[expr_handle] {
             crate::Expression::Literal(literal) => self.writer.get_constant_scalar(literal),
-            crate::Expression::Constant(handle) => {
-                let init = self.ir_module.constants[handle].init;
-                self.writer.constant_ids[init.index()]
-            }
+            crate::Expression::Constant(handle) => match self.writer.override_ids[handle.index()] {
+                0 => {
+                    let init = self.ir_module.constants[handle].init;
+                    self.writer.constant_ids[init.index()]
+                }
+                override_id => override_id,
+            },
             crate::Expression::ZeroValue(_) => self.writer.get_constant_null(result_type_id),
             crate::Expression::Compose { ty, ref components } => {
                 self.temp_list.clear();
@@ -831,6 +995,34 @@ impl<'w> BlockContext<'w> {
                             "Correct TypeInner for dot product should be already validated"
                         ),
                     },
+                    Mf::Dot4I8Packed | Mf::Dot4U8Packed => {
+                        let signed = fun == Mf::Dot4I8Packed;
+                        if self.writer.use_integer_dot_product() {
+                            MathOp::Custom(Instruction::ternary(
+                                if signed {
+                                    spirv::Op::SDot
+                                } else {
+                                    spirv::Op::UDot
+                                },
+                                result_type_id,
+                                id,
+                                arg0_id,
+                                arg1_id,
+                                spirv::PackedVectorFormat::PackedVectorFormat4x8Bit as u32,
+                            ))
+                        } else {
+                            self.write_dot4_packed(
+                                id,
+                                result_type_id,
+                                arg0_id,
+                                arg1_id,
+                                signed,
+                                block,
+                            );
+                            self.cached[expr_handle] = id;
+                            return Ok(());
+                        }
+                    }
                     Mf::Outer => MathOp::Custom(Instruction::binary(
                         spirv::Op::OuterProduct,
                         result_type_id,
@@ -907,6 +1099,12 @@ impl<'w> BlockContext<'w> {
                         arg0_id,
                     )),
                     Mf::Determinant => MathOp::Ext(spirv::GLOp::Determinant),
+                    Mf::QuantizeToF16 => MathOp::Custom(Instruction::unary(
+                        spirv::Op::QuantizeToF16,
+                        result_type_id,
+                        id,
+                        arg0_id,
+                    )),
                     Mf::ReverseBits => MathOp::Custom(Instruction::unary(
                         spirv::Op::BitReverse,
                         result_type_id,
@@ -1071,57 +1269,15 @@ impl<'w> BlockContext<'w> {
             }
             crate::Expression::LocalVariable(variable) => self.function.variables[&variable].id,
             crate::Expression::Load { pointer } => {
-                match self.write_expression_pointer(pointer, block, None)? {
-                    ExpressionPointer::Ready { pointer_id } => {
-                        let id = self.gen_id();
-                        let atomic_space =
-                            match *self.fun_info[pointer].ty.inner_with(&self.ir_module.types) {
-                                crate::TypeInner::Pointer { base, space } => {
-                                    match self.ir_module.types[base].inner {
-                                        crate::TypeInner::Atomic { .. } => Some(space),
-                                        _ => None,
-                                    }
-                                }
-                                _ => None,
-                            };
-                        let instruction = if let Some(space) = atomic_space {
-                            let (semantics, scope) = space.to_spirv_semantics_and_scope();
-                            let scope_constant_id = self.get_scope_constant(scope as u32);
-                            let semantics_id = self.get_index_constant(semantics.bits());
-                            Instruction::atomic_load(
-                                result_type_id,
-                                id,
-                                pointer_id,
-                                scope_constant_id,
-                                semantics_id,
-                            )
-                        } else {
-                            Instruction::load(result_type_id, id, pointer_id, None)
-                        };
-                        block.body.push(instruction);
-                        id
-                    }
-                    ExpressionPointer::Conditional { condition, access } => {
-                        //TODO: support atomics?
-                        self.write_conditional_indexed_load(
-                            result_type_id,
-                            condition,
-                            block,
-                            move |id_gen, block| {
-                                // The in-bounds path. Perform the access and the load.
-                                let pointer_id = access.result_id.unwrap();
-                                let value_id = id_gen.next();
-                                block.body.push(access);
-                                block.body.push(Instruction::load(
-                                    result_type_id,
-                                    value_id,
-                                    pointer_id,
-                                    None,
-                                ));
-                                value_id
-                            },
-                        )
-                    }
+                let block_struct = self.binding_array_block_struct(pointer);
+                let load_type_id = match block_struct {
+                    Some(base) => self.get_type_id(LookupType::BlockStruct(base)),
+                    None => result_type_id,
+                };
+                let loaded_id = self.write_load(pointer, result_type_id, load_type_id, block)?;
+                match block_struct {
+                    Some(base) => self.write_struct_copy(base, loaded_id, result_type_id, block),
+                    None => loaded_id,
                 }
             }
             crate::Expression::FunctionArgument(index) => self.function.parameter_id(index),
@@ -1318,6 +1474,13 @@ impl<'w> BlockContext<'w> {
                 depth_ref,
                 block,
             )?,
+            crate::Expression::Select {
+                condition,
+                accept,
+                reject,
+            } if self.is_intermediate(expr_handle) => {
+                self.write_pointer_select(expr_handle, condition, accept, reject, block)?
+            }
             crate::Expression::Select {
                 condition,
                 accept,
@@ -1408,11 +1571,18 @@ impl<'w> BlockContext<'w> {
                     Rf::IsNan => spirv::Op::IsNan,
                     Rf::IsInf => spirv::Op::IsInf,
                 };
-                let id = self.gen_id();
-                block
-                    .body
-                    .push(Instruction::relational(op, result_type_id, id, arg_id));
-                id
+                let arg_ty = self.fun_info[argument].ty.inner_with(&self.ir_module.types);
+                match (fun, arg_ty) {
+                    // `OpAll` and `OpAny` only take vectors.
+                    (Rf::All | Rf::Any, &crate::TypeInner::Scalar(_)) => arg_id,
+                    _ => {
+                        let id = self.gen_id();
+                        block
+                            .body
+                            .push(Instruction::relational(op, result_type_id, id, arg_id));
+                        id
+                    }
+                }
             }
             crate::Expression::ArrayLength(expr) => self.write_runtime_array_length(expr, block)?,
             crate::Expression::RayQueryGetIntersection { query, committed } => {
@@ -1427,6 +1597,253 @@ impl<'w> BlockContext<'w> {
         Ok(())
     }
 
+    /// Load the value `pointer` points to.
+    ///
+    /// The `load_type_id` is the type of the loaded value, which differs from
+    /// `result_type_id` only for block structs; see
+    /// [`binding_array_block_struct`].
+    ///
+    /// [`binding_array_block_struct`]: Self::binding_array_block_struct
+    fn write_load(
+        &mut self,
+        pointer: Handle<crate::Expression>,
+        result_type_id: Word,
+        load_type_id: Word,
+        block: &mut Block,
+    ) -> Result<Word, Error> {
+        if let Some((condition, accept, reject)) = self.legalized_pointer_select(pointer) {
+            // Without variable pointers, load through each pointer on its
+            // own branch, and merge the values.
+            let condition_id = self.cached[condition];
+            let mut selection = Selection::start(block, load_type_id);
+            let false_label = selection.if_else(self, condition_id);
+            let accept_id =
+                self.write_load(accept, result_type_id, load_type_id, selection.block())?;
+            selection.otherwise(self, false_label, accept_id);
+            let reject_id =
+                self.write_load(reject, result_type_id, load_type_id, selection.block())?;
+            return Ok(selection.finish(self, reject_id));
+        }
+
+        let loaded_id = match self.write_expression_pointer(pointer, block, None)? {
+            ExpressionPointer::Ready { pointer_id } => {
+                let id = self.gen_id();
+                let atomic_space =
+                    match *self.fun_info[pointer].ty.inner_with(&self.ir_module.types) {
+                        crate::TypeInner::Pointer { base, space } => {
+                            match self.ir_module.types[base].inner {
+                                crate::TypeInner::Atomic { .. } => Some(space),
+                                _ => None,
+                            }
+                        }
+                        _ => None,
+                    };
+                let instruction = if let Some(space) = atomic_space {
+                    let (semantics, scope) = space.to_spirv_semantics_and_scope();
+                    let scope_constant_id = self.get_scope_constant(scope as u32);
+                    let semantics_id = self.get_index_constant(semantics.bits());
+                    Instruction::atomic_load(
+                        result_type_id,
+                        id,
+                        pointer_id,
+                        scope_constant_id,
+                        semantics_id,
+                    )
+                } else {
+                    let memory_access = self.memory_access(pointer, false);
+                    Instruction::load(load_type_id, id, pointer_id, memory_access)
+                };
+                block.body.push(instruction);
+                id
+            }
+            ExpressionPointer::Conditional { condition, access } => {
+                //TODO: support atomics?
+                let memory_access = self.memory_access(pointer, false);
+                self.write_conditional_indexed_load(
+                    load_type_id,
+                    condition,
+                    block,
+                    move |id_gen, block| {
+                        // The in-bounds path. Perform the access and the load.
+                        let pointer_id = access.result_id.unwrap();
+                        let value_id = id_gen.next();
+                        block.body.push(access);
+                        block.body.push(Instruction::load(
+                            load_type_id,
+                            value_id,
+                            pointer_id,
+                            memory_access,
+                        ));
+                        value_id
+                    },
+                )
+            }
+        };
+        Ok(loaded_id)
+    }
+
+    /// Store `value_id` to the location `pointer` points to.
+    fn write_store(
+        &mut self,
+        pointer: Handle<crate::Expression>,
+        value_id: Word,
+        block: &mut Block,
+    ) -> Result<(), Error> {
+        if let Some((condition, accept, reject)) = self.legalized_pointer_select(pointer) {
+            // Without variable pointers, store through each pointer on its
+            // own branch.
+            let condition_id = self.cached[condition];
+            let mut selection = Selection::start(block, ());
+            let false_label = selection.if_else(self, condition_id);
+            self.write_store(accept, value_id, selection.block())?;
+            selection.otherwise(self, false_label, ());
+            self.write_store(reject, value_id, selection.block())?;
+            selection.finish(self, ());
+            return Ok(());
+        }
+
+        match self.write_expression_pointer(pointer, block, None)? {
+            ExpressionPointer::Ready { pointer_id } => {
+                let atomic_space =
+                    match *self.fun_info[pointer].ty.inner_with(&self.ir_module.types) {
+                        crate::TypeInner::Pointer { base, space } => {
+                            match self.ir_module.types[base].inner {
+                                crate::TypeInner::Atomic { .. } => Some(space),
+                                _ => None,
+                            }
+                        }
+                        _ => None,
+                    };
+                let instruction = if let Some(space) = atomic_space {
+                    let (semantics, scope) = space.to_spirv_semantics_and_scope();
+                    let scope_constant_id = self.get_scope_constant(scope as u32);
+                    let semantics_id = self.get_index_constant(semantics.bits());
+                    Instruction::atomic_store(pointer_id, scope_constant_id, semantics_id, value_id)
+                } else {
+                    let memory_access = self.memory_access(pointer, true);
+                    Instruction::store(pointer_id, value_id, memory_access)
+                };
+                block.body.push(instruction);
+            }
+            ExpressionPointer::Conditional { condition, access } => {
+                let memory_access = self.memory_access(pointer, true);
+                let mut selection = Selection::start(block, ());
+                selection.if_true(self, condition, ());
+
+                // The in-bounds path. Perform the access and the store.
+                let pointer_id = access.result_id.unwrap();
+                selection.block().body.push(access);
+                selection.block().body.push(Instruction::store(
+                    pointer_id,
+                    value_id,
+                    memory_access,
+                ));
+
+                // Finish the in-bounds block and start the merge block. This
+                // is the block we'll leave current on return.
+                selection.finish(self, ());
+            }
+        }
+        Ok(())
+    }
+
+    /// Write a [`Select`] between two pointers as an `OpSelect`, if variable
+    /// pointers are available for them.
+    ///
+    /// Otherwise, return 0. [`write_load`] and [`write_store`] then choose
+    /// between the two pointers with a branch instead, and any other use of
+    /// the selected pointer is an error.
+    ///
+    /// [`Select`]: crate::Expression::Select
+    /// [`write_load`]: Self::write_load
+    /// [`write_store`]: Self::write_store
+    fn write_pointer_select(
+        &mut self,
+        expr_handle: Handle<crate::Expression>,
+        condition: Handle<crate::Expression>,
+        accept: Handle<crate::Expression>,
+        reject: Handle<crate::Expression>,
+        block: &mut Block,
+    ) -> Result<Word, Error> {
+        let space = self.fun_info[expr_handle]
+            .ty
+            .inner_with(&self.ir_module.types)
+            .pointer_space()
+            .unwrap();
+        if !self.writer.use_variable_pointers(space) {
+            return Ok(0);
+        }
+
+        let mut operand =
+            |this: &mut Self, pointer| match this.write_expression_pointer(pointer, block, None)? {
+                ExpressionPointer::Ready { pointer_id } => Ok(pointer_id),
+                ExpressionPointer::Conditional { .. } => Err(Error::FeatureNotImplemented(
+                    "selecting between bounds-checked pointers",
+                )),
+            };
+        let accept_id = operand(self, accept)?;
+        let reject_id = operand(self, reject)?;
+
+        let result_type_id = self.get_expression_type_id(&self.fun_info[expr_handle].ty);
+        let id = self.gen_id();
+        block.body.push(Instruction::select(
+            result_type_id,
+            id,
+            self.cached[condition],
+            accept_id,
+            reject_id,
+        ));
+        Ok(id)
+    }
+
+    /// Return the error for a use of a selected pointer that needs variable
+    /// pointers, which `expr_handle` couldn't have.
+    fn pointer_select_error(&self, expr_handle: Handle<crate::Expression>) -> Error {
+        const WHAT: &str = "a selected pointer other than to load or store through it";
+        match self.fun_info[expr_handle]
+            .ty
+            .inner_with(&self.ir_module.types)
+            .pointer_space()
+        {
+            Some(crate::AddressSpace::Storage { .. }) => Error::MissingCapabilities(
+                WHAT,
+                vec![
+                    spirv::Capability::VariablePointersStorageBuffer,
+                    spirv::Capability::VariablePointers,
+                ],
+            ),
+            Some(crate::AddressSpace::WorkGroup) => {
+                Error::MissingCapabilities(WHAT, vec![spirv::Capability::VariablePointers])
+            }
+            _ => Error::FeatureNotImplemented(
+                "using a selected pointer other than to load or store through it, \
+                 outside of storage or workgroup memory",
+            ),
+        }
+    }
+
+    /// If `pointer` is a [`Select`] between pointers that we couldn't emit
+    /// as an `OpSelect`, return its condition and operands.
+    ///
+    /// [`Select`]: crate::Expression::Select
+    fn legalized_pointer_select(
+        &self,
+        pointer: Handle<crate::Expression>,
+    ) -> Option<(
+        Handle<crate::Expression>,
+        Handle<crate::Expression>,
+        Handle<crate::Expression>,
+    )> {
+        match self.ir_function.expressions[pointer] {
+            crate::Expression::Select {
+                condition,
+                accept,
+                reject,
+            } if self.cached.ids[pointer.index()] == 0 => Some((condition, accept, reject)),
+            _ => None,
+        }
+    }
+
     /// Build an `OpAccessChain` instruction.
     ///
     /// Emit any needed bounds-checking expressions to `block`.
@@ -1451,7 +1868,18 @@ impl<'w> BlockContext<'w> {
                 Some(ty) => ty,
                 None => LookupType::Handle(ty_handle),
             },
-            TypeResolution::Value(ref inner) => LookupType::Local(make_local(inner).unwrap()),
+            TypeResolution::Value(ref inner) => {
+                let local = match (
+                    make_local(inner).unwrap(),
+                    self.binding_array_block_struct(expr_handle),
+                ) {
+                    (LocalType::Pointer { class, .. }, Some(base)) => {
+                        LocalType::PointerToBlockStruct { base, class }
+                    }
+                    (local, _) => local,
+                };
+                LookupType::Local(local)
+            }
         };
         let result_type_id = self.get_type_id(result_lookup_ty);
 
@@ -1534,6 +1962,10 @@ impl<'w> BlockContext<'w> {
                 crate::Expression::FunctionArgument(index) => {
                     break self.function.parameter_id(index);
                 }
+                crate::Expression::Select { .. } => match self.cached.ids[expr_handle.index()] {
+                    0 => return Err(self.pointer_select_error(expr_handle)),
+                    id => break id,
+                },
                 ref other => unimplemented!("Unexpected pointer expression {:?}", other),
             }
         };
@@ -1547,7 +1979,7 @@ impl<'w> BlockContext<'w> {
             )
         } else {
             self.temp_list.reverse();
-            let pointer_id = self.gen_id();
+            let pointer_id = self.writer.id_gen.next();
             let access =
                 Instruction::access_chain(result_type_id, pointer_id, root_id, &self.temp_list);
 
@@ -1575,6 +2007,76 @@ impl<'w> BlockContext<'w> {
         Ok(expr_pointer)
     }
 
+    /// Return the struct type `pointer` points to, if it is an element of a
+    /// binding array of buffers.
+    ///
+    /// Such elements have the [`LookupType::BlockStruct`] copy of the struct
+    /// as their SPIR-V type, rather than the arena type, so values loaded
+    /// from or stored to them need to be converted with
+    /// [`write_struct_copy`](Self::write_struct_copy).
+    fn binding_array_block_struct(
+        &self,
+        pointer: Handle<crate::Expression>,
+    ) -> Option<Handle<crate::Type>> {
+        let base = match self.ir_function.expressions[pointer] {
+            crate::Expression::Access { base, .. }
+            | crate::Expression::AccessIndex { base, .. } => base,
+            _ => return None,
+        };
+        let var_handle = match self.ir_function.expressions[base] {
+            crate::Expression::GlobalVariable(var_handle) => var_handle,
+            _ => return None,
+        };
+        let types = &self.ir_module.types;
+        match types[self.ir_module.global_variables[var_handle].ty].inner {
+            crate::TypeInner::BindingArray { base, .. } => match types[base].inner {
+                crate::TypeInner::Struct { .. } => Some(base),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Copy the struct value `value_id` member by member into a new value of
+    /// type `result_type_id`.
+    ///
+    /// `struct_ty` and `result_type_id` must be the arena struct and its
+    /// [`LookupType::BlockStruct`] copy, in either order.
+    fn write_struct_copy(
+        &mut self,
+        struct_ty: Handle<crate::Type>,
+        value_id: Word,
+        result_type_id: Word,
+        block: &mut Block,
+    ) -> Word {
+        let ir_module = self.ir_module;
+        let members = match ir_module.types[struct_ty].inner {
+            crate::TypeInner::Struct { ref members, .. } => members,
+            _ => unreachable!(),
+        };
+
+        let mut member_ids = Vec::with_capacity(members.len());
+        for (index, member) in members.iter().enumerate() {
+            let member_type_id = self.get_type_id(LookupType::Handle(member.ty));
+            let member_id = self.writer.id_gen.next();
+            block.body.push(Instruction::composite_extract(
+                member_type_id,
+                member_id,
+                value_id,
+                &[index as u32],
+            ));
+            member_ids.push(member_id);
+        }
+
+        let id = self.gen_id();
+        block.body.push(Instruction::composite_construct(
+            result_type_id,
+            id,
+            &member_ids,
+        ));
+        id
+    }
+
     /// Build the instructions for matrix - matrix column operations
     #[allow(clippy::too_many_arguments)]
     fn write_matrix_matrix_column_op(
@@ -1731,6 +2233,431 @@ impl<'w> BlockContext<'w> {
         }
     }
 
+    /// Build the instructions for `dot4I8Packed` or `dot4U8Packed`, without
+    /// the integer dot product instructions.
+    ///
+    /// Each byte of the packed operands is extracted with `OpBitFieldSExtract`
+    /// or `OpBitFieldUExtract`, which takes care of sign extension, and the
+    /// products are summed as in [`write_dot_product`].
+    ///
+    /// [`write_dot_product`]: BlockContext::write_dot_product
+    fn write_dot4_packed(
+        &mut self,
+        result_id: Word,
+        result_type_id: Word,
+        arg0_id: Word,
+        arg1_id: Word,
+        signed: bool,
+        block: &mut Block,
+    ) {
+        let (extract_op, operands) = if signed {
+            // `OpBitFieldSExtract` returns the type of its base, so
+            // reinterpret the operands as `i32` first.
+            let mut bitcast = |arg_id| {
+                let id = self.gen_id();
+                block.body.push(Instruction::unary(
+                    spirv::Op::Bitcast,
+                    result_type_id,
+                    id,
+                    arg_id,
+                ));
+                id
+            };
+            let operands = [bitcast(arg0_id), bitcast(arg1_id)];
+            (spirv::Op::BitFieldSExtract, operands)
+        } else {
+            (spirv::Op::BitFieldUExtract, [arg0_id, arg1_id])
+        };
+
+        let count_id = self.get_index_constant(8);
+        let mut partial_sum = self.writer.get_constant_null(result_type_id);
+        for index in 0..4 {
+            let offset_id = self.get_index_constant(index * 8);
+            let [a_id, b_id] = operands.map(|operand_id| {
+                let id = self.gen_id();
+                block.body.push(Instruction::ternary(
+                    extract_op,
+                    result_type_id,
+                    id,
+                    operand_id,
+                    offset_id,
+                    count_id,
+                ));
+                id
+            });
+            let prod_id = self.gen_id();
+            block.body.push(Instruction::binary(
+                spirv::Op::IMul,
+                result_type_id,
+                prod_id,
+                a_id,
+                b_id,
+            ));
+
+            let id = if index == 3 { result_id } else { self.gen_id() };
+            block.body.push(Instruction::binary(
+                spirv::Op::IAdd,
+                result_type_id,
+                id,
+                partial_sum,
+                prod_id,
+            ));
+            partial_sum = id;
+        }
+    }
+
+    /// If `expr` is a constant expression with a scalar value, return it.
+    ///
+    /// Override-expressions are not considered constant here, since their
+    /// value isn't known until pipeline creation time.
+    fn constant_literal(&self, expr: Handle<crate::Expression>) -> Option<crate::Literal> {
+        if !self.expression_constness.is_const(expr) {
+            return None;
+        }
+        if let crate::Expression::Constant(constant) = self.ir_function.expressions[expr] {
+            if self.ir_module.constants[constant].r#override != crate::Override::None {
+                return None;
+            }
+        }
+        self.ir_module
+            .to_ctx()
+            .eval_expr_to_literal_from(expr, &self.ir_function.expressions)
+    }
+
+    /// If `selector` has a known value, return the index in `cases` of the
+    /// case that a `Switch` on it would jump to.
+    fn constant_switch_case(
+        &self,
+        selector: Handle<crate::Expression>,
+        cases: &[crate::SwitchCase],
+    ) -> Option<usize> {
+        let value = match self.constant_literal(selector)? {
+            crate::Literal::I32(value) => crate::SwitchValue::I32(value),
+            crate::Literal::U32(value) => crate::SwitchValue::U32(value),
+            _ => return None,
+        };
+        cases
+            .iter()
+            .position(|case| case.value == value)
+            .or_else(|| {
+                cases
+                    .iter()
+                    .position(|case| case.value == crate::SwitchValue::Default)
+            })
+    }
+
+    /// Recognize a run of per-component stores at the start of `statements`.
+    ///
+    /// Front ends lower a swizzled store like `v.xz = value` into a `Store` of
+    /// each component of `value` through an `AccessIndex` on `v`, each preceded
+    /// by an `Emit` of those two expressions. (The first `Emit` may cover
+    /// other expressions too, like the computation of `value`.) Written one by
+    /// one, each of those takes an `OpCompositeExtract`, an `OpAccessChain`
+    /// and an `OpStore`. If we spot such a run, [`write_swizzle_store`] can
+    /// instead load the vector once, mix in the new components with a single
+    /// `OpVectorShuffle`, and store it back.
+    ///
+    /// [`write_swizzle_store`]: Self::write_swizzle_store
+    fn find_swizzle_store(&self, statements: &[Statement]) -> Option<SwizzleStore> {
+        let mut store: Option<SwizzleStore> = None;
+        let mut position = 0;
+        loop {
+            let emitted = match statements.get(position) {
+                Some(&crate::Statement::Emit(ref range)) => {
+                    position += 1;
+                    Some(range.clone())
+                }
+                _ => None,
+            };
+            let (dst, src) = match statements.get(position) {
+                Some(&crate::Statement::Store { pointer, value }) => (pointer, value),
+                _ => break,
+            };
+            let (
+                &crate::Expression::AccessIndex {
+                    base: pointer,
+                    index: dst_index,
+                },
+                &crate::Expression::AccessIndex {
+                    base: value,
+                    index: src_index,
+                },
+            ) = (
+                &self.ir_function.expressions[dst],
+                &self.ir_function.expressions[src],
+            )
+            else {
+                break;
+            };
+            // The components we extract must not be needed by anything else,
+            // since we're not going to emit them. Other expressions emitted
+            // between the stores would have to see the earlier ones, so
+            // don't bother with those.
+            if self.fun_info[src].ref_count != 1
+                || self.ir_function.named_expressions.contains_key(&src)
+                || (store.is_some()
+                    && emitted.is_some_and(|mut range| range.any(|h| h != dst && h != src)))
+            {
+                break;
+            }
+
+            match store {
+                Some(ref mut store) => {
+                    if store.pointer != pointer
+                        || store.value != value
+                        || store.components.iter().any(|&(d, _)| d == dst_index)
+                    {
+                        break;
+                    }
+                    store.components.push((dst_index, src_index));
+                    store.sources.push(src);
+                    store.statement_count = position + 1;
+                }
+                None => {
+                    let types = &self.ir_module.types;
+                    let is_vector_pointer = match *self.fun_info[pointer].ty.inner_with(types) {
+                        crate::TypeInner::Pointer { base, .. } => {
+                            matches!(types[base].inner, crate::TypeInner::Vector { .. })
+                        }
+                        crate::TypeInner::ValuePointer { size, .. } => size.is_some(),
+                        _ => false,
+                    };
+                    let is_vector_value = matches!(
+                        *self.fun_info[value].ty.inner_with(types),
+                        crate::TypeInner::Vector { .. }
+                    );
+                    if !is_vector_pointer || !is_vector_value {
+                        break;
+                    }
+                    let mut components = ArrayVec::new();
+                    components.push((dst_index, src_index));
+                    let mut sources = ArrayVec::new();
+                    sources.push(src);
+                    store = Some(SwizzleStore {
+                        pointer,
+                        value,
+                        components,
+                        sources,
+                        statement_count: position + 1,
+                    });
+                }
+            }
+            position += 1;
+        }
+
+        // A single component is best stored through an access chain.
+        store.filter(|store| store.components.len() > 1)
+    }
+
+    /// Write a [`SwizzleStore`] as a load, an `OpVectorShuffle`, and a store.
+    ///
+    /// The `statements` are those the run covers. If every component of the
+    /// destination is overwritten, skip the load and shuffle the source value
+    /// with itself.
+    fn write_swizzle_store(
+        &mut self,
+        store: &SwizzleStore,
+        statements: &[Statement],
+        block: &mut Block,
+    ) -> Result<(), Error> {
+        for statement in statements {
+            if let Statement::Emit(ref range) = *statement {
+                for handle in range.clone() {
+                    if !self.expression_constness.is_const(handle)
+                        && !store.sources.contains(&handle)
+                    {
+                        self.cache_expression_value(handle, block)?;
+                    }
+                }
+            }
+        }
+
+        let (size, scalar) = match *self.fun_info[store.pointer]
+            .ty
+            .inner_with(&self.ir_module.types)
+        {
+            crate::TypeInner::Pointer { base, .. } => match self.ir_module.types[base].inner {
+                crate::TypeInner::Vector { size, scalar } => (size, scalar),
+                _ => unreachable!(),
+            },
+            crate::TypeInner::ValuePointer {
+                size: Some(size),
+                scalar,
+                ..
+            } => (size, scalar),
+            _ => unreachable!(),
+        };
+        let vector_type_id = self.get_type_id(LookupType::Local(LocalType::Value {
+            vector_size: Some(size),
+            scalar,
+            pointer_space: None,
+        }));
+        let value_id = self.cached[store.value];
+        let full = store.components.len() == size as usize;
+
+        // In the second operand of `OpVectorShuffle`, components are numbered
+        // after those of the first.
+        let offset = if full { 0 } else { size as u32 };
+        let components: ArrayVec<Word, 4> = (0..size as u32)
+            .map(
+                |c| match store.components.iter().find(|&&(dst, _)| dst == c) {
+                    Some(&(_, src)) => offset + src,
+                    None => c,
+                },
+            )
+            .collect();
+
+        let load_access = self.memory_access(store.pointer, false);
+        let store_access = self.memory_access(store.pointer, true);
+        let write = |this: &mut Self, block: &mut Block, pointer_id: Word| {
+            let old_id = if full {
+                value_id
+            } else {
+                let id = this.gen_id();
+                block.body.push(Instruction::load(
+                    vector_type_id,
+                    id,
+                    pointer_id,
+                    load_access,
+                ));
+                id
+            };
+            let new_id = this.gen_id();
+            block.body.push(Instruction::vector_shuffle(
+                vector_type_id,
+                new_id,
+                old_id,
+                value_id,
+                &components,
+            ));
+            block
+                .body
+                .push(Instruction::store(pointer_id, new_id, store_access));
+        };
+
+        match self.write_expression_pointer(store.pointer, block, None)? {
+            ExpressionPointer::Ready { pointer_id } => write(self, block, pointer_id),
+            ExpressionPointer::Conditional { condition, access } => {
+                let mut selection = Selection::start(block, ());
+                selection.if_true(self, condition, ());
+                let pointer_id = access.result_id.unwrap();
+                selection.block().body.push(access);
+                write(self, selection.block(), pointer_id);
+                selection.finish(self, ());
+            }
+        }
+        Ok(())
+    }
+
+    /// Recognize a copy of a whole struct or array at the start of `statements`.
+    ///
+    /// Written literally, `a = b` loads all of `b` into a value and stores it
+    /// back out, which some drivers compile poorly for large composites. If
+    /// the loaded value is used nowhere else, a single `OpCopyMemory` between
+    /// the two pointers does the same thing. The `Emit` may cover other
+    /// expressions too; those are still emitted, but none of them can write
+    /// to memory, so copying when we reach the `Store` reads the same value
+    /// the `Load` would have.
+    ///
+    /// Copies that need memory operands, such as those from `@coherent`
+    /// storage under the Vulkan memory model, are left as a load and a store.
+    fn find_copy_store(&self, statements: &[Statement]) -> Option<CopyStore> {
+        let (range, pointer, load) = match *statements {
+            [Statement::Emit(ref range), Statement::Store { pointer, value }, ..] => {
+                (range, pointer, value)
+            }
+            _ => return None,
+        };
+        let crate::Expression::Load { pointer: source } = self.ir_function.expressions[load] else {
+            return None;
+        };
+        if !range.clone().any(|handle| handle == load)
+            || self.fun_info[load].ref_count != 1
+            || self.ir_function.named_expressions.contains_key(&load)
+        {
+            return None;
+        }
+        match *self.fun_info[load].ty.inner_with(&self.ir_module.types) {
+            crate::TypeInner::Struct { .. } | crate::TypeInner::Array { .. } => {}
+            _ => return None,
+        }
+        if !self.is_unconditional_pointer(pointer) || !self.is_unconditional_pointer(source) {
+            return None;
+        }
+        if !self.memory_decorations(pointer).is_empty()
+            || !self.memory_decorations(source).is_empty()
+        {
+            return None;
+        }
+        Some(CopyStore {
+            pointer,
+            load,
+            source,
+        })
+    }
+
+    /// Return true if [`write_expression_pointer`] always produces an
+    /// [`ExpressionPointer::Ready`] for `pointer`, and no block struct
+    /// wrapper is involved.
+    ///
+    /// [`write_expression_pointer`]: Self::write_expression_pointer
+    fn is_unconditional_pointer(&self, mut pointer: Handle<crate::Expression>) -> bool {
+        if self.binding_array_block_struct(pointer).is_some() {
+            return false;
+        }
+        loop {
+            pointer = match self.ir_function.expressions[pointer] {
+                crate::Expression::AccessIndex { base, .. } => base,
+                crate::Expression::Access { base, .. } => {
+                    if self
+                        .writer
+                        .bounds_check_policies
+                        .contains(crate::proc::BoundsCheckPolicy::ReadZeroSkipWrite)
+                    {
+                        return false;
+                    }
+                    base
+                }
+                crate::Expression::GlobalVariable(_)
+                | crate::Expression::LocalVariable(_)
+                | crate::Expression::FunctionArgument(_) => return true,
+                _ => return false,
+            }
+        }
+    }
+
+    /// Write a [`CopyStore`] as a single `OpCopyMemory`.
+    ///
+    /// The `emit` statement is the `Emit` covering the `Load`.
+    fn write_copy_store(
+        &mut self,
+        copy: &CopyStore,
+        emit: &Statement,
+        block: &mut Block,
+    ) -> Result<(), Error> {
+        if let Statement::Emit(ref range) = *emit {
+            for handle in range.clone() {
+                if !self.expression_constness.is_const(handle) && handle != copy.load {
+                    self.cache_expression_value(handle, block)?;
+                }
+            }
+        }
+
+        let mut ready_pointer =
+            |this: &mut Self, pointer| match this.write_expression_pointer(pointer, block, None)? {
+                ExpressionPointer::Ready { pointer_id } => Ok(pointer_id),
+                ExpressionPointer::Conditional { .. } => Err(Error::Validation(
+                    "composite copy through a bounds-checked pointer",
+                )),
+            };
+        let source_id = ready_pointer(self, copy.source)?;
+        let target_id = ready_pointer(self, copy.pointer)?;
+        block
+            .body
+            .push(Instruction::copy_memory(target_id, source_id));
+        Ok(())
+    }
+
     pub(super) fn write_block(
         &mut self,
         label_id: Word,
@@ -1740,7 +2667,12 @@ impl<'w> BlockContext<'w> {
         debug_info: Option<&DebugInfoInner>,
     ) -> Result<(), Error> {
         let mut block = Block::new(label_id);
-        for (statement, span) in naga_block.span_iter() {
+        let mut skip = 0;
+        for (index, (statement, span)) in naga_block.span_iter().enumerate() {
+            if skip != 0 {
+                skip -= 1;
+                continue;
+            }
             if let (Some(debug_info), false) = (
                 debug_info,
                 matches!(
@@ -1750,6 +2682,7 @@ impl<'w> BlockContext<'w> {
                         | Statement::Continue
                         | Statement::Kill
                         | Statement::Return { .. }
+                        | Statement::DebugMarker(_)
                         | Statement::Loop { .. })
                 ),
             ) {
@@ -1760,6 +2693,19 @@ impl<'w> BlockContext<'w> {
                     loc.line_position,
                 ));
             };
+            if let crate::Statement::Emit(_) | crate::Statement::Store { .. } = *statement {
+                if let Some(store) = self.find_swizzle_store(&naga_block[index..]) {
+                    let statements = &naga_block[index..index + store.statement_count];
+                    self.write_swizzle_store(&store, statements, &mut block)?;
+                    skip = store.statement_count - 1;
+                    continue;
+                }
+                if let Some(copy) = self.find_copy_store(&naga_block[index..]) {
+                    self.write_copy_store(&copy, statement, &mut block)?;
+                    skip = 1;
+                    continue;
+                }
+            }
             match *statement {
                 crate::Statement::Emit(ref range) => {
                     for handle in range.clone() {
@@ -1788,7 +2734,30 @@ impl<'w> BlockContext<'w> {
                     condition,
                     ref accept,
                     ref reject,
+                    hint: _,
                 } => {
+                    // If the condition is a known boolean, only the live arm
+                    // can ever execute, so don't generate the other one at all.
+                    if let Some(crate::Literal::Bool(value)) = self.constant_literal(condition) {
+                        let live = if value { accept } else { reject };
+                        if !live.is_empty() {
+                            let scope_id = self.gen_id();
+                            self.function.consume(block, Instruction::branch(scope_id));
+
+                            let merge_id = self.gen_id();
+                            self.write_block(
+                                scope_id,
+                                live,
+                                BlockExit::Branch { target: merge_id },
+                                loop_context,
+                                debug_info,
+                            )?;
+
+                            block = Block::new(merge_id);
+                        }
+                        continue;
+                    }
+
                     let condition_id = self.cached[condition];
 
                     let merge_id = self.gen_id();
@@ -1850,6 +2819,47 @@ impl<'w> BlockContext<'w> {
                         spirv::SelectionControl::NONE,
                     ));
 
+                    let inner_context = LoopContext {
+                        break_id: Some(merge_id),
+                        ..loop_context
+                    };
+
+                    // If the selector is a known value, we can tell which case
+                    // will be taken. Keep the `OpSwitch` so that `break`
+                    // statements still have a construct to exit, but give it
+                    // only a default target, and skip the dead cases.
+                    if let Some(live_index) = self.constant_switch_case(selector, cases) {
+                        let live_cases = cases[live_index..]
+                            .iter()
+                            .scan(true, |reachable, case| {
+                                let this = (*reachable).then_some(case);
+                                *reachable = case.fall_through;
+                                this
+                            })
+                            .collect::<Vec<_>>();
+                        let label_ids =
+                            live_cases.iter().map(|_| self.gen_id()).collect::<Vec<_>>();
+
+                        self.function
+                            .consume(block, Instruction::switch(selector_id, label_ids[0], &[]));
+
+                        for (i, case) in live_cases.into_iter().enumerate() {
+                            let case_finish_id = label_ids.get(i + 1).copied().unwrap_or(merge_id);
+                            self.write_block(
+                                label_ids[i],
+                                &case.body,
+                                BlockExit::Branch {
+                                    target: case_finish_id,
+                                },
+                                inner_context,
+                                debug_info,
+                            )?;
+                        }
+
+                        block = Block::new(merge_id);
+                        continue;
+                    }
+
                     let mut default_id = None;
                     // id of previous empty fall-through case
                     let mut last_id = None;
@@ -1889,11 +2899,6 @@ impl<'w> BlockContext<'w> {
                         Instruction::switch(selector_id, default_id, &raw_cases),
                     );
 
-                    let inner_context = LoopContext {
-                        break_id: Some(merge_id),
-                        ..loop_context
-                    };
-
                     for (i, (case, label_id)) in cases
                         .iter()
                         .zip(case_ids.iter())
@@ -1922,6 +2927,7 @@ impl<'w> BlockContext<'w> {
                     ref body,
                     ref continuing,
                     break_if,
+                    hint: _,
                 } => {
                     let preamble_id = self.gen_id();
                     self.function
@@ -2002,6 +3008,11 @@ impl<'w> BlockContext<'w> {
                 crate::Statement::Return { value: Some(value) } => {
                     let value_id = self.cached[value];
                     let instruction = match self.function.entry_point_context {
+                        // Task shaders launch mesh shader workgroups instead.
+                        Some(super::EntryPointContext {
+                            task_size_member: Some(index),
+                            ..
+                        }) => self.write_emit_mesh_tasks(value_id, index, &mut block),
                         // If this is an entry point, and we need to return anything,
                         // let's instead store the output variables and return `void`.
                         Some(ref context) => {
@@ -2029,54 +3040,24 @@ impl<'w> BlockContext<'w> {
                 crate::Statement::Barrier(flags) => {
                     self.writer.write_barrier(flags, &mut block);
                 }
+                crate::Statement::MeshFunction(ref fun) => {
+                    self.write_mesh_function(fun, &mut block)?;
+                }
+                crate::Statement::DebugMarker(_) => {}
                 crate::Statement::Store { pointer, value } => {
-                    let value_id = self.cached[value];
-                    match self.write_expression_pointer(pointer, &mut block, None)? {
-                        ExpressionPointer::Ready { pointer_id } => {
-                            let atomic_space = match *self.fun_info[pointer]
-                                .ty
-                                .inner_with(&self.ir_module.types)
-                            {
-                                crate::TypeInner::Pointer { base, space } => {
-                                    match self.ir_module.types[base].inner {
-                                        crate::TypeInner::Atomic { .. } => Some(space),
-                                        _ => None,
-                                    }
-                                }
-                                _ => None,
-                            };
-                            let instruction = if let Some(space) = atomic_space {
-                                let (semantics, scope) = space.to_spirv_semantics_and_scope();
-                                let scope_constant_id = self.get_scope_constant(scope as u32);
-                                let semantics_id = self.get_index_constant(semantics.bits());
-                                Instruction::atomic_store(
-                                    pointer_id,
-                                    scope_constant_id,
-                                    semantics_id,
-                                    value_id,
-                                )
-                            } else {
-                                Instruction::store(pointer_id, value_id, None)
-                            };
-                            block.body.push(instruction);
-                        }
-                        ExpressionPointer::Conditional { condition, access } => {
-                            let mut selection = Selection::start(&mut block, ());
-                            selection.if_true(self, condition, ());
-
-                            // The in-bounds path. Perform the access and the store.
-                            let pointer_id = access.result_id.unwrap();
-                            selection.block().body.push(access);
-                            selection
-                                .block()
-                                .body
-                                .push(Instruction::store(pointer_id, value_id, None));
-
-                            // Finish the in-bounds block and start the merge block. This
-                            // is the block we'll leave current on return.
-                            selection.finish(self, ());
+                    let value_id = match self.binding_array_block_struct(pointer) {
+                        Some(base) => {
+                            let block_struct_id = self.get_type_id(LookupType::BlockStruct(base));
+                            self.write_struct_copy(
+                                base,
+                                self.cached[value],
+                                block_struct_id,
+                                &mut block,
+                            )
                         }
+                        None => self.cached[value],
                     };
+                    self.write_store(pointer, value_id, &mut block)?;
                 }
                 crate::Statement::ImageStore {
                     image,
@@ -2090,9 +3071,32 @@ impl<'w> BlockContext<'w> {
                     result,
                 } => {
                     let id = self.gen_id();
-                    self.temp_list.clear();
+                    // `write_expression_pointer` uses `temp_list` itself.
+                    let mut argument_ids = Vec::with_capacity(arguments.len());
                     for &argument in arguments {
-                        self.temp_list.push(self.cached[argument]);
+                        if self.legalized_pointer_select(argument).is_some() {
+                            return Err(self.pointer_select_error(argument));
+                        }
+                        // Pointers into variables aren't cached, since
+                        // they're usually only needed by loads and stores.
+                        let is_pointer = self.fun_info[argument]
+                            .ty
+                            .inner_with(&self.ir_module.types)
+                            .pointer_space()
+                            .is_some();
+                        let id = if is_pointer {
+                            match self.write_expression_pointer(argument, &mut block, None)? {
+                                ExpressionPointer::Ready { pointer_id } => pointer_id,
+                                ExpressionPointer::Conditional { .. } => {
+                                    return Err(Error::FeatureNotImplemented(
+                                        "passing bounds-checked pointers to functions",
+                                    ))
+                                }
+                            }
+                        } else {
+                            self.cached[argument]
+                        };
+                        argument_ids.push(id);
                     }
 
                     let type_id = match result {
@@ -2107,7 +3111,7 @@ impl<'w> BlockContext<'w> {
                         type_id,
                         id,
                         self.writer.lookup_function[&local_function],
-                        &self.temp_list,
+                        &argument_ids,
                     ));
                 }
                 crate::Statement::Atomic {
@@ -49,6 +49,29 @@ pub(super) fn contains_builtin(
     }
 }
 
+/// Return true if `ty` holds an integer or float scalar `width` bytes wide,
+/// directly or in one of its components, elements, or members.
+pub(super) fn contains_scalar_width(
+    ty: Handle<crate::Type>,
+    arena: &UniqueArena<crate::Type>,
+    width: crate::Bytes,
+) -> bool {
+    use crate::TypeInner as Ti;
+    match arena[ty].inner {
+        Ti::Scalar(scalar)
+        | Ti::Vector { scalar, .. }
+        | Ti::Matrix { scalar, .. }
+        | Ti::Atomic(scalar) => scalar.kind != crate::ScalarKind::Bool && scalar.width == width,
+        Ti::Array { base, .. } | Ti::BindingArray { base, .. } => {
+            contains_scalar_width(base, arena, width)
+        }
+        Ti::Struct { ref members, .. } => members
+            .iter()
+            .any(|member| contains_scalar_width(member.ty, arena, width)),
+        _ => false,
+    }
+}
+
 impl crate::AddressSpace {
     pub(super) const fn to_spirv_semantics_and_scope(
         self,
@@ -117,6 +117,7 @@ impl Load {
             crate::ImageClass::Depth { .. } | crate::ImageClass::Sampled { .. } => {
                 spirv::Op::ImageFetch
             }
+            crate::ImageClass::External => unreachable!(),
         };
 
         // `OpImageRead` and `OpImageFetch` instructions produce vec4<f32>
@@ -727,6 +728,38 @@ impl<'w> BlockContext<'w> {
         Ok(selection.finish(self, texel_id))
     }
 
+    /// Return the id of the sample index `sample` as an `i32`.
+    ///
+    /// The sample index may be either signed or unsigned, but the bounds
+    /// checks in [`write_restricted_coordinates`] and the `ImageQuerySamples`
+    /// result they compare it against are signed, so bitcast unsigned indices.
+    ///
+    /// [`write_restricted_coordinates`]: BlockContext::write_restricted_coordinates
+    fn write_sample_index(&mut self, sample: Handle<crate::Expression>, block: &mut Block) -> Word {
+        let sample_id = self.cached[sample];
+        match *self.fun_info[sample].ty.inner_with(&self.ir_module.types) {
+            crate::TypeInner::Scalar(crate::Scalar {
+                kind: crate::ScalarKind::Uint,
+                ..
+            }) => {
+                let i32_type_id = self.get_type_id(LookupType::Local(LocalType::Value {
+                    vector_size: None,
+                    scalar: crate::Scalar::I32,
+                    pointer_space: None,
+                }));
+                let id = self.gen_id();
+                block.body.push(Instruction::unary(
+                    spirv::Op::Bitcast,
+                    i32_type_id,
+                    id,
+                    sample_id,
+                ));
+                id
+            }
+            _ => sample_id,
+        }
+    }
+
     /// Generate code for an `ImageLoad` expression.
     ///
     /// The arguments are the components of an `Expression::ImageLoad` variant.
@@ -752,7 +785,7 @@ impl<'w> BlockContext<'w> {
         let coordinates = self.write_image_coordinates(coordinate, array_index, block)?;
 
         let level_id = level.map(|expr| self.cached[expr]);
-        let sample_id = sample.map(|expr| self.cached[expr]);
+        let sample_id = sample.map(|expr| self.write_sample_index(expr, block));
 
         // Perform the access, according to the bounds check policy.
         let access_id = match self.writer.bounds_check_policies.image_load {
@@ -996,6 +1029,60 @@ impl<'w> BlockContext<'w> {
         Ok(id)
     }
 
+    /// Query the size of `image_id`, followed by its layer count if it is
+    /// `arrayed`.
+    ///
+    /// Storage and multisampled images have no mipmaps, so they use
+    /// `OpImageQuerySize`. Other images use `OpImageQuerySizeLod`, with
+    /// `level`, or level zero if that is `None`.
+    ///
+    /// Return the ids of the queried vector and its type.
+    fn write_image_query_size(
+        &mut self,
+        image_id: Word,
+        dim_coords: usize,
+        arrayed: bool,
+        class: crate::ImageClass,
+        level: Option<Handle<crate::Expression>>,
+        block: &mut Block,
+    ) -> (Word, Word) {
+        let vector_size = match dim_coords + usize::from(arrayed) {
+            2 => Some(crate::VectorSize::Bi),
+            3 => Some(crate::VectorSize::Tri),
+            4 => Some(crate::VectorSize::Quad),
+            _ => None,
+        };
+        let extended_size_type_id = self.get_type_id(LookupType::Local(LocalType::Value {
+            vector_size,
+            scalar: crate::Scalar::U32,
+            pointer_space: None,
+        }));
+
+        let level_id = if class.is_mipmapped() {
+            Some(match level {
+                Some(expr) => self.cached[expr],
+                None => self.get_index_constant(0),
+            })
+        } else {
+            None
+        };
+        let query_op = if level_id.is_some() {
+            spirv::Op::ImageQuerySizeLod
+        } else {
+            spirv::Op::ImageQuerySize
+        };
+
+        let id_extended = self.gen_id();
+        let mut inst =
+            Instruction::image_query(query_op, extended_size_type_id, id_extended, image_id);
+        if let Some(level_id) = level_id {
+            inst.add_operand(level_id);
+        }
+        block.body.push(inst);
+
+        (id_extended, extended_size_type_id)
+    }
+
     /// Generate code for an `ImageQuery` expression.
     ///
     /// The arguments are the components of an `Expression::ImageQuery` variant.
@@ -1006,7 +1093,7 @@ impl<'w> BlockContext<'w> {
         query: crate::ImageQuery,
         block: &mut Block,
     ) -> Result<Word, Error> {
-        use crate::{ImageClass as Ic, ImageDimension as Id, ImageQuery as Iq};
+        use crate::{ImageDimension as Id, ImageQuery as Iq};
 
         let image_id = self.get_handle_id(image);
         let image_type = self.fun_info[image].ty.handle().unwrap();
@@ -1024,54 +1111,32 @@ impl<'w> BlockContext<'w> {
         self.writer
             .require_any("image queries", &[spirv::Capability::ImageQuery])?;
 
+        let dim_coords = match dim {
+            Id::D1 => 1,
+            Id::D2 | Id::Cube => 2,
+            Id::D3 => 3,
+        };
+
         let id = match query {
             Iq::Size { level } => {
-                let dim_coords = match dim {
-                    Id::D1 => 1,
-                    Id::D2 | Id::Cube => 2,
-                    Id::D3 => 3,
-                };
-                let array_coords = usize::from(arrayed);
-                let vector_size = match dim_coords + array_coords {
-                    2 => Some(crate::VectorSize::Bi),
-                    3 => Some(crate::VectorSize::Tri),
-                    4 => Some(crate::VectorSize::Quad),
-                    _ => None,
-                };
-                let extended_size_type_id = self.get_type_id(LookupType::Local(LocalType::Value {
-                    vector_size,
-                    scalar: crate::Scalar::U32,
-                    pointer_space: None,
-                }));
-
-                let (query_op, level_id) = match class {
-                    Ic::Sampled { multi: true, .. }
-                    | Ic::Depth { multi: true }
-                    | Ic::Storage { .. } => (spirv::Op::ImageQuerySize, None),
-                    _ => {
-                        let level_id = match level {
-                            Some(expr) => self.cached[expr],
-                            None => self.get_index_constant(0),
-                        };
-                        (spirv::Op::ImageQuerySizeLod, Some(level_id))
-                    }
-                };
-
-                // The ID of the vector returned by SPIR-V, which contains the dimensions
-                // as well as the layer count.
-                let id_extended = self.gen_id();
-                let mut inst = Instruction::image_query(
-                    query_op,
-                    extended_size_type_id,
-                    id_extended,
-                    image_id,
-                );
-                if let Some(expr_id) = level_id {
-                    inst.add_operand(expr_id);
-                }
-                block.body.push(inst);
+                let (id_extended, extended_size_type_id) =
+                    self.write_image_query_size(image_id, dim_coords, arrayed, class, level, block);
 
-                if result_type_id != extended_size_type_id {
+                if result_type_id == extended_size_type_id {
+                    id_extended
+                } else if dim_coords == 1 {
+                    // Only a one-dimensional array image gets here, and
+                    // `OpVectorShuffle` can't produce a scalar.
+                    let id = self.gen_id();
+                    block.body.push(Instruction::composite_extract(
+                        result_type_id,
+                        id,
+                        id_extended,
+                        &[0],
+                    ));
+                    id
+                } else {
+                    // Drop the layer count, which WGSL doesn't include.
                     let id = self.gen_id();
                     let components = match dim {
                         // always pick the first component, and duplicate it for all 3 dimensions
@@ -1085,10 +1150,7 @@ impl<'w> BlockContext<'w> {
                         id_extended,
                         components,
                     ));
-
                     id
-                } else {
-                    id_extended
                 }
             }
             Iq::NumLevels => {
@@ -1103,32 +1165,15 @@ impl<'w> BlockContext<'w> {
                 query_id
             }
             Iq::NumLayers => {
-                let vec_size = match dim {
-                    Id::D1 => crate::VectorSize::Bi,
-                    Id::D2 | Id::Cube => crate::VectorSize::Tri,
-                    Id::D3 => crate::VectorSize::Quad,
-                };
-                let extended_size_type_id = self.get_type_id(LookupType::Local(LocalType::Value {
-                    vector_size: Some(vec_size),
-                    scalar: crate::Scalar::U32,
-                    pointer_space: None,
-                }));
-                let id_extended = self.gen_id();
-                let mut inst = Instruction::image_query(
-                    spirv::Op::ImageQuerySizeLod,
-                    extended_size_type_id,
-                    id_extended,
-                    image_id,
-                );
-                inst.add_operand(self.get_index_constant(0));
-                block.body.push(inst);
+                let (id_extended, _) =
+                    self.write_image_query_size(image_id, dim_coords, arrayed, class, None, block);
 
                 let extract_id = self.gen_id();
                 block.body.push(Instruction::composite_extract(
                     result_type_id,
                     extract_id,
                     id_extended,
-                    &[vec_size as u32 - 1],
+                    &[dim_coords as u32],
                 ));
 
                 extract_id
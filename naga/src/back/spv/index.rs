@@ -98,6 +98,12 @@ impl<'w> BlockContext<'w> {
             Ok(crate::proc::IndexableLength::Known(known_length)) => {
                 Ok(MaybeKnown::Known(known_length))
             }
+            Ok(crate::proc::IndexableLength::Pending(constant)) => {
+                match self.writer.override_ids[constant.index()] {
+                    0 => Err(Error::Validation("array size override")),
+                    length_id => Ok(MaybeKnown::Computed(length_id)),
+                }
+            }
             Ok(crate::proc::IndexableLength::Dynamic) => {
                 let length_id = self.write_runtime_array_length(sequence, block)?;
                 Ok(MaybeKnown::Computed(length_id))
@@ -348,6 +354,12 @@ impl<'w> BlockContext<'w> {
             &self.ir_module.types,
             self.fun_info,
         );
+        log::debug!(
+            "bounds check policy {:?} for {:?}[{:?}]",
+            policy,
+            base,
+            index
+        );
 
         Ok(match policy {
             BoundsCheckPolicy::Restrict => self.write_restricted_index(base, index, block)?,
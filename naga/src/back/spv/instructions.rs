@@ -390,6 +390,32 @@ impl super::Instruction {
         instruction
     }
 
+    pub(super) fn spec_constant_true(result_type_id: Word, id: Word) -> Self {
+        let mut instruction = Self::new(Op::SpecConstantTrue);
+        instruction.set_type(result_type_id);
+        instruction.set_result(id);
+        instruction
+    }
+
+    pub(super) fn spec_constant_false(result_type_id: Word, id: Word) -> Self {
+        let mut instruction = Self::new(Op::SpecConstantFalse);
+        instruction.set_type(result_type_id);
+        instruction.set_result(id);
+        instruction
+    }
+
+    pub(super) fn spec_constant(result_type_id: Word, id: Word, values: &[Word]) -> Self {
+        let mut instruction = Self::new(Op::SpecConstant);
+        instruction.set_type(result_type_id);
+        instruction.set_result(id);
+
+        for value in values {
+            instruction.add_operand(*value);
+        }
+
+        instruction
+    }
+
     pub(super) fn constant_composite(
         result_type_id: Word,
         id: Word,
@@ -428,19 +454,24 @@ impl super::Instruction {
         instruction
     }
 
+    /// `memory_access` holds the memory operands, and the scope id that
+    /// `MakePointerVisible` needs, if set.
     pub(super) fn load(
         result_type_id: Word,
         id: Word,
         pointer_id: Word,
-        memory_access: Option<spirv::MemoryAccess>,
+        memory_access: Option<(spirv::MemoryAccess, Option<Word>)>,
     ) -> Self {
         let mut instruction = Self::new(Op::Load);
         instruction.set_type(result_type_id);
         instruction.set_result(id);
         instruction.add_operand(pointer_id);
 
-        if let Some(memory_access) = memory_access {
+        if let Some((memory_access, scope_id)) = memory_access {
             instruction.add_operand(memory_access.bits());
+            if let Some(scope_id) = scope_id {
+                instruction.add_operand(scope_id);
+            }
         }
 
         instruction
@@ -462,22 +493,34 @@ impl super::Instruction {
         instruction
     }
 
+    /// `memory_access` holds the memory operands, and the scope id that
+    /// `MakePointerAvailable` needs, if set.
     pub(super) fn store(
         pointer_id: Word,
         value_id: Word,
-        memory_access: Option<spirv::MemoryAccess>,
+        memory_access: Option<(spirv::MemoryAccess, Option<Word>)>,
     ) -> Self {
         let mut instruction = Self::new(Op::Store);
         instruction.add_operand(pointer_id);
         instruction.add_operand(value_id);
 
-        if let Some(memory_access) = memory_access {
+        if let Some((memory_access, scope_id)) = memory_access {
             instruction.add_operand(memory_access.bits());
+            if let Some(scope_id) = scope_id {
+                instruction.add_operand(scope_id);
+            }
         }
 
         instruction
     }
 
+    pub(super) fn copy_memory(target_id: Word, source_id: Word) -> Self {
+        let mut instruction = Self::new(Op::CopyMemory);
+        instruction.add_operand(target_id);
+        instruction.add_operand(source_id);
+        instruction
+    }
+
     pub(super) fn atomic_store(
         pointer_id: Word,
         scope_id: Word,
@@ -1016,6 +1059,14 @@ impl super::Instruction {
         instruction
     }
 
+    pub(super) fn emit_mesh_tasks(x_id: Word, y_id: Word, z_id: Word) -> Self {
+        let mut instruction = Self::new(Op::EmitMeshTasksEXT);
+        instruction.add_operand(x_id);
+        instruction.add_operand(y_id);
+        instruction.add_operand(z_id);
+        instruction
+    }
+
     //
     //  Atomic Instructions
     //
@@ -1026,6 +1077,13 @@ impl super::Instruction {
 
     // Barriers
 
+    pub(super) fn set_mesh_outputs(vertex_count_id: Word, primitive_count_id: Word) -> Self {
+        let mut instruction = Self::new(Op::SetMeshOutputsEXT);
+        instruction.add_operand(vertex_count_id);
+        instruction.add_operand(primitive_count_id);
+        instruction
+    }
+
     pub(super) fn control_barrier(
         exec_scope_id: Word,
         mem_scope_id: Word,
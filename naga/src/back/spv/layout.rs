@@ -1,6 +1,6 @@
 use super::{Instruction, LogicalLayout, PhysicalLayout};
 use spirv::{Op, Word, MAGIC_NUMBER};
-use std::iter;
+use std::{iter, ops};
 
 // https://github.com/KhronosGroup/SPIRV-Headers/pull/195
 const GENERATOR: Word = 28;
@@ -53,6 +53,104 @@ impl LogicalLayout {
     }
 }
 
+impl LogicalLayout {
+    /// Reorder `self.declarations` canonically: types first, then constants,
+    /// then global variables, each group ordered by opcode.
+    ///
+    /// Instructions are only moved after everything they refer to, so the
+    /// result is as valid as the original order. Since the original order
+    /// only refers back to earlier instructions, any operand matching an
+    /// earlier result id is treated as a dependency; a literal that happens
+    /// to match just constrains the order a bit more.
+    pub(super) fn sort_declarations(&mut self) {
+        use std::cmp::Reverse;
+        use std::collections::BinaryHeap;
+
+        struct Declaration {
+            range: ops::Range<usize>,
+            /// The group and opcode to sort by.
+            key: (u8, Word),
+            /// How many of the declarations this one depends on haven't been
+            /// emitted yet.
+            pending: usize,
+            dependents: Vec<usize>,
+        }
+
+        let words = &self.declarations;
+        let mut declarations: Vec<Declaration> = Vec::new();
+        let mut result_ids: crate::FastHashMap<Word, usize> = crate::FastHashMap::default();
+        let mut offset = 0;
+        while offset < words.len() {
+            let wc = (words[offset] >> 16).max(1) as usize;
+            let opcode = words[offset] & 0xffff;
+            let (group, result) = match Op::from_u32(opcode) {
+                Some(Op::Variable) => (2, 2),
+                Some(
+                    Op::Constant
+                    | Op::ConstantTrue
+                    | Op::ConstantFalse
+                    | Op::ConstantComposite
+                    | Op::ConstantNull
+                    | Op::ConstantSampler
+                    | Op::SpecConstant
+                    | Op::SpecConstantTrue
+                    | Op::SpecConstantFalse
+                    | Op::SpecConstantComposite
+                    | Op::SpecConstantOp
+                    | Op::Undef,
+                ) => (1, 2),
+                _ => (0, 1),
+            };
+
+            let index = declarations.len();
+            let mut pending = 0;
+            for (i, word) in words[offset..offset + wc].iter().enumerate().skip(1) {
+                if i == result {
+                    continue;
+                }
+                if let Some(&dependency) = result_ids.get(word) {
+                    let dependents = &mut declarations[dependency].dependents;
+                    if dependents.last() != Some(&index) {
+                        dependents.push(index);
+                        pending += 1;
+                    }
+                }
+            }
+
+            if let Some(&id) = words.get(offset + result) {
+                result_ids.insert(id, index);
+            }
+            declarations.push(Declaration {
+                range: offset..offset + wc,
+                key: (group, opcode),
+                pending,
+                dependents: Vec::new(),
+            });
+            offset += wc;
+        }
+
+        let mut ready: BinaryHeap<_> = declarations
+            .iter()
+            .enumerate()
+            .filter(|&(_, declaration)| declaration.pending == 0)
+            .map(|(index, declaration)| Reverse((declaration.key, index)))
+            .collect();
+        let mut sorted = Vec::with_capacity(words.len());
+        while let Some(Reverse((_, index))) = ready.pop() {
+            sorted.extend_from_slice(&words[declarations[index].range.clone()]);
+            for dependent in std::mem::take(&mut declarations[index].dependents) {
+                let declaration = &mut declarations[dependent];
+                declaration.pending -= 1;
+                if declaration.pending == 0 {
+                    ready.push(Reverse((declaration.key, dependent)));
+                }
+            }
+        }
+
+        self.declarations = sorted;
+    }
+}
+
 impl super::recyclable::Recyclable for LogicalLayout {
     fn recycle(self) -> Self {
         Self {
@@ -208,3 +306,45 @@ fn test_logical_layout_in_words() {
         index += wc;
     }
 }
+
+#[test]
+fn test_logical_layout_sort_declarations() {
+    let int = {
+        let mut instruction = Instruction::new(Op::TypeInt);
+        instruction.set_result(1);
+        instruction.add_operands(vec![32, 0]);
+        instruction
+    };
+    let four = {
+        let mut instruction = Instruction::new(Op::Constant);
+        instruction.set_type(1);
+        instruction.set_result(2);
+        instruction.add_operand(4);
+        instruction
+    };
+    let array = {
+        let mut instruction = Instruction::new(Op::TypeArray);
+        instruction.set_result(3);
+        instruction.add_operands(vec![1, 2]);
+        instruction
+    };
+    let float = {
+        let mut instruction = Instruction::new(Op::TypeFloat);
+        instruction.set_result(4);
+        instruction.add_operand(32);
+        instruction
+    };
+
+    let mut layout = LogicalLayout::default();
+    for instruction in [&int, &four, &array, &float] {
+        instruction.to_words(&mut layout.declarations);
+    }
+    layout.sort_declarations();
+
+    // Types go first, but `array` has to wait for the constant it uses.
+    let mut expected = vec![];
+    for instruction in [&int, &float, &four, &array] {
+        instruction.to_words(&mut expected);
+    }
+    assert_eq!(layout.declarations, expected);
+}
@@ -60,8 +60,20 @@ const BITS_PER_BYTE: crate::Bytes = 8;
 
 #[derive(Clone, Debug, Error)]
 pub enum Error {
-    #[error("The requested entry point couldn't be found")]
-    EntryPointNotFound,
+    #[error(
+        "The requested {stage:?} entry point `{name}` couldn't be found; \
+         the module's entry points are: {available}"
+    )]
+    EntryPointNotFound {
+        stage: crate::ShaderStage,
+        name: String,
+        available: crate::back::EntryPointNames,
+    },
+    #[error("The module has more than one {stage:?} entry point named `{name}`")]
+    AmbiguousEntryPoint {
+        stage: crate::ShaderStage,
+        name: String,
+    },
     #[error("target SPIRV-{0}.{1} is not supported")]
     UnsupportedVersion(u8, u8),
     #[error("using {0} requires at least one of the capabilities {1:?}, but none are available")]
@@ -70,6 +82,21 @@ pub enum Error {
     FeatureNotImplemented(&'static str),
     #[error("module is not validated properly: {0}")]
     Validation(&'static str),
+    #[error("resource global `{0}` is used by an entry point but has no binding")]
+    MissingBinding(String),
+    #[error("using {0} requires SPIR-V {1}.{2} or later")]
+    RequiresVersion(&'static str, u8, u8),
+    #[error(
+        "id {id} (member {member:?}) is already decorated {decoration:?} {existing:?}, \
+         so it can't also be decorated {decoration:?} {new:?}"
+    )]
+    ConflictingDecoration {
+        id: Word,
+        member: Option<u32>,
+        decoration: spirv::Decoration,
+        existing: Vec<Word>,
+        new: Vec<Word>,
+    },
 }
 
 #[derive(Default)]
@@ -130,9 +157,33 @@ struct ResultMember {
     built_in: Option<crate::BuiltIn>,
 }
 
+/// One member of a mesh shader's vertex or primitive output struct.
+///
+/// Each member gets its own `Output` variable, an array with one element per
+/// vertex or primitive.
+struct MeshOutputMember {
+    /// The id of the array variable.
+    id: Word,
+    /// The type of the member, and of each element of the array.
+    type_id: Word,
+    /// The type of a pointer to one element of the array.
+    pointer_type_id: Word,
+    built_in: Option<crate::BuiltIn>,
+}
+
 struct EntryPointContext {
     argument_ids: Vec<Word>,
     results: Vec<ResultMember>,
+    /// For a task shader, the index of the result member holding the
+    /// [`MeshTaskSize`] built-in, which is zero if the result is the
+    /// built-in itself.
+    ///
+    /// [`MeshTaskSize`]: crate::BuiltIn::MeshTaskSize
+    task_size_member: Option<u32>,
+    /// For a mesh shader, the variables holding the vertex outputs.
+    mesh_vertices: Vec<MeshOutputMember>,
+    /// For a mesh shader, the variables holding the primitive outputs.
+    mesh_primitives: Vec<MeshOutputMember>,
 }
 
 #[derive(Default)]
@@ -222,6 +273,7 @@ impl LocalImageType {
                 flags: make_flags(false, ImageTypeFlags::empty()),
                 image_format: format.into(),
             },
+            crate::ImageClass::External => unreachable!(),
         }
     }
 }
@@ -238,7 +290,8 @@ impl LocalImageType {
 ///     instructions, requiring deduplication.
 ///
 /// This is not a complete copy of [`TypeInner`]: for example, SPIR-V generation
-/// never synthesizes new struct types, so `LocalType` has nothing for that.
+/// only synthesizes struct types as decorated copies of arena structs, which
+/// [`LookupType::BlockStruct`] covers, so `LocalType` has nothing for that.
 ///
 /// Each `LocalType` variant should be handled identically to its analogous
 /// `TypeInner` variant. You can use the [`make_local`] function to help with
@@ -308,6 +361,11 @@ enum LocalType {
         base: Handle<crate::Type>,
         size: u32,
     },
+    /// A pointer to the [`LookupType::BlockStruct`] copy of the struct `base`.
+    PointerToBlockStruct {
+        base: Handle<crate::Type>,
+        class: spirv::StorageClass,
+    },
     AccelerationStructure,
     RayQuery,
 }
@@ -338,6 +396,16 @@ enum LocalType {
 enum LookupType {
     Handle(Handle<crate::Type>),
     Local(LocalType),
+    /// A copy of the struct type `Handle` decorated with `Block`, used as the
+    /// element type of binding arrays of buffers.
+    ///
+    /// Buffer globals are usually wrapped in a made-up `Block` struct (see
+    /// [`helpers::global_needs_wrapper`]), but binding array elements aren't,
+    /// so they need the decoration on the struct itself. Putting it on the
+    /// arena's struct type would leak it into every other use of that struct,
+    /// such as local variables or members of other structs, which SPIR-V
+    /// forbids.
+    BlockStruct(Handle<crate::Type>),
 }
 
 impl From<LocalType> for LookupType {
@@ -576,6 +644,64 @@ impl BlockContext<'_> {
         self.writer
             .get_constant_scalar(crate::Literal::I32(scope as _))
     }
+
+    /// Return the [`MemoryDecorations`] that loads and stores through
+    /// `pointer` must carry as memory operands.
+    ///
+    /// These are only needed under the Vulkan memory model, when `pointer`
+    /// points into a global with [`MemoryDecorations`]. Under GLSL450, the
+    /// global itself is decorated instead, and this returns an empty set.
+    ///
+    /// [`MemoryDecorations`]: crate::MemoryDecorations
+    fn memory_decorations(
+        &self,
+        mut pointer: Handle<crate::Expression>,
+    ) -> crate::MemoryDecorations {
+        if !self.writer.flags.contains(WriterFlags::VULKAN_MEMORY_MODEL) {
+            return crate::MemoryDecorations::empty();
+        }
+        loop {
+            match self.ir_function.expressions[pointer] {
+                crate::Expression::Access { base, .. }
+                | crate::Expression::AccessIndex { base, .. } => pointer = base,
+                crate::Expression::GlobalVariable(handle) => {
+                    break self.ir_module.global_variables[handle].memory_decorations
+                }
+                _ => break crate::MemoryDecorations::empty(),
+            }
+        }
+    }
+
+    /// Return the memory operands for a load or store through `pointer`.
+    ///
+    /// See [`memory_decorations`](Self::memory_decorations) for when these
+    /// are needed.
+    fn memory_access(
+        &mut self,
+        pointer: Handle<crate::Expression>,
+        store: bool,
+    ) -> Option<(spirv::MemoryAccess, Option<Word>)> {
+        let decorations = self.memory_decorations(pointer);
+        if decorations.is_empty() {
+            return None;
+        }
+
+        let mut access = spirv::MemoryAccess::NON_PRIVATE_POINTER;
+        if decorations.contains(crate::MemoryDecorations::VOLATILE) {
+            access |= spirv::MemoryAccess::VOLATILE;
+        }
+        let scope_id = if decorations.contains(crate::MemoryDecorations::COHERENT) {
+            access |= if store {
+                spirv::MemoryAccess::MAKE_POINTER_AVAILABLE
+            } else {
+                spirv::MemoryAccess::MAKE_POINTER_VISIBLE
+            };
+            Some(self.get_scope_constant(spirv::Scope::Device as u32))
+        } else {
+            None
+        };
+        Some((access, scope_id))
+    }
 }
 
 #[derive(Clone, Copy, Default)]
@@ -605,6 +731,9 @@ pub struct Writer {
 
     debugs: Vec<Instruction>,
     annotations: Vec<Instruction>,
+    /// The operands of every decoration in `annotations`, by target id,
+    /// member index and decoration, so duplicates can be caught.
+    decorations: crate::FastHashMap<(Word, Option<u32>, spirv::Decoration), Vec<Word>>,
     flags: WriterFlags,
     bounds_check_policies: BoundsCheckPolicies,
     zero_initialize_workgroup_memory: ZeroInitializeWorkgroupMemoryMode,
@@ -615,6 +744,9 @@ pub struct Writer {
     lookup_function_type: crate::FastHashMap<LookupFunctionType, Word>,
     /// Indexed by const-expression handle indexes
     constant_ids: Vec<Word>,
+    /// The `OpSpecConstant` ids of override constants, indexed by constant
+    /// handle indexes. Zero for constants that aren't overrides.
+    override_ids: Vec<Word>,
     cached_constants: crate::FastHashMap<CachedConstant, Word>,
     global_variables: Vec<GlobalVariable>,
     binding_map: BindingMap,
@@ -627,6 +759,43 @@ pub struct Writer {
 
     // Just a temporary list of SPIR-V ids
     temp_list: Vec<Word>,
+
+    #[cfg(feature = "telemetry")]
+    statistics: WriteStatistics,
+}
+
+/// Where [`Writer::write`] spent its time, and what it produced.
+///
+/// This is only available with the `telemetry` feature. Retrieve it with
+/// [`Writer::statistics`] after a call to `write`.
+#[cfg(feature = "telemetry")]
+#[derive(Clone, Debug, Default)]
+pub struct WriteStatistics {
+    /// Time spent emitting type declarations.
+    pub types: std::time::Duration,
+    /// Time spent emitting the module's constant expressions.
+    pub constants: std::time::Duration,
+    /// Time spent emitting global variables.
+    pub global_variables: std::time::Duration,
+    /// Time spent emitting function bodies, not including entry points.
+    pub functions: std::time::Duration,
+    /// Time spent emitting entry points, including their wrapper functions.
+    pub entry_points: std::time::Duration,
+    /// Time spent in the whole call to [`Writer::write`].
+    ///
+    /// This is recorded even if writing fails. The phases the writer didn't
+    /// finish are left zero.
+    pub total: std::time::Duration,
+    /// The number of instructions in the module, not counting the header.
+    pub instructions: usize,
+    /// The number of SPIR-V ids allocated. This is one less than the
+    /// module's id bound.
+    pub ids_allocated: Word,
+    /// The number of constants that were emitted.
+    pub constants_created: usize,
+    /// The number of constant requests satisfied by a previously emitted
+    /// constant.
+    pub constants_cached: usize,
 }
 
 bitflags::bitflags! {
@@ -645,6 +814,34 @@ bitflags::bitflags! {
         const FORCE_POINT_SIZE = 0x8;
         /// Clamp `BuiltIn::FragDepth` output between 0 and 1.
         const CLAMP_FRAG_DEPTH = 0x10;
+        /// Use the Vulkan memory model instead of GLSL450.
+        ///
+        /// Coherent and volatile storage buffers then get memory operands on
+        /// each load and store, rather than decorations on the variable.
+        const VULKAN_MEMORY_MODEL = 0x20;
+        /// Allow resource globals used by entry points to lack a
+        /// [`ResourceBinding`], writing them without `DescriptorSet` and
+        /// `Binding` decorations.
+        ///
+        /// This is only useful if the decorations are patched in later.
+        ///
+        /// [`ResourceBinding`]: crate::ResourceBinding
+        const ALLOW_MISSING_BINDINGS = 0x40;
+        /// Produce the smallest output we can, for shipping.
+        ///
+        /// This takes precedence over [`DEBUG`] and [`LABEL_VARYINGS`], so
+        /// no debug instructions are written. In addition, duplicate
+        /// decorations are dropped, decorations are sorted by their target,
+        /// and type, constant and global variable declarations are grouped
+        /// by kind, which helps general purpose compressors.
+        ///
+        /// The semantics of the module are unchanged: only instructions that
+        /// have no effect on execution are dropped, and declarations are
+        /// never moved before anything they refer to.
+        ///
+        /// [`DEBUG`]: Self::DEBUG
+        /// [`LABEL_VARYINGS`]: Self::LABEL_VARYINGS
+        const MINIMAL_OUTPUT = 0x80;
     }
 }
 
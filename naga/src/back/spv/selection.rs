@@ -9,7 +9,8 @@ short-circuiting 'and' and 'or' structures. These track labels and can emit
 `OpPhi` instructions to merge values produced along different paths.
 
 This currently only supports exactly the forms Naga uses, so it doesn't
-support `or` or `else`, and only supports zero or one merged values.
+support `or`, only supports `else` on a selection's first condition, and
+only supports zero or one merged values.
 
 Naga needs to emit code roughly like this:
 
@@ -130,6 +131,43 @@ impl<'b, M: MergeTuple> Selection<'b, M> {
         );
     }
 
+    /// Branch to one of two successor blocks, depending on `cond`.
+    ///
+    /// Proceed to a new block for the case where `cond` is true, and return
+    /// the label of the block for the case where it is false. Once the true
+    /// case is written, pass that label to [`otherwise`] to move on.
+    ///
+    /// This must be the first branch in the selection.
+    ///
+    /// [`otherwise`]: Selection::otherwise
+    pub(super) fn if_else(&mut self, ctx: &mut BlockContext, cond: Word) -> Word {
+        debug_assert!(self.merge_label.is_none());
+        self.make_merge_label(ctx);
+        let true_label = ctx.gen_id();
+        let false_label = ctx.gen_id();
+        ctx.function.consume(
+            std::mem::replace(self.block, Block::new(true_label)),
+            Instruction::branch_conditional(cond, true_label, false_label),
+        );
+        false_label
+    }
+
+    /// Finish the true case of an [`if_else`], and proceed to its false case.
+    ///
+    /// Branch to the merge block, using `values` as the merged values, and
+    /// continue in the block labeled `false_label`.
+    ///
+    /// [`if_else`]: Selection::if_else
+    pub(super) fn otherwise(&mut self, ctx: &mut BlockContext, false_label: Word, values: M) {
+        self.values.push((values, self.block.label_id));
+
+        let merge_label = self.make_merge_label(ctx);
+        ctx.function.consume(
+            std::mem::replace(self.block, Block::new(false_label)),
+            Instruction::branch(merge_label),
+        );
+    }
+
     /// Emit an unconditional branch to the merge block, and compute merged
     /// values.
     ///
@@ -1,6 +1,6 @@
 use super::{
     block::DebugInfoInner,
-    helpers::{contains_builtin, global_needs_wrapper, map_storage_class},
+    helpers::{contains_builtin, contains_scalar_width, global_needs_wrapper, map_storage_class},
     make_local, Block, BlockContext, CachedConstant, CachedExpressions, DebugInfo,
     EntryPointContext, Error, Function, FunctionArgument, GlobalVariable, IdGenerator, Instruction,
     LocalType, LocalVariable, LogicalLayout, LookupFunctionType, LookupType, LoopContext, Options,
@@ -18,6 +18,7 @@ use std::collections::hash_map::Entry;
 struct FunctionInterface<'a> {
     varying_ids: &'a mut Vec<Word>,
     stage: crate::ShaderStage,
+    mesh_info: Option<&'a crate::MeshStageInfo>,
 }
 
 impl Function {
@@ -55,6 +56,11 @@ impl Writer {
         let gl450_ext_inst_id = id_gen.next();
         let void_type = id_gen.next();
 
+        let mut flags = options.flags;
+        if flags.contains(WriterFlags::MINIMAL_OUTPUT) {
+            flags.remove(WriterFlags::DEBUG | WriterFlags::LABEL_VARYINGS);
+        }
+
         Ok(Writer {
             physical_layout: PhysicalLayout::new(raw_version),
             logical_layout: LogicalLayout::default(),
@@ -64,7 +70,8 @@ impl Writer {
             extensions_used: crate::FastIndexSet::default(),
             debugs: vec![],
             annotations: vec![],
-            flags: options.flags,
+            decorations: crate::FastHashMap::default(),
+            flags,
             bounds_check_policies: options.bounds_check_policies,
             zero_initialize_workgroup_memory: options.zero_initialize_workgroup_memory,
             void_type,
@@ -72,12 +79,15 @@ impl Writer {
             lookup_function: crate::FastHashMap::default(),
             lookup_function_type: crate::FastHashMap::default(),
             constant_ids: Vec::new(),
+            override_ids: Vec::new(),
             cached_constants: crate::FastHashMap::default(),
             global_variables: Vec::new(),
             binding_map: options.binding_map.clone(),
             saved_cached: CachedExpressions::default(),
             gl450_ext_inst_id,
             temp_list: Vec::new(),
+            #[cfg(feature = "telemetry")]
+            statistics: super::WriteStatistics::default(),
         })
     }
 
@@ -120,14 +130,19 @@ impl Writer {
             logical_layout: take(&mut self.logical_layout).recycle(),
             debugs: take(&mut self.debugs).recycle(),
             annotations: take(&mut self.annotations).recycle(),
+            decorations: take(&mut self.decorations).recycle(),
             lookup_type: take(&mut self.lookup_type).recycle(),
             lookup_function: take(&mut self.lookup_function).recycle(),
             lookup_function_type: take(&mut self.lookup_function_type).recycle(),
             constant_ids: take(&mut self.constant_ids).recycle(),
+            override_ids: take(&mut self.override_ids).recycle(),
             cached_constants: take(&mut self.cached_constants).recycle(),
             global_variables: take(&mut self.global_variables).recycle(),
             saved_cached: take(&mut self.saved_cached).recycle(),
             temp_list: take(&mut self.temp_list).recycle(),
+
+            #[cfg(feature = "telemetry")]
+            statistics: super::WriteStatistics::default(),
         };
 
         *self = fresh;
@@ -165,11 +180,21 @@ impl Writer {
                         match capabilities.iter().find(|cap| available.contains(cap)) {
                             Some(&cap) => cap,
                             None => {
-                                return Err(Error::MissingCapabilities(what, capabilities.to_vec()))
+                                log::debug!("no capability available for {what}");
+                                return Err(Error::MissingCapabilities(
+                                    what,
+                                    capabilities.to_vec(),
+                                ));
                             }
                         }
                     }
                 };
+                log::debug!(
+                    "selected capability {:?} for {} out of {:?}",
+                    selected,
+                    what,
+                    capabilities
+                );
                 self.capabilities_used.insert(selected);
                 Ok(())
             }
@@ -181,12 +206,92 @@ impl Writer {
         self.extensions_used.insert(extension);
     }
 
+    /// Try to enable the packed integer dot product instructions, `OpSDot`
+    /// and `OpUDot`, returning `false` if they aren't available.
+    ///
+    /// They are core in SPIR-V 1.6, and come from the
+    /// `SPV_KHR_integer_dot_product` extension before that. Since most
+    /// drivers don't support them, they are only used before 1.6 if the
+    /// [`Options`] list their capabilities as available.
+    pub(super) fn use_integer_dot_product(&mut self) -> bool {
+        const CAPABILITIES: [spirv::Capability; 2] = [
+            spirv::Capability::DotProduct,
+            spirv::Capability::DotProductInput4x8BitPacked,
+        ];
+        let core = self.physical_layout.version >= 0x10600;
+        let available = match self.capabilities_available {
+            Some(ref available) => CAPABILITIES.iter().all(|cap| available.contains(cap)),
+            None => core,
+        };
+        if !available {
+            return false;
+        }
+        self.capabilities_used.extend(CAPABILITIES);
+        if !core {
+            self.use_extension("SPV_KHR_integer_dot_product");
+        }
+        true
+    }
+
+    /// Try to enable variable pointers for pointers in `space`, returning
+    /// `false` if they aren't available.
+    ///
+    /// Variable pointers let `OpSelect` choose between pointers. That needs
+    /// `VariablePointersStorageBuffer` for storage buffers and
+    /// `VariablePointers` for workgroup memory; SPIR-V never allows it for
+    /// other storage classes. Since many drivers don't support them, they are
+    /// only used if the [`Options`] list their capabilities as available.
+    pub(super) fn use_variable_pointers(&mut self, space: crate::AddressSpace) -> bool {
+        let candidates: &[spirv::Capability] = match space {
+            crate::AddressSpace::Storage { .. } => &[
+                spirv::Capability::VariablePointersStorageBuffer,
+                spirv::Capability::VariablePointers,
+            ],
+            crate::AddressSpace::WorkGroup => &[spirv::Capability::VariablePointers],
+            _ => &[],
+        };
+        let Some(ref available) = self.capabilities_available else {
+            return false;
+        };
+        let Some(&capability) = candidates.iter().find(|cap| available.contains(cap)) else {
+            return false;
+        };
+        self.capabilities_used.insert(capability);
+        if self.physical_layout.version < 0x10300 {
+            self.use_extension("SPV_KHR_variable_pointers");
+        }
+        true
+    }
+
+    /// Try to enable variable pointers for passing pointers to functions,
+    /// returning `false` if they aren't available.
+    ///
+    /// Without them, each pointer argument must be a whole variable or a
+    /// function parameter. See [`crate::proc::inline_pointer_calls`].
+    fn use_variable_pointers_for_calls(&mut self) -> bool {
+        let Some(ref available) = self.capabilities_available else {
+            return false;
+        };
+        if !available.contains(&spirv::Capability::VariablePointers) {
+            return false;
+        }
+        self.capabilities_used
+            .insert(spirv::Capability::VariablePointers);
+        if self.physical_layout.version < 0x10300 {
+            self.use_extension("SPV_KHR_variable_pointers");
+        }
+        true
+    }
+
     pub(super) fn get_type_id(&mut self, lookup_ty: LookupType) -> Word {
         match self.lookup_type.entry(lookup_ty) {
             Entry::Occupied(e) => *e.get(),
             Entry::Vacant(e) => {
                 let local = match lookup_ty {
                     LookupType::Handle(_handle) => unreachable!("Handles are populated at start"),
+                    LookupType::BlockStruct(_handle) => {
+                        unreachable!("Block structs are declared along with their binding arrays")
+                    }
                     LookupType::Local(local) => local,
                 };
 
@@ -316,9 +421,62 @@ impl Writer {
         self.get_type_id(local_type.into())
     }
 
-    pub(super) fn decorate(&mut self, id: Word, decoration: spirv::Decoration, operands: &[Word]) {
-        self.annotations
-            .push(Instruction::decorate(id, decoration, operands));
+    pub(super) fn decorate(
+        &mut self,
+        id: Word,
+        decoration: spirv::Decoration,
+        operands: &[Word],
+    ) -> Result<(), Error> {
+        if self.record_decoration(id, None, decoration, operands)? {
+            self.annotations
+                .push(Instruction::decorate(id, decoration, operands));
+        }
+        Ok(())
+    }
+
+    fn member_decorate(
+        &mut self,
+        id: Word,
+        index: u32,
+        decoration: spirv::Decoration,
+        operands: &[Word],
+    ) -> Result<(), Error> {
+        if self.record_decoration(id, Some(index), decoration, operands)? {
+            self.annotations.push(Instruction::member_decorate(
+                id, index, decoration, operands,
+            ));
+        }
+        Ok(())
+    }
+
+    /// Note that `id`, or its member `index`, is decorated with `decoration`.
+    ///
+    /// Return `false` if it already has exactly this decoration, so the
+    /// caller should skip it. A target can't have the same decoration twice
+    /// with different operands, so that is an error.
+    fn record_decoration(
+        &mut self,
+        id: Word,
+        member: Option<u32>,
+        decoration: spirv::Decoration,
+        operands: &[Word],
+    ) -> Result<bool, Error> {
+        use std::collections::hash_map::Entry;
+
+        match self.decorations.entry((id, member, decoration)) {
+            Entry::Vacant(entry) => {
+                entry.insert(operands.to_vec());
+                Ok(true)
+            }
+            Entry::Occupied(entry) if entry.get() == operands => Ok(false),
+            Entry::Occupied(entry) => Err(Error::ConflictingDecoration {
+                id,
+                member,
+                decoration,
+                existing: entry.get().clone(),
+                new: operands.to_vec(),
+            }),
+        }
     }
 
     fn write_function(
@@ -336,13 +494,15 @@ impl Writer {
         let mut ep_context = EntryPointContext {
             argument_ids: Vec::new(),
             results: Vec::new(),
+            task_size_member: None,
+            mesh_vertices: Vec::new(),
+            mesh_primitives: Vec::new(),
         };
 
         let mut local_invocation_id = None;
 
         let mut parameter_type_ids = Vec::with_capacity(ir_function.arguments.len());
         for argument in ir_function.arguments.iter() {
-            let class = spirv::StorageClass::Input;
             let handle_ty = ir_module.types[argument.ty].inner.is_handle();
             let argument_type_id = match handle_ty {
                 true => self.get_pointer_id(
@@ -357,19 +517,14 @@ impl Writer {
                 let id = if let Some(ref binding) = argument.binding {
                     let name = argument.name.as_deref();
 
-                    let varying_id = self.write_varying(
+                    let id = self.write_input(
                         ir_module,
-                        iface.stage,
-                        class,
+                        iface,
                         name,
                         argument.ty,
                         binding,
+                        &mut prelude,
                     )?;
-                    iface.varying_ids.push(varying_id);
-                    let id = self.id_gen.next();
-                    prelude
-                        .body
-                        .push(Instruction::load(argument_type_id, id, varying_id, None));
 
                     if binding == &crate::Binding::BuiltIn(crate::BuiltIn::LocalInvocationId) {
                         local_invocation_id = Some(id);
@@ -382,22 +537,16 @@ impl Writer {
                     let struct_id = self.id_gen.next();
                     let mut constituent_ids = Vec::with_capacity(members.len());
                     for member in members {
-                        let type_id = self.get_type_id(LookupType::Handle(member.ty));
                         let name = member.name.as_deref();
                         let binding = member.binding.as_ref().unwrap();
-                        let varying_id = self.write_varying(
+                        let id = self.write_input(
                             ir_module,
-                            iface.stage,
-                            class,
+                            iface,
                             name,
                             member.ty,
                             binding,
+                            &mut prelude,
                         )?;
-                        iface.varying_ids.push(varying_id);
-                        let id = self.id_gen.next();
-                        prelude
-                            .body
-                            .push(Instruction::load(type_id, id, varying_id, None));
                         constituent_ids.push(id);
 
                         if binding == &crate::Binding::BuiltIn(crate::BuiltIn::GlobalInvocationId) {
@@ -442,6 +591,30 @@ impl Writer {
         }
 
         let return_type_id = match ir_function.result {
+            // Task shaders pass their result to `OpEmitMeshTasksEXT`, rather
+            // than storing it in output variables.
+            Some(ref result)
+                if matches!(
+                    interface,
+                    Some(FunctionInterface {
+                        stage: crate::ShaderStage::Task,
+                        ..
+                    })
+                ) =>
+            {
+                let task_size = crate::Binding::BuiltIn(crate::BuiltIn::MeshTaskSize);
+                let index = match ir_module.types[result.ty].inner {
+                    crate::TypeInner::Struct { ref members, .. } if result.binding.is_none() => {
+                        members
+                            .iter()
+                            .position(|member| member.binding.as_ref() == Some(&task_size))
+                            .ok_or(Error::Validation("task shader result has no task size"))?
+                    }
+                    _ => 0,
+                };
+                ep_context.task_size_member = Some(index as u32);
+                self.void_type
+            }
             Some(ref result) => {
                 if let Some(ref mut iface) = interface {
                     let mut has_point_size = false;
@@ -505,7 +678,7 @@ impl Writer {
                             varying_id,
                             spirv::Decoration::BuiltIn,
                             &[spirv::BuiltIn::PointSize as u32],
-                        );
+                        )?;
                         iface.varying_ids.push(varying_id);
 
                         let default_value_id = self.get_constant_scalar(crate::Literal::F32(1.0));
@@ -521,6 +694,25 @@ impl Writer {
             None => self.void_type,
         };
 
+        if let Some(ref mut iface) = interface {
+            if let Some(mesh_info) = iface.mesh_info {
+                ep_context.mesh_vertices = self.write_mesh_outputs(
+                    ir_module,
+                    iface,
+                    mesh_info.vertex_output_type,
+                    mesh_info.max_vertices,
+                    false,
+                )?;
+                ep_context.mesh_primitives = self.write_mesh_outputs(
+                    ir_module,
+                    iface,
+                    mesh_info.primitive_output_type,
+                    mesh_info.max_primitives,
+                    true,
+                )?;
+            }
+        }
+
         let lookup_function_type = LookupFunctionType {
             parameter_type_ids,
             return_type_id,
@@ -618,6 +810,25 @@ impl Writer {
             ),
         };
 
+        // Uses of overrides refer to their `OpSpecConstant`, which can't be a
+        // constituent of an `OpConstantComposite`.
+        for (handle, expr) in ir_function.expressions.iter() {
+            let constness = &context.expression_constness;
+            let non_const = match *expr {
+                crate::Expression::Constant(constant) => {
+                    context.writer.override_ids[constant.index()] != 0
+                }
+                crate::Expression::Compose { ref components, .. } => components
+                    .iter()
+                    .any(|&component| !constness.is_const(component)),
+                crate::Expression::Splat { value, .. } => !constness.is_const(value),
+                _ => false,
+            };
+            if non_const {
+                context.expression_constness.force_non_const(handle);
+            }
+        }
+
         // fill up the pre-emitted and const expressions
         context.cached.reset(ir_function.expressions.len());
         for (handle, expr) in ir_function.expressions.iter() {
@@ -637,6 +848,8 @@ impl Writer {
                 }
             }
 
+            // Locals without an `init` start out zeroed, sharing one
+            // `OpConstantNull` per type.
             let init_word = variable.init.map(|constant| context.cached[constant]);
             let pointer_type_id = context.writer.get_pointer_id(
                 &ir_module.types,
@@ -651,7 +864,7 @@ impl Writer {
                     crate::TypeInner::RayQuery => None,
                     _ => {
                         let type_id = context.get_type_id(LookupType::Handle(variable.ty));
-                        Some(context.writer.write_constant_null(type_id))
+                        Some(context.writer.get_constant_null(type_id))
                     }
                 }),
             );
@@ -680,7 +893,10 @@ impl Writer {
                     super::ZeroInitializeWorkgroupMemoryMode::Polyfill,
                     Some(
                         ref mut interface @ FunctionInterface {
-                            stage: crate::ShaderStage::Compute,
+                            stage:
+                                crate::ShaderStage::Compute
+                                | crate::ShaderStage::Task
+                                | crate::ShaderStage::Mesh,
                             ..
                         },
                     ),
@@ -691,7 +907,7 @@ impl Writer {
                     local_invocation_id,
                     interface,
                     context.function,
-                ),
+                )?,
                 _ => None,
             };
 
@@ -750,6 +966,7 @@ impl Writer {
             Some(FunctionInterface {
                 varying_ids: &mut interface_ids,
                 stage: entry_point.stage,
+                mesh_info: entry_point.mesh_info.as_ref(),
             }),
             debug_info,
         )?;
@@ -769,11 +986,36 @@ impl Writer {
                             function_id,
                             spirv::ExecutionMode::DepthReplacing,
                         )?;
+                        // The conservative depth modes only describe how
+                        // the replaced depth relates to the interpolated one,
+                        // so they're only declared alongside `DepthReplacing`.
+                        if let Some(crate::EarlyDepthTest {
+                            conservative: Some(conservative),
+                        }) = entry_point.early_depth_test
+                        {
+                            let mode = match conservative {
+                                crate::ConservativeDepth::GreaterEqual => {
+                                    spirv::ExecutionMode::DepthGreater
+                                }
+                                crate::ConservativeDepth::LessEqual => {
+                                    spirv::ExecutionMode::DepthLess
+                                }
+                                crate::ConservativeDepth::Unchanged => {
+                                    spirv::ExecutionMode::DepthUnchanged
+                                }
+                            };
+                            self.write_execution_mode(function_id, mode)?;
+                        }
                     }
                 }
                 spirv::ExecutionModel::Fragment
             }
             crate::ShaderStage::Compute => {
+                if entry_point.workgroup_size_overrides.is_some() {
+                    return Err(Error::FeatureNotImplemented(
+                        "workgroup size from overrides",
+                    ));
+                }
                 let execution_mode = spirv::ExecutionMode::LocalSize;
                 //self.check(execution_mode.required_capabilities())?;
                 Instruction::execution_mode(
@@ -784,6 +1026,48 @@ impl Writer {
                 .to_words(&mut self.logical_layout.execution_modes);
                 spirv::ExecutionModel::GLCompute
             }
+            crate::ShaderStage::Task | crate::ShaderStage::Mesh => {
+                self.require_mesh_shading()?;
+                if entry_point.workgroup_size_overrides.is_some() {
+                    return Err(Error::FeatureNotImplemented(
+                        "workgroup size from overrides",
+                    ));
+                }
+                Instruction::execution_mode(
+                    function_id,
+                    spirv::ExecutionMode::LocalSize,
+                    &entry_point.workgroup_size,
+                )
+                .to_words(&mut self.logical_layout.execution_modes);
+                match entry_point.mesh_info {
+                    Some(ref mesh_info) => {
+                        Instruction::execution_mode(
+                            function_id,
+                            spirv::ExecutionMode::OutputVertices,
+                            &[mesh_info.max_vertices],
+                        )
+                        .to_words(&mut self.logical_layout.execution_modes);
+                        Instruction::execution_mode(
+                            function_id,
+                            spirv::ExecutionMode::OutputPrimitivesEXT,
+                            &[mesh_info.max_primitives],
+                        )
+                        .to_words(&mut self.logical_layout.execution_modes);
+                        let topology = match mesh_info.topology {
+                            crate::MeshOutputTopology::Points => spirv::ExecutionMode::OutputPoints,
+                            crate::MeshOutputTopology::Lines => {
+                                spirv::ExecutionMode::OutputLinesEXT
+                            }
+                            crate::MeshOutputTopology::Triangles => {
+                                spirv::ExecutionMode::OutputTrianglesEXT
+                            }
+                        };
+                        self.write_execution_mode(function_id, topology)?;
+                        spirv::ExecutionModel::MeshEXT
+                    }
+                    None => spirv::ExecutionModel::TaskEXT,
+                }
+            }
         };
         //self.check(exec_model.required_capabilities())?;
 
@@ -844,6 +1128,7 @@ impl Writer {
                         self.request_image_format_capabilities(format.into())?;
                         false
                     }
+                    crate::ImageClass::External => unreachable!(),
                 };
 
                 match dim {
@@ -945,7 +1230,7 @@ impl Writer {
                 Instruction::type_sampled_image(id, image_type_id)
             }
             LocalType::BindingArray { base, size } => {
-                let inner_ty = self.get_type_id(LookupType::Handle(base));
+                let inner_ty = self.get_binding_array_element_id(base);
                 let scalar_id = self.get_constant_scalar(crate::Literal::U32(size));
                 Instruction::type_array(id, inner_ty, scalar_id)
             }
@@ -955,6 +1240,10 @@ impl Writer {
                 let class = map_storage_class(space);
                 Instruction::type_pointer(id, class, inner_ty)
             }
+            LocalType::PointerToBlockStruct { base, class } => {
+                let inner_ty = self.get_type_id(LookupType::BlockStruct(base));
+                Instruction::type_pointer(id, class, inner_ty)
+            }
             LocalType::AccelerationStructure => Instruction::type_acceleration_structure(id),
             LocalType::RayQuery => Instruction::type_ray_query(id),
         };
@@ -996,7 +1285,7 @@ impl Writer {
             let id = self.id_gen.next();
             let instruction = match ty.inner {
                 crate::TypeInner::Array { base, size, stride } => {
-                    self.decorate(id, Decoration::ArrayStride, &[stride]);
+                    self.decorate(id, Decoration::ArrayStride, &[stride])?;
 
                     let type_id = self.get_type_id(LookupType::Handle(base));
                     match size {
@@ -1004,16 +1293,29 @@ impl Writer {
                             let length_id = self.get_index_constant(length.get());
                             Instruction::type_array(id, type_id, length_id)
                         }
+                        crate::ArraySize::Pending(constant) => {
+                            let length_id = self.override_ids[constant.index()];
+                            if length_id == 0 {
+                                return Err(Error::Validation("array size override"));
+                            }
+                            Instruction::type_array(id, type_id, length_id)
+                        }
                         crate::ArraySize::Dynamic => Instruction::type_runtime_array(id, type_id),
                     }
                 }
                 crate::TypeInner::BindingArray { base, size } => {
-                    let type_id = self.get_type_id(LookupType::Handle(base));
+                    if let crate::TypeInner::Struct { ref members, .. } = arena[base].inner {
+                        self.write_block_struct(base, members, arena)?;
+                    }
+                    let type_id = self.get_binding_array_element_id(base);
                     match size {
                         crate::ArraySize::Constant(length) => {
                             let length_id = self.get_index_constant(length.get());
                             Instruction::type_array(id, type_id, length_id)
                         }
+                        crate::ArraySize::Pending(_) => {
+                            return Err(Error::Validation("binding array size"))
+                        }
                         crate::ArraySize::Dynamic => Instruction::type_runtime_array(id, type_id),
                     }
                 }
@@ -1021,26 +1323,20 @@ impl Writer {
                     ref members,
                     span: _,
                 } => {
-                    let mut has_runtime_array = false;
-                    let mut member_ids = Vec::with_capacity(members.len());
-                    for (index, member) in members.iter().enumerate() {
-                        let member_ty = &arena[member.ty];
-                        match member_ty.inner {
+                    // A struct with a runtime-sized array can only be used as the
+                    // root of a storage buffer, so it can carry `Block` itself.
+                    let has_runtime_array = members.iter().any(|member| {
+                        matches!(
+                            arena[member.ty].inner,
                             crate::TypeInner::Array {
-                                base: _,
                                 size: crate::ArraySize::Dynamic,
-                                stride: _,
-                            } => {
-                                has_runtime_array = true;
+                                ..
                             }
-                            _ => (),
-                        }
-                        self.decorate_struct_member(id, index, member, arena)?;
-                        let member_id = self.get_type_id(LookupType::Handle(member.ty));
-                        member_ids.push(member_id);
-                    }
+                        )
+                    });
+                    let member_ids = self.write_struct_members(id, members, arena)?;
                     if has_runtime_array {
-                        self.decorate(id, Decoration::Block, &[]);
+                        self.decorate(id, Decoration::Block, &[])?;
                     }
                     Instruction::type_struct(id, member_ids.as_slice())
                 }
@@ -1075,6 +1371,60 @@ impl Writer {
         Ok(id)
     }
 
+    /// Decorate the members of the struct type `id`, and return their type ids.
+    fn write_struct_members(
+        &mut self,
+        id: Word,
+        members: &[crate::StructMember],
+        arena: &UniqueArena<crate::Type>,
+    ) -> Result<Vec<Word>, Error> {
+        let mut member_ids = Vec::with_capacity(members.len());
+        for (index, member) in members.iter().enumerate() {
+            self.decorate_struct_member(id, index, member, arena)?;
+            member_ids.push(self.get_type_id(LookupType::Handle(member.ty)));
+        }
+        Ok(member_ids)
+    }
+
+    /// Declare the [`LookupType::BlockStruct`] copy of the struct `handle`,
+    /// if we haven't already.
+    fn write_block_struct(
+        &mut self,
+        handle: Handle<crate::Type>,
+        members: &[crate::StructMember],
+        arena: &UniqueArena<crate::Type>,
+    ) -> Result<(), Error> {
+        let lookup_ty = LookupType::BlockStruct(handle);
+        if self.lookup_type.contains_key(&lookup_ty) {
+            return Ok(());
+        }
+
+        let id = self.id_gen.next();
+        self.lookup_type.insert(lookup_ty, id);
+        let member_ids = self.write_struct_members(id, members, arena)?;
+        self.decorate(id, spirv::Decoration::Block, &[])?;
+        Instruction::type_struct(id, &member_ids).to_words(&mut self.logical_layout.declarations);
+
+        if self.flags.contains(WriterFlags::DEBUG) {
+            if let Some(ref name) = arena[handle].name {
+                self.debugs.push(Instruction::name(id, name));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Return the id of the element type of binding arrays of `base`.
+    ///
+    /// Binding arrays of buffers use the [`LookupType::BlockStruct`] copy of
+    /// their struct, which is declared before the binding array type itself.
+    pub(super) fn get_binding_array_element_id(&mut self, base: Handle<crate::Type>) -> Word {
+        match self.lookup_type.get(&LookupType::BlockStruct(base)) {
+            Some(&id) => id,
+            None => self.get_type_id(LookupType::Handle(base)),
+        }
+    }
+
     fn request_image_format_capabilities(
         &mut self,
         format: spirv::ImageFormat,
@@ -1147,11 +1497,24 @@ impl Writer {
         )
     }
 
+    /// Record a request for a constant, and whether it was already cached.
+    #[cfg_attr(not(feature = "telemetry"), allow(unused_variables))]
+    fn count_constant(&mut self, cached: bool) {
+        #[cfg(feature = "telemetry")]
+        if cached {
+            self.statistics.constants_cached += 1;
+        } else {
+            self.statistics.constants_created += 1;
+        }
+    }
+
     pub(super) fn get_constant_scalar(&mut self, value: crate::Literal) -> Word {
         let scalar = CachedConstant::Literal(value);
         if let Some(&id) = self.cached_constants.get(&scalar) {
+            self.count_constant(true);
             return id;
         }
+        self.count_constant(false);
         let id = self.id_gen.next();
         self.write_constant_scalar(id, &value, None);
         self.cached_constants.insert(scalar, id);
@@ -1195,6 +1558,66 @@ impl Writer {
         instruction.to_words(&mut self.logical_layout.declarations);
     }
 
+    /// Write an `OpSpecConstant` for each override constant with a scalar
+    /// value, and record its id in `override_ids`.
+    ///
+    /// Arrays sized by an override use these as their length, and so do
+    /// expressions in function bodies that refer to the override.
+    fn write_override_constants(&mut self, ir_module: &crate::Module) -> Result<(), Error> {
+        self.override_ids.clear();
+        self.override_ids.resize(ir_module.constants.len(), 0);
+        for (handle, constant) in ir_module.constants.iter() {
+            let spec_id = match constant.r#override {
+                crate::Override::None => continue,
+                crate::Override::ByName => None,
+                crate::Override::ByNameOrId(spec_id) => Some(spec_id),
+            };
+            let Some(value) = ir_module.to_ctx().eval_expr_to_literal(constant.init) else {
+                continue;
+            };
+
+            let id = self.id_gen.next();
+            let type_id = self.get_type_id(LookupType::Local(LocalType::Value {
+                vector_size: None,
+                scalar: value.scalar(),
+                pointer_space: None,
+            }));
+            let instruction = match value {
+                crate::Literal::F64(value) => {
+                    let bits = value.to_bits();
+                    Instruction::spec_constant(type_id, id, &[bits as u32, (bits >> 32) as u32])
+                }
+                crate::Literal::F32(value) => {
+                    Instruction::spec_constant(type_id, id, &[value.to_bits()])
+                }
+                crate::Literal::U32(value) => Instruction::spec_constant(type_id, id, &[value]),
+                crate::Literal::I32(value) => {
+                    Instruction::spec_constant(type_id, id, &[value as u32])
+                }
+                crate::Literal::I64(value) => {
+                    Instruction::spec_constant(type_id, id, &[value as u32, (value >> 32) as u32])
+                }
+                crate::Literal::Bool(true) => Instruction::spec_constant_true(type_id, id),
+                crate::Literal::Bool(false) => Instruction::spec_constant_false(type_id, id),
+                crate::Literal::AbstractInt(_) | crate::Literal::AbstractFloat(_) => {
+                    unreachable!("Abstract types should not appear in IR presented to backends");
+                }
+            };
+            instruction.to_words(&mut self.logical_layout.declarations);
+
+            if let Some(spec_id) = spec_id {
+                self.decorate(id, spirv::Decoration::SpecId, &[spec_id])?;
+            }
+            if self.flags.contains(WriterFlags::DEBUG) {
+                if let Some(ref name) = constant.name {
+                    self.debugs.push(Instruction::name(id, name));
+                }
+            }
+            self.override_ids[handle.index()] = id;
+        }
+        Ok(())
+    }
+
     pub(super) fn get_constant_composite(
         &mut self,
         ty: LookupType,
@@ -1205,8 +1628,10 @@ impl Writer {
             constituent_ids: constituent_ids.to_vec(),
         };
         if let Some(&id) = self.cached_constants.get(&composite) {
+            self.count_constant(true);
             return id;
         }
+        self.count_constant(false);
         let id = self.id_gen.next();
         self.write_constant_composite(id, ty, constituent_ids, None);
         self.cached_constants.insert(composite, id);
@@ -1233,8 +1658,10 @@ impl Writer {
     pub(super) fn get_constant_null(&mut self, type_id: Word) -> Word {
         let null = CachedConstant::ZeroValue(type_id);
         if let Some(&id) = self.cached_constants.get(&null) {
+            self.count_constant(true);
             return id;
         }
+        self.count_constant(false);
         let id = self.write_constant_null(type_id);
         self.cached_constants.insert(null, id);
         id
@@ -1323,7 +1750,7 @@ impl Writer {
         local_invocation_id: Option<Word>,
         interface: &mut FunctionInterface,
         function: &mut Function,
-    ) -> Option<Word> {
+    ) -> Result<Option<Word>, Error> {
         let body = ir_module
             .global_variables
             .iter()
@@ -1342,7 +1769,7 @@ impl Writer {
             .collect::<Vec<_>>();
 
         if body.is_empty() {
-            return None;
+            return Ok(None);
         }
 
         let uint3_type_id = self.get_uint3_type_id();
@@ -1363,7 +1790,7 @@ impl Writer {
                 varying_id,
                 spirv::Decoration::BuiltIn,
                 &[spirv::BuiltIn::LocalInvocationId as u32],
-            );
+            )?;
 
             interface.varying_ids.push(varying_id);
             let id = self.id_gen.next();
@@ -1419,7 +1846,92 @@ impl Writer {
 
         let next_id = self.id_gen.next();
         function.consume(post_if_block, Instruction::branch(next_id));
-        Some(next_id)
+        Ok(Some(next_id))
+    }
+
+    /// Declare the `Input` variables for one value in an entry point's
+    /// interface, load them in `prelude`, and return the id of the value.
+    ///
+    /// Vertex inputs that take several locations, like matrices, get one
+    /// variable per location, whose values are recomposed here.
+    fn write_input(
+        &mut self,
+        ir_module: &crate::Module,
+        iface: &mut FunctionInterface,
+        debug_name: Option<&str>,
+        ty: Handle<crate::Type>,
+        binding: &crate::Binding,
+        prelude: &mut Block,
+    ) -> Result<Word, Error> {
+        let class = spirv::StorageClass::Input;
+        let type_id = self.get_type_id(LookupType::Handle(ty));
+        let split = match *binding {
+            crate::Binding::Location { location, .. }
+                if iface.stage == crate::ShaderStage::Vertex =>
+            {
+                ir_module.types[ty]
+                    .inner
+                    .vertex_input_split(&ir_module.types)
+                    .map(|(count, inner)| (location, count, inner))
+            }
+            _ => None,
+        };
+        let Some((location, count, inner)) = split else {
+            let varying_id =
+                self.write_varying(ir_module, iface.stage, class, debug_name, ty, binding)?;
+            iface.varying_ids.push(varying_id);
+            let id = self.id_gen.next();
+            prelude
+                .body
+                .push(Instruction::load(type_id, id, varying_id, None));
+            return Ok(id);
+        };
+
+        let (vector_size, scalar) = match inner {
+            crate::TypeInner::Scalar(scalar) => (None, scalar),
+            crate::TypeInner::Vector { size, scalar } => (Some(size), scalar),
+            _ => unreachable!("vertex inputs are split into scalars or vectors"),
+        };
+        let part_type_id = self.get_type_id(LookupType::Local(LocalType::Value {
+            vector_size,
+            scalar,
+            pointer_space: None,
+        }));
+        let pointer_type_id = self.get_type_id(LookupType::Local(LocalType::Value {
+            vector_size,
+            scalar,
+            pointer_space: Some(class),
+        }));
+
+        let mut part_ids = Vec::with_capacity(count as usize);
+        for index in 0..count {
+            let varying_id = self.id_gen.next();
+            Instruction::variable(pointer_type_id, varying_id, class, None)
+                .to_words(&mut self.logical_layout.declarations);
+            if self
+                .flags
+                .contains(WriterFlags::DEBUG | WriterFlags::LABEL_VARYINGS)
+            {
+                if let Some(name) = debug_name {
+                    self.debugs
+                        .push(Instruction::name(varying_id, &format!("{name}_{index}")));
+                }
+            }
+            self.decorate(varying_id, spirv::Decoration::Location, &[location + index])?;
+            iface.varying_ids.push(varying_id);
+
+            let id = self.id_gen.next();
+            prelude
+                .body
+                .push(Instruction::load(part_type_id, id, varying_id, None));
+            part_ids.push(id);
+        }
+
+        let id = self.id_gen.next();
+        prelude
+            .body
+            .push(Instruction::composite_construct(type_id, id, &part_ids));
+        Ok(id)
     }
 
     /// Generate an `OpVariable` for one value in an [`EntryPoint`]'s IO interface.
@@ -1464,6 +1976,25 @@ impl Writer {
             }
         }
 
+        self.decorate_varying(id, ir_module, stage, class, ty, binding)?;
+        Ok(id)
+    }
+
+    /// Add the decorations for `binding` to the varying variable `id`.
+    ///
+    /// See [`write_varying`] for the meaning of the arguments. For mesh shader
+    /// outputs, `id` is an array of values of type `ty`.
+    ///
+    /// [`write_varying`]: Writer::write_varying
+    fn decorate_varying(
+        &mut self,
+        id: Word,
+        ir_module: &crate::Module,
+        stage: crate::ShaderStage,
+        class: spirv::StorageClass,
+        ty: Handle<crate::Type>,
+        binding: &crate::Binding,
+    ) -> Result<(), Error> {
         use spirv::{BuiltIn, Decoration};
 
         match *binding {
@@ -1473,7 +2004,7 @@ impl Writer {
                 sampling,
                 second_blend_source,
             } => {
-                self.decorate(id, Decoration::Location, &[location]);
+                self.decorate(id, Decoration::Location, &[location])?;
 
                 let no_decorations =
                     // VUID-StandaloneSpirv-Flat-06202
@@ -1490,29 +2021,29 @@ impl Writer {
                         // Perspective-correct interpolation is the default in SPIR-V.
                         None | Some(crate::Interpolation::Perspective) => (),
                         Some(crate::Interpolation::Flat) => {
-                            self.decorate(id, Decoration::Flat, &[]);
+                            self.decorate(id, Decoration::Flat, &[])?;
                         }
                         Some(crate::Interpolation::Linear) => {
-                            self.decorate(id, Decoration::NoPerspective, &[]);
+                            self.decorate(id, Decoration::NoPerspective, &[])?;
                         }
                     }
                     match sampling {
                         // Center sampling is the default in SPIR-V.
                         None | Some(crate::Sampling::Center) => (),
                         Some(crate::Sampling::Centroid) => {
-                            self.decorate(id, Decoration::Centroid, &[]);
+                            self.decorate(id, Decoration::Centroid, &[])?;
                         }
                         Some(crate::Sampling::Sample) => {
                             self.require_any(
                                 "per-sample interpolation",
                                 &[spirv::Capability::SampleRateShading],
                             )?;
-                            self.decorate(id, Decoration::Sample, &[]);
+                            self.decorate(id, Decoration::Sample, &[])?;
                         }
                     }
                 }
                 if second_blend_source {
-                    self.decorate(id, Decoration::Index, &[1]);
+                    self.decorate(id, Decoration::Index, &[1])?;
                 }
             }
             crate::Binding::BuiltIn(built_in) => {
@@ -1520,7 +2051,7 @@ impl Writer {
                 let built_in = match built_in {
                     Bi::Position { invariant } => {
                         if invariant {
-                            self.decorate(id, Decoration::Invariant, &[]);
+                            self.decorate(id, Decoration::Invariant, &[])?;
                         }
 
                         if class == spirv::StorageClass::Output {
@@ -1580,9 +2111,19 @@ impl Writer {
                     Bi::WorkGroupId => BuiltIn::WorkgroupId,
                     Bi::WorkGroupSize => BuiltIn::WorkgroupSize,
                     Bi::NumWorkGroups => BuiltIn::NumWorkgroups,
+                    // mesh shading
+                    Bi::TriangleIndices => BuiltIn::PrimitiveTriangleIndicesEXT,
+                    Bi::LineIndices => BuiltIn::PrimitiveLineIndicesEXT,
+                    Bi::PointIndex => BuiltIn::PrimitivePointIndicesEXT,
+                    Bi::CullPrimitive => BuiltIn::CullPrimitiveEXT,
+                    Bi::MeshTaskSize => {
+                        return Err(Error::Validation(
+                            "`mesh_task_size` is passed to `OpEmitMeshTasksEXT`",
+                        ))
+                    }
                 };
 
-                self.decorate(id, Decoration::BuiltIn, &[built_in as u32]);
+                self.decorate(id, Decoration::BuiltIn, &[built_in as u32])?;
 
                 use crate::ScalarKind as Sk;
 
@@ -1607,13 +2148,116 @@ impl Writer {
                     };
 
                     if is_flat {
-                        self.decorate(id, Decoration::Flat, &[]);
+                        self.decorate(id, Decoration::Flat, &[])?;
                     }
                 }
             }
         }
 
-        Ok(id)
+        Ok(())
+    }
+
+    /// Write the output variables for the members of a mesh shader's vertex
+    /// or primitive output struct, `ty`.
+    ///
+    /// Each member gets an `Output` variable holding an array of `size`
+    /// values, which [`MeshFunction`] statements store into.
+    ///
+    /// [`MeshFunction`]: crate::Statement::MeshFunction
+    fn write_mesh_outputs(
+        &mut self,
+        ir_module: &crate::Module,
+        iface: &mut FunctionInterface,
+        ty: Handle<crate::Type>,
+        size: u32,
+        per_primitive: bool,
+    ) -> Result<Vec<super::MeshOutputMember>, Error> {
+        let class = spirv::StorageClass::Output;
+        let members = match ir_module.types[ty].inner {
+            crate::TypeInner::Struct { ref members, .. } => members,
+            _ => return Err(Error::Validation("mesh outputs must be structs")),
+        };
+        let length_id = self.get_index_constant(size);
+
+        let mut outputs = Vec::with_capacity(members.len());
+        for member in members {
+            let binding = member
+                .binding
+                .as_ref()
+                .ok_or(Error::Validation("mesh output member has no binding"))?;
+            let type_id = self.get_type_id(LookupType::Handle(member.ty));
+            let pointer_type_id = self.get_pointer_id(&ir_module.types, member.ty, class)?;
+
+            let array_type_id = self.id_gen.next();
+            Instruction::type_array(array_type_id, type_id, length_id)
+                .to_words(&mut self.logical_layout.declarations);
+            let array_pointer_type_id = self.id_gen.next();
+            Instruction::type_pointer(array_pointer_type_id, class, array_type_id)
+                .to_words(&mut self.logical_layout.declarations);
+
+            let id = self.id_gen.next();
+            Instruction::variable(array_pointer_type_id, id, class, None)
+                .to_words(&mut self.logical_layout.declarations);
+            if self
+                .flags
+                .contains(WriterFlags::DEBUG | WriterFlags::LABEL_VARYINGS)
+            {
+                if let Some(ref name) = member.name {
+                    self.debugs.push(Instruction::name(id, name));
+                }
+            }
+            self.decorate_varying(id, ir_module, iface.stage, class, member.ty, binding)?;
+            if per_primitive {
+                self.decorate(id, spirv::Decoration::PerPrimitiveEXT, &[])?;
+            }
+            iface.varying_ids.push(id);
+
+            outputs.push(super::MeshOutputMember {
+                id,
+                type_id,
+                pointer_type_id,
+                built_in: binding.to_built_in(),
+            });
+        }
+        Ok(outputs)
+    }
+
+    /// Require the `SPV_EXT_mesh_shader` extension, for task and mesh shaders.
+    fn require_mesh_shading(&mut self) -> Result<(), Error> {
+        if self.physical_layout.version < 0x10400 {
+            return Err(Error::RequiresVersion("mesh shading", 1, 4));
+        }
+        self.require_any("mesh shading", &[spirv::Capability::MeshShadingEXT])?;
+        self.use_extension("SPV_EXT_mesh_shader");
+        Ok(())
+    }
+
+    /// Check that `var` has a binding if it is a resource used by any entry
+    /// point, since SPIR-V without `DescriptorSet` and `Binding` decorations
+    /// can't be bound by the API.
+    fn check_global_binding(
+        &self,
+        ir_module: &crate::Module,
+        mod_info: &ModuleInfo,
+        handle: Handle<crate::GlobalVariable>,
+        var: &crate::GlobalVariable,
+    ) -> Result<(), Error> {
+        if var.binding.is_some() || self.flags.contains(WriterFlags::ALLOW_MISSING_BINDINGS) {
+            return Ok(());
+        }
+        match var.space {
+            crate::AddressSpace::Handle
+            | crate::AddressSpace::Uniform
+            | crate::AddressSpace::Storage { .. } => {}
+            _ => return Ok(()),
+        }
+        let used = (0..ir_module.entry_points.len())
+            .any(|index| !mod_info.get_entry_point(index)[handle].is_empty());
+        if used {
+            let name = var.name.clone().unwrap_or_else(|| format!("{handle:?}"));
+            return Err(Error::MissingBinding(name));
+        }
+        Ok(())
     }
 
     fn write_global_variable(
@@ -1627,6 +2271,7 @@ impl Writer {
         let class = map_storage_class(global_variable.space);
 
         //self.check(class.required_capabilities())?;
+        self.request_narrow_storage_capabilities(ir_module, global_variable)?;
 
         if self.flags.contains(WriterFlags::DEBUG) {
             if let Some(ref name) = global_variable.name {
@@ -1646,10 +2291,21 @@ impl Writer {
         };
         if let Some(storage_access) = storage_access {
             if !storage_access.contains(crate::StorageAccess::LOAD) {
-                self.decorate(id, Decoration::NonReadable, &[]);
+                self.decorate(id, Decoration::NonReadable, &[])?;
             }
             if !storage_access.contains(crate::StorageAccess::STORE) {
-                self.decorate(id, Decoration::NonWritable, &[]);
+                self.decorate(id, Decoration::NonWritable, &[])?;
+            }
+        }
+
+        // The Vulkan memory model puts these on each access instead.
+        if !self.flags.contains(WriterFlags::VULKAN_MEMORY_MODEL) {
+            let decorations = global_variable.memory_decorations;
+            if decorations.contains(crate::MemoryDecorations::COHERENT) {
+                self.decorate(id, Decoration::Coherent, &[])?;
+            }
+            if decorations.contains(crate::MemoryDecorations::VOLATILE) {
+                self.decorate(id, Decoration::Volatile, &[])?;
             }
         }
 
@@ -1658,8 +2314,8 @@ impl Writer {
         // and it is failing on 0.
         let mut substitute_inner_type_lookup = None;
         if let Some(ref res_binding) = global_variable.binding {
-            self.decorate(id, Decoration::DescriptorSet, &[res_binding.group]);
-            self.decorate(id, Decoration::Binding, &[res_binding.binding]);
+            self.decorate(id, Decoration::DescriptorSet, &[res_binding.group])?;
+            self.decorate(id, Decoration::Binding, &[res_binding.binding])?;
 
             if let Some(&BindingInfo {
                 binding_array_size: Some(remapped_binding_array_size),
@@ -1689,7 +2345,7 @@ impl Writer {
         let pointer_type_id = if global_needs_wrapper(ir_module, global_variable) {
             let wrapper_type_id = self.id_gen.next();
 
-            self.decorate(wrapper_type_id, Decoration::Block, &[]);
+            self.decorate(wrapper_type_id, Decoration::Block, &[])?;
             let member = crate::StructMember {
                 name: None,
                 ty: global_variable.ty,
@@ -1709,18 +2365,9 @@ impl Writer {
         } else {
             // This is a global variable in the Storage address space. The only
             // way it could have `global_needs_wrapper() == false` is if it has
-            // a runtime-sized or binding array.
-            // Runtime-sized arrays were decorated when iterating through struct content.
-            // Now binding arrays require Block decorating.
-            if let crate::AddressSpace::Storage { .. } = global_variable.space {
-                match ir_module.types[global_variable.ty].inner {
-                    crate::TypeInner::BindingArray { base, .. } => {
-                        let decorated_id = self.get_type_id(LookupType::Handle(base));
-                        self.decorate(decorated_id, Decoration::Block, &[]);
-                    }
-                    _ => (),
-                };
-            }
+            // a runtime-sized or binding array. Structs with runtime-sized
+            // arrays, and the elements of binding arrays of buffers, were
+            // decorated with `Block` when their types were declared.
             if substitute_inner_type_lookup.is_some() {
                 inner_type_id
             } else {
@@ -1741,6 +2388,52 @@ impl Writer {
         Ok(id)
     }
 
+    /// Request the capabilities and extensions needed to store 8- and 16-bit
+    /// types in `global_variable`'s buffer.
+    ///
+    /// The `Int8` and `Int16` capabilities requested when declaring the types
+    /// themselves don't cover their use in `Uniform`, `StorageBuffer`, or
+    /// `PushConstant` storage, which needs the capabilities from
+    /// `SPV_KHR_8bit_storage` and `SPV_KHR_16bit_storage`.
+    fn request_narrow_storage_capabilities(
+        &mut self,
+        ir_module: &crate::Module,
+        global_variable: &crate::GlobalVariable,
+    ) -> Result<(), Error> {
+        use spirv::Capability as Ca;
+
+        let (storage_8, storage_16): (&[Ca], &[Ca]) = match global_variable.space {
+            crate::AddressSpace::Storage { .. } => (
+                &[
+                    Ca::StorageBuffer8BitAccess,
+                    Ca::UniformAndStorageBuffer8BitAccess,
+                ],
+                &[
+                    Ca::StorageBuffer16BitAccess,
+                    Ca::UniformAndStorageBuffer16BitAccess,
+                ],
+            ),
+            crate::AddressSpace::Uniform => (
+                &[Ca::UniformAndStorageBuffer8BitAccess],
+                &[Ca::UniformAndStorageBuffer16BitAccess],
+            ),
+            crate::AddressSpace::PushConstant => {
+                (&[Ca::StoragePushConstant8], &[Ca::StoragePushConstant16])
+            }
+            _ => return Ok(()),
+        };
+
+        if contains_scalar_width(global_variable.ty, &ir_module.types, 1) {
+            self.require_any("8-bit types in buffers", storage_8)?;
+            self.use_extension("SPV_KHR_8bit_storage");
+        }
+        if contains_scalar_width(global_variable.ty, &ir_module.types, 2) {
+            self.require_any("16-bit types in buffers", storage_16)?;
+            self.use_extension("SPV_KHR_16bit_storage");
+        }
+        Ok(())
+    }
+
     /// Write the necessary decorations for a struct member.
     ///
     /// Emit decorations for the `index`'th member of the struct type
@@ -1754,12 +2447,12 @@ impl Writer {
     ) -> Result<(), Error> {
         use spirv::Decoration;
 
-        self.annotations.push(Instruction::member_decorate(
+        self.member_decorate(
             struct_id,
             index as u32,
             Decoration::Offset,
             &[member.offset],
-        ));
+        )?;
 
         if self.flags.contains(WriterFlags::DEBUG) {
             if let Some(ref name) = member.name {
@@ -1781,18 +2474,13 @@ impl Writer {
         } = *member_array_subty_inner
         {
             let byte_stride = Alignment::from(rows) * scalar.width as u32;
-            self.annotations.push(Instruction::member_decorate(
-                struct_id,
-                index as u32,
-                Decoration::ColMajor,
-                &[],
-            ));
-            self.annotations.push(Instruction::member_decorate(
+            self.member_decorate(struct_id, index as u32, Decoration::ColMajor, &[])?;
+            self.member_decorate(
                 struct_id,
                 index as u32,
                 Decoration::MatrixStride,
                 &[byte_stride],
-            ));
+            )?;
         }
 
         Ok(())
@@ -1842,6 +2530,10 @@ impl Writer {
             }
         }
 
+        if crate::external_texture::has_external_textures(ir_module) {
+            return Err(Error::FeatureNotImplemented("external textures"));
+        }
+
         let has_storage_buffers =
             ir_module
                 .global_variables
@@ -1871,6 +2563,20 @@ impl Writer {
             Instruction::extension("SPV_KHR_ray_query")
                 .to_words(&mut self.logical_layout.extensions)
         }
+        if self.flags.contains(WriterFlags::VULKAN_MEMORY_MODEL) {
+            self.require_any(
+                "the Vulkan memory model",
+                &[spirv::Capability::VulkanMemoryModel],
+            )?;
+            // Atomics and coherent accesses to storage buffers use `Device` scope.
+            self.require_any(
+                "device scope in the Vulkan memory model",
+                &[spirv::Capability::VulkanMemoryModelDeviceScope],
+            )?;
+            if self.physical_layout.version < 0x10500 {
+                self.use_extension("SPV_KHR_vulkan_memory_model");
+            }
+        }
         Instruction::type_void(self.void_type).to_words(&mut self.logical_layout.declarations);
         Instruction::ext_inst_import(self.gl450_ext_inst_id, "GLSL.std.450")
             .to_words(&mut self.logical_layout.ext_inst_imports);
@@ -1896,10 +2602,29 @@ impl Writer {
             }
         }
 
+        #[cfg(feature = "telemetry")]
+        let mut stopwatch = crate::telemetry::Stopwatch::start();
+
+        // write override constants first, since array types may use them
+        self.write_override_constants(ir_module)?;
+
         // write all types
-        for (handle, _) in ir_module.types.iter() {
+        for (handle, ty) in ir_module.types.iter() {
+            // `external_texture::lower` may leave the external image type
+            // behind, unused.
+            if let crate::TypeInner::Image {
+                class: crate::ImageClass::External,
+                ..
+            } = ty.inner
+            {
+                continue;
+            }
             self.write_type_declaration_arena(&ir_module.types, handle)?;
         }
+        #[cfg(feature = "telemetry")]
+        {
+            self.statistics.types = stopwatch.lap();
+        }
 
         // write all const-expressions as constants
         self.constant_ids
@@ -1908,10 +2633,18 @@ impl Writer {
             self.write_constant_expr(handle, ir_module, mod_info)?;
         }
         debug_assert!(self.constant_ids.iter().all(|&id| id != 0));
+        #[cfg(feature = "telemetry")]
+        {
+            self.statistics.constants = stopwatch.lap();
+        }
 
         // write the name of constants on their respective const-expression initializer
         if self.flags.contains(WriterFlags::DEBUG) {
-            for (_, constant) in ir_module.constants.iter() {
+            for (handle, constant) in ir_module.constants.iter() {
+                // `write_override_constants` named these already.
+                if self.override_ids[handle.index()] != 0 {
+                    continue;
+                }
                 if let Some(ref name) = constant.name {
                     let id = self.constant_ids[constant.init.index()];
                     self.debugs.push(Instruction::name(id, name));
@@ -1929,12 +2662,17 @@ impl Writer {
                     GlobalVariable::dummy()
                 }
                 _ => {
+                    self.check_global_binding(ir_module, mod_info, handle, var)?;
                     let id = self.write_global_variable(ir_module, var)?;
                     GlobalVariable::new(id)
                 }
             };
             self.global_variables.push(gvar);
         }
+        #[cfg(feature = "telemetry")]
+        {
+            self.statistics.global_variables = stopwatch.lap();
+        }
 
         // write all functions
         for (handle, ir_function) in ir_module.functions.iter() {
@@ -1945,7 +2683,10 @@ impl Writer {
                 // because the entry point and its callees didn't use them,
                 // then we must skip it.
                 if !ep_info.dominates_global_use(info) {
-                    log::info!("Skip function {:?}", ir_function.name);
+                    log::debug!(
+                        "Skip function {:?}: it uses globals the entry point doesn't",
+                        ir_function.name
+                    );
                     continue;
                 }
 
@@ -1959,16 +2700,25 @@ impl Writer {
                 // `ShaderStages::all()`, so this will write all functions in the module, and
                 // the downstream GLSL compiler will catch any problems.
                 if !info.available_stages.contains(ep_info.available_stages) {
+                    log::debug!(
+                        "Skip function {:?}: not available in the entry point's stages",
+                        ir_function.name
+                    );
                     continue;
                 }
             }
             let id = self.write_function(ir_function, info, ir_module, None, &debug_info_inner)?;
             self.lookup_function.insert(handle, id);
         }
+        #[cfg(feature = "telemetry")]
+        {
+            self.statistics.functions = stopwatch.lap();
+        }
 
         // write all or one entry points
         for (index, ir_ep) in ir_module.entry_points.iter().enumerate() {
             if ep_index.is_some() && ep_index != Some(index) {
+                log::debug!("Skip entry point {:?}: not selected", ir_ep.name);
                 continue;
             }
             let info = mod_info.get_entry_point(index);
@@ -1976,6 +2726,10 @@ impl Writer {
                 self.write_entry_point(ir_ep, info, ir_module, &debug_info_inner)?;
             ep_instruction.to_words(&mut self.logical_layout.entry_points);
         }
+        #[cfg(feature = "telemetry")]
+        {
+            self.statistics.entry_points = stopwatch.lap();
+        }
 
         for capability in self.capabilities_used.iter() {
             Instruction::capability(*capability).to_words(&mut self.logical_layout.capabilities);
@@ -1990,7 +2744,11 @@ impl Writer {
         }
 
         let addressing_model = spirv::AddressingModel::Logical;
-        let memory_model = spirv::MemoryModel::GLSL450;
+        let memory_model = if self.flags.contains(WriterFlags::VULKAN_MEMORY_MODEL) {
+            spirv::MemoryModel::Vulkan
+        } else {
+            spirv::MemoryModel::GLSL450
+        };
         //self.check(addressing_model.required_capabilities())?;
         //self.check(memory_model.required_capabilities())?;
 
@@ -1998,11 +2756,17 @@ impl Writer {
             .to_words(&mut self.logical_layout.memory_model);
 
         if self.flags.contains(WriterFlags::DEBUG) {
+            self.sort_debug_names();
             for debug in self.debugs.iter() {
                 debug.to_words(&mut self.logical_layout.debugs);
             }
         }
 
+        if self.flags.contains(WriterFlags::MINIMAL_OUTPUT) {
+            self.sort_annotations();
+            self.logical_layout.sort_declarations();
+        }
+
         for annotation in self.annotations.iter() {
             annotation.to_words(&mut self.logical_layout.annotations);
         }
@@ -2010,6 +2774,47 @@ impl Writer {
         Ok(())
     }
 
+    /// Put the decorations in `self.annotations` in order of their target.
+    ///
+    /// [`Writer::decorate`] has already dropped any duplicates.
+    fn sort_annotations(&mut self) {
+        // Decorations of a target sort before decorations of its members.
+        fn key(instruction: &Instruction) -> (Word, bool, &[Word]) {
+            (
+                instruction.operands[0],
+                instruction.op != spirv::Op::Decorate,
+                &instruction.operands[1..],
+            )
+        }
+
+        self.annotations.sort_by(|a, b| key(a).cmp(&key(b)));
+    }
+
+    /// Put the `OpName` and `OpMemberName` instructions in `self.debugs` in
+    /// order of their target, after any other debug instructions.
+    ///
+    /// The order names are added in depends on how the module is traversed,
+    /// and distinct IR constants may share an id. If a target was named more
+    /// than once, only the first name is kept.
+    fn sort_debug_names(&mut self) {
+        // Within a target, its `OpName` sorts before its `OpMemberName`s.
+        fn key(instruction: &Instruction) -> Option<(Word, Word)> {
+            match instruction.op {
+                spirv::Op::Name => Some((instruction.operands[0], 0)),
+                spirv::Op::MemberName => {
+                    Some((instruction.operands[0], instruction.operands[1] + 1))
+                }
+                _ => None,
+            }
+        }
+
+        // Sorting is stable, so the other instructions keep their order,
+        // and duplicates stay in the order they were added.
+        self.debugs.sort_by_key(key);
+        self.debugs
+            .dedup_by(|later, earlier| key(later).is_some() && key(later) == key(earlier));
+    }
+
     pub fn write(
         &mut self,
         ir_module: &crate::Module,
@@ -2017,30 +2822,93 @@ impl Writer {
         pipeline_options: Option<&PipelineOptions>,
         debug_info: &Option<DebugInfo>,
         words: &mut Vec<Word>,
+    ) -> Result<(), Error> {
+        #[cfg(feature = "telemetry")]
+        let stopwatch = crate::telemetry::Stopwatch::start();
+
+        let result = self.write_module(ir_module, info, pipeline_options, debug_info, words);
+        #[cfg(feature = "telemetry")]
+        {
+            self.statistics.total = stopwatch.total();
+        }
+        result
+    }
+
+    fn write_module(
+        &mut self,
+        ir_module: &crate::Module,
+        info: &ModuleInfo,
+        pipeline_options: Option<&PipelineOptions>,
+        debug_info: &Option<DebugInfo>,
+        words: &mut Vec<Word>,
     ) -> Result<(), Error> {
         self.reset();
 
         // Try to find the entry point and corresponding index
         let ep_index = match pipeline_options {
             Some(po) => {
-                let index = ir_module
-                    .entry_points
-                    .iter()
-                    .position(|ep| po.shader_stage == ep.stage && po.entry_point == ep.name)
-                    .ok_or(Error::EntryPointNotFound)?;
+                let index =
+                    crate::back::find_entry_point(ir_module, po.shader_stage, &po.entry_point)
+                        .map_err(|error| {
+                            let stage = po.shader_stage;
+                            let name = po.entry_point.clone();
+                            match error {
+                                crate::back::EntryPointLookupError::NotFound => {
+                                    Error::EntryPointNotFound {
+                                        stage,
+                                        name,
+                                        available: crate::back::EntryPointNames::new(ir_module),
+                                    }
+                                }
+                                crate::back::EntryPointLookupError::Ambiguous => {
+                                    Error::AmbiguousEntryPoint { stage, name }
+                                }
+                            }
+                        })?;
                 Some(index)
             }
             None => None,
         };
 
+        let inlined;
+        let (ir_module, info) = if crate::proc::has_pointer_calls(ir_module)
+            && !self.use_variable_pointers_for_calls()
+        {
+            inlined = inline_pointer_calls(ir_module)?;
+            (&inlined.0, &inlined.1)
+        } else {
+            (ir_module, info)
+        };
+
         self.write_logical_layout(ir_module, info, ep_index, debug_info)?;
         self.write_physical_layout();
 
+        #[cfg(feature = "telemetry")]
+        let header_end = words.len() + 5;
         self.physical_layout.in_words(words);
         self.logical_layout.in_words(words);
+
+        #[cfg(feature = "telemetry")]
+        {
+            let mut instructions = 0;
+            let mut offset = header_end;
+            while offset < words.len() {
+                // The high half of an instruction's first word is its length.
+                offset += (words[offset] >> 16).max(1) as usize;
+                instructions += 1;
+            }
+            self.statistics.instructions = instructions;
+            self.statistics.ids_allocated = self.id_gen.0;
+        }
         Ok(())
     }
 
+    /// Return statistics about the last module written.
+    #[cfg(feature = "telemetry")]
+    pub const fn statistics(&self) -> &super::WriteStatistics {
+        &self.statistics
+    }
+
     /// Return the set of capabilities the last module written used.
     pub const fn get_capabilities_used(&self) -> &crate::FastIndexSet<spirv::Capability> {
         &self.capabilities_used
@@ -2049,11 +2917,28 @@ impl Writer {
     pub fn decorate_non_uniform_binding_array_access(&mut self, id: Word) -> Result<(), Error> {
         self.require_any("NonUniformEXT", &[spirv::Capability::ShaderNonUniform])?;
         self.use_extension("SPV_EXT_descriptor_indexing");
-        self.decorate(id, spirv::Decoration::NonUniform, &[]);
+        self.decorate(id, spirv::Decoration::NonUniform, &[])?;
         Ok(())
     }
 }
 
+/// Return a copy of `module` with the calls that pass pointers other than
+/// whole variables inlined, and its new [`ModuleInfo`].
+fn inline_pointer_calls(module: &crate::Module) -> Result<(crate::Module, ModuleInfo), Error> {
+    let mut module = module.clone();
+    crate::proc::inline_pointer_calls(&mut module);
+    let info = crate::valid::Validator::new(
+        crate::valid::ValidationFlags::default(),
+        crate::valid::Capabilities::all(),
+    )
+    .validate(&module)
+    .map_err(|error| {
+        log::error!("inlining calls that pass pointers broke the module: {error}");
+        Error::Validation("inlining calls that pass pointers")
+    })?;
+    Ok((module, info))
+}
+
 #[test]
 fn test_write_physical_layout() {
     let mut writer = Writer::new(&Options::default()).unwrap();
@@ -2061,3 +2946,140 @@ fn test_write_physical_layout() {
     writer.write_physical_layout();
     assert_eq!(writer.physical_layout.bound, 3);
 }
+
+#[cfg(feature = "wgsl-in")]
+#[test]
+fn test_debug_names_sorted_and_unique() {
+    // `a` and `b` share a constant id, and `S` has both a name and member names.
+    let source = "
+        const a = 1;
+        const b = 1;
+        struct S { x: i32, y: i32 }
+        @group(0) @binding(0) var<storage, read_write> s: S;
+        @compute @workgroup_size(1)
+        fn main() {
+            s.x = a;
+            s.y = b;
+        }
+    ";
+    let module = crate::front::wgsl::parse_str(source).unwrap();
+    let info = crate::valid::Validator::new(
+        crate::valid::ValidationFlags::all(),
+        crate::valid::Capabilities::all(),
+    )
+    .validate(&module)
+    .unwrap();
+    let options = Options {
+        flags: WriterFlags::DEBUG,
+        ..Options::default()
+    };
+    let first = super::write_vec(&module, &info, &options, None).unwrap();
+    let second = super::write_vec(&module, &info, &options, None).unwrap();
+    assert_eq!(first, second);
+
+    // Skip the header, then collect the targets of every name.
+    let mut names = Vec::new();
+    let mut words = &first[5..];
+    while let Some(&word) = words.first() {
+        let (count, op) = ((word >> 16) as usize, word & 0xffff);
+        if op == spirv::Op::Name as u32 {
+            names.push((words[1], 0));
+        } else if op == spirv::Op::MemberName as u32 {
+            names.push((words[1], words[2] + 1));
+        }
+        words = &words[count..];
+    }
+    assert!(names.len() > 2);
+    assert!(
+        names.windows(2).all(|pair| pair[0] < pair[1]),
+        "names are not sorted and unique: {names:?}"
+    );
+}
+
+#[test]
+fn test_decorations_unique() {
+    use spirv::Decoration;
+
+    let mut writer = Writer::new(&Options::default()).unwrap();
+    writer.decorate(5, Decoration::Location, &[0]).unwrap();
+    writer.decorate(5, Decoration::Location, &[0]).unwrap();
+    writer
+        .member_decorate(5, 0, Decoration::Offset, &[0])
+        .unwrap();
+    writer
+        .member_decorate(5, 1, Decoration::Offset, &[16])
+        .unwrap();
+    writer
+        .member_decorate(5, 1, Decoration::Offset, &[16])
+        .unwrap();
+    assert_eq!(writer.annotations.len(), 3);
+
+    match writer.decorate(5, Decoration::Location, &[1]) {
+        Err(Error::ConflictingDecoration {
+            id: 5,
+            member: None,
+            decoration: Decoration::Location,
+            existing,
+            new,
+        }) => assert_eq!((existing, new), (vec![0], vec![1])),
+        other => panic!("expected a conflicting decoration, got {other:?}"),
+    }
+    assert!(matches!(
+        writer.member_decorate(5, 1, Decoration::Offset, &[32]),
+        Err(Error::ConflictingDecoration {
+            member: Some(1),
+            ..
+        })
+    ));
+    assert_eq!(writer.annotations.len(), 3);
+}
+
+/// Writing a module resets the decorations, so a `Writer` can write several
+/// modules, whose ids overlap.
+#[cfg(feature = "wgsl-in")]
+#[test]
+fn test_decorations_reset() {
+    let sources = [
+        "
+        struct Varyings {
+            @builtin(position) position: vec4<f32>,
+            @location(0) color: vec4<f32>,
+            @location(1) @interpolate(flat) index: u32,
+        }
+        @vertex
+        fn vs(@location(2) position: vec4<f32>) -> Varyings {
+            return Varyings(position, position, 0u);
+        }
+        @fragment
+        fn fs(in: Varyings) -> @location(0) vec4<f32> {
+            return in.color;
+        }
+        ",
+        "
+        @group(0) @binding(3) var<storage, read_write> data: array<u32>;
+        @compute @workgroup_size(1)
+        fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+            data[id.x] = id.y;
+        }
+        ",
+    ];
+
+    let mut writer = Writer::new(&Options::default()).unwrap();
+    let mut outputs = Vec::new();
+    for source in sources.iter().chain(&sources) {
+        let module = crate::front::wgsl::parse_str(source).unwrap();
+        let info = crate::valid::Validator::new(
+            crate::valid::ValidationFlags::all(),
+            crate::valid::Capabilities::all(),
+        )
+        .validate(&module)
+        .unwrap();
+        let mut words = Vec::new();
+        writer
+            .write(&module, &info, None, &None, &mut words)
+            .unwrap();
+        outputs.push(words);
+    }
+    assert_eq!(outputs[0], outputs[2]);
+    assert_eq!(outputs[1], outputs[3]);
+}
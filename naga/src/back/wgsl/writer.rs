@@ -13,13 +13,15 @@ type BackendResult = Result<(), Error>;
 enum Attribute {
     Binding(u32),
     BuiltIn(crate::BuiltIn),
+    Coherent,
     Group(u32),
     Invariant,
     Interpolate(Option<crate::Interpolation>, Option<crate::Sampling>),
     Location(u32),
     SecondBlendSource,
     Stage(ShaderStage),
-    WorkGroupSize([u32; 3]),
+    Volatile,
+    WorkGroupSize([u32; 3], Option<[Option<Handle<crate::Constant>>; 3]>),
 }
 
 /// The WGSL form that `write_expr_with_indirection` should use to render a Naga
@@ -58,6 +60,12 @@ bitflags::bitflags! {
     pub struct WriterFlags: u32 {
         /// Always annotate the type information instead of inferring.
         const EXPLICIT_TYPES = 0x1;
+        /// Declare named types other than structs as type aliases, like
+        /// `alias Vec = vec4<f32>;`, and refer to them by name.
+        ///
+        /// The WGSL frontend names the types it creates for aliases, so this
+        /// keeps their names in round-tripped shaders.
+        const USE_TYPE_ALIASES = 0x2;
     }
 }
 
@@ -108,6 +116,15 @@ impl<W: Write> Writer<W> {
     pub fn write(&mut self, module: &Module, info: &valid::ModuleInfo) -> BackendResult {
         self.reset(module);
 
+        // Write all enable directives
+        let extensions = required_extensions(module);
+        if !extensions.is_empty() {
+            for extension in extensions.iter() {
+                writeln!(self.out, "enable {};", extension.to_wgsl())?;
+            }
+            writeln!(self.out)?;
+        }
+
         // Save all ep result types
         for (_, ep) in module.entry_points.iter().enumerate() {
             if let Some(ref result) = ep.function.result {
@@ -115,17 +132,29 @@ impl<W: Write> Writer<W> {
             }
         }
 
-        // Write all structs
+        // Write all structs, and type aliases if requested. Types only refer
+        // to types before them in the arena, so declaring them in order
+        // declares everything before its first use.
+        let mut wrote_alias = false;
         for (handle, ty) in module.types.iter() {
             if let TypeInner::Struct { ref members, .. } = ty.inner {
-                {
-                    if !self.is_builtin_wgsl_struct(module, handle) {
-                        self.write_struct(module, handle, members)?;
+                if !self.is_builtin_wgsl_struct(module, handle) {
+                    if std::mem::take(&mut wrote_alias) {
                         writeln!(self.out)?;
                     }
+                    self.write_struct(module, handle, members)?;
+                    writeln!(self.out)?;
                 }
+            } else if self.is_type_alias(module, handle) {
+                write!(self.out, "alias {} = ", self.names[&NameKey::Type(handle)])?;
+                self.write_value_type(module, &ty.inner)?;
+                writeln!(self.out, ";")?;
+                wrote_alias = true;
             }
         }
+        if wrote_alias {
+            writeln!(self.out)?;
+        }
 
         // Write all named constants
         let mut constants = module
@@ -172,12 +201,20 @@ impl<W: Write> Writer<W> {
         for (index, ep) in module.entry_points.iter().enumerate() {
             let attributes = match ep.stage {
                 ShaderStage::Vertex | ShaderStage::Fragment => vec![Attribute::Stage(ep.stage)],
-                ShaderStage::Compute => vec![
-                    Attribute::Stage(ShaderStage::Compute),
-                    Attribute::WorkGroupSize(ep.workgroup_size),
+                ShaderStage::Compute | ShaderStage::Task => vec![
+                    Attribute::Stage(ep.stage),
+                    Attribute::WorkGroupSize(ep.workgroup_size, ep.workgroup_size_overrides),
                 ],
+                // The `@mesh` attribute names types, so it is written below.
+                ShaderStage::Mesh => vec![Attribute::WorkGroupSize(
+                    ep.workgroup_size,
+                    ep.workgroup_size_overrides,
+                )],
             };
 
+            if let Some(ref mesh_info) = ep.mesh_info {
+                self.write_mesh_attribute(module, mesh_info)?;
+            }
             self.write_attributes(&attributes)?;
             // Add a newline after attribute
             writeln!(self.out)?;
@@ -198,6 +235,34 @@ impl<W: Write> Writer<W> {
         Ok(())
     }
 
+    /// Write the `@mesh(...)` attribute of a mesh shader entry point.
+    fn write_mesh_attribute(
+        &mut self,
+        module: &Module,
+        mesh_info: &crate::MeshStageInfo,
+    ) -> BackendResult {
+        let topology = match mesh_info.topology {
+            crate::MeshOutputTopology::Points => "points",
+            crate::MeshOutputTopology::Lines => "lines",
+            crate::MeshOutputTopology::Triangles => "triangles",
+        };
+        write!(self.out, "@mesh({topology}, ")?;
+        self.write_type(module, mesh_info.vertex_output_type)?;
+        write!(self.out, ", {}, ", mesh_info.max_vertices)?;
+        self.write_type(module, mesh_info.primitive_output_type)?;
+        write!(self.out, ", {}) ", mesh_info.max_primitives)?;
+        Ok(())
+    }
+
+    /// Return true if we should declare `handle` with an `alias`
+    /// declaration, and refer to it by name.
+    fn is_type_alias(&self, module: &Module, handle: Handle<crate::Type>) -> bool {
+        let ty = &module.types[handle];
+        self.flags.contains(WriterFlags::USE_TYPE_ALIASES)
+            && ty.name.is_some()
+            && !matches!(ty.inner, TypeInner::Struct { .. })
+    }
+
     /// Helper method used to write struct name
     ///
     /// # Notes
@@ -209,6 +274,8 @@ impl<W: Write> Writer<W> {
                     ShaderStage::Compute => "ComputeOutput",
                     ShaderStage::Fragment => "FragmentOutput",
                     ShaderStage::Vertex => "VertexOutput",
+                    ShaderStage::Task => "TaskOutput",
+                    ShaderStage::Mesh => "MeshOutput",
                 };
 
                 write!(self.out, "{name}")?;
@@ -331,18 +398,32 @@ impl<W: Write> Writer<W> {
                         ShaderStage::Vertex => "vertex",
                         ShaderStage::Fragment => "fragment",
                         ShaderStage::Compute => "compute",
+                        ShaderStage::Task => "task",
+                        ShaderStage::Mesh => "mesh",
                     };
                     write!(self.out, "@{stage_str} ")?;
                 }
-                Attribute::WorkGroupSize(size) => {
-                    write!(
-                        self.out,
-                        "@workgroup_size({}, {}, {}) ",
-                        size[0], size[1], size[2]
-                    )?;
+                Attribute::WorkGroupSize(size, overrides) => {
+                    write!(self.out, "@workgroup_size(")?;
+                    for (i, (size, handle)) in
+                        size.iter().zip(overrides.unwrap_or_default()).enumerate()
+                    {
+                        if i != 0 {
+                            write!(self.out, ", ")?;
+                        }
+                        match handle {
+                            Some(handle) => {
+                                write!(self.out, "{}", self.names[&NameKey::Constant(handle)])?
+                            }
+                            None => write!(self.out, "{size}")?,
+                        }
+                    }
+                    write!(self.out, ") ")?;
                 }
                 Attribute::Binding(id) => write!(self.out, "@binding({id}) ")?,
                 Attribute::Group(id) => write!(self.out, "@group({id}) ")?,
+                Attribute::Coherent => write!(self.out, "@coherent ")?,
+                Attribute::Volatile => write!(self.out, "@volatile ")?,
                 Attribute::Invariant => write!(self.out, "@invariant ")?,
                 Attribute::Interpolate(interpolation, sampling) => {
                     if sampling.is_some() && sampling != Some(crate::Sampling::Center) {
@@ -414,6 +495,9 @@ impl<W: Write> Writer<W> {
         let inner = &module.types[ty].inner;
         match *inner {
             TypeInner::Struct { .. } => self.write_struct_name(module, ty)?,
+            _ if self.is_type_alias(module, ty) => {
+                write!(self.out, "{}", self.names[&NameKey::Type(ty)])?
+            }
             ref other => self.write_value_type(module, other)?,
         }
 
@@ -438,6 +522,12 @@ impl<W: Write> Writer<W> {
             TypeInner::Sampler { comparison: true } => {
                 write!(self.out, "sampler_comparison")?;
             }
+            TypeInner::Image {
+                class: crate::ImageClass::External,
+                ..
+            } => {
+                write!(self.out, "texture_external")?;
+            }
             TypeInner::Image {
                 dim,
                 arrayed,
@@ -471,6 +561,7 @@ impl<W: Write> Writer<W> {
                             ",write"
                         },
                     ),
+                    Ic::External => unreachable!(),
                 };
                 write!(
                     self.out,
@@ -501,6 +592,10 @@ impl<W: Write> Writer<W> {
                         self.write_type(module, base)?;
                         write!(self.out, ", {len}")?;
                     }
+                    crate::ArraySize::Pending(handle) => {
+                        self.write_type(module, base)?;
+                        write!(self.out, ", {}", self.names[&NameKey::Constant(handle)])?;
+                    }
                     crate::ArraySize::Dynamic => {
                         self.write_type(module, base)?;
                     }
@@ -515,6 +610,8 @@ impl<W: Write> Writer<W> {
                         self.write_type(module, base)?;
                         write!(self.out, ", {len}")?;
                     }
+                    // Rejected by validation.
+                    crate::ArraySize::Pending(_) => unreachable!(),
                     crate::ArraySize::Dynamic => {
                         self.write_type(module, base)?;
                     }
@@ -654,8 +751,12 @@ impl<W: Write> Writer<W> {
                 condition,
                 ref accept,
                 ref reject,
+                hint,
             } => {
                 write!(self.out, "{level}")?;
+                if let Some(hint) = hint.filter(|_| writes_hints(module)) {
+                    write!(self.out, "{} ", branch_hint_str(hint))?;
+                }
                 write!(self.out, "if ")?;
                 self.write_expr(module, condition, func_ctx)?;
                 writeln!(self.out, " {{")?;
@@ -694,6 +795,13 @@ impl<W: Write> Writer<W> {
                 write!(self.out, "{level}")?;
                 writeln!(self.out, "discard;")?
             }
+            // Write markers in the form the WGSL frontend reads them back.
+            Statement::DebugMarker(ref text) => {
+                for line in back::comment_lines(text) {
+                    let space = if line.is_empty() { "" } else { " " };
+                    writeln!(self.out, "{level}//@marker{space}{line}")?;
+                }
+            }
             Statement::Store { pointer, value } => {
                 write!(self.out, "{level}")?;
 
@@ -869,8 +977,12 @@ impl<W: Write> Writer<W> {
                 ref body,
                 ref continuing,
                 break_if,
+                hint,
             } => {
                 write!(self.out, "{level}")?;
+                if let Some(hint) = hint.filter(|_| writes_hints(module)) {
+                    write!(self.out, "{} ", loop_hint_str(hint))?;
+                }
                 writeln!(self.out, "loop {{")?;
 
                 let l2 = level.next();
@@ -918,6 +1030,23 @@ impl<W: Write> Writer<W> {
                     writeln!(self.out, "{level}workgroupBarrier();")?;
                 }
             }
+            Statement::MeshFunction(ref fun) => {
+                let (name, first, second) = match *fun {
+                    crate::MeshFunction::SetMeshOutputs {
+                        vertex_count,
+                        primitive_count,
+                    } => ("setMeshOutputs", vertex_count, primitive_count),
+                    crate::MeshFunction::SetVertex { index, value } => ("setVertex", index, value),
+                    crate::MeshFunction::SetPrimitive { index, value } => {
+                        ("setPrimitive", index, value)
+                    }
+                };
+                write!(self.out, "{level}{name}(")?;
+                self.write_expr(module, first, func_ctx)?;
+                write!(self.out, ", ")?;
+                self.write_expr(module, second, func_ctx)?;
+                writeln!(self.out, ");")?;
+            }
             Statement::RayQuery { .. } => unreachable!(),
         }
 
@@ -1245,6 +1374,29 @@ impl<W: Write> Writer<W> {
                     ref other => return Err(Error::Custom(format!("Cannot index {other:?}"))),
                 }
             }
+            // External images can only be sampled at level zero, clamped to
+            // the edge texels.
+            Expression::ImageSample {
+                image,
+                sampler,
+                coordinate,
+                ..
+            } if matches!(
+                *func_ctx.resolve_type(image, &module.types),
+                TypeInner::Image {
+                    class: crate::ImageClass::External,
+                    ..
+                }
+            ) =>
+            {
+                write!(self.out, "textureSampleBaseClampToEdge(")?;
+                self.write_expr(module, image, func_ctx)?;
+                write!(self.out, ", ")?;
+                self.write_expr(module, sampler, func_ctx)?;
+                write!(self.out, ", ")?;
+                self.write_expr(module, coordinate, func_ctx)?;
+                write!(self.out, ")")?;
+            }
             Expression::ImageSample {
                 image,
                 sampler,
@@ -1552,6 +1704,8 @@ impl<W: Write> Writer<W> {
                     Mf::Pow => Function::Regular("pow"),
                     // geometry
                     Mf::Dot => Function::Regular("dot"),
+                    Mf::Dot4I8Packed => Function::Regular("dot4I8Packed"),
+                    Mf::Dot4U8Packed => Function::Regular("dot4U8Packed"),
                     Mf::Cross => Function::Regular("cross"),
                     Mf::Distance => Function::Regular("distance"),
                     Mf::Length => Function::Regular("length"),
@@ -1569,6 +1723,7 @@ impl<W: Write> Writer<W> {
                     Mf::InverseSqrt => Function::Regular("inverseSqrt"),
                     Mf::Transpose => Function::Regular("transpose"),
                     Mf::Determinant => Function::Regular("determinant"),
+                    Mf::QuantizeToF16 => Function::Regular("quantizeToF16"),
                     // bits
                     Mf::CountTrailingZeros => Function::Regular("countTrailingZeros"),
                     Mf::CountLeadingZeros => Function::Regular("countLeadingZeros"),
@@ -1706,6 +1861,24 @@ impl<W: Write> Writer<W> {
             writeln!(self.out)?;
         }
 
+        if !global.memory_decorations.is_empty() {
+            let mut attributes = Vec::new();
+            if global
+                .memory_decorations
+                .contains(crate::MemoryDecorations::COHERENT)
+            {
+                attributes.push(Attribute::Coherent);
+            }
+            if global
+                .memory_decorations
+                .contains(crate::MemoryDecorations::VOLATILE)
+            {
+                attributes.push(Attribute::Volatile);
+            }
+            self.write_attributes(&attributes)?;
+            writeln!(self.out)?;
+        }
+
         // First write global name and address space if supported
         write!(self.out, "var")?;
         let (address, maybe_access) = address_space_str(global.space);
@@ -1765,6 +1938,90 @@ impl<W: Write> Writer<W> {
     }
 }
 
+/// Return the extensions `module` needs enabled to be expressed in WGSL.
+///
+/// This is the union of [`Module::enable_extensions`] and any extensions
+/// implied by entry point bindings, so that modules from other front ends
+/// still produce valid WGSL.
+fn required_extensions(module: &Module) -> crate::EnableExtensions {
+    fn visit(
+        module: &Module,
+        ty: Handle<crate::Type>,
+        binding: Option<&crate::Binding>,
+        extensions: &mut crate::EnableExtensions,
+    ) {
+        match binding {
+            Some(&crate::Binding::BuiltIn(crate::BuiltIn::ClipDistance)) => {
+                *extensions |= crate::EnableExtensions::CLIP_DISTANCES;
+            }
+            Some(&crate::Binding::Location {
+                second_blend_source: true,
+                ..
+            }) => {
+                *extensions |= crate::EnableExtensions::DUAL_SOURCE_BLENDING;
+            }
+            Some(_) => {}
+            None => {
+                if let TypeInner::Struct { ref members, .. } = module.types[ty].inner {
+                    for member in members {
+                        visit(module, member.ty, member.binding.as_ref(), extensions);
+                    }
+                }
+            }
+        }
+    }
+
+    let mut extensions = module.enable_extensions;
+    for ep in module.entry_points.iter() {
+        for arg in ep.function.arguments.iter() {
+            visit(module, arg.ty, arg.binding.as_ref(), &mut extensions);
+        }
+        if let Some(ref result) = ep.function.result {
+            visit(module, result.ty, result.binding.as_ref(), &mut extensions);
+        }
+    }
+    if module
+        .global_variables
+        .iter()
+        .any(|(_, var)| !var.memory_decorations.is_empty())
+    {
+        extensions |= crate::EnableExtensions::NAGA_MEMORY_DECORATIONS;
+    }
+    if module
+        .entry_points
+        .iter()
+        .any(|ep| matches!(ep.stage, ShaderStage::Task | ShaderStage::Mesh))
+    {
+        extensions |= crate::EnableExtensions::NAGA_MESH_SHADER;
+    }
+    extensions
+}
+
+/// Return true if loop and `if` hints should be written for `module`.
+///
+/// The hint attributes are specific to Naga, and need an `enable` directive.
+/// Since hints don't affect behavior, drop them unless the module's source
+/// already enabled them.
+const fn writes_hints(module: &Module) -> bool {
+    module
+        .enable_extensions
+        .contains(crate::EnableExtensions::NAGA_CONTROL_FLOW_HINTS)
+}
+
+const fn loop_hint_str(hint: crate::LoopHint) -> &'static str {
+    match hint {
+        crate::LoopHint::Unroll => "@unroll",
+        crate::LoopHint::DontUnroll => "@dont_unroll",
+    }
+}
+
+const fn branch_hint_str(hint: crate::BranchHint) -> &'static str {
+    match hint {
+        crate::BranchHint::Branch => "@branch",
+        crate::BranchHint::Flatten => "@flatten",
+    }
+}
+
 fn builtin_str(built_in: crate::BuiltIn) -> Result<&'static str, Error> {
     use crate::BuiltIn as Bi;
 
@@ -1783,9 +2040,14 @@ fn builtin_str(built_in: crate::BuiltIn) -> Result<&'static str, Error> {
         Bi::SampleMask => "sample_mask",
         Bi::PrimitiveIndex => "primitive_index",
         Bi::ViewIndex => "view_index",
+        Bi::ClipDistance => "clip_distances",
+        Bi::MeshTaskSize => "mesh_task_size",
+        Bi::TriangleIndices => "triangle_indices",
+        Bi::LineIndices => "line_indices",
+        Bi::PointIndex => "point_index",
+        Bi::CullPrimitive => "cull_primitive",
         Bi::BaseInstance
         | Bi::BaseVertex
-        | Bi::ClipDistance
         | Bi::CullDistance
         | Bi::PointSize
         | Bi::PointCoord
@@ -5,13 +5,20 @@ use std::ops::{Deref, DerefMut, RangeBounds};
 #[derive(Debug, Clone, Default)]
 #[cfg_attr(feature = "serialize", derive(serde::Serialize))]
 #[cfg_attr(feature = "serialize", serde(transparent))]
-#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct Block {
     body: Vec<Statement>,
     #[cfg_attr(feature = "serialize", serde(skip))]
     span_info: Vec<Span>,
 }
 
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Block {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        // Keep the spans in step with the statements.
+        Ok(Self::from_vec(u.arbitrary()?))
+    }
+}
+
 impl Block {
     pub const fn new() -> Self {
         Self {
@@ -65,6 +72,11 @@ impl Block {
         self.span_info.splice(range.clone(), other.span_info);
         self.body.splice(range, other.body);
     }
+    /// Iterate over the statements in this block, along with their spans.
+    ///
+    /// Spans are undefined for statements the front end didn't supply a
+    /// location for; backends can use them to map generated code back to
+    /// the shader's source.
     pub fn span_iter(&self) -> impl Iterator<Item = (&Statement, &Span)> {
         let span_iter = self.span_info.iter();
         self.body.iter().zip(span_iter)
@@ -75,6 +87,11 @@ impl Block {
         self.body.iter_mut().zip(span_iter)
     }
 
+    pub fn span_into_iter(self) -> impl Iterator<Item = (Statement, Span)> {
+        let Block { body, span_info } = self;
+        body.into_iter().zip(span_info)
+    }
+
     pub fn is_empty(&self) -> bool {
         self.body.is_empty()
     }
@@ -84,6 +101,13 @@ impl Block {
     }
 }
 
+/// Blocks compare equal if their statements do, regardless of span information.
+impl PartialEq for Block {
+    fn eq(&self, other: &Self) -> bool {
+        self.body == other.body
+    }
+}
+
 impl Deref for Block {
     type Target = [Statement];
     fn deref(&self) -> &[Statement] {
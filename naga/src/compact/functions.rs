@@ -100,6 +100,11 @@ impl FunctionMap {
         std::mem::swap(&mut function.named_expressions, reuse);
         assert!(reuse.is_empty());
 
+        // Adjust the expression ranges diagnostic filters apply to.
+        for &mut (ref mut range, _) in function.diagnostic_filter_ranges.iter_mut() {
+            self.expressions.adjust_range(range, &function.expressions);
+        }
+
         // Adjust statements.
         self.adjust_body(function);
     }
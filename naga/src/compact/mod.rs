@@ -79,6 +79,20 @@ pub fn compact(module: &mut crate::Module) {
         .iter()
         .map(|e| {
             log::trace!("tracing entry point {:?}", e.function.name);
+            for &constant in e.workgroup_size_overrides.iter().flatten().flatten() {
+                module_tracer.constants_used.insert(constant);
+                module_tracer
+                    .const_expressions_used
+                    .insert(module.constants[constant].init);
+            }
+            if let Some(ref mesh_info) = e.mesh_info {
+                module_tracer
+                    .types_used
+                    .insert(mesh_info.vertex_output_type);
+                module_tracer
+                    .types_used
+                    .insert(mesh_info.primitive_output_type);
+            }
             let mut used = module_tracer.as_function(&e.function);
             used.trace();
             FunctionMap::from(used)
@@ -181,6 +195,20 @@ pub fn compact(module: &mut crate::Module) {
     // Compact each entry point.
     for (entry, map) in module.entry_points.iter_mut().zip(entry_point_maps.iter()) {
         log::trace!("compacting entry point {:?}", entry.function.name);
+        for constant in entry
+            .workgroup_size_overrides
+            .iter_mut()
+            .flatten()
+            .flatten()
+        {
+            module_map.constants.adjust(constant);
+        }
+        if let Some(ref mut mesh_info) = entry.mesh_info {
+            module_map.types.adjust(&mut mesh_info.vertex_output_type);
+            module_map
+                .types
+                .adjust(&mut mesh_info.primitive_output_type);
+        }
         map.compact(
             &mut entry.function,
             &module_map,
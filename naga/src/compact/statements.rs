@@ -21,6 +21,7 @@ impl FunctionTracer<'_> {
                         condition,
                         ref accept,
                         ref reject,
+                        hint: _,
                     } => {
                         self.expressions_used.insert(condition);
                         worklist.push(accept);
@@ -39,6 +40,7 @@ impl FunctionTracer<'_> {
                         ref body,
                         ref continuing,
                         break_if,
+                        hint: _,
                     } => {
                         if let Some(break_if) = break_if {
                             self.expressions_used.insert(break_if);
@@ -97,13 +99,31 @@ impl FunctionTracer<'_> {
                         self.expressions_used.insert(query);
                         self.trace_ray_query_function(fun);
                     }
+                    St::MeshFunction(
+                        crate::MeshFunction::SetMeshOutputs {
+                            vertex_count: first,
+                            primitive_count: second,
+                        }
+                        | crate::MeshFunction::SetVertex {
+                            index: first,
+                            value: second,
+                        }
+                        | crate::MeshFunction::SetPrimitive {
+                            index: first,
+                            value: second,
+                        },
+                    ) => {
+                        self.expressions_used.insert(first);
+                        self.expressions_used.insert(second);
+                    }
 
                     // Trivial statements.
                     St::Break
                     | St::Continue
                     | St::Kill
                     | St::Barrier(_)
-                    | St::Return { value: None } => {}
+                    | St::Return { value: None }
+                    | St::DebugMarker(_) => {}
                 }
             }
         }
@@ -165,6 +185,7 @@ impl FunctionMap {
                         ref mut condition,
                         ref mut accept,
                         ref mut reject,
+                        hint: _,
                     } => {
                         adjust(condition);
                         worklist.push(accept);
@@ -183,6 +204,7 @@ impl FunctionMap {
                         ref mut body,
                         ref mut continuing,
                         ref mut break_if,
+                        hint: _,
                     } => {
                         if let Some(ref mut break_if) = *break_if {
                             adjust(break_if);
@@ -250,13 +272,31 @@ impl FunctionMap {
                         adjust(query);
                         self.adjust_ray_query_function(fun);
                     }
+                    St::MeshFunction(
+                        crate::MeshFunction::SetMeshOutputs {
+                            vertex_count: ref mut first,
+                            primitive_count: ref mut second,
+                        }
+                        | crate::MeshFunction::SetVertex {
+                            index: ref mut first,
+                            value: ref mut second,
+                        }
+                        | crate::MeshFunction::SetPrimitive {
+                            index: ref mut first,
+                            value: ref mut second,
+                        },
+                    ) => {
+                        adjust(first);
+                        adjust(second);
+                    }
 
                     // Trivial statements.
                     St::Break
                     | St::Continue
                     | St::Kill
                     | St::Barrier(_)
-                    | St::Return { value: None } => {}
+                    | St::Return { value: None }
+                    | St::DebugMarker(_) => {}
                 }
             }
         }
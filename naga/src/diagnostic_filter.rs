@@ -0,0 +1,150 @@
+/*!
+Scoped overrides of diagnostic severities.
+
+WGSL's `diagnostic(severity, rule)` directive and `@diagnostic(severity, rule)`
+attribute change how severe a diagnostic is within some part of a module:
+the whole module, a function, or a statement. Naga records these as a tree of
+[`DiagnosticFilterNode`]s in [`Module::diagnostic_filters`]. Each scope holds
+a handle to its innermost node, and the node's [`parent`] chain leads out
+through the enclosing scopes:
+
+- [`Module::diagnostic_filter_leaf`] is the innermost filter that applies to
+  the whole module.
+
+- [`Function::diagnostic_filter_leaf`] is the innermost filter that applies
+  to a function's body. Its chain includes the module's filters.
+
+- [`Function::diagnostic_filter_ranges`] holds the filters that apply to
+  statements within the function, identified by the expressions those
+  statements evaluate.
+
+To find the severity of a diagnostic, walk the chain from the innermost node,
+and take the first filter whose [`triggering_rule`] matches. If there is none,
+the rule's [default severity] applies.
+
+[`Module::diagnostic_filters`]: crate::Module::diagnostic_filters
+[`Module::diagnostic_filter_leaf`]: crate::Module::diagnostic_filter_leaf
+[`Function::diagnostic_filter_leaf`]: crate::Function::diagnostic_filter_leaf
+[`Function::diagnostic_filter_ranges`]: crate::Function::diagnostic_filter_ranges
+[`parent`]: DiagnosticFilterNode::parent
+[`triggering_rule`]: DiagnosticFilter::triggering_rule
+[default severity]: StandardFilterableTriggeringRule::default_severity
+*/
+
+use crate::{Arena, Handle};
+
+#[cfg(feature = "arbitrary")]
+use arbitrary::Arbitrary;
+#[cfg(feature = "deserialize")]
+use serde::Deserialize;
+#[cfg(feature = "serialize")]
+use serde::Serialize;
+
+/// How severe a diagnostic is.
+///
+/// The variants are ordered from least to most severe.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "deserialize", derive(Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+pub enum Severity {
+    /// The diagnostic is not reported.
+    Off,
+    /// The diagnostic is reported as information, and does not make the
+    /// module invalid.
+    Info,
+    /// The diagnostic is reported as a warning, and does not make the module
+    /// invalid.
+    Warning,
+    /// The diagnostic is reported as an error, and makes the module invalid.
+    Error,
+}
+
+/// A rule defined by the WGSL specification whose diagnostics can be
+/// filtered.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "deserialize", derive(Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+pub enum StandardFilterableTriggeringRule {
+    /// A derivative or implicit-level texture sample in non-uniform control
+    /// flow.
+    DerivativeUniformity,
+}
+
+impl StandardFilterableTriggeringRule {
+    /// The severity of this rule's diagnostics when no filter applies.
+    ///
+    /// The WGSL specification makes `derivative_uniformity` an error by
+    /// default. Naga's uniformity analysis rejects some programs the
+    /// specification accepts, so until that is fixed, derivatives in
+    /// non-uniform control flow are only reported when a filter asks for it.
+    pub const fn default_severity(self) -> Severity {
+        match self {
+            Self::DerivativeUniformity => Severity::Off,
+        }
+    }
+}
+
+/// The rule a [`DiagnosticFilter`] applies to.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "deserialize", derive(Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+pub enum FilterableTriggeringRule {
+    /// A rule Naga knows about.
+    Standard(StandardFilterableTriggeringRule),
+    /// A single-word rule name that Naga doesn't recognize.
+    ///
+    /// The WGSL specification says such names only deserve a warning, so the
+    /// filter is kept, but never matches any diagnostic.
+    Unknown(Box<str>),
+    /// A two-part rule name, like `my_tool.my_rule`.
+    ///
+    /// These are reserved for tools other than the WGSL implementation, so
+    /// Naga keeps them without comment.
+    User(Box<[String; 2]>),
+}
+
+/// A filter that changes the severity of one rule's diagnostics.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "deserialize", derive(Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+pub struct DiagnosticFilter {
+    pub new_severity: Severity,
+    pub triggering_rule: FilterableTriggeringRule,
+}
+
+/// A [`DiagnosticFilter`] in a tree of scopes.
+///
+/// See the [module documentation](self) for details.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "deserialize", derive(Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+pub struct DiagnosticFilterNode {
+    pub inner: DiagnosticFilter,
+    /// The next filter out, which must precede this node in the arena.
+    pub parent: Option<Handle<DiagnosticFilterNode>>,
+}
+
+impl DiagnosticFilterNode {
+    /// Return the severity `rule` has in the scope whose innermost filter is
+    /// `leaf`.
+    pub fn severity(
+        arena: &Arena<Self>,
+        leaf: Option<Handle<Self>>,
+        rule: StandardFilterableTriggeringRule,
+    ) -> Severity {
+        let mut next = leaf;
+        while let Some(handle) = next {
+            let node = &arena[handle];
+            if node.inner.triggering_rule == FilterableTriggeringRule::Standard(rule) {
+                return node.inner.new_severity;
+            }
+            next = node.parent;
+        }
+        rule.default_severity()
+    }
+}
@@ -0,0 +1,304 @@
+/*!
+A unified diagnostic type for reporting errors from every stage of Naga.
+
+Each front end, the validator, and each back end has its own error type,
+shaped to suit the code that produces it. Tools that drive Naga as a library
+(bundlers, editor integrations, and so on) usually want a single,
+machine-readable representation instead. [`Diagnostic`] is that
+representation: it can be built from any of Naga's error types through the
+`From` impls in this module, and rendered as plain text, ANSI-colored text, or,
+with the `json` feature, JSON.
+
+The JSON produced by `Diagnostic::emit_to_json` has the following stable
+shape:
+
+```json
+{
+  "severity": "error",
+  "code": "wgsl::parse",
+  "message": "expected identifier, found '('",
+  "labels": [
+    {
+      "start": 3,
+      "end": 4,
+      "line": 1,
+      "column": 4,
+      "message": "expected identifier"
+    }
+  ],
+  "notes": []
+}
+```
+
+`start` and `end` are byte offsets into the source. `line` and `column` are
+1-based, and are only present when the source text was provided. The members
+of each object may appear in any order.
+*/
+
+use crate::{Span, WithSpan};
+use codespan_reporting::{files::SimpleFile, term};
+use std::error::Error;
+use termcolor::{Ansi, ColorChoice, NoColor, StandardStream, WriteColor};
+
+/// How serious a [`Diagnostic`] is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Severity {
+    Error,
+    Warning,
+    Note,
+}
+
+impl Severity {
+    /// The lowercase name of this severity, as used in the JSON output.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Error => "error",
+            Self::Warning => "warning",
+            Self::Note => "note",
+        }
+    }
+}
+
+/// A span in the source text, together with a description of its relevance.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DiagnosticLabel {
+    pub span: Span,
+    pub message: String,
+}
+
+/// A single error or warning, in a form independent of where it came from.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// A short, stable identifier for the class of error, like `"wgsl::parse"`
+    /// or `"validation"`.
+    pub code: &'static str,
+    pub message: String,
+    pub labels: Vec<DiagnosticLabel>,
+    pub notes: Vec<String>,
+}
+
+impl Diagnostic {
+    /// Create a new error diagnostic with no labels or notes.
+    pub fn error(code: &'static str, message: impl ToString) -> Self {
+        Self {
+            severity: Severity::Error,
+            code,
+            message: message.to_string(),
+            labels: Vec::new(),
+            notes: Vec::new(),
+        }
+    }
+
+    /// Add a label covering `span`.
+    ///
+    /// Undefined spans are ignored.
+    pub fn with_label(mut self, span: Span, message: impl ToString) -> Self {
+        if span.is_defined() {
+            self.labels.push(DiagnosticLabel {
+                span,
+                message: message.to_string(),
+            });
+        }
+        self
+    }
+
+    /// Add a note.
+    pub fn with_note(mut self, note: impl ToString) -> Self {
+        self.notes.push(note.to_string());
+        self
+    }
+
+    /// Build a diagnostic for an arbitrary [`Error`], recording the chain of
+    /// [`Error::source`]s as notes.
+    pub fn from_error(code: &'static str, error: &dyn Error) -> Self {
+        let mut diagnostic = Self::error(code, error);
+        let mut source = error.source();
+        while let Some(next) = source {
+            diagnostic.notes.push(next.to_string());
+            source = next.source();
+        }
+        diagnostic
+    }
+
+    fn to_codespan(&self) -> codespan_reporting::diagnostic::Diagnostic<()> {
+        use codespan_reporting::diagnostic::{Diagnostic, Label, Severity as CsSeverity};
+
+        let severity = match self.severity {
+            Severity::Error => CsSeverity::Error,
+            Severity::Warning => CsSeverity::Warning,
+            Severity::Note => CsSeverity::Note,
+        };
+        Diagnostic::new(severity)
+            .with_code(self.code)
+            .with_message(self.message.clone())
+            .with_labels(
+                self.labels
+                    .iter()
+                    .filter_map(|label| {
+                        let range = label.span.to_range()?;
+                        Some(Label::primary((), range).with_message(label.message.clone()))
+                    })
+                    .collect(),
+            )
+            .with_notes(
+                self.notes
+                    .iter()
+                    .map(|note| format!("note: {note}"))
+                    .collect(),
+            )
+    }
+
+    /// Emits a summary of the diagnostic to `writer`, using colors if the
+    /// writer supports them.
+    pub fn emit_to_writer_with_path(&self, writer: &mut impl WriteColor, source: &str, path: &str) {
+        let files = SimpleFile::new(path, source);
+        let config = term::Config::default();
+        term::emit(writer, &config, &files, &self.to_codespan()).expect("cannot write error");
+    }
+
+    /// Emits a summary of the diagnostic to standard error stream.
+    pub fn emit_to_stderr_with_path(&self, source: &str, path: &str) {
+        let writer = StandardStream::stderr(ColorChoice::Auto);
+        self.emit_to_writer_with_path(&mut writer.lock(), source, path);
+    }
+
+    /// Emits a summary of the diagnostic to a string, without colors.
+    pub fn emit_to_string_with_path(&self, source: &str, path: &str) -> String {
+        let mut writer = NoColor::new(Vec::new());
+        self.emit_to_writer_with_path(&mut writer, source, path);
+        String::from_utf8(writer.into_inner()).unwrap()
+    }
+
+    /// Emits a summary of the diagnostic to a string, using ANSI escape
+    /// sequences for colors.
+    pub fn emit_to_ansi_string_with_path(&self, source: &str, path: &str) -> String {
+        let mut writer = Ansi::new(Vec::new());
+        self.emit_to_writer_with_path(&mut writer, source, path);
+        String::from_utf8(writer.into_inner()).unwrap()
+    }
+
+    /// Emits the diagnostic as a single-line JSON object.
+    ///
+    /// If `source` is provided, labels also carry 1-based `line` and `column`
+    /// fields. See the [module documentation](self) for the schema.
+    #[cfg(feature = "json")]
+    pub fn emit_to_json(&self, source: Option<&str>) -> String {
+        let labels = self
+            .labels
+            .iter()
+            .map(|label| {
+                let range = label.span.to_range().unwrap_or(0..0);
+                let mut object = serde_json::json!({
+                    "start": range.start,
+                    "end": range.end,
+                    "message": label.message,
+                });
+                if let Some(source) = source {
+                    let location = label.span.location(source);
+                    object["line"] = location.line_number.into();
+                    object["column"] = location.line_position.into();
+                }
+                object
+            })
+            .collect::<Vec<_>>();
+        serde_json::json!({
+            "severity": self.severity.as_str(),
+            "code": self.code,
+            "message": self.message,
+            "labels": labels,
+            "notes": self.notes,
+        })
+        .to_string()
+    }
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}[{}]: {}",
+            self.severity.as_str(),
+            self.code,
+            self.message
+        )
+    }
+}
+
+impl From<&WithSpan<crate::valid::ValidationError>> for Diagnostic {
+    fn from(error: &WithSpan<crate::valid::ValidationError>) -> Self {
+        let mut diagnostic = Self::from_error("validation", error);
+        for &(span, ref description) in error.spans() {
+            diagnostic = diagnostic.with_label(span, description);
+        }
+        diagnostic
+    }
+}
+
+impl From<&WithSpan<crate::valid::ValidationWarning>> for Diagnostic {
+    fn from(warning: &WithSpan<crate::valid::ValidationWarning>) -> Self {
+        let mut diagnostic = Self::from_error("validation", warning);
+        diagnostic.severity = Severity::Warning;
+        for &(span, ref description) in warning.spans() {
+            diagnostic = diagnostic.with_label(span, description);
+        }
+        diagnostic
+    }
+}
+
+#[cfg(feature = "wgsl-in")]
+impl From<&crate::front::wgsl::ParseError> for Diagnostic {
+    fn from(error: &crate::front::wgsl::ParseError) -> Self {
+        let mut diagnostic = Self::error("wgsl::parse", error.message());
+        for (span, message) in error.labels() {
+            diagnostic = diagnostic.with_label(span, message);
+        }
+        for note in error.notes() {
+            diagnostic = diagnostic.with_note(note);
+        }
+        diagnostic
+    }
+}
+
+#[cfg(feature = "glsl-in")]
+impl From<&crate::front::glsl::Error> for Diagnostic {
+    fn from(error: &crate::front::glsl::Error) -> Self {
+        Self::error("glsl::parse", &error.kind).with_label(error.meta, "")
+    }
+}
+
+#[cfg(feature = "spv-in")]
+impl From<&crate::front::spv::Error> for Diagnostic {
+    fn from(error: &crate::front::spv::Error) -> Self {
+        Self::from_error("spv::parse", error)
+    }
+}
+
+macro_rules! impl_from_backend_error {
+    ($feature:literal, $module:ident, $code:literal) => {
+        #[cfg(feature = $feature)]
+        impl From<&crate::back::$module::Error> for Diagnostic {
+            fn from(error: &crate::back::$module::Error) -> Self {
+                Self::from_error($code, error)
+            }
+        }
+    };
+}
+
+impl_from_backend_error!("spv-out", spv, "spv::write");
+impl_from_backend_error!("msl-out", msl, "msl::write");
+impl_from_backend_error!("glsl-out", glsl, "glsl::write");
+impl_from_backend_error!("hlsl-out", hlsl, "hlsl::write");
+impl_from_backend_error!("wgsl-out", wgsl, "wgsl::write");
+
+#[cfg(feature = "json")]
+#[test]
+fn json_escaping() {
+    let diagnostic = Diagnostic::error("test", "a \"quoted\"\nmessage\u{1}")
+        .with_label(Span::new(0, 1), "back\\slash")
+        .with_note("note");
+    let json: serde_json::Value = serde_json::from_str(&diagnostic.emit_to_json(None)).unwrap();
+    assert_eq!(json["message"], "a \"quoted\"\nmessage\u{1}");
+    assert_eq!(json["labels"][0]["message"], "back\\slash");
+    assert_eq!(json["notes"][0], "note");
+}
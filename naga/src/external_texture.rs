@@ -0,0 +1,604 @@
+/*!
+Lowering external textures to ordinary images.
+
+An [`External`] image, WGSL's `texture_external`, stands for a texture whose
+layout is only known to the application: it may be a single RGBA plane, or
+a video frame stored as separate luma and chroma planes that need color
+conversion. Backends have no way to express that, so [`lower`] rewrites
+each external texture into ordinary bindings, chosen by the application:
+
+- The external texture's global becomes a `texture_2d<f32>` holding the
+  single plane, or the luma plane.
+
+- For two-plane textures, [`lower`] adds a second `texture_2d<f32>` global
+  for the chroma plane, and a uniform buffer global holding the matrix that
+  converts luma and chroma to RGB.
+
+Operations on the external texture are rewritten to match:
+
+- [`ImageSample`] clamps its coordinates to the centers of the edge texels
+  of each plane, and samples each plane at level zero.
+
+- [`ImageLoad`] loads from level zero of each plane. The chroma plane has
+  half the resolution of the luma plane, so its coordinates are halved.
+
+- [`ImageQuery::Size`] reports the size of the first plane.
+
+For two-plane textures, the luma and chroma values are then converted to
+RGB with the matrix in the uniform buffer.
+
+Run [`lower`] after validating the module, since it relies on the
+restrictions [`External`] images have, and validate the result again
+before passing it to a backend.
+
+[`External`]: crate::ImageClass::External
+[`ImageSample`]: crate::Expression::ImageSample
+[`ImageLoad`]: crate::Expression::ImageLoad
+[`ImageQuery::Size`]: crate::ImageQuery::Size
+*/
+
+use crate::arena::{Arena, Handle, Range};
+use crate::{
+    Block, Expression, FastHashMap, Function, GlobalVariable, ImageClass, Module, ResourceBinding,
+    Span, Statement, Type, TypeInner, UniqueArena,
+};
+use std::collections::BTreeMap;
+
+/// The ordinary bindings to lower an external texture to.
+#[derive(Clone, Debug, Hash, Eq, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+pub enum ExternalTextureBindings {
+    /// A single RGBA plane, sampled as it is.
+    SinglePlane { plane: ResourceBinding },
+    /// A full resolution luma plane, whose first channel holds Y, and a half
+    /// resolution chroma plane, whose first two channels hold Cb and Cr, as
+    /// in NV12.
+    ///
+    /// `params` is a uniform buffer holding the conversion matrix:
+    ///
+    /// ```wgsl
+    /// struct NagaExternalTextureParams {
+    ///     yuv_conversion_matrix: mat4x4<f32>,
+    /// }
+    /// ```
+    ///
+    /// A texel's color is `yuv_conversion_matrix * vec4(y, cb, cr, 1.0)`,
+    /// with an alpha of one.
+    TwoPlane {
+        luma: ResourceBinding,
+        chroma: ResourceBinding,
+        params: ResourceBinding,
+    },
+}
+
+/// The bindings to lower each external texture to, by the binding of its
+/// `texture_external` global.
+pub type BindingMap = BTreeMap<ResourceBinding, ExternalTextureBindings>;
+
+/// The globals an external texture was lowered to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LoweredTexture {
+    /// The binding the `texture_external` global had.
+    pub binding: ResourceBinding,
+    /// The single plane, or the luma plane.
+    ///
+    /// This is the external texture's own global, with a new type and
+    /// binding, so handles to it remain valid.
+    pub plane: Handle<GlobalVariable>,
+    /// The chroma plane, for [`ExternalTextureBindings::TwoPlane`].
+    pub chroma: Option<Handle<GlobalVariable>>,
+    /// The conversion parameters, for [`ExternalTextureBindings::TwoPlane`].
+    pub params: Option<Handle<GlobalVariable>>,
+}
+
+/// An error lowering external textures.
+#[derive(Clone, Debug, thiserror::Error, PartialEq)]
+pub enum Error {
+    #[error("No bindings were given for the external texture bound at {0:?}")]
+    MissingBindings(ResourceBinding),
+    #[error("The external texture {0:?} has no binding")]
+    Unbound(Handle<GlobalVariable>),
+    #[error("Binding arrays of external textures, like {0:?}, can't be lowered")]
+    BindingArray(Handle<GlobalVariable>),
+    #[error("Function {function:?} takes an external texture as argument {index}, which can't be lowered")]
+    Argument {
+        function: Handle<Function>,
+        index: u32,
+    },
+}
+
+/// The chroma plane and parameters globals of each lowered external
+/// texture, or `None` for single-plane textures.
+type Textures =
+    FastHashMap<Handle<GlobalVariable>, Option<(Handle<GlobalVariable>, Handle<GlobalVariable>)>>;
+
+fn is_external(types: &UniqueArena<Type>, ty: Handle<Type>) -> bool {
+    match types[ty].inner {
+        TypeInner::Image {
+            class: ImageClass::External,
+            ..
+        } => true,
+        TypeInner::BindingArray { base, .. } => is_external(types, base),
+        _ => false,
+    }
+}
+
+/// Return true if `module` has external textures that need [`lower`]ing.
+///
+/// Backends refuse to write such modules.
+pub fn has_external_textures(module: &Module) -> bool {
+    let functions = module
+        .functions
+        .iter()
+        .map(|(_, function)| function)
+        .chain(module.entry_points.iter().map(|ep| &ep.function));
+    module
+        .global_variables
+        .iter()
+        .any(|(_, var)| is_external(&module.types, var.ty))
+        || functions
+            .flat_map(|function| function.arguments.iter())
+            .any(|argument| is_external(&module.types, argument.ty))
+}
+
+/// Lower each external texture in `module` to the ordinary bindings given
+/// for it in `bindings`.
+///
+/// Return the globals each external texture was lowered to, in the order
+/// the external textures appear in the module.
+pub fn lower(module: &mut Module, bindings: &BindingMap) -> Result<Vec<LoweredTexture>, Error> {
+    for (handle, function) in module.functions.iter() {
+        let argument = function
+            .arguments
+            .iter()
+            .position(|argument| is_external(&module.types, argument.ty));
+        if let Some(index) = argument {
+            return Err(Error::Argument {
+                function: handle,
+                index: index as u32,
+            });
+        }
+    }
+
+    let mut externals = Vec::new();
+    for (handle, var) in module.global_variables.iter() {
+        match module.types[var.ty].inner {
+            TypeInner::Image {
+                class: ImageClass::External,
+                ..
+            } => externals.push(handle),
+            TypeInner::BindingArray { base, .. } if is_external(&module.types, base) => {
+                return Err(Error::BindingArray(handle));
+            }
+            _ => {}
+        }
+    }
+    if externals.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let plane_ty = module.types.insert(
+        Type {
+            name: None,
+            inner: TypeInner::Image {
+                dim: crate::ImageDimension::D2,
+                arrayed: false,
+                class: ImageClass::Sampled {
+                    kind: crate::ScalarKind::Float,
+                    multi: false,
+                },
+            },
+        },
+        Span::UNDEFINED,
+    );
+    // The types only two-plane textures need.
+    let mut two_plane_types = None;
+
+    let mut lowered = Vec::with_capacity(externals.len());
+    let mut textures = FastHashMap::default();
+    for handle in externals {
+        let span = module.global_variables.get_span(handle);
+        let var = &module.global_variables[handle];
+        let binding = var.binding.clone().ok_or(Error::Unbound(handle))?;
+        let suffixed = |suffix| var.name.as_ref().map(|name| format!("{name}_{suffix}"));
+
+        let (plane_binding, chroma_and_params) = match bindings.get(&binding) {
+            Some(&ExternalTextureBindings::SinglePlane { ref plane }) => (plane.clone(), None),
+            Some(&ExternalTextureBindings::TwoPlane {
+                ref luma,
+                ref chroma,
+                ref params,
+            }) => {
+                let (_, params_ty) =
+                    *two_plane_types.get_or_insert_with(|| add_two_plane_types(&mut module.types));
+                let chroma = GlobalVariable {
+                    name: suffixed("chroma"),
+                    space: crate::AddressSpace::Handle,
+                    binding: Some(chroma.clone()),
+                    ty: plane_ty,
+                    init: None,
+                    memory_decorations: crate::MemoryDecorations::empty(),
+                };
+                let params = GlobalVariable {
+                    name: suffixed("params"),
+                    space: crate::AddressSpace::Uniform,
+                    binding: Some(params.clone()),
+                    ty: params_ty,
+                    init: None,
+                    memory_decorations: crate::MemoryDecorations::empty(),
+                };
+                (luma.clone(), Some((chroma, params)))
+            }
+            None => return Err(Error::MissingBindings(binding)),
+        };
+        let chroma_and_params = chroma_and_params.map(|(chroma, params)| {
+            (
+                module.global_variables.append(chroma, span),
+                module.global_variables.append(params, span),
+            )
+        });
+
+        let var = &mut module.global_variables[handle];
+        var.ty = plane_ty;
+        var.binding = Some(plane_binding);
+
+        textures.insert(handle, chroma_and_params);
+        lowered.push(LoweredTexture {
+            binding,
+            plane: handle,
+            chroma: chroma_and_params.map(|(chroma, _)| chroma),
+            params: chroma_and_params.map(|(_, params)| params),
+        });
+    }
+
+    let vec4_ty = two_plane_types.map(|(vec4_ty, _)| vec4_ty);
+    for (_, function) in module.functions.iter_mut() {
+        lower_function(function, &textures, vec4_ty);
+    }
+    for ep in module.entry_points.iter_mut() {
+        lower_function(&mut ep.function, &textures, vec4_ty);
+    }
+
+    Ok(lowered)
+}
+
+/// Add the `vec4<f32>` type and the conversion parameters struct that
+/// two-plane textures need, and return their handles.
+fn add_two_plane_types(types: &mut UniqueArena<Type>) -> (Handle<Type>, Handle<Type>) {
+    let vec4_ty = types.insert(
+        Type {
+            name: None,
+            inner: TypeInner::Vector {
+                size: crate::VectorSize::Quad,
+                scalar: crate::Scalar::F32,
+            },
+        },
+        Span::UNDEFINED,
+    );
+    let matrix_ty = types.insert(
+        Type {
+            name: None,
+            inner: TypeInner::Matrix {
+                columns: crate::VectorSize::Quad,
+                rows: crate::VectorSize::Quad,
+                scalar: crate::Scalar::F32,
+            },
+        },
+        Span::UNDEFINED,
+    );
+    let params_ty = types.insert(
+        Type {
+            name: Some("NagaExternalTextureParams".to_string()),
+            inner: TypeInner::Struct {
+                members: vec![crate::StructMember {
+                    name: Some("yuv_conversion_matrix".to_string()),
+                    ty: matrix_ty,
+                    binding: None,
+                    offset: 0,
+                }],
+                span: 64,
+            },
+        },
+        Span::UNDEFINED,
+    );
+    (vec4_ty, params_ty)
+}
+
+/// Literals and globals the lowered expressions of a function refer to.
+///
+/// These don't need to be emitted, so they all go at the start of the
+/// function's new expression arena, where they can't end up inside an
+/// [`Emit`] range.
+///
+/// [`Emit`]: Statement::Emit
+struct Prelude {
+    half: Handle<Expression>,
+    one: Handle<Expression>,
+    zero_i32: Handle<Expression>,
+    one_u32: Handle<Expression>,
+    /// The chroma plane and parameters globals of each two-plane texture.
+    two_plane: FastHashMap<Handle<GlobalVariable>, (Handle<Expression>, Handle<Expression>)>,
+}
+
+/// Appends the expressions that one operation on an external texture is
+/// lowered to.
+struct Builder<'a> {
+    expressions: &'a mut Arena<Expression>,
+    prelude: &'a Prelude,
+    vec4_ty: Option<Handle<Type>>,
+    span: Span,
+}
+
+impl Builder<'_> {
+    fn push(&mut self, expr: Expression) -> Handle<Expression> {
+        self.expressions.append(expr, self.span)
+    }
+
+    /// Clamp `coordinate` to the centers of the edge texels of `image`.
+    fn clamp_to_edge(
+        &mut self,
+        image: Handle<Expression>,
+        coordinate: Handle<Expression>,
+    ) -> Handle<Expression> {
+        let size = self.push(Expression::ImageQuery {
+            image,
+            query: crate::ImageQuery::Size { level: None },
+        });
+        let size = self.push(Expression::As {
+            expr: size,
+            kind: crate::ScalarKind::Float,
+            convert: Some(4),
+        });
+        let half = self.push(Expression::Splat {
+            size: crate::VectorSize::Bi,
+            value: self.prelude.half,
+        });
+        let one = self.push(Expression::Splat {
+            size: crate::VectorSize::Bi,
+            value: self.prelude.one,
+        });
+        let min = self.push(Expression::Binary {
+            op: crate::BinaryOperator::Divide,
+            left: half,
+            right: size,
+        });
+        let max = self.push(Expression::Binary {
+            op: crate::BinaryOperator::Subtract,
+            left: one,
+            right: min,
+        });
+        self.push(Expression::Math {
+            fun: crate::MathFunction::Clamp,
+            arg: coordinate,
+            arg1: Some(min),
+            arg2: Some(max),
+            arg3: None,
+        })
+    }
+
+    fn sample(
+        &mut self,
+        image: Handle<Expression>,
+        sampler: Handle<Expression>,
+        coordinate: Handle<Expression>,
+    ) -> Handle<Expression> {
+        let coordinate = self.clamp_to_edge(image, coordinate);
+        self.push(Expression::ImageSample {
+            image,
+            sampler,
+            gather: None,
+            coordinate,
+            array_index: None,
+            offset: None,
+            level: crate::SampleLevel::Zero,
+            depth_ref: None,
+        })
+    }
+
+    fn load(
+        &mut self,
+        image: Handle<Expression>,
+        coordinate: Handle<Expression>,
+    ) -> Handle<Expression> {
+        self.push(Expression::ImageLoad {
+            image,
+            coordinate,
+            array_index: None,
+            sample: None,
+            level: Some(self.prelude.zero_i32),
+        })
+    }
+
+    /// Convert texels from the luma and chroma planes to an RGBA color.
+    fn convert(
+        &mut self,
+        luma: Handle<Expression>,
+        chroma: Handle<Expression>,
+        params: Handle<Expression>,
+    ) -> Handle<Expression> {
+        use crate::SwizzleComponent as Sc;
+
+        let vec4_ty = self.vec4_ty.unwrap();
+        let y = self.push(Expression::AccessIndex {
+            base: luma,
+            index: 0,
+        });
+        let cb_cr = self.push(Expression::Swizzle {
+            size: crate::VectorSize::Bi,
+            vector: chroma,
+            pattern: [Sc::X, Sc::Y, Sc::X, Sc::X],
+        });
+        let yuv = self.push(Expression::Compose {
+            ty: vec4_ty,
+            components: vec![y, cb_cr, self.prelude.one],
+        });
+        let matrix = self.push(Expression::AccessIndex {
+            base: params,
+            index: 0,
+        });
+        let matrix = self.push(Expression::Load { pointer: matrix });
+        let rgb = self.push(Expression::Binary {
+            op: crate::BinaryOperator::Multiply,
+            left: matrix,
+            right: yuv,
+        });
+        let rgb = self.push(Expression::Swizzle {
+            size: crate::VectorSize::Tri,
+            vector: rgb,
+            pattern: [Sc::X, Sc::Y, Sc::Z, Sc::X],
+        });
+        self.push(Expression::Compose {
+            ty: vec4_ty,
+            components: vec![rgb, self.prelude.one],
+        })
+    }
+}
+
+/// Return the external texture `expr` samples or loads from, if any.
+fn external_operand(
+    expr: &Expression,
+    expressions: &Arena<Expression>,
+    textures: &Textures,
+) -> Option<Handle<GlobalVariable>> {
+    match *expr {
+        Expression::ImageSample { image, .. } | Expression::ImageLoad { image, .. } => {
+            match expressions[image] {
+                Expression::GlobalVariable(global) if textures.contains_key(&global) => {
+                    Some(global)
+                }
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Rewrite the operations on external textures in `function`.
+///
+/// Each operation expands into several expressions, which all have to
+/// precede the operation's users in the arena, so this builds a new arena
+/// and adjusts every handle in the function to match.
+fn lower_function(function: &mut Function, textures: &Textures, vec4_ty: Option<Handle<Type>>) {
+    if !function
+        .expressions
+        .iter()
+        .any(|(_, expr)| external_operand(expr, &function.expressions, textures).is_some())
+    {
+        return;
+    }
+
+    let old = std::mem::take(&mut function.expressions);
+    let expressions = &mut function.expressions;
+
+    let mut literal = |literal| expressions.append(Expression::Literal(literal), Span::UNDEFINED);
+    let mut prelude = Prelude {
+        half: literal(crate::Literal::F32(0.5)),
+        one: literal(crate::Literal::F32(1.0)),
+        zero_i32: literal(crate::Literal::I32(0)),
+        one_u32: literal(crate::Literal::U32(1)),
+        two_plane: FastHashMap::default(),
+    };
+    for (&texture, &chroma_and_params) in textures.iter() {
+        if let Some((chroma, params)) = chroma_and_params {
+            let chroma = expressions.append(Expression::GlobalVariable(chroma), Span::UNDEFINED);
+            let params = expressions.append(Expression::GlobalVariable(params), Span::UNDEFINED);
+            prelude.two_plane.insert(texture, (chroma, params));
+        }
+    }
+
+    // For each expression in `old`, the first expression it expanded to,
+    // and the expression that produces its value.
+    let mut first = Vec::with_capacity(old.len());
+    let mut map = Vec::with_capacity(old.len());
+    for (handle, expr) in old.iter() {
+        let mut expr = expr.clone();
+        crate::proc::for_each_operand_mut(&mut expr, |operand| {
+            *operand = map[operand.index()];
+        });
+        let span = old.get_span(handle);
+        let start = expressions.len();
+
+        let texture = external_operand(&expr, expressions, textures);
+        let mut builder = Builder {
+            expressions,
+            prelude: &prelude,
+            vec4_ty,
+            span,
+        };
+        let lowered = match (texture, expr) {
+            (
+                Some(texture),
+                Expression::ImageSample {
+                    image,
+                    sampler,
+                    coordinate,
+                    ..
+                },
+            ) => match prelude.two_plane.get(&texture) {
+                None => builder.sample(image, sampler, coordinate),
+                Some(&(chroma, params)) => {
+                    let luma = builder.sample(image, sampler, coordinate);
+                    let chroma = builder.sample(chroma, sampler, coordinate);
+                    builder.convert(luma, chroma, params)
+                }
+            },
+            (
+                Some(texture),
+                Expression::ImageLoad {
+                    image, coordinate, ..
+                },
+            ) => match prelude.two_plane.get(&texture) {
+                None => builder.load(image, coordinate),
+                Some(&(chroma, params)) => {
+                    let luma = builder.load(image, coordinate);
+                    let one = builder.push(Expression::Splat {
+                        size: crate::VectorSize::Bi,
+                        value: prelude.one_u32,
+                    });
+                    let coordinate = builder.push(Expression::Binary {
+                        op: crate::BinaryOperator::ShiftRight,
+                        left: coordinate,
+                        right: one,
+                    });
+                    let chroma = builder.load(chroma, coordinate);
+                    builder.convert(luma, chroma, params)
+                }
+            },
+            (_, expr) => builder.push(expr),
+        };
+
+        first.push(expressions.range_from(start).first_and_last().unwrap().0);
+        map.push(lowered);
+    }
+
+    for (_, local) in function.local_variables.iter_mut() {
+        if let Some(ref mut init) = local.init {
+            *init = map[init.index()];
+        }
+    }
+    function.named_expressions = std::mem::take(&mut function.named_expressions)
+        .into_iter()
+        .map(|(handle, name)| (map[handle.index()], name))
+        .collect();
+    adjust_block(&mut function.body, &first, &map);
+}
+
+/// Point the handles in `block` at the new expression arena.
+///
+/// An [`Emit`] range covers everything its expressions expanded to.
+///
+/// [`Emit`]: Statement::Emit
+fn adjust_block(block: &mut Block, first: &[Handle<Expression>], map: &[Handle<Expression>]) {
+    for statement in block.iter_mut() {
+        if let Statement::Emit(ref mut range) = *statement {
+            if let Some((start, end)) = range.first_and_last() {
+                *range = Range::new_from_bounds(first[start.index()], map[end.index()]);
+            }
+        }
+        crate::proc::for_each_statement_operand_mut(statement, |handle| {
+            *handle = map[handle.index()];
+        });
+        crate::proc::for_each_child_block_mut(statement, |block| adjust_block(block, first, map));
+    }
+}
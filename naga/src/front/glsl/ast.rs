@@ -171,6 +171,21 @@ pub enum HirExprKind {
     },
 }
 
+/// The names of the layout qualifiers handled by the frontend, any other
+/// [`QualifierKey::String`] is ignored with a warning.
+const KNOWN_LAYOUT_QUALIFIERS: &[&str] = &[
+    "location",
+    "binding",
+    "set",
+    "push_constant",
+    "local_size_x",
+    "local_size_y",
+    "local_size_z",
+    "early_fragment_tests",
+    "origin_upper_left",
+    "pixel_center_integer",
+];
+
 #[derive(Debug, Hash, PartialEq, Eq)]
 pub enum QualifierKey<'a> {
     String(Cow<'a, str>),
@@ -199,6 +214,9 @@ pub struct TypeQualifiers<'a> {
     /// Memory qualifiers used in the declaration to set the storage access to be used
     /// in declarations that support it (storage images and buffers)
     pub storage_access: Option<(StorageAccess, Span)>,
+    /// The `coherent` and `volatile` qualifiers used in the declaration, for
+    /// buffer declarations.
+    pub memory_decorations: Option<(crate::MemoryDecorations, Span)>,
     pub layout_qualifiers: crate::FastHashMap<QualifierKey<'a>, (QualifierValue, Span)>,
 }
 
@@ -241,6 +259,16 @@ impl<'a> TypeQualifiers<'a> {
             });
         }
 
+        if let Some((_, meta)) = self.memory_decorations {
+            errors.push(super::Error {
+                kind: super::ErrorKind::SemanticError(
+                    "Coherent and volatile qualifiers can only be used in buffer declarations"
+                        .into(),
+                ),
+                meta,
+            });
+        }
+
         for &(_, meta) in self.layout_qualifiers.values() {
             errors.push(super::Error {
                 kind: super::ErrorKind::SemanticError("Unexpected qualifier".into()),
@@ -249,6 +277,28 @@ impl<'a> TypeQualifiers<'a> {
         }
     }
 
+    /// Removes the layout qualifiers naga doesn't know about or can't represent,
+    /// appending `warnings` with a warning for each of them instead of failing
+    pub fn unsupported_warnings(&mut self, warnings: &mut Vec<super::Error>) {
+        self.layout_qualifiers.retain(|key, &mut (_, meta)| {
+            let name = match *key {
+                QualifierKey::String(ref name) => name,
+                QualifierKey::Layout | QualifierKey::Format => return true,
+            };
+
+            let kind = if name.starts_with("blend_support_") {
+                super::ErrorKind::NotImplemented("advanced blend equations")
+            } else if KNOWN_LAYOUT_QUALIFIERS.contains(&name.as_ref()) {
+                return true;
+            } else {
+                super::ErrorKind::UnknownLayoutQualifier(name.to_string())
+            };
+
+            warnings.push(super::Error { kind, meta });
+            false
+        });
+    }
+
     /// Removes the layout qualifier with `name`, if it exists and adds an error if it isn't
     /// a [`QualifierValue::Uint`]
     pub fn uint_layout_qualifier(
@@ -2078,7 +2078,7 @@ impl Frontend {
             let (shadow, storage) = match class {
                 ImageClass::Depth { .. } => (true, false),
                 ImageClass::Storage { .. } => (false, true),
-                ImageClass::Sampled { .. } => (false, false),
+                ImageClass::Sampled { .. } | ImageClass::External => (false, false),
             };
 
             let coordinate = match (image_size, coord_size) {
@@ -2196,7 +2196,7 @@ pub fn sampled_to_depth(
             }
             ImageClass::Depth { .. } => {}
             // Other image classes aren't allowed to be transformed to depth
-            ImageClass::Storage { .. } => errors.push(Error {
+            ImageClass::Storage { .. } | ImageClass::External => errors.push(Error {
                 kind: ErrorKind::SemanticError("Not a texture".into()),
                 meta,
             }),
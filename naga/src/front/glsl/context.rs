@@ -1160,6 +1160,7 @@ impl<'a> Context<'a> {
                         condition,
                         accept: accept_body,
                         reject: reject_body,
+                        hint: None,
                     },
                     meta,
                 );
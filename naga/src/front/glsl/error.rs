@@ -21,6 +21,14 @@ fn join_with_comma(list: &[ExpectedToken]) -> String {
     string
 }
 
+fn join_candidates(candidates: &[(String, Span)]) -> String {
+    let signatures: Vec<_> = candidates
+        .iter()
+        .map(|&(ref sig, _)| sig.as_str())
+        .collect();
+    signatures.join(", ")
+}
+
 /// One of the expected tokens returned in [`InvalidToken`](ErrorKind::InvalidToken).
 #[derive(Clone, Debug, PartialEq)]
 pub enum ExpectedToken {
@@ -110,6 +118,16 @@ pub enum ErrorKind {
     /// A semantic error was detected in the shader.
     #[error("{0}")]
     SemanticError(Cow<'static, str>),
+    /// A function call matched several overloads, none of which was a better
+    /// match than the others.
+    ///
+    /// Each candidate is given as its signature and the span of its
+    /// declaration, which is empty for built-in functions.
+    #[error("Ambiguous best function for '{name}', candidates are: {}", join_candidates(.candidates))]
+    AmbiguousFunctionCall {
+        name: String,
+        candidates: Vec<(String, Span)>,
+    },
     /// An error was returned by the preprocessor.
     #[error("{0:?}")]
     PreprocessorError(PreprocessorError),
@@ -157,9 +175,18 @@ impl ParseError {
         for err in &self.errors {
             let mut diagnostic = Diagnostic::error().with_message(err.kind.to_string());
 
+            let mut labels = Vec::new();
             if let Some(range) = err.meta.to_range() {
-                diagnostic = diagnostic.with_labels(vec![Label::primary((), range)]);
+                labels.push(Label::primary((), range));
+            }
+            if let ErrorKind::AmbiguousFunctionCall { ref candidates, .. } = err.kind {
+                for &(ref signature, span) in candidates {
+                    if let Some(range) = span.to_range() {
+                        labels.push(Label::secondary((), range).with_message(signature));
+                    }
+                }
             }
+            diagnostic = diagnostic.with_labels(labels);
 
             term::emit(writer, &config, &files, &diagnostic).expect("cannot write error");
         }
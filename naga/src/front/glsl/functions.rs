@@ -3,7 +3,7 @@ use super::{
     builtins::{inject_builtin, sampled_to_depth},
     context::{Context, ExprPos, StmtContext},
     error::{Error, ErrorKind},
-    types::scalar_components,
+    types::{scalar_components, type_name},
     Frontend, Result,
 };
 use crate::{
@@ -11,7 +11,7 @@ use crate::{
     Expression, Function, FunctionArgument, FunctionResult, Handle, Literal, LocalVariable, Scalar,
     ScalarKind, Span, Statement, StructMember, Type, TypeInner,
 };
-use std::iter;
+use std::{cmp::Ordering, iter};
 
 /// Struct detailing a store operation that must happen after a function call
 struct ProxyWrite {
@@ -536,16 +536,12 @@ impl Frontend {
         // Borrow again but without mutability, at this point a declaration is guaranteed
         let declaration = self.lookup_function.get(&name).unwrap();
 
-        // Possibly contains the overload to be used in the call
-        let mut maybe_overload = None;
-        // The conversions needed for the best analyzed overload, this is initialized all to
-        // `NONE` to make sure that conversions always pass the first time without ambiguity
-        let mut old_conversions = vec![Conversion::None; args.len()];
-        // Tracks whether the comparison between overloads lead to an ambiguity
-        let mut ambiguous = false;
+        // The overloads that the call could resolve to, along with the conversion
+        // each of the call arguments needs to match them
+        let mut candidates = Vec::new();
 
-        // Iterate over all the available overloads to select either an exact match or a
-        // overload which has suitable implicit conversions
+        // Iterate over all the available overloads to collect the ones which either
+        // match the call exactly or have suitable implicit conversions
         'outer: for (overload_idx, overload) in declaration.overloads.iter().enumerate() {
             // If the overload and the function call don't have the same number of arguments
             // continue to the next overload
@@ -555,19 +551,11 @@ impl Frontend {
 
             log::trace!("Testing overload {}", overload_idx);
 
-            // Stores whether the current overload matches exactly the function call
-            let mut exact = true;
-            // State of the selection
-            // If None we still don't know what is the best overload
-            // If Some(true) the new overload is better
-            // If Some(false) the old overload is better
-            let mut superior = None;
-            // Store the conversions for the current overload so that later they can replace the
-            // conversions used for querying the best overload
-            let mut new_conversions = vec![Conversion::None; args.len()];
-
-            // Loop through the overload parameters and check if the current overload is better
-            // compared to the previous best overload.
+            // The conversions needed by each of the call arguments
+            let mut conversions = Vec::with_capacity(args.len());
+
+            // Loop through the overload parameters and check whether the call arguments
+            // can be passed to them.
             for (i, overload_parameter) in overload.parameters.iter().enumerate() {
                 let call_argument = &args[i];
                 let parameter_info = &overload.parameters_info[i];
@@ -643,11 +631,11 @@ impl Frontend {
                     }
 
                     // The images satisfy the conditions to be considered as an exact match
-                    new_conversions[i] = Conversion::Exact;
+                    conversions.push(Conversion::Exact);
                     continue;
                 } else if overload_param_ty == call_arg_ty {
                     // If the types match there's no need to check for conversions so continue
-                    new_conversions[i] = Conversion::Exact;
+                    conversions.push(Conversion::Exact);
                     continue;
                 }
 
@@ -676,98 +664,53 @@ impl Frontend {
                     conversion(overload_param_ty, call_arg_ty)
                 };
 
-                let conversion = match maybe_conversion {
-                    Some(info) => info,
+                match maybe_conversion {
+                    Some(conversion) => conversions.push(conversion),
                     None => continue 'outer,
-                };
-
-                // At this point a conversion will be needed so the overload no longer
-                // exactly matches the call arguments
-                exact = false;
-
-                // Compare the conversions needed for this overload parameter to that of the
-                // last overload analyzed respective parameter, the value is:
-                // - `true` when the new overload argument has a better conversion
-                // - `false` when the old overload argument has a better conversion
-                let best_arg = match (conversion, old_conversions[i]) {
-                    // An exact match is always better, we don't need to check this for the
-                    // current overload since it was checked earlier
-                    (_, Conversion::Exact) => false,
-                    // No overload was yet analyzed so this one is the best yet
-                    (_, Conversion::None) => true,
-                    // A conversion from a float to a double is the best possible conversion
-                    (Conversion::FloatToDouble, _) => true,
-                    (_, Conversion::FloatToDouble) => false,
-                    // A conversion from a float to an integer is preferred than one
-                    // from double to an integer
-                    (Conversion::IntToFloat, Conversion::IntToDouble) => true,
-                    (Conversion::IntToDouble, Conversion::IntToFloat) => false,
-                    // This case handles things like no conversion and exact which were already
-                    // treated and other cases which no conversion is better than the other
-                    _ => continue,
-                };
-
-                // Check if the best parameter corresponds to the current selected overload
-                // to pass to the next comparison, if this isn't true mark it as ambiguous
-                match best_arg {
-                    true => match superior {
-                        Some(false) => ambiguous = true,
-                        _ => {
-                            superior = Some(true);
-                            new_conversions[i] = conversion
-                        }
-                    },
-                    false => match superior {
-                        Some(true) => ambiguous = true,
-                        _ => superior = Some(false),
-                    },
                 }
             }
 
-            // The overload matches exactly the function call so there's no ambiguity (since
-            // repeated overload aren't allowed) and the current overload is selected, no
-            // further querying is needed.
-            if exact {
-                maybe_overload = Some(overload);
-                ambiguous = false;
-                break;
-            }
-
-            match superior {
-                // New overload is better keep it
-                Some(true) => {
-                    maybe_overload = Some(overload);
-                    // Replace the conversions
-                    old_conversions = new_conversions;
-                }
-                // Old overload is better do nothing
-                Some(false) => {}
-                // No overload was better than the other this can be caused
-                // when all conversions are ambiguous in which the overloads themselves are
-                // ambiguous.
-                None => {
-                    ambiguous = true;
-                    // Assign the new overload, this helps ensures that in this case of
-                    // ambiguity the parsing won't end immediately and allow for further
-                    // collection of errors.
-                    maybe_overload = Some(overload);
-                }
-            }
+            candidates.push((overload, conversions));
         }
 
-        if ambiguous {
-            self.errors.push(Error {
-                kind: ErrorKind::SemanticError(
-                    format!("Ambiguous best function for '{name}'").into(),
-                ),
-                meta,
-            })
-        }
+        let overload = match best_overload(&candidates) {
+            Ok(index) => candidates[index].0,
+            Err(ambiguous) if !ambiguous.is_empty() => {
+                let ambiguous_candidates = ambiguous
+                    .iter()
+                    .map(|&index| {
+                        let overload = candidates[index].0;
+                        let parameters: Vec<_> = overload
+                            .parameters
+                            .iter()
+                            .map(|&ty| type_name(&ctx.module.types[ty]))
+                            .collect();
+                        let span = match overload.kind {
+                            FunctionKind::Call(function) => ctx.module.functions.get_span(function),
+                            FunctionKind::Macro(_) => Span::default(),
+                        };
+                        (format!("{name}({})", parameters.join(", ")), span)
+                    })
+                    .collect();
+                self.errors.push(Error {
+                    kind: ErrorKind::AmbiguousFunctionCall {
+                        name: name.clone(),
+                        candidates: ambiguous_candidates,
+                    },
+                    meta,
+                });
 
-        let overload = maybe_overload.ok_or_else(|| Error {
-            kind: ErrorKind::SemanticError(format!("Unknown function '{name}'").into()),
-            meta,
-        })?;
+                // Keep going with one of the candidates, so that parsing doesn't end
+                // immediately and further errors can be collected.
+                candidates[ambiguous[0]].0
+            }
+            Err(_) => {
+                return Err(Error {
+                    kind: ErrorKind::SemanticError(format!("Unknown function '{name}'").into()),
+                    meta,
+                })
+            }
+        };
 
         let parameters_info = overload.parameters_info.clone();
         let parameters = overload.parameters.clone();
@@ -1065,6 +1008,8 @@ impl Frontend {
             expressions,
             named_expressions: crate::NamedExpressions::default(),
             body,
+            diagnostic_filter_leaf: None,
+            diagnostic_filter_ranges: Vec::new(),
         };
 
         'outer: for decl in declaration.overloads.iter_mut() {
@@ -1201,6 +1146,10 @@ impl Frontend {
     /// - build its return value from whatever values the GLSL `main` left in
     ///   the Naga globals representing GLSL `output` variables.
     ///
+    /// Since `main` is called rather than spliced in, a `return` anywhere in
+    /// it still reaches the code that builds the entry point's result, so
+    /// outputs written before an early return are preserved.
+    ///
     /// Upon entry, [`ctx.body`] should contain code, accumulated by prior calls
     /// to [`ParsingContext::parse_external_declaration`][pxd], to initialize
     /// private global variables as needed. This code gets spliced into the
@@ -1288,6 +1237,8 @@ impl Frontend {
 
             let ty = ctx.module.global_variables[arg.handle].ty;
 
+            // Emit the member pointers along with the loads through them.
+            let len = ctx.expressions.len();
             ctx.arg_type_walker(
                 arg.name.clone(),
                 arg.binding.clone(),
@@ -1303,17 +1254,18 @@ impl Frontend {
 
                     span += ctx.module.types[ty].inner.size(ctx.module.to_ctx());
 
-                    let len = ctx.expressions.len();
                     let load = ctx
                         .expressions
                         .append(Expression::Load { pointer }, Default::default());
-                    ctx.body.push(
-                        Statement::Emit(ctx.expressions.range_from(len)),
-                        Default::default(),
-                    );
                     components.push(load)
                 },
-            )?
+            )?;
+            if ctx.expressions.len() != len {
+                ctx.body.push(
+                    Statement::Emit(ctx.expressions.range_from(len)),
+                    Default::default(),
+                );
+            }
         }
 
         let (ty, value) = if !components.is_empty() {
@@ -1352,6 +1304,8 @@ impl Frontend {
             early_depth_test: Some(crate::EarlyDepthTest { conservative: None })
                 .filter(|_| self.meta.early_fragment_tests),
             workgroup_size: self.meta.workgroup_size,
+            workgroup_size_overrides: None,
+            mesh_info: None,
             function: Function {
                 arguments,
                 expressions,
@@ -1493,8 +1447,80 @@ enum Conversion {
     IntToDouble,
     /// Other type of conversion needed
     Other,
-    /// No conversion was yet registered
-    None,
+}
+
+impl Conversion {
+    /// Compares two conversions of the same call argument, a conversion is greater
+    /// than another if it makes for a better match.
+    ///
+    /// The GLSL specification only ranks some conversions, all other pairs are
+    /// considered to be equally good.
+    fn rank(self, other: Self) -> Ordering {
+        match (self, other) {
+            _ if self == other => Ordering::Equal,
+            // An exact match is better than any conversion
+            (Conversion::Exact, _) => Ordering::Greater,
+            (_, Conversion::Exact) => Ordering::Less,
+            // A conversion from a float to a double is better than any other conversion
+            (Conversion::FloatToDouble, _) => Ordering::Greater,
+            (_, Conversion::FloatToDouble) => Ordering::Less,
+            // A conversion from an integer to a float is better than one to a double
+            (Conversion::IntToFloat, Conversion::IntToDouble) => Ordering::Greater,
+            (Conversion::IntToDouble, Conversion::IntToFloat) => Ordering::Less,
+            _ => Ordering::Equal,
+        }
+    }
+}
+
+/// Selects the best overload for a call out of `candidates`, each of them given
+/// with the conversions the call arguments need to match it.
+///
+/// This follows the rules of section 6.1.1 of the GLSL 4.60 specification, an
+/// exact match is always selected, otherwise the selected overload must be better
+/// than all the others, that is, none of its conversions may be worse and at
+/// least one of them must be better.
+///
+/// If no overload is selected, returns the indices of the candidates that no
+/// other candidate is better than, which is empty if there were no candidates.
+fn best_overload<T>(candidates: &[(T, Vec<Conversion>)]) -> std::result::Result<usize, Vec<usize>> {
+    let exact = candidates.iter().position(|&(_, ref conversions)| {
+        conversions
+            .iter()
+            .all(|&conversion| conversion == Conversion::Exact)
+    });
+    if let Some(index) = exact {
+        return Ok(index);
+    }
+
+    let better = |a: usize, b: usize| {
+        let mut better = false;
+        for (&a, &b) in candidates[a].1.iter().zip(candidates[b].1.iter()) {
+            match a.rank(b) {
+                Ordering::Less => return false,
+                Ordering::Greater => better = true,
+                Ordering::Equal => {}
+            }
+        }
+        better
+    };
+
+    let indices = 0..candidates.len();
+    let best = indices
+        .clone()
+        .find(|&a| indices.clone().all(|b| a == b || better(a, b)));
+    if let Some(index) = best {
+        return Ok(index);
+    }
+
+    let unbeaten: Vec<_> = indices
+        .clone()
+        .filter(|&a| !indices.clone().any(|b| better(b, a)))
+        .collect();
+    Err(if unbeaten.is_empty() {
+        indices.collect()
+    } else {
+        unbeaten
+    })
 }
 
 /// Helper function, returns the type of conversion from `source` to `target`, if a
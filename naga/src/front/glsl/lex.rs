@@ -1,9 +1,11 @@
+use std::borrow::Cow;
+
 use super::{
     ast::Precision,
     token::{Directive, DirectiveKind, Token, TokenValue},
     types::parse_type,
 };
-use crate::{FastHashMap, Span, StorageAccess};
+use crate::{FastHashMap, FastHashSet, MemoryDecorations, Span, StorageAccess};
 use pp_rs::{
     pp::Preprocessor,
     token::{PreprocessorError, Punct, TokenValue as PPTokenValue},
@@ -26,16 +28,102 @@ pub enum LexerResultKind {
 
 pub struct Lexer<'a> {
     pp: Preprocessor<'a>,
+    input: &'a str,
+    /// End offsets of the floating-point literals in `input` that had an
+    /// `lf` suffix, as returned by [`hide_double_suffixes`].
+    double_literals: FastHashSet<u32>,
 }
 
 impl<'a> Lexer<'a> {
-    pub fn new(input: &'a str, defines: &'a FastHashMap<String, String>) -> Self {
+    pub fn new(
+        input: &'a str,
+        defines: &'a FastHashMap<String, String>,
+        double_literals: FastHashSet<u32>,
+    ) -> Self {
         let mut pp = Preprocessor::new(input);
         for (define, value) in defines {
             pp.add_define(define, value).unwrap(); //TODO: handle error
         }
-        Lexer { pp }
+        Lexer {
+            pp,
+            input,
+            double_literals,
+        }
+    }
+
+    /// Returns the double-precision value of the floating-point literal at
+    /// `location`, if it had an `lf` suffix.
+    ///
+    /// The preprocessor only produces single-precision values, so the literal
+    /// is parsed again from the source.
+    fn double_literal(&self, location: pp_rs::token::Location) -> Option<f64> {
+        if !self.double_literals.contains(&location.end) {
+            return None;
+        }
+        self.input
+            .get(location.start as usize..location.end as usize)?
+            .parse()
+            .ok()
+    }
+}
+
+/// Replaces the `lf` suffixes of double-precision floating-point literals in
+/// `source` with spaces.
+///
+/// The preprocessor rejects these suffixes, so they are hidden from it and
+/// the end offsets of the literals that had them are returned instead. The
+/// replacement preserves byte offsets, so spans still point into `source`.
+pub fn hide_double_suffixes(source: &str) -> (Cow<'_, str>, FastHashSet<u32>) {
+    let is_ident = |byte: u8| byte.is_ascii_alphanumeric() || byte == b'_';
+
+    let bytes = source.as_bytes();
+    let mut ends = FastHashSet::default();
+    let mut i = 0;
+    while i < bytes.len() {
+        let starts_number = bytes[i].is_ascii_digit()
+            || (bytes[i] == b'.' && bytes.get(i + 1).is_some_and(u8::is_ascii_digit));
+        if !starts_number || (i > 0 && is_ident(bytes[i - 1])) {
+            i += 1;
+            continue;
+        }
+
+        let mut is_float = false;
+        while i < bytes.len() && (bytes[i].is_ascii_digit() || bytes[i] == b'.') {
+            is_float |= bytes[i] == b'.';
+            i += 1;
+        }
+        if matches!(bytes.get(i), Some(b'e' | b'E')) {
+            let mut exponent = i + 1;
+            if matches!(bytes.get(exponent), Some(b'+' | b'-')) {
+                exponent += 1;
+            }
+            if bytes.get(exponent).is_some_and(u8::is_ascii_digit) {
+                is_float = true;
+                i = exponent;
+                while bytes.get(i).is_some_and(u8::is_ascii_digit) {
+                    i += 1;
+                }
+            }
+        }
+
+        let has_suffix = matches!(bytes.get(i..i + 2), Some([b'l' | b'L', b'f' | b'F']))
+            && !bytes.get(i + 2).copied().is_some_and(is_ident);
+        if is_float && has_suffix {
+            ends.insert(i as u32);
+            i += 2;
+        }
     }
+
+    if ends.is_empty() {
+        return (Cow::Borrowed(source), ends);
+    }
+
+    let mut hidden = source.to_string();
+    for &end in ends.iter() {
+        let end = end as usize;
+        hidden.replace_range(end..end + 2, "  ");
+    }
+    (Cow::Owned(hidden), ends)
 }
 
 impl<'a> Iterator for Lexer<'a> {
@@ -62,7 +150,10 @@ impl<'a> Iterator for Lexer<'a> {
                     meta,
                 })
             }
-            PPTokenValue::Float(float) => TokenValue::FloatConstant(float),
+            PPTokenValue::Float(float) => match self.double_literal(pp_token.location) {
+                Some(value) => TokenValue::DoubleConstant(value),
+                None => TokenValue::FloatConstant(float),
+            },
             PPTokenValue::Ident(ident) => {
                 match ident.as_str() {
                     // Qualifiers
@@ -87,6 +178,8 @@ impl<'a> Iterator for Lexer<'a> {
                     "restrict" => TokenValue::Restrict,
                     "readonly" => TokenValue::MemoryQualifier(StorageAccess::LOAD),
                     "writeonly" => TokenValue::MemoryQualifier(StorageAccess::STORE),
+                    "coherent" => TokenValue::MemoryDecoration(MemoryDecorations::COHERENT),
+                    "volatile" => TokenValue::MemoryDecoration(MemoryDecorations::VOLATILE),
                     // values
                     "true" => TokenValue::BoolConstant(true),
                     "false" => TokenValue::BoolConstant(false),
@@ -213,7 +306,11 @@ mod tests {
         let defines = crate::FastHashMap::default();
 
         // line comments
-        let mut lex = Lexer::new("#version 450\nvoid main () {}", &defines);
+        let mut lex = Lexer::new(
+            "#version 450\nvoid main () {}",
+            &defines,
+            Default::default(),
+        );
         let mut location = Location::default();
         location.start = 9;
         location.end = 12;
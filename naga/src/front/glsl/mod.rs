@@ -89,6 +89,17 @@ pub struct ShaderMetadata {
     /// Whether or not early fragment tests where requested by the shader.
     /// Defaults to `false`.
     pub early_fragment_tests: bool,
+    /// Whether or not `gl_FragCoord` was redeclared with the
+    /// `origin_upper_left` layout qualifier, placing the origin of window
+    /// coordinates in the upper left corner instead of the lower left one.
+    /// Defaults to `false`.
+    ///
+    /// Backends that flip the Y coordinate should consult this.
+    pub origin_upper_left: bool,
+    /// Whether or not `gl_FragCoord` was redeclared with the
+    /// `pixel_center_integer` layout qualifier, placing pixel centers on
+    /// integer coordinates instead of half-integer ones. Defaults to `false`.
+    pub pixel_center_integer: bool,
 
     /// The shader can request extensions via the
     /// `#extension` preprocessor directive, in the directive a behavior
@@ -107,6 +118,8 @@ impl ShaderMetadata {
         self.stage = stage;
         self.workgroup_size = [u32::from(stage == ShaderStage::Compute); 3];
         self.early_fragment_tests = false;
+        self.origin_upper_left = false;
+        self.pixel_center_integer = false;
         self.extensions.clear();
     }
 }
@@ -119,6 +132,8 @@ impl Default for ShaderMetadata {
             stage: ShaderStage::Vertex,
             workgroup_size: [0; 3],
             early_fragment_tests: false,
+            origin_upper_left: false,
+            pixel_center_integer: false,
             extensions: FastHashSet::default(),
         }
     }
@@ -175,6 +190,7 @@ pub struct Frontend {
     layouter: Layouter,
 
     errors: Vec<Error>,
+    warnings: Vec<Error>,
 }
 
 impl Frontend {
@@ -186,6 +202,7 @@ impl Frontend {
         self.global_variables.clear();
         self.entry_args.clear();
         self.layouter.clear();
+        self.warnings.clear();
     }
 
     /// Parses a shader either outputting a shader [`Module`] or a list of
@@ -199,7 +216,8 @@ impl Frontend {
     ) -> std::result::Result<Module, ParseError> {
         self.reset(options.stage);
 
-        let lexer = lex::Lexer::new(source, &options.defines);
+        let (source, double_literals) = lex::hide_double_suffixes(source);
+        let lexer = lex::Lexer::new(&source, &options.defines, double_literals);
         let mut ctx = ParsingContext::new(lexer);
 
         match ctx.parse(self) {
@@ -229,4 +247,13 @@ impl Frontend {
     pub const fn metadata(&self) -> &ShaderMetadata {
         &self.meta
     }
+
+    /// Returns the warnings produced while parsing the last shader.
+    ///
+    /// Warnings are produced for constructs that naga ignores instead of
+    /// failing, like unknown layout qualifiers (see
+    /// [`ErrorKind::UnknownLayoutQualifier`]).
+    pub fn warnings(&self) -> &[Error] {
+        &self.warnings
+    }
 }
@@ -84,7 +84,7 @@ pub fn calculate_offset(
 
             let span = match size {
                 crate::ArraySize::Constant(size) => size.get() * stride,
-                crate::ArraySize::Dynamic => stride,
+                crate::ArraySize::Pending(_) | crate::ArraySize::Dynamic => stride,
             };
 
             let ty_span = types.get_span(ty);
@@ -118,8 +118,9 @@ pub fn calculate_offset(
                 align = align.max(Alignment::MIN_UNIFORM);
             }
 
-            // See comment on the error kind
-            if StructLayout::Std140 == layout && rows == crate::VectorSize::Bi {
+            // See comment on the error kind. Double-precision columns are
+            // already 16 bytes wide, so `dmatCx2` is fine.
+            if StructLayout::Std140 == layout && rows == crate::VectorSize::Bi && scalar.width < 8 {
                 errors.push(Error {
                     kind: ErrorKind::UnsupportedMatrixTypeInStd140,
                     meta,
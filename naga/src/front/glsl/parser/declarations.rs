@@ -430,6 +430,7 @@ impl<'source> ParsingContext<'source> {
                             if qualifiers.invariant.take().is_some() {
                                 frontend.make_variable_invariant(ctx, &ty_name, token.meta)?;
 
+                                qualifiers.unsupported_warnings(&mut frontend.warnings);
                                 qualifiers.unused_errors(&mut frontend.errors);
                                 self.expect(frontend, TokenValue::Semicolon)?;
                                 return Ok(Some(qualifiers.span));
@@ -464,6 +465,7 @@ impl<'source> ParsingContext<'source> {
                         frontend.meta.early_fragment_tests |= qualifiers
                             .none_layout_qualifier("early_fragment_tests", &mut frontend.errors);
 
+                        qualifiers.unsupported_warnings(&mut frontend.warnings);
                         qualifiers.unused_errors(&mut frontend.errors);
 
                         Ok(Some(qualifiers.span))
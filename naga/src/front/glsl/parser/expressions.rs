@@ -44,6 +44,7 @@ impl<'source> ParsingContext<'source> {
                 }
                 Literal::F32(float.value)
             }
+            TokenValue::DoubleConstant(value) => Literal::F64(value),
             TokenValue::BoolConstant(value) => Literal::Bool(value),
             TokenValue::LeftParen => {
                 let expr = self.parse_expression(frontend, ctx, stmt)?;
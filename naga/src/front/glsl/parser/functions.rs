@@ -157,6 +157,7 @@ impl<'source> ParsingContext<'source> {
                         condition,
                         accept,
                         reject,
+                        hint: None,
                     },
                     meta,
                 );
@@ -351,6 +352,7 @@ impl<'source> ParsingContext<'source> {
                             condition,
                             accept: new_break(),
                             reject: Block::new(),
+                            hint: None,
                         },
                         crate::Span::default(),
                     );
@@ -368,6 +370,7 @@ impl<'source> ParsingContext<'source> {
                         body: loop_body,
                         continuing: Block::new(),
                         break_if: None,
+                        hint: None,
                     },
                     meta,
                 );
@@ -406,6 +409,7 @@ impl<'source> ParsingContext<'source> {
                             condition,
                             accept: new_break(),
                             reject: Block::new(),
+                            hint: None,
                         },
                         crate::Span::default(),
                     );
@@ -421,6 +425,7 @@ impl<'source> ParsingContext<'source> {
                         body: loop_body,
                         continuing: Block::new(),
                         break_if: None,
+                        hint: None,
                     },
                     meta,
                 );
@@ -494,6 +499,7 @@ impl<'source> ParsingContext<'source> {
                                 condition,
                                 accept: new_break(),
                                 reject: Block::new(),
+                                hint: None,
                             },
                             crate::Span::default(),
                         );
@@ -529,6 +535,7 @@ impl<'source> ParsingContext<'source> {
                         body: loop_body,
                         continuing,
                         break_if: None,
+                        hint: None,
                     },
                     meta,
                 );
@@ -160,6 +160,7 @@ impl<'source> ParsingContext<'source> {
             | TokenValue::Buffer
             | TokenValue::Restrict
             | TokenValue::MemoryQualifier(_)
+            | TokenValue::MemoryDecoration(_)
             | TokenValue::Layout => true,
             _ => false,
         })
@@ -289,6 +290,22 @@ impl<'source> ParsingContext<'source> {
                     storage_access.0 &= access;
                     storage_access.1.subsume(token.meta);
                 }
+                TokenValue::MemoryDecoration(decoration) => {
+                    let decorations = qualifiers
+                        .memory_decorations
+                        .get_or_insert((crate::MemoryDecorations::empty(), token.meta));
+                    if decorations.0.contains(decoration) {
+                        frontend.errors.push(Error {
+                            kind: ErrorKind::SemanticError(
+                                "The same memory qualifier can only be used once".into(),
+                            ),
+                            meta: token.meta,
+                        })
+                    }
+
+                    decorations.0 |= decoration;
+                    decorations.1.subsume(token.meta);
+                }
                 TokenValue::Restrict => continue,
                 _ => unreachable!(),
             };
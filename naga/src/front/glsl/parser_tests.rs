@@ -330,6 +330,105 @@ fn declarations() {
         .unwrap();
 }
 
+#[test]
+fn global_layout_qualifiers() {
+    let mut frontend = Frontend::default();
+
+    let module = frontend
+        .parse(
+            &Options::from(ShaderStage::Fragment),
+            r#"
+        #version 450
+        layout(early_fragment_tests) in;
+
+        void main() {}
+        "#,
+        )
+        .unwrap();
+    assert!(frontend.metadata().early_fragment_tests);
+    assert_eq!(
+        module.entry_points[0].early_depth_test,
+        Some(crate::EarlyDepthTest { conservative: None })
+    );
+    assert!(frontend.warnings().is_empty());
+
+    let module = frontend
+        .parse(
+            &Options::from(ShaderStage::Fragment),
+            r#"
+        #version 450
+        layout(origin_upper_left) in vec4 gl_FragCoord;
+        layout(location = 0) out vec4 o_color;
+
+        void main() {
+            o_color = gl_FragCoord;
+        }
+        "#,
+        )
+        .unwrap();
+    assert!(frontend.metadata().origin_upper_left);
+    assert!(!frontend.metadata().pixel_center_integer);
+    assert!(frontend.warnings().is_empty());
+    // The redeclaration refers to the builtin, it doesn't add a new input
+    assert_eq!(module.global_variables.len(), 2);
+    assert_eq!(
+        module.entry_points[0].function.arguments[0].binding,
+        Some(crate::Binding::BuiltIn(crate::BuiltIn::Position {
+            invariant: false
+        }))
+    );
+
+    frontend
+        .parse(
+            &Options::from(ShaderStage::Fragment),
+            r#"
+        #version 450
+        layout(pixel_center_integer) in vec4 gl_FragCoord;
+
+        void main() {}
+        "#,
+        )
+        .unwrap();
+    assert!(!frontend.metadata().origin_upper_left);
+    assert!(frontend.metadata().pixel_center_integer);
+
+    frontend
+        .parse(
+            &Options::from(ShaderStage::Fragment),
+            r#"
+        #version 450
+        layout(blend_support_multiply, blend_support_screen) out;
+
+        void main() {}
+        "#,
+        )
+        .unwrap();
+    assert_eq!(frontend.warnings().len(), 2);
+    assert!(frontend
+        .warnings()
+        .iter()
+        .all(|warning| warning.kind == ErrorKind::NotImplemented("advanced blend equations")));
+
+    frontend
+        .parse(
+            &Options::from(ShaderStage::Fragment),
+            r#"
+        #version 450
+        layout(foo_unknown) in;
+
+        void main() {}
+        "#,
+        )
+        .unwrap();
+    assert_eq!(
+        frontend.warnings(),
+        [Error {
+            kind: ErrorKind::UnknownLayoutQualifier("foo_unknown".into()),
+            meta: Span::new(37, 48),
+        }]
+    );
+}
+
 #[test]
 fn textures() {
     let mut frontend = Frontend::default();
@@ -576,6 +675,83 @@ fn constants() {
     assert!(constants.next().is_none());
 }
 
+#[test]
+fn double_literals() {
+    use crate::{Expression, Literal};
+
+    let mut frontend = Frontend::default();
+
+    let module = frontend
+        .parse(
+            &Options::from(ShaderStage::Vertex),
+            r#"
+        #  version 450
+        const double a = 0.1lf;
+        const double b = 1e2LF;
+        const double c = .5lf;
+        const float d = 1.5;
+
+        void main() {}
+        "#,
+        )
+        .unwrap();
+
+    let literals: Vec<_> = module
+        .const_expressions
+        .iter()
+        .filter_map(|(_, expr)| match *expr {
+            Expression::Literal(literal) => Some(literal),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        literals,
+        [
+            Literal::F64(0.1),
+            Literal::F64(100.0),
+            Literal::F64(0.5),
+            Literal::F32(1.5)
+        ]
+    );
+}
+
+#[test]
+fn double_std140_layout() {
+    use crate::TypeInner;
+
+    let mut frontend = Frontend::default();
+
+    let module = frontend
+        .parse(
+            &Options::from(ShaderStage::Vertex),
+            r#"
+        #  version 450
+        layout(std140, set = 0, binding = 0) uniform Block {
+            double a;
+            dvec3 b;
+            dmat2 c;
+            dmat3 d;
+            float e;
+        };
+
+        void main() {}
+        "#,
+        )
+        .unwrap();
+
+    let (members, span) = module
+        .types
+        .iter()
+        .find_map(|(_, ty)| match ty.inner {
+            TypeInner::Struct { ref members, span } => Some((members, span)),
+            _ => None,
+        })
+        .unwrap();
+    let offsets: Vec<_> = members.iter().map(|member| member.offset).collect();
+    assert_eq!(offsets, [0, 32, 64, 96, 192]);
+    assert_eq!(span, 224);
+}
+
 #[test]
 fn function_overloading() {
     let mut frontend = Frontend::default();
@@ -662,13 +838,81 @@ fn implicit_conversions() {
             .unwrap(),
         ParseError {
             errors: vec![Error {
-                kind: ErrorKind::SemanticError("Ambiguous best function for \'test\'".into()),
+                kind: ErrorKind::AmbiguousFunctionCall {
+                    name: "test".into(),
+                    candidates: vec![
+                        ("test(float)".into(), Span::new(48, 66)),
+                        ("test(uint)".into(), Span::new(86, 103)),
+                    ],
+                },
                 meta: Span::new(158, 165),
             }]
         }
     );
 }
 
+#[test]
+fn overload_resolution() {
+    let mut frontend = Frontend::default();
+
+    // Each call below only type checks if the right overload is selected.
+    frontend
+        .parse(
+            &Options::from(ShaderStage::Vertex),
+            r#"
+        #  version 450
+        // An exact match is preferred over any conversion.
+        float exact(float a) { return a; }
+        int exact(int a) { return a; }
+
+        // An int to float conversion is preferred over an int to double one.
+        int promote(float a) { return 0; }
+        double promote(double a) { return a; }
+
+        // A float to double conversion is preferred over any other conversion.
+        int widen(double a, float b) { return 0; }
+        double widen(uint a, double b) { return a; }
+
+        // The selected overload needs to be at least as good for all arguments.
+        int pick(float a, float b) { return 0; }
+        double pick(double a, double b) { return a; }
+
+        void main() {
+            int a = exact(1);
+            int b = promote(1);
+            int c = widen(1.0, 1.0);
+            int d = pick(1, 2.0);
+
+            // Built-ins go through the same resolution.
+            uint e = min(1, 2u);
+            float f = max(1, 2.0);
+            vec2 g = mix(vec2(0.0), vec2(1.0), bvec2(true, false));
+        }
+        "#,
+        )
+        .unwrap();
+
+    // Each overload is better for one of the arguments.
+    let error = frontend
+        .parse(
+            &Options::from(ShaderStage::Vertex),
+            r#"
+        #  version 450
+        void test(double a, uint b) {}
+        void test(float a, float b) {}
+
+        void main() {
+            test(1.0, 1u);
+        }
+        "#,
+        )
+        .unwrap_err();
+    assert_eq!(
+        error.errors[0].kind.to_string(),
+        "Ambiguous best function for 'test', candidates are: test(double, uint), test(float, float)"
+    );
+}
+
 #[test]
 fn structs() {
     let mut frontend = Frontend::default();
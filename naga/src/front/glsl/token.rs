@@ -25,6 +25,8 @@ pub enum TokenValue {
     Identifier(String),
 
     FloatConstant(Float),
+    /// A floating-point literal with an `lf` suffix.
+    DoubleConstant(f64),
     IntConstant(Integer),
     BoolConstant(bool),
 
@@ -43,6 +45,8 @@ pub enum TokenValue {
     /// The associated [`crate::StorageAccess`] is the access being allowed
     /// (for example `writeonly` has an associated value of [`crate::StorageAccess::STORE`])
     MemoryQualifier(crate::StorageAccess),
+    /// The `coherent` or `volatile` memory qualifier.
+    MemoryDecoration(crate::MemoryDecorations),
 
     Invariant,
     Interpolation(Interpolation),
@@ -209,6 +209,47 @@ pub const fn type_power(scalar: Scalar) -> Option<u32> {
     })
 }
 
+/// Returns the GLSL name of `ty`, for use in diagnostics.
+pub fn type_name(ty: &Type) -> String {
+    const fn prefix(scalar: Scalar) -> &'static str {
+        match scalar.kind {
+            ScalarKind::Sint => "i",
+            ScalarKind::Uint => "u",
+            ScalarKind::Bool => "b",
+            ScalarKind::Float if scalar.width == 8 => "d",
+            _ => "",
+        }
+    }
+
+    match ty.inner {
+        TypeInner::Scalar(scalar) => match scalar.kind {
+            ScalarKind::Sint => "int",
+            ScalarKind::Uint => "uint",
+            ScalarKind::Bool => "bool",
+            ScalarKind::Float if scalar.width == 8 => "double",
+            _ => "float",
+        }
+        .to_string(),
+        TypeInner::Vector { size, scalar } => format!("{}vec{}", prefix(scalar), size as u8),
+        TypeInner::Matrix {
+            columns,
+            rows,
+            scalar,
+        } if columns == rows => format!("{}mat{}", prefix(scalar), columns as u8),
+        TypeInner::Matrix {
+            columns,
+            rows,
+            scalar,
+        } => format!("{}mat{}x{}", prefix(scalar), columns as u8, rows as u8),
+        TypeInner::Sampler { comparison: false } => "sampler".to_string(),
+        TypeInner::Sampler { comparison: true } => "samplerShadow".to_string(),
+        ref other => match ty.name {
+            Some(ref name) => name.clone(),
+            None => format!("{other:?}"),
+        },
+    }
+}
+
 impl Context<'_> {
     /// Resolves the types of the expressions until `expr` (inclusive)
     ///
@@ -59,6 +59,7 @@ impl Frontend {
                 binding: None,
                 ty,
                 init: None,
+                memory_decorations: crate::MemoryDecorations::empty(),
             },
             meta,
         );
@@ -415,6 +416,29 @@ impl Frontend {
         }: VarDeclaration,
     ) -> Result<GlobalOrConstant> {
         let storage = qualifiers.storage.0;
+
+        // `gl_FragCoord` may be redeclared to change its conventions, this
+        // doesn't declare a new variable but refers to the builtin
+        if storage == StorageQualifier::Input && name.as_deref() == Some("gl_FragCoord") {
+            self.meta.origin_upper_left |=
+                qualifiers.none_layout_qualifier("origin_upper_left", &mut self.errors);
+            self.meta.pixel_center_integer |=
+                qualifiers.none_layout_qualifier("pixel_center_integer", &mut self.errors);
+
+            let var = self
+                .lookup_variable(ctx, "gl_FragCoord", meta)?
+                .and_then(|var| var.entry_arg)
+                .ok_or_else(|| Error {
+                    kind: ErrorKind::UnknownVariable("gl_FragCoord".into()),
+                    meta,
+                })?;
+
+            qualifiers.unsupported_warnings(&mut self.warnings);
+            qualifiers.unused_errors(&mut self.errors);
+
+            return Ok(GlobalOrConstant::Global(self.entry_args[var].handle));
+        }
+
         let (ret, lookup) = match storage {
             StorageQualifier::Input | StorageQualifier::Output => {
                 let input = storage == StorageQualifier::Input;
@@ -439,6 +463,7 @@ impl Frontend {
                         binding: None,
                         ty,
                         init,
+                        memory_decorations: crate::MemoryDecorations::empty(),
                     },
                     meta,
                 );
@@ -487,11 +512,15 @@ impl Frontend {
                 (GlobalOrConstant::Constant(handle), lookup)
             }
             StorageQualifier::AddressSpace(mut space) => {
+                let mut memory_decorations = crate::MemoryDecorations::empty();
                 match space {
                     AddressSpace::Storage { ref mut access } => {
                         if let Some((allowed_access, _)) = qualifiers.storage_access.take() {
                             *access = allowed_access;
                         }
+                        if let Some((decorations, _)) = qualifiers.memory_decorations.take() {
+                            memory_decorations = decorations;
+                        }
                     }
                     AddressSpace::Uniform => match ctx.module.types[ty].inner {
                         TypeInner::Image {
@@ -575,6 +604,7 @@ impl Frontend {
                         binding,
                         ty,
                         init,
+                        memory_decorations,
                     },
                     meta,
                 );
@@ -595,6 +625,7 @@ impl Frontend {
             self.global_variables.push((name, lookup));
         }
 
+        qualifiers.unsupported_warnings(&mut self.warnings);
         qualifiers.unused_errors(&mut self.errors);
 
         Ok(ret)
@@ -639,6 +670,7 @@ impl Frontend {
             }
         }
 
+        decl.qualifiers.unsupported_warnings(&mut self.warnings);
         decl.qualifiers.unused_errors(&mut self.errors);
 
         Ok(expr)
@@ -21,7 +21,8 @@ impl crate::Binding {
     /// being interpolated:
     ///
     /// - If `ty` is a floating-point scalar, vector, or matrix type, then
-    ///   default to [`Perspective`] interpolation and [`Center`] sampling.
+    ///   default to [`Perspective`] interpolation, and to [`Center`] sampling
+    ///   unless a sampling is already given.
     ///
     /// - If `ty` is an integral scalar or vector, then default to [`Flat`]
     ///   interpolation, which has no associated sampling.
@@ -49,7 +50,8 @@ impl crate::Binding {
             match ty.scalar_kind() {
                 Some(crate::ScalarKind::Float) => {
                     *interpolation = Some(crate::Interpolation::Perspective);
-                    *sampling = Some(crate::Sampling::Center);
+                    // Keep any explicit `centroid` or `sample` qualifier.
+                    sampling.get_or_insert(crate::Sampling::Center);
                 }
                 Some(crate::ScalarKind::Sint | crate::ScalarKind::Uint) => {
                     *interpolation = Some(crate::Interpolation::Flat);
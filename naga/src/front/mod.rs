@@ -13,7 +13,7 @@ pub mod spv;
 pub mod wgsl;
 
 use crate::{
-    arena::{Arena, Handle, UniqueArena},
+    arena::{Arena, Checkpoint, Handle, UniqueArena},
     proc::{ResolveContext, ResolveError, TypeResolution},
     FastHashMap,
 };
@@ -64,6 +64,19 @@ impl Typifier {
         self.resolutions.clear()
     }
 
+    /// Forget the types of the expressions appended to an arena since
+    /// `checkpoint` was taken, after truncating it to `checkpoint`.
+    pub fn truncate(&mut self, checkpoint: Checkpoint<crate::Expression>) {
+        self.resolutions.truncate(checkpoint.len());
+    }
+
+    /// Return the type of `expr_handle`, which must already have been covered
+    /// by a call to [`grow`](Self::grow).
+    ///
+    /// The returned [`TypeInner`] may refer to `types`, which should be the
+    /// arena of the [`ResolveContext`] passed to `grow`.
+    ///
+    /// [`TypeInner`]: crate::TypeInner
     pub fn get<'a>(
         &'a self,
         expr_handle: Handle<crate::Expression>,
@@ -100,6 +113,12 @@ impl Typifier {
     }
 
     /// Grow this typifier until it contains a type for `expr_handle`.
+    ///
+    /// This resolves the types of every expression in `expressions` up to and
+    /// including `expr_handle` that this typifier hasn't seen yet, so it's
+    /// cheap to call repeatedly as an arena grows. `ctx` must describe the
+    /// module and function that `expressions` belongs to; see
+    /// [`ResolveContext::with_function`].
     pub fn grow(
         &mut self,
         expr_handle: Handle<crate::Expression>,
@@ -9,6 +9,8 @@ use termcolor::{NoColor, WriteColor};
 pub enum Error {
     #[error("invalid header")]
     InvalidHeader,
+    #[error("unsupported SPIR-V version {0}.{1}")]
+    UnsupportedVersion(u8, u8),
     #[error("invalid word count")]
     InvalidWordCount,
     #[error("unknown instruction {0}")]
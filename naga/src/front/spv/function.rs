@@ -63,6 +63,8 @@ impl<I: Iterator<Item = u32>> super::Frontend<I> {
                     .make_expression_storage(&module.global_variables, &module.constants),
                 named_expressions: crate::NamedExpressions::default(),
                 body: crate::Block::new(),
+                diagnostic_filter_leaf: None,
+                diagnostic_filter_ranges: Vec::new(),
             }
         };
 
@@ -301,6 +303,8 @@ impl<I: Iterator<Item = u32>> super::Frontend<I> {
                 expressions: Arena::new(),
                 named_expressions: crate::NamedExpressions::default(),
                 body: crate::Block::new(),
+                diagnostic_filter_leaf: None,
+                diagnostic_filter_ranges: Vec::new(),
             };
 
             // 1. copy the inputs from arguments to privates
@@ -312,14 +316,70 @@ impl<I: Iterator<Item = u32>> super::Frontend<I> {
                         crate::Expression::FunctionArgument(function.arguments.len() as u32),
                         span,
                     );
-                    let load_expr = if arg.ty == module.global_variables[lvar.handle].ty {
-                        arg_expr
+
+                    let mut arg = arg.clone();
+                    if ep.stage == crate::ShaderStage::Fragment {
+                        if let Some(ref mut binding) = arg.binding {
+                            binding.apply_default_interpolation(&module.types[arg.ty].inner);
+                        }
+                        // Input interface blocks carry their bindings on the
+                        // members, so apply the defaults there instead.
+                        arg.ty = default_member_interpolation(module, arg.ty);
+                    }
+
+                    let global_ty = module.global_variables[lvar.handle].ty;
+                    if arg.ty == global_ty {
+                        let pointer = function
+                            .expressions
+                            .append(crate::Expression::GlobalVariable(lvar.handle), span);
+                        function.body.push(
+                            crate::Statement::Store {
+                                pointer,
+                                value: arg_expr,
+                            },
+                            span,
+                        );
+                    } else if let crate::TypeInner::Struct { ref members, .. } =
+                        module.types[arg.ty].inner
+                    {
+                        // Only the members' bindings differ, so copy the block
+                        // member by member.
+                        let pointer = function
+                            .expressions
+                            .append(crate::Expression::GlobalVariable(lvar.handle), span);
+                        let mut emitter = Emitter::default();
+                        emitter.start(&function.expressions);
+                        let mut stores = Vec::with_capacity(members.len());
+                        for index in 0..members.len() as u32 {
+                            let member_pointer = function.expressions.append(
+                                crate::Expression::AccessIndex {
+                                    base: pointer,
+                                    index,
+                                },
+                                span,
+                            );
+                            let value = function.expressions.append(
+                                crate::Expression::AccessIndex {
+                                    base: arg_expr,
+                                    index,
+                                },
+                                span,
+                            );
+                            stores.push(crate::Statement::Store {
+                                pointer: member_pointer,
+                                value,
+                            });
+                        }
+                        function.body.extend(emitter.finish(&function.expressions));
+                        for store in stores {
+                            function.body.push(store, span);
+                        }
                     } else {
-                        // The only case where the type is different is if we need to treat
-                        // unsigned integer as signed.
+                        // The only other case where the type is different is
+                        // if we need to treat unsigned integer as signed.
                         let mut emitter = Emitter::default();
                         emitter.start(&function.expressions);
-                        let handle = function.expressions.append(
+                        let value = function.expressions.append(
                             crate::Expression::As {
                                 expr: arg_expr,
                                 kind: crate::ScalarKind::Sint,
@@ -328,24 +388,14 @@ impl<I: Iterator<Item = u32>> super::Frontend<I> {
                             span,
                         );
                         function.body.extend(emitter.finish(&function.expressions));
-                        handle
-                    };
-                    function.body.push(
-                        crate::Statement::Store {
-                            pointer: function
-                                .expressions
-                                .append(crate::Expression::GlobalVariable(lvar.handle), span),
-                            value: load_expr,
-                        },
-                        span,
-                    );
-
-                    let mut arg = arg.clone();
-                    if ep.stage == crate::ShaderStage::Fragment {
-                        if let Some(ref mut binding) = arg.binding {
-                            binding.apply_default_interpolation(&module.types[arg.ty].inner);
-                        }
+                        let pointer = function
+                            .expressions
+                            .append(crate::Expression::GlobalVariable(lvar.handle), span);
+                        function
+                            .body
+                            .push(crate::Statement::Store { pointer, value }, span);
                     }
+
                     function.arguments.push(arg);
                 }
             }
@@ -412,6 +462,8 @@ impl<I: Iterator<Item = u32>> super::Frontend<I> {
                             members: ref sub_members,
                             ..
                         } => {
+                            let mut emitter = Emitter::default();
+                            emitter.start(&function.expressions);
                             for (index, sm) in sub_members.iter().enumerate() {
                                 if sm.binding.is_none() {
                                     continue;
@@ -436,6 +488,7 @@ impl<I: Iterator<Item = u32>> super::Frontend<I> {
                                     span,
                                 ));
                             }
+                            function.body.extend(emitter.finish(&function.expressions));
                         }
                         ref inner => {
                             let mut binding = result.binding.clone();
@@ -560,6 +613,8 @@ impl<I: Iterator<Item = u32>> super::Frontend<I> {
                 stage: ep.stage,
                 early_depth_test: ep.early_depth_test,
                 workgroup_size: ep.workgroup_size,
+                workgroup_size_overrides: None,
+                mesh_info: None,
                 function,
             });
         }
@@ -602,6 +657,7 @@ impl<'function> BlockContext<'function> {
                                 condition,
                                 accept,
                                 reject,
+                                hint: None,
                             },
                             crate::Span::default(),
                         )
@@ -619,6 +675,7 @@ impl<'function> BlockContext<'function> {
                                 body,
                                 continuing,
                                 break_if,
+                                hint: None,
                             },
                             crate::Span::default(),
                         )
@@ -672,3 +729,30 @@ impl<'function> BlockContext<'function> {
         lower_impl(&mut self.blocks, &self.bodies, 0)
     }
 }
+
+/// Return `ty`, with the default interpolation applied to the bindings of its
+/// members, if it is a struct.
+///
+/// If no member needs a default, this returns `ty` itself.
+fn default_member_interpolation(
+    module: &mut crate::Module,
+    ty: Handle<crate::Type>,
+) -> Handle<crate::Type> {
+    let (mut members, span) = match module.types[ty].inner {
+        crate::TypeInner::Struct { ref members, span } => (members.clone(), span),
+        _ => return ty,
+    };
+    for member in members.iter_mut() {
+        if let Some(ref mut binding) = member.binding {
+            binding.apply_default_interpolation(&module.types[member.ty].inner);
+        }
+    }
+    let name = module.types[ty].name.clone();
+    module.types.insert(
+        crate::Type {
+            name,
+            inner: crate::TypeInner::Struct { members, span },
+        },
+        module.types.get_span(ty),
+    )
+}
@@ -167,6 +167,8 @@ bitflags::bitflags! {
     struct DecorationFlags: u32 {
         const NON_READABLE = 0x1;
         const NON_WRITABLE = 0x2;
+        const COHERENT = 0x4;
+        const VOLATILE = 0x8;
     }
 }
 
@@ -181,6 +183,17 @@ impl DecorationFlags {
         }
         access
     }
+
+    fn to_memory_decorations(self) -> crate::MemoryDecorations {
+        let mut decorations = crate::MemoryDecorations::empty();
+        if self.contains(DecorationFlags::COHERENT) {
+            decorations |= crate::MemoryDecorations::COHERENT;
+        }
+        if self.contains(DecorationFlags::VOLATILE) {
+            decorations |= crate::MemoryDecorations::VOLATILE;
+        }
+        decorations
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -567,6 +580,9 @@ pub struct Frontend<I> {
     lookup_type: FastHashMap<spirv::Word, LookupType>,
     lookup_void_type: Option<spirv::Word>,
     lookup_storage_buffer_types: FastHashMap<Handle<crate::Type>, crate::StorageAccess>,
+    /// The access permitted by the `NonReadable` and `NonWritable` member
+    /// decorations of each struct type.
+    lookup_struct_access: FastHashMap<Handle<crate::Type>, crate::StorageAccess>,
     // Lookup for samplers and sampled images, storing flags on how they are used.
     lookup_constant: FastHashMap<spirv::Word, LookupConstant>,
     lookup_variable: FastHashMap<spirv::Word, LookupVariable>,
@@ -620,6 +636,7 @@ impl<I: Iterator<Item = u32>> Frontend<I> {
             lookup_type: FastHashMap::default(),
             lookup_void_type: None,
             lookup_storage_buffer_types: FastHashMap::default(),
+            lookup_struct_access: FastHashMap::default(),
             lookup_constant: FastHashMap::default(),
             lookup_variable: FastHashMap::default(),
             lookup_expression: FastHashMap::default(),
@@ -746,6 +763,12 @@ impl<I: Iterator<Item = u32>> Frontend<I> {
             spirv::Decoration::NonWritable => {
                 dec.flags |= DecorationFlags::NON_WRITABLE;
             }
+            spirv::Decoration::Coherent => {
+                dec.flags |= DecorationFlags::COHERENT;
+            }
+            spirv::Decoration::Volatile => {
+                dec.flags |= DecorationFlags::VOLATILE;
+            }
             spirv::Decoration::ColMajor => {
                 dec.matrix_major = Some(Majority::Column);
             }
@@ -1215,8 +1238,9 @@ impl<I: Iterator<Item = u32>> Frontend<I> {
             crate::TypeInner::Array { size, .. } => {
                 let size = match size {
                     crate::ArraySize::Constant(size) => size.get(),
-                    // A runtime sized array is not a composite type
-                    crate::ArraySize::Dynamic => {
+                    // A runtime sized array is not a composite type, and we
+                    // never produce arrays sized by an override.
+                    crate::ArraySize::Pending(_) | crate::ArraySize::Dynamic => {
                         return Err(Error::InvalidAccessType(root_type_id))
                     }
                 };
@@ -2490,7 +2514,7 @@ impl<I: Iterator<Item = u32>> Frontend<I> {
                         },
                     );
                 }
-                Op::BitReverse | Op::BitCount => {
+                Op::BitReverse | Op::BitCount | Op::QuantizeToF16 => {
                     inst.expect(4)?;
 
                     let result_type_id = self.next()?;
@@ -2502,6 +2526,7 @@ impl<I: Iterator<Item = u32>> Frontend<I> {
                         fun: match inst.op {
                             Op::BitReverse => crate::MathFunction::ReverseBits,
                             Op::BitCount => crate::MathFunction::CountOneBits,
+                            Op::QuantizeToF16 => crate::MathFunction::QuantizeToF16,
                             _ => unreachable!(),
                         },
                         arg: base_handle,
@@ -2709,15 +2734,59 @@ impl<I: Iterator<Item = u32>> Frontend<I> {
                     let o2_lexp = self.lookup_expression.lookup(o2_id)?;
                     let o2_handle = get_expr_handle!(o2_id, o2_lexp);
 
-                    let expr = crate::Expression::Select {
-                        condition: cond_handle,
-                        accept: o1_handle,
-                        reject: o2_handle,
+                    let result_ty = self.lookup_type.lookup(result_type_id)?.handle;
+                    let handle = match ctx.type_arena[result_ty].inner {
+                        crate::TypeInner::Scalar(_)
+                        | crate::TypeInner::Vector { .. }
+                        | crate::TypeInner::Pointer { .. }
+                        | crate::TypeInner::ValuePointer { .. } => {
+                            let expr = crate::Expression::Select {
+                                condition: cond_handle,
+                                accept: o1_handle,
+                                reject: o2_handle,
+                            };
+                            ctx.expressions.append(expr, span)
+                        }
+                        _ => {
+                            // SPIR-V 1.4 allows selecting between composites,
+                            // but `Expression::Select` only handles scalars
+                            // and vectors, so store the chosen operand in a
+                            // temporary.
+                            block.extend(emitter.finish(ctx.expressions));
+                            let local = ctx.local_arena.append(
+                                crate::LocalVariable {
+                                    name: None,
+                                    ty: result_ty,
+                                    init: None,
+                                },
+                                span,
+                            );
+                            let pointer = ctx
+                                .expressions
+                                .append(crate::Expression::LocalVariable(local), span);
+                            let store = |value| {
+                                let mut block = crate::Block::new();
+                                block.push(crate::Statement::Store { pointer, value }, span);
+                                block
+                            };
+                            block.push(
+                                crate::Statement::If {
+                                    condition: cond_handle,
+                                    accept: store(o1_handle),
+                                    reject: store(o2_handle),
+                                    hint: None,
+                                },
+                                span,
+                            );
+                            emitter.start(ctx.expressions);
+                            ctx.expressions
+                                .append(crate::Expression::Load { pointer }, span)
+                        }
                     };
                     self.lookup_expression.insert(
                         result_id,
                         LookupExpression {
-                            handle: ctx.expressions.append(expr, span),
+                            handle,
                             type_id: result_type_id,
                             block_id,
                         },
@@ -2750,7 +2819,9 @@ impl<I: Iterator<Item = u32>> Frontend<I> {
                     for _ in 5..inst.wc as usize {
                         let mut index = self.next()?;
                         if index == u32::MAX {
-                            // treat Undefined as X
+                            // The component is undefined, and the IR has no
+                            // undefined values, so any component will do.
+                            log::debug!("\tundefined shuffle component treated as 0");
                             index = 0;
                         }
                         max_component = max_component.max(index);
@@ -3782,6 +3853,7 @@ impl<I: Iterator<Item = u32>> Frontend<I> {
                     condition: _,
                     ref mut accept,
                     ref mut reject,
+                    hint: _,
                 } => {
                     self.patch_statements(reject, expressions, fun_parameter_sampling)?;
                     self.patch_statements(accept, expressions, fun_parameter_sampling)?;
@@ -3798,6 +3870,7 @@ impl<I: Iterator<Item = u32>> Frontend<I> {
                     ref mut body,
                     ref mut continuing,
                     break_if: _,
+                    hint: _,
                 } => {
                     self.patch_statements(body, expressions, fun_parameter_sampling)?;
                     self.patch_statements(continuing, expressions, fun_parameter_sampling)?;
@@ -3807,10 +3880,12 @@ impl<I: Iterator<Item = u32>> Frontend<I> {
                 | S::Return { .. }
                 | S::Kill
                 | S::Barrier(_)
+                | S::DebugMarker(_)
                 | S::Store { .. }
                 | S::ImageStore { .. }
                 | S::Atomic { .. }
-                | S::RayQuery { .. } => {}
+                | S::RayQuery { .. }
+                | S::MeshFunction(_) => {}
                 S::Call {
                     function: ref mut callee,
                     ref arguments,
@@ -3890,6 +3965,12 @@ impl<I: Iterator<Item = u32>> Frontend<I> {
                 return Err(Error::InvalidHeader);
             }
             let version_raw = self.next()?;
+            // The version is `0x00MMmm00`, for major version `MM` and minor
+            // version `mm`.
+            let (major, minor) = ((version_raw >> 16) as u8, (version_raw >> 8) as u8);
+            if major != 1 || minor > 6 {
+                return Err(Error::UnsupportedVersion(major, minor));
+            }
             let generator = self.next()?;
             let _bound = self.next()?;
             let _schema = self.next()?;
@@ -4713,6 +4794,7 @@ impl<I: Iterator<Item = u32>> Frontend<I> {
             self.lookup_storage_buffer_types
                 .insert(ty_handle, storage_access);
         }
+        self.lookup_struct_access.insert(ty_handle, storage_access);
         for (i, member_lookup) in member_lookups.into_iter().enumerate() {
             self.lookup_member
                 .insert((ty_handle, i as u32), member_lookup);
@@ -5140,8 +5222,16 @@ impl<I: Iterator<Item = u32>> Frontend<I> {
 
         let (inner, var) = match ext_class {
             ExtendedClass::Global(mut space) => {
+                let mut memory_decorations = crate::MemoryDecorations::empty();
                 if let crate::AddressSpace::Storage { ref mut access } = space {
                     *access &= dec.flags.to_storage_access();
+                    // Buffers in the `StorageBuffer` storage class aren't
+                    // registered as storage buffer types, so apply their
+                    // member decorations here.
+                    if let Some(&struct_access) = self.lookup_struct_access.get(&ty) {
+                        *access &= struct_access;
+                    }
+                    memory_decorations = dec.flags.to_memory_decorations();
                 }
                 let var = crate::GlobalVariable {
                     binding: dec.resource_binding(),
@@ -5149,13 +5239,19 @@ impl<I: Iterator<Item = u32>> Frontend<I> {
                     space,
                     ty,
                     init,
+                    memory_decorations,
                 };
                 (Variable::Global, var)
             }
             ExtendedClass::Input => {
-                let binding = dec.io_binding()?;
+                // For input interface blocks, this would be a structure whose
+                // members carry the bindings.
+                let binding = match module.types[ty].inner {
+                    crate::TypeInner::Struct { .. } => None,
+                    _ => Some(dec.io_binding()?),
+                };
                 let mut unsigned_ty = ty;
-                if let crate::Binding::BuiltIn(built_in) = binding {
+                if let Some(crate::Binding::BuiltIn(built_in)) = binding {
                     let needs_inner_uint = match built_in {
                         crate::BuiltIn::BaseInstance
                         | crate::BuiltIn::BaseVertex
@@ -5190,12 +5286,13 @@ impl<I: Iterator<Item = u32>> Frontend<I> {
                     binding: None,
                     ty,
                     init: None,
+                    memory_decorations: crate::MemoryDecorations::empty(),
                 };
 
                 let inner = Variable::Input(crate::FunctionArgument {
                     name: dec.name,
                     ty: unsigned_ty,
-                    binding: Some(binding),
+                    binding,
                 });
                 (inner, var)
             }
@@ -5250,6 +5347,7 @@ impl<I: Iterator<Item = u32>> Frontend<I> {
                     binding: None,
                     ty,
                     init,
+                    memory_decorations: crate::MemoryDecorations::empty(),
                 };
                 let inner = Variable::Output(crate::FunctionResult { ty, binding });
                 (inner, var)
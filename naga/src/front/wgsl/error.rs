@@ -28,6 +28,10 @@ impl ParseError {
         &self.message
     }
 
+    pub fn notes(&self) -> impl ExactSizeIterator<Item = &str> + '_ {
+        self.notes.iter().map(String::as_str)
+    }
+
     fn diagnostic(&self) -> Diagnostic<()> {
         let diagnostic = Diagnostic::error()
             .with_message(self.message.to_string())
@@ -166,12 +170,16 @@ pub enum Error<'a> {
     /// A break if appeared outside of a continuing block
     InvalidBreakIf(Span),
     InvalidGatherComponent(Span),
+    InvalidClampToEdgeTexture(Span),
     InvalidConstructorComponentType(Span, i32),
     InvalidIdentifierUnderscore(Span),
     ReservedIdentifierPrefix(Span),
     UnknownAddressSpace(Span),
     RepeatedAttribute(Span),
     UnknownAttribute(Span),
+    InapplicableHint(Span),
+    InapplicableMemoryDecoration(Span),
+    InapplicableDiagnosticFilter(Span),
     UnknownBuiltin(Span),
     UnknownAccess(Span),
     UnknownIdent(Span, &'a str),
@@ -179,6 +187,19 @@ pub enum Error<'a> {
     UnknownType(Span),
     UnknownStorageFormat(Span),
     UnknownConservativeDepth(Span),
+    UnknownMeshOutputTopology(Span),
+    UnknownSeverity(Span),
+    /// Two diagnostic filters in the same scope apply to the same rule.
+    DiagnosticFilterConflict {
+        first: Span,
+        second: Span,
+    },
+    UnknownEnableExtension(Span, &'a str),
+    EnableExtensionNotEnabled {
+        extension: crate::EnableExtensions,
+        span: Span,
+    },
+    UnimplementedF16(Span),
     SizeAttributeTooLow(Span, u32),
     AlignAttributeTooLow(Span, Alignment),
     NonPowerOfTwoAlignAttribute(Span),
@@ -249,7 +270,16 @@ pub enum Error<'a> {
     ExpectedConstExprConcreteIntegerScalar(Span),
     ExpectedNonNegative(Span),
     ExpectedPositiveArrayLength(Span),
+    ArrayTooLarge(Span),
     MissingWorkgroupSize(Span),
+    ExpectedConstExprBool(Span),
+    ExpectedConstExprSampleOffset(Span),
+    ConstAssertFailed {
+        span: Span,
+        /// The values of the operands, if the condition is a binary operation
+        /// on scalars.
+        operands: Option<(String, String)>,
+    },
     ConstantEvaluatorError(ConstantEvaluatorError, Span),
     AutoConversion {
         dest_span: Span,
@@ -414,6 +444,15 @@ impl<'a> Error<'a> {
                 labels: vec![(bad_span, "invalid component".into())],
                 notes: vec![],
             },
+            Error::InvalidClampToEdgeTexture(bad_span) => ParseError {
+                message: format!(
+                    "textureSampleBaseClampToEdge requires a texture_2d<f32> or texture_external, \
+                     but '{}' is neither",
+                    &source[bad_span]
+                ),
+                labels: vec![(bad_span, "invalid texture".into())],
+                notes: vec![],
+            },
             Error::InvalidConstructorComponentType(bad_span, component) => ParseError {
                 message: format!("invalid type for constructor component at index [{component}]"),
                 labels: vec![(bad_span, "invalid component type".into())],
@@ -450,6 +489,36 @@ impl<'a> Error<'a> {
                 labels: vec![(bad_span, "unknown attribute".into())],
                 notes: vec![],
             },
+            Error::InapplicableHint(bad_span) => ParseError {
+                message: format!(
+                    "the '{}' hint can't be applied to this statement",
+                    &source[bad_span]
+                ),
+                labels: vec![(bad_span, "hint not allowed here".into())],
+                notes: vec![
+                    "`@unroll` and `@dont_unroll` apply to loops, and `@branch` and `@flatten` to `if` statements"
+                        .into(),
+                ],
+            },
+            Error::InapplicableMemoryDecoration(bad_span) => ParseError {
+                message: format!(
+                    "the '{}' attribute can't be applied to this declaration",
+                    &source[bad_span]
+                ),
+                labels: vec![(bad_span, "attribute not allowed here".into())],
+                notes: vec![
+                    "`@coherent` and `@volatile` apply to `var<storage>` declarations".into(),
+                ],
+            },
+            Error::InapplicableDiagnosticFilter(bad_span) => ParseError {
+                message: "the 'diagnostic' attribute can't be applied to this item".into(),
+                labels: vec![(bad_span, "attribute not allowed here".into())],
+                notes: vec![
+                    "`@diagnostic` applies to functions, compound statements, and `if`, \
+                     `switch`, `loop`, `for` and `while` statements"
+                        .into(),
+                ],
+            },
             Error::UnknownBuiltin(bad_span) => ParseError {
                 message: format!("unknown builtin: '{}'", &source[bad_span]),
                 labels: vec![(bad_span, "unknown builtin".into())],
@@ -470,6 +539,62 @@ impl<'a> Error<'a> {
                 labels: vec![(bad_span, "unknown conservative depth".into())],
                 notes: vec![],
             },
+            Error::UnknownMeshOutputTopology(bad_span) => ParseError {
+                message: format!("unknown mesh output topology: '{}'", &source[bad_span]),
+                labels: vec![(bad_span, "unknown topology".into())],
+                notes: vec!["Topologies are points, lines and triangles".into()],
+            },
+            Error::UnknownSeverity(bad_span) => ParseError {
+                message: format!("unknown diagnostic severity: '{}'", &source[bad_span]),
+                labels: vec![(bad_span, "unknown severity".into())],
+                notes: vec!["Severities are error, warning, info and off".into()],
+            },
+            Error::DiagnosticFilterConflict { first, second } => ParseError {
+                message: format!(
+                    "conflicting diagnostic filters for rule '{}'",
+                    &source[second]
+                ),
+                labels: vec![
+                    (first, "first filter for this rule".into()),
+                    (second, "second filter for the same rule".into()),
+                ],
+                notes: vec![
+                    "A scope may only have one diagnostic filter for each rule".into(),
+                ],
+            },
+            Error::UnknownEnableExtension(bad_span, name) => ParseError {
+                message: format!("unknown enable-extension: '{name}'"),
+                labels: vec![(bad_span, "unknown enable-extension".into())],
+                notes: vec![
+                    "Supported enable-extensions are f16, dual_source_blending, clip_distances, \
+                     naga_control_flow_hints, naga_memory_decorations, naga_mesh_shader"
+                        .into(),
+                ],
+            },
+            Error::EnableExtensionNotEnabled { extension, span } => ParseError {
+                message: format!(
+                    "the `{}` enable-extension is not enabled",
+                    extension.to_wgsl()
+                ),
+                labels: vec![(
+                    span,
+                    format!(
+                        "the `{}` enable-extension is needed for this functionality, \
+                         but it is not currently enabled",
+                        extension.to_wgsl()
+                    )
+                    .into(),
+                )],
+                notes: vec![format!(
+                    "You can enable this extension by adding `enable {};` at the top of the shader.",
+                    extension.to_wgsl()
+                )],
+            },
+            Error::UnimplementedF16(span) => ParseError {
+                message: "16-bit floating-point types are not yet supported".to_string(),
+                labels: vec![(span, "unsupported type".into())],
+                notes: vec![],
+            },
             Error::UnknownType(bad_span) => ParseError {
                 message: format!("unknown type: '{}'", &source[bad_span]),
                 labels: vec![(bad_span, "unknown type".into())],
@@ -717,19 +842,47 @@ impl<'a> Error<'a> {
                 labels: vec![(span, "must be positive".into())],
                 notes: vec![],
             },
+            Error::ArrayTooLarge(span) => ParseError {
+                message: "array is too large".to_string(),
+                labels: vec![(span, "size in bytes must fit in 32 bits".into())],
+                notes: vec![],
+            },
             Error::ConstantEvaluatorError(ref e, span) => ParseError {
                 message: e.to_string(),
                 labels: vec![(span, "see msg".into())],
                 notes: vec![],
             },
             Error::MissingWorkgroupSize(span) => ParseError {
-                message: "workgroup size is missing on compute shader entry point".to_string(),
+                message: format!(
+                    "workgroup size is missing on {} shader entry point",
+                    &source[span]
+                ),
                 labels: vec![(
                     span,
                     "must be paired with a @workgroup_size attribute".into(),
                 )],
                 notes: vec![],
             },
+            Error::ExpectedConstExprBool(span) => ParseError {
+                message: "must be a const-expression that resolves to a `bool`".to_string(),
+                labels: vec![(span, "must resolve to a `bool`".into())],
+                notes: vec![],
+            },
+            Error::ExpectedConstExprSampleOffset(span) => ParseError {
+                message: "texture sample offset must be a const-expression".to_string(),
+                labels: vec![(span, "must be a const-expression".into())],
+                notes: vec![],
+            },
+            Error::ConstAssertFailed { span, ref operands } => ParseError {
+                message: "`const_assert` failure".to_string(),
+                labels: vec![(span, "evaluates to `false`".into())],
+                notes: match *operands {
+                    Some((ref left, ref right)) => vec![format!(
+                        "the left operand is `{left}`, and the right operand is `{right}`"
+                    )],
+                    None => vec![],
+                },
+            },
             Error::AutoConversion { dest_span, ref dest_type, source_span, ref source_type } => ParseError {
                 message: format!("automatic conversions cannot convert `{source_type}` to `{dest_type}`"),
                 labels: vec![
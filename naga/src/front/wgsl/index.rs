@@ -20,11 +20,13 @@ impl<'a> Index<'a> {
         // While doing so, reject conflicting definitions.
         let mut globals = FastHashMap::with_capacity_and_hasher(tu.decls.len(), Default::default());
         for (handle, decl) in tu.decls.iter() {
-            let ident = decl_ident(decl);
-            let name = ident.name;
-            if let Some(old) = globals.insert(name, handle) {
+            // `const_assert`s have no name, and can't be referred to.
+            let Some(ident) = decl_ident(decl) else {
+                continue;
+            };
+            if let Some(old) = globals.insert(ident.name, handle) {
                 return Err(Error::Redefinition {
-                    previous: decl_ident(&tu.decls[old]).span,
+                    previous: decl_ident(&tu.decls[old]).unwrap().span,
                     current: ident.span,
                 });
             }
@@ -130,7 +132,7 @@ impl<'a> DependencySolver<'a, '_> {
                     return if dep_id == id {
                         // A declaration refers to itself directly.
                         Err(Error::RecursiveDeclaration {
-                            ident: decl_ident(decl).span,
+                            ident: decl_ident(decl).unwrap().span,
                             usage: dep.usage,
                         })
                     } else {
@@ -146,14 +148,14 @@ impl<'a> DependencySolver<'a, '_> {
                             .unwrap_or(0);
 
                         Err(Error::CyclicDeclaration {
-                            ident: decl_ident(&self.module.decls[dep_id]).span,
+                            ident: decl_ident(&self.module.decls[dep_id]).unwrap().span,
                             path: self.path[start_at..]
                                 .iter()
                                 .map(|curr_dep| {
                                     let curr_id = curr_dep.decl;
                                     let curr_decl = &self.module.decls[curr_id];
 
-                                    (decl_ident(curr_decl).span, curr_dep.usage)
+                                    (decl_ident(curr_decl).unwrap().span, curr_dep.usage)
                                 })
                                 .collect(),
                         })
@@ -182,12 +184,17 @@ impl<'a> DependencySolver<'a, '_> {
     }
 }
 
-const fn decl_ident<'a>(decl: &ast::GlobalDecl<'a>) -> ast::Ident<'a> {
+/// Return the name of `decl`, if it has one.
+///
+/// Only declarations with names can be referred to, so any declaration
+/// reached through a dependency has one.
+const fn decl_ident<'a>(decl: &ast::GlobalDecl<'a>) -> Option<ast::Ident<'a>> {
     match decl.kind {
-        ast::GlobalDeclKind::Fn(ref f) => f.name,
-        ast::GlobalDeclKind::Var(ref v) => v.name,
-        ast::GlobalDeclKind::Const(ref c) => c.name,
-        ast::GlobalDeclKind::Struct(ref s) => s.name,
-        ast::GlobalDeclKind::Type(ref t) => t.name,
+        ast::GlobalDeclKind::Fn(ref f) => Some(f.name),
+        ast::GlobalDeclKind::Var(ref v) => Some(v.name),
+        ast::GlobalDeclKind::Const(ref c) => Some(c.name),
+        ast::GlobalDeclKind::Struct(ref s) => Some(s.name),
+        ast::GlobalDeclKind::Type(ref t) => Some(t.name),
+        ast::GlobalDeclKind::ConstAssert(_) => None,
     }
 }
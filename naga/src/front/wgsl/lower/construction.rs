@@ -579,6 +579,7 @@ impl<'source, 'temp> Lowerer<'source, 'temp> {
             }
             ast::ConstructorType::PartialVector { size } => Constructor::PartialVector { size },
             ast::ConstructorType::Vector { size, scalar } => {
+                let scalar = self.component_scalar(scalar, &ctx.as_global())?;
                 let ty = ctx.ensure_type_exists(scalar.to_inner_vector(size));
                 Constructor::Type(ty)
             }
@@ -588,12 +589,13 @@ impl<'source, 'temp> Lowerer<'source, 'temp> {
             ast::ConstructorType::Matrix {
                 rows,
                 columns,
-                width,
+                scalar,
             } => {
+                let scalar = self.matrix_component_scalar(scalar, &ctx.as_global())?;
                 let ty = ctx.ensure_type_exists(crate::TypeInner::Matrix {
                     columns,
                     rows,
-                    scalar: crate::Scalar::float(width),
+                    scalar,
                 });
                 Constructor::Type(ty)
             }
@@ -51,7 +51,7 @@ impl<'source, 'temp, 'out> super::ExpressionContext<'source, 'temp, 'out> {
                 }
             };
 
-        self.convert_leaf_scalar(expr, expr_span, goal_scalar)
+        self.convert_abstract_leaf_scalar(expr, goal_scalar)
     }
 
     /// Try to convert `expr`'s leaf scalar to `goal` using automatic conversions.
@@ -103,30 +103,70 @@ impl<'source, 'temp, 'out> super::ExpressionContext<'source, 'temp, 'out> {
 
         assert!(expr_scalar.is_abstract());
 
-        self.convert_leaf_scalar(expr, expr_span, goal_scalar)
+        self.convert_abstract_leaf_scalar(expr, goal_scalar)
     }
 
-    fn convert_leaf_scalar(
+    /// Convert the leaf scalars of the abstract value `expr` to `goal`.
+    ///
+    /// Abstract values are always constant, so this uses the constant
+    /// evaluator, rather than generating an `As` expression. If `goal` can't
+    /// represent the value, return a [`ConcretizationFailed`] error.
+    ///
+    /// [`ConcretizationFailed`]: super::Error::ConcretizationFailed
+    fn convert_abstract_leaf_scalar(
         &mut self,
         expr: Handle<crate::Expression>,
-        expr_span: Span,
-        goal_scalar: crate::Scalar,
+        goal: crate::Scalar,
+    ) -> Result<Handle<crate::Expression>, super::Error<'source>> {
+        let expr_span = self.get_expression_span(expr);
+        self.grow_types(expr)?;
+        self.as_const_evaluator()
+            .cast_array(expr, goal, expr_span)
+            .map_err(|err| {
+                // A `TypeResolution` includes the type's full name, if it has
+                // one.
+                let expr_type = &self.typifier()[expr];
+                super::Error::ConcretizationFailed {
+                    expr_span,
+                    expr_type: expr_type.to_wgsl(&self.module.to_ctx()),
+                    scalar: goal.to_wgsl(),
+                    inner: err,
+                }
+            })
+    }
+
+    /// Apply WGSL's automatic conversions to `expr` for a use expecting a
+    /// value of type `goal_ty`, if `expr` is abstract.
+    ///
+    /// This is how abstract values are materialized at use sites with a known
+    /// type, like typed `let` declarations, assignments, `return` statements,
+    /// and function call arguments. If `expr` is already concrete, return it
+    /// unchanged, leaving any type mismatch for the caller or the validator
+    /// to report.
+    pub fn try_automatic_conversions_for_abstract(
+        &mut self,
+        expr: Handle<crate::Expression>,
+        goal_ty: &crate::proc::TypeResolution,
+        goal_span: Span,
     ) -> Result<Handle<crate::Expression>, super::Error<'source>> {
-        let expr_inner = super::resolve_inner!(self, expr);
-        if let crate::TypeInner::Array { .. } = *expr_inner {
-            self.as_const_evaluator()
-                .cast_array(expr, goal_scalar, expr_span)
-                .map_err(|err| super::Error::ConstantEvaluatorError(err, expr_span))
+        if self.is_abstract(expr)? {
+            self.try_automatic_conversions(expr, goal_ty, goal_span)
         } else {
-            let cast = crate::Expression::As {
-                expr,
-                kind: goal_scalar.kind,
-                convert: Some(goal_scalar.width),
-            };
-            self.append_expression(cast, expr_span)
+            Ok(expr)
         }
     }
 
+    /// Return `true` if `expr`'s leaf scalar type is abstract.
+    pub fn is_abstract(
+        &mut self,
+        expr: Handle<crate::Expression>,
+    ) -> Result<bool, super::Error<'source>> {
+        let inner = super::resolve_inner!(self, expr);
+        Ok(inner
+            .automatically_convertible_scalar(&self.module.types)
+            .is_some_and(|scalar| scalar.is_abstract()))
+    }
+
     /// Try to convert `exprs` to `goal_ty` using WGSL's automatic conversions.
     pub fn try_automatic_conversions_slice(
         &mut self,
@@ -198,7 +238,16 @@ impl<'source, 'temp, 'out> super::ExpressionContext<'source, 'temp, 'out> {
         let inner = super::resolve_inner!(self, *expr);
         // Do nothing if `inner` doesn't even have leaf scalars;
         // it's a type error that validation will catch.
-        if inner.scalar() != Some(goal) {
+        let Some(scalar) = inner.automatically_convertible_scalar(&self.module.types) else {
+            return Ok(());
+        };
+        // Abstract values must be converted by the constant evaluator, so
+        // that values `goal` can't represent are reported.
+        if scalar.is_abstract() && scalar != goal {
+            *expr = self.convert_abstract_leaf_scalar(*expr, goal)?;
+            return Ok(());
+        }
+        if scalar != goal {
             let cast = crate::Expression::As {
                 expr: *expr,
                 kind: goal.kind,
@@ -233,6 +282,87 @@ impl<'source, 'temp, 'out> super::ExpressionContext<'source, 'temp, 'out> {
         Ok(())
     }
 
+    /// Apply WGSL's automatic conversions to the arguments of the built-in
+    /// function `fun`, and concretize them.
+    ///
+    /// Most built-in functions take arguments that share a leaf scalar type,
+    /// so abstract arguments are converted to the consensus scalar of all of
+    /// them, as for a binary operator. The exceptions are `ldexp`'s exponent,
+    /// and the bit offsets and counts of `extractBits` and `insertBits`.
+    /// Functions that only accept floating-point arguments convert
+    /// `AbstractInt` arguments to `AbstractFloat` first.
+    ///
+    /// If the arguments can't be reconciled, leave them as they are:
+    /// validation will report the problem.
+    pub fn convert_math_arguments(
+        &mut self,
+        fun: crate::MathFunction,
+        args: &mut [Handle<crate::Expression>],
+        span: Span,
+    ) -> Result<(), super::Error<'source>> {
+        use crate::MathFunction as Mf;
+
+        let (shared, rest) = match fun {
+            Mf::Ldexp | Mf::ExtractBits => args.split_at_mut(1.min(args.len())),
+            Mf::InsertBits => args.split_at_mut(2.min(args.len())),
+            _ => args.split_at_mut(args.len()),
+        };
+
+        for &arg in shared.iter() {
+            self.grow_types(arg)?;
+        }
+        if let Ok(mut consensus_scalar) = self.automatic_conversion_consensus(shared.iter()) {
+            let accepts_integers = matches!(
+                fun,
+                Mf::Abs
+                    | Mf::Min
+                    | Mf::Max
+                    | Mf::Clamp
+                    | Mf::Sign
+                    | Mf::Dot
+                    | Mf::CountTrailingZeros
+                    | Mf::CountLeadingZeros
+                    | Mf::CountOneBits
+                    | Mf::ReverseBits
+                    | Mf::ExtractBits
+                    | Mf::InsertBits
+                    | Mf::FindLsb
+                    | Mf::FindMsb
+            );
+            let takes_packed = matches!(
+                fun,
+                Mf::Dot4I8Packed
+                    | Mf::Dot4U8Packed
+                    | Mf::Unpack4x8snorm
+                    | Mf::Unpack4x8unorm
+                    | Mf::Unpack2x16snorm
+                    | Mf::Unpack2x16unorm
+                    | Mf::Unpack2x16float
+            );
+            if consensus_scalar.kind == crate::ScalarKind::AbstractInt {
+                if takes_packed {
+                    consensus_scalar = crate::Scalar::U32;
+                } else if !accepts_integers {
+                    consensus_scalar = crate::Scalar::ABSTRACT_FLOAT;
+                }
+            }
+            self.convert_slice_to_common_leaf_scalar(shared, consensus_scalar)?;
+        }
+
+        if let Mf::ExtractBits | Mf::InsertBits = fun {
+            for arg in rest.iter_mut() {
+                *arg =
+                    self.try_automatic_conversion_for_leaf_scalar(*arg, crate::Scalar::U32, span)?;
+            }
+        }
+
+        for arg in args.iter_mut() {
+            *arg = self.concretize(*arg)?;
+        }
+
+        Ok(())
+    }
+
     /// Return an expression for the concretized value of `expr`.
     ///
     /// If `expr` is already concrete, return it unchanged.
@@ -245,22 +375,7 @@ impl<'source, 'temp, 'out> super::ExpressionContext<'source, 'temp, 'out> {
             let concretized = scalar.concretize();
             if concretized != scalar {
                 assert!(scalar.is_abstract());
-                let expr_span = self.get_expression_span(expr);
-                expr = self
-                    .as_const_evaluator()
-                    .cast_array(expr, concretized, expr_span)
-                    .map_err(|err| {
-                        // A `TypeResolution` includes the type's full name, if
-                        // it has one. Also, avoid holding the borrow of `inner`
-                        // across the call to `cast_array`.
-                        let expr_type = &self.typifier()[expr];
-                        super::Error::ConcretizationFailed {
-                            expr_span,
-                            expr_type: expr_type.to_wgsl(&self.module.to_ctx()),
-                            scalar: concretized.to_wgsl(),
-                            inner: err,
-                        }
-                    })?;
+                expr = self.convert_abstract_leaf_scalar(expr, concretized)?;
             }
         }
 
@@ -303,9 +418,13 @@ impl<'source, 'temp, 'out> super::ExpressionContext<'source, 'temp, 'out> {
                 .map(|inner| inner.to_wgsl(&self.module.to_ctx()))
                 .collect::<Vec<String>>()
         );
-        let mut best = inners.next().unwrap().scalar().ok_or(0_usize)?;
+        let mut best = inners
+            .next()
+            .unwrap()
+            .automatically_convertible_scalar(types)
+            .ok_or(0_usize)?;
         for (inner, i) in inners.zip(1..) {
-            let scalar = inner.scalar().ok_or(i)?;
+            let scalar = inner.automatically_convertible_scalar(types).ok_or(i)?;
             match best.automatic_conversion_combine(scalar) {
                 Some(new_best) => {
                     best = new_best;
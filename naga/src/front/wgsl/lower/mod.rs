@@ -1,5 +1,6 @@
 use std::num::NonZeroU32;
 
+use crate::diagnostic_filter::{DiagnosticFilter, DiagnosticFilterNode};
 use crate::front::wgsl::error::{Error, ExpectedToken, InvalidAssignmentType};
 use crate::front::wgsl::index::Index;
 use crate::front::wgsl::parse::number::Number;
@@ -165,6 +166,10 @@ pub struct StatementContext<'source, 'temp, 'out> {
     /// we should consider them to be const. See the use of `force_non_const` in
     /// the code for lowering `let` bindings.
     expression_constness: &'temp mut crate::proc::ExpressionConstnessTracker,
+
+    /// The innermost diagnostic filter that applies to the statements we're
+    /// lowering.
+    diagnostic_filter_leaf: Option<Handle<DiagnosticFilterNode>>,
 }
 
 impl<'a, 'temp> StatementContext<'a, 'temp, '_> {
@@ -538,6 +543,70 @@ impl<'source, 'temp, 'out> ExpressionContext<'source, 'temp, 'out> {
         }
     }
 
+    /// Clamp `coordinate` to the centers of the edge texels of the
+    /// two-dimensional `image`, for `textureSampleBaseClampToEdge`.
+    fn clamp_to_edge(
+        &mut self,
+        image: Handle<crate::Expression>,
+        coordinate: Handle<crate::Expression>,
+        span: Span,
+    ) -> Result<Handle<crate::Expression>, Error<'source>> {
+        let size = self.append_expression(
+            crate::Expression::ImageQuery {
+                image,
+                query: crate::ImageQuery::Size { level: None },
+            },
+            span,
+        )?;
+        let size = self.append_expression(
+            crate::Expression::As {
+                expr: size,
+                kind: crate::ScalarKind::Float,
+                convert: Some(4),
+            },
+            span,
+        )?;
+        let splat = |ctx: &mut Self, value| {
+            let value = ctx
+                .interrupt_emitter(crate::Expression::Literal(crate::Literal::F32(value)), span)?;
+            ctx.append_expression(
+                crate::Expression::Splat {
+                    size: crate::VectorSize::Bi,
+                    value,
+                },
+                span,
+            )
+        };
+        let half = splat(self, 0.5)?;
+        let one = splat(self, 1.0)?;
+        let min = self.append_expression(
+            crate::Expression::Binary {
+                op: crate::BinaryOperator::Divide,
+                left: half,
+                right: size,
+            },
+            span,
+        )?;
+        let max = self.append_expression(
+            crate::Expression::Binary {
+                op: crate::BinaryOperator::Subtract,
+                left: one,
+                right: min,
+            },
+            span,
+        )?;
+        self.append_expression(
+            crate::Expression::Math {
+                fun: crate::MathFunction::Clamp,
+                arg: coordinate,
+                arg1: Some(min),
+                arg2: Some(max),
+                arg3: None,
+            },
+            span,
+        )
+    }
+
     fn prepare_args<'b>(
         &mut self,
         args: &'b [Handle<ast::Expression<'source>>],
@@ -596,6 +665,28 @@ impl<'source, 'temp, 'out> ExpressionContext<'source, 'temp, 'out> {
         Ok(())
     }
 
+    /// Return the type of the value `pointer` refers to, if it is a pointer.
+    fn pointee_type(
+        &mut self,
+        pointer: Handle<crate::Expression>,
+    ) -> Result<Option<crate::proc::TypeResolution>, Error<'source>> {
+        use crate::proc::TypeResolution as Tr;
+        use crate::TypeInner as Ti;
+
+        Ok(match *resolve_inner!(self, pointer) {
+            Ti::Pointer { base, .. } => Some(Tr::Handle(base)),
+            Ti::ValuePointer {
+                size: None, scalar, ..
+            } => Some(Tr::Value(Ti::Scalar(scalar))),
+            Ti::ValuePointer {
+                size: Some(size),
+                scalar,
+                ..
+            } => Some(Tr::Value(Ti::Vector { size, scalar })),
+            _ => None,
+        })
+    }
+
     /// Add a single expression to the expression table that is not covered by `self.emitter`.
     ///
     /// This is useful for `CallResult` and `AtomicResult` expressions, which should not be covered by
@@ -800,7 +891,7 @@ enum Texture {
     SampleCompareLevel,
     SampleGrad,
     SampleLevel,
-    // SampleBaseClampToEdge,
+    SampleBaseClampToEdge,
 }
 
 impl Texture {
@@ -815,7 +906,7 @@ impl Texture {
             "textureSampleCompareLevel" => Self::SampleCompareLevel,
             "textureSampleGrad" => Self::SampleGrad,
             "textureSampleLevel" => Self::SampleLevel,
-            // "textureSampleBaseClampToEdge" => Some(Self::SampleBaseClampToEdge),
+            "textureSampleBaseClampToEdge" => Self::SampleBaseClampToEdge,
             _ => return None,
         })
     }
@@ -831,11 +922,33 @@ impl Texture {
             Self::SampleCompareLevel => 5,
             Self::SampleGrad => 6,
             Self::SampleLevel => 5,
-            // Self::SampleBaseClampToEdge => 3,
+            Self::SampleBaseClampToEdge => 3,
         }
     }
 }
 
+/// Append `filters` to `arena` as a chain of nodes whose outermost parent is
+/// `parent`, and return the innermost node.
+///
+/// Filters in the same scope never apply to the same rule, so their order in
+/// the chain doesn't matter.
+fn append_diagnostic_filters(
+    arena: &mut Arena<DiagnosticFilterNode>,
+    parent: Option<Handle<DiagnosticFilterNode>>,
+    filters: &[DiagnosticFilter],
+    span: Span,
+) -> Option<Handle<DiagnosticFilterNode>> {
+    filters.iter().fold(parent, |parent, filter| {
+        Some(arena.append(
+            DiagnosticFilterNode {
+                inner: filter.clone(),
+                parent,
+            },
+            span,
+        ))
+    })
+}
+
 pub struct Lowerer<'source, 'temp> {
     index: &'temp Index<'source>,
     layouter: Layouter,
@@ -853,7 +966,16 @@ impl<'source, 'temp> Lowerer<'source, 'temp> {
         &mut self,
         tu: &'temp ast::TranslationUnit<'source>,
     ) -> Result<crate::Module, Error<'source>> {
-        let mut module = crate::Module::default();
+        let mut module = crate::Module {
+            enable_extensions: tu.enable_extensions,
+            ..Default::default()
+        };
+        module.diagnostic_filter_leaf = append_diagnostic_filters(
+            &mut module.diagnostic_filters,
+            None,
+            &tu.diagnostic_filters,
+            Span::UNDEFINED,
+        );
 
         let mut ctx = GlobalContext {
             ast_expressions: &tu.expressions,
@@ -873,31 +995,46 @@ impl<'source, 'temp> Lowerer<'source, 'temp> {
                     ctx.globals.insert(f.name.name, lowered_decl);
                 }
                 ast::GlobalDeclKind::Var(ref v) => {
-                    let ty = self.resolve_ast_type(v.ty, &mut ctx)?;
+                    let explicit_ty =
+                        v.ty.map(|ast| self.resolve_ast_type(ast, &mut ctx))
+                            .transpose()?;
 
+                    let mut ectx = ctx.as_const();
+
+                    let ty;
                     let init;
-                    if let Some(init_ast) = v.init {
-                        let mut ectx = ctx.as_const();
-                        let lowered = self.expression_for_abstract(init_ast, &mut ectx)?;
-                        let ty_res = crate::proc::TypeResolution::Handle(ty);
-                        let converted = ectx
-                            .try_automatic_conversions(lowered, &ty_res, v.name.span)
-                            .map_err(|error| match error {
-                                Error::AutoConversion {
-                                    dest_span: _,
-                                    dest_type,
-                                    source_span: _,
-                                    source_type,
-                                } => Error::InitializationTypeMismatch {
-                                    name: v.name.span,
-                                    expected: dest_type,
-                                    got: source_type,
-                                },
-                                other => other,
-                            })?;
-                        init = Some(converted);
-                    } else {
-                        init = None;
+                    match (v.init, explicit_ty) {
+                        (Some(init_ast), Some(explicit_ty)) => {
+                            let lowered = self.expression_for_abstract(init_ast, &mut ectx)?;
+                            let ty_res = crate::proc::TypeResolution::Handle(explicit_ty);
+                            let converted = ectx
+                                .try_automatic_conversions(lowered, &ty_res, v.name.span)
+                                .map_err(|error| match error {
+                                    Error::AutoConversion {
+                                        dest_span: _,
+                                        dest_type,
+                                        source_span: _,
+                                        source_type,
+                                    } => Error::InitializationTypeMismatch {
+                                        name: v.name.span,
+                                        expected: dest_type,
+                                        got: source_type,
+                                    },
+                                    other => other,
+                                })?;
+                            ty = explicit_ty;
+                            init = Some(converted);
+                        }
+                        (Some(init_ast), None) => {
+                            let concretized = self.expression(init_ast, &mut ectx)?;
+                            ty = ectx.register_type(concretized)?;
+                            init = Some(concretized);
+                        }
+                        (None, Some(explicit_ty)) => {
+                            ty = explicit_ty;
+                            init = None;
+                        }
+                        (None, None) => return Err(Error::MissingType(v.name.span)),
                     }
 
                     let binding = if let Some(ref binding) = v.binding {
@@ -916,6 +1053,7 @@ impl<'source, 'temp> Lowerer<'source, 'temp> {
                             binding,
                             ty,
                             init,
+                            memory_decorations: v.memory_decorations,
                         },
                         span,
                     );
@@ -980,6 +1118,9 @@ impl<'source, 'temp> Lowerer<'source, 'temp> {
                     ctx.globals
                         .insert(alias.name.name, LoweredGlobalDecl::Type(ty));
                 }
+                ast::GlobalDeclKind::ConstAssert(condition) => {
+                    self.const_assert(condition, span, &mut ctx.as_const())?;
+                }
             }
         }
 
@@ -1040,9 +1181,17 @@ impl<'source, 'temp> Lowerer<'source, 'temp> {
             expressions,
             named_expressions: crate::NamedExpressions::default(),
             body: crate::Block::default(),
+            diagnostic_filter_leaf: append_diagnostic_filters(
+                &mut ctx.module.diagnostic_filters,
+                ctx.module.diagnostic_filter_leaf,
+                &f.diagnostic_filters,
+                span,
+            ),
+            diagnostic_filter_ranges: Vec::new(),
         };
 
         let mut typifier = Typifier::default();
+        let diagnostic_filter_leaf = function.diagnostic_filter_leaf;
         let mut stmt_ctx = StatementContext {
             local_table: &mut local_table,
             globals: ctx.globals,
@@ -1054,6 +1203,7 @@ impl<'source, 'temp> Lowerer<'source, 'temp> {
             types: ctx.types,
             module: ctx.module,
             expression_constness: &mut crate::proc::ExpressionConstnessTracker::new(),
+            diagnostic_filter_leaf,
         };
         let mut body = self.block(&f.body, false, &mut stmt_ctx)?;
         ensure_block_returns(&mut body);
@@ -1078,11 +1228,26 @@ impl<'source, 'temp> Lowerer<'source, 'temp> {
                 [0; 3]
             };
 
+            let mesh_info = match entry.mesh_output {
+                Some(ref output) => Some(crate::MeshStageInfo {
+                    topology: output.topology,
+                    max_vertices: self.const_u32(output.max_vertices, &mut ctx.as_const())?.0,
+                    max_primitives: self
+                        .const_u32(output.max_primitives, &mut ctx.as_const())?
+                        .0,
+                    vertex_output_type: self.resolve_ast_type(output.vertex_type, ctx)?,
+                    primitive_output_type: self.resolve_ast_type(output.primitive_type, ctx)?,
+                }),
+                None => None,
+            };
+
             ctx.module.entry_points.push(crate::EntryPoint {
                 name: f.name.name.to_string(),
                 stage: entry.stage,
                 early_depth_test: entry.early_depth_test,
                 workgroup_size,
+                workgroup_size_overrides: None,
+                mesh_info,
                 function,
             });
             Ok(LoweredGlobalDecl::EntryPoint)
@@ -1124,8 +1289,46 @@ impl<'source, 'temp> Lowerer<'source, 'temp> {
                     let mut emitter = Emitter::default();
                     emitter.start(&ctx.function.expressions);
 
-                    let value =
-                        self.expression(l.init, &mut ctx.as_expression(block, &mut emitter))?;
+                    let explicit_ty =
+                        l.ty.map(|ty| self.resolve_ast_type(ty, &mut ctx.as_global()))
+                            .transpose()?;
+
+                    let mut ectx = ctx.as_expression(block, &mut emitter);
+                    let value = match explicit_ty {
+                        Some(ty) => {
+                            let init = self.expression_for_abstract(l.init, &mut ectx)?;
+                            let ty_res = crate::proc::TypeResolution::Handle(ty);
+                            let init = ectx
+                                .try_automatic_conversions_for_abstract(init, &ty_res, l.name.span)
+                                .map_err(|error| match error {
+                                    Error::AutoConversion {
+                                        dest_span: _,
+                                        dest_type,
+                                        source_span: _,
+                                        source_type,
+                                    } => Error::InitializationTypeMismatch {
+                                        name: l.name.span,
+                                        expected: dest_type,
+                                        got: source_type,
+                                    },
+                                    other => other,
+                                })?;
+                            let init_ty = ectx.register_type(init)?;
+                            if !ectx.module.types[ty]
+                                .inner
+                                .equivalent(&ectx.module.types[init_ty].inner, &ectx.module.types)
+                            {
+                                let gctx = &ectx.module.to_ctx();
+                                return Err(Error::InitializationTypeMismatch {
+                                    name: l.name.span,
+                                    expected: ty.to_wgsl(gctx),
+                                    got: init_ty.to_wgsl(gctx),
+                                });
+                            }
+                            init
+                        }
+                        None => self.expression(l.init, &mut ectx)?,
+                    };
 
                     // The WGSL spec says that any expression that refers to a
                     // `let`-bound variable is not a const expression. This
@@ -1134,26 +1337,6 @@ impl<'source, 'temp> Lowerer<'source, 'temp> {
                     // optimization.
                     ctx.expression_constness.force_non_const(value);
 
-                    let explicit_ty =
-                        l.ty.map(|ty| self.resolve_ast_type(ty, &mut ctx.as_global()))
-                            .transpose()?;
-
-                    if let Some(ty) = explicit_ty {
-                        let mut ctx = ctx.as_expression(block, &mut emitter);
-                        let init_ty = ctx.register_type(value)?;
-                        if !ctx.module.types[ty]
-                            .inner
-                            .equivalent(&ctx.module.types[init_ty].inner, &ctx.module.types)
-                        {
-                            let gctx = &ctx.module.to_ctx();
-                            return Err(Error::InitializationTypeMismatch {
-                                name: l.name.span,
-                                expected: ty.to_wgsl(gctx),
-                                got: init_ty.to_wgsl(gctx),
-                            });
-                        }
-                    }
-
                     block.extend(emitter.finish(&ctx.function.expressions));
                     ctx.local_table.insert(l.handle, Typed::Plain(value));
                     ctx.named_expressions
@@ -1255,6 +1438,7 @@ impl<'source, 'temp> Lowerer<'source, 'temp> {
                 condition,
                 ref accept,
                 ref reject,
+                hint,
             } => {
                 let mut emitter = Emitter::default();
                 emitter.start(&ctx.function.expressions);
@@ -1270,6 +1454,7 @@ impl<'source, 'temp> Lowerer<'source, 'temp> {
                     condition,
                     accept,
                     reject,
+                    hint,
                 }
             }
             ast::StatementKind::Switch {
@@ -1321,6 +1506,7 @@ impl<'source, 'temp> Lowerer<'source, 'temp> {
                 ref body,
                 ref continuing,
                 break_if,
+                hint,
             } => {
                 let body = self.block(body, true, ctx)?;
                 let mut continuing = self.block(continuing, true, ctx)?;
@@ -1338,6 +1524,7 @@ impl<'source, 'temp> Lowerer<'source, 'temp> {
                     body,
                     continuing,
                     break_if,
+                    hint,
                 }
             }
             ast::StatementKind::Break => crate::Statement::Break,
@@ -1346,14 +1533,57 @@ impl<'source, 'temp> Lowerer<'source, 'temp> {
                 let mut emitter = Emitter::default();
                 emitter.start(&ctx.function.expressions);
 
-                let value = value
-                    .map(|expr| self.expression(expr, &mut ctx.as_expression(block, &mut emitter)))
-                    .transpose()?;
+                let result_ty = ctx.function.result.as_ref().map(|result| result.ty);
+                let mut ectx = ctx.as_expression(block, &mut emitter);
+                let value = match (value, result_ty) {
+                    (Some(expr), Some(result_ty)) => {
+                        let expr_span = ectx.ast_expressions.get_span(expr);
+                        let value = self.expression_for_abstract(expr, &mut ectx)?;
+                        let value = ectx.try_automatic_conversions_for_abstract(
+                            value,
+                            &crate::proc::TypeResolution::Handle(result_ty),
+                            expr_span,
+                        )?;
+                        Some(ectx.concretize(value)?)
+                    }
+                    (Some(expr), None) => Some(self.expression(expr, &mut ectx)?),
+                    (None, _) => None,
+                };
                 block.extend(emitter.finish(&ctx.function.expressions));
 
                 crate::Statement::Return { value }
             }
             ast::StatementKind::Kill => crate::Statement::Kill,
+            ast::StatementKind::DebugMarker(text) => {
+                crate::Statement::DebugMarker(text.to_string())
+            }
+            ast::StatementKind::DiagnosticScope {
+                ref filters,
+                ref body,
+            } => {
+                let outer_leaf = ctx.diagnostic_filter_leaf;
+                let leaf = append_diagnostic_filters(
+                    &mut ctx.module.diagnostic_filters,
+                    outer_leaf,
+                    filters,
+                    stmt.span,
+                );
+                let first_expression = ctx.function.expressions.len();
+
+                ctx.diagnostic_filter_leaf = leaf;
+                for stmt in body.stmts.iter() {
+                    self.statement(stmt, block, is_inside_loop, ctx)?;
+                }
+                ctx.diagnostic_filter_leaf = outer_leaf;
+
+                // Nested scopes have already pushed their ranges, so the
+                // innermost scope's range always comes first.
+                if let Some(leaf) = leaf {
+                    let range = ctx.function.expressions.range_from(first_expression);
+                    ctx.function.diagnostic_filter_ranges.push((range, leaf));
+                }
+                return Ok(());
+            }
             ast::StatementKind::Call {
                 ref function,
                 ref arguments,
@@ -1382,8 +1612,9 @@ impl<'source, 'temp> Lowerer<'source, 'temp> {
                     ast_target,
                     &mut ctx.as_expression(block, &mut emitter),
                 )?;
-                let mut value =
-                    self.expression(value, &mut ctx.as_expression(block, &mut emitter))?;
+                let value_span = ctx.ast_expressions.get_span(value);
+                let mut value = self
+                    .expression_for_abstract(value, &mut ctx.as_expression(block, &mut emitter))?;
 
                 let target_handle = match target {
                     Typed::Reference(handle) => handle,
@@ -1396,12 +1627,33 @@ impl<'source, 'temp> Lowerer<'source, 'temp> {
                     }
                 };
 
+                let mut ectx = ctx.as_expression(block, &mut emitter);
+                let goal = ectx.pointee_type(target_handle)?;
                 let value = match op {
                     Some(op) => {
-                        let mut ctx = ctx.as_expression(block, &mut emitter);
-                        let mut left = ctx.apply_load_rule(target)?;
-                        ctx.binary_op_splat(op, &mut left, &mut value)?;
-                        ctx.append_expression(
+                        // Convert an abstract `value` before loading `target`,
+                        // so that the load is emitted along with the operation.
+                        let goal_scalar = match op {
+                            crate::BinaryOperator::ShiftLeft
+                            | crate::BinaryOperator::ShiftRight => Some(crate::Scalar::U32),
+                            _ => goal
+                                .as_ref()
+                                .and_then(|goal| goal.inner_with(&ectx.module.types).scalar()),
+                        };
+                        if let Some(goal_scalar) = goal_scalar {
+                            if ectx.is_abstract(value)? {
+                                value = ectx.try_automatic_conversion_for_leaf_scalar(
+                                    value,
+                                    goal_scalar,
+                                    value_span,
+                                )?;
+                            }
+                        }
+                        value = ectx.concretize(value)?;
+
+                        let mut left = ectx.apply_load_rule(target)?;
+                        ectx.binary_op_splat(op, &mut left, &mut value)?;
+                        ectx.append_expression(
                             crate::Expression::Binary {
                                 op,
                                 left,
@@ -1410,7 +1662,14 @@ impl<'source, 'temp> Lowerer<'source, 'temp> {
                             stmt.span,
                         )?
                     }
-                    None => value,
+                    None => {
+                        let value = match goal {
+                            Some(ref goal) => ectx
+                                .try_automatic_conversions_for_abstract(value, goal, value_span)?,
+                            None => value,
+                        };
+                        ectx.concretize(value)?
+                    }
                 };
                 block.extend(emitter.finish(&ctx.function.expressions));
 
@@ -1484,6 +1743,11 @@ impl<'source, 'temp> Lowerer<'source, 'temp> {
                 block.extend(emitter.finish(&ctx.function.expressions));
                 return Ok(());
             }
+            ast::StatementKind::ConstAssert(condition) => {
+                // Checked entirely at parse time; nothing reaches the IR.
+                self.const_assert(condition, stmt.span, &mut ctx.as_global().as_const())?;
+                return Ok(());
+            }
         };
 
         block.push(out, stmt.span);
@@ -1491,6 +1755,62 @@ impl<'source, 'temp> Lowerer<'source, 'temp> {
         Ok(())
     }
 
+    /// Evaluate the condition of a `const_assert` spanning `span`, and
+    /// return an error if it is false.
+    ///
+    /// If the condition is a binary operation, the error reports the values
+    /// of its operands.
+    ///
+    /// Nothing refers to the condition once it has been evaluated, so this
+    /// removes the expressions lowering it appended to the module.
+    fn const_assert(
+        &mut self,
+        condition: Handle<ast::Expression<'source>>,
+        span: Span,
+        ctx: &mut ExpressionContext<'source, '_, '_>,
+    ) -> Result<(), Error<'source>> {
+        let checkpoint = ctx.module.const_expressions.checkpoint();
+        let expr = self.expression(condition, ctx)?;
+        let value = ctx
+            .module
+            .to_ctx()
+            .eval_expr_to_literal_from(expr, &ctx.module.const_expressions);
+        ctx.module.const_expressions.truncate(checkpoint);
+        ctx.const_typifier.truncate(checkpoint);
+        match value {
+            Some(crate::Literal::Bool(true)) => Ok(()),
+            Some(crate::Literal::Bool(false)) => {
+                let operands = match ctx.ast_expressions[condition] {
+                    ast::Expression::Binary { left, right, .. } => {
+                        let left = self.const_literal(left, ctx)?;
+                        let right = self.const_literal(right, ctx)?;
+                        left.zip(right)
+                            .map(|(left, right)| (left.to_wgsl(), right.to_wgsl()))
+                    }
+                    _ => None,
+                };
+                Err(Error::ConstAssertFailed { span, operands })
+            }
+            _ => Err(Error::ExpectedConstExprBool(
+                ctx.ast_expressions.get_span(condition),
+            )),
+        }
+    }
+
+    /// Lower the const-expression `expr`, and return its value if it is a
+    /// scalar.
+    fn const_literal(
+        &mut self,
+        expr: Handle<ast::Expression<'source>>,
+        ctx: &mut ExpressionContext<'source, '_, '_>,
+    ) -> Result<Option<crate::Literal>, Error<'source>> {
+        let expr = self.expression_for_abstract(expr, ctx)?;
+        Ok(ctx
+            .module
+            .to_ctx()
+            .eval_expr_to_literal_from(expr, &ctx.module.const_expressions))
+    }
+
     /// Lower `expr` and apply the Load Rule if possible.
     ///
     /// For the time being, this concretizes abstract values, to support
@@ -1824,7 +2144,19 @@ impl<'source, 'temp> Lowerer<'source, 'temp> {
             Some(&LoweredGlobalDecl::Function(function)) => {
                 let arguments = arguments
                     .iter()
-                    .map(|&arg| self.expression(arg, ctx))
+                    .enumerate()
+                    .map(|(i, &arg)| {
+                        let arg_span = ctx.ast_expressions.get_span(arg);
+                        let mut arg = self.expression_for_abstract(arg, ctx)?;
+                        // Let the validator complain about argument count
+                        // mismatches.
+                        if let Some(param) = ctx.module.functions[function].arguments.get(i) {
+                            let param_ty = crate::proc::TypeResolution::Handle(param.ty);
+                            arg = ctx
+                                .try_automatic_conversions_for_abstract(arg, &param_ty, arg_span)?;
+                        }
+                        ctx.concretize(arg)
+                    })
                     .collect::<Result<Vec<_>, _>>()?;
 
                 let has_result = ctx.module.functions[function].result.is_some();
@@ -1885,25 +2217,23 @@ impl<'source, 'temp> Lowerer<'source, 'temp> {
                     let expected = fun.argument_count() as _;
                     let mut args = ctx.prepare_args(arguments, expected, span);
 
-                    let arg = self.expression(args.next()?, ctx)?;
-                    let arg1 = args
-                        .next()
-                        .map(|x| self.expression(x, ctx))
-                        .ok()
-                        .transpose()?;
-                    let arg2 = args
-                        .next()
-                        .map(|x| self.expression(x, ctx))
-                        .ok()
-                        .transpose()?;
-                    let arg3 = args
-                        .next()
-                        .map(|x| self.expression(x, ctx))
-                        .ok()
-                        .transpose()?;
+                    let mut lowered = vec![self.expression_for_abstract(args.next()?, ctx)?];
+                    for _ in 0..3 {
+                        match args.next() {
+                            Ok(arg) => lowered.push(self.expression_for_abstract(arg, ctx)?),
+                            Err(_) => break,
+                        }
+                    }
 
                     args.finish()?;
 
+                    ctx.convert_math_arguments(fun, &mut lowered, span)?;
+                    let mut lowered = lowered.into_iter();
+                    let arg = lowered.next().unwrap();
+                    let arg1 = lowered.next();
+                    let arg2 = lowered.next();
+                    let arg3 = lowered.next();
+
                     if fun == crate::MathFunction::Modf || fun == crate::MathFunction::Frexp {
                         if let Some((size, width)) = match *resolve_inner!(ctx, arg) {
                             crate::TypeInner::Scalar(crate::Scalar { width, .. }) => {
@@ -1940,12 +2270,25 @@ impl<'source, 'temp> Lowerer<'source, 'temp> {
                         "select" => {
                             let mut args = ctx.prepare_args(arguments, 3, span);
 
-                            let reject = self.expression(args.next()?, ctx)?;
-                            let accept = self.expression(args.next()?, ctx)?;
+                            let mut reject = self.expression_for_abstract(args.next()?, ctx)?;
+                            let mut accept = self.expression_for_abstract(args.next()?, ctx)?;
                             let condition = self.expression(args.next()?, ctx)?;
 
                             args.finish()?;
 
+                            // Reconcile the leaf scalar types of the two
+                            // values, as for a binary operator.
+                            ctx.grow_types(reject)?;
+                            ctx.grow_types(accept)?;
+                            if let Ok(consensus_scalar) =
+                                ctx.automatic_conversion_consensus([reject, accept].iter())
+                            {
+                                ctx.convert_to_leaf_scalar(&mut reject, consensus_scalar)?;
+                                ctx.convert_to_leaf_scalar(&mut accept, consensus_scalar)?;
+                            }
+                            let reject = ctx.concretize(reject)?;
+                            let accept = ctx.concretize(accept)?;
+
                             crate::Expression::Select {
                                 reject,
                                 accept,
@@ -1969,7 +2312,7 @@ impl<'source, 'temp> Lowerer<'source, 'temp> {
                         "atomicStore" => {
                             let mut args = ctx.prepare_args(arguments, 2, span);
                             let pointer = self.atomic_pointer(args.next()?, ctx)?;
-                            let value = self.expression(args.next()?, ctx)?;
+                            let value = self.atomic_value(pointer, args.next()?, ctx)?;
                             args.finish()?;
 
                             let rctx = ctx.runtime_expression_ctx(span)?;
@@ -2049,11 +2392,11 @@ impl<'source, 'temp> Lowerer<'source, 'temp> {
 
                             let pointer = self.atomic_pointer(args.next()?, ctx)?;
 
-                            let compare = self.expression(args.next()?, ctx)?;
+                            let compare = self.atomic_value(pointer, args.next()?, ctx)?;
 
                             let value = args.next()?;
                             let value_span = ctx.ast_expressions.get_span(value);
-                            let value = self.expression(value, ctx)?;
+                            let value = self.atomic_value(pointer, value, ctx)?;
 
                             args.finish()?;
 
@@ -2309,6 +2652,45 @@ impl<'source, 'temp> Lowerer<'source, 'temp> {
                             )?;
                             return Ok(Some(handle));
                         }
+                        "setMeshOutputs" | "setVertex" | "setPrimitive" => {
+                            if !ctx
+                                .module
+                                .enable_extensions
+                                .contains(crate::EnableExtensions::NAGA_MESH_SHADER)
+                            {
+                                return Err(Error::EnableExtensionNotEnabled {
+                                    extension: crate::EnableExtensions::NAGA_MESH_SHADER,
+                                    span: function.span,
+                                });
+                            }
+
+                            let mut args = ctx.prepare_args(arguments, 2, span);
+                            let first = self.expression(args.next()?, ctx)?;
+                            let second = self.expression(args.next()?, ctx)?;
+                            args.finish()?;
+
+                            let fun = match function.name {
+                                "setMeshOutputs" => crate::MeshFunction::SetMeshOutputs {
+                                    vertex_count: first,
+                                    primitive_count: second,
+                                },
+                                "setVertex" => crate::MeshFunction::SetVertex {
+                                    index: first,
+                                    value: second,
+                                },
+                                _ => crate::MeshFunction::SetPrimitive {
+                                    index: first,
+                                    value: second,
+                                },
+                            };
+
+                            let rctx = ctx.runtime_expression_ctx(span)?;
+                            rctx.block
+                                .extend(rctx.emitter.finish(&rctx.function.expressions));
+                            rctx.emitter.start(&rctx.function.expressions);
+                            rctx.block.push(crate::Statement::MeshFunction(fun), span);
+                            return Ok(None);
+                        }
                         _ => return Err(Error::UnknownIdent(function.span, function.name)),
                     }
                 };
@@ -2342,6 +2724,35 @@ impl<'source, 'temp> Lowerer<'source, 'temp> {
         }
     }
 
+    /// Lower `expr`, an operand of an atomic operation on `pointer`.
+    ///
+    /// Abstract operands are converted to the atomic's scalar type.
+    fn atomic_value(
+        &mut self,
+        pointer: Handle<crate::Expression>,
+        expr: Handle<ast::Expression<'source>>,
+        ctx: &mut ExpressionContext<'source, '_, '_>,
+    ) -> Result<Handle<crate::Expression>, Error<'source>> {
+        let span = ctx.ast_expressions.get_span(expr);
+        let value = self.expression_for_abstract(expr, ctx)?;
+
+        let scalar = match *resolve_inner!(ctx, pointer) {
+            crate::TypeInner::Pointer { base, .. } => match ctx.module.types[base].inner {
+                crate::TypeInner::Atomic(scalar) => Some(scalar),
+                _ => None,
+            },
+            _ => None,
+        };
+        let value = match scalar {
+            Some(scalar) => {
+                let goal = crate::proc::TypeResolution::Value(crate::TypeInner::Scalar(scalar));
+                ctx.try_automatic_conversions_for_abstract(value, &goal, span)?
+            }
+            None => value,
+        };
+        ctx.concretize(value)
+    }
+
     fn atomic_helper(
         &mut self,
         span: Span,
@@ -2353,8 +2764,7 @@ impl<'source, 'temp> Lowerer<'source, 'temp> {
 
         let pointer = self.atomic_pointer(args.next()?, ctx)?;
 
-        let value = args.next()?;
-        let value = self.expression(value, ctx)?;
+        let value = self.atomic_value(pointer, args.next()?, ctx)?;
         let ty = ctx.register_type(value)?;
 
         args.finish()?;
@@ -2442,7 +2852,7 @@ impl<'source, 'temp> Lowerer<'source, 'temp> {
 
         let sampler = self.expression(args.next()?, ctx)?;
 
-        let coordinate = self.expression(args.next()?, ctx)?;
+        let mut coordinate = self.expression(args.next()?, ctx)?;
 
         let (_, arrayed) = ctx.image_data(image, image_span)?;
         let array_index = arrayed
@@ -2478,11 +2888,40 @@ impl<'source, 'temp> Lowerer<'source, 'temp> {
                 let level = self.expression(args.next()?, ctx)?;
                 (crate::SampleLevel::Exact(level), None)
             }
+            Texture::SampleBaseClampToEdge => {
+                match *resolve_inner!(ctx, image) {
+                    // External images are clamped by `external_texture::lower`,
+                    // once the size of each plane is known.
+                    crate::TypeInner::Image {
+                        class: crate::ImageClass::External,
+                        ..
+                    } => {}
+                    crate::TypeInner::Image {
+                        dim: crate::ImageDimension::D2,
+                        arrayed: false,
+                        class:
+                            crate::ImageClass::Sampled {
+                                kind: crate::ScalarKind::Float,
+                                multi: false,
+                            },
+                    } => coordinate = ctx.clamp_to_edge(image, coordinate, span)?,
+                    _ => return Err(Error::InvalidClampToEdgeTexture(image_span)),
+                }
+                (crate::SampleLevel::Zero, None)
+            }
         };
 
         let offset = args
             .next()
-            .map(|arg| self.expression(arg, &mut ctx.as_const()))
+            .map(|arg| {
+                self.expression(arg, &mut ctx.as_const())
+                    .map_err(|error| match error {
+                        Error::UnexpectedOperationInConstContext(_) => {
+                            Error::ExpectedConstExprSampleOffset(ctx.ast_expressions.get_span(arg))
+                        }
+                        error => error,
+                    })
+            })
             .ok()
             .transpose()?;
 
@@ -2640,17 +3079,19 @@ impl<'source, 'temp> Lowerer<'source, 'temp> {
     ) -> Result<Handle<crate::Type>, Error<'source>> {
         let inner = match ctx.types[handle] {
             ast::Type::Scalar(scalar) => scalar.to_inner_scalar(),
-            ast::Type::Vector { size, scalar } => scalar.to_inner_vector(size),
+            ast::Type::Vector { size, scalar } => {
+                self.component_scalar(scalar, ctx)?.to_inner_vector(size)
+            }
             ast::Type::Matrix {
                 rows,
                 columns,
-                width,
+                scalar,
             } => crate::TypeInner::Matrix {
                 columns,
                 rows,
-                scalar: crate::Scalar::float(width),
+                scalar: self.matrix_component_scalar(scalar, ctx)?,
             },
-            ast::Type::Atomic(scalar) => scalar.to_inner_atomic(),
+            ast::Type::Atomic(scalar) => self.component_scalar(scalar, ctx)?.to_inner_atomic(),
             ast::Type::Pointer { base, space } => {
                 let base = self.resolve_ast_type(base, ctx)?;
                 crate::TypeInner::Pointer { base, space }
@@ -2662,7 +3103,11 @@ impl<'source, 'temp> Lowerer<'source, 'temp> {
                 self.layouter.update(ctx.module.to_ctx()).unwrap();
                 let stride = self.layouter[base].to_stride();
 
-                crate::TypeInner::Array { base, size, stride }
+                let inner = crate::TypeInner::Array { base, size, stride };
+                if inner.try_size(ctx.module.to_ctx()).is_none() {
+                    return Err(Error::ArrayTooLarge(ctx.types.get_span(handle)));
+                }
+                inner
             }
             ast::Type::Image {
                 dim,
@@ -2699,6 +3144,46 @@ impl<'source, 'temp> Lowerer<'source, 'temp> {
         Ok(ctx.ensure_type_exists(name, inner))
     }
 
+    /// Return the scalar type a vector, matrix or atomic type's `component`
+    /// refers to.
+    fn component_scalar(
+        &mut self,
+        component: ast::ComponentType<'source>,
+        ctx: &GlobalContext<'source, '_, '_>,
+    ) -> Result<crate::Scalar, Error<'source>> {
+        let ident = match component {
+            ast::ComponentType::Scalar(scalar) => return Ok(scalar),
+            ast::ComponentType::Alias(ident) => ident,
+        };
+
+        match ctx.globals.get(ident.name) {
+            Some(&LoweredGlobalDecl::Type(handle)) => match ctx.module.types[handle].inner {
+                crate::TypeInner::Scalar(scalar) => Ok(scalar),
+                _ => Err(Error::UnknownScalarType(ident.span)),
+            },
+            _ => Err(Error::UnknownScalarType(ident.span)),
+        }
+    }
+
+    /// Like [`component_scalar`], but for a matrix, whose components must
+    /// be floating-point.
+    ///
+    /// [`component_scalar`]: Lowerer::component_scalar
+    fn matrix_component_scalar(
+        &mut self,
+        component: ast::ComponentType<'source>,
+        ctx: &GlobalContext<'source, '_, '_>,
+    ) -> Result<crate::Scalar, Error<'source>> {
+        let scalar = self.component_scalar(component, ctx)?;
+        match component {
+            // The parser checks scalar types written out directly.
+            ast::ComponentType::Alias(ident) if scalar.kind != crate::ScalarKind::Float => {
+                Err(Error::BadMatrixScalarKind(ident.span, scalar))
+            }
+            _ => Ok(scalar),
+        }
+    }
+
     /// Return a Naga `Handle<Type>` representing the front-end type `handle`.
     fn resolve_ast_type(
         &mut self,
@@ -1,3 +1,4 @@
+use crate::diagnostic_filter::DiagnosticFilter;
 use crate::front::wgsl::parse::number::Number;
 use crate::front::wgsl::Scalar;
 use crate::{Arena, FastIndexSet, Handle, Span};
@@ -24,6 +25,12 @@ pub struct TranslationUnit<'a> {
     /// These are referred to by `Handle<ast::Type<'a>>` values.
     /// User-defined types are referred to by name until lowering.
     pub types: Arena<Type<'a>>,
+
+    /// Extensions enabled by `enable` directives.
+    pub enable_extensions: crate::EnableExtensions,
+
+    /// Filters from `diagnostic` directives, which apply to the whole module.
+    pub diagnostic_filters: Vec<DiagnosticFilter>,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -84,6 +91,7 @@ pub enum GlobalDeclKind<'a> {
     Const(Const<'a>),
     Struct(Struct<'a>),
     Type(TypeAlias<'a>),
+    ConstAssert(Handle<Expression<'a>>),
 }
 
 #[derive(Debug)]
@@ -105,6 +113,18 @@ pub struct EntryPoint<'a> {
     pub stage: crate::ShaderStage,
     pub early_depth_test: Option<crate::EarlyDepthTest>,
     pub workgroup_size: Option<[Option<Handle<Expression<'a>>>; 3]>,
+    pub mesh_output: Option<MeshOutput<'a>>,
+}
+
+/// The arguments of a `@mesh(topology, vertex, max_vertices, primitive,
+/// max_primitives)` attribute.
+#[derive(Debug)]
+pub struct MeshOutput<'a> {
+    pub topology: crate::MeshOutputTopology,
+    pub vertex_type: Handle<Type<'a>>,
+    pub max_vertices: Handle<Expression<'a>>,
+    pub primitive_type: Handle<Type<'a>>,
+    pub max_primitives: Handle<Expression<'a>>,
 }
 
 #[cfg(doc)]
@@ -113,6 +133,8 @@ use crate::front::wgsl::lower::{RuntimeExpressionContext, StatementContext};
 #[derive(Debug)]
 pub struct Function<'a> {
     pub entry_point: Option<EntryPoint<'a>>,
+    /// Filters from `@diagnostic` attributes on the function.
+    pub diagnostic_filters: Vec<DiagnosticFilter>,
     pub name: Ident<'a>,
     pub arguments: Vec<FunctionArgument<'a>>,
     pub result: Option<FunctionResult<'a>>,
@@ -168,8 +190,9 @@ pub struct GlobalVariable<'a> {
     pub name: Ident<'a>,
     pub space: crate::AddressSpace,
     pub binding: Option<ResourceBinding<'a>>,
-    pub ty: Handle<Type<'a>>,
+    pub ty: Option<Handle<Type<'a>>>,
     pub init: Option<Handle<Expression<'a>>>,
+    pub memory_decorations: crate::MemoryDecorations,
 }
 
 #[derive(Debug)]
@@ -211,19 +234,38 @@ pub enum ArraySize<'a> {
     Dynamic,
 }
 
+/// The component type of a vector, matrix or atomic type, like the `f32` in
+/// `vec4<f32>`.
+#[derive(Debug, Copy, Clone)]
+pub enum ComponentType<'a> {
+    Scalar(Scalar),
+
+    /// A type alias, which must name a scalar type.
+    ///
+    /// Aliases may be declared after their uses, so the parser can't check
+    /// this; lowering does.
+    Alias(Ident<'a>),
+}
+
+impl From<Scalar> for ComponentType<'_> {
+    fn from(scalar: Scalar) -> Self {
+        Self::Scalar(scalar)
+    }
+}
+
 #[derive(Debug)]
 pub enum Type<'a> {
     Scalar(Scalar),
     Vector {
         size: crate::VectorSize,
-        scalar: Scalar,
+        scalar: ComponentType<'a>,
     },
     Matrix {
         columns: crate::VectorSize,
         rows: crate::VectorSize,
-        width: crate::Bytes,
+        scalar: ComponentType<'a>,
     },
-    Atomic(Scalar),
+    Atomic(ComponentType<'a>),
     Pointer {
         base: Handle<Type<'a>>,
         space: crate::AddressSpace,
@@ -272,6 +314,7 @@ pub enum StatementKind<'a> {
         condition: Handle<Expression<'a>>,
         accept: Block<'a>,
         reject: Block<'a>,
+        hint: Option<crate::BranchHint>,
     },
     Switch {
         selector: Handle<Expression<'a>>,
@@ -281,6 +324,7 @@ pub enum StatementKind<'a> {
         body: Block<'a>,
         continuing: Block<'a>,
         break_if: Option<Handle<Expression<'a>>>,
+        hint: Option<crate::LoopHint>,
     },
     Break,
     Continue,
@@ -300,6 +344,16 @@ pub enum StatementKind<'a> {
     Increment(Handle<Expression<'a>>),
     Decrement(Handle<Expression<'a>>),
     Ignore(Handle<Expression<'a>>),
+    ConstAssert(Handle<Expression<'a>>),
+    DebugMarker(&'a str),
+    /// Statements preceded by `@diagnostic` attributes.
+    ///
+    /// This is usually a single statement, but a `for` loop's initializer
+    /// is a separate statement that the filters apply to as well.
+    DiagnosticScope {
+        filters: Vec<DiagnosticFilter>,
+        body: Block<'a>,
+    },
 }
 
 #[derive(Debug)]
@@ -348,7 +402,7 @@ pub enum ConstructorType<'a> {
     /// `vec3<f32>(1.0)`.
     Vector {
         size: crate::VectorSize,
-        scalar: Scalar,
+        scalar: ComponentType<'a>,
     },
 
     /// A matrix construction whose component type is inferred from the
@@ -363,7 +417,7 @@ pub enum ConstructorType<'a> {
     Matrix {
         columns: crate::VectorSize,
         rows: crate::VectorSize,
-        width: crate::Bytes,
+        scalar: ComponentType<'a>,
     },
 
     /// An array whose component type and size are inferred from the arguments:
@@ -29,16 +29,80 @@ pub fn map_built_in(word: &str, span: Span) -> Result<crate::BuiltIn, Error<'_>>
         "primitive_index" => crate::BuiltIn::PrimitiveIndex,
         "sample_index" => crate::BuiltIn::SampleIndex,
         "sample_mask" => crate::BuiltIn::SampleMask,
+        "clip_distances" => crate::BuiltIn::ClipDistance,
         // compute
         "global_invocation_id" => crate::BuiltIn::GlobalInvocationId,
         "local_invocation_id" => crate::BuiltIn::LocalInvocationId,
         "local_invocation_index" => crate::BuiltIn::LocalInvocationIndex,
         "workgroup_id" => crate::BuiltIn::WorkGroupId,
         "num_workgroups" => crate::BuiltIn::NumWorkGroups,
+        // mesh shading
+        "mesh_task_size" => crate::BuiltIn::MeshTaskSize,
+        "triangle_indices" => crate::BuiltIn::TriangleIndices,
+        "line_indices" => crate::BuiltIn::LineIndices,
+        "point_index" => crate::BuiltIn::PointIndex,
+        "cull_primitive" => crate::BuiltIn::CullPrimitive,
         _ => return Err(Error::UnknownBuiltin(span)),
     })
 }
 
+pub fn map_enable_extension(word: &str, span: Span) -> Result<crate::EnableExtensions, Error<'_>> {
+    match word {
+        "f16" => Ok(crate::EnableExtensions::F16),
+        "dual_source_blending" => Ok(crate::EnableExtensions::DUAL_SOURCE_BLENDING),
+        "clip_distances" => Ok(crate::EnableExtensions::CLIP_DISTANCES),
+        "naga_control_flow_hints" => Ok(crate::EnableExtensions::NAGA_CONTROL_FLOW_HINTS),
+        "naga_memory_decorations" => Ok(crate::EnableExtensions::NAGA_MEMORY_DECORATIONS),
+        "naga_mesh_shader" => Ok(crate::EnableExtensions::NAGA_MESH_SHADER),
+        _ => Err(Error::UnknownEnableExtension(span, word)),
+    }
+}
+
+pub fn map_mesh_output_topology(
+    word: &str,
+    span: Span,
+) -> Result<crate::MeshOutputTopology, Error<'_>> {
+    match word {
+        "points" => Ok(crate::MeshOutputTopology::Points),
+        "lines" => Ok(crate::MeshOutputTopology::Lines),
+        "triangles" => Ok(crate::MeshOutputTopology::Triangles),
+        _ => Err(Error::UnknownMeshOutputTopology(span)),
+    }
+}
+
+pub fn map_severity(
+    word: &str,
+    span: Span,
+) -> Result<crate::diagnostic_filter::Severity, Error<'_>> {
+    use crate::diagnostic_filter::Severity;
+    match word {
+        "error" => Ok(Severity::Error),
+        "warning" => Ok(Severity::Warning),
+        "info" => Ok(Severity::Info),
+        "off" => Ok(Severity::Off),
+        _ => Err(Error::UnknownSeverity(span)),
+    }
+}
+
+/// Map a single-word diagnostic rule name to the rule it names.
+///
+/// The WGSL specification only asks for a warning about unknown names, so
+/// those are kept as [`FilterableTriggeringRule::Unknown`].
+///
+/// [`FilterableTriggeringRule::Unknown`]: crate::diagnostic_filter::FilterableTriggeringRule::Unknown
+pub fn map_triggering_rule(word: &str) -> crate::diagnostic_filter::FilterableTriggeringRule {
+    use crate::diagnostic_filter::{FilterableTriggeringRule, StandardFilterableTriggeringRule};
+    match word {
+        "derivative_uniformity" => FilterableTriggeringRule::Standard(
+            StandardFilterableTriggeringRule::DerivativeUniformity,
+        ),
+        _ => {
+            log::warn!("unknown diagnostic rule `{word}`");
+            FilterableTriggeringRule::Unknown(word.into())
+        }
+    }
+}
+
 pub fn map_interpolation(word: &str, span: Span) -> Result<crate::Interpolation, Error<'_>> {
     match word {
         "linear" => Ok(crate::Interpolation::Linear),
@@ -198,6 +262,8 @@ pub fn map_standard_fun(word: &str) -> Option<crate::MathFunction> {
         "pow" => Mf::Pow,
         // geometry
         "dot" => Mf::Dot,
+        "dot4I8Packed" => Mf::Dot4I8Packed,
+        "dot4U8Packed" => Mf::Dot4U8Packed,
         "cross" => Mf::Cross,
         "distance" => Mf::Distance,
         "length" => Mf::Length,
@@ -215,6 +281,7 @@ pub fn map_standard_fun(word: &str) -> Option<crate::MathFunction> {
         "inverseSqrt" => Mf::InverseSqrt,
         "transpose" => Mf::Transpose,
         "determinant" => Mf::Determinant,
+        "quantizeToF16" => Mf::QuantizeToF16,
         // bits
         "countTrailingZeros" => Mf::CountTrailingZeros,
         "countLeadingZeros" => Mf::CountLeadingZeros,
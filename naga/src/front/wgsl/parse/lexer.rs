@@ -6,6 +6,10 @@ use crate::Span;
 
 type TokenSpan<'a> = (Token<'a>, Span);
 
+/// The start of a line comment that the parser turns into a
+/// [`Statement::DebugMarker`](crate::Statement::DebugMarker).
+const DEBUG_MARKER_PREFIX: &str = "//@marker";
+
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub enum Token<'a> {
     Separator(char),
@@ -246,6 +250,30 @@ impl<'a> Lexer<'a> {
         }
     }
 
+    /// If a debug marker comment precedes the next token, consume it and
+    /// everything before it, and return its text and span.
+    ///
+    /// Other comments and whitespace are left in place.
+    pub(in crate::front::wgsl) fn next_debug_marker(&mut self) -> Option<(&'a str, Span)> {
+        let mut input = self.input;
+        loop {
+            let (token, rest) = consume_token(input, false);
+            if token != Token::Trivia {
+                return None;
+            }
+            let trivia = input[..input.len() - rest.len()].trim_end();
+            if let Some(text) = trivia.strip_prefix(DEBUG_MARKER_PREFIX) {
+                if text.is_empty() || text.starts_with(char::is_whitespace) {
+                    let start = self.source.len() - input.len();
+                    self.input = rest;
+                    self.last_end_offset = start + trivia.len();
+                    return Some((text.trim(), self.span_from(start)));
+                }
+            }
+            input = rest;
+        }
+    }
+
     fn peek_token_and_rest(&mut self) -> (TokenSpan<'a>, &'a str) {
         let mut cloned = self.clone();
         let token = cloned.next();
@@ -373,19 +401,6 @@ impl<'a> Lexer<'a> {
         Ok(ident)
     }
 
-    /// Parses a generic scalar type, for example `<f32>`.
-    pub(in crate::front::wgsl) fn next_scalar_generic(&mut self) -> Result<Scalar, Error<'a>> {
-        self.expect_generic_paren('<')?;
-        let pair = match self.next() {
-            (Token::Word(word), span) => {
-                conv::get_scalar_type(word).ok_or(Error::UnknownScalarType(span))
-            }
-            (_, span) => Err(Error::UnknownScalarType(span)),
-        }?;
-        self.expect_generic_paren('>')?;
-        Ok(pair)
-    }
-
     /// Parses a generic scalar type, for example `<f32>`.
     ///
     /// Returns the span covering the inner type, excluding the brackets.
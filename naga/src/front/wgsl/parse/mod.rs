@@ -1,4 +1,5 @@
-use crate::front::wgsl::error::{Error, ExpectedToken};
+use crate::diagnostic_filter::{DiagnosticFilter, FilterableTriggeringRule};
+use crate::front::wgsl::error::{Error, ExpectedToken, NumberError};
 use crate::front::wgsl::parse::lexer::{Lexer, Token};
 use crate::front::wgsl::parse::number::Number;
 use crate::front::wgsl::Scalar;
@@ -131,6 +132,41 @@ impl<T> ParsedAttribute<T> {
     }
 }
 
+/// Diagnostic filters that apply to the same scope.
+#[derive(Default)]
+struct DiagnosticFilters {
+    /// Each filter, with the span of its rule name.
+    filters: Vec<(DiagnosticFilter, Span)>,
+}
+
+impl DiagnosticFilters {
+    fn add(&mut self, filter: DiagnosticFilter, span: Span) -> Result<(), Error<'static>> {
+        if let Some(&(_, first)) = self
+            .filters
+            .iter()
+            .find(|&&(ref other, _)| other.triggering_rule == filter.triggering_rule)
+        {
+            return Err(Error::DiagnosticFilterConflict {
+                first,
+                second: span,
+            });
+        }
+        self.filters.push((filter, span));
+        Ok(())
+    }
+
+    fn into_filters(self) -> Vec<DiagnosticFilter> {
+        self.filters.into_iter().map(|(filter, _)| filter).collect()
+    }
+}
+
+/// The hint attributes that precede a statement.
+#[derive(Default)]
+struct StatementHints {
+    loop_hint: ParsedAttribute<(crate::LoopHint, Span)>,
+    branch_hint: ParsedAttribute<(crate::BranchHint, Span)>,
+}
+
 #[derive(Default)]
 struct BindingParser<'a> {
     location: ParsedAttribute<Handle<ast::Expression<'a>>>,
@@ -160,8 +196,22 @@ impl<'a> BindingParser<'a> {
             "builtin" => {
                 lexer.expect(Token::Paren('('))?;
                 let (raw, span) = lexer.next_ident_with_span()?;
-                self.built_in
-                    .set(conv::map_built_in(raw, span)?, name_span)?;
+                let built_in = conv::map_built_in(raw, span)?;
+                match built_in {
+                    crate::BuiltIn::ClipDistance => {
+                        parser.require_extension(crate::EnableExtensions::CLIP_DISTANCES, span)?;
+                    }
+                    crate::BuiltIn::MeshTaskSize
+                    | crate::BuiltIn::TriangleIndices
+                    | crate::BuiltIn::LineIndices
+                    | crate::BuiltIn::PointIndex
+                    | crate::BuiltIn::CullPrimitive => {
+                        parser
+                            .require_extension(crate::EnableExtensions::NAGA_MESH_SHADER, span)?;
+                    }
+                    _ => {}
+                }
+                self.built_in.set(built_in, name_span)?;
                 lexer.expect(Token::Paren(')'))?;
             }
             "interpolate" => {
@@ -177,6 +227,8 @@ impl<'a> BindingParser<'a> {
                 lexer.expect(Token::Paren(')'))?;
             }
             "second_blend_source" => {
+                parser
+                    .require_extension(crate::EnableExtensions::DUAL_SOURCE_BLENDING, name_span)?;
                 self.second_blend_source.set(true, name_span)?;
             }
             "invariant" => {
@@ -221,15 +273,104 @@ impl<'a> BindingParser<'a> {
 
 pub struct Parser {
     rules: Vec<(Rule, usize)>,
+    enable_extensions: crate::EnableExtensions,
+    /// The names of all the type aliases the source declares, so that
+    /// component types can name aliases declared after their use.
+    aliases: Vec<String>,
 }
 
 impl Parser {
     pub const fn new() -> Self {
-        Parser { rules: Vec::new() }
+        Parser {
+            rules: Vec::new(),
+            enable_extensions: crate::EnableExtensions::empty(),
+            aliases: Vec::new(),
+        }
     }
 
     fn reset(&mut self) {
         self.rules.clear();
+        self.enable_extensions = crate::EnableExtensions::empty();
+        self.aliases.clear();
+    }
+
+    /// Collect the names of the type aliases `lexer` declares.
+    fn collect_aliases(&mut self, mut lexer: Lexer) {
+        loop {
+            match lexer.next().0 {
+                Token::Word("alias") => {
+                    if let Token::Word(name) = lexer.peek().0 {
+                        self.aliases.push(name.to_string());
+                    }
+                }
+                Token::End => break,
+                _ => {}
+            }
+        }
+    }
+
+    /// Check that `extension` was enabled by an `enable` directive, since
+    /// the construct at `span` depends on it.
+    const fn require_extension(
+        &self,
+        extension: crate::EnableExtensions,
+        span: Span,
+    ) -> Result<(), Error<'static>> {
+        if self.enable_extensions.contains(extension) {
+            Ok(())
+        } else {
+            Err(Error::EnableExtensionNotEnabled { extension, span })
+        }
+    }
+
+    /// Parse an `enable` directive, after the `enable` keyword.
+    fn enable_directive<'a>(&mut self, lexer: &mut Lexer<'a>) -> Result<(), Error<'a>> {
+        loop {
+            let (name, span) = lexer.next_ident_with_span()?;
+            self.enable_extensions |= conv::map_enable_extension(name, span)?;
+            if !lexer.skip(Token::Separator(',')) {
+                break;
+            }
+            // Trailing commas are permitted.
+            if lexer.peek().0 == Token::Separator(';') {
+                break;
+            }
+        }
+        lexer.expect(Token::Separator(';'))
+    }
+
+    /// Parse the arguments of a `diagnostic` directive or attribute, after
+    /// the `diagnostic` keyword, and add the filter to `filters`.
+    fn diagnostic_filter<'a>(
+        &mut self,
+        lexer: &mut Lexer<'a>,
+        filters: &mut DiagnosticFilters,
+    ) -> Result<(), Error<'a>> {
+        lexer.expect(Token::Paren('('))?;
+        let (severity, severity_span) = lexer.next_ident_with_span()?;
+        let new_severity = conv::map_severity(severity, severity_span)?;
+        lexer.expect(Token::Separator(','))?;
+
+        let start = lexer.start_byte_offset();
+        let (name, _) = lexer.next_ident_with_span()?;
+        let triggering_rule = if lexer.skip(Token::Separator('.')) {
+            let (member, _) = lexer.next_ident_with_span()?;
+            FilterableTriggeringRule::User(Box::new([name.to_string(), member.to_string()]))
+        } else {
+            conv::map_triggering_rule(name)
+        };
+        let rule_span = lexer.span_from(start);
+
+        // Trailing commas are permitted.
+        let _ = lexer.skip(Token::Separator(','));
+        lexer.expect(Token::Paren(')'))?;
+
+        let filter = DiagnosticFilter {
+            new_severity,
+            triggering_rule,
+        };
+        filters.add(filter, rule_span)?;
+        Ok(())
     }
 
     fn push_rule_span(&mut self, rule: Rule, lexer: &mut Lexer<'_>) {
@@ -292,7 +433,8 @@ impl Parser {
                     scalar: Scalar {
                         kind: crate::ScalarKind::Sint,
                         width: 4,
-                    },
+                    }
+                    .into(),
                 }))
             }
             "vec2u" => {
@@ -301,13 +443,14 @@ impl Parser {
                     scalar: Scalar {
                         kind: crate::ScalarKind::Uint,
                         width: 4,
-                    },
+                    }
+                    .into(),
                 }))
             }
             "vec2f" => {
                 return Ok(Some(ast::ConstructorType::Vector {
                     size: crate::VectorSize::Bi,
-                    scalar: Scalar::F32,
+                    scalar: Scalar::F32.into(),
                 }))
             }
             "vec3" => ast::ConstructorType::PartialVector {
@@ -316,19 +459,19 @@ impl Parser {
             "vec3i" => {
                 return Ok(Some(ast::ConstructorType::Vector {
                     size: crate::VectorSize::Tri,
-                    scalar: Scalar::I32,
+                    scalar: Scalar::I32.into(),
                 }))
             }
             "vec3u" => {
                 return Ok(Some(ast::ConstructorType::Vector {
                     size: crate::VectorSize::Tri,
-                    scalar: Scalar::U32,
+                    scalar: Scalar::U32.into(),
                 }))
             }
             "vec3f" => {
                 return Ok(Some(ast::ConstructorType::Vector {
                     size: crate::VectorSize::Tri,
-                    scalar: Scalar::F32,
+                    scalar: Scalar::F32.into(),
                 }))
             }
             "vec4" => ast::ConstructorType::PartialVector {
@@ -337,19 +480,19 @@ impl Parser {
             "vec4i" => {
                 return Ok(Some(ast::ConstructorType::Vector {
                     size: crate::VectorSize::Quad,
-                    scalar: Scalar::I32,
+                    scalar: Scalar::I32.into(),
                 }))
             }
             "vec4u" => {
                 return Ok(Some(ast::ConstructorType::Vector {
                     size: crate::VectorSize::Quad,
-                    scalar: Scalar::U32,
+                    scalar: Scalar::U32.into(),
                 }))
             }
             "vec4f" => {
                 return Ok(Some(ast::ConstructorType::Vector {
                     size: crate::VectorSize::Quad,
-                    scalar: Scalar::F32,
+                    scalar: Scalar::F32.into(),
                 }))
             }
             "mat2x2" => ast::ConstructorType::PartialMatrix {
@@ -360,7 +503,7 @@ impl Parser {
                 return Ok(Some(ast::ConstructorType::Matrix {
                     columns: crate::VectorSize::Bi,
                     rows: crate::VectorSize::Bi,
-                    width: 4,
+                    scalar: Scalar::F32.into(),
                 }))
             }
             "mat2x3" => ast::ConstructorType::PartialMatrix {
@@ -371,7 +514,7 @@ impl Parser {
                 return Ok(Some(ast::ConstructorType::Matrix {
                     columns: crate::VectorSize::Bi,
                     rows: crate::VectorSize::Tri,
-                    width: 4,
+                    scalar: Scalar::F32.into(),
                 }))
             }
             "mat2x4" => ast::ConstructorType::PartialMatrix {
@@ -382,7 +525,7 @@ impl Parser {
                 return Ok(Some(ast::ConstructorType::Matrix {
                     columns: crate::VectorSize::Bi,
                     rows: crate::VectorSize::Quad,
-                    width: 4,
+                    scalar: Scalar::F32.into(),
                 }))
             }
             "mat3x2" => ast::ConstructorType::PartialMatrix {
@@ -393,7 +536,7 @@ impl Parser {
                 return Ok(Some(ast::ConstructorType::Matrix {
                     columns: crate::VectorSize::Tri,
                     rows: crate::VectorSize::Bi,
-                    width: 4,
+                    scalar: Scalar::F32.into(),
                 }))
             }
             "mat3x3" => ast::ConstructorType::PartialMatrix {
@@ -404,7 +547,7 @@ impl Parser {
                 return Ok(Some(ast::ConstructorType::Matrix {
                     columns: crate::VectorSize::Tri,
                     rows: crate::VectorSize::Tri,
-                    width: 4,
+                    scalar: Scalar::F32.into(),
                 }))
             }
             "mat3x4" => ast::ConstructorType::PartialMatrix {
@@ -415,7 +558,7 @@ impl Parser {
                 return Ok(Some(ast::ConstructorType::Matrix {
                     columns: crate::VectorSize::Tri,
                     rows: crate::VectorSize::Quad,
-                    width: 4,
+                    scalar: Scalar::F32.into(),
                 }))
             }
             "mat4x2" => ast::ConstructorType::PartialMatrix {
@@ -426,7 +569,7 @@ impl Parser {
                 return Ok(Some(ast::ConstructorType::Matrix {
                     columns: crate::VectorSize::Quad,
                     rows: crate::VectorSize::Bi,
-                    width: 4,
+                    scalar: Scalar::F32.into(),
                 }))
             }
             "mat4x3" => ast::ConstructorType::PartialMatrix {
@@ -437,7 +580,7 @@ impl Parser {
                 return Ok(Some(ast::ConstructorType::Matrix {
                     columns: crate::VectorSize::Quad,
                     rows: crate::VectorSize::Tri,
-                    width: 4,
+                    scalar: Scalar::F32.into(),
                 }))
             }
             "mat4x4" => ast::ConstructorType::PartialMatrix {
@@ -448,7 +591,7 @@ impl Parser {
                 return Ok(Some(ast::ConstructorType::Matrix {
                     columns: crate::VectorSize::Quad,
                     rows: crate::VectorSize::Quad,
-                    width: 4,
+                    scalar: Scalar::F32.into(),
                 }))
             }
             "array" => ast::ConstructorType::PartialArray,
@@ -470,6 +613,7 @@ impl Parser {
             | "texture_depth_cube"
             | "texture_depth_cube_array"
             | "texture_depth_multisampled_2d"
+            | "texture_external"
             | "texture_storage_1d"
             | "texture_storage_1d_array"
             | "texture_storage_2d"
@@ -481,19 +625,16 @@ impl Parser {
         // parse component type if present
         match (lexer.peek().0, partial) {
             (Token::Paren('<'), ast::ConstructorType::PartialVector { size }) => {
-                let scalar = lexer.next_scalar_generic()?;
+                let (scalar, _) = self.component_type(lexer, ctx)?;
                 Ok(Some(ast::ConstructorType::Vector { size, scalar }))
             }
             (Token::Paren('<'), ast::ConstructorType::PartialMatrix { columns, rows }) => {
-                let (scalar, span) = lexer.next_scalar_generic_with_span()?;
-                match scalar.kind {
-                    crate::ScalarKind::Float => Ok(Some(ast::ConstructorType::Matrix {
-                        columns,
-                        rows,
-                        width: scalar.width,
-                    })),
-                    _ => Err(Error::BadMatrixScalarKind(span, scalar)),
-                }
+                let scalar = self.matrix_component_type(lexer, ctx)?;
+                Ok(Some(ast::ConstructorType::Matrix {
+                    columns,
+                    rows,
+                    scalar,
+                }))
             }
             (Token::Paren('<'), ast::ConstructorType::PartialArray) => {
                 lexer.expect_generic_paren('<')?;
@@ -630,6 +771,9 @@ impl Parser {
             }
             (Token::Number(res), span) => {
                 let _ = lexer.next();
+                if let Err(NumberError::UnimplementedF16) = res {
+                    self.require_extension(crate::EnableExtensions::F16, span)?;
+                }
                 let num = res.map_err(|err| Error::BadNumber(span, err))?;
                 ast::Expression::Literal(ast::Literal::Number(num))
             }
@@ -959,8 +1103,12 @@ impl Parser {
             lexer.expect(Token::Paren('>'))?;
         }
         let name = lexer.next_ident()?;
-        lexer.expect(Token::Separator(':'))?;
-        let ty = self.type_decl(lexer, ctx)?;
+
+        let ty = if lexer.skip(Token::Separator(':')) {
+            Some(self.type_decl(lexer, ctx)?)
+        } else {
+            None
+        };
 
         let init = if lexer.skip(Token::Operation('=')) {
             let handle = self.general_expression(lexer, ctx)?;
@@ -977,6 +1125,7 @@ impl Parser {
             binding: None,
             ty,
             init,
+            memory_decorations: crate::MemoryDecorations::empty(),
         })
     }
 
@@ -1037,21 +1186,64 @@ impl Parser {
         Ok(members)
     }
 
+    /// Parse a generic component type, like the `<f32>` in `vec4<f32>`.
+    ///
+    /// Returns the span covering the component type, excluding the brackets.
+    fn component_type<'a>(
+        &mut self,
+        lexer: &mut Lexer<'a>,
+        ctx: &mut ExpressionContext<'a, '_, '_>,
+    ) -> Result<(ast::ComponentType<'a>, Span), Error<'a>> {
+        lexer.expect_generic_paren('<')?;
+        let component = match lexer.next() {
+            (Token::Word(word), span) => match conv::get_scalar_type(word) {
+                Some(scalar) => (ast::ComponentType::Scalar(scalar), span),
+                None if self.aliases.iter().any(|alias| alias == word) => {
+                    ctx.unresolved.insert(ast::Dependency {
+                        ident: word,
+                        usage: span,
+                    });
+                    let ident = ast::Ident { name: word, span };
+                    (ast::ComponentType::Alias(ident), span)
+                }
+                None => return Err(Error::UnknownScalarType(span)),
+            },
+            (_, span) => return Err(Error::UnknownScalarType(span)),
+        };
+        lexer.expect_generic_paren('>')?;
+        Ok(component)
+    }
+
+    /// Parse a matrix's generic component type, which must be
+    /// floating-point.
+    fn matrix_component_type<'a>(
+        &mut self,
+        lexer: &mut Lexer<'a>,
+        ctx: &mut ExpressionContext<'a, '_, '_>,
+    ) -> Result<ast::ComponentType<'a>, Error<'a>> {
+        match self.component_type(lexer, ctx)? {
+            (ast::ComponentType::Scalar(scalar), span)
+                if scalar.kind != crate::ScalarKind::Float =>
+            {
+                Err(Error::BadMatrixScalarKind(span, scalar))
+            }
+            (component, _) => Ok(component),
+        }
+    }
+
     fn matrix_scalar_type<'a>(
         &mut self,
         lexer: &mut Lexer<'a>,
         columns: crate::VectorSize,
         rows: crate::VectorSize,
+        ctx: &mut ExpressionContext<'a, '_, '_>,
     ) -> Result<ast::Type<'a>, Error<'a>> {
-        let (scalar, span) = lexer.next_scalar_generic_with_span()?;
-        match scalar.kind {
-            crate::ScalarKind::Float => Ok(ast::Type::Matrix {
-                columns,
-                rows,
-                width: scalar.width,
-            }),
-            _ => Err(Error::BadMatrixScalarKind(span, scalar)),
-        }
+        let scalar = self.matrix_component_type(lexer, ctx)?;
+        Ok(ast::Type::Matrix {
+            columns,
+            rows,
+            scalar,
+        })
     }
 
     fn type_decl_impl<'a>(
@@ -1066,7 +1258,7 @@ impl Parser {
 
         Ok(Some(match word {
             "vec2" => {
-                let scalar = lexer.next_scalar_generic()?;
+                let (scalar, _) = self.component_type(lexer, ctx)?;
                 ast::Type::Vector {
                     size: crate::VectorSize::Bi,
                     scalar,
@@ -1077,21 +1269,23 @@ impl Parser {
                 scalar: Scalar {
                     kind: crate::ScalarKind::Sint,
                     width: 4,
-                },
+                }
+                .into(),
             },
             "vec2u" => ast::Type::Vector {
                 size: crate::VectorSize::Bi,
                 scalar: Scalar {
                     kind: crate::ScalarKind::Uint,
                     width: 4,
-                },
+                }
+                .into(),
             },
             "vec2f" => ast::Type::Vector {
                 size: crate::VectorSize::Bi,
-                scalar: Scalar::F32,
+                scalar: Scalar::F32.into(),
             },
             "vec3" => {
-                let scalar = lexer.next_scalar_generic()?;
+                let (scalar, _) = self.component_type(lexer, ctx)?;
                 ast::Type::Vector {
                     size: crate::VectorSize::Tri,
                     scalar,
@@ -1102,21 +1296,23 @@ impl Parser {
                 scalar: Scalar {
                     kind: crate::ScalarKind::Sint,
                     width: 4,
-                },
+                }
+                .into(),
             },
             "vec3u" => ast::Type::Vector {
                 size: crate::VectorSize::Tri,
                 scalar: Scalar {
                     kind: crate::ScalarKind::Uint,
                     width: 4,
-                },
+                }
+                .into(),
             },
             "vec3f" => ast::Type::Vector {
                 size: crate::VectorSize::Tri,
-                scalar: Scalar::F32,
+                scalar: Scalar::F32.into(),
             },
             "vec4" => {
-                let scalar = lexer.next_scalar_generic()?;
+                let (scalar, _) = self.component_type(lexer, ctx)?;
                 ast::Type::Vector {
                     size: crate::VectorSize::Quad,
                     scalar,
@@ -1127,93 +1323,104 @@ impl Parser {
                 scalar: Scalar {
                     kind: crate::ScalarKind::Sint,
                     width: 4,
-                },
+                }
+                .into(),
             },
             "vec4u" => ast::Type::Vector {
                 size: crate::VectorSize::Quad,
                 scalar: Scalar {
                     kind: crate::ScalarKind::Uint,
                     width: 4,
-                },
+                }
+                .into(),
             },
             "vec4f" => ast::Type::Vector {
                 size: crate::VectorSize::Quad,
-                scalar: Scalar::F32,
+                scalar: Scalar::F32.into(),
             },
             "mat2x2" => {
-                self.matrix_scalar_type(lexer, crate::VectorSize::Bi, crate::VectorSize::Bi)?
+                self.matrix_scalar_type(lexer, crate::VectorSize::Bi, crate::VectorSize::Bi, ctx)?
             }
             "mat2x2f" => ast::Type::Matrix {
                 columns: crate::VectorSize::Bi,
                 rows: crate::VectorSize::Bi,
-                width: 4,
+                scalar: Scalar::F32.into(),
             },
             "mat2x3" => {
-                self.matrix_scalar_type(lexer, crate::VectorSize::Bi, crate::VectorSize::Tri)?
+                self.matrix_scalar_type(lexer, crate::VectorSize::Bi, crate::VectorSize::Tri, ctx)?
             }
             "mat2x3f" => ast::Type::Matrix {
                 columns: crate::VectorSize::Bi,
                 rows: crate::VectorSize::Tri,
-                width: 4,
+                scalar: Scalar::F32.into(),
             },
             "mat2x4" => {
-                self.matrix_scalar_type(lexer, crate::VectorSize::Bi, crate::VectorSize::Quad)?
+                self.matrix_scalar_type(lexer, crate::VectorSize::Bi, crate::VectorSize::Quad, ctx)?
             }
             "mat2x4f" => ast::Type::Matrix {
                 columns: crate::VectorSize::Bi,
                 rows: crate::VectorSize::Quad,
-                width: 4,
+                scalar: Scalar::F32.into(),
             },
             "mat3x2" => {
-                self.matrix_scalar_type(lexer, crate::VectorSize::Tri, crate::VectorSize::Bi)?
+                self.matrix_scalar_type(lexer, crate::VectorSize::Tri, crate::VectorSize::Bi, ctx)?
             }
             "mat3x2f" => ast::Type::Matrix {
                 columns: crate::VectorSize::Tri,
                 rows: crate::VectorSize::Bi,
-                width: 4,
+                scalar: Scalar::F32.into(),
             },
             "mat3x3" => {
-                self.matrix_scalar_type(lexer, crate::VectorSize::Tri, crate::VectorSize::Tri)?
+                self.matrix_scalar_type(lexer, crate::VectorSize::Tri, crate::VectorSize::Tri, ctx)?
             }
             "mat3x3f" => ast::Type::Matrix {
                 columns: crate::VectorSize::Tri,
                 rows: crate::VectorSize::Tri,
-                width: 4,
+                scalar: Scalar::F32.into(),
             },
-            "mat3x4" => {
-                self.matrix_scalar_type(lexer, crate::VectorSize::Tri, crate::VectorSize::Quad)?
-            }
+            "mat3x4" => self.matrix_scalar_type(
+                lexer,
+                crate::VectorSize::Tri,
+                crate::VectorSize::Quad,
+                ctx,
+            )?,
             "mat3x4f" => ast::Type::Matrix {
                 columns: crate::VectorSize::Tri,
                 rows: crate::VectorSize::Quad,
-                width: 4,
+                scalar: Scalar::F32.into(),
             },
             "mat4x2" => {
-                self.matrix_scalar_type(lexer, crate::VectorSize::Quad, crate::VectorSize::Bi)?
+                self.matrix_scalar_type(lexer, crate::VectorSize::Quad, crate::VectorSize::Bi, ctx)?
             }
             "mat4x2f" => ast::Type::Matrix {
                 columns: crate::VectorSize::Quad,
                 rows: crate::VectorSize::Bi,
-                width: 4,
+                scalar: Scalar::F32.into(),
             },
-            "mat4x3" => {
-                self.matrix_scalar_type(lexer, crate::VectorSize::Quad, crate::VectorSize::Tri)?
-            }
+            "mat4x3" => self.matrix_scalar_type(
+                lexer,
+                crate::VectorSize::Quad,
+                crate::VectorSize::Tri,
+                ctx,
+            )?,
             "mat4x3f" => ast::Type::Matrix {
                 columns: crate::VectorSize::Quad,
                 rows: crate::VectorSize::Tri,
-                width: 4,
+                scalar: Scalar::F32.into(),
             },
-            "mat4x4" => {
-                self.matrix_scalar_type(lexer, crate::VectorSize::Quad, crate::VectorSize::Quad)?
-            }
+            "mat4x4" => self.matrix_scalar_type(
+                lexer,
+                crate::VectorSize::Quad,
+                crate::VectorSize::Quad,
+                ctx,
+            )?,
             "mat4x4f" => ast::Type::Matrix {
                 columns: crate::VectorSize::Quad,
                 rows: crate::VectorSize::Quad,
-                width: 4,
+                scalar: Scalar::F32.into(),
             },
             "atomic" => {
-                let scalar = lexer.next_scalar_generic()?;
+                let (scalar, _) = self.component_type(lexer, ctx)?;
                 ast::Type::Atomic(scalar)
             }
             "ptr" => {
@@ -1393,6 +1600,11 @@ impl Parser {
                 arrayed: false,
                 class: crate::ImageClass::Depth { multi: true },
             },
+            "texture_external" => ast::Type::Image {
+                dim: crate::ImageDimension::D2,
+                arrayed: false,
+                class: crate::ImageClass::External,
+            },
             "texture_storage_1d" => {
                 let (format, access) = lexer.next_format_generic()?;
                 ast::Type::Image {
@@ -1462,6 +1674,10 @@ impl Parser {
         self.push_rule_span(Rule::TypeDecl, lexer);
 
         let (name, span) = lexer.next_ident_with_span()?;
+        if name == "f16" {
+            self.require_extension(crate::EnableExtensions::F16, span)?;
+            return Err(Error::UnimplementedF16(span));
+        }
 
         let ty = match self.type_decl_impl(lexer, name, ctx)? {
             Some(ty) => ty,
@@ -1474,9 +1690,9 @@ impl Parser {
             }
         };
 
-        self.pop_rule_span(lexer);
+        let decl_span = self.pop_rule_span(lexer);
 
-        let handle = ctx.types.append(ty, Span::UNDEFINED);
+        let handle = ctx.types.append(ty, decl_span);
         Ok(handle)
     }
 
@@ -1614,12 +1830,96 @@ impl Parser {
         }
     }
 
+    /// Parse a statement preceded by attributes, like `@unroll`.
+    ///
+    /// Loop hints apply to `loop`, `for` and `while` statements, and branch
+    /// hints to `if` statements. `@diagnostic` filters apply to those and
+    /// to `switch` statements and compound statements.
+    fn hinted_statement<'a>(
+        &mut self,
+        lexer: &mut Lexer<'a>,
+        ctx: &mut ExpressionContext<'a, '_, '_>,
+        block: &mut ast::Block<'a>,
+    ) -> Result<(), Error<'a>> {
+        let start = lexer.start_byte_offset();
+        let mut hints = StatementHints::default();
+        let mut diagnostic_filters = DiagnosticFilters::default();
+        let mut diagnostic_span = None;
+        while lexer.skip(Token::Attribute) {
+            let (name, span) = lexer.next_ident_with_span()?;
+            if name == "diagnostic" {
+                self.diagnostic_filter(lexer, &mut diagnostic_filters)?;
+                diagnostic_span.get_or_insert(span);
+                continue;
+            }
+            match name {
+                "unroll" => hints.loop_hint.set((crate::LoopHint::Unroll, span), span)?,
+                "dont_unroll" => hints
+                    .loop_hint
+                    .set((crate::LoopHint::DontUnroll, span), span)?,
+                "branch" => hints
+                    .branch_hint
+                    .set((crate::BranchHint::Branch, span), span)?,
+                "flatten" => hints
+                    .branch_hint
+                    .set((crate::BranchHint::Flatten, span), span)?,
+                _ => return Err(Error::UnknownAttribute(span)),
+            }
+            self.require_extension(crate::EnableExtensions::NAGA_CONTROL_FLOW_HINTS, span)?;
+        }
+
+        // A `for` statement's initializer precedes the loop itself.
+        let num_statements = block.stmts.len();
+        self.statement(lexer, ctx, block)?;
+        let kind = block.stmts[num_statements..]
+            .last_mut()
+            .map(|statement| &mut statement.kind);
+        match (kind, hints.loop_hint.value, hints.branch_hint.value) {
+            (Some(&mut ast::StatementKind::Loop { ref mut hint, .. }), Some((value, _)), None) => {
+                *hint = Some(value);
+            }
+            (Some(&mut ast::StatementKind::If { ref mut hint, .. }), None, Some((value, _))) => {
+                *hint = Some(value);
+            }
+            (_, Some((_, span)), _) | (_, None, Some((_, span))) => {
+                return Err(Error::InapplicableHint(span));
+            }
+            (_, None, None) => {}
+        }
+
+        if let Some(span) = diagnostic_span {
+            match block.stmts[num_statements..]
+                .last()
+                .map(|statement| &statement.kind)
+            {
+                Some(&ast::StatementKind::Block(_))
+                | Some(&ast::StatementKind::If { .. })
+                | Some(&ast::StatementKind::Switch { .. })
+                | Some(&ast::StatementKind::Loop { .. }) => {}
+                _ => return Err(Error::InapplicableDiagnosticFilter(span)),
+            }
+            let stmts = block.stmts.drain(num_statements..).collect();
+            block.stmts.push(ast::Statement {
+                kind: ast::StatementKind::DiagnosticScope {
+                    filters: diagnostic_filters.into_filters(),
+                    body: ast::Block { stmts },
+                },
+                span: lexer.span_from(start),
+            });
+        }
+        Ok(())
+    }
+
     fn statement<'a>(
         &mut self,
         lexer: &mut Lexer<'a>,
         ctx: &mut ExpressionContext<'a, '_, '_>,
         block: &mut ast::Block<'a>,
     ) -> Result<(), Error<'a>> {
+        if lexer.peek().0 == Token::Attribute {
+            return self.hinted_statement(lexer, ctx, block);
+        }
+
         self.push_rule_span(Rule::Statement, lexer);
         match lexer.peek() {
             (Token::Separator(';'), _) => {
@@ -1646,6 +1946,13 @@ impl Parser {
 
                         ast::StatementKind::Ignore(expr)
                     }
+                    "const_assert" => {
+                        let _ = lexer.next();
+                        let condition = self.general_expression(lexer, ctx)?;
+                        lexer.expect(Token::Separator(';'))?;
+
+                        ast::StatementKind::ConstAssert(condition)
+                    }
                     "let" => {
                         let _ = lexer.next();
                         let name = lexer.next_ident()?;
@@ -1741,6 +2048,7 @@ impl Parser {
                                 condition: other_cond,
                                 accept: other_block.0,
                                 reject,
+                                hint: None,
                             };
                             reject = ast::Block::default();
                             let span = lexer.span_from(other_span_start);
@@ -1754,6 +2062,7 @@ impl Parser {
                             condition,
                             accept,
                             reject,
+                            hint: None,
                         }
                     }
                     "switch" => {
@@ -1830,6 +2139,7 @@ impl Parser {
                                 condition,
                                 accept: ast::Block::default(),
                                 reject,
+                                hint: None,
                             },
                             span,
                         });
@@ -1844,6 +2154,7 @@ impl Parser {
                             body,
                             continuing: ast::Block::default(),
                             break_if: None,
+                            hint: None,
                         }
                     }
                     "for" => {
@@ -1887,6 +2198,7 @@ impl Parser {
                                     condition,
                                     accept: ast::Block::default(),
                                     reject,
+                                    hint: None,
                                 },
                                 span,
                             });
@@ -1914,6 +2226,7 @@ impl Parser {
                             body,
                             continuing,
                             break_if: None,
+                            hint: None,
                         }
                     }
                     "break" => {
@@ -1975,6 +2288,7 @@ impl Parser {
         ctx.local_table.push_scope();
 
         loop {
+            Self::debug_markers(lexer, &mut body);
             if lexer.skip(Token::Word("continuing")) {
                 // Branch for the `continuing` block, this must be
                 // the last thing in the loop body
@@ -1982,6 +2296,7 @@ impl Parser {
                 // Expect a opening brace to start the continuing block
                 lexer.expect(Token::Paren('{'))?;
                 loop {
+                    Self::debug_markers(lexer, &mut continuing);
                     if lexer.skip(Token::Word("break")) {
                         // Branch for the `break if` statement, this statement
                         // has the form `break if <expr>;` and must be the last
@@ -2032,9 +2347,21 @@ impl Parser {
             body,
             continuing,
             break_if,
+            hint: None,
         })
     }
 
+    /// Add statements to `block` for any debug marker comments that come
+    /// before the next token.
+    fn debug_markers<'a>(lexer: &mut Lexer<'a>, block: &mut ast::Block<'a>) {
+        while let Some((text, span)) = lexer.next_debug_marker() {
+            block.stmts.push(ast::Statement {
+                kind: ast::StatementKind::DebugMarker(text),
+                span,
+            });
+        }
+    }
+
     /// compound_statement
     fn block<'a>(
         &mut self,
@@ -2047,7 +2374,11 @@ impl Parser {
 
         lexer.expect(Token::Paren('{'))?;
         let mut block = ast::Block::default();
-        while !lexer.skip(Token::Paren('}')) {
+        loop {
+            Self::debug_markers(lexer, &mut block);
+            if lexer.skip(Token::Paren('}')) {
+                break;
+            }
             self.statement(lexer, ctx, &mut block)?;
         }
 
@@ -2136,7 +2467,11 @@ impl Parser {
         // do not use `self.block` here, since we must not push a new scope
         lexer.expect(Token::Paren('{'))?;
         let mut body = ast::Block::default();
-        while !lexer.skip(Token::Paren('}')) {
+        loop {
+            Self::debug_markers(lexer, &mut body);
+            if lexer.skip(Token::Paren('}')) {
+                break;
+            }
             self.statement(lexer, &mut ctx, &mut body)?;
         }
 
@@ -2144,6 +2479,7 @@ impl Parser {
 
         let fun = ast::Function {
             entry_point: None,
+            diagnostic_filters: Vec::new(),
             name: fun_name,
             arguments,
             result,
@@ -2168,8 +2504,12 @@ impl Parser {
         let mut compute_span = Span::new(0, 0);
         let mut workgroup_size = ParsedAttribute::default();
         let mut early_depth_test = ParsedAttribute::default();
+        let mut mesh_output = None;
         let (mut bind_index, mut bind_group) =
             (ParsedAttribute::default(), ParsedAttribute::default());
+        let (mut coherent, mut volatile) = (ParsedAttribute::default(), ParsedAttribute::default());
+        let mut diagnostic_filters = DiagnosticFilters::default();
+        let mut diagnostic_span = None;
 
         let mut dependencies = FastIndexSet::default();
         let mut ctx = ExpressionContext {
@@ -2203,6 +2543,38 @@ impl Parser {
                     stage.set(crate::ShaderStage::Compute, name_span)?;
                     compute_span = name_span;
                 }
+                ("task", name_span) => {
+                    self.require_extension(crate::EnableExtensions::NAGA_MESH_SHADER, name_span)?;
+                    stage.set(crate::ShaderStage::Task, name_span)?;
+                    compute_span = name_span;
+                }
+                ("mesh", name_span) => {
+                    self.require_extension(crate::EnableExtensions::NAGA_MESH_SHADER, name_span)?;
+                    stage.set(crate::ShaderStage::Mesh, name_span)?;
+                    compute_span = name_span;
+
+                    lexer.expect(Token::Paren('('))?;
+                    let (ident, ident_span) = lexer.next_ident_with_span()?;
+                    let topology = conv::map_mesh_output_topology(ident, ident_span)?;
+                    lexer.expect(Token::Separator(','))?;
+                    let vertex_type = self.type_decl(lexer, &mut ctx)?;
+                    lexer.expect(Token::Separator(','))?;
+                    let max_vertices = self.general_expression(lexer, &mut ctx)?;
+                    lexer.expect(Token::Separator(','))?;
+                    let primitive_type = self.type_decl(lexer, &mut ctx)?;
+                    lexer.expect(Token::Separator(','))?;
+                    let max_primitives = self.general_expression(lexer, &mut ctx)?;
+                    // Trailing commas are permitted.
+                    let _ = lexer.skip(Token::Separator(','));
+                    lexer.expect(Token::Paren(')'))?;
+                    mesh_output = Some(ast::MeshOutput {
+                        topology,
+                        vertex_type,
+                        max_vertices,
+                        primitive_type,
+                        max_primitives,
+                    });
+                }
                 ("workgroup_size", name_span) => {
                     lexer.expect(Token::Paren('('))?;
                     let mut new_workgroup_size = [None; 3];
@@ -2232,6 +2604,24 @@ impl Parser {
                     };
                     early_depth_test.set(crate::EarlyDepthTest { conservative }, name_span)?;
                 }
+                ("coherent", name_span) => {
+                    self.require_extension(
+                        crate::EnableExtensions::NAGA_MEMORY_DECORATIONS,
+                        name_span,
+                    )?;
+                    coherent.set(name_span, name_span)?;
+                }
+                ("volatile", name_span) => {
+                    self.require_extension(
+                        crate::EnableExtensions::NAGA_MEMORY_DECORATIONS,
+                        name_span,
+                    )?;
+                    volatile.set(name_span, name_span)?;
+                }
+                ("diagnostic", name_span) => {
+                    self.diagnostic_filter(lexer, &mut diagnostic_filters)?;
+                    diagnostic_span.get_or_insert(name_span);
+                }
                 (_, word_span) => return Err(Error::UnknownAttribute(word_span)),
             }
         }
@@ -2283,26 +2673,45 @@ impl Parser {
 
                 Some(ast::GlobalDeclKind::Const(ast::Const { name, ty, init }))
             }
+            (Token::Word("const_assert"), _) => {
+                let condition = self.general_expression(lexer, &mut ctx)?;
+                lexer.expect(Token::Separator(';'))?;
+
+                Some(ast::GlobalDeclKind::ConstAssert(condition))
+            }
             (Token::Word("var"), _) => {
                 let mut var = self.variable_decl(lexer, &mut ctx)?;
                 var.binding = binding.take();
+                if coherent.value.take().is_some() {
+                    var.memory_decorations |= crate::MemoryDecorations::COHERENT;
+                }
+                if volatile.value.take().is_some() {
+                    var.memory_decorations |= crate::MemoryDecorations::VOLATILE;
+                }
                 Some(ast::GlobalDeclKind::Var(var))
             }
             (Token::Word("fn"), _) => {
                 let function = self.function_decl(lexer, out, &mut dependencies)?;
+                diagnostic_span = None;
                 Some(ast::GlobalDeclKind::Fn(ast::Function {
                     entry_point: if let Some(stage) = stage.value {
-                        if stage == ShaderStage::Compute && workgroup_size.value.is_none() {
+                        if matches!(
+                            stage,
+                            ShaderStage::Compute | ShaderStage::Task | ShaderStage::Mesh
+                        ) && workgroup_size.value.is_none()
+                        {
                             return Err(Error::MissingWorkgroupSize(compute_span));
                         }
                         Some(ast::EntryPoint {
                             stage,
                             early_depth_test: early_depth_test.value,
                             workgroup_size: workgroup_size.value,
+                            mesh_output: mesh_output.take(),
                         })
                     } else {
                         None
                     },
+                    diagnostic_filters: std::mem::take(&mut diagnostic_filters).into_filters(),
                     ..function
                 }))
             }
@@ -2310,6 +2719,15 @@ impl Parser {
             other => return Err(Error::Unexpected(other.1, ExpectedToken::GlobalItem)),
         };
 
+        // Memory decorations only apply to variables.
+        if let Some(span) = coherent.value.or(volatile.value) {
+            return Err(Error::InapplicableMemoryDecoration(span));
+        }
+        // Diagnostic filters only apply to functions.
+        if let Some(span) = diagnostic_span {
+            return Err(Error::InapplicableDiagnosticFilter(span));
+        }
+
         if let Some(kind) = kind {
             out.decls.append(
                 ast::GlobalDecl { kind, dependencies },
@@ -2334,6 +2752,23 @@ impl Parser {
 
         let mut lexer = Lexer::new(source);
         let mut tu = ast::TranslationUnit::default();
+        self.collect_aliases(lexer.clone());
+
+        // Directives must precede all global declarations.
+        let mut diagnostic_filters = DiagnosticFilters::default();
+        loop {
+            if lexer.skip(Token::Word("enable")) {
+                self.enable_directive(&mut lexer)?;
+            } else if lexer.skip(Token::Word("diagnostic")) {
+                self.diagnostic_filter(&mut lexer, &mut diagnostic_filters)?;
+                lexer.expect(Token::Separator(';'))?;
+            } else {
+                break;
+            }
+        }
+        tu.enable_extensions = self.enable_extensions;
+        tu.diagnostic_filters = diagnostic_filters.into_filters();
+
         loop {
             match self.global_decl(&mut lexer, &mut tu) {
                 Err(error) => return Err(error),
@@ -543,14 +543,127 @@ fn parse_storage_buffers() {
     .unwrap();
 }
 
+#[test]
+fn parse_const_assert() {
+    let module = parse_str(
+        "
+        const_assert(x < y);
+        const x = 1;
+        const y = 2;
+        const_assert x * 2 == y && true;
+
+        fn f() {
+            const_assert(y > x);
+        }
+        ",
+    )
+    .unwrap();
+
+    // Nothing is left behind in the function body.
+    let (_, f) = module.functions.iter().next().unwrap();
+    assert!(matches!(
+        f.body[..],
+        [crate::Statement::Return { value: None }]
+    ));
+
+    // Nor in the module's constant expressions, apart from the constants'
+    // initializers.
+    assert_eq!(module.const_expressions.len(), 2);
+}
+
 #[test]
 fn parse_alias() {
-    parse_str(
+    let module = parse_str(
         "
         alias Vec4 = vec4<f32>;
         ",
     )
     .unwrap();
+    // Unused aliases still declare their types, so they get validated.
+    let (_, ty) = module.types.iter().next().unwrap();
+    assert_eq!(ty.name.as_deref(), Some("Vec4"));
+
+    let module = parse_str(
+        "
+        fn scale(v: Vec, s: Scalar) -> Vec { return v * s; }
+        alias Vec = vec3<Scalar>;
+        alias Scalar = Float;
+        alias Float = f32;
+        alias Mat = mat3x3<Scalar>;
+        var<workgroup> counter: atomic<Count>;
+        alias Count = u32;
+        ",
+    )
+    .unwrap();
+    crate::valid::Validator::new(Default::default(), Default::default())
+        .validate(&module)
+        .unwrap();
+
+    let scale = &module.functions.iter().next().unwrap().1;
+    let vec = &module.types[scale.arguments[0].ty];
+    assert_eq!(vec.name.as_deref(), Some("Vec"));
+    assert_eq!(
+        vec.inner,
+        crate::TypeInner::Vector {
+            size: crate::VectorSize::Tri,
+            scalar: crate::Scalar::F32,
+        }
+    );
+    assert_eq!(
+        module.types[scale.result.as_ref().unwrap().ty]
+            .name
+            .as_deref(),
+        Some("Vec")
+    );
+    assert_eq!(
+        module.types[scale.arguments[1].ty].inner,
+        crate::TypeInner::Scalar(crate::Scalar::F32)
+    );
+}
+
+#[test]
+fn parse_declarations_in_reverse_order() {
+    // Every declaration is used before it appears.
+    let module = parse_str(
+        "
+        @compute @workgroup_size(1)
+        fn main() {
+            _ = first();
+        }
+        fn first() -> Pair {
+            return second(S(C));
+        }
+        fn second(s: S) -> Pair {
+            return s.pair;
+        }
+        struct S {
+            pair: Pair,
+        }
+        alias Pair = vec2<f32>;
+        const C = Pair(D, D);
+        const D = E * 2.0;
+        const E = 1.0;
+        ",
+    )
+    .unwrap();
+    crate::valid::Validator::new(Default::default(), Default::default())
+        .validate(&module)
+        .unwrap();
+
+    let (_, c) = module
+        .constants
+        .iter()
+        .find(|&(_, c)| c.name.as_deref() == Some("C"))
+        .unwrap();
+    let crate::Expression::Compose { ref components, .. } = module.const_expressions[c.init] else {
+        panic!("`C` should be a vector");
+    };
+    for &component in components {
+        assert_eq!(
+            module.const_expressions[component],
+            crate::Expression::Literal(crate::Literal::F32(2.0))
+        );
+    }
 }
 
 #[test]
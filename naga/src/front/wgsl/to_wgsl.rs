@@ -67,6 +67,10 @@ impl crate::TypeInner {
                 let base = base.to_wgsl(gctx);
                 match size {
                     crate::ArraySize::Constant(size) => format!("array<{base}, {size}>"),
+                    crate::ArraySize::Pending(constant) => {
+                        let size = gctx.constants[constant].name.as_deref().unwrap_or("?");
+                        format!("array<{base}, {size}>")
+                    }
                     crate::ArraySize::Dynamic => format!("array<{base}>"),
                 }
             }
@@ -74,6 +78,10 @@ impl crate::TypeInner {
                 // TODO: Actually output the struct?
                 "struct".to_string()
             }
+            Ti::Image {
+                class: crate::ImageClass::External,
+                ..
+            } => "texture_external".to_string(),
             Ti::Image {
                 dim,
                 arrayed,
@@ -92,7 +100,8 @@ impl crate::TypeInner {
                     crate::ImageClass::Depth { multi: false } => "_depth",
                     crate::ImageClass::Depth { multi: true } => "_depth_multisampled",
                     crate::ImageClass::Sampled { multi: false, .. }
-                    | crate::ImageClass::Storage { .. } => "",
+                    | crate::ImageClass::Storage { .. }
+                    | crate::ImageClass::External => "",
                 };
 
                 let type_in_brackets = match class {
@@ -103,7 +112,9 @@ impl crate::TypeInner {
                         let element_type = crate::Scalar { kind, width: 4 }.to_wgsl();
                         format!("<{element_type}>")
                     }
-                    crate::ImageClass::Depth { multi: _ } => String::new(),
+                    crate::ImageClass::Depth { multi: _ } | crate::ImageClass::External => {
+                        String::new()
+                    }
                     crate::ImageClass::Storage { format, access } => {
                         if access.contains(crate::StorageAccess::STORE) {
                             format!("<{},write>", format.to_wgsl())
@@ -123,6 +134,10 @@ impl crate::TypeInner {
                 let base = member_type.name.as_deref().unwrap_or("unknown");
                 match size {
                     crate::ArraySize::Constant(size) => format!("binding_array<{base}, {size}>"),
+                    crate::ArraySize::Pending(constant) => {
+                        let size = gctx.constants[constant].name.as_deref().unwrap_or("?");
+                        format!("binding_array<{base}, {size}>")
+                    }
                     crate::ArraySize::Dynamic => format!("binding_array<{base}>"),
                 }
             }
@@ -147,6 +162,22 @@ impl crate::Scalar {
     }
 }
 
+impl crate::Literal {
+    /// Format this literal as WGSL source, with the suffix for its type.
+    pub fn to_wgsl(self) -> String {
+        match self {
+            Self::F64(value) => format!("{value:?}lf"),
+            Self::F32(value) => format!("{value:?}f"),
+            Self::U32(value) => format!("{value}u"),
+            Self::I32(value) => format!("{value}i"),
+            Self::I64(value) => format!("{value}li"),
+            Self::Bool(value) => value.to_string(),
+            Self::AbstractInt(value) => value.to_string(),
+            Self::AbstractFloat(value) => format!("{value:?}"),
+        }
+    }
+}
+
 impl crate::StorageFormat {
     pub const fn to_wgsl(self) -> &'static str {
         use crate::StorageFormat as Sf;
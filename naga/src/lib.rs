@@ -281,13 +281,22 @@ pub mod back;
 mod block;
 #[cfg(feature = "compact")]
 pub mod compact;
+pub mod diagnostic_filter;
+pub mod error;
+pub mod external_texture;
 pub mod front;
 pub mod keywords;
+#[cfg(feature = "link")]
+pub mod link;
+#[cfg(feature = "metrics")]
+pub mod metrics;
 pub mod proc;
 mod span;
+#[cfg(feature = "telemetry")]
+mod telemetry;
 pub mod valid;
 
-pub use crate::arena::{Arena, Handle, Range, UniqueArena};
+pub use crate::arena::{Arena, Checkpoint, Handle, Range, Speculation, UniqueArena};
 
 pub use crate::span::{SourceLocation, Span, SpanContext, WithSpan};
 #[cfg(feature = "arbitrary")]
@@ -384,6 +393,10 @@ pub enum ShaderStage {
     Vertex,
     Fragment,
     Compute,
+    /// Launches mesh shader workgroups. See [`BuiltIn::MeshTaskSize`].
+    Task,
+    /// Produces vertices and primitives. See [`MeshStageInfo`].
+    Mesh,
 }
 
 /// Addressing space of variables.
@@ -438,6 +451,19 @@ pub enum BuiltIn {
     WorkGroupId,
     WorkGroupSize,
     NumWorkGroups,
+    // task
+    // The number of mesh workgroups a task shader launches, as a `vec3<u32>`
+    // returned from the entry point.
+    MeshTaskSize,
+    // mesh primitive outputs
+    // The vertex indices of a triangle, as a `vec3<u32>`.
+    TriangleIndices,
+    // The vertex indices of a line, as a `vec2<u32>`.
+    LineIndices,
+    // The vertex index of a point, as a `u32`.
+    PointIndex,
+    // Whether to discard the primitive, as a `bool`.
+    CullPrimitive,
 }
 
 /// Number of bytes per scalar.
@@ -511,10 +537,51 @@ pub struct Scalar {
 pub enum ArraySize {
     /// The array size is constant.
     Constant(std::num::NonZeroU32),
+    /// The array size is the value of an override [`Constant`], known at
+    /// pipeline creation time.
+    ///
+    /// The constant must have an integer scalar type, and its [`override`]
+    /// must not be [`None`]. Arrays of this size may only be used as the
+    /// type of a [`WorkGroup`] global variable.
+    ///
+    /// [`override`]: Constant::override
+    /// [`None`]: Override::None
+    /// [`WorkGroup`]: AddressSpace::WorkGroup
+    Pending(Handle<Constant>),
     /// The array size can change at runtime.
     Dynamic,
 }
 
+/// A hint about how to implement a [`Statement::Loop`].
+///
+/// Hints never change what a shader does, and backends with no way to
+/// express them ignore them.
+#[derive(Clone, Copy, Debug, Hash, Eq, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "deserialize", derive(Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+pub enum LoopHint {
+    /// Unroll the loop. HLSL's `[unroll]`.
+    Unroll,
+    /// Keep the loop as a loop, rather than unrolling it. HLSL's `[loop]`.
+    DontUnroll,
+}
+
+/// A hint about how to implement a [`Statement::If`].
+///
+/// Hints never change what a shader does, and backends with no way to
+/// express them ignore them.
+#[derive(Clone, Copy, Debug, Hash, Eq, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "deserialize", derive(Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+pub enum BranchHint {
+    /// Branch, executing only the chosen block. HLSL's `[branch]`.
+    Branch,
+    /// Execute both blocks and select the results. HLSL's `[flatten]`.
+    Flatten,
+}
+
 /// The interpolation qualifier of a binding or struct field.
 #[derive(Clone, Copy, Debug, Hash, Eq, Ord, PartialEq, PartialOrd)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
@@ -684,6 +751,22 @@ pub enum ImageClass {
         format: StorageFormat,
         access: StorageAccess,
     },
+    /// External image, like a video frame, sampled as `vec4<f32>`.
+    ///
+    /// An external image may be stored as several planes that need color
+    /// conversion, so only a few operations are permitted on it:
+    /// [`ImageSample`] at [`SampleLevel::Zero`], which clamps the coordinates
+    /// to the edge of the image, [`ImageLoad`] without a level, and
+    /// [`ImageQuery::Size`] without a level. External images are always
+    /// two-dimensional and never arrayed.
+    ///
+    /// Backends don't support external images directly: use
+    /// [`external_texture::lower`] to rewrite them into ordinary images first.
+    ///
+    /// [`ImageSample`]: Expression::ImageSample
+    /// [`ImageLoad`]: Expression::ImageLoad
+    /// [`external_texture::lower`]: crate::external_texture::lower
+    External,
 }
 
 /// A data type declared in the module.
@@ -974,6 +1057,26 @@ pub struct ResourceBinding {
     pub binding: u32,
 }
 
+bitflags::bitflags! {
+    /// Memory decorations of a [`GlobalVariable`].
+    ///
+    /// These matter to algorithms that communicate between workgroups
+    /// through storage buffers: without them, an invocation may not see
+    /// values that invocations in other workgroups have stored.
+    #[cfg_attr(feature = "serialize", derive(Serialize))]
+    #[cfg_attr(feature = "deserialize", derive(Deserialize))]
+    #[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+    #[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+    pub struct MemoryDecorations: u8 {
+        /// Stores are made visible to, and loads see the stores of, all
+        /// invocations on the device, not just those in the same workgroup.
+        const COHERENT = 0x1;
+        /// Every access reaches memory: loads and stores are not cached,
+        /// combined, or eliminated.
+        const VOLATILE = 0x2;
+    }
+}
+
 /// Variable defined at module level.
 #[derive(Clone, Debug, PartialEq)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
@@ -992,6 +1095,13 @@ pub struct GlobalVariable {
     ///
     /// Expression handle lives in const_expressions
     pub init: Option<Handle<Expression>>,
+    /// How accesses to this variable interact with other invocations.
+    ///
+    /// Only variables in the [`Storage`] address space may have memory
+    /// decorations.
+    ///
+    /// [`Storage`]: AddressSpace::Storage
+    pub memory_decorations: MemoryDecorations,
 }
 
 /// Variable defined at function level.
@@ -1176,6 +1286,8 @@ pub enum MathFunction {
     // decomposition
     Ceil,
     Floor,
+    /// Round to the nearest integer, with halfway cases rounded to the nearest
+    /// even integer.
     Round,
     Fract,
     Trunc,
@@ -1190,6 +1302,8 @@ pub enum MathFunction {
     Pow,
     // geometry
     Dot,
+    Dot4I8Packed,
+    Dot4U8Packed,
     Outer,
     Cross,
     Distance,
@@ -1209,6 +1323,7 @@ pub enum MathFunction {
     Inverse,
     Transpose,
     Determinant,
+    QuantizeToF16,
     // bits
     CountTrailingZeros,
     CountLeadingZeros,
@@ -1425,6 +1540,9 @@ pub enum Expression {
     ///
     /// For [`TypeInner::Atomic`] the result is a corresponding scalar.
     /// For other types behind the `pointer<T>`, the result is `T`.
+    ///
+    /// A load through a pointer to an atomic is WGSL's `atomicLoad`, and
+    /// backends must write it as an atomic operation.
     Load { pointer: Handle<Expression> },
     /// Sample a point from a sampled or a depth image.
     ImageSample {
@@ -1610,7 +1728,7 @@ pub enum SwitchValue {
 
 /// A case for a switch statement.
 // Clone is used only for error reporting and is not intended for end users
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 #[cfg_attr(feature = "deserialize", derive(Deserialize))]
 #[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
@@ -1628,7 +1746,7 @@ pub struct SwitchCase {
 ///
 /// [`RayQuery` statement]: Statement::RayQuery
 /// [`query`]: Statement::RayQuery::query
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 #[cfg_attr(feature = "deserialize", derive(Deserialize))]
 #[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
@@ -1669,7 +1787,7 @@ pub enum RayQueryFunction {
 //TODO: consider removing `Clone`. It's not valid to clone `Statement::Emit` anyway.
 /// Instructions which make up an executable block.
 // Clone is used only for error reporting and is not intended for end users
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 #[cfg_attr(feature = "deserialize", derive(Deserialize))]
 #[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
@@ -1683,10 +1801,13 @@ pub enum Statement {
     /// A block containing more statements, to be executed sequentially.
     Block(Block),
     /// Conditionally executes one of two blocks, based on the value of the condition.
+    ///
+    /// If present, `hint` suggests how backends should implement the choice.
     If {
         condition: Handle<Expression>, //bool
         accept: Block,
         reject: Block,
+        hint: Option<BranchHint>,
     },
     /// Conditionally executes one of multiple blocks, based on the value of the selector.
     ///
@@ -1736,6 +1857,8 @@ pub enum Statement {
     /// if" statement in WGSL, or a loop whose back edge is an
     /// `OpBranchConditional` instruction in SPIR-V.
     ///
+    /// If present, `hint` suggests whether backends should unroll the loop.
+    ///
     /// [`Break`]: Statement::Break
     /// [`Continue`]: Statement::Continue
     /// [`Kill`]: Statement::Kill
@@ -1745,6 +1868,7 @@ pub enum Statement {
         body: Block,
         continuing: Block,
         break_if: Option<Handle<Expression>>,
+        hint: Option<LoopHint>,
     },
 
     /// Exits the innermost enclosing [`Loop`] or [`Switch`].
@@ -1792,6 +1916,9 @@ pub enum Statement {
     /// has to be a corresponding scalar.
     /// For other types behind the `pointer<T>`, the value is `T`.
     ///
+    /// A store through a pointer to an atomic is WGSL's `atomicStore`, and
+    /// backends must write it as an atomic operation.
+    ///
     /// This statement is a barrier for any operations on the
     /// `Expression::LocalVariable` or `Expression::GlobalVariable`
     /// that is the destination of an access chain, started
@@ -1865,6 +1992,53 @@ pub enum Statement {
         /// The specific operation we're performing on `query`.
         fun: RayQueryFunction,
     },
+    /// Writes the outputs of a [`Mesh`] entry point.
+    ///
+    /// These may only appear in mesh entry points and the functions they call.
+    ///
+    /// [`Mesh`]: ShaderStage::Mesh
+    MeshFunction(MeshFunction),
+    /// Marks a point in the code, to help find it in the generated output.
+    ///
+    /// This has no effect on execution. Textual backends write the text as a
+    /// comment, and the SPIR-V backend ignores it. The WGSL frontend produces
+    /// these from line comments starting with `//@marker`.
+    DebugMarker(String),
+}
+
+/// An operation on the outputs of a mesh shader.
+///
+/// See [`Statement::MeshFunction`] and [`MeshStageInfo`].
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "deserialize", derive(Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+pub enum MeshFunction {
+    /// Set how many vertices and primitives the workgroup produces.
+    ///
+    /// Both counts are `u32` values, no greater than the entry point's
+    /// [`max_vertices`] and [`max_primitives`].
+    ///
+    /// [`max_vertices`]: MeshStageInfo::max_vertices
+    /// [`max_primitives`]: MeshStageInfo::max_primitives
+    SetMeshOutputs {
+        vertex_count: Handle<Expression>,
+        primitive_count: Handle<Expression>,
+    },
+    /// Store `value`, of the [`vertex_output_type`], as vertex `index`.
+    ///
+    /// [`vertex_output_type`]: MeshStageInfo::vertex_output_type
+    SetVertex {
+        index: Handle<Expression>,
+        value: Handle<Expression>,
+    },
+    /// Store `value`, of the [`primitive_output_type`], as primitive `index`.
+    ///
+    /// [`primitive_output_type`]: MeshStageInfo::primitive_output_type
+    SetPrimitive {
+        index: Handle<Expression>,
+        value: Handle<Expression>,
+    },
 }
 
 /// A function argument.
@@ -1900,7 +2074,6 @@ pub struct FunctionResult {
 #[cfg_attr(feature = "clone", derive(Clone))]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 #[cfg_attr(feature = "deserialize", derive(Deserialize))]
-#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
 pub struct Function {
     /// Name of the function, if any.
     pub name: Option<String>,
@@ -1919,6 +2092,25 @@ pub struct Function {
     pub named_expressions: NamedExpressions,
     /// Block of instructions comprising the body of the function.
     pub body: Block,
+    /// The innermost diagnostic filter that applies to the whole body of this
+    /// function, or `None` if no filter does.
+    ///
+    /// This includes the module's filters; see the [`diagnostic_filter`]
+    /// module for details.
+    pub diagnostic_filter_leaf: Option<Handle<diagnostic_filter::DiagnosticFilterNode>>,
+    /// Diagnostic filters that apply to individual statements of this
+    /// function.
+    ///
+    /// Each entry pairs the innermost filter for a statement with the
+    /// [`expressions`] the statement evaluates. Nested statements' entries
+    /// come first, so the first entry whose range contains an expression
+    /// holds the filter that applies to it.
+    ///
+    /// [`expressions`]: Function::expressions
+    pub diagnostic_filter_ranges: Vec<(
+        Range<Expression>,
+        Handle<diagnostic_filter::DiagnosticFilterNode>,
+    )>,
 }
 
 /// The main function for a pipeline stage.
@@ -1977,10 +2169,62 @@ pub struct EntryPoint {
     pub early_depth_test: Option<EarlyDepthTest>,
     /// Workgroup size for compute stages
     pub workgroup_size: [u32; 3],
+    /// Override [`Constant`]s giving the workgroup size, known only at
+    /// pipeline creation time.
+    ///
+    /// Where an element is `Some`, the override's value replaces the
+    /// matching element of [`workgroup_size`], which is then ignored.
+    ///
+    /// [`workgroup_size`]: EntryPoint::workgroup_size
+    pub workgroup_size_overrides: Option<[Option<Handle<Constant>>; 3]>,
+    /// The outputs of a [`Mesh`] stage, which must have this set.
+    ///
+    /// [`Mesh`]: ShaderStage::Mesh
+    pub mesh_info: Option<MeshStageInfo>,
     /// The entrance function.
     pub function: Function,
 }
 
+/// The kind of primitive a mesh shader produces.
+#[derive(Clone, Copy, Debug, Hash, Eq, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "deserialize", derive(Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+pub enum MeshOutputTopology {
+    Points,
+    Lines,
+    Triangles,
+}
+
+/// The output arrays of a [`Mesh`] entry point.
+///
+/// Instead of returning its outputs, a mesh shader fills in arrays of
+/// vertices and primitives with [`MeshFunction`] statements.
+///
+/// [`Mesh`]: ShaderStage::Mesh
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "deserialize", derive(Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+pub struct MeshStageInfo {
+    /// The kind of primitive produced.
+    pub topology: MeshOutputTopology,
+    /// The size of the vertex array.
+    pub max_vertices: u32,
+    /// The size of the primitive array.
+    pub max_primitives: u32,
+    /// The type of each vertex: a struct whose members all have [`Binding`]s,
+    /// like the result of a vertex shader.
+    pub vertex_output_type: Handle<Type>,
+    /// The type of each primitive: a struct whose members all have
+    /// [`Binding`]s, including the vertex indices built-in that matches
+    /// `topology`, such as [`BuiltIn::TriangleIndices`]. Its [`Location`]s
+    /// are per-primitive values.
+    ///
+    /// [`Location`]: Binding::Location
+    pub primitive_output_type: Handle<Type>,
+}
+
 /// Return types predeclared for the frexp, modf, and atomicCompareExchangeWeak built-in functions.
 ///
 /// These cannot be spelled in WGSL source.
@@ -2029,6 +2273,39 @@ pub struct SpecialTypes {
     pub predeclared_types: FastIndexMap<PredeclaredType, Handle<Type>>,
 }
 
+bitflags::bitflags! {
+    /// Optional shading language features that a module's source explicitly
+    /// asked for.
+    ///
+    /// These correspond to WGSL's `enable` directives. Front ends for other
+    /// languages leave this empty.
+    #[cfg_attr(feature = "serialize", derive(Serialize))]
+    #[cfg_attr(feature = "deserialize", derive(Deserialize))]
+    #[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+    #[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+    pub struct EnableExtensions: u32 {
+        /// The `f16` extension: 16-bit floating-point types.
+        const F16 = 0x1;
+        /// The `dual_source_blending` extension: the `@second_blend_source`
+        /// attribute.
+        const DUAL_SOURCE_BLENDING = 0x2;
+        /// The `clip_distances` extension: the `clip_distances` built-in.
+        const CLIP_DISTANCES = 0x4;
+        /// The `naga_control_flow_hints` extension, specific to Naga: the
+        /// `@unroll`, `@dont_unroll`, `@branch` and `@flatten` attributes on
+        /// loops and `if` statements. See [`LoopHint`] and [`BranchHint`].
+        const NAGA_CONTROL_FLOW_HINTS = 0x8;
+        /// The `naga_memory_decorations` extension, specific to Naga: the
+        /// `@coherent` and `@volatile` attributes on global variables. See
+        /// [`MemoryDecorations`].
+        const NAGA_MEMORY_DECORATIONS = 0x10;
+        /// The `naga_mesh_shader` extension, specific to Naga: `@task` and
+        /// `@mesh` entry points and their built-ins. See
+        /// [`ShaderStage::Mesh`].
+        const NAGA_MESH_SHADER = 0x20;
+    }
+}
+
 /// Shader module.
 ///
 /// A module is a set of constants, global variables and functions, as well as
@@ -2044,7 +2321,6 @@ pub struct SpecialTypes {
 #[cfg_attr(feature = "clone", derive(Clone))]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 #[cfg_attr(feature = "deserialize", derive(Deserialize))]
-#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
 pub struct Module {
     /// Arena for the types defined in this module.
     pub types: UniqueArena<Type>,
@@ -2069,4 +2345,77 @@ pub struct Module {
     pub functions: Arena<Function>,
     /// Entry points.
     pub entry_points: Vec<EntryPoint>,
+    /// Extensions enabled by the module's source.
+    pub enable_extensions: EnableExtensions,
+    /// Arena for the diagnostic filters of this module and its functions.
+    ///
+    /// Each node's parent must precede it in the arena.
+    pub diagnostic_filters: Arena<diagnostic_filter::DiagnosticFilterNode>,
+    /// The innermost diagnostic filter that applies to the whole module, or
+    /// `None` if no filter does.
+    pub diagnostic_filter_leaf: Option<Handle<diagnostic_filter::DiagnosticFilterNode>>,
+}
+
+// `Function` and `Module` implement `Arbitrary` by hand, to reserve room for
+// handles to arenas that are generated after the items that refer to them.
+// See `arena::arbitrary_handles` for details.
+
+#[cfg(feature = "arbitrary")]
+impl<'a> Arbitrary<'a> for Function {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let name = u.arbitrary()?;
+        let arguments = u.arbitrary()?;
+        let result = u.arbitrary()?;
+        // Local variable initializers refer to `expressions`.
+        let expression_count = u.arbitrary_len::<Expression>()?;
+        arena::arbitrary_handles::set_len::<Expression>(expression_count);
+        let local_variables = u.arbitrary()?;
+        let expressions = Arena::arbitrary_with_len(u, expression_count)?;
+        Ok(Self {
+            name,
+            arguments,
+            result,
+            local_variables,
+            expressions,
+            named_expressions: u.arbitrary()?,
+            body: u.arbitrary()?,
+            diagnostic_filter_leaf: u.arbitrary()?,
+            diagnostic_filter_ranges: u.arbitrary()?,
+        })
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> Arbitrary<'a> for Module {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        arena::arbitrary_handles::reset();
+        // Array types may be sized by `constants`.
+        let constant_count = u.arbitrary_len::<Constant>()?;
+        arena::arbitrary_handles::set_len::<Constant>(constant_count);
+        let types = u.arbitrary()?;
+        let special_types = u.arbitrary()?;
+        // Constant and global variable initializers refer to `const_expressions`.
+        let const_expression_count = u.arbitrary_len::<Expression>()?;
+        arena::arbitrary_handles::set_len::<Expression>(const_expression_count);
+        let constants = Arena::arbitrary_with_len(u, constant_count)?;
+        let global_variables = u.arbitrary()?;
+        // Constant expressions can't refer to functions or their locals.
+        arena::arbitrary_handles::set_len::<Function>(0);
+        arena::arbitrary_handles::set_len::<LocalVariable>(0);
+        let const_expressions = Arena::arbitrary_with_len(u, const_expression_count)?;
+        // Functions refer to `diagnostic_filters`.
+        let diagnostic_filters = u.arbitrary()?;
+        Ok(Self {
+            types,
+            special_types,
+            constants,
+            global_variables,
+            const_expressions,
+            functions: u.arbitrary()?,
+            entry_points: u.arbitrary()?,
+            enable_extensions: u.arbitrary()?,
+            diagnostic_filters,
+            diagnostic_filter_leaf: u.arbitrary()?,
+        })
+    }
 }
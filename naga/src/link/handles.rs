@@ -0,0 +1,108 @@
+//! Rewriting `src` handles in items moved into `dst`.
+
+use super::HandleRemap;
+use crate::proc::for_each_operand_mut;
+use crate::{Block, Expression, Function, Statement, TypeInner};
+
+impl HandleRemap {
+    /// Fix up the handles in `inner` to refer to `dst`'s types.
+    pub(super) fn adjust_type(&self, inner: &mut TypeInner) {
+        match *inner {
+            TypeInner::Pointer { ref mut base, .. }
+            | TypeInner::Array { ref mut base, .. }
+            | TypeInner::BindingArray { ref mut base, .. } => *base = self.ty(*base),
+            TypeInner::Struct {
+                ref mut members, ..
+            } => {
+                for member in members {
+                    member.ty = self.ty(member.ty);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Fix up the handles in `expr` to refer to `dst`'s module-scope items.
+    ///
+    /// If `expr` is a constant expression, `is_const` should be true, and
+    /// its operands are adjusted as well. Otherwise, `expr` belongs to a
+    /// function that is moved into `dst` whole, so its operands are left
+    /// alone.
+    pub(super) fn adjust_expression(&self, expr: &mut Expression, is_const: bool) {
+        if is_const {
+            for_each_operand_mut(expr, |operand| *operand = self.const_expression(*operand));
+        }
+
+        use crate::Expression as Ex;
+        match *expr {
+            Ex::Constant(ref mut constant) => *constant = self.constant(*constant),
+            Ex::ZeroValue(ref mut ty)
+            | Ex::Compose { ref mut ty, .. }
+            | Ex::AtomicResult { ref mut ty, .. }
+            | Ex::WorkGroupUniformLoadResult { ref mut ty } => *ty = self.ty(*ty),
+            Ex::GlobalVariable(ref mut global) => *global = self.global_variable(*global),
+            Ex::CallResult(ref mut function) => *function = self.function(*function),
+            Ex::ImageSample {
+                offset: Some(ref mut offset),
+                ..
+            } => *offset = self.const_expression(*offset),
+            _ => {}
+        }
+    }
+
+    /// Fix up the handles in `function` to refer to `dst`'s module-scope items.
+    pub(super) fn adjust_function(&self, function: &mut Function) {
+        for argument in function.arguments.iter_mut() {
+            argument.ty = self.ty(argument.ty);
+        }
+        if let Some(ref mut result) = function.result {
+            result.ty = self.ty(result.ty);
+        }
+        for (_, local) in function.local_variables.iter_mut() {
+            local.ty = self.ty(local.ty);
+        }
+        for (_, expr) in function.expressions.iter_mut() {
+            self.adjust_expression(expr, false);
+        }
+        self.adjust_block(&mut function.body);
+        if let Some(ref mut leaf) = function.diagnostic_filter_leaf {
+            *leaf = self.diagnostic_filter(*leaf);
+        }
+        for &mut (_, ref mut filter) in function.diagnostic_filter_ranges.iter_mut() {
+            *filter = self.diagnostic_filter(*filter);
+        }
+    }
+
+    fn adjust_block(&self, block: &mut Block) {
+        for statement in block.iter_mut() {
+            match *statement {
+                Statement::Block(ref mut block) => self.adjust_block(block),
+                Statement::If {
+                    ref mut accept,
+                    ref mut reject,
+                    ..
+                } => {
+                    self.adjust_block(accept);
+                    self.adjust_block(reject);
+                }
+                Statement::Switch { ref mut cases, .. } => {
+                    for case in cases {
+                        self.adjust_block(&mut case.body);
+                    }
+                }
+                Statement::Loop {
+                    ref mut body,
+                    ref mut continuing,
+                    ..
+                } => {
+                    self.adjust_block(body);
+                    self.adjust_block(continuing);
+                }
+                Statement::Call {
+                    ref mut function, ..
+                } => *function = self.function(*function),
+                _ => {}
+            }
+        }
+    }
+}
@@ -0,0 +1,464 @@
+/*!
+Combining separately parsed modules into one.
+
+[`merge`] moves the contents of one [`Module`] into another. Types are
+interned, so structurally identical types are shared, and named constants
+and global variables that both modules define identically are merged into
+a single definition. Definitions that share a name but differ, and globals
+with different names bound to the same resource, are reported as a
+[`LinkError`].
+
+Naga has no way to declare a function without defining it, so a module
+can't call a function that only another module defines. Instead, parse
+the shared source together with each module that uses it, and merge the
+results with [`Options::deduplicate_functions`] set, so that only one copy
+of each shared function survives.
+*/
+
+mod handles;
+
+use crate::{
+    arena::Arena, diagnostic_filter::DiagnosticFilterNode, Constant, EntryPoint, Expression,
+    FastHashMap, Function, GlobalVariable, Handle, Module, ResourceBinding, ShaderStage, Span,
+    Type,
+};
+
+/// Options for [`merge_with_options`].
+#[derive(Clone, Debug, Default)]
+pub struct Options {
+    /// Merge functions that both modules define identically into a single
+    /// function, instead of keeping both copies.
+    pub deduplicate_functions: bool,
+}
+
+/// An error produced by [`merge`].
+#[derive(Clone, Debug, PartialEq, thiserror::Error)]
+pub enum LinkError {
+    #[error("Type `{0}` is defined differently by each module")]
+    ConflictingType(String),
+    #[error("Constant `{0}` is defined differently by each module")]
+    ConflictingConstant(String),
+    #[error(
+        "Global variable `{0}` has a different type, address space, binding or initializer in each module"
+    )]
+    ConflictingGlobalVariable(String),
+    #[error(
+        "Global variables `{first}` and `{second}` are both bound to @group({}) @binding({})",
+        binding.group,
+        binding.binding
+    )]
+    ConflictingBinding {
+        first: String,
+        second: String,
+        binding: ResourceBinding,
+    },
+    #[error("Function `{0}` is defined differently by each module")]
+    ConflictingFunction(String),
+    #[error("Both modules have a {stage:?} entry point named `{name}`")]
+    ConflictingEntryPoint { name: String, stage: ShaderStage },
+}
+
+/// The handles in the destination module of everything [`merge`] took from
+/// the source module.
+///
+/// Each method maps a handle into the source module's arenas to the handle
+/// of the corresponding item in the destination module, which is either a
+/// newly added copy or an identical definition that was already present.
+#[derive(Debug, Default)]
+pub struct HandleRemap {
+    types: Vec<Handle<Type>>,
+    constants: Vec<Handle<Constant>>,
+    const_expressions: Vec<Handle<Expression>>,
+    global_variables: Vec<Handle<GlobalVariable>>,
+    functions: Vec<Handle<Function>>,
+    diagnostic_filters: Vec<Handle<DiagnosticFilterNode>>,
+}
+
+impl HandleRemap {
+    pub fn ty(&self, handle: Handle<Type>) -> Handle<Type> {
+        self.types[handle.index()]
+    }
+
+    pub fn constant(&self, handle: Handle<Constant>) -> Handle<Constant> {
+        self.constants[handle.index()]
+    }
+
+    pub fn const_expression(&self, handle: Handle<Expression>) -> Handle<Expression> {
+        self.const_expressions[handle.index()]
+    }
+
+    pub fn global_variable(&self, handle: Handle<GlobalVariable>) -> Handle<GlobalVariable> {
+        self.global_variables[handle.index()]
+    }
+
+    pub fn function(&self, handle: Handle<Function>) -> Handle<Function> {
+        self.functions[handle.index()]
+    }
+
+    pub fn diagnostic_filter(
+        &self,
+        handle: Handle<DiagnosticFilterNode>,
+    ) -> Handle<DiagnosticFilterNode> {
+        self.diagnostic_filters[handle.index()]
+    }
+}
+
+/// Move the contents of `src` into `dst`, using default [`Options`].
+///
+/// See [`merge_with_options`].
+pub fn merge(dst: &mut Module, src: Module) -> Result<HandleRemap, LinkError> {
+    merge_with_options(dst, src, &Options::default())
+}
+
+/// Move the contents of `src` into `dst`.
+///
+/// Return the handles in `dst` of the items taken from `src`. Entry points
+/// from both modules are kept.
+///
+/// If `src` and `dst` have a type, constant, global variable or function
+/// with the same name but different definitions, or entry points with the
+/// same name and stage, return an error. In that case, `dst` may already
+/// hold some of `src`'s items, and should be discarded.
+///
+/// If both modules are valid, and this succeeds, `dst` is valid.
+pub fn merge_with_options(
+    dst: &mut Module,
+    src: Module,
+    options: &Options,
+) -> Result<HandleRemap, LinkError> {
+    let Module {
+        types,
+        special_types,
+        constants,
+        global_variables,
+        const_expressions,
+        functions,
+        entry_points,
+        enable_extensions,
+        diagnostic_filters,
+        diagnostic_filter_leaf,
+    } = src;
+    let mut remap = HandleRemap::default();
+
+    let named_types: FastHashMap<String, Handle<Type>> = dst
+        .types
+        .iter()
+        .filter_map(|(handle, ty)| Some((ty.name.clone()?, handle)))
+        .collect();
+    for (handle, ty) in types.iter() {
+        let mut ty = ty.clone();
+        remap.adjust_type(&mut ty.inner);
+        let new_handle = dst.types.insert(ty, types.get_span(handle));
+        if let Some(ref name) = types[handle].name {
+            if named_types
+                .get(name)
+                .is_some_and(|&existing| existing != new_handle)
+            {
+                return Err(LinkError::ConflictingType(name.clone()));
+            }
+        }
+        remap.types.push(new_handle);
+    }
+
+    if dst.special_types.ray_desc.is_none() {
+        dst.special_types.ray_desc = special_types.ray_desc.map(|ty| remap.ty(ty));
+    }
+    if dst.special_types.ray_intersection.is_none() {
+        dst.special_types.ray_intersection = special_types.ray_intersection.map(|ty| remap.ty(ty));
+    }
+    for (predeclared, ty) in special_types.predeclared_types {
+        dst.special_types
+            .predeclared_types
+            .entry(predeclared)
+            .or_insert_with(|| remap.ty(ty));
+    }
+
+    // Constants and constant expressions refer to each other, so they have
+    // to be merged together. A constant's initializer always precedes any
+    // expression that refers to the constant, so merge each constant just
+    // before its first use.
+    let mut constant_merger = ConstantMerger {
+        named: dst
+            .constants
+            .iter()
+            .filter_map(|(handle, constant)| Some((constant.name.clone()?, handle)))
+            .collect(),
+        src: &constants,
+        merged: vec![false; constants.len()],
+    };
+    // Until a constant is merged, nothing refers to it, so its handle in
+    // `dst` doesn't matter.
+    remap.constants = vec![Handle::new(std::num::NonZeroU32::MIN); constants.len()];
+    for (handle, expr) in const_expressions.iter() {
+        if let Expression::Constant(constant) = *expr {
+            constant_merger.merge(constant, dst, &mut remap)?;
+        }
+        let mut expr = expr.clone();
+        remap.adjust_expression(&mut expr, true);
+        let new_handle = dst
+            .const_expressions
+            .append(expr, const_expressions.get_span(handle));
+        remap.const_expressions.push(new_handle);
+    }
+    for (handle, _) in constants.iter() {
+        constant_merger.merge(handle, dst, &mut remap)?;
+    }
+
+    let named_globals: FastHashMap<String, Handle<GlobalVariable>> = dst
+        .global_variables
+        .iter()
+        .filter_map(|(handle, global)| Some((global.name.clone()?, handle)))
+        .collect();
+    let bound_globals: FastHashMap<ResourceBinding, Handle<GlobalVariable>> = dst
+        .global_variables
+        .iter()
+        .filter_map(|(handle, global)| Some((global.binding.clone()?, handle)))
+        .collect();
+    for (handle, global) in global_variables.iter() {
+        let mut global = global.clone();
+        global.ty = remap.ty(global.ty);
+        global.init = global.init.map(|init| remap.const_expression(init));
+        let existing = global
+            .name
+            .as_ref()
+            .and_then(|name| named_globals.get(name).copied());
+        let new_handle = match existing {
+            Some(existing) => {
+                if !same_global_variable(dst, &dst.global_variables[existing], &global) {
+                    return Err(LinkError::ConflictingGlobalVariable(global.name.unwrap()));
+                }
+                existing
+            }
+            None => {
+                let bound = global
+                    .binding
+                    .as_ref()
+                    .and_then(|binding| bound_globals.get(binding).copied());
+                if let Some(bound) = bound {
+                    return Err(LinkError::ConflictingBinding {
+                        first: dst.global_variables[bound].name.clone().unwrap_or_default(),
+                        second: global.name.unwrap_or_default(),
+                        binding: global.binding.unwrap(),
+                    });
+                }
+                dst.global_variables
+                    .append(global, global_variables.get_span(handle))
+            }
+        };
+        remap.global_variables.push(new_handle);
+    }
+
+    // Share identical filter chains, so that functions both modules define
+    // identically still compare equal.
+    for (handle, node) in diagnostic_filters.iter() {
+        let mut node = node.clone();
+        node.parent = node.parent.map(|parent| remap.diagnostic_filter(parent));
+        let existing = dst
+            .diagnostic_filters
+            .iter()
+            .find(|&(_, other)| other.inner == node.inner && other.parent == node.parent)
+            .map(|(existing, _)| existing);
+        let new_handle = existing.unwrap_or_else(|| {
+            dst.diagnostic_filters
+                .append(node, diagnostic_filters.get_span(handle))
+        });
+        remap.diagnostic_filters.push(new_handle);
+    }
+    if dst.diagnostic_filter_leaf.is_none() {
+        dst.diagnostic_filter_leaf =
+            diagnostic_filter_leaf.map(|leaf| remap.diagnostic_filter(leaf));
+    }
+
+    let named_functions: FastHashMap<String, Handle<Function>> = dst
+        .functions
+        .iter()
+        .filter_map(|(handle, function)| Some((function.name.clone()?, handle)))
+        .collect();
+    for (mut function, span) in into_iter_with_spans(functions) {
+        // Functions only call functions that precede them, which have
+        // already been remapped.
+        remap.adjust_function(&mut function);
+        let existing = function
+            .name
+            .as_ref()
+            .and_then(|name| named_functions.get(name).copied());
+        let new_handle = match existing {
+            Some(existing) => {
+                if !same_function(&dst.functions[existing], &function) {
+                    return Err(LinkError::ConflictingFunction(function.name.unwrap()));
+                }
+                if options.deduplicate_functions {
+                    existing
+                } else {
+                    dst.functions.append(function, span)
+                }
+            }
+            None => dst.functions.append(function, span),
+        };
+        remap.functions.push(new_handle);
+    }
+
+    for mut entry_point in entry_points {
+        if dst
+            .entry_points
+            .iter()
+            .any(|ep| ep.name == entry_point.name && ep.stage == entry_point.stage)
+        {
+            let EntryPoint { name, stage, .. } = entry_point;
+            return Err(LinkError::ConflictingEntryPoint { name, stage });
+        }
+        for constant in entry_point
+            .workgroup_size_overrides
+            .iter_mut()
+            .flatten()
+            .flatten()
+        {
+            *constant = remap.constant(*constant);
+        }
+        remap.adjust_function(&mut entry_point.function);
+        dst.entry_points.push(entry_point);
+    }
+
+    dst.enable_extensions |= enable_extensions;
+
+    Ok(remap)
+}
+
+/// State for merging the source module's constants on demand.
+struct ConstantMerger<'a> {
+    /// The destination module's named constants.
+    named: FastHashMap<String, Handle<Constant>>,
+
+    /// The source module's constants.
+    src: &'a Arena<Constant>,
+
+    /// Whether each source constant has been merged yet.
+    merged: Vec<bool>,
+}
+
+impl ConstantMerger<'_> {
+    /// Merge the source constant `handle` into `dst`, if we haven't already.
+    fn merge(
+        &mut self,
+        handle: Handle<Constant>,
+        dst: &mut Module,
+        remap: &mut HandleRemap,
+    ) -> Result<(), LinkError> {
+        if std::mem::replace(&mut self.merged[handle.index()], true) {
+            return Ok(());
+        }
+
+        let mut constant = self.src[handle].clone();
+        constant.ty = remap.ty(constant.ty);
+        constant.init = remap.const_expression(constant.init);
+        let existing = constant
+            .name
+            .as_ref()
+            .and_then(|name| self.named.get(name).copied());
+        remap.constants[handle.index()] = match existing {
+            Some(existing) => {
+                if !same_constant(dst, existing, &constant) {
+                    return Err(LinkError::ConflictingConstant(constant.name.unwrap()));
+                }
+                existing
+            }
+            None => dst.constants.append(constant, self.src.get_span(handle)),
+        };
+        Ok(())
+    }
+}
+
+fn into_iter_with_spans<T>(arena: Arena<T>) -> impl Iterator<Item = (T, Span)> {
+    let spans: Vec<_> = arena
+        .iter()
+        .map(|(handle, _)| arena.get_span(handle))
+        .collect();
+    arena.into_inner().into_iter().zip(spans)
+}
+
+/// Return true if the constant expressions `a` and `b` in `module` compute
+/// the same value in the same way.
+fn same_const_expression(module: &Module, a: Handle<Expression>, b: Handle<Expression>) -> bool {
+    if a == b {
+        return true;
+    }
+
+    // Compare the expressions with their operands blanked out, and then
+    // compare the operands recursively.
+    let strip = |handle: Handle<Expression>| {
+        let mut expr = module.const_expressions[handle].clone();
+        let mut operands = Vec::new();
+        crate::proc::for_each_operand_mut(&mut expr, |operand| {
+            operands.push(*operand);
+            *operand = a;
+        });
+        (expr, operands)
+    };
+    let (expr_a, operands_a) = strip(a);
+    let (expr_b, operands_b) = strip(b);
+
+    match (expr_a, expr_b) {
+        (Expression::Constant(a), Expression::Constant(b)) => {
+            a == b || same_constant(module, a, &module.constants[b])
+        }
+        (expr_a, expr_b) => {
+            expr_a == expr_b
+                && operands_a.len() == operands_b.len()
+                && operands_a
+                    .into_iter()
+                    .zip(operands_b)
+                    .all(|(a, b)| same_const_expression(module, a, b))
+        }
+    }
+}
+
+fn same_constant(module: &Module, a: Handle<Constant>, b: &Constant) -> bool {
+    let a = &module.constants[a];
+    a.name == b.name
+        && a.r#override == b.r#override
+        && a.ty == b.ty
+        && same_const_expression(module, a.init, b.init)
+}
+
+fn same_global_variable(module: &Module, a: &GlobalVariable, b: &GlobalVariable) -> bool {
+    a.name == b.name
+        && a.space == b.space
+        && a.binding == b.binding
+        && a.ty == b.ty
+        && match (a.init, b.init) {
+            (None, None) => true,
+            (Some(a), Some(b)) => same_const_expression(module, a, b),
+            _ => false,
+        }
+}
+
+/// Return true if `a` and `b` are the same function.
+///
+/// Both functions must already refer to the same module's handles.
+fn same_function(a: &Function, b: &Function) -> bool {
+    a.name == b.name
+        && a.arguments.len() == b.arguments.len()
+        && a.arguments
+            .iter()
+            .zip(b.arguments.iter())
+            .all(|(a, b)| a.name == b.name && a.ty == b.ty && a.binding == b.binding)
+        && match (a.result.as_ref(), b.result.as_ref()) {
+            (None, None) => true,
+            (Some(a), Some(b)) => a.ty == b.ty && a.binding == b.binding,
+            _ => false,
+        }
+        && a.local_variables.len() == b.local_variables.len()
+        && a.local_variables
+            .iter()
+            .zip(b.local_variables.iter())
+            .all(|((_, a), (_, b))| a.name == b.name && a.ty == b.ty && a.init == b.init)
+        && a.expressions.len() == b.expressions.len()
+        && a.expressions
+            .iter()
+            .zip(b.expressions.iter())
+            .all(|((_, a), (_, b))| a == b)
+        && a.named_expressions == b.named_expressions
+        && a.body == b.body
+        && a.diagnostic_filter_leaf == b.diagnostic_filter_leaf
+        && a.diagnostic_filter_ranges == b.diagnostic_filter_ranges
+}
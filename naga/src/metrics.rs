@@ -0,0 +1,250 @@
+/*!
+Measuring the size of generated code, to catch regressions.
+
+It is easy to make a backend produce more code than it needs to, by
+declaring a type twice or splitting a block in two, without changing what
+the code does. Snapshot tests show such changes in a diff, but they are
+easy to miss. This module condenses each backend's output into a few
+numbers, a set of [`Metrics`], that can be checked into a repository and
+compared against later runs.
+
+The workflow is:
+
+1. Translate a corpus of shaders, and record the size of each output in a
+   [`Metrics`] with [`Metrics::measure_spirv`] or [`Metrics::measure_text`].
+
+2. Compare it against a checked-in baseline, parsed with
+   [`Metrics::parse`], using [`Metrics::regressions`]. Any metric that grew
+   by more than the given tolerance, or that disappeared because its
+   backend now fails, is a [`Regression`].
+
+3. When a regression is intended, or to record an improvement, regenerate
+   the baseline by writing out the current metrics with their [`Display`]
+   implementation.
+
+Naga's snapshot tests do this for every input, with the same options they
+use to generate the snapshots, keeping one baseline per input in
+`tests/out/metrics`. A baseline is written when an input doesn't have one
+yet. To regenerate the existing ones, run the snapshot tests with the
+`NAGA_UPDATE_METRICS` environment variable set:
+
+```text
+NAGA_UPDATE_METRICS=1 cargo test --all-features --test naga-test snapshots
+```
+
+These are only compiled with the `metrics` feature.
+
+[`Display`]: std::fmt::Display
+*/
+
+use std::{collections::BTreeMap, fmt};
+
+/// A set of named measurements of generated code.
+///
+/// Names are dot-separated paths, such as `spv.instructions.type`. The text
+/// format, produced by [`Display`] and read by [`Metrics::parse`], has one
+/// `name value` pair per line, sorted by name.
+///
+/// [`Display`]: fmt::Display
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Metrics {
+    values: BTreeMap<String, u64>,
+}
+
+/// A metric that grew beyond the tolerance passed to
+/// [`Metrics::regressions`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Regression {
+    pub name: String,
+    pub baseline: u64,
+    /// The current value, or `None` if the metric is no longer produced.
+    pub current: Option<u64>,
+}
+
+/// An error produced by [`Metrics::parse`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, thiserror::Error)]
+#[error("line {line}: expected a metric name followed by an integer")]
+pub struct ParseError {
+    pub line: usize,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, name: impl Into<String>, value: u64) {
+        self.values.insert(name.into(), value);
+    }
+
+    pub fn get(&self, name: &str) -> Option<u64> {
+        self.values.get(name).copied()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, u64)> {
+        self.values
+            .iter()
+            .map(|(name, &value)| (name.as_str(), value))
+    }
+
+    /// Parse metrics in the format written by [`Display`].
+    ///
+    /// Blank lines and lines starting with `#` are ignored.
+    ///
+    /// [`Display`]: fmt::Display
+    pub fn parse(text: &str) -> Result<Self, ParseError> {
+        let mut metrics = Self::new();
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let error = ParseError { line: index + 1 };
+            let (name, value) = line.split_once(' ').ok_or(error)?;
+            let value = value.trim().parse().map_err(|_| error)?;
+            metrics.insert(name, value);
+        }
+        Ok(metrics)
+    }
+
+    /// Return the metrics in `self`, the baseline, that are missing from
+    /// `current` or grew by more than `tolerance` percent.
+    ///
+    /// Metrics that only `current` has are new, and aren't regressions.
+    pub fn regressions(&self, current: &Metrics, tolerance: u64) -> Vec<Regression> {
+        self.iter()
+            .filter_map(|(name, baseline)| {
+                let value = current.get(name);
+                let regressed = match value {
+                    Some(value) => value * 100 > baseline * (100 + tolerance),
+                    None => true,
+                };
+                regressed.then(|| Regression {
+                    name: name.to_string(),
+                    baseline,
+                    current: value,
+                })
+            })
+            .collect()
+    }
+
+    /// Record the size of the SPIR-V module `words` under `prefix`.
+    ///
+    /// This records the total number of words and instructions, and the
+    /// number of instructions in each of the classes returned by
+    /// [`spirv_class`].
+    pub fn measure_spirv(&mut self, prefix: &str, words: &[u32]) {
+        const HEADER_WORDS: usize = 5;
+
+        let mut instructions = 0;
+        let mut classes = BTreeMap::new();
+        let mut rest = words.get(HEADER_WORDS..).unwrap_or_default();
+        while let Some(&first) = rest.first() {
+            let count = (first >> 16) as usize;
+            let opcode = first & 0xffff;
+            instructions += 1;
+            *classes.entry(spirv_class(opcode)).or_insert(0) += 1;
+            rest = rest.get(count.max(1)..).unwrap_or_default();
+        }
+
+        self.insert(join(prefix, "words"), words.len() as u64);
+        self.insert(join(prefix, "instructions"), instructions);
+        for (class, count) in classes {
+            self.insert(join(prefix, &format!("instructions.{class}")), count);
+        }
+    }
+
+    /// Record the number of lines in `source` under `prefix`.
+    pub fn measure_text(&mut self, prefix: &str, source: &str) {
+        self.insert(join(prefix, "lines"), source.lines().count() as u64);
+    }
+}
+
+impl fmt::Display for Metrics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (name, value) in self.iter() {
+            writeln!(f, "{name} {value}")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Regression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.current {
+            Some(current) => write!(f, "{}: {} -> {current}", self.name, self.baseline),
+            None => write!(f, "{}: {} -> missing", self.name, self.baseline),
+        }
+    }
+}
+
+/// Return the class of SPIR-V instruction `opcode`, for
+/// [`Metrics::measure_spirv`].
+///
+/// The classes roughly follow the sections of a SPIR-V module, with function
+/// bodies split into memory access, control flow, and everything else.
+pub fn spirv_class(opcode: u32) -> &'static str {
+    use spirv::Op;
+
+    let Some(op) = Op::from_u32(opcode) else {
+        return "unknown";
+    };
+    match op {
+        Op::Capability
+        | Op::Extension
+        | Op::ExtInstImport
+        | Op::MemoryModel
+        | Op::EntryPoint
+        | Op::ExecutionMode
+        | Op::ExecutionModeId => "mode",
+        Op::SourceContinued
+        | Op::Source
+        | Op::SourceExtension
+        | Op::Name
+        | Op::MemberName
+        | Op::String
+        | Op::Line
+        | Op::NoLine
+        | Op::ModuleProcessed => "debug",
+        Op::Decorate
+        | Op::MemberDecorate
+        | Op::DecorationGroup
+        | Op::GroupDecorate
+        | Op::GroupMemberDecorate
+        | Op::DecorateId
+        | Op::DecorateString
+        | Op::MemberDecorateString => "annotation",
+        _ if (Op::TypeVoid as u32..=Op::TypeForwardPointer as u32).contains(&opcode) => "type",
+        Op::TypeRayQueryKHR | Op::TypeAccelerationStructureKHR => "type",
+        _ if (Op::ConstantTrue as u32..=Op::SpecConstantOp as u32).contains(&opcode) => "constant",
+        Op::Function | Op::FunctionParameter | Op::FunctionEnd | Op::FunctionCall => "function",
+        Op::Variable
+        | Op::Load
+        | Op::Store
+        | Op::CopyMemory
+        | Op::AccessChain
+        | Op::InBoundsAccessChain
+        | Op::PtrAccessChain
+        | Op::ArrayLength => "memory",
+        Op::Phi
+        | Op::LoopMerge
+        | Op::SelectionMerge
+        | Op::Label
+        | Op::Branch
+        | Op::BranchConditional
+        | Op::Switch
+        | Op::Kill
+        | Op::Return
+        | Op::ReturnValue
+        | Op::Unreachable => "control",
+        _ => "other",
+    }
+}
+
+fn join(prefix: &str, name: &str) -> String {
+    if prefix.is_empty() {
+        name.to_string()
+    } else {
+        format!("{prefix}.{name}")
+    }
+}
@@ -365,6 +365,8 @@ pub enum ConstantEvaluatorError {
     InvalidArrayLengthArg,
     #[error("Constants cannot get the array length of a dynamically sized array")]
     ArrayLengthDynamic,
+    #[error("Constants cannot get the array length of an array sized by an override")]
+    ArrayLengthPending,
     #[error("Constants cannot call functions")]
     Call,
     #[error("Constants don't support workGroupUniformLoad")]
@@ -984,6 +986,28 @@ impl<'a> ConstantEvaluator<'a> {
                 })
             }
 
+            // geometry
+            crate::MathFunction::Dot4I8Packed => {
+                let [a, b] = self.packed_u32_args([arg, arg1.unwrap()], span)?;
+                let dot = a
+                    .to_le_bytes()
+                    .into_iter()
+                    .zip(b.to_le_bytes())
+                    .map(|(a, b)| i32::from(a as i8) * i32::from(b as i8))
+                    .sum();
+                self.register_evaluated_expr(Expression::Literal(Literal::I32(dot)), span)
+            }
+            crate::MathFunction::Dot4U8Packed => {
+                let [a, b] = self.packed_u32_args([arg, arg1.unwrap()], span)?;
+                let dot = a
+                    .to_le_bytes()
+                    .into_iter()
+                    .zip(b.to_le_bytes())
+                    .map(|(a, b)| u32::from(a) * u32::from(b))
+                    .sum();
+                self.register_evaluated_expr(Expression::Literal(Literal::U32(dot)), span)
+            }
+
             // computational
             crate::MathFunction::Sign => {
                 component_wise_signed!(self, span, [arg], |e| { Ok([e.signum()]) })
@@ -1007,6 +1031,15 @@ impl<'a> ConstantEvaluator<'a> {
             crate::MathFunction::InverseSqrt => {
                 component_wise_float!(self, span, [arg], |e| { Ok([1. / e.sqrt()]) })
             }
+            crate::MathFunction::QuantizeToF16 => {
+                component_wise_float(self, span, [arg], |e| match e {
+                    Float::F32([e]) => quantize_to_f16(e)
+                        .map(|e| Float::F32([e]))
+                        .ok_or_else(|| ConstantEvaluatorError::Overflow("quantizeToF16".into())),
+                    // The front ends concretize this built-in's argument.
+                    Float::Abstract(_) => Err(ConstantEvaluatorError::InvalidMathArg),
+                })
+            }
 
             // bits
             crate::MathFunction::CountTrailingZeros => {
@@ -1046,6 +1079,24 @@ impl<'a> ConstantEvaluator<'a> {
         }
     }
 
+    /// Evaluate `args`, the `u32` operands of a packed built-in like
+    /// `dot4I8Packed`, to their values.
+    fn packed_u32_args<const N: usize>(
+        &mut self,
+        args: [Handle<Expression>; N],
+        span: Span,
+    ) -> Result<[u32; N], ConstantEvaluatorError> {
+        let mut values = [0; N];
+        for (value, arg) in values.iter_mut().zip(args) {
+            let arg = self.eval_zero_value_and_splat(arg, span)?;
+            *value = match self.expressions[arg] {
+                Expression::Literal(Literal::U32(value)) => value,
+                _ => return Err(ConstantEvaluatorError::InvalidMathArg),
+            };
+        }
+        Ok(values)
+    }
+
     fn array_length(
         &mut self,
         array: Handle<Expression>,
@@ -1059,6 +1110,9 @@ impl<'a> ConstantEvaluator<'a> {
                             let expr = Expression::Literal(Literal::U32(len.get()));
                             self.register_evaluated_expr(expr, span)
                         }
+                        crate::ArraySize::Pending(_) => {
+                            Err(ConstantEvaluatorError::ArrayLengthPending)
+                        }
                         crate::ArraySize::Dynamic => {
                             Err(ConstantEvaluatorError::ArrayLengthDynamic)
                         }
@@ -1125,7 +1179,7 @@ impl<'a> ConstantEvaluator<'a> {
                 if matches!(
                     self.types[ty].inner,
                     crate::TypeInner::Scalar(crate::Scalar {
-                        kind: ScalarKind::Uint,
+                        kind: ScalarKind::Uint | ScalarKind::Sint,
                         ..
                     })
                 ) =>
@@ -1133,6 +1187,9 @@ impl<'a> ConstantEvaluator<'a> {
                 Ok(0)
             }
             Expression::Literal(Literal::U32(index)) => Ok(index as usize),
+            Expression::Literal(Literal::I32(index)) => {
+                usize::try_from(index).map_err(|_| ConstantEvaluatorError::InvalidAccessIndex)
+            }
             _ => Err(ConstantEvaluatorError::InvalidAccessIndexTy),
         }
     }
@@ -1632,6 +1689,22 @@ impl<'a> ConstantEvaluator<'a> {
                                 _ => return Err(ConstantEvaluatorError::InvalidBinaryOpArgs),
                             })
                         }
+                        (Literal::AbstractInt(a), Literal::U32(b)) => {
+                            Literal::AbstractInt(match op {
+                                BinaryOperator::ShiftLeft => a
+                                    .checked_shl(b)
+                                    .filter(|&shifted| shifted >> b == a)
+                                    .ok_or_else(|| {
+                                        ConstantEvaluatorError::Overflow("left shift".into())
+                                    })?,
+                                BinaryOperator::ShiftRight => {
+                                    a.checked_shr(b).ok_or_else(|| {
+                                        ConstantEvaluatorError::Overflow("right shift".into())
+                                    })?
+                                }
+                                _ => return Err(ConstantEvaluatorError::InvalidBinaryOpArgs),
+                            })
+                        }
                         (Literal::AbstractFloat(a), Literal::AbstractFloat(b)) => {
                             Literal::AbstractFloat(match op {
                                 BinaryOperator::Add => a + b,
@@ -1877,6 +1950,30 @@ impl<'a> ConstantEvaluator<'a> {
     }
 }
 
+/// Round `value` to the nearest `f16` value, ties to even.
+///
+/// Return `None` if the result is outside `f16`'s finite range, which WGSL
+/// makes a shader-creation error for constant expressions.
+fn quantize_to_f16(value: f32) -> Option<f32> {
+    /// The smallest normal `f16` value, 2^-14.
+    const MIN_NORMAL: f32 = 6.103_515_6e-5;
+    /// The largest finite `f16` value.
+    const MAX: f32 = 65504.0;
+
+    let magnitude = value.abs();
+    let quantized = if magnitude < MIN_NORMAL {
+        // Subnormal `f16` values are multiples of 2^-24, as are `f32` values
+        // in [0.5, 1), so let the addition do the rounding.
+        (magnitude + 0.5) - 0.5
+    } else {
+        // Keep the top 10 of `f32`'s 23 mantissa bits. A carry out of the
+        // mantissa correctly bumps the exponent.
+        let bits = magnitude.to_bits();
+        f32::from_bits((bits + 0xfff + ((bits >> 13) & 1)) & !0x1fff)
+    };
+    (quantized <= MAX).then(|| quantized.copysign(value))
+}
+
 #[cfg(test)]
 mod tests {
     use std::vec;
@@ -1886,7 +1983,7 @@ mod tests {
         UniqueArena, VectorSize,
     };
 
-    use super::{Behavior, ConstantEvaluator};
+    use super::{Behavior, ConstantEvaluator, ConstantEvaluatorError};
 
     #[test]
     fn unary_op() {
@@ -2383,6 +2480,102 @@ mod tests {
             panic!("unexpected evaluation result")
         }
     }
+
+    #[test]
+    fn decomposition() {
+        use crate::MathFunction as Mf;
+
+        let mut types = UniqueArena::new();
+        let constants = Arena::new();
+        let mut const_expressions = Arena::new();
+
+        let mut solver = ConstantEvaluator {
+            behavior: Behavior::Wgsl,
+            types: &mut types,
+            constants: &constants,
+            expressions: &mut const_expressions,
+            function_local_data: None,
+        };
+
+        let cases: &[(Mf, f32, f32)] = &[
+            // `round` rounds halfway cases to even
+            (Mf::Round, 0.5, 0.0),
+            (Mf::Round, 1.5, 2.0),
+            (Mf::Round, 2.5, 2.0),
+            (Mf::Round, -0.5, -0.0),
+            (Mf::Round, -1.5, -2.0),
+            (Mf::Round, -2.5, -2.0),
+            (Mf::Round, 2.4, 2.0),
+            (Mf::Round, -2.6, -3.0),
+            // `fract` is `e - floor(e)`, so it's never negative
+            (Mf::Fract, 1.25, 0.25),
+            (Mf::Fract, -1.25, 0.75),
+            (Mf::Fract, -2.0, 0.0),
+            (Mf::Trunc, 1.75, 1.0),
+            (Mf::Trunc, -1.75, -1.0),
+            (Mf::Floor, 1.75, 1.0),
+            (Mf::Floor, -1.25, -2.0),
+            (Mf::Ceil, 1.25, 2.0),
+            (Mf::Ceil, -1.75, -1.0),
+            // `quantizeToF16` rounds to the nearest `f16`, ties to even
+            (Mf::QuantizeToF16, 1.0, 1.0),
+            (Mf::QuantizeToF16, 1.000_488_3, 1.0),
+            (Mf::QuantizeToF16, 1.001_464_8, 1.001_953_1),
+            (Mf::QuantizeToF16, -1.000_7, -1.000_976_6),
+            (Mf::QuantizeToF16, 65519.0, 65504.0),
+            (Mf::QuantizeToF16, -65504.0, -65504.0),
+            // ...including to `f16`'s subnormal values
+            (Mf::QuantizeToF16, 8.940_697e-8, 1.192_092_9e-7),
+            (Mf::QuantizeToF16, 6.1e-5, 6.097_555e-5),
+            (Mf::QuantizeToF16, 1.490_116_1e-8, 0.0),
+            (Mf::QuantizeToF16, -1.490_116_1e-8, -0.0),
+        ];
+
+        for &(fun, arg, expected) in cases {
+            let arg = solver
+                .try_eval_and_append(&Expression::Literal(Literal::F32(arg)), Default::default())
+                .unwrap();
+            let res = solver
+                .try_eval_and_append(
+                    &Expression::Math {
+                        fun,
+                        arg,
+                        arg1: None,
+                        arg2: None,
+                        arg3: None,
+                    },
+                    Default::default(),
+                )
+                .unwrap();
+            match solver.expressions[res] {
+                Expression::Literal(Literal::F32(value)) => assert!(
+                    value == expected && value.is_sign_negative() == expected.is_sign_negative(),
+                    "{fun:?}({:?}) = {value:?}, expected {expected:?}",
+                    solver.expressions[arg],
+                ),
+                ref other => panic!("unexpected evaluation result {other:?}"),
+            }
+        }
+
+        // `quantizeToF16` of a value outside `f16`'s finite range is an error.
+        let arg = solver
+            .try_eval_and_append(
+                &Expression::Literal(Literal::F32(65520.0)),
+                Default::default(),
+            )
+            .unwrap();
+        let res = solver.try_eval_and_append(
+            &Expression::Math {
+                fun: Mf::QuantizeToF16,
+                arg,
+                arg1: None,
+                arg2: None,
+                arg3: None,
+            },
+            Default::default(),
+        );
+        assert!(matches!(res, Err(ConstantEvaluatorError::Overflow(_))));
+    }
 }
 
 /// Trait for conversions of abstract values to concrete types.
@@ -0,0 +1,729 @@
+/*!
+Recomputing [`Emit`] statements for a function body.
+
+Code that builds Naga IR programmatically, or translates it from a language
+that has no notion of evaluation points, can find it awkward to produce
+correct [`Emit`] ranges. [`ensure_emits`] discards whatever [`Emit`]
+statements a function has and derives new ones from how its expressions are
+actually used.
+
+[`Emit`]: crate::Statement::Emit
+*/
+
+use crate::arena::{Arena, Handle, Range};
+use crate::{Block, Expression, Span, Statement};
+
+/// Call `f` on each expression `expr` refers to in its own arena.
+///
+/// Handles into the module's constant expression arena, like
+/// [`ImageSample::offset`], are not visited.
+///
+/// [`ImageSample::offset`]: crate::Expression::ImageSample::offset
+pub(crate) fn for_each_operand(expr: &Expression, mut f: impl FnMut(Handle<Expression>)) {
+    use crate::Expression as Ex;
+    match *expr {
+        Ex::Literal(_)
+        | Ex::Constant(_)
+        | Ex::ZeroValue(_)
+        | Ex::FunctionArgument(_)
+        | Ex::GlobalVariable(_)
+        | Ex::LocalVariable(_)
+        | Ex::CallResult(_)
+        | Ex::AtomicResult { .. }
+        | Ex::WorkGroupUniformLoadResult { .. }
+        | Ex::RayQueryProceedResult => {}
+        Ex::Compose { ref components, .. } => components.iter().cloned().for_each(f),
+        Ex::Access { base, index } => {
+            f(base);
+            f(index);
+        }
+        Ex::AccessIndex { base, .. } => f(base),
+        Ex::Splat { value, .. } => f(value),
+        Ex::Swizzle { vector, .. } => f(vector),
+        Ex::Load { pointer } => f(pointer),
+        Ex::ImageSample {
+            image,
+            sampler,
+            gather: _,
+            coordinate,
+            array_index,
+            offset: _,
+            ref level,
+            depth_ref,
+        } => {
+            f(image);
+            f(sampler);
+            f(coordinate);
+            array_index.into_iter().for_each(&mut f);
+            match *level {
+                crate::SampleLevel::Auto | crate::SampleLevel::Zero => {}
+                crate::SampleLevel::Exact(expr) | crate::SampleLevel::Bias(expr) => f(expr),
+                crate::SampleLevel::Gradient { x, y } => {
+                    f(x);
+                    f(y);
+                }
+            }
+            depth_ref.into_iter().for_each(f);
+        }
+        Ex::ImageLoad {
+            image,
+            coordinate,
+            array_index,
+            sample,
+            level,
+        } => {
+            f(image);
+            f(coordinate);
+            array_index
+                .into_iter()
+                .chain(sample)
+                .chain(level)
+                .for_each(f);
+        }
+        Ex::ImageQuery { image, ref query } => {
+            f(image);
+            if let crate::ImageQuery::Size { level: Some(level) } = *query {
+                f(level);
+            }
+        }
+        Ex::Unary { expr, .. } => f(expr),
+        Ex::Binary { left, right, .. } => {
+            f(left);
+            f(right);
+        }
+        Ex::Select {
+            condition,
+            accept,
+            reject,
+        } => {
+            f(condition);
+            f(accept);
+            f(reject);
+        }
+        Ex::Derivative { expr, .. } => f(expr),
+        Ex::Relational { argument, .. } => f(argument),
+        Ex::Math {
+            arg,
+            arg1,
+            arg2,
+            arg3,
+            ..
+        } => {
+            f(arg);
+            arg1.into_iter().chain(arg2).chain(arg3).for_each(f);
+        }
+        Ex::As { expr, .. } => f(expr),
+        Ex::ArrayLength(expr) => f(expr),
+        Ex::RayQueryGetIntersection { query, .. } => f(query),
+    }
+}
+
+/// Like [`for_each_operand`], but lets `f` modify each operand.
+pub(crate) fn for_each_operand_mut(
+    expr: &mut Expression,
+    mut f: impl FnMut(&mut Handle<Expression>),
+) {
+    fn f_option(
+        f: &mut impl FnMut(&mut Handle<Expression>),
+        expr: &mut Option<Handle<Expression>>,
+    ) {
+        if let Some(ref mut expr) = *expr {
+            f(expr);
+        }
+    }
+
+    use crate::Expression as Ex;
+    match *expr {
+        Ex::Literal(_)
+        | Ex::Constant(_)
+        | Ex::ZeroValue(_)
+        | Ex::FunctionArgument(_)
+        | Ex::GlobalVariable(_)
+        | Ex::LocalVariable(_)
+        | Ex::CallResult(_)
+        | Ex::AtomicResult { .. }
+        | Ex::WorkGroupUniformLoadResult { .. }
+        | Ex::RayQueryProceedResult => {}
+        Ex::Compose {
+            ref mut components, ..
+        } => {
+            for component in components {
+                f(component);
+            }
+        }
+        Ex::Access {
+            ref mut base,
+            ref mut index,
+        } => {
+            f(base);
+            f(index);
+        }
+        Ex::AccessIndex { ref mut base, .. } => f(base),
+        Ex::Splat { ref mut value, .. } => f(value),
+        Ex::Swizzle { ref mut vector, .. } => f(vector),
+        Ex::Load { ref mut pointer } => f(pointer),
+        Ex::ImageSample {
+            ref mut image,
+            ref mut sampler,
+            ref mut coordinate,
+            ref mut array_index,
+            ref mut level,
+            ref mut depth_ref,
+            ..
+        } => {
+            f(image);
+            f(sampler);
+            f(coordinate);
+            f_option(&mut f, array_index);
+            match *level {
+                crate::SampleLevel::Auto | crate::SampleLevel::Zero => {}
+                crate::SampleLevel::Exact(ref mut expr)
+                | crate::SampleLevel::Bias(ref mut expr) => f(expr),
+                crate::SampleLevel::Gradient {
+                    ref mut x,
+                    ref mut y,
+                } => {
+                    f(x);
+                    f(y);
+                }
+            }
+            f_option(&mut f, depth_ref);
+        }
+        Ex::ImageLoad {
+            ref mut image,
+            ref mut coordinate,
+            ref mut array_index,
+            ref mut sample,
+            ref mut level,
+        } => {
+            f(image);
+            f(coordinate);
+            f_option(&mut f, array_index);
+            f_option(&mut f, sample);
+            f_option(&mut f, level);
+        }
+        Ex::ImageQuery {
+            ref mut image,
+            ref mut query,
+        } => {
+            f(image);
+            if let crate::ImageQuery::Size { ref mut level } = *query {
+                f_option(&mut f, level);
+            }
+        }
+        Ex::Unary { ref mut expr, .. } => f(expr),
+        Ex::Binary {
+            ref mut left,
+            ref mut right,
+            ..
+        } => {
+            f(left);
+            f(right);
+        }
+        Ex::Select {
+            ref mut condition,
+            ref mut accept,
+            ref mut reject,
+        } => {
+            f(condition);
+            f(accept);
+            f(reject);
+        }
+        Ex::Derivative { ref mut expr, .. } => f(expr),
+        Ex::Relational {
+            ref mut argument, ..
+        } => f(argument),
+        Ex::Math {
+            ref mut arg,
+            ref mut arg1,
+            ref mut arg2,
+            ref mut arg3,
+            ..
+        } => {
+            f(arg);
+            f_option(&mut f, arg1);
+            f_option(&mut f, arg2);
+            f_option(&mut f, arg3);
+        }
+        Ex::As { ref mut expr, .. } => f(expr),
+        Ex::ArrayLength(ref mut expr) => f(expr),
+        Ex::RayQueryGetIntersection { ref mut query, .. } => f(query),
+    }
+}
+
+/// Call `f` on each expression handle `statement` holds, including the
+/// results it introduces.
+///
+/// This doesn't visit [`Emit`] ranges, or look into child blocks; see
+/// [`for_each_child_block_mut`].
+///
+/// [`Emit`]: crate::Statement::Emit
+pub(crate) fn for_each_statement_operand_mut(
+    statement: &mut Statement,
+    mut f: impl FnMut(&mut Handle<Expression>),
+) {
+    use crate::Statement as S;
+    match *statement {
+        S::Emit(_)
+        | S::Block(_)
+        | S::Break
+        | S::Continue
+        | S::Kill
+        | S::Barrier(_)
+        | S::DebugMarker(_) => {}
+        S::If {
+            ref mut condition, ..
+        } => f(condition),
+        S::Switch {
+            ref mut selector, ..
+        } => f(selector),
+        S::Loop {
+            ref mut break_if, ..
+        } => break_if.iter_mut().for_each(f),
+        S::Return { ref mut value } => value.iter_mut().for_each(f),
+        S::Store {
+            ref mut pointer,
+            ref mut value,
+        } => {
+            f(pointer);
+            f(value);
+        }
+        S::ImageStore {
+            ref mut image,
+            ref mut coordinate,
+            ref mut array_index,
+            ref mut value,
+        } => {
+            f(image);
+            f(coordinate);
+            array_index.iter_mut().for_each(&mut f);
+            f(value);
+        }
+        S::Atomic {
+            ref mut pointer,
+            ref mut fun,
+            ref mut value,
+            ref mut result,
+        } => {
+            f(pointer);
+            if let crate::AtomicFunction::Exchange {
+                compare: Some(ref mut compare),
+            } = *fun
+            {
+                f(compare);
+            }
+            f(value);
+            f(result);
+        }
+        S::WorkGroupUniformLoad {
+            ref mut pointer,
+            ref mut result,
+        } => {
+            f(pointer);
+            f(result);
+        }
+        S::Call {
+            ref mut arguments,
+            ref mut result,
+            ..
+        } => {
+            arguments.iter_mut().for_each(&mut f);
+            result.iter_mut().for_each(f);
+        }
+        S::RayQuery {
+            ref mut query,
+            ref mut fun,
+        } => {
+            f(query);
+            match *fun {
+                crate::RayQueryFunction::Initialize {
+                    ref mut acceleration_structure,
+                    ref mut descriptor,
+                } => {
+                    f(acceleration_structure);
+                    f(descriptor);
+                }
+                crate::RayQueryFunction::Proceed { ref mut result } => f(result),
+                crate::RayQueryFunction::Terminate => {}
+            }
+        }
+        S::MeshFunction(
+            crate::MeshFunction::SetMeshOutputs {
+                vertex_count: ref mut first,
+                primitive_count: ref mut second,
+            }
+            | crate::MeshFunction::SetVertex {
+                index: ref mut first,
+                value: ref mut second,
+            }
+            | crate::MeshFunction::SetPrimitive {
+                index: ref mut first,
+                value: ref mut second,
+            },
+        ) => {
+            f(first);
+            f(second);
+        }
+    }
+}
+
+/// Return true if `expr` is introduced by a statement, rather than by
+/// [`Emit`](crate::Statement::Emit).
+const fn is_statement_result(expr: &Expression) -> bool {
+    matches!(
+        *expr,
+        Expression::CallResult(_)
+            | Expression::AtomicResult { .. }
+            | Expression::WorkGroupUniformLoadResult { .. }
+            | Expression::RayQueryProceedResult
+    )
+}
+
+/// A point in a function body: just before the `index`'th statement of the
+/// block numbered `block`.
+#[derive(Clone, Copy)]
+struct Placement {
+    block: usize,
+    index: usize,
+}
+
+/// A block's position in the tree of blocks making up a function body.
+struct BlockNode {
+    /// Where this block sits in its parent, or `None` for the function body.
+    parent: Option<Placement>,
+    depth: usize,
+}
+
+/// Replace the [`Emit`] statements in `fun` with freshly computed ones.
+///
+/// Every expression that is used by some statement, directly or through
+/// other expressions, is emitted exactly once, at the latest point that
+/// precedes all of its uses and is visible to all of them. Expressions that
+/// don't need emitting (see [`Expression::needs_pre_emit`]) and the results
+/// of statements like [`Call`] are left alone, and unused expressions are
+/// not emitted at all.
+///
+/// This is conservative: an expression used in both arms of an `if` is
+/// evaluated once before the `if`, and one used both inside and after a
+/// loop is evaluated once before the loop.
+///
+/// [`Emit`]: crate::Statement::Emit
+/// [`Call`]: crate::Statement::Call
+pub fn ensure_emits(fun: &mut crate::Function) {
+    strip_emits(&mut fun.body);
+
+    let mut placer = Placer {
+        blocks: Vec::new(),
+        placements: vec![None; fun.expressions.len()],
+    };
+    placer.visit_block(&fun.body, None);
+
+    // An expression may only refer to expressions that precede it in the
+    // arena, so a single backwards pass pushes every use down to operands.
+    for (handle, expr) in fun.expressions.iter().rev() {
+        if let Some(placement) = placer.placements[handle.index()] {
+            for_each_operand(expr, |operand| placer.place(operand, placement));
+        }
+    }
+
+    let mut pending = vec![Vec::new(); placer.blocks.len()];
+    for (handle, expr) in fun.expressions.iter() {
+        if expr.needs_pre_emit() || is_statement_result(expr) {
+            continue;
+        }
+        if let Some(placement) = placer.placements[handle.index()] {
+            pending[placement.block].push((placement.index, handle));
+        }
+    }
+
+    let mut next_block = 0;
+    insert_emits(
+        &mut fun.body,
+        &fun.expressions,
+        &mut pending,
+        &mut next_block,
+    );
+}
+
+fn strip_emits(block: &mut Block) {
+    let old = std::mem::take(block);
+    for (mut statement, span) in old.span_into_iter() {
+        if let Statement::Emit(_) = statement {
+            continue;
+        }
+        for_each_child_block_mut(&mut statement, strip_emits);
+        block.push(statement, span);
+    }
+}
+
+/// Call `f` on each block directly nested in `statement`.
+pub(crate) fn for_each_child_block_mut(statement: &mut Statement, mut f: impl FnMut(&mut Block)) {
+    match *statement {
+        Statement::Block(ref mut block) => f(block),
+        Statement::If {
+            ref mut accept,
+            ref mut reject,
+            ..
+        } => {
+            f(accept);
+            f(reject);
+        }
+        Statement::Switch { ref mut cases, .. } => {
+            for case in cases.iter_mut() {
+                f(&mut case.body);
+            }
+        }
+        Statement::Loop {
+            ref mut body,
+            ref mut continuing,
+            ..
+        } => {
+            f(body);
+            f(continuing);
+        }
+        _ => {}
+    }
+}
+
+struct Placer {
+    blocks: Vec<BlockNode>,
+    placements: Vec<Option<Placement>>,
+}
+
+impl Placer {
+    fn new_block(&mut self, parent: Option<Placement>) -> usize {
+        let depth = parent.map_or(0, |parent| self.blocks[parent.block].depth + 1);
+        self.blocks.push(BlockNode { parent, depth });
+        self.blocks.len() - 1
+    }
+
+    /// Record a use of `expr` at `at`.
+    fn place(&mut self, expr: Handle<Expression>, at: Placement) {
+        let placement = match self.placements[expr.index()] {
+            Some(previous) => self.meet(previous, at),
+            None => at,
+        };
+        self.placements[expr.index()] = Some(placement);
+    }
+
+    /// Return the latest placement that precedes both `a` and `b`, in a
+    /// block visible to both.
+    fn meet(&self, mut a: Placement, mut b: Placement) -> Placement {
+        while self.blocks[a.block].depth > self.blocks[b.block].depth {
+            a = self.blocks[a.block].parent.unwrap();
+        }
+        while self.blocks[b.block].depth > self.blocks[a.block].depth {
+            b = self.blocks[b.block].parent.unwrap();
+        }
+        while a.block != b.block {
+            a = self.blocks[a.block].parent.unwrap();
+            b = self.blocks[b.block].parent.unwrap();
+        }
+        Placement {
+            block: a.block,
+            index: a.index.min(b.index),
+        }
+    }
+
+    /// Number `block` and its descendants, and record the uses they make.
+    ///
+    /// Blocks are numbered in the same order `insert_emits` visits them.
+    fn visit_block(&mut self, block: &Block, parent: Option<Placement>) -> usize {
+        let id = self.new_block(parent);
+        for (index, statement) in block.iter().enumerate() {
+            let at = Placement { block: id, index };
+            self.visit_statement(statement, at);
+        }
+        id
+    }
+
+    fn visit_statement(&mut self, statement: &Statement, at: Placement) {
+        use crate::Statement as S;
+        match *statement {
+            S::Emit(_) | S::Break | S::Continue | S::Kill | S::Barrier(_) | S::DebugMarker(_) => {}
+            S::Block(ref block) => {
+                self.visit_block(block, Some(at));
+            }
+            S::If {
+                condition,
+                ref accept,
+                ref reject,
+                hint: _,
+            } => {
+                self.place(condition, at);
+                self.visit_block(accept, Some(at));
+                self.visit_block(reject, Some(at));
+            }
+            S::Switch {
+                selector,
+                ref cases,
+            } => {
+                self.place(selector, at);
+                for case in cases.iter() {
+                    self.visit_block(&case.body, Some(at));
+                }
+            }
+            S::Loop {
+                ref body,
+                ref continuing,
+                break_if,
+                hint: _,
+            } => {
+                let body_id = self.visit_block(body, Some(at));
+                // The `continuing` block can see everything emitted in
+                // `body`, as if it were nested at the end of it.
+                let continuing_at = Placement {
+                    block: body_id,
+                    index: body.len(),
+                };
+                let continuing_id = self.visit_block(continuing, Some(continuing_at));
+                if let Some(condition) = break_if {
+                    let break_if_at = Placement {
+                        block: continuing_id,
+                        index: continuing.len(),
+                    };
+                    self.place(condition, break_if_at);
+                }
+            }
+            S::Return { value } => {
+                if let Some(value) = value {
+                    self.place(value, at);
+                }
+            }
+            S::Store { pointer, value } => {
+                self.place(pointer, at);
+                self.place(value, at);
+            }
+            S::ImageStore {
+                image,
+                coordinate,
+                array_index,
+                value,
+            } => {
+                self.place(image, at);
+                self.place(coordinate, at);
+                if let Some(array_index) = array_index {
+                    self.place(array_index, at);
+                }
+                self.place(value, at);
+            }
+            S::Atomic {
+                pointer,
+                ref fun,
+                value,
+                result: _,
+            } => {
+                self.place(pointer, at);
+                if let crate::AtomicFunction::Exchange {
+                    compare: Some(compare),
+                } = *fun
+                {
+                    self.place(compare, at);
+                }
+                self.place(value, at);
+            }
+            S::WorkGroupUniformLoad { pointer, result: _ } => self.place(pointer, at),
+            S::Call { ref arguments, .. } => {
+                for &argument in arguments {
+                    self.place(argument, at);
+                }
+            }
+            S::RayQuery { query, ref fun } => {
+                self.place(query, at);
+                if let crate::RayQueryFunction::Initialize {
+                    acceleration_structure,
+                    descriptor,
+                } = *fun
+                {
+                    self.place(acceleration_structure, at);
+                    self.place(descriptor, at);
+                }
+            }
+            S::MeshFunction(
+                crate::MeshFunction::SetMeshOutputs {
+                    vertex_count: first,
+                    primitive_count: second,
+                }
+                | crate::MeshFunction::SetVertex {
+                    index: first,
+                    value: second,
+                }
+                | crate::MeshFunction::SetPrimitive {
+                    index: first,
+                    value: second,
+                },
+            ) => {
+                self.place(first, at);
+                self.place(second, at);
+            }
+        }
+    }
+}
+
+/// Insert the `Emit` statements recorded in `pending` into `block` and its
+/// descendants.
+///
+/// `pending[id]` holds `(index, expression)` pairs for the block numbered
+/// `id`, meaning that `expression` should be emitted just before the
+/// `index`'th statement.
+fn insert_emits(
+    block: &mut Block,
+    expressions: &Arena<Expression>,
+    pending: &mut [Vec<(usize, Handle<Expression>)>],
+    next_block: &mut usize,
+) {
+    let id = *next_block;
+    *next_block += 1;
+
+    let mut emits = std::mem::take(&mut pending[id]);
+    emits.sort_by_key(|&(index, handle)| (index, handle.index()));
+    let mut emits = emits.into_iter().peekable();
+
+    let old = std::mem::take(block);
+    let len = old.len();
+    for (index, (mut statement, span)) in old.span_into_iter().enumerate() {
+        push_emits(block, expressions, &mut emits, index);
+        for_each_child_block_mut(&mut statement, |child| {
+            insert_emits(child, expressions, pending, next_block)
+        });
+        block.push(statement, span);
+    }
+    push_emits(block, expressions, &mut emits, len);
+}
+
+/// Push `Emit` statements for the expressions at the front of `emits` that
+/// belong before statement `index`, one for each contiguous run of handles.
+fn push_emits(
+    block: &mut Block,
+    expressions: &Arena<Expression>,
+    emits: &mut std::iter::Peekable<impl Iterator<Item = (usize, Handle<Expression>)>>,
+    index: usize,
+) {
+    let mut run: Option<(Handle<Expression>, Handle<Expression>)> = None;
+    while let Some((_, handle)) = emits.next_if(|&(at, _)| at == index) {
+        run = match run {
+            Some((first, last)) if last.index() + 1 == handle.index() => Some((first, handle)),
+            Some(finished) => {
+                push_emit(block, expressions, finished);
+                Some((handle, handle))
+            }
+            None => Some((handle, handle)),
+        };
+    }
+    if let Some(finished) = run {
+        push_emit(block, expressions, finished);
+    }
+}
+
+fn push_emit(
+    block: &mut Block,
+    expressions: &Arena<Expression>,
+    (first, last): (Handle<Expression>, Handle<Expression>),
+) {
+    let range = Range::new_from_bounds(first, last);
+    let mut span = Span::default();
+    for handle in range.clone() {
+        span.subsume(expressions.get_span(handle));
+    }
+    block.push(Statement::Emit(range), span);
+}
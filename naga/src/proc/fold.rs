@@ -0,0 +1,202 @@
+/*!
+Folding accesses to constant values in function bodies.
+
+Frontends that evaluate constant expressions as they build the module, like
+the WGSL frontend, never produce a [`Swizzle`] or [`Access`] of a constant
+vector: they apply [`ConstantEvaluator`] to each expression as it is parsed.
+Other frontends, and code that builds Naga IR directly, may leave such
+expressions in function bodies, where backends must compute them at run time.
+[`fold_constants`] evaluates them after the fact.
+
+[`Swizzle`]: crate::Expression::Swizzle
+[`Access`]: crate::Expression::Access
+*/
+
+use super::ConstantEvaluator;
+use crate::arena::Handle;
+use crate::{Constant, Expression, Function, Module, Override, Type, TypeInner};
+
+/// Replace accesses to constant values in `module`'s functions with their values.
+///
+/// Every [`Swizzle`], [`Access`], and [`AccessIndex`] expression whose
+/// operands are constant is evaluated with a [`ConstantEvaluator`]. Scalar
+/// results replace the expression with a [`Literal`] or [`ZeroValue`], and
+/// composite results with a new unnamed [`Constant`]. Since the folded
+/// expressions no longer need evaluating, the [`Emit`] statements of affected
+/// functions are recomputed with [`ensure_emits`].
+///
+/// The operands of folded expressions are left in the function's arena, even
+/// if they are no longer used; run `compact::compact` afterwards to remove
+/// them.
+///
+/// [`Swizzle`]: Expression::Swizzle
+/// [`Access`]: Expression::Access
+/// [`AccessIndex`]: Expression::AccessIndex
+/// [`Literal`]: Expression::Literal
+/// [`ZeroValue`]: Expression::ZeroValue
+/// [`Emit`]: crate::Statement::Emit
+/// [`ensure_emits`]: super::ensure_emits
+pub fn fold_constants(module: &mut Module) {
+    let mut functions = std::mem::take(&mut module.functions);
+    for (_, function) in functions.iter_mut() {
+        fold_function(module, function);
+    }
+    module.functions = functions;
+
+    let mut entry_points = std::mem::take(&mut module.entry_points);
+    for entry_point in entry_points.iter_mut() {
+        fold_function(module, &mut entry_point.function);
+    }
+    module.entry_points = entry_points;
+}
+
+fn fold_function(module: &mut Module, function: &mut Function) {
+    let mut folder = Folder {
+        values: vec![None; function.expressions.len()],
+    };
+    let handles: Vec<_> = function
+        .expressions
+        .iter()
+        .map(|(handle, _)| handle)
+        .collect();
+
+    let mut changed = false;
+    for handle in handles {
+        let expr = match function.expressions[handle] {
+            Expression::Swizzle {
+                size,
+                vector,
+                pattern,
+            } => folder
+                .value(module, function, vector)
+                .map(|vector| Expression::Swizzle {
+                    size,
+                    vector,
+                    pattern,
+                }),
+            Expression::AccessIndex { base, index } => folder
+                .value(module, function, base)
+                .map(|base| Expression::AccessIndex { base, index }),
+            Expression::Access { base, index } => folder
+                .value(module, function, base)
+                .zip(folder.value(module, function, index))
+                .map(|(base, index)| Expression::Access { base, index }),
+            _ => None,
+        };
+        let Some(expr) = expr else {
+            continue;
+        };
+
+        let span = function.expressions.get_span(handle);
+        let value =
+            match ConstantEvaluator::for_wgsl_module(module).try_eval_and_append(&expr, span) {
+                Ok(value) => value,
+                Err(err) => {
+                    log::debug!("not folding {:?}: {}", handle, err);
+                    continue;
+                }
+            };
+
+        let folded = match module.const_expressions[value] {
+            ref scalar @ (Expression::Literal(_) | Expression::ZeroValue(_)) => scalar.clone(),
+            Expression::Compose { ty, .. } => Expression::Constant(add_constant(module, ty, value)),
+            Expression::Splat {
+                size,
+                value: scalar,
+            } => {
+                let scalar = match module.const_expressions[scalar] {
+                    Expression::Literal(literal) => literal.scalar(),
+                    _ => continue,
+                };
+                let ty = module.types.insert(
+                    Type {
+                        name: None,
+                        inner: TypeInner::Vector { size, scalar },
+                    },
+                    span,
+                );
+                Expression::Constant(add_constant(module, ty, value))
+            }
+            _ => continue,
+        };
+
+        function.expressions[handle] = folded;
+        folder.values[handle.index()] = Some(value);
+        changed = true;
+    }
+
+    if changed {
+        super::ensure_emits(function);
+    }
+}
+
+fn add_constant(
+    module: &mut Module,
+    ty: Handle<Type>,
+    init: Handle<Expression>,
+) -> Handle<Constant> {
+    let span = module.const_expressions.get_span(init);
+    module.constants.append(
+        Constant {
+            name: None,
+            r#override: Override::None,
+            ty,
+            init,
+        },
+        span,
+    )
+}
+
+struct Folder {
+    /// For each expression in the function, the handle of its value in
+    /// the module's constant expression arena, if we have needed it yet.
+    values: Vec<Option<Handle<Expression>>>,
+}
+
+impl Folder {
+    /// Return a handle to the value of `function`'s expression `expr` in
+    /// `module`'s constant expression arena, or `None` if it isn't constant.
+    ///
+    /// Values are copied into the constant expression arena on demand, so
+    /// that only the operands of foldable expressions end up there.
+    fn value(
+        &mut self,
+        module: &mut Module,
+        function: &Function,
+        expr: Handle<Expression>,
+    ) -> Option<Handle<Expression>> {
+        if let Some(value) = self.values[expr.index()] {
+            return Some(value);
+        }
+
+        let span = function.expressions.get_span(expr);
+        let value = match function.expressions[expr] {
+            Expression::Literal(_) | Expression::ZeroValue(_) => module
+                .const_expressions
+                .append(function.expressions[expr].clone(), span),
+            Expression::Constant(c) => match module.constants[c].r#override {
+                Override::None => module.constants[c].init,
+                _ => return None,
+            },
+            Expression::Compose { ty, ref components } => {
+                let components = components
+                    .iter()
+                    .map(|&component| self.value(module, function, component))
+                    .collect::<Option<_>>()?;
+                module
+                    .const_expressions
+                    .append(Expression::Compose { ty, components }, span)
+            }
+            Expression::Splat { size, value } => {
+                let value = self.value(module, function, value)?;
+                module
+                    .const_expressions
+                    .append(Expression::Splat { size, value }, span)
+            }
+            _ => return None,
+        };
+
+        self.values[expr.index()] = Some(value);
+        Some(value)
+    }
+}
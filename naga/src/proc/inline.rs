@@ -0,0 +1,784 @@
+/*!
+Inlining function calls.
+
+In SPIR-V's logical addressing model, each pointer argument to
+`OpFunctionCall` must be a memory object declaration, that is, an
+`OpVariable` or an `OpFunctionParameter`, unless the module declares the
+`VariablePointers` capability. Naga IR has no such restriction: a call like
+`f(&s.field)` passes a pointer that only an access chain can produce.
+
+[`inline_pointer_calls`] replaces each such call with a copy of the callee's
+body, so the pointer never crosses a function boundary. [`inline_all_calls`]
+does the same for every call, for targets and tools that want a module
+without any.
+
+Inlining a callee can turn the calls in its body into ones that need
+inlining too, so this repeats until no such calls remain, which terminates
+because Naga IR has no recursion.
+*/
+
+use crate::arena::{Arena, Handle, Range};
+use crate::{
+    Block, Expression, FastHashMap, Function, LocalVariable, Module, Span, Statement, Type,
+    TypeInner, UniqueArena,
+};
+
+/// Decides whether a call from `caller` to `callee` with `arguments` should
+/// be inlined.
+type NeedsInlining = fn(
+    module: &Module,
+    caller: &Function,
+    callee: Handle<Function>,
+    arguments: &[Handle<Expression>],
+) -> bool;
+
+/// Return true if `caller` passes `arguments` to `callee` in a way that
+/// SPIR-V can only express with variable pointers.
+#[cfg(feature = "spv-out")]
+fn passes_pointer_expression(
+    module: &Module,
+    caller: &Function,
+    callee: Handle<Function>,
+    arguments: &[Handle<Expression>],
+) -> bool {
+    module.functions[callee]
+        .arguments
+        .iter()
+        .zip(arguments)
+        .any(|(declaration, &argument)| {
+            module.types[declaration.ty].inner.pointer_space().is_some()
+                && !matches!(
+                    caller.expressions[argument],
+                    Expression::LocalVariable(_)
+                        | Expression::GlobalVariable(_)
+                        | Expression::FunctionArgument(_)
+                )
+        })
+}
+
+/// A call that is being inlined.
+struct CallSite {
+    function: Handle<Function>,
+    arguments: Vec<Handle<Expression>>,
+    result: Option<Handle<Expression>>,
+    /// The call's position among all [`Call`] statements in the caller, in
+    /// the order [`find_calls`] visits them.
+    ///
+    /// [`Call`]: Statement::Call
+    ordinal: usize,
+    /// Whether the call is in a loop, so that it may run more than once.
+    in_loop: bool,
+}
+
+/// Append the calls in `block` that need inlining to `calls`.
+///
+/// `ordinal` counts every call visited so far.
+fn find_calls(
+    module: &Module,
+    caller: &Function,
+    block: &Block,
+    needs_inlining: NeedsInlining,
+    in_loop: bool,
+    ordinal: &mut usize,
+    calls: &mut Vec<CallSite>,
+) {
+    for statement in block.iter() {
+        match *statement {
+            Statement::Call {
+                function,
+                ref arguments,
+                result,
+            } => {
+                if needs_inlining(module, caller, function, arguments) {
+                    calls.push(CallSite {
+                        function,
+                        arguments: arguments.clone(),
+                        result,
+                        ordinal: *ordinal,
+                        in_loop,
+                    });
+                }
+                *ordinal += 1;
+            }
+            Statement::Block(ref block) => {
+                find_calls(
+                    module,
+                    caller,
+                    block,
+                    needs_inlining,
+                    in_loop,
+                    ordinal,
+                    calls,
+                );
+            }
+            Statement::If {
+                ref accept,
+                ref reject,
+                ..
+            } => {
+                find_calls(
+                    module,
+                    caller,
+                    accept,
+                    needs_inlining,
+                    in_loop,
+                    ordinal,
+                    calls,
+                );
+                find_calls(
+                    module,
+                    caller,
+                    reject,
+                    needs_inlining,
+                    in_loop,
+                    ordinal,
+                    calls,
+                );
+            }
+            Statement::Switch { ref cases, .. } => {
+                for case in cases.iter() {
+                    find_calls(
+                        module,
+                        caller,
+                        &case.body,
+                        needs_inlining,
+                        in_loop,
+                        ordinal,
+                        calls,
+                    );
+                }
+            }
+            Statement::Loop {
+                ref body,
+                ref continuing,
+                ..
+            } => {
+                find_calls(module, caller, body, needs_inlining, true, ordinal, calls);
+                find_calls(
+                    module,
+                    caller,
+                    continuing,
+                    needs_inlining,
+                    true,
+                    ordinal,
+                    calls,
+                );
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Return true if some call in `module` passes a pointer that SPIR-V can
+/// only pass with variable pointers.
+#[cfg(feature = "spv-out")]
+pub(crate) fn has_pointer_calls(module: &Module) -> bool {
+    let functions = module
+        .functions
+        .iter()
+        .map(|(_, function)| function)
+        .chain(module.entry_points.iter().map(|ep| &ep.function));
+    functions.into_iter().any(|function| {
+        let mut calls = Vec::new();
+        find_calls(
+            module,
+            function,
+            &function.body,
+            passes_pointer_expression,
+            false,
+            &mut 0,
+            &mut calls,
+        );
+        !calls.is_empty()
+    })
+}
+
+/// Inline every call in `module` that passes a pointer that SPIR-V can only
+/// pass with variable pointers.
+///
+/// Inlined callees stay in the module, since other calls may still use them.
+#[cfg(feature = "spv-out")]
+pub(crate) fn inline_pointer_calls(module: &mut Module) {
+    inline(module, passes_pointer_expression);
+}
+
+/// Inline every function call in `module`.
+///
+/// Afterwards, no function or entry point contains a [`Call`] statement.
+/// Each call is replaced by a copy of its callee's body: the callee's locals
+/// become locals of the caller, its arguments are replaced by the values
+/// passed, and its [`Return`]s store the result to a new local and leave
+/// the copied body. The copied expressions and statements keep the
+/// callee's spans.
+///
+/// The callees themselves stay in the module; run `compact::compact` or
+/// ignore them as needed.
+///
+/// [`Call`]: Statement::Call
+/// [`Return`]: Statement::Return
+pub fn inline_all_calls(module: &mut Module) {
+    inline(module, |_, _, _, _| true);
+}
+
+fn inline(module: &mut Module, needs_inlining: NeedsInlining) {
+    let bool_ty = module.types.insert(
+        Type {
+            name: None,
+            inner: TypeInner::Scalar(crate::Scalar::BOOL),
+        },
+        Span::UNDEFINED,
+    );
+
+    // Callees always precede their callers, so each callee has already had
+    // its own calls inlined by the time we copy it.
+    let handles: Vec<_> = module.functions.iter().map(|(handle, _)| handle).collect();
+    for handle in handles {
+        let mut function = std::mem::take(module.functions.get_mut(handle));
+        inline_calls(module, &mut function, needs_inlining, bool_ty);
+        *module.functions.get_mut(handle) = function;
+    }
+    for index in 0..module.entry_points.len() {
+        let mut function = std::mem::take(&mut module.entry_points[index].function);
+        inline_calls(module, &mut function, needs_inlining, bool_ty);
+        module.entry_points[index].function = function;
+    }
+}
+
+fn inline_calls(
+    module: &Module,
+    function: &mut Function,
+    needs_inlining: NeedsInlining,
+    bool_ty: Handle<Type>,
+) {
+    loop {
+        let mut calls = Vec::new();
+        find_calls(
+            module,
+            function,
+            &function.body,
+            needs_inlining,
+            false,
+            &mut 0,
+            &mut calls,
+        );
+        if calls.is_empty() {
+            break;
+        }
+        inline_call_sites(module, function, bool_ty, calls);
+    }
+}
+
+/// Replace `calls` in `function` with copies of their callees' bodies.
+///
+/// The copied expressions need to precede the uses of each call's result,
+/// so this builds a new expression arena with each callee's expressions
+/// spliced in where its result was, and adjusts every handle in the
+/// function to match.
+///
+/// Front ends put a call's arguments before its result, but other
+/// producers of Naga IR needn't, so an expression whose result or operands
+/// aren't ready yet is moved later in the arena, until they are.
+fn inline_call_sites(
+    module: &Module,
+    function: &mut Function,
+    bool_ty: Handle<Type>,
+    calls: Vec<CallSite>,
+) {
+    let old = std::mem::take(&mut function.expressions);
+    let old_local_count = function.local_variables.len();
+    let mut named = Vec::new();
+
+    let by_result: FastHashMap<_, _> = calls
+        .iter()
+        .enumerate()
+        .filter_map(|(index, call)| Some((call.result?, index)))
+        .collect();
+    let mut bodies: Vec<Option<Block>> = (0..calls.len()).map(|_| None).collect();
+
+    let mut map: Vec<Option<Handle<Expression>>> = vec![None; old.len()];
+    let ready = |map: &[Option<_>], handle: Handle<Expression>| {
+        let mut ready = true;
+        match by_result.get(&handle) {
+            Some(&index) => calls[index]
+                .arguments
+                .iter()
+                .for_each(|argument| ready &= map[argument.index()].is_some()),
+            None => crate::proc::for_each_operand(&old[handle], |operand| {
+                ready &= map[operand.index()].is_some();
+            }),
+        }
+        ready
+    };
+    let mut deferred = Vec::new();
+    for (handle, _) in old.iter() {
+        deferred.push(handle);
+        while let Some(position) = deferred.iter().position(|&handle| ready(&map, handle)) {
+            let handle = deferred.remove(position);
+            let new = match by_result.get(&handle) {
+                Some(&index) => {
+                    let call = &calls[index];
+                    let arguments = call
+                        .arguments
+                        .iter()
+                        .map(|argument| map[argument.index()].unwrap())
+                        .collect();
+                    let mut splicer = Splicer {
+                        types: &module.types,
+                        expressions: &mut function.expressions,
+                        local_variables: &mut function.local_variables,
+                        named: &mut named,
+                        bool_ty,
+                    };
+                    let (body, result) = splicer.splice(module, call, arguments);
+                    bodies[index] = Some(body);
+                    result.unwrap()
+                }
+                None => {
+                    let mut expr = old[handle].clone();
+                    crate::proc::for_each_operand_mut(&mut expr, |operand| {
+                        *operand = map[operand.index()].unwrap();
+                    });
+                    function.expressions.append(expr, old.get_span(handle))
+                }
+            };
+            map[handle.index()] = Some(new);
+        }
+    }
+    // Naga IR has no cycles, so everything was ready eventually.
+    let map: Vec<_> = map.into_iter().map(Option::unwrap).collect();
+
+    for (index, call) in calls.iter().enumerate() {
+        if call.result.is_none() {
+            let arguments = call
+                .arguments
+                .iter()
+                .map(|argument| map[argument.index()])
+                .collect();
+            let mut splicer = Splicer {
+                types: &module.types,
+                expressions: &mut function.expressions,
+                local_variables: &mut function.local_variables,
+                named: &mut named,
+                bool_ty,
+            };
+            let (body, _) = splicer.splice(module, call, arguments);
+            bodies[index] = Some(body);
+        }
+    }
+
+    for (_, local) in function.local_variables.iter_mut().take(old_local_count) {
+        if let Some(ref mut init) = local.init {
+            *init = map[init.index()];
+        }
+    }
+    function.named_expressions = std::mem::take(&mut function.named_expressions)
+        .into_iter()
+        .map(|(handle, name)| (map[handle.index()], name))
+        .chain(named)
+        .collect();
+
+    let by_ordinal: FastHashMap<_, _> = calls
+        .iter()
+        .enumerate()
+        .map(|(index, call)| (call.ordinal, index))
+        .collect();
+    let mut rebuilder = Rebuilder {
+        map: &map,
+        calls: &calls,
+        by_ordinal: &by_ordinal,
+        bodies: &mut bodies,
+        ordinal: 0,
+    };
+    function.body = rebuilder.rebuild(std::mem::take(&mut function.body));
+}
+
+/// Copies a callee's locals, expressions and body into its caller.
+struct Splicer<'a> {
+    types: &'a UniqueArena<Type>,
+    expressions: &'a mut Arena<Expression>,
+    local_variables: &'a mut Arena<LocalVariable>,
+    named: &'a mut Vec<(Handle<Expression>, String)>,
+    bool_ty: Handle<Type>,
+}
+
+/// How to translate a callee's `Return` statements.
+struct Returns {
+    /// The local holding the callee's result, if it has one.
+    result: Option<Handle<Expression>>,
+    /// A local recording whether the callee has returned, if it returns
+    /// from inside a loop or switch, where `Break` can't leave the body.
+    returned: Option<Handle<Expression>>,
+    /// Whether the body is wrapped in a loop to `Break` out of.
+    wrapped: bool,
+}
+
+impl Splicer<'_> {
+    fn append(&mut self, expr: Expression) -> Handle<Expression> {
+        self.expressions.append(expr, Span::UNDEFINED)
+    }
+
+    /// Append `Emit` statements to `block` for the expressions from `start`
+    /// on that need them.
+    fn emit_from(&self, start: usize, block: &mut Block) {
+        let mut run: Option<(Handle<Expression>, Handle<Expression>)> = None;
+        let flush = |run: &mut Option<_>, block: &mut Block| {
+            if let Some((first, last)) = run.take() {
+                block.push(
+                    Statement::Emit(Range::new_from_bounds(first, last)),
+                    Span::UNDEFINED,
+                );
+            }
+        };
+        for (handle, expr) in self.expressions.iter().skip(start) {
+            if expr.needs_pre_emit() {
+                flush(&mut run, block);
+            } else {
+                run = Some((run.map_or(handle, |(first, _)| first), handle));
+            }
+        }
+        flush(&mut run, block);
+    }
+
+    /// Append a fresh copy of the expression `handle` refers to, and all
+    /// its operands.
+    fn copy_tree(&mut self, handle: Handle<Expression>) -> Handle<Expression> {
+        let mut expr = self.expressions[handle].clone();
+        crate::proc::for_each_operand_mut(&mut expr, |operand| {
+            *operand = self.copy_tree(*operand);
+        });
+        self.append(expr)
+    }
+
+    /// Splice `call`'s callee into the caller, passing `arguments`, the
+    /// caller's new handles for the call's arguments.
+    ///
+    /// Return the statements replacing the call, and the expression
+    /// replacing its result, if any.
+    fn splice(
+        &mut self,
+        module: &Module,
+        call: &CallSite,
+        arguments: Vec<Handle<Expression>>,
+    ) -> (Block, Option<Handle<Expression>>) {
+        let callee = &module.functions[call.function];
+
+        let locals: Vec<_> = callee
+            .local_variables
+            .iter()
+            .map(|(handle, local)| {
+                self.local_variables.append(
+                    LocalVariable {
+                        name: local.name.clone(),
+                        ty: local.ty,
+                        init: None,
+                    },
+                    callee.local_variables.get_span(handle),
+                )
+            })
+            .collect();
+
+        let mut cmap = Vec::with_capacity(callee.expressions.len());
+        for (handle, expr) in callee.expressions.iter() {
+            let span = callee.expressions.get_span(handle);
+            let new = match *expr {
+                Expression::FunctionArgument(index) => arguments[index as usize],
+                Expression::LocalVariable(local) => self
+                    .expressions
+                    .append(Expression::LocalVariable(locals[local.index()]), span),
+                ref expr => {
+                    let mut expr = expr.clone();
+                    crate::proc::for_each_operand_mut(&mut expr, |operand| {
+                        *operand = cmap[operand.index()];
+                    });
+                    self.expressions.append(expr, span)
+                }
+            };
+            cmap.push(new);
+        }
+        for (&handle, name) in callee.named_expressions.iter() {
+            if !matches!(callee.expressions[handle], Expression::FunctionArgument(_)) {
+                self.named.push((cmap[handle.index()], name.clone()));
+            }
+        }
+
+        let mut block = Block::new();
+        for ((_, local), &new) in callee.local_variables.iter().zip(locals.iter()) {
+            let init = local.init.map(|init| cmap[init.index()]);
+            self.local_variables.get_mut(new).init = init;
+
+            // Locals start out with their initial values each time the
+            // callee runs, but a local's initializer only applies once.
+            if call.in_loop && !matches!(self.types[local.ty].inner, TypeInner::RayQuery) {
+                let ty = local.ty;
+                let pointer = self.append(Expression::LocalVariable(new));
+                let start = self.expressions.len();
+                let value = match init {
+                    Some(init) => self.copy_tree(init),
+                    None => self.append(Expression::ZeroValue(ty)),
+                };
+                self.emit_from(start, &mut block);
+                block.push(Statement::Store { pointer, value }, Span::UNDEFINED);
+            }
+        }
+
+        let result = callee.result.as_ref().map(|result| {
+            let local = self.local_variables.append(
+                LocalVariable {
+                    name: callee.name.as_ref().map(|name| format!("{name}_result")),
+                    ty: result.ty,
+                    init: None,
+                },
+                Span::UNDEFINED,
+            );
+            self.append(Expression::LocalVariable(local))
+        });
+
+        // A `Return` can only be the last statement of the body, unless
+        // it's nested, in which case we need something to `Break` out of.
+        let wrapped = callee.body.iter().any(has_nested_return_in);
+        let returned = if returns_from_breakable(&callee.body) {
+            let local = self.local_variables.append(
+                LocalVariable {
+                    name: Some("returned".to_string()),
+                    ty: self.bool_ty,
+                    init: None,
+                },
+                Span::UNDEFINED,
+            );
+            let pointer = self.append(Expression::LocalVariable(local));
+            let value = self.append(Expression::Literal(crate::Literal::Bool(false)));
+            block.push(Statement::Store { pointer, value }, Span::UNDEFINED);
+            Some(pointer)
+        } else {
+            None
+        };
+        let returns = Returns {
+            result,
+            returned,
+            wrapped,
+        };
+
+        let mut body = self.copy_block(&callee.body, &cmap, &returns, 0);
+        if wrapped {
+            if !ends_in_terminator(&body) {
+                body.push(Statement::Break, Span::UNDEFINED);
+            }
+            block.push(
+                Statement::Loop {
+                    body,
+                    continuing: Block::new(),
+                    break_if: None,
+                    hint: None,
+                },
+                Span::UNDEFINED,
+            );
+        } else {
+            block.push(Statement::Block(body), Span::UNDEFINED);
+        }
+
+        let value = result.map(|pointer| self.append(Expression::Load { pointer }));
+        (block, value)
+    }
+
+    /// Copy the callee's `block` into the caller.
+    ///
+    /// `depth` is the number of loops and switches in the callee enclosing
+    /// `block`.
+    fn copy_block(
+        &mut self,
+        block: &Block,
+        cmap: &[Handle<Expression>],
+        returns: &Returns,
+        depth: usize,
+    ) -> Block {
+        let mut new = Block::with_capacity(block.len());
+        for (statement, &span) in block.span_iter() {
+            let mut statement = match *statement {
+                Statement::Emit(ref range) => {
+                    if let Some((first, last)) = range.first_and_last() {
+                        let range = Range::new_from_bounds(cmap[first.index()], cmap[last.index()]);
+                        new.push(Statement::Emit(range), span);
+                    }
+                    continue;
+                }
+                Statement::Return { value } => {
+                    if let (Some(pointer), Some(value)) = (returns.result, value) {
+                        let value = cmap[value.index()];
+                        new.push(Statement::Store { pointer, value }, span);
+                    }
+                    if depth > 0 {
+                        let pointer = returns.returned.unwrap();
+                        let value = self.append(Expression::Literal(crate::Literal::Bool(true)));
+                        new.push(Statement::Store { pointer, value }, span);
+                    }
+                    if returns.wrapped {
+                        new.push(Statement::Break, span);
+                    }
+                    continue;
+                }
+                ref other => other.clone(),
+            };
+            crate::proc::for_each_statement_operand_mut(&mut statement, |handle| {
+                *handle = cmap[handle.index()];
+            });
+
+            let nested = match statement {
+                Statement::Loop { .. } | Statement::Switch { .. } => depth + 1,
+                _ => depth,
+            };
+            let returns_inside = nested > depth && has_nested_return_in(&statement);
+            crate::proc::for_each_child_block_mut(&mut statement, |child| {
+                *child = self.copy_block(child, cmap, returns, nested);
+            });
+            new.push(statement, span);
+
+            // A `Break` for a `Return` inside a loop or switch only leaves
+            // that, so keep leaving until we're out of the body.
+            if returns_inside {
+                let pointer = returns.returned.unwrap();
+                let start = self.expressions.len();
+                let condition = self.append(Expression::Load { pointer });
+                self.emit_from(start, &mut new);
+                new.push(
+                    Statement::If {
+                        condition,
+                        accept: Block::from_vec(vec![Statement::Break]),
+                        reject: Block::new(),
+                        hint: None,
+                    },
+                    Span::UNDEFINED,
+                );
+            }
+        }
+        new
+    }
+}
+
+/// Return true if `statement` contains a `Return` in a child block.
+fn has_nested_return_in(statement: &Statement) -> bool {
+    let mut found = false;
+    for_each_child_block(statement, |child| found |= contains_return(child));
+    found
+}
+
+fn contains_return(block: &Block) -> bool {
+    block.iter().any(|statement| match *statement {
+        Statement::Return { .. } => true,
+        _ => has_nested_return_in(statement),
+    })
+}
+
+/// Return true if `block` has a `Return` inside a loop or switch.
+fn returns_from_breakable(block: &Block) -> bool {
+    block.iter().any(|statement| match *statement {
+        Statement::Loop { .. } | Statement::Switch { .. } => has_nested_return_in(statement),
+        _ => {
+            let mut found = false;
+            for_each_child_block(statement, |child| found |= returns_from_breakable(child));
+            found
+        }
+    })
+}
+
+fn for_each_child_block(statement: &Statement, mut f: impl FnMut(&Block)) {
+    match *statement {
+        Statement::Block(ref block) => f(block),
+        Statement::If {
+            ref accept,
+            ref reject,
+            ..
+        } => {
+            f(accept);
+            f(reject);
+        }
+        Statement::Switch { ref cases, .. } => cases.iter().for_each(|case| f(&case.body)),
+        Statement::Loop {
+            ref body,
+            ref continuing,
+            ..
+        } => {
+            f(body);
+            f(continuing);
+        }
+        _ => {}
+    }
+}
+
+fn ends_in_terminator(block: &Block) -> bool {
+    matches!(
+        block.last(),
+        Some(
+            &Statement::Break | &Statement::Continue | &Statement::Return { .. } | &Statement::Kill
+        )
+    )
+}
+
+/// Rebuilds the caller's body with its calls replaced.
+struct Rebuilder<'a> {
+    map: &'a [Handle<Expression>],
+    calls: &'a [CallSite],
+    by_ordinal: &'a FastHashMap<usize, usize>,
+    bodies: &'a mut [Option<Block>],
+    /// The number of `Call` statements visited so far.
+    ordinal: usize,
+}
+
+impl Rebuilder<'_> {
+    fn rebuild(&mut self, block: Block) -> Block {
+        let mut new = Block::with_capacity(block.len());
+        for (mut statement, span) in block.span_into_iter() {
+            match statement {
+                Statement::Emit(ref range) => {
+                    // Expressions that were moved may split the range, so
+                    // emit its new handles in order, in as few ranges as
+                    // possible.
+                    let mut handles: Vec<_> = range
+                        .clone()
+                        .map(|handle| self.map[handle.index()])
+                        .collect();
+                    handles.sort();
+                    let mut run: Option<(Handle<Expression>, Handle<Expression>)> = None;
+                    for handle in handles {
+                        match run {
+                            Some((first, last)) if last.index() + 1 == handle.index() => {
+                                run = Some((first, handle));
+                            }
+                            _ => {
+                                if let Some((first, last)) = run.replace((handle, handle)) {
+                                    let range = Range::new_from_bounds(first, last);
+                                    new.push(Statement::Emit(range), span);
+                                }
+                            }
+                        }
+                    }
+                    if let Some((first, last)) = run {
+                        new.push(Statement::Emit(Range::new_from_bounds(first, last)), span);
+                    }
+                    continue;
+                }
+                Statement::Call { .. } => {
+                    let ordinal = self.ordinal;
+                    self.ordinal += 1;
+                    if let Some(&index) = self.by_ordinal.get(&ordinal) {
+                        let body = self.bodies[index].take().unwrap();
+                        new.push(Statement::Block(body), span);
+                        if let Some(result) = self.calls[index].result {
+                            let value = self.map[result.index()];
+                            new.push(Statement::Emit(Range::new_from_bounds(value, value)), span);
+                        }
+                        continue;
+                    }
+                }
+                _ => {}
+            }
+            crate::proc::for_each_statement_operand_mut(&mut statement, |handle| {
+                *handle = self.map[handle.index()];
+            });
+            crate::proc::for_each_child_block_mut(&mut statement, |child| {
+                *child = self.rebuild(std::mem::take(child));
+            });
+            new.push(statement, span);
+        }
+        new
+    }
+}
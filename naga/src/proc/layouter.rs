@@ -106,8 +106,14 @@ impl TypeLayout {
 /// A `Layouter` may be indexed by `Handle<Type>` values: `layouter[handle]` is the
 /// layout of the type whose handle is `handle`.
 ///
+/// Arrays whose length is [`Dynamic`] or [`Pending`] on an override are laid
+/// out as if they held a single element. Checking their actual size is left
+/// to runtime or pipeline creation.
+///
 /// [WGSL §4.3.7, "Memory Layout"](https://gpuweb.github.io/gpuweb/wgsl/#memory-layouts)
-#[derive(Debug, Default)]
+/// [`Dynamic`]: crate::ArraySize::Dynamic
+/// [`Pending`]: crate::ArraySize::Pending
+#[derive(Clone, Debug, Default)]
 #[cfg_attr(feature = "serialize", derive(serde::Serialize))]
 #[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
 pub struct Layouter {
@@ -130,6 +136,8 @@ pub enum LayoutErrorInner {
     InvalidStructMemberType(u32, Handle<crate::Type>),
     #[error("Type width must be a power of two")]
     NonPowerOfTwoWidth,
+    #[error("Type is too large")]
+    TooLarge,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, thiserror::Error)]
@@ -169,7 +177,10 @@ impl Layouter {
         use crate::TypeInner as Ti;
 
         for (ty_handle, ty) in gctx.types.iter().skip(self.layouts.len()) {
-            let size = ty.inner.size(gctx);
+            let size = ty
+                .inner
+                .try_size(gctx)
+                .ok_or(LayoutErrorInner::TooLarge.with(ty_handle))?;
             let layout = match ty.inner {
                 Ti::Scalar(scalar) | Ti::Atomic(scalar) => {
                     let alignment = Alignment::new(scalar.width as u32)
@@ -3,22 +3,36 @@
 */
 
 mod constant_evaluator;
+mod emits;
 mod emitter;
+mod fold;
 pub mod index;
+mod inline;
 mod layouter;
 mod namer;
+mod rename;
 mod terminator;
 mod typifier;
 
 pub use constant_evaluator::{
     ConstantEvaluator, ConstantEvaluatorError, ExpressionConstnessTracker,
 };
+pub use emits::ensure_emits;
+pub(crate) use emits::{
+    for_each_child_block_mut, for_each_operand, for_each_operand_mut,
+    for_each_statement_operand_mut,
+};
 pub use emitter::Emitter;
+pub use fold::fold_constants;
 pub use index::{BoundsCheckPolicies, BoundsCheckPolicy, IndexableLength, IndexableLengthError};
+pub use inline::inline_all_calls;
+#[cfg(feature = "spv-out")]
+pub(crate) use inline::{has_pointer_calls, inline_pointer_calls};
 pub use layouter::{Alignment, LayoutError, LayoutErrorInner, Layouter, TypeLayout};
 pub use namer::{EntryPointIndex, NameKey, Namer};
+pub use rename::{rename_entry_point, set_global_name, RenameError};
 pub use terminator::ensure_block_returns;
-pub use typifier::{ResolveContext, ResolveError, TypeResolution};
+pub use typifier::{resolve_expression_type, ResolveContext, ResolveError, TypeResolution};
 
 impl From<super::StorageFormat> for super::ScalarKind {
     fn from(format: super::StorageFormat) -> Self {
@@ -287,8 +301,18 @@ impl super::TypeInner {
     }
 
     /// Get the size of this type.
-    pub fn size(&self, _gctx: GlobalCtx) -> u32 {
-        match *self {
+    ///
+    /// Panics if the size doesn't fit in a `u32`, which the validator rules
+    /// out. Use [`try_size`] on types that haven't been validated.
+    ///
+    /// [`try_size`]: Self::try_size
+    pub fn size(&self, gctx: GlobalCtx) -> u32 {
+        self.try_size(gctx).expect("type is too large")
+    }
+
+    /// Get the size of this type, or `None` if it doesn't fit in a `u32`.
+    pub fn try_size(&self, _gctx: GlobalCtx) -> Option<u32> {
+        Some(match *self {
             Self::Scalar(scalar) | Self::Atomic(scalar) => scalar.width as u32,
             Self::Vector { size, scalar } => size as u32 * scalar.width as u32,
             // matrices are treated as arrays of aligned columns
@@ -305,10 +329,12 @@ impl super::TypeInner {
             } => {
                 let count = match size {
                     super::ArraySize::Constant(count) => count.get(),
-                    // A dynamically-sized array has to have at least one element
-                    super::ArraySize::Dynamic => 1,
+                    // A dynamically-sized array has to have at least one element,
+                    // and so does one sized by an override, whose real size
+                    // is only checked at pipeline creation time.
+                    super::ArraySize::Pending(_) | super::ArraySize::Dynamic => 1,
                 };
-                count * stride
+                return count.checked_mul(stride);
             }
             Self::Struct { span, .. } => span,
             Self::Image { .. }
@@ -316,7 +342,7 @@ impl super::TypeInner {
             | Self::AccelerationStructure
             | Self::RayQuery
             | Self::BindingArray { .. } => 0,
-        }
+        })
     }
 
     /// Return the canonical form of `self`, or `None` if it's already in
@@ -408,6 +434,38 @@ impl super::TypeInner {
             _ => return None,
         })
     }
+
+    /// If a vertex input of this type must be split across several
+    /// consecutive locations, return how many it takes and the type of the
+    /// value each one holds.
+    ///
+    /// Matrices take one location per column, and fixed-size arrays of
+    /// scalars or vectors take one per element.
+    pub fn vertex_input_split(
+        &self,
+        types: &crate::UniqueArena<crate::Type>,
+    ) -> Option<(u32, crate::TypeInner)> {
+        match *self {
+            Self::Matrix {
+                columns,
+                rows,
+                scalar,
+            } => Some((columns as u32, Self::Vector { size: rows, scalar })),
+            Self::Array {
+                base,
+                size: crate::ArraySize::Constant(len),
+                ..
+            } => match types[base].inner {
+                ref element @ (Self::Scalar(scalar) | Self::Vector { scalar, .. })
+                    if scalar.kind.is_numeric() =>
+                {
+                    Some((len.get(), element.clone()))
+                }
+                _ => None,
+            },
+            _ => None,
+        }
+    }
 }
 
 impl super::AddressSpace {
@@ -467,6 +525,7 @@ impl super::MathFunction {
             Self::Pow => 2,
             // geometry
             Self::Dot => 2,
+            Self::Dot4I8Packed | Self::Dot4U8Packed => 2,
             Self::Outer => 2,
             Self::Cross => 2,
             Self::Distance => 2,
@@ -486,6 +545,7 @@ impl super::MathFunction {
             Self::Inverse => 1,
             Self::Transpose => 1,
             Self::Determinant => 1,
+            Self::QuantizeToF16 => 1,
             // bits
             Self::CountTrailingZeros => 1,
             Self::CountLeadingZeros => 1,
@@ -620,14 +680,39 @@ impl super::ImageClass {
     pub const fn is_multisampled(self) -> bool {
         match self {
             crate::ImageClass::Sampled { multi, .. } | crate::ImageClass::Depth { multi } => multi,
-            crate::ImageClass::Storage { .. } => false,
+            crate::ImageClass::Storage { .. } | crate::ImageClass::External => false,
         }
     }
 
     pub const fn is_mipmapped(self) -> bool {
         match self {
             crate::ImageClass::Sampled { multi, .. } | crate::ImageClass::Depth { multi } => !multi,
-            crate::ImageClass::Storage { .. } => false,
+            crate::ImageClass::Storage { .. } | crate::ImageClass::External => false,
+        }
+    }
+}
+
+#[cfg(any(feature = "wgsl-in", feature = "wgsl-out"))]
+impl crate::EnableExtensions {
+    /// Return the name used to enable this extension in an `enable`
+    /// directive.
+    ///
+    /// `self` should contain exactly one extension.
+    pub fn to_wgsl(self) -> &'static str {
+        if self == Self::F16 {
+            "f16"
+        } else if self == Self::DUAL_SOURCE_BLENDING {
+            "dual_source_blending"
+        } else if self == Self::CLIP_DISTANCES {
+            "clip_distances"
+        } else if self == Self::NAGA_CONTROL_FLOW_HINTS {
+            "naga_control_flow_hints"
+        } else if self == Self::NAGA_MEMORY_DECORATIONS {
+            "naga_memory_decorations"
+        } else if self == Self::NAGA_MESH_SHADER {
+            "naga_mesh_shader"
+        } else {
+            "<unknown extension>"
         }
     }
 }
@@ -688,7 +773,7 @@ impl GlobalCtx<'_> {
         self.eval_expr_to_literal_from(handle, self.const_expressions)
     }
 
-    fn eval_expr_to_literal_from(
+    pub(crate) fn eval_expr_to_literal_from(
         &self,
         handle: crate::Handle<crate::Expression>,
         arena: &crate::Arena<crate::Expression>,
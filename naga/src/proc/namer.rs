@@ -41,7 +41,7 @@ impl Namer {
     /// The return value is a valid identifier prefix in all of Naga's output languages,
     /// and it never ends with a `SEPARATOR` character.
     /// It is used as a key into the unique table.
-    fn sanitize<'s>(&self, string: &'s str) -> Cow<'s, str> {
+    pub(super) fn sanitize<'s>(&self, string: &'s str) -> Cow<'s, str> {
         let string = string
             .trim_start_matches(|c: char| c.is_numeric())
             .trim_end_matches(SEPARATOR);
@@ -102,7 +102,7 @@ impl Namer {
         // just going to throw away. The approach below double-hashes only when we create
         // a new entry, in which case the heap allocation of the owned key was more
         // expensive anyway.
-        match self.unique.get_mut(base.as_ref()) {
+        let name = match self.unique.get_mut(base.as_ref()) {
             Some(count) => {
                 *count += 1;
                 // Add the suffix. This may fit in base's existing allocation.
@@ -126,7 +126,12 @@ impl Namer {
                 self.unique.insert(base.into_owned(), 0);
                 suffixed
             }
+        };
+
+        if log::log_enabled!(log::Level::Debug) && name != label_raw {
+            log::debug!("renamed {:?} to {:?}", label_raw, name);
         }
+        name
     }
 
     pub fn call_or(&mut self, label: &Option<String>, fallback: &str) -> String {
@@ -0,0 +1,134 @@
+/*!
+Renaming entry points and global variables.
+
+Tools that post-process modules, like asset pipelines that normalize entry
+point names or prefix resource names per material, can use these instead of
+editing the source and parsing it again.
+
+The new names must be identifiers the [`Namer`] would use unchanged, and
+must not be WGSL keywords, so they survive a round trip through the WGSL
+backend. Backends whose languages reserve the name anyway, like `main` in
+MSL, still adjust it as usual and report the name they used, for example in
+the MSL backend's `TranslationInfo::entry_point_names`.
+
+[`Namer`]: super::Namer
+*/
+
+use super::Namer;
+use crate::{arena::Handle, GlobalVariable, Module};
+
+#[derive(Clone, Debug, PartialEq, thiserror::Error)]
+pub enum RenameError {
+    #[error("{0:?} is not a valid identifier")]
+    InvalidIdentifier(String),
+    #[error("{0:?} is a reserved word")]
+    Reserved(String),
+    #[error("No entry point is named {0:?}")]
+    MissingEntryPoint(String),
+    #[error("Another module-scope item is already named {0:?}")]
+    DuplicateName(String),
+}
+
+/// Check that `name` is legal for the things this module renames.
+fn check_identifier(name: &str) -> Result<(), RenameError> {
+    // The `Namer` adds a separator to names ending in digits, to keep them
+    // apart from its numeric suffixes.
+    if Namer::default().sanitize(name) != name || name.ends_with(char::is_numeric) {
+        return Err(RenameError::InvalidIdentifier(name.to_string()));
+    }
+    #[cfg(any(feature = "wgsl-in", feature = "wgsl-out"))]
+    if crate::keywords::wgsl::RESERVED.contains(&name) {
+        return Err(RenameError::Reserved(name.to_string()));
+    }
+    Ok(())
+}
+
+/// Return an error if a module-scope item other than `except` is named `name`.
+///
+/// Global variables, constants, functions and entry points share a
+/// namespace in every textual backend.
+fn check_unused(module: &Module, name: &str, except: Item) -> Result<(), RenameError> {
+    let globals = module
+        .global_variables
+        .iter()
+        .filter(|&(handle, _)| except != Item::GlobalVariable(handle))
+        .map(|(_, var)| var.name.as_deref());
+    let constants = module
+        .constants
+        .iter()
+        .map(|(_, constant)| constant.name.as_deref());
+    let functions = module
+        .functions
+        .iter()
+        .map(|(_, function)| function.name.as_deref());
+    let entry_points = module
+        .entry_points
+        .iter()
+        .filter(|ep| except != Item::EntryPoint(&ep.name))
+        .flat_map(|ep| [Some(ep.name.as_str()), ep.function.name.as_deref()]);
+
+    let used = globals
+        .chain(constants)
+        .chain(functions)
+        .chain(entry_points)
+        .any(|other| other == Some(name));
+    match used {
+        true => Err(RenameError::DuplicateName(name.to_string())),
+        false => Ok(()),
+    }
+}
+
+#[derive(PartialEq)]
+enum Item<'a> {
+    EntryPoint(&'a str),
+    GlobalVariable(Handle<GlobalVariable>),
+}
+
+/// Rename every entry point named `old` to `new`.
+///
+/// If an entry point's function has the same name as the entry point, rename
+/// the function as well.
+pub fn rename_entry_point(module: &mut Module, old: &str, new: &str) -> Result<(), RenameError> {
+    if !module.entry_points.iter().any(|ep| ep.name == old) {
+        return Err(RenameError::MissingEntryPoint(old.to_string()));
+    }
+    check_identifier(new)?;
+    check_unused(module, new, Item::EntryPoint(old))?;
+
+    for ep in module.entry_points.iter_mut().filter(|ep| ep.name == old) {
+        ep.name = new.to_string();
+        if ep.function.name.as_deref() == Some(old) {
+            ep.function.name = Some(new.to_string());
+        }
+    }
+    Ok(())
+}
+
+/// Set the name of the global variable `handle` to `name`.
+pub fn set_global_name(
+    module: &mut Module,
+    handle: Handle<GlobalVariable>,
+    name: &str,
+) -> Result<(), RenameError> {
+    check_identifier(name)?;
+    check_unused(module, name, Item::GlobalVariable(handle))?;
+    module.global_variables[handle].name = Some(name.to_string());
+    Ok(())
+}
+
+#[test]
+fn identifiers() {
+    assert_eq!(check_identifier("albedo_map"), Ok(()));
+    assert_eq!(check_identifier("main"), Ok(()));
+    for name in ["", "0tex", "tex_", "a__b", "tex-map", "tex2"] {
+        assert_eq!(
+            check_identifier(name),
+            Err(RenameError::InvalidIdentifier(name.to_string()))
+        );
+    }
+    #[cfg(any(feature = "wgsl-in", feature = "wgsl-out"))]
+    assert_eq!(
+        check_identifier("loop"),
+        Err(RenameError::Reserved("loop".to_string()))
+    );
+}
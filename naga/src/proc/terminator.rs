@@ -6,7 +6,12 @@
 /// e.g. when the user already has returns in if/else branches.
 pub fn ensure_block_returns(block: &mut crate::Block) {
     use crate::Statement as S;
-    match block.last_mut() {
+    // Debug markers have no effect, so look past any at the end.
+    let last = block
+        .iter_mut()
+        .rev()
+        .find(|statement| !matches!(**statement, S::DebugMarker(_)));
+    match last {
         Some(&mut S::Block(ref mut b)) => {
             ensure_block_returns(b);
         }
@@ -14,6 +19,7 @@ pub fn ensure_block_returns(block: &mut crate::Block) {
             condition: _,
             ref mut accept,
             ref mut reject,
+            hint: _,
         }) => {
             ensure_block_returns(accept);
             ensure_block_returns(reject);
@@ -35,9 +41,11 @@ pub fn ensure_block_returns(block: &mut crate::Block) {
             | S::ImageStore { .. }
             | S::Call { .. }
             | S::RayQuery { .. }
+            | S::MeshFunction(_)
             | S::Atomic { .. }
             | S::WorkGroupUniformLoad { .. }
-            | S::Barrier(_)),
+            | S::Barrier(_)
+            | S::DebugMarker(_)),
         )
         | None => block.push(S::Return { value: None }, Default::default()),
     }
@@ -110,6 +110,26 @@ impl TypeResolution {
             Self::Value(ref inner) => inner,
         }
     }
+
+    /// Return the kind of scalar this type holds, if it is a scalar, vector,
+    /// matrix, atomic, or a pointer to one of those without an arena handle.
+    ///
+    /// See [`TypeInner::scalar_kind`](crate::TypeInner::scalar_kind).
+    pub fn scalar_kind(&self, arena: &UniqueArena<crate::Type>) -> Option<crate::ScalarKind> {
+        self.inner_with(arena).scalar_kind()
+    }
+
+    /// Return the address space of this type, if it is a pointer.
+    pub fn pointer_space(&self, arena: &UniqueArena<crate::Type>) -> Option<crate::AddressSpace> {
+        self.inner_with(arena).pointer_space()
+    }
+
+    /// Return the size of this type in bytes.
+    ///
+    /// See [`TypeInner::size`](crate::TypeInner::size).
+    pub fn size(&self, gctx: super::GlobalCtx) -> u32 {
+        self.inner_with(gctx.types).size(gctx)
+    }
 }
 
 // Clone is only implemented for numeric variants of `TypeInner`.
@@ -181,6 +201,8 @@ pub enum ResolveError {
     FunctionArgumentNotFound(u32),
     #[error("Special type is not registered within the module")]
     MissingSpecialType,
+    #[error("Expression {0:?} is not defined before its use")]
+    ExpressionNotDefined(Handle<crate::Expression>),
 }
 
 pub struct ResolveContext<'a> {
@@ -211,6 +233,11 @@ impl<'a> ResolveContext<'a> {
         }
     }
 
+    /// Initialize a resolve context for the expressions of `function`.
+    pub fn with_function(module: &'a crate::Module, function: &'a crate::Function) -> Self {
+        Self::with_locals(module, &function.local_variables, &function.arguments)
+    }
+
     /// Determine the type of `expr`.
     ///
     /// The `past` argument must be a closure that can resolve the types of any
@@ -483,6 +510,10 @@ impl<'a> ResolveContext<'a> {
                         scalar: crate::Scalar { kind, width: 4 },
                         size: crate::VectorSize::Quad,
                     },
+                    crate::ImageClass::External => Ti::Vector {
+                        scalar: crate::Scalar::F32,
+                        size: crate::VectorSize::Quad,
+                    },
                     crate::ImageClass::Storage { format, .. } => Ti::Vector {
                         scalar: crate::Scalar {
                             kind: format.into(),
@@ -700,6 +731,8 @@ impl<'a> ResolveContext<'a> {
                                 format!("{fun:?}({other:?}, _)")
                             )),
                     },
+                    Mf::Dot4I8Packed => TypeResolution::Value(Ti::Scalar(crate::Scalar::I32)),
+                    Mf::Dot4U8Packed => TypeResolution::Value(Ti::Scalar(crate::Scalar::U32)),
                     Mf::Outer => {
                         let arg1 = arg1.ok_or_else(|| ResolveError::IncompatibleOperands(
                             format!("{fun:?}(_, None)")
@@ -739,7 +772,8 @@ impl<'a> ResolveContext<'a> {
                     Mf::Step |
                     Mf::SmoothStep |
                     Mf::Sqrt |
-                    Mf::InverseSqrt => res_arg.clone(),
+                    Mf::InverseSqrt |
+                    Mf::QuantizeToF16 => res_arg.clone(),
                     Mf::Transpose => match *res_arg.inner_with(types) {
                         Ti::Matrix {
                             columns,
@@ -886,8 +920,108 @@ impl<'a> ResolveContext<'a> {
     }
 }
 
+/// Determine the type of the expression `expr` in `function`.
+///
+/// This resolves the types of all of `function`'s expressions up to and
+/// including `expr`, and doesn't require the module to have been validated.
+/// When asking about many expressions in the same function, use a
+/// [`front::Typifier`] instead, which keeps its results around.
+///
+/// If `expr` is not one of `function`'s expressions, or one of the
+/// expressions it depends on refers to a later one, this returns
+/// [`ResolveError::ExpressionNotDefined`].
+///
+/// ```
+/// # #[cfg(feature = "wgsl-in")] {
+/// use naga::proc::resolve_expression_type;
+///
+/// let module = naga::front::wgsl::parse_str(
+///     "
+///     @group(0) @binding(0) var<storage, read_write> data: array<vec4<f32>>;
+///
+///     @compute @workgroup_size(1)
+///     fn main() {
+///         data[1] = data[0];
+///     }
+///     ",
+/// )
+/// .unwrap();
+///
+/// let function = &module.entry_points[0].function;
+/// let (load, _) = function
+///     .expressions
+///     .iter()
+///     .find(|&(_, expr)| matches!(*expr, naga::Expression::Load { .. }))
+///     .unwrap();
+/// let naga::Expression::Load { pointer } = function.expressions[load] else {
+///     unreachable!()
+/// };
+///
+/// let loaded = resolve_expression_type(&module, function, load).unwrap();
+/// assert_eq!(loaded.scalar_kind(&module.types), Some(naga::ScalarKind::Float));
+/// assert_eq!(loaded.size(module.to_ctx()), 16);
+///
+/// let pointer = resolve_expression_type(&module, function, pointer).unwrap();
+/// assert!(matches!(
+///     pointer.pointer_space(&module.types),
+///     Some(naga::AddressSpace::Storage { .. })
+/// ));
+/// # }
+/// ```
+///
+/// [`front::Typifier`]: crate::front::Typifier
+pub fn resolve_expression_type(
+    module: &crate::Module,
+    function: &crate::Function,
+    expr: Handle<crate::Expression>,
+) -> Result<TypeResolution, ResolveError> {
+    if expr.index() >= function.expressions.len() {
+        return Err(ResolveError::ExpressionNotDefined(expr));
+    }
+    let ctx = ResolveContext::with_function(module, function);
+    let mut resolutions = Vec::with_capacity(expr.index() + 1);
+    for (_, expression) in function.expressions.iter().take(expr.index() + 1) {
+        let resolution = ctx.resolve(expression, |h| {
+            resolutions
+                .get(h.index())
+                .ok_or(ResolveError::ExpressionNotDefined(h))
+        })?;
+        resolutions.push(resolution);
+    }
+    Ok(resolutions.pop().unwrap())
+}
+
 #[test]
 fn test_error_size() {
     use std::mem::size_of;
     assert_eq!(size_of::<ResolveError>(), 32);
 }
+
+#[test]
+fn resolve_undefined_expression() {
+    use crate::{Expression, Literal, Span};
+
+    let module = crate::Module::default();
+    let mut function = crate::Function::default();
+    let one = function
+        .expressions
+        .append(Expression::Literal(Literal::I32(1)), Span::UNDEFINED);
+    let later = Handle::from_usize(2);
+    let negated = function.expressions.append(
+        Expression::Unary {
+            op: crate::UnaryOperator::Negate,
+            expr: later,
+        },
+        Span::UNDEFINED,
+    );
+
+    assert!(resolve_expression_type(&module, &function, one).is_ok());
+    assert_eq!(
+        resolve_expression_type(&module, &function, negated),
+        Err(ResolveError::ExpressionNotDefined(later))
+    );
+    assert_eq!(
+        resolve_expression_type(&module, &function, later),
+        Err(ResolveError::ExpressionNotDefined(later))
+    );
+}
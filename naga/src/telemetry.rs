@@ -0,0 +1,41 @@
+/*!
+Helpers for collecting compile-time statistics.
+
+These are only compiled with the `telemetry` feature. The statistics
+themselves live with the code that collects them; see
+[`back::spv::WriteStatistics`] and [`valid::ValidationStatistics`].
+
+[`back::spv::WriteStatistics`]: crate::back::spv::WriteStatistics
+[`valid::ValidationStatistics`]: crate::valid::ValidationStatistics
+*/
+
+use std::time::{Duration, Instant};
+
+/// Measures the consecutive phases of a longer operation.
+pub(crate) struct Stopwatch {
+    start: Instant,
+    last: Instant,
+}
+
+impl Stopwatch {
+    pub fn start() -> Self {
+        let now = Instant::now();
+        Stopwatch {
+            start: now,
+            last: now,
+        }
+    }
+
+    /// Return the time since the previous lap, or since the start.
+    pub fn lap(&mut self) -> Duration {
+        let now = Instant::now();
+        let elapsed = now - self.last;
+        self.last = now;
+        elapsed
+    }
+
+    /// Return the time since the stopwatch was started.
+    pub fn total(&self) -> Duration {
+        self.start.elapsed()
+    }
+}
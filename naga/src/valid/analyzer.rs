@@ -6,20 +6,19 @@ Figures out the following properties:
   - expression reference counts
 !*/
 
-use super::{ExpressionError, FunctionError, ModuleInfo, ShaderStages, ValidationFlags};
+use super::{
+    ExpressionError, FunctionError, ModuleInfo, ShaderStages, ValidationFlags, ValidationWarning,
+};
 use crate::span::{AddSpan as _, WithSpan};
 use crate::{
     arena::{Arena, Handle},
+    diagnostic_filter::{DiagnosticFilterNode, Severity, StandardFilterableTriggeringRule},
     proc::{ResolveContext, TypeResolution},
 };
 use std::ops;
 
 pub type NonUniformResult = Option<Handle<crate::Expression>>;
 
-// Remove this once we update our uniformity analysis and
-// add support for the `derivative_uniformity` diagnostic
-const DISABLE_UNIFORMITY_REQ_FOR_FRAGMENT_STAGE: bool = true;
-
 bitflags::bitflags! {
     /// Kinds of expressions that require uniform control flow.
     #[cfg_attr(feature = "serialize", derive(serde::Serialize))]
@@ -27,8 +26,37 @@ bitflags::bitflags! {
     #[derive(Clone, Copy, Debug, Eq, PartialEq)]
     pub struct UniformityRequirements: u8 {
         const WORK_GROUP_BARRIER = 0x1;
-        const DERIVATIVE = if DISABLE_UNIFORMITY_REQ_FOR_FRAGMENT_STAGE { 0 } else { 0x2 };
-        const IMPLICIT_LEVEL = if DISABLE_UNIFORMITY_REQ_FOR_FRAGMENT_STAGE { 0 } else { 0x4 };
+        const DERIVATIVE = 0x2;
+        const IMPLICIT_LEVEL = 0x4;
+    }
+}
+
+/// The diagnostic filters that apply to a function's expressions.
+#[derive(Clone, Copy)]
+struct DiagnosticFilters<'a> {
+    arena: &'a Arena<DiagnosticFilterNode>,
+    /// The innermost filter for the function's body.
+    leaf: Option<Handle<DiagnosticFilterNode>>,
+    /// See [`crate::Function::diagnostic_filter_ranges`].
+    ranges: &'a [(
+        crate::Range<crate::Expression>,
+        Handle<DiagnosticFilterNode>,
+    )],
+}
+
+impl DiagnosticFilters<'_> {
+    fn severity(
+        &self,
+        expr: Handle<crate::Expression>,
+        rule: StandardFilterableTriggeringRule,
+    ) -> Severity {
+        let index = expr.index() as u32;
+        let leaf = self
+            .ranges
+            .iter()
+            .find(|&&(ref range, _)| range.zero_based_index_range().contains(&index))
+            .map_or(self.leaf, |&(_, filter)| Some(filter));
+        DiagnosticFilterNode::severity(self.arena, leaf, rule)
     }
 }
 
@@ -145,10 +173,31 @@ pub struct SamplingKey {
 #[derive(Clone, Debug)]
 #[cfg_attr(feature = "serialize", derive(serde::Serialize))]
 #[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+/// Information about an expression in a function's body.
+///
+/// See [`FunctionInfo::expressions`].
 pub struct ExpressionInfo {
+    /// Whether this expression produces a uniform value, and whether it
+    /// requires uniform control flow.
     pub uniformity: Uniformity,
+
+    /// The number of times this expression is used, by other expressions,
+    /// by statements, and by local variable initializers.
+    ///
+    /// An expression used twice by the same expression or statement counts
+    /// twice. See [`expression_ref_count`] for the uses by other expressions
+    /// alone.
+    ///
+    /// [`expression_ref_count`]: ExpressionInfo::expression_ref_count
     pub ref_count: usize,
+
+    /// The number of times this expression is used by other expressions.
+    expression_ref_count: usize,
+
+    /// The global variable this expression points into, if any.
     assignable_global: Option<Handle<crate::GlobalVariable>>,
+
+    /// The type of this expression's value.
     pub ty: TypeResolution,
 }
 
@@ -157,6 +206,7 @@ impl ExpressionInfo {
         ExpressionInfo {
             uniformity: Uniformity::new(),
             ref_count: 0,
+            expression_ref_count: 0,
             assignable_global: None,
             // this doesn't matter at this point, will be overwritten
             ty: TypeResolution::Value(crate::TypeInner::Scalar(crate::Scalar {
@@ -165,6 +215,36 @@ impl ExpressionInfo {
             })),
         }
     }
+
+    /// Return the number of times this expression is used by other
+    /// expressions in the same function.
+    pub const fn expression_ref_count(&self) -> usize {
+        self.expression_ref_count
+    }
+
+    /// Return the number of times this expression is used by statements
+    /// and local variable initializers.
+    pub const fn statement_ref_count(&self) -> usize {
+        self.ref_count - self.expression_ref_count
+    }
+
+    /// Return `true` if this expression has the same value in all
+    /// invocations.
+    pub const fn is_uniform(&self) -> bool {
+        self.uniformity.non_uniform_result.is_none()
+    }
+
+    /// Return the global variable this expression points into, if any.
+    ///
+    /// This is the variable of a [`GlobalVariable`] expression, and of any
+    /// [`Access`] or [`AccessIndex`] chain starting from one.
+    ///
+    /// [`GlobalVariable`]: crate::Expression::GlobalVariable
+    /// [`Access`]: crate::Expression::Access
+    /// [`AccessIndex`]: crate::Expression::AccessIndex
+    pub const fn assignable_global(&self) -> Option<Handle<crate::GlobalVariable>> {
+        self.assignable_global
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -201,6 +281,23 @@ struct Sampling {
     sampler: GlobalOrArgument,
 }
 
+/// A user-defined vertex input that takes several consecutive locations.
+///
+/// Backends split such inputs into one attribute per location, so the
+/// vertex buffer layout must supply each of them separately.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+pub struct SplitVertexInput {
+    /// The location given by the input's binding, which is the first one
+    /// it takes.
+    pub location: u32,
+    /// The number of locations it takes.
+    pub count: u32,
+    /// The scalar or vector type of the value at each location.
+    pub ty: crate::TypeInner,
+}
+
 #[derive(Debug)]
 #[cfg_attr(feature = "serialize", derive(serde::Serialize))]
 #[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
@@ -263,6 +360,16 @@ pub struct FunctionInfo {
 
     /// Indicates that the function is using dual source blending.
     pub dual_source_blending: bool,
+
+    /// The vertex inputs of this entry point that take several locations,
+    /// in the order they are declared.
+    pub split_vertex_inputs: Vec<SplitVertexInput>,
+
+    /// Informational diagnostics about this function, in expression order.
+    ///
+    /// These are only collected for the [`ValidationFlags`] that ask for them.
+    #[cfg_attr(any(feature = "serialize", feature = "deserialize"), serde(skip))]
+    pub(super) warnings: Vec<WithSpan<ValidationWarning>>,
 }
 
 impl FunctionInfo {
@@ -272,6 +379,77 @@ impl FunctionInfo {
     pub const fn expression_count(&self) -> usize {
         self.expressions.len()
     }
+    /// Informational diagnostics about this function. See
+    /// [`ModuleInfo::warnings`].
+    pub fn warnings(&self) -> &[WithSpan<ValidationWarning>] {
+        &self.warnings
+    }
+    /// Iterate over the [`ExpressionInfo`] of each expression in this
+    /// function's body, along with the expression's handle.
+    ///
+    /// An optimizing pass can use this to find expressions worth duplicating
+    /// at each use, rather than computing once: for example, uniform values
+    /// used several times that read no memory and are not the results of
+    /// statements.
+    ///
+    /// ```
+    /// # use naga::{valid, Expression, Function, Handle};
+    /// fn duplicable(function: &Function, info: &valid::FunctionInfo) -> Vec<Handle<Expression>> {
+    ///     info.expressions()
+    ///         .filter(|&(handle, expr_info)| {
+    ///             expr_info.ref_count > 1
+    ///                 && expr_info.is_uniform()
+    ///                 && info.accessed_globals(handle, &function.expressions).is_empty()
+    ///                 && !matches!(
+    ///                     function.expressions[handle],
+    ///                     Expression::Load { .. }
+    ///                         | Expression::CallResult(_)
+    ///                         | Expression::AtomicResult { .. }
+    ///                         | Expression::WorkGroupUniformLoadResult { .. }
+    ///                         | Expression::RayQueryProceedResult
+    ///                 )
+    ///         })
+    ///         .map(|(handle, _)| handle)
+    ///         .collect()
+    /// }
+    /// ```
+    pub fn expressions(
+        &self,
+    ) -> impl ExactSizeIterator<Item = (Handle<crate::Expression>, &ExpressionInfo)> {
+        self.expressions
+            .iter()
+            .enumerate()
+            .map(|(index, info)| (Handle::from_usize(index), info))
+    }
+
+    /// Return the global variables that evaluating `handle` may access.
+    ///
+    /// These are the variables that `handle` or any of the expressions it
+    /// uses, directly or indirectly, point into. See
+    /// [`ExpressionInfo::assignable_global`].
+    ///
+    /// The result is sorted and has no duplicates. `expressions` must be the
+    /// arena of the function this `FunctionInfo` was built for.
+    pub fn accessed_globals(
+        &self,
+        handle: Handle<crate::Expression>,
+        expressions: &Arena<crate::Expression>,
+    ) -> Vec<Handle<crate::GlobalVariable>> {
+        let mut visited = crate::FastHashSet::default();
+        let mut pending = vec![handle];
+        let mut globals = Vec::new();
+        while let Some(handle) = pending.pop() {
+            if !visited.insert(handle) {
+                continue;
+            }
+            globals.extend(self[handle].assignable_global);
+            crate::proc::for_each_operand(&expressions[handle], |operand| pending.push(operand));
+        }
+        globals.sort();
+        globals.dedup();
+        globals
+    }
+
     pub fn dominates_global_use(&self, other: &Self) -> bool {
         for (self_global_uses, other_global_uses) in
             self.global_uses.iter().zip(other.global_uses.iter())
@@ -539,7 +717,10 @@ impl FunctionInfo {
             }
             // depends on the builtin or interpolation
             E::FunctionArgument(index) => {
-                let arg = &resolve_context.arguments[index as usize];
+                let arg = resolve_context
+                    .arguments
+                    .get(index as usize)
+                    .ok_or(crate::proc::ResolveError::FunctionArgumentNotFound(index))?;
                 let uniform = match arg.binding {
                     Some(crate::Binding::BuiltIn(built_in)) => match built_in {
                         // per-polygon built-ins are uniform
@@ -681,13 +862,28 @@ impl FunctionInfo {
                 condition,
                 accept,
                 reject,
-            } => Uniformity {
-                non_uniform_result: self
-                    .add_ref(condition)
-                    .or(self.add_ref(accept))
-                    .or(self.add_ref(reject)),
-                requirements: UniformityRequirements::empty(),
-            },
+            } => {
+                // A `Store` through a selected pointer may write to either
+                // operand's global, and `assignable_global` can only name
+                // one, so count the write against both here.
+                let global_use = match self[accept]
+                    .ty
+                    .inner_with(resolve_context.types)
+                    .pointer_space()
+                {
+                    Some(space) if space.access().contains(crate::StorageAccess::STORE) => {
+                        GlobalUse::READ | GlobalUse::WRITE
+                    }
+                    _ => GlobalUse::READ,
+                };
+                Uniformity {
+                    non_uniform_result: self
+                        .add_ref(condition)
+                        .or(self.add_ref_impl(accept, global_use))
+                        .or(self.add_ref_impl(reject, global_use)),
+                    requirements: UniformityRequirements::empty(),
+                }
+            }
             // explicit derivatives require uniform
             E::Derivative { expr, .. } => Uniformity {
                 //Note: taking a derivative of a uniform doesn't make it non-uniform
@@ -746,6 +942,7 @@ impl FunctionInfo {
         self.expressions[handle.index()] = ExpressionInfo {
             uniformity,
             ref_count: 0,
+            expression_ref_count: 0,
             assignable_global,
             ty,
         };
@@ -760,7 +957,8 @@ impl FunctionInfo {
     /// The parent control flow is uniform if `disruptor.is_none()`.
     ///
     /// Returns a `NonUniformControlFlow` error if any of the expressions in the block
-    /// require uniformity, but the current flow is non-uniform.
+    /// require uniformity, but the current flow is non-uniform. For derivatives,
+    /// `filters` decides whether that is an error, a warning, or nothing at all.
     #[allow(clippy::or_fun_call)]
     fn process_block(
         &mut self,
@@ -768,6 +966,7 @@ impl FunctionInfo {
         other_functions: &[FunctionInfo],
         mut disruptor: Option<UniformityDisruptor>,
         expression_arena: &Arena<crate::Expression>,
+        filters: &DiagnosticFilters,
     ) -> Result<FunctionUniformity, WithSpan<FunctionError>> {
         use crate::Statement as S;
 
@@ -784,8 +983,32 @@ impl FunctionInfo {
                             && !req.is_empty()
                         {
                             if let Some(cause) = disruptor {
-                                return Err(FunctionError::NonUniformControlFlow(req, expr, cause)
-                                    .with_span_handle(expr, expression_arena));
+                                let derivative = UniformityRequirements::DERIVATIVE
+                                    | UniformityRequirements::IMPLICIT_LEVEL;
+                                let severity = if derivative.contains(req) {
+                                    filters.severity(
+                                        expr,
+                                        StandardFilterableTriggeringRule::DerivativeUniformity,
+                                    )
+                                } else {
+                                    Severity::Error
+                                };
+                                match severity {
+                                    Severity::Error => {
+                                        return Err(FunctionError::NonUniformControlFlow(
+                                            req, expr, cause,
+                                        )
+                                        .with_span_handle(expr, expression_arena));
+                                    }
+                                    Severity::Warning | Severity::Info => self.warnings.push(
+                                        ValidationWarning::NonUniformDerivative {
+                                            handle: expr,
+                                            cause,
+                                        }
+                                        .with_span_handle(expr, expression_arena),
+                                    ),
+                                    Severity::Off => {}
+                                }
                             }
                         }
                         requirements |= req;
@@ -798,7 +1021,7 @@ impl FunctionInfo {
                         exit: ExitFlags::empty(),
                     }
                 }
-                S::Break | S::Continue => FunctionUniformity::new(),
+                S::Break | S::Continue | S::DebugMarker(_) => FunctionUniformity::new(),
                 S::Kill => FunctionUniformity {
                     result: Uniformity::new(),
                     exit: if disruptor.is_some() {
@@ -844,12 +1067,13 @@ impl FunctionInfo {
                     }
                 }
                 S::Block(ref b) => {
-                    self.process_block(b, other_functions, disruptor, expression_arena)?
+                    self.process_block(b, other_functions, disruptor, expression_arena, filters)?
                 }
                 S::If {
                     condition,
                     ref accept,
                     ref reject,
+                    hint: _,
                 } => {
                     let condition_nur = self.add_ref(condition);
                     let branch_disruptor =
@@ -859,12 +1083,14 @@ impl FunctionInfo {
                         other_functions,
                         branch_disruptor,
                         expression_arena,
+                        filters,
                     )?;
                     let reject_uniformity = self.process_block(
                         reject,
                         other_functions,
                         branch_disruptor,
                         expression_arena,
+                        filters,
                     )?;
                     accept_uniformity | reject_uniformity
                 }
@@ -883,6 +1109,7 @@ impl FunctionInfo {
                             other_functions,
                             case_disruptor,
                             expression_arena,
+                            filters,
                         )?;
                         case_disruptor = if case.fall_through {
                             case_disruptor.or(case_uniformity.exit_disruptor())
@@ -897,15 +1124,22 @@ impl FunctionInfo {
                     ref body,
                     ref continuing,
                     break_if,
+                    hint: _,
                 } => {
-                    let body_uniformity =
-                        self.process_block(body, other_functions, disruptor, expression_arena)?;
+                    let body_uniformity = self.process_block(
+                        body,
+                        other_functions,
+                        disruptor,
+                        expression_arena,
+                        filters,
+                    )?;
                     let continuing_disruptor = disruptor.or(body_uniformity.exit_disruptor());
                     let continuing_uniformity = self.process_block(
                         continuing,
                         other_functions,
                         continuing_disruptor,
                         expression_arena,
+                        filters,
                     )?;
                     if let Some(expr) = break_if {
                         let _ = self.add_ref(expr);
@@ -982,6 +1216,24 @@ impl FunctionInfo {
                     }
                     FunctionUniformity::new()
                 }
+                S::MeshFunction(
+                    crate::MeshFunction::SetMeshOutputs {
+                        vertex_count: first,
+                        primitive_count: second,
+                    }
+                    | crate::MeshFunction::SetVertex {
+                        index: first,
+                        value: second,
+                    }
+                    | crate::MeshFunction::SetPrimitive {
+                        index: first,
+                        value: second,
+                    },
+                ) => {
+                    let _ = self.add_ref(first);
+                    let _ = self.add_ref(second);
+                    FunctionUniformity::new()
+                }
             };
 
             disruptor = disruptor.or(uniformity.exit_disruptor());
@@ -1006,10 +1258,13 @@ impl ModuleInfo {
 
     /// Builds the `FunctionInfo` based on the function, and validates the
     /// uniform control flow if required by the expressions of this function.
+    ///
+    /// `functions` holds the information for the functions `fun` may call.
     pub(super) fn process_function(
         &self,
         fun: &crate::Function,
         module: &crate::Module,
+        functions: &[FunctionInfo],
         flags: ValidationFlags,
         capabilities: super::Capabilities,
     ) -> Result<FunctionInfo, WithSpan<FunctionError>> {
@@ -1023,6 +1278,8 @@ impl ModuleInfo {
             expressions: vec![ExpressionInfo::new(); fun.expressions.len()].into_boxed_slice(),
             sampling: crate::FastHashSet::default(),
             dual_source_blending: false,
+            split_vertex_inputs: Vec::new(),
+            warnings: Vec::new(),
         };
         let resolve_context =
             ResolveContext::with_locals(module, &fun.local_variables, &fun.arguments);
@@ -1031,7 +1288,7 @@ impl ModuleInfo {
             if let Err(source) = info.process_expression(
                 handle,
                 &fun.expressions,
-                &self.functions,
+                functions,
                 &resolve_context,
                 capabilities,
             ) {
@@ -1040,13 +1297,23 @@ impl ModuleInfo {
             }
         }
 
+        for info in info.expressions.iter_mut() {
+            info.expression_ref_count = info.ref_count;
+        }
+
         for (_, expr) in fun.local_variables.iter() {
             if let Some(init) = expr.init {
                 let _ = info.add_ref(init);
             }
         }
 
-        let uniformity = info.process_block(&fun.body, &self.functions, None, &fun.expressions)?;
+        let filters = DiagnosticFilters {
+            arena: &module.diagnostic_filters,
+            leaf: fun.diagnostic_filter_leaf,
+            ranges: &fun.diagnostic_filter_ranges,
+        };
+        let uniformity =
+            info.process_block(&fun.body, functions, None, &fun.expressions, &filters)?;
         info.uniformity = uniformity.result;
         info.may_kill = uniformity.exit.contains(ExitFlags::MAY_KILL);
 
@@ -1056,6 +1323,32 @@ impl ModuleInfo {
     pub fn get_entry_point(&self, index: usize) -> &FunctionInfo {
         &self.entry_points[index]
     }
+
+    /// Informational diagnostics about the module's functions, then its
+    /// entry points.
+    ///
+    /// These never affect whether the module is valid. The validator only
+    /// collects them for the [`ValidationFlags`] that ask for them, like
+    /// [`ValidationFlags::DYNAMIC_INDEXING`].
+    pub fn warnings(&self) -> impl Iterator<Item = &WithSpan<ValidationWarning>> {
+        self.functions
+            .iter()
+            .chain(self.entry_points.iter())
+            .flat_map(|info| info.warnings.iter())
+    }
+
+    /// Replace the information for `handle` with `info`, returning the old
+    /// information.
+    ///
+    /// This is meant for storing the result of
+    /// [`Validator::validate_function`](super::Validator::validate_function).
+    pub fn replace_function(
+        &mut self,
+        handle: Handle<crate::Function>,
+        info: FunctionInfo,
+    ) -> FunctionInfo {
+        std::mem::replace(&mut self.functions[handle.index()], info)
+    }
 }
 
 #[test]
@@ -1081,6 +1374,7 @@ fn uniform_control_flow() {
             ty,
             space: crate::AddressSpace::Handle,
             binding: None,
+            memory_decorations: crate::MemoryDecorations::empty(),
         },
         Default::default(),
     );
@@ -1091,6 +1385,7 @@ fn uniform_control_flow() {
             ty,
             binding: None,
             space: crate::AddressSpace::Uniform,
+            memory_decorations: crate::MemoryDecorations::empty(),
         },
         Default::default(),
     );
@@ -1136,6 +1431,8 @@ fn uniform_control_flow() {
         expressions: vec![ExpressionInfo::new(); expressions.len()].into_boxed_slice(),
         sampling: crate::FastHashSet::default(),
         dual_source_blending: false,
+        split_vertex_inputs: Vec::new(),
+        warnings: Vec::new(),
     };
     let resolve_context = ResolveContext {
         constants: &Arena::new(),
@@ -1163,6 +1460,26 @@ fn uniform_control_flow() {
     assert_eq!(info[non_uniform_global], GlobalUse::empty());
     assert_eq!(info[uniform_global], GlobalUse::QUERY);
 
+    // Make derivatives in non-uniform control flow an error.
+    let mut filter_arena = Arena::new();
+    let filter = filter_arena.append(
+        DiagnosticFilterNode {
+            inner: crate::diagnostic_filter::DiagnosticFilter {
+                new_severity: Severity::Error,
+                triggering_rule: crate::diagnostic_filter::FilterableTriggeringRule::Standard(
+                    StandardFilterableTriggeringRule::DerivativeUniformity,
+                ),
+            },
+            parent: None,
+        },
+        Default::default(),
+    );
+    let filters = DiagnosticFilters {
+        arena: &filter_arena,
+        leaf: Some(filter),
+        ranges: &[],
+    };
+
     let stmt_emit1 = S::Emit(emit_range_globals.clone());
     let stmt_if_uniform = S::If {
         condition: uniform_global_expr,
@@ -1175,13 +1492,15 @@ fn uniform_control_flow() {
             },
         ]
         .into(),
+        hint: None,
     };
     assert_eq!(
         info.process_block(
             &vec![stmt_emit1, stmt_if_uniform].into(),
             &[],
             None,
-            &expressions
+            &expressions,
+            &filters
         ),
         Ok(FunctionUniformity {
             result: Uniformity {
@@ -1206,6 +1525,7 @@ fn uniform_control_flow() {
         ]
         .into(),
         reject: crate::Block::new(),
+        hint: None,
     };
     {
         let block_info = info.process_block(
@@ -1213,21 +1533,18 @@ fn uniform_control_flow() {
             &[],
             None,
             &expressions,
+            &filters,
         );
-        if DISABLE_UNIFORMITY_REQ_FOR_FRAGMENT_STAGE {
-            assert_eq!(info[derivative_expr].ref_count, 2);
-        } else {
-            assert_eq!(
-                block_info,
-                Err(FunctionError::NonUniformControlFlow(
-                    UniformityRequirements::DERIVATIVE,
-                    derivative_expr,
-                    UniformityDisruptor::Expression(non_uniform_global_expr)
-                )
-                .with_span()),
-            );
-            assert_eq!(info[derivative_expr].ref_count, 1);
-        }
+        assert_eq!(
+            block_info,
+            Err(FunctionError::NonUniformControlFlow(
+                UniformityRequirements::DERIVATIVE,
+                derivative_expr,
+                UniformityDisruptor::Expression(non_uniform_global_expr)
+            )
+            .with_span()),
+        );
+        assert_eq!(info[derivative_expr].ref_count, 1);
     }
     assert_eq!(info[non_uniform_global], GlobalUse::READ);
 
@@ -1240,7 +1557,8 @@ fn uniform_control_flow() {
             &vec![stmt_emit3, stmt_return_non_uniform].into(),
             &[],
             Some(UniformityDisruptor::Return),
-            &expressions
+            &expressions,
+            &filters
         ),
         Ok(FunctionUniformity {
             result: Uniformity {
@@ -1267,7 +1585,8 @@ fn uniform_control_flow() {
             &vec![stmt_emit4, stmt_assign, stmt_kill, stmt_return_pointer].into(),
             &[],
             Some(UniformityDisruptor::Discard),
-            &expressions
+            &expressions,
+            &filters
         ),
         Ok(FunctionUniformity {
             result: Uniformity {
@@ -1279,3 +1598,146 @@ fn uniform_control_flow() {
     );
     assert_eq!(info[non_uniform_global], GlobalUse::READ | GlobalUse::WRITE);
 }
+
+#[test]
+fn expression_ref_counts() {
+    use crate::{Expression as E, Statement as S};
+
+    let mut module = crate::Module::default();
+    let ty = module.types.insert(
+        crate::Type {
+            name: None,
+            inner: crate::TypeInner::Scalar(crate::Scalar::F32),
+        },
+        Default::default(),
+    );
+    let uniform_global = module.global_variables.append(
+        crate::GlobalVariable {
+            name: None,
+            init: None,
+            ty,
+            space: crate::AddressSpace::Uniform,
+            binding: Some(crate::ResourceBinding {
+                group: 0,
+                binding: 0,
+            }),
+            memory_decorations: crate::MemoryDecorations::empty(),
+        },
+        Default::default(),
+    );
+    let private_global = module.global_variables.append(
+        crate::GlobalVariable {
+            name: None,
+            init: None,
+            ty,
+            space: crate::AddressSpace::Private,
+            binding: None,
+            memory_decorations: crate::MemoryDecorations::empty(),
+        },
+        Default::default(),
+    );
+
+    let mut fun = crate::Function {
+        result: Some(crate::FunctionResult { ty, binding: None }),
+        ..Default::default()
+    };
+    let ex = &mut fun.expressions;
+    let uniform_ptr = ex.append(E::GlobalVariable(uniform_global), Default::default());
+    let private_ptr = ex.append(E::GlobalVariable(private_global), Default::default());
+    let two = ex.append(E::Literal(crate::Literal::F32(2.0)), Default::default());
+    let emitted = ex.len();
+    let uniform_value = ex.append(
+        E::Load {
+            pointer: uniform_ptr,
+        },
+        Default::default(),
+    );
+    let private_value = ex.append(
+        E::Load {
+            pointer: private_ptr,
+        },
+        Default::default(),
+    );
+    let product = ex.append(
+        E::Binary {
+            op: crate::BinaryOperator::Multiply,
+            left: uniform_value,
+            right: two,
+        },
+        Default::default(),
+    );
+    let double = ex.append(
+        E::Binary {
+            op: crate::BinaryOperator::Add,
+            left: product,
+            right: product,
+        },
+        Default::default(),
+    );
+    let sum = ex.append(
+        E::Binary {
+            op: crate::BinaryOperator::Add,
+            left: double,
+            right: private_value,
+        },
+        Default::default(),
+    );
+    let emit_range = ex.range_from(emitted);
+    fun.body.push(S::Emit(emit_range), Default::default());
+    fun.body.push(
+        S::Store {
+            pointer: private_ptr,
+            value: double,
+        },
+        Default::default(),
+    );
+    fun.body
+        .push(S::Return { value: Some(sum) }, Default::default());
+    let handle = module.functions.append(fun, Default::default());
+
+    let module_info = super::Validator::new(ValidationFlags::all(), super::Capabilities::empty())
+        .validate(&module)
+        .unwrap();
+    let info = &module_info[handle];
+    let expressions = &module.functions[handle].expressions;
+    assert_eq!(info.expressions().len(), expressions.len());
+
+    // (expression, uses by expressions, uses by statements)
+    let counts = [
+        (uniform_ptr, 1, 0),
+        (private_ptr, 1, 1),
+        (two, 1, 0),
+        (uniform_value, 1, 0),
+        (private_value, 1, 0),
+        (product, 2, 0),
+        (double, 1, 1),
+        (sum, 0, 1),
+    ];
+    for (handle, expression_refs, statement_refs) in counts {
+        let expr_info = &info[handle];
+        assert_eq!(
+            expr_info.expression_ref_count(),
+            expression_refs,
+            "{handle:?}"
+        );
+        assert_eq!(
+            expr_info.statement_ref_count(),
+            statement_refs,
+            "{handle:?}"
+        );
+        assert_eq!(expr_info.ref_count, expression_refs + statement_refs);
+    }
+
+    assert!(info[double].is_uniform());
+    assert!(!info[private_value].is_uniform());
+    assert!(!info[sum].is_uniform());
+
+    assert_eq!(info[private_ptr].assignable_global(), Some(private_global));
+    assert_eq!(info[private_value].assignable_global(), None);
+    assert!(info.accessed_globals(two, expressions).is_empty());
+    assert_eq!(info.accessed_globals(double, expressions), [uniform_global]);
+    assert_eq!(
+        info.accessed_globals(sum, expressions),
+        [uniform_global, private_global]
+    );
+}
@@ -54,7 +54,7 @@ pub enum ExpressionError {
     ),
     #[error("Selecting is not possible")]
     InvalidSelectTypes,
-    #[error("Relational argument {0:?} is not a boolean vector")]
+    #[error("Relational argument {0:?} is not a boolean scalar or vector")]
     InvalidBooleanVector(Handle<crate::Expression>),
     #[error("Relational argument {0:?} is not a float")]
     InvalidFloatArgument(Handle<crate::Expression>),
@@ -92,12 +92,18 @@ pub enum ExpressionError {
     },
     #[error("Sample offset constant {1:?} doesn't match the image dimension {0:?}")]
     InvalidSampleOffset(crate::ImageDimension, Handle<crate::Expression>),
+    #[error("Sample offset {0:?} is not a constant")]
+    NonConstSampleOffset(Handle<crate::Expression>),
+    #[error("Sample offset {0:?} has component {1}, outside the range {min}..={max}", min = SAMPLE_OFFSET_RANGE.start(), max = SAMPLE_OFFSET_RANGE.end())]
+    SampleOffsetOutOfRange(Handle<crate::Expression>, i32),
     #[error("Depth reference {0:?} is not a scalar float")]
     InvalidDepthReference(Handle<crate::Expression>),
     #[error("Depth sample level can only be Auto or Zero")]
     InvalidDepthSampleLevel,
     #[error("Gather level can only be Zero")]
     InvalidGatherLevel,
+    #[error("External images can only be sampled at level Zero, without an offset or gather")]
+    InvalidExternalSample,
     #[error("Gather component {0:?} doesn't exist in the image")]
     InvalidGatherComponent(crate::SwizzleComponent),
     #[error("Gather can't be done for image dimension {0:?}")]
@@ -430,6 +436,13 @@ impl super::Validator {
                         multi: false,
                     } if gather.is_some() => false,
                     crate::ImageClass::Depth { multi: false } => true,
+                    crate::ImageClass::External => {
+                        if gather.is_some() || offset.is_some() || level != crate::SampleLevel::Zero
+                        {
+                            return Err(ExpressionError::InvalidExternalSample);
+                        }
+                        false
+                    }
                     _ => return Err(ExpressionError::InvalidImageClass(class)),
                 };
                 if comparison != depth_ref.is_some() || (comparison && !image_depth) {
@@ -472,6 +485,19 @@ impl super::Validator {
                             return Err(ExpressionError::InvalidSampleOffset(dim, const_expr));
                         }
                     }
+
+                    let mut components = Vec::with_capacity(num_components as usize);
+                    if !sample_offset_components(module, const_expr, &mut components) {
+                        return Err(ExpressionError::NonConstSampleOffset(const_expr));
+                    }
+                    if let Some(&component) = components
+                        .iter()
+                        .find(|component| !SAMPLE_OFFSET_RANGE.contains(component))
+                    {
+                        return Err(ExpressionError::SampleOffsetOutOfRange(
+                            const_expr, component,
+                        ));
+                    }
                 }
 
                 // check depth reference type
@@ -602,13 +628,15 @@ impl super::Validator {
 
                         match (sample, class.is_multisampled()) {
                             (None, false) => {}
-                            (Some(sample), true) => {
-                                if resolver[sample].scalar_kind() != Some(Sk::Sint) {
-                                    return Err(ExpressionError::InvalidImageOtherIndexType(
-                                        sample,
-                                    ));
+                            (Some(sample), true) => match resolver[sample] {
+                                Ti::Scalar(Sc {
+                                    kind: Sk::Sint | Sk::Uint,
+                                    width: _,
+                                }) => {}
+                                _ => {
+                                    return Err(ExpressionError::InvalidImageOtherIndexType(sample))
                                 }
-                            }
+                            },
                             _ => {
                                 return Err(ExpressionError::InvalidImageOtherIndex);
                             }
@@ -856,9 +884,21 @@ impl super::Validator {
                         width: _,
                     }) => {
                         // When `condition` is a single boolean, `accept` and
-                        // `reject` can be vectors or scalars.
+                        // `reject` can be vectors or scalars, or pointers if
+                        // the backend can select between those.
                         match *accept_inner {
                             Ti::Scalar { .. } | Ti::Vector { .. } => true,
+                            Ti::Pointer { .. } | Ti::ValuePointer { .. } => {
+                                if !self
+                                    .capabilities
+                                    .contains(super::Capabilities::POINTER_SELECT)
+                                {
+                                    return Err(ExpressionError::MissingCapabilities(
+                                        super::Capabilities::POINTER_SELECT,
+                                    ));
+                                }
+                                true
+                            }
                             _ => false,
                         }
                     }
@@ -902,8 +942,10 @@ impl super::Validator {
                 use crate::RelationalFunction as Rf;
                 let argument_inner = &resolver[argument];
                 match fun {
+                    // Of a single boolean, these are the identity.
                     Rf::All | Rf::Any => match *argument_inner {
-                        Ti::Vector {
+                        Ti::Scalar(Sc { kind: Sk::Bool, .. })
+                        | Ti::Vector {
                             scalar: Sc { kind: Sk::Bool, .. },
                             ..
                         } => {}
@@ -1148,6 +1190,22 @@ impl super::Validator {
                             ));
                         }
                     }
+                    Mf::Dot4I8Packed | Mf::Dot4U8Packed => {
+                        let arg1_ty = match (arg1_ty, arg2_ty, arg3_ty) {
+                            (Some(ty1), None, None) => ty1,
+                            _ => return Err(ExpressionError::WrongArgumentCount(fun)),
+                        };
+                        if *arg_ty != Ti::Scalar(Sc::U32) {
+                            return Err(ExpressionError::InvalidArgumentType(fun, 0, arg));
+                        }
+                        if *arg1_ty != Ti::Scalar(Sc::U32) {
+                            return Err(ExpressionError::InvalidArgumentType(
+                                fun,
+                                1,
+                                arg1.unwrap(),
+                            ));
+                        }
+                    }
                     Mf::Outer | Mf::Cross | Mf::Reflect => {
                         let arg1_ty = match (arg1_ty, arg2_ty, arg3_ty) {
                             (Some(ty1), None, None) => ty1,
@@ -1323,6 +1381,18 @@ impl super::Validator {
                             return Err(ExpressionError::InvalidArgumentType(fun, 0, arg));
                         }
                     }
+                    Mf::QuantizeToF16 => {
+                        if arg1_ty.is_some() || arg2_ty.is_some() || arg3_ty.is_some() {
+                            return Err(ExpressionError::WrongArgumentCount(fun));
+                        }
+                        match *arg_ty {
+                            Ti::Scalar(Sc::F32)
+                            | Ti::Vector {
+                                scalar: Sc::F32, ..
+                            } => {}
+                            _ => return Err(ExpressionError::InvalidArgumentType(fun, 0, arg)),
+                        }
+                    }
                     Mf::Transpose => {
                         if arg1_ty.is_some() || arg2_ty.is_some() || arg3_ty.is_some() {
                             return Err(ExpressionError::WrongArgumentCount(fun));
@@ -1547,7 +1617,7 @@ impl super::Validator {
                     // WorkGroupUniformLoad
                     .contains(TypeFlags::SIZED | TypeFlags::CONSTRUCTIBLE)
                 {
-                    ShaderStages::COMPUTE
+                    ShaderStages::COMPUTE_LIKE
                 } else {
                     return Err(ExpressionError::InvalidWorkGroupUniformLoadResultType(ty));
                 }
@@ -1629,6 +1699,57 @@ impl super::Validator {
     }
 }
 
+/// The range of texel offsets that image sampling operations accept.
+///
+/// SPIR-V, GLSL, HLSL and MSL all guarantee at least this range.
+const SAMPLE_OFFSET_RANGE: std::ops::RangeInclusive<i32> = -8..=7;
+
+/// Append the components of the sample offset `handle` to `components`.
+///
+/// `handle` refers to `module.const_expressions`. Return `false` if some
+/// component isn't a known `i32` value.
+fn sample_offset_components(
+    module: &crate::Module,
+    handle: Handle<crate::Expression>,
+    components: &mut Vec<i32>,
+) -> bool {
+    match module.const_expressions[handle] {
+        crate::Expression::Literal(crate::Literal::I32(value)) => components.push(value),
+        crate::Expression::ZeroValue(ty) => {
+            let count = match module.types[ty].inner {
+                crate::TypeInner::Vector { size, .. } => size as usize,
+                _ => 1,
+            };
+            components.extend(std::iter::repeat(0).take(count));
+        }
+        crate::Expression::Constant(constant) => {
+            return sample_offset_components(module, module.constants[constant].init, components)
+        }
+        crate::Expression::Compose {
+            ty,
+            components: ref composed,
+        } => {
+            return crate::proc::flatten_compose(
+                ty,
+                composed,
+                &module.const_expressions,
+                &module.types,
+            )
+            .all(|component| sample_offset_components(module, component, components));
+        }
+        crate::Expression::Splat { size, value } => {
+            let start = components.len();
+            if !sample_offset_components(module, value, components) {
+                return false;
+            }
+            let value = components[start];
+            components.extend(std::iter::repeat(value).take(size as usize - 1));
+        }
+        _ => return false,
+    }
+    true
+}
+
 pub fn check_literal_value(literal: crate::Literal) -> Result<(), LiteralError> {
     let is_nan = match literal {
         crate::Literal::F64(v) => v.is_nan(),
@@ -52,6 +52,10 @@ pub enum AtomicError {
 pub enum LocalVariableError {
     #[error("Local variable has a type {0:?} that can't be stored in a local variable.")]
     InvalidType(Handle<crate::Type>),
+    #[error(
+        "Local variable has type {0:?}, which is {1}, but local variables must be constructible"
+    )]
+    NonConstructibleType(Handle<crate::Type>, super::TypeClass),
     #[error("Initializer doesn't match the variable type")]
     InitializerType,
     #[error("Initializer is not const")]
@@ -68,6 +72,13 @@ pub enum FunctionError {
     },
     #[error("Expression {0:?} can't be introduced - it's already in scope")]
     ExpressionAlreadyInScope(Handle<crate::Expression>),
+    #[error("Expression {0:?} is emitted more than once")]
+    ExpressionAlreadyEmitted(Handle<crate::Expression>),
+    #[error("Expression {expression:?} is emitted before its operand {operand:?} is in scope")]
+    OperandNotInScope {
+        expression: Handle<crate::Expression>,
+        operand: Handle<crate::Expression>,
+    },
     #[error("Local variable {handle:?} '{name}' is invalid")]
     LocalVariable {
         handle: Handle<crate::LocalVariable>,
@@ -76,8 +87,13 @@ pub enum FunctionError {
     },
     #[error("Argument '{name}' at index {index} has a type that can't be passed into functions.")]
     InvalidArgumentType { index: usize, name: String },
-    #[error("The function's given return type cannot be returned from functions")]
-    NonConstructibleReturnType,
+    #[error("The function's return type is {0}, but only constructible types can be returned")]
+    NonConstructibleReturnType(super::TypeClass),
+    #[error("The `let` binding '{name}' has {class}, but it must be constructible or a pointer")]
+    NonConstructibleLet {
+        name: String,
+        class: super::TypeClass,
+    },
     #[error("Argument '{name}' at index {index} is a pointer of space {space:?}, which can't be passed into functions.")]
     InvalidArgumentPointerSpace {
         index: usize,
@@ -92,6 +108,8 @@ pub enum FunctionError {
     ContinueOutsideOfLoop,
     #[error("The `return` is called within a `continuing` block")]
     InvalidReturnSpot,
+    #[error("The `kill` is called within a `continuing` block")]
+    InvalidKillSpot,
     #[error("The `return` value {0:?} does not match the function return value")]
     InvalidReturnType(Option<Handle<crate::Expression>>),
     #[error("The `if` condition {0:?} is not a boolean scalar")]
@@ -119,6 +137,12 @@ pub enum FunctionError {
     },
     #[error("Image store parameters are invalid")]
     InvalidImageStore(#[source] ExpressionError),
+    #[error("Global variable {global:?} is declared with access {declared:?}, but is accessed with {used:?}")]
+    InvalidStorageAccess {
+        global: Handle<crate::GlobalVariable>,
+        declared: crate::StorageAccess,
+        used: crate::StorageAccess,
+    },
     #[error("Call to {function:?} is invalid")]
     InvalidCall {
         function: Handle<crate::Function>,
@@ -155,6 +179,10 @@ pub enum FunctionError {
     WorkgroupUniformLoadExpressionMismatch(Handle<crate::Expression>),
     #[error("The expression {0:?} is not valid as a WorkGroupUniformLoad argument. It should be a Pointer in Workgroup address space")]
     WorkgroupUniformLoadInvalidPointer(Handle<crate::Expression>),
+    #[error("Mesh output count or index {0:?} is not a `u32`")]
+    InvalidMeshOutputIndex(Handle<crate::Expression>),
+    #[error("Mesh output value {0:?} is not a struct")]
+    InvalidMeshOutputValue(Handle<crate::Expression>),
 }
 
 bitflags::bitflags! {
@@ -167,6 +195,8 @@ bitflags::bitflags! {
         const BREAK = 0x2;
         /// The control can continue.
         const CONTINUE = 0x4;
+        /// The control can kill the invocation.
+        const KILL = 0x8;
     }
 }
 
@@ -196,7 +226,7 @@ impl<'a> BlockContext<'a> {
         prev_infos: &'a [FunctionInfo],
     ) -> Self {
         Self {
-            abilities: ControlFlowAbility::RETURN,
+            abilities: ControlFlowAbility::RETURN | ControlFlowAbility::KILL,
             info,
             expressions: &fun.expressions,
             types: &module.types,
@@ -255,6 +285,106 @@ impl<'a> BlockContext<'a> {
     }
 }
 
+/// Return the global variable that the pointer or image `expr` refers to,
+/// looking through any accesses, or `None` if it doesn't refer to a global.
+fn root_global(
+    expressions: &Arena<crate::Expression>,
+    mut expr: Handle<crate::Expression>,
+) -> Option<Handle<crate::GlobalVariable>> {
+    loop {
+        expr = match expressions[expr] {
+            crate::Expression::Access { base, .. }
+            | crate::Expression::AccessIndex { base, .. } => base,
+            crate::Expression::GlobalVariable(global) => return Some(global),
+            _ => return None,
+        }
+    }
+}
+
+/// If `base` is a function-local vector or matrix, or a pointer to one,
+/// return which it is.
+///
+/// Values are local by nature. Pointers are local if they point into
+/// [`Function`] address space.
+///
+/// [`Function`]: crate::AddressSpace::Function
+fn local_composite(
+    base: Handle<crate::Expression>,
+    info: &FunctionInfo,
+    types: &UniqueArena<crate::Type>,
+) -> Option<&'static str> {
+    let inner = match *info[base].ty.inner_with(types) {
+        crate::TypeInner::Pointer {
+            base,
+            space: crate::AddressSpace::Function,
+        } => &types[base].inner,
+        crate::TypeInner::ValuePointer {
+            size: Some(_),
+            space: crate::AddressSpace::Function,
+            ..
+        } => return Some("vector"),
+        ref inner => inner,
+    };
+    match *inner {
+        crate::TypeInner::Vector { .. } => Some("vector"),
+        crate::TypeInner::Matrix { .. } => Some("matrix"),
+        _ => None,
+    }
+}
+
+/// Return the kinds of access `var`'s declaration permits to its contents.
+fn declared_access(
+    var: &crate::GlobalVariable,
+    types: &UniqueArena<crate::Type>,
+) -> crate::StorageAccess {
+    use crate::AddressSpace as As;
+
+    match var.space {
+        As::Storage { access } => access,
+        As::Uniform | As::PushConstant => crate::StorageAccess::LOAD,
+        As::Handle => {
+            let inner = match types[var.ty].inner {
+                crate::TypeInner::BindingArray { base, .. } => &types[base].inner,
+                ref inner => inner,
+            };
+            match *inner {
+                crate::TypeInner::Image {
+                    class: crate::ImageClass::Storage { access, .. },
+                    ..
+                } => access,
+                _ => crate::StorageAccess::LOAD,
+            }
+        }
+        As::Function | As::Private | As::WorkGroup => crate::StorageAccess::all(),
+    }
+}
+
+/// Check that the global, if any, that `expr` refers to permits `used`
+/// access, and report the access at `span` if it doesn't.
+fn validate_storage_access(
+    expr: Handle<crate::Expression>,
+    used: crate::StorageAccess,
+    span: crate::Span,
+    expressions: &Arena<crate::Expression>,
+    global_vars: &Arena<crate::GlobalVariable>,
+    types: &UniqueArena<crate::Type>,
+) -> Result<(), WithSpan<FunctionError>> {
+    let Some(global) = root_global(expressions, expr) else {
+        return Ok(());
+    };
+    let declared = declared_access(&global_vars[global], types);
+    if declared.contains(used) {
+        return Ok(());
+    }
+    Err(FunctionError::InvalidStorageAccess {
+        global,
+        declared,
+        used,
+    }
+    .with_span_static(span, "accessed here")
+    .with_handle(global, global_vars))
+}
+
 impl super::Validator {
     fn validate_call(
         &mut self,
@@ -317,12 +447,33 @@ impl super::Validator {
         handle: Handle<crate::Expression>,
         context: &BlockContext,
     ) -> Result<(), WithSpan<FunctionError>> {
-        if self.valid_expression_set.insert(handle.index()) {
-            self.valid_expression_list.push(handle);
-            Ok(())
-        } else {
-            Err(FunctionError::ExpressionAlreadyInScope(handle)
-                .with_span_handle(handle, context.expressions))
+        if !self.valid_expression_set.insert(handle.index()) {
+            return Err(FunctionError::ExpressionAlreadyInScope(handle)
+                .with_span_handle(handle, context.expressions));
+        }
+        self.valid_expression_list.push(handle);
+
+        // An expression in scope in one block may also have been emitted
+        // in a sibling block, which `valid_expression_set` has forgotten.
+        if !self.emitted_expression_set.insert(handle.index()) {
+            return Err(FunctionError::ExpressionAlreadyEmitted(handle)
+                .with_span_handle(handle, context.expressions));
+        }
+
+        let mut missing = None;
+        crate::proc::for_each_operand(&context.expressions[handle], |operand| {
+            if missing.is_none() && !self.valid_expression_set.contains(operand.index()) {
+                missing = Some(operand);
+            }
+        });
+        match missing {
+            Some(operand) => Err(FunctionError::OperandNotInScope {
+                expression: handle,
+                operand,
+            }
+            .with_span_handle(handle, context.expressions)
+            .with_handle(operand, context.expressions)),
+            None => Ok(()),
         }
     }
 
@@ -409,7 +560,8 @@ impl super::Validator {
         let mut finished = false;
         let mut stages = super::ShaderStages::all();
         for (statement, &span) in statements.span_iter() {
-            if finished {
+            // Markers don't execute, so they may follow a `Return`.
+            if finished && !matches!(*statement, S::DebugMarker(_)) {
                 return Err(FunctionError::InstructionsAfterReturn
                     .with_span_static(span, "instructions after return"));
             }
@@ -428,6 +580,7 @@ impl super::Validator {
                     condition,
                     ref accept,
                     ref reject,
+                    hint: _,
                 } => {
                     match *context.resolve_type(condition, &self.valid_expression_set)? {
                         Ti::Scalar(crate::Scalar {
@@ -510,7 +663,9 @@ impl super::Validator {
                         }
                     }
                     let pass_through_abilities = context.abilities
-                        & (ControlFlowAbility::RETURN | ControlFlowAbility::CONTINUE);
+                        & (ControlFlowAbility::RETURN
+                            | ControlFlowAbility::CONTINUE
+                            | ControlFlowAbility::KILL);
                     let sub_context =
                         context.with_abilities(pass_through_abilities | ControlFlowAbility::BREAK);
                     for case in cases {
@@ -521,11 +676,13 @@ impl super::Validator {
                     ref body,
                     ref continuing,
                     break_if,
+                    hint: _,
                 } => {
                     // special handling for block scoping is needed here,
                     // because the continuing{} block inherits the scope
                     let base_expression_count = self.valid_expression_list.len();
-                    let pass_through_abilities = context.abilities & ControlFlowAbility::RETURN;
+                    let pass_through_abilities =
+                        context.abilities & (ControlFlowAbility::RETURN | ControlFlowAbility::KILL);
                     stages &= self
                         .validate_block_impl(
                             body,
@@ -610,12 +767,18 @@ impl super::Validator {
                     finished = true;
                 }
                 S::Kill => {
+                    if !context.abilities.contains(ControlFlowAbility::KILL) {
+                        return Err(
+                            FunctionError::InvalidKillSpot.with_span_static(span, "invalid kill")
+                        );
+                    }
                     stages &= super::ShaderStages::FRAGMENT;
                     finished = true;
                 }
                 S::Barrier(_) => {
-                    stages &= super::ShaderStages::COMPUTE;
+                    stages &= super::ShaderStages::COMPUTE_LIKE;
                 }
+                S::DebugMarker(_) => {}
                 S::Store { pointer, value } => {
                     let mut current = pointer;
                     loop {
@@ -627,7 +790,8 @@ impl super::Validator {
                             | crate::Expression::AccessIndex { base, .. } => current = base,
                             crate::Expression::LocalVariable(_)
                             | crate::Expression::GlobalVariable(_)
-                            | crate::Expression::FunctionArgument(_) => break,
+                            | crate::Expression::FunctionArgument(_)
+                            | crate::Expression::Select { .. } => break,
                             _ => {
                                 return Err(FunctionError::InvalidStorePointer(current)
                                     .with_span_handle(pointer, context.expressions))
@@ -672,6 +836,14 @@ impl super::Validator {
                             .with_handle(value, context.expressions));
                     }
 
+                    validate_storage_access(
+                        pointer,
+                        crate::StorageAccess::STORE,
+                        span,
+                        context.expressions,
+                        context.global_vars,
+                        context.types,
+                    )?;
                     if let Some(space) = pointer_ty.pointer_space() {
                         if !space.access().contains(crate::StorageAccess::STORE) {
                             return Err(FunctionError::InvalidStorePointer(pointer)
@@ -795,6 +967,15 @@ impl super::Validator {
                         return Err(FunctionError::InvalidStoreValue(value)
                             .with_span_handle(value, context.expressions));
                     }
+
+                    validate_storage_access(
+                        image,
+                        crate::StorageAccess::STORE,
+                        span,
+                        context.expressions,
+                        context.global_vars,
+                        context.types,
+                    )?;
                 }
                 S::Call {
                     function,
@@ -816,9 +997,17 @@ impl super::Validator {
                     result,
                 } => {
                     self.validate_atomic(pointer, fun, value, result, context)?;
+                    validate_storage_access(
+                        pointer,
+                        crate::StorageAccess::all(),
+                        span,
+                        context.expressions,
+                        context.global_vars,
+                        context.types,
+                    )?;
                 }
                 S::WorkGroupUniformLoad { pointer, result } => {
-                    stages &= super::ShaderStages::COMPUTE;
+                    stages &= super::ShaderStages::COMPUTE_LIKE;
                     let pointer_inner =
                         context.resolve_type(pointer, &self.valid_expression_set)?;
                     match *pointer_inner {
@@ -904,6 +1093,37 @@ impl super::Validator {
                         crate::RayQueryFunction::Terminate => {}
                     }
                 }
+                S::MeshFunction(ref fun) => {
+                    stages &= super::ShaderStages::MESH;
+                    let (counts, value) = match *fun {
+                        crate::MeshFunction::SetMeshOutputs {
+                            vertex_count,
+                            primitive_count,
+                        } => ([Some(vertex_count), Some(primitive_count)], None),
+                        crate::MeshFunction::SetVertex { index, value }
+                        | crate::MeshFunction::SetPrimitive { index, value } => {
+                            ([Some(index), None], Some(value))
+                        }
+                    };
+                    for handle in counts.into_iter().flatten() {
+                        if *context.resolve_type(handle, &self.valid_expression_set)?
+                            != Ti::Scalar(crate::Scalar::U32)
+                        {
+                            return Err(FunctionError::InvalidMeshOutputIndex(handle)
+                                .with_span_handle(handle, context.expressions));
+                        }
+                    }
+                    // The entry point checks the type against its `MeshStageInfo`.
+                    if let Some(value) = value {
+                        if !matches!(
+                            *context.resolve_type(value, &self.valid_expression_set)?,
+                            Ti::Struct { .. }
+                        ) {
+                            return Err(FunctionError::InvalidMeshOutputValue(value)
+                                .with_span_handle(value, context.expressions));
+                        }
+                    }
+                }
             }
         }
         Ok(BlockInfo { stages, finished })
@@ -935,7 +1155,8 @@ impl super::Validator {
             .get(var.ty.index())
             .ok_or(LocalVariableError::InvalidType(var.ty))?;
         if !type_info.flags.contains(super::TypeFlags::CONSTRUCTIBLE) {
-            return Err(LocalVariableError::InvalidType(var.ty));
+            let class = super::TypeClass::classify(type_info.flags, &gctx.types[var.ty].inner);
+            return Err(LocalVariableError::NonConstructibleType(var.ty, class));
         }
 
         if let Some(init) = var.init {
@@ -953,14 +1174,16 @@ impl super::Validator {
         Ok(())
     }
 
-    pub(super) fn validate_function(
+    pub(super) fn validate_function_impl(
         &mut self,
         fun: &crate::Function,
         module: &crate::Module,
         mod_info: &ModuleInfo,
+        functions: &[FunctionInfo],
         entry_point: bool,
     ) -> Result<FunctionInfo, WithSpan<FunctionError>> {
-        let mut info = mod_info.process_function(fun, module, self.flags, self.capabilities)?;
+        let mut info =
+            mod_info.process_function(fun, module, functions, self.flags, self.capabilities)?;
 
         let expression_constness =
             crate::proc::ExpressionConstnessTracker::from_arena(&fun.expressions);
@@ -1011,11 +1234,10 @@ impl super::Validator {
         }
 
         if let Some(ref result) = fun.result {
-            if !self.types[result.ty.index()]
-                .flags
-                .contains(super::TypeFlags::CONSTRUCTIBLE)
-            {
-                return Err(FunctionError::NonConstructibleReturnType
+            let flags = self.types[result.ty.index()].flags;
+            if !flags.contains(super::TypeFlags::CONSTRUCTIBLE) {
+                let class = super::TypeClass::classify(flags, &module.types[result.ty].inner);
+                return Err(FunctionError::NonConstructibleReturnType(class)
                     .with_span_handle(result.ty, &module.types));
             }
 
@@ -1027,6 +1249,7 @@ impl super::Validator {
 
         self.valid_expression_set.clear();
         self.valid_expression_list.clear();
+        self.emitted_expression_set.clear();
         for (handle, expr) in fun.expressions.iter() {
             if expr.needs_pre_emit() {
                 self.valid_expression_set.insert(handle.index());
@@ -1039,15 +1262,63 @@ impl super::Validator {
                             .with_span_handle(handle, &fun.expressions))
                     }
                 }
+                match *expr {
+                    crate::Expression::Load { pointer: source }
+                    | crate::Expression::ImageLoad { image: source, .. } => {
+                        validate_storage_access(
+                            source,
+                            crate::StorageAccess::LOAD,
+                            fun.expressions.get_span(handle),
+                            &fun.expressions,
+                            &module.global_variables,
+                            &module.types,
+                        )?;
+                    }
+                    _ => {}
+                }
+            }
+            if self
+                .flags
+                .contains(super::ValidationFlags::DYNAMIC_INDEXING)
+            {
+                if let crate::Expression::Access { base, .. } = *expr {
+                    if let Some(composite) = local_composite(base, &info, &module.types) {
+                        info.warnings.push(
+                            super::ValidationWarning::DynamicIndex { handle, composite }
+                                .with_span_handle(handle, &fun.expressions),
+                        );
+                    }
+                }
+            }
+        }
+
+        for (&handle, name) in fun.named_expressions.iter() {
+            // Front ends name argument expressions too, and handle-typed
+            // arguments are fine.
+            if let crate::Expression::FunctionArgument(_) = fun.expressions[handle] {
+                continue;
+            }
+            let class = match info[handle].ty {
+                crate::proc::TypeResolution::Handle(ty) => super::TypeClass::classify(
+                    self.types[ty.index()].flags,
+                    &module.types[ty].inner,
+                ),
+                crate::proc::TypeResolution::Value(ref inner) => {
+                    super::TypeClass::classify(super::TypeFlags::CONSTRUCTIBLE, inner)
+                }
+            };
+            if let super::TypeClass::Plain | super::TypeClass::Handle = class {
+                return Err(FunctionError::NonConstructibleLet {
+                    name: name.clone(),
+                    class,
+                }
+                .with_span_handle(handle, &fun.expressions));
             }
         }
 
         if self.flags.contains(super::ValidationFlags::BLOCKS) {
             let stages = self
-                .validate_block(
-                    &fun.body,
-                    &BlockContext::new(fun, module, &info, &mod_info.functions),
-                )?
+                .validate_block(&fun.body, &BlockContext::new(fun, module, &info, functions))?
                 .stages;
             info.available_stages &= stages;
         }
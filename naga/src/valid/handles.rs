@@ -37,6 +37,9 @@ impl super::Validator {
             ref types,
             ref special_types,
             ref const_expressions,
+            enable_extensions: _,
+            ref diagnostic_filters,
+            diagnostic_filter_leaf,
         } = module;
 
         // NOTE: Types being first is important. All other forms of validation depend on this.
@@ -54,8 +57,13 @@ impl super::Validator {
                 crate::TypeInner::Pointer { base, space: _ } => {
                     this_handle.check_dep(base)?;
                 }
-                crate::TypeInner::Array { base, .. }
-                | crate::TypeInner::BindingArray { base, .. } => {
+                crate::TypeInner::Array { base, size, .. } => {
+                    this_handle.check_dep(base)?;
+                    if let crate::ArraySize::Pending(constant) = size {
+                        Self::validate_constant_handle(constant, constants)?;
+                    }
+                }
+                crate::TypeInner::BindingArray { base, .. } => {
                     this_handle.check_dep(base)?;
                 }
                 crate::TypeInner::Struct {
@@ -67,8 +75,23 @@ impl super::Validator {
             }
         }
 
+        for (this_handle, node) in diagnostic_filters.iter() {
+            let &crate::diagnostic_filter::DiagnosticFilterNode { inner: _, parent } = node;
+            this_handle.check_dep_opt(parent)?;
+        }
+        if let Some(leaf) = diagnostic_filter_leaf {
+            leaf.check_valid_for(diagnostic_filters)?;
+        }
+
         for handle_and_expr in const_expressions.iter() {
-            Self::validate_const_expression_handles(handle_and_expr, constants, types)?;
+            Self::validate_const_expression_handles(
+                handle_and_expr,
+                constants,
+                const_expressions,
+                types,
+                global_variables,
+                functions,
+            )?;
         }
 
         let validate_type = |handle| Self::validate_type_handle(handle, types);
@@ -93,6 +116,7 @@ impl super::Validator {
                 binding: _,
                 ty,
                 init,
+                memory_decorations: _,
             } = global_variable;
             validate_type(ty)?;
             if let Some(init_expr) = init {
@@ -100,73 +124,115 @@ impl super::Validator {
             }
         }
 
-        let validate_function = |function_handle, function: &_| -> Result<_, InvalidHandleError> {
-            let &crate::Function {
-                name: _,
-                ref arguments,
-                ref result,
-                ref local_variables,
-                ref expressions,
-                ref named_expressions,
-                ref body,
-            } = function;
-
-            for arg in arguments.iter() {
-                let &crate::FunctionArgument {
-                    name: _,
-                    ty,
-                    binding: _,
-                } = arg;
-                validate_type(ty)?;
-            }
+        for entry_point in entry_points.iter() {
+            for constant in entry_point
+                .workgroup_size_overrides
+                .iter()
+                .flatten()
+                .flatten()
+            {
+                Self::validate_constant_handle(*constant, constants)?;
+            }
+            if let Some(ref mesh_info) = entry_point.mesh_info {
+                validate_type(mesh_info.vertex_output_type)?;
+                validate_type(mesh_info.primitive_output_type)?;
+            }
+            Self::validate_function_handles(module, None, &entry_point.function)?;
+        }
 
-            if let &Some(crate::FunctionResult { ty, binding: _ }) = result {
-                validate_type(ty)?;
-            }
+        for (function_handle, function) in functions.iter() {
+            Self::validate_function_handles(module, Some(function_handle), function)?;
+        }
 
-            for (_handle, local_variable) in local_variables.iter() {
-                let &crate::LocalVariable { name: _, ty, init } = local_variable;
-                validate_type(ty)?;
-                if let Some(init) = init {
-                    Self::validate_expression_handle(init, expressions)?;
-                }
-            }
+        if let Some(ty) = special_types.ray_desc {
+            validate_type(ty)?;
+        }
+        if let Some(ty) = special_types.ray_intersection {
+            validate_type(ty)?;
+        }
+        for (_, &ty) in special_types.predeclared_types.iter() {
+            validate_type(ty)?;
+        }
 
-            for handle in named_expressions.keys().copied() {
-                Self::validate_expression_handle(handle, expressions)?;
-            }
+        Ok(())
+    }
 
-            for handle_and_expr in expressions.iter() {
-                Self::validate_expression_handles(
-                    handle_and_expr,
-                    constants,
-                    const_expressions,
-                    types,
-                    local_variables,
-                    global_variables,
-                    functions,
-                    function_handle,
-                )?;
-            }
+    /// Validate the handles used by `function`, which is `function_handle`
+    /// in `module`, or an entry point if that is `None`.
+    pub(super) fn validate_function_handles(
+        module: &crate::Module,
+        function_handle: Option<Handle<crate::Function>>,
+        function: &crate::Function,
+    ) -> Result<(), InvalidHandleError> {
+        let &crate::Module {
+            ref constants,
+            ref functions,
+            ref global_variables,
+            ref types,
+            ref const_expressions,
+            ref diagnostic_filters,
+            ..
+        } = module;
+        let validate_type = |handle| Self::validate_type_handle(handle, types);
 
-            Self::validate_block_handles(body, expressions, functions)?;
+        let &crate::Function {
+            name: _,
+            ref arguments,
+            ref result,
+            ref local_variables,
+            ref expressions,
+            ref named_expressions,
+            ref body,
+            diagnostic_filter_leaf,
+            ref diagnostic_filter_ranges,
+        } = function;
+
+        for arg in arguments.iter() {
+            let &crate::FunctionArgument {
+                name: _,
+                ty,
+                binding: _,
+            } = arg;
+            validate_type(ty)?;
+        }
 
-            Ok(())
-        };
+        if let &Some(crate::FunctionResult { ty, binding: _ }) = result {
+            validate_type(ty)?;
+        }
 
-        for entry_point in entry_points.iter() {
-            validate_function(None, &entry_point.function)?;
+        for (_handle, local_variable) in local_variables.iter() {
+            let &crate::LocalVariable { name: _, ty, init } = local_variable;
+            validate_type(ty)?;
+            if let Some(init) = init {
+                Self::validate_expression_handle(init, expressions)?;
+            }
         }
 
-        for (function_handle, function) in functions.iter() {
-            validate_function(Some(function_handle), function)?;
+        for handle in named_expressions.keys().copied() {
+            Self::validate_expression_handle(handle, expressions)?;
         }
 
-        if let Some(ty) = special_types.ray_desc {
-            validate_type(ty)?;
+        for handle_and_expr in expressions.iter() {
+            Self::validate_expression_handles(
+                handle_and_expr,
+                constants,
+                const_expressions,
+                types,
+                local_variables,
+                global_variables,
+                functions,
+                function_handle,
+            )?;
         }
-        if let Some(ty) = special_types.ray_intersection {
-            validate_type(ty)?;
+
+        Self::validate_block_handles(body, expressions, functions, function_handle)?;
+
+        if let Some(leaf) = diagnostic_filter_leaf {
+            leaf.check_valid_for(diagnostic_filters)?;
+        }
+        for &(ref range, filter) in diagnostic_filter_ranges.iter() {
+            range.check_valid_for(expressions)?;
+            filter.check_valid_for(diagnostic_filters)?;
         }
 
         Ok(())
@@ -203,7 +269,10 @@ impl super::Validator {
     fn validate_const_expression_handles(
         (handle, expression): (Handle<crate::Expression>, &crate::Expression),
         constants: &Arena<crate::Constant>,
+        const_expressions: &Arena<crate::Expression>,
         types: &UniqueArena<crate::Type>,
+        global_variables: &Arena<crate::GlobalVariable>,
+        functions: &Arena<crate::Function>,
     ) -> Result<(), InvalidHandleError> {
         let validate_constant = |handle| Self::validate_constant_handle(handle, constants);
         let validate_type = |handle| Self::validate_type_handle(handle, types);
@@ -221,7 +290,19 @@ impl super::Validator {
                 validate_type(ty)?;
                 handle.check_dep_iter(components.iter().copied())?;
             }
-            _ => {}
+            // Expressions that aren't allowed in the constant expression
+            // arena are rejected by later validation, but their handles
+            // must still be checked before that can happen.
+            _ => Self::validate_expression_handles(
+                (handle, expression),
+                constants,
+                const_expressions,
+                types,
+                &Arena::new(),
+                global_variables,
+                functions,
+                None,
+            )?,
         }
         Ok(())
     }
@@ -388,9 +469,11 @@ impl super::Validator {
                     handle.check_dep(function)?;
                 }
             }
-            crate::Expression::AtomicResult { .. }
-            | crate::Expression::RayQueryProceedResult
-            | crate::Expression::WorkGroupUniformLoadResult { .. } => (),
+            crate::Expression::AtomicResult { ty, .. }
+            | crate::Expression::WorkGroupUniformLoadResult { ty } => {
+                validate_type(ty)?;
+            }
+            crate::Expression::RayQueryProceedResult => (),
             crate::Expression::ArrayLength(array) => {
                 handle.check_dep(array)?;
             }
@@ -408,8 +491,10 @@ impl super::Validator {
         block: &crate::Block,
         expressions: &Arena<crate::Expression>,
         functions: &Arena<crate::Function>,
+        current_function: Option<Handle<crate::Function>>,
     ) -> Result<(), InvalidHandleError> {
-        let validate_block = |block| Self::validate_block_handles(block, expressions, functions);
+        let validate_block =
+            |block| Self::validate_block_handles(block, expressions, functions, current_function);
         let validate_expr = |handle| Self::validate_expression_handle(handle, expressions);
         let validate_expr_opt = |handle_opt| {
             if let Some(handle) = handle_opt {
@@ -431,6 +516,7 @@ impl super::Validator {
                 condition,
                 ref accept,
                 ref reject,
+                hint: _,
             } => {
                 validate_expr(condition)?;
                 validate_block(accept)?;
@@ -456,6 +542,7 @@ impl super::Validator {
                 ref body,
                 ref continuing,
                 break_if,
+                hint: _,
             } => {
                 validate_block(body)?;
                 validate_block(continuing)?;
@@ -512,6 +599,9 @@ impl super::Validator {
                 result,
             } => {
                 Self::validate_function_handle(function, functions)?;
+                if let Some(handle) = current_function {
+                    handle.check_dep(function)?;
+                }
                 for arg in arguments.iter().copied() {
                     validate_expr(arg)?;
                 }
@@ -535,10 +625,29 @@ impl super::Validator {
                 }
                 Ok(())
             }
+            crate::Statement::MeshFunction(
+                crate::MeshFunction::SetMeshOutputs {
+                    vertex_count: first,
+                    primitive_count: second,
+                }
+                | crate::MeshFunction::SetVertex {
+                    index: first,
+                    value: second,
+                }
+                | crate::MeshFunction::SetPrimitive {
+                    index: first,
+                    value: second,
+                },
+            ) => {
+                validate_expr(first)?;
+                validate_expr(second)?;
+                Ok(())
+            }
             crate::Statement::Break
             | crate::Statement::Continue
             | crate::Statement::Kill
-            | crate::Statement::Barrier(_) => Ok(()),
+            | crate::Statement::Barrier(_)
+            | crate::Statement::DebugMarker(_) => Ok(()),
         })
     }
 }
@@ -692,7 +801,10 @@ fn constant_deps() {
         assert!(super::Validator::validate_const_expression_handles(
             handle_and_expr,
             &constants,
+            &const_exprs,
             &types,
+            &Arena::new(),
+            &Arena::new(),
         )
         .is_err());
     }
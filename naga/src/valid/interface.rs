@@ -9,6 +9,11 @@ use bit_set::BitSet;
 
 const MAX_WORKGROUP_SIZE: u32 = 0x4000;
 
+/// The largest number of clip distances a vertex shader may write.
+///
+/// This is the limit imposed by D3D, which packs them into two `float4`s.
+const MAX_CLIP_DISTANCES: u32 = 8;
+
 #[derive(Clone, Debug, thiserror::Error)]
 pub enum GlobalVariableError {
     #[error("Usage isn't compatible with address space {0:?}")]
@@ -36,6 +41,8 @@ pub enum GlobalVariableError {
     InitializerNotAllowed(crate::AddressSpace),
     #[error("Storage address space doesn't support write-only access")]
     StorageAddressSpaceWriteOnlyNotSupported,
+    #[error("Memory decorations can't be used with address space {0:?}")]
+    MemoryDecorationsNotAllowed(crate::AddressSpace),
 }
 
 #[derive(Clone, Debug, thiserror::Error)]
@@ -52,6 +59,8 @@ pub enum VaryingError {
     InvalidBuiltInStage(crate::BuiltIn),
     #[error("Built-in type for {0:?} is invalid")]
     InvalidBuiltInType(crate::BuiltIn),
+    #[error("At most {MAX_CLIP_DISTANCES} clip distances can be written, but {0} are declared")]
+    TooManyClipDistances(u32),
     #[error("Entry point arguments and return values must all have bindings")]
     MissingBinding,
     #[error("Struct member {0} is missing a binding")]
@@ -83,10 +92,14 @@ pub enum EntryPointError {
     MissingVertexOutputPosition,
     #[error("Early depth test is not applicable")]
     UnexpectedEarlyDepthTest,
+    #[error("Conservative depth {0:?} requires the entry point to write `FragDepth`")]
+    ConservativeDepthWithoutFragDepth(crate::ConservativeDepth),
     #[error("Workgroup size is not applicable")]
     UnexpectedWorkgroupSize,
     #[error("Workgroup size is out of range")]
     OutOfRangeWorkgroupSize,
+    #[error("Workgroup size override {0:?} is not an integer override constant")]
+    InvalidWorkgroupSizeOverride(Handle<crate::Constant>),
     #[error("Uses operations forbidden at this stage")]
     ForbiddenStageOperations,
     #[error("Global variable {0:?} is used incorrectly as {1:?}")]
@@ -107,6 +120,28 @@ pub enum EntryPointError {
         "Invalid locations {location_mask:?} are set while dual source blending. Only location 0 may be set."
     )]
     InvalidLocationsWhileDualSourceBlending { location_mask: BitSet },
+    #[error("The {0:?} stage requires the `MESH_SHADER` capability")]
+    UnsupportedStage(crate::ShaderStage),
+    #[error("Mesh shaders must declare their outputs")]
+    MissingMeshInfo,
+    #[error("Mesh outputs are only applicable to mesh shaders")]
+    UnexpectedMeshInfo,
+    #[error("Mesh shaders can't return a value")]
+    UnexpectedMeshResult,
+    #[error(
+        "Mesh shaders must produce between 1 and the maximum number of vertices and primitives"
+    )]
+    OutOfRangeMeshOutputs,
+    #[error("Mesh vertex output is invalid")]
+    MeshVertexOutput(#[source] VaryingError),
+    #[error("Mesh primitive output is invalid")]
+    MeshPrimitiveOutput(#[source] VaryingError),
+    #[error("Mesh primitive outputs must have the vertex indices built-in for {0:?}")]
+    MissingMeshPrimitiveIndices(crate::MeshOutputTopology),
+    #[error("Mesh output value {0:?} doesn't have the type the entry point declares")]
+    InvalidMeshOutputType(Handle<crate::Expression>),
+    #[error("Task shaders must return a `@builtin(mesh_task_size)` value")]
+    MissingMeshTaskSize,
 }
 
 fn storage_usage(access: crate::StorageAccess) -> GlobalUse {
@@ -123,6 +158,8 @@ fn storage_usage(access: crate::StorageAccess) -> GlobalUse {
 struct VaryingContext<'a> {
     stage: crate::ShaderStage,
     output: bool,
+    /// Whether this is a mesh shader's primitive output.
+    per_primitive: bool,
     second_blend_source: bool,
     types: &'a UniqueArena<crate::Type>,
     type_info: &'a Vec<super::r#type::TypeInfo>,
@@ -130,6 +167,7 @@ struct VaryingContext<'a> {
     built_ins: &'a mut crate::FastHashSet<crate::BuiltIn>,
     capabilities: Capabilities,
     flags: super::ValidationFlags,
+    split_vertex_inputs: Vec<super::SplitVertexInput>,
 }
 
 impl VaryingContext<'_> {
@@ -162,6 +200,11 @@ impl VaryingContext<'_> {
                     Bi::PrimitiveIndex => Capabilities::PRIMITIVE_INDEX,
                     Bi::ViewIndex => Capabilities::MULTIVIEW,
                     Bi::SampleIndex => Capabilities::MULTISAMPLED_SHADING,
+                    Bi::MeshTaskSize
+                    | Bi::TriangleIndices
+                    | Bi::LineIndices
+                    | Bi::PointIndex
+                    | Bi::CullPrimitive => Capabilities::MESH_SHADER,
                     _ => Capabilities::empty(),
                 };
                 if !self.capabilities.contains(required) {
@@ -176,9 +219,11 @@ impl VaryingContext<'_> {
                     Bi::ClipDistance | Bi::CullDistance => (
                         self.stage == St::Vertex && self.output,
                         match *ty_inner {
-                            Ti::Array { base, .. } => {
-                                self.types[base].inner == Ti::Scalar(crate::Scalar::F32)
-                            }
+                            Ti::Array {
+                                base,
+                                size: crate::ArraySize::Constant(_),
+                                ..
+                            } => self.types[base].inner == Ti::Scalar(crate::Scalar::F32),
                             _ => false,
                         },
                     ),
@@ -198,7 +243,8 @@ impl VaryingContext<'_> {
                         match self.stage {
                             St::Vertex => self.output,
                             St::Fragment => !self.output,
-                            St::Compute => false,
+                            St::Mesh => self.output && !self.per_primitive,
+                            St::Compute | St::Task => false,
                         },
                         *ty_inner
                             == Ti::Vector {
@@ -209,7 +255,7 @@ impl VaryingContext<'_> {
                     Bi::ViewIndex => (
                         match self.stage {
                             St::Vertex | St::Fragment => !self.output,
-                            St::Compute => false,
+                            St::Compute | St::Task | St::Mesh => false,
                         },
                         *ty_inner == Ti::Scalar(crate::Scalar::I32),
                     ),
@@ -234,7 +280,7 @@ impl VaryingContext<'_> {
                         *ty_inner == Ti::Scalar(crate::Scalar::U32),
                     ),
                     Bi::LocalInvocationIndex => (
-                        self.stage == St::Compute && !self.output,
+                        matches!(self.stage, St::Compute | St::Task | St::Mesh) && !self.output,
                         *ty_inner == Ti::Scalar(crate::Scalar::U32),
                     ),
                     Bi::GlobalInvocationId
@@ -242,13 +288,45 @@ impl VaryingContext<'_> {
                     | Bi::WorkGroupId
                     | Bi::WorkGroupSize
                     | Bi::NumWorkGroups => (
-                        self.stage == St::Compute && !self.output,
+                        matches!(self.stage, St::Compute | St::Task | St::Mesh) && !self.output,
+                        *ty_inner
+                            == Ti::Vector {
+                                size: Vs::Tri,
+                                scalar: crate::Scalar::U32,
+                            },
+                    ),
+                    Bi::MeshTaskSize => (
+                        self.stage == St::Task && self.output,
+                        *ty_inner
+                            == Ti::Vector {
+                                size: Vs::Tri,
+                                scalar: crate::Scalar::U32,
+                            },
+                    ),
+                    Bi::TriangleIndices => (
+                        self.stage == St::Mesh && self.per_primitive,
                         *ty_inner
                             == Ti::Vector {
                                 size: Vs::Tri,
                                 scalar: crate::Scalar::U32,
                             },
                     ),
+                    Bi::LineIndices => (
+                        self.stage == St::Mesh && self.per_primitive,
+                        *ty_inner
+                            == Ti::Vector {
+                                size: Vs::Bi,
+                                scalar: crate::Scalar::U32,
+                            },
+                    ),
+                    Bi::PointIndex => (
+                        self.stage == St::Mesh && self.per_primitive,
+                        *ty_inner == Ti::Scalar(crate::Scalar::U32),
+                    ),
+                    Bi::CullPrimitive => (
+                        self.stage == St::Mesh && self.per_primitive,
+                        *ty_inner == Ti::Scalar(crate::Scalar::BOOL),
+                    ),
                 };
 
                 if !visible {
@@ -258,6 +336,18 @@ impl VaryingContext<'_> {
                     log::warn!("Wrong builtin type: {:?}", ty_inner);
                     return Err(VaryingError::InvalidBuiltInType(built_in));
                 }
+                if let (
+                    Bi::ClipDistance,
+                    &Ti::Array {
+                        size: crate::ArraySize::Constant(count),
+                        ..
+                    },
+                ) = (built_in, ty_inner)
+                {
+                    if count.get() > MAX_CLIP_DISTANCES {
+                        return Err(VaryingError::TooManyClipDistances(count.get()));
+                    }
+                }
             }
             crate::Binding::Location {
                 location,
@@ -265,13 +355,25 @@ impl VaryingContext<'_> {
                 sampling,
                 second_blend_source,
             } => {
-                // Only IO-shareable types may be stored in locations.
-                if !self.type_info[ty.index()]
-                    .flags
-                    .contains(super::TypeFlags::IO_SHAREABLE)
-                {
-                    return Err(VaryingError::NotIOShareableType(ty));
-                }
+                // Vertex inputs may also be matrices or arrays, which take
+                // one location per column or element.
+                let split = match (self.stage, self.output) {
+                    (St::Vertex, false) => ty_inner.vertex_input_split(self.types),
+                    _ => None,
+                };
+                let (location_count, ty_inner) = match split {
+                    Some((count, ref inner)) => (count, inner),
+                    None => {
+                        // Only IO-shareable types may be stored in locations.
+                        if !self.type_info[ty.index()]
+                            .flags
+                            .contains(super::TypeFlags::IO_SHAREABLE)
+                        {
+                            return Err(VaryingError::NotIOShareableType(ty));
+                        }
+                        (1, ty_inner)
+                    }
+                };
 
                 if second_blend_source {
                     if !self
@@ -302,16 +404,21 @@ impl VaryingContext<'_> {
                     }
 
                     self.second_blend_source = true;
-                } else if !self.location_mask.insert(location as usize) {
-                    if self.flags.contains(super::ValidationFlags::BINDINGS) {
-                        return Err(VaryingError::BindingCollision { location });
+                } else {
+                    for location in location..location.saturating_add(location_count) {
+                        if !self.location_mask.insert(location as usize)
+                            && self.flags.contains(super::ValidationFlags::BINDINGS)
+                        {
+                            return Err(VaryingError::BindingCollision { location });
+                        }
                     }
                 }
 
                 let needs_interpolation = match self.stage {
                     crate::ShaderStage::Vertex => self.output,
                     crate::ShaderStage::Fragment => !self.output,
-                    crate::ShaderStage::Compute => false,
+                    crate::ShaderStage::Mesh => self.output && !self.per_primitive,
+                    crate::ShaderStage::Compute | crate::ShaderStage::Task => false,
                 };
 
                 // It doesn't make sense to specify a sampling when `interpolation` is `Flat`, but
@@ -341,6 +448,14 @@ impl VaryingContext<'_> {
                     }
                     None => return Err(VaryingError::InvalidType(ty)),
                 }
+
+                if let Some((count, inner)) = split {
+                    self.split_vertex_inputs.push(super::SplitVertexInput {
+                        location,
+                        count,
+                        ty: inner,
+                    });
+                }
             }
         }
 
@@ -399,6 +514,11 @@ impl super::Validator {
         use super::TypeFlags;
 
         log::debug!("var {:?}", var);
+        if !var.memory_decorations.is_empty()
+            && !matches!(var.space, crate::AddressSpace::Storage { .. })
+        {
+            return Err(GlobalVariableError::MemoryDecorationsNotAllowed(var.space));
+        }
         let inner_ty = match gctx.types[var.ty].inner {
             // A binding array is (mostly) supposed to behave the same as a
             // series of individually bound resources, so we can (mostly)
@@ -485,7 +605,18 @@ impl super::Validator {
                 (TypeFlags::empty(), true)
             }
             crate::AddressSpace::Private => (TypeFlags::CONSTRUCTIBLE, false),
-            crate::AddressSpace::WorkGroup => (TypeFlags::DATA | TypeFlags::SIZED, false),
+            crate::AddressSpace::WorkGroup => {
+                // Arrays sized by an override get their size at pipeline
+                // creation time, and workgroup memory is the only place they
+                // can live.
+                match gctx.types[var.ty].inner {
+                    crate::TypeInner::Array {
+                        size: crate::ArraySize::Pending(_),
+                        ..
+                    } => (TypeFlags::DATA, false),
+                    _ => (TypeFlags::DATA | TypeFlags::SIZED, false),
+                }
+            }
             crate::AddressSpace::PushConstant => {
                 if !self.capabilities.contains(Capabilities::PUSH_CONSTANT) {
                     return Err(GlobalVariableError::UnsupportedCapability(
@@ -533,6 +664,75 @@ impl super::Validator {
         Ok(())
     }
 
+    /// Check the vertex and primitive outputs of a mesh entry point.
+    fn validate_mesh_info(
+        &mut self,
+        mesh_info: &crate::MeshStageInfo,
+        module: &crate::Module,
+    ) -> Result<(), WithSpan<EntryPointError>> {
+        if mesh_info.max_vertices == 0 || mesh_info.max_primitives == 0 {
+            return Err(EntryPointError::OutOfRangeMeshOutputs.with_span());
+        }
+
+        // Per-vertex and per-primitive values share the fragment stage's
+        // input locations.
+        self.location_mask.clear();
+        for (ty, per_primitive) in [
+            (mesh_info.vertex_output_type, false),
+            (mesh_info.primitive_output_type, true),
+        ] {
+            let mut built_ins = crate::FastHashSet::default();
+            let mut ctx = VaryingContext {
+                stage: crate::ShaderStage::Mesh,
+                output: true,
+                per_primitive,
+                second_blend_source: false,
+                types: &module.types,
+                type_info: &self.types,
+                location_mask: &mut self.location_mask,
+                built_ins: &mut built_ins,
+                capabilities: self.capabilities,
+                flags: self.flags,
+                split_vertex_inputs: Vec::new(),
+            };
+            ctx.validate(ty, None).map_err_inner(|e| {
+                if per_primitive {
+                    EntryPointError::MeshPrimitiveOutput(e)
+                } else {
+                    EntryPointError::MeshVertexOutput(e)
+                }
+                .with_span_handle(ty, &module.types)
+            })?;
+
+            if per_primitive {
+                let expected = match mesh_info.topology {
+                    crate::MeshOutputTopology::Points => crate::BuiltIn::PointIndex,
+                    crate::MeshOutputTopology::Lines => crate::BuiltIn::LineIndices,
+                    crate::MeshOutputTopology::Triangles => crate::BuiltIn::TriangleIndices,
+                };
+                let indices = [
+                    crate::BuiltIn::PointIndex,
+                    crate::BuiltIn::LineIndices,
+                    crate::BuiltIn::TriangleIndices,
+                ];
+                if !built_ins.contains(&expected)
+                    || indices
+                        .iter()
+                        .any(|bi| *bi != expected && built_ins.contains(bi))
+                {
+                    return Err(
+                        EntryPointError::MissingMeshPrimitiveIndices(mesh_info.topology)
+                            .with_span_handle(ty, &module.types),
+                    );
+                }
+            } else if !built_ins.contains(&crate::BuiltIn::Position { invariant: false }) {
+                return Err(EntryPointError::MissingVertexOutputPosition
+                    .with_span_handle(ty, &module.types));
+            }
+        }
+        Ok(())
+    }
+
     pub(super) fn validate_entry_point(
         &mut self,
         ep: &crate::EntryPoint,
@@ -553,20 +753,47 @@ impl super::Validator {
             }
         }
 
-        if ep.stage == crate::ShaderStage::Compute {
+        if matches!(
+            ep.stage,
+            crate::ShaderStage::Task | crate::ShaderStage::Mesh
+        ) && !self.capabilities.contains(Capabilities::MESH_SHADER)
+        {
+            return Err(EntryPointError::UnsupportedStage(ep.stage).with_span());
+        }
+
+        if matches!(
+            ep.stage,
+            crate::ShaderStage::Compute | crate::ShaderStage::Task | crate::ShaderStage::Mesh
+        ) {
+            let overrides = ep.workgroup_size_overrides.unwrap_or_default();
             if ep
                 .workgroup_size
                 .iter()
-                .any(|&s| s == 0 || s > MAX_WORKGROUP_SIZE)
+                .zip(overrides)
+                .any(|(&s, o)| o.is_none() && (s == 0 || s > MAX_WORKGROUP_SIZE))
             {
                 return Err(EntryPointError::OutOfRangeWorkgroupSize.with_span());
             }
-        } else if ep.workgroup_size != [0; 3] {
+            for handle in overrides.into_iter().flatten() {
+                let constant = &module.constants[handle];
+                let is_integer = matches!(
+                    module.types[constant.ty].inner,
+                    crate::TypeInner::Scalar(crate::Scalar {
+                        kind: crate::ScalarKind::Sint | crate::ScalarKind::Uint,
+                        ..
+                    })
+                );
+                if constant.r#override == crate::Override::None || !is_integer {
+                    return Err(EntryPointError::InvalidWorkgroupSizeOverride(handle)
+                        .with_span_handle(handle, &module.constants));
+                }
+            }
+        } else if ep.workgroup_size != [0; 3] || ep.workgroup_size_overrides.is_some() {
             return Err(EntryPointError::UnexpectedWorkgroupSize.with_span());
         }
 
         let mut info = self
-            .validate_function(&ep.function, module, mod_info, true)
+            .validate_function_impl(&ep.function, module, mod_info, &mod_info.functions, true)
             .map_err(WithSpan::into_other)?;
 
         {
@@ -576,6 +803,8 @@ impl super::Validator {
                 crate::ShaderStage::Vertex => ShaderStages::VERTEX,
                 crate::ShaderStage::Fragment => ShaderStages::FRAGMENT,
                 crate::ShaderStage::Compute => ShaderStages::COMPUTE,
+                crate::ShaderStage::Task => ShaderStages::TASK,
+                crate::ShaderStage::Mesh => ShaderStages::MESH,
             };
 
             if !info.available_stages.contains(stage_bit) {
@@ -590,6 +819,7 @@ impl super::Validator {
             let mut ctx = VaryingContext {
                 stage: ep.stage,
                 output: false,
+                per_primitive: false,
                 second_blend_source: false,
                 types: &module.types,
                 type_info: &self.types,
@@ -597,17 +827,21 @@ impl super::Validator {
                 built_ins: &mut argument_built_ins,
                 capabilities: self.capabilities,
                 flags: self.flags,
+                split_vertex_inputs: Vec::new(),
             };
             ctx.validate(fa.ty, fa.binding.as_ref())
                 .map_err_inner(|e| EntryPointError::Argument(index as u32, e).with_span())?;
+            info.split_vertex_inputs
+                .append(&mut ctx.split_vertex_inputs);
         }
 
         self.location_mask.clear();
+        let mut result_built_ins = crate::FastHashSet::default();
         if let Some(ref fr) = ep.function.result {
-            let mut result_built_ins = crate::FastHashSet::default();
             let mut ctx = VaryingContext {
                 stage: ep.stage,
                 output: true,
+                per_primitive: false,
                 second_blend_source: false,
                 types: &module.types,
                 type_info: &self.types,
@@ -615,6 +849,7 @@ impl super::Validator {
                 built_ins: &mut result_built_ins,
                 capabilities: self.capabilities,
                 flags: self.flags,
+                split_vertex_inputs: Vec::new(),
             };
             ctx.validate(fr.ty, fr.binding.as_ref())
                 .map_err_inner(|e| EntryPointError::Result(e).with_span())?;
@@ -639,6 +874,42 @@ impl super::Validator {
             return Err(EntryPointError::MissingVertexOutputPosition.with_span());
         }
 
+        if ep.stage == crate::ShaderStage::Task
+            && !result_built_ins.contains(&crate::BuiltIn::MeshTaskSize)
+        {
+            return Err(EntryPointError::MissingMeshTaskSize.with_span());
+        }
+
+        match (ep.stage, ep.mesh_info.as_ref()) {
+            (crate::ShaderStage::Mesh, Some(mesh_info)) => {
+                if ep.function.result.is_some() {
+                    return Err(EntryPointError::UnexpectedMeshResult.with_span());
+                }
+                self.validate_mesh_info(mesh_info, module)?;
+                validate_mesh_output_types(&ep.function, &info, module, mod_info, mesh_info)?;
+            }
+            (crate::ShaderStage::Mesh, None) => {
+                return Err(EntryPointError::MissingMeshInfo.with_span());
+            }
+            (_, Some(_)) => return Err(EntryPointError::UnexpectedMeshInfo.with_span()),
+            (_, None) => {}
+        }
+
+        // Promising that depth only moves in one direction is meaningless
+        // unless the entry point actually replaces it.
+        if let Some(crate::EarlyDepthTest {
+            conservative: Some(conservative),
+        }) = ep.early_depth_test
+        {
+            if conservative != crate::ConservativeDepth::Unchanged
+                && !result_built_ins.contains(&crate::BuiltIn::FragDepth)
+            {
+                return Err(
+                    EntryPointError::ConservativeDepthWithoutFragDepth(conservative).with_span(),
+                );
+            }
+        }
+
         {
             let used_push_constants = module
                 .global_variables
@@ -707,3 +978,72 @@ impl super::Validator {
         Ok(info)
     }
 }
+
+/// Check that the mesh output statements in `function`, and in the functions
+/// it calls, store values of the types `mesh_info` declares.
+fn validate_mesh_output_types(
+    function: &crate::Function,
+    info: &FunctionInfo,
+    module: &crate::Module,
+    mod_info: &ModuleInfo,
+    mesh_info: &crate::MeshStageInfo,
+) -> Result<(), WithSpan<EntryPointError>> {
+    let mut blocks = vec![&function.body];
+    while let Some(block) = blocks.pop() {
+        for statement in block.iter() {
+            use crate::Statement as S;
+            let (value, expected) = match *statement {
+                S::MeshFunction(crate::MeshFunction::SetVertex { value, .. }) => {
+                    (value, mesh_info.vertex_output_type)
+                }
+                S::MeshFunction(crate::MeshFunction::SetPrimitive { value, .. }) => {
+                    (value, mesh_info.primitive_output_type)
+                }
+                S::Block(ref block) => {
+                    blocks.push(block);
+                    continue;
+                }
+                S::If {
+                    ref accept,
+                    ref reject,
+                    ..
+                } => {
+                    blocks.push(accept);
+                    blocks.push(reject);
+                    continue;
+                }
+                S::Switch { ref cases, .. } => {
+                    blocks.extend(cases.iter().map(|case| &case.body));
+                    continue;
+                }
+                S::Loop {
+                    ref body,
+                    ref continuing,
+                    ..
+                } => {
+                    blocks.push(body);
+                    blocks.push(continuing);
+                    continue;
+                }
+                S::Call {
+                    function: callee, ..
+                } => {
+                    validate_mesh_output_types(
+                        &module.functions[callee],
+                        &mod_info[callee],
+                        module,
+                        mod_info,
+                        mesh_info,
+                    )?;
+                    continue;
+                }
+                _ => continue,
+            };
+            if info[value].ty.handle() != Some(expected) {
+                return Err(EntryPointError::InvalidMeshOutputType(value)
+                    .with_span_handle(value, &function.expressions));
+            }
+        }
+    }
+    Ok(())
+}
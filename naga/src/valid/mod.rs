@@ -8,6 +8,8 @@ mod expression;
 mod function;
 mod handles;
 mod interface;
+#[cfg(feature = "parallel")]
+mod parallel;
 mod r#type;
 
 use crate::{
@@ -22,13 +24,16 @@ use std::ops;
 // merge the corresponding matches over expressions and statements.
 
 use crate::span::{AddSpan as _, WithSpan};
-pub use analyzer::{ExpressionInfo, FunctionInfo, GlobalUse, Uniformity, UniformityRequirements};
+pub use analyzer::{
+    ExpressionInfo, FunctionInfo, GlobalUse, SplitVertexInput, Uniformity, UniformityDisruptor,
+    UniformityRequirements,
+};
 pub use compose::ComposeError;
 pub use expression::{check_literal_value, LiteralError};
 pub use expression::{ConstExpressionError, ExpressionError};
 pub use function::{CallError, FunctionError, LocalVariableError};
 pub use interface::{EntryPointError, GlobalVariableError, VaryingError};
-pub use r#type::{Disalignment, TypeError, TypeFlags};
+pub use r#type::{Disalignment, TypeClass, TypeError, TypeFlags};
 
 use self::handles::InvalidHandleError;
 
@@ -44,8 +49,10 @@ bitflags::bitflags! {
     /// code generation will either succeed or return an error; it
     /// should never panic.
     ///
-    /// The default value for `ValidationFlags` is
-    /// `ValidationFlags::all()`.
+    /// The default value for `ValidationFlags` is every flag except the
+    /// ones that only collect warnings, like [`DYNAMIC_INDEXING`].
+    ///
+    /// [`DYNAMIC_INDEXING`]: ValidationFlags::DYNAMIC_INDEXING
     #[cfg_attr(feature = "serialize", derive(serde::Serialize))]
     #[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
     #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -62,12 +69,18 @@ bitflags::bitflags! {
         const CONSTANTS = 0x10;
         /// Group, binding, and location attributes.
         const BINDINGS = 0x20;
+        /// Warn about runtime indices into function-local vectors and
+        /// matrices, which some backends can only implement by copying the
+        /// value to memory or by emitting a switch.
+        ///
+        /// See [`ValidationWarning::DynamicIndex`].
+        const DYNAMIC_INDEXING = 0x40;
     }
 }
 
 impl Default for ValidationFlags {
     fn default() -> Self {
-        Self::all()
+        Self::all() - Self::DYNAMIC_INDEXING
     }
 }
 
@@ -77,7 +90,7 @@ bitflags::bitflags! {
     #[cfg_attr(feature = "serialize", derive(serde::Serialize))]
     #[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
     #[derive(Clone, Copy, Debug, Eq, PartialEq)]
-    pub struct Capabilities: u16 {
+    pub struct Capabilities: u32 {
         /// Support for [`AddressSpace:PushConstant`].
         const PUSH_CONSTANT = 0x1;
         /// Float values with width = 8.
@@ -108,6 +121,16 @@ bitflags::bitflags! {
         const DUAL_SOURCE_BLENDING = 0x2000;
         /// Support for arrayed cube textures.
         const CUBE_ARRAY_TEXTURES = 0x4000;
+        /// Support for [`Expression::Select`] choosing between two pointers.
+        ///
+        /// Only the SPIR-V backend can generate code for this.
+        ///
+        /// [`Expression::Select`]: crate::Expression::Select
+        const POINTER_SELECT = 0x8000;
+        /// Support for task and mesh shaders.
+        ///
+        /// Only the SPIR-V backend can generate code for these.
+        const MESH_SHADER = 0x10000;
     }
 }
 
@@ -126,6 +149,10 @@ bitflags::bitflags! {
         const VERTEX = 0x1;
         const FRAGMENT = 0x2;
         const COMPUTE = 0x4;
+        const TASK = 0x8;
+        const MESH = 0x10;
+        /// The stages that run in workgroups.
+        const COMPUTE_LIKE = Self::COMPUTE.bits() | Self::TASK.bits() | Self::MESH.bits();
     }
 }
 
@@ -160,7 +187,7 @@ impl ops::Index<Handle<crate::Expression>> for ModuleInfo {
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Validator {
     flags: ValidationFlags,
     capabilities: Capabilities,
@@ -172,6 +199,34 @@ pub struct Validator {
     switch_values: FastHashSet<crate::SwitchValue>,
     valid_expression_list: Vec<Handle<crate::Expression>>,
     valid_expression_set: BitSet,
+    /// Every expression emitted so far in the current function, in any block.
+    emitted_expression_set: BitSet,
+    #[cfg(feature = "telemetry")]
+    statistics: ValidationStatistics,
+}
+
+/// Where [`Validator::validate`] spent its time.
+///
+/// This is only available with the `telemetry` feature. Retrieve it with
+/// [`Validator::statistics`] after a call to `validate`.
+#[cfg(feature = "telemetry")]
+#[derive(Clone, Debug, Default)]
+pub struct ValidationStatistics {
+    /// Time spent validating types and computing their layouts.
+    pub types: std::time::Duration,
+    /// Time spent validating constant expressions and constants.
+    pub constants: std::time::Duration,
+    /// Time spent validating global variables.
+    pub global_variables: std::time::Duration,
+    /// Time spent validating functions, not including entry points.
+    pub functions: std::time::Duration,
+    /// Time spent validating entry points.
+    pub entry_points: std::time::Duration,
+    /// Time spent in the whole call to [`Validator::validate`].
+    ///
+    /// This is recorded even if validation fails. The phases validation
+    /// didn't finish are left zero.
+    pub total: std::time::Duration,
 }
 
 #[derive(Clone, Debug, thiserror::Error)]
@@ -180,6 +235,99 @@ pub enum ConstantError {
     InvalidType,
     #[error("The type is not constructible")]
     NonConstructibleType,
+    #[error("Initializer{} is invalid", component_path(.path))]
+    Initializer {
+        /// The offending expression in [`Module::const_expressions`].
+        ///
+        /// [`Module::const_expressions`]: crate::Module::const_expressions
+        expression: Handle<crate::Expression>,
+        /// Component indices leading from the constant's initializer down to
+        /// `expression`, outermost first.
+        path: Vec<u32>,
+        source: Box<ConstExpressionError>,
+    },
+}
+
+fn component_path(path: &[u32]) -> String {
+    use std::fmt::Write as _;
+
+    let mut out = String::new();
+    if !path.is_empty() {
+        out.push_str(" component ");
+        for index in path {
+            let _ = write!(out, "[{index}]");
+        }
+    }
+    out
+}
+
+/// Find a constant whose initializer contains `expr`, and the component
+/// indices leading to it.
+///
+/// Only [`Compose`] components and [`Splat`] values are followed; references
+/// to other constants belong to those constants.
+///
+/// [`Compose`]: crate::Expression::Compose
+/// [`Splat`]: crate::Expression::Splat
+fn find_constant_component(
+    module: &crate::Module,
+    expr: Handle<crate::Expression>,
+) -> Option<(Handle<crate::Constant>, Vec<u32>)> {
+    fn search(
+        exprs: &crate::Arena<crate::Expression>,
+        root: Handle<crate::Expression>,
+        target: Handle<crate::Expression>,
+        path: &mut Vec<u32>,
+    ) -> bool {
+        if root == target {
+            return true;
+        }
+        match exprs[root] {
+            crate::Expression::Compose { ref components, .. } => {
+                for (index, &component) in components.iter().enumerate() {
+                    path.push(index as u32);
+                    if search(exprs, component, target, path) {
+                        return true;
+                    }
+                    path.pop();
+                }
+                false
+            }
+            crate::Expression::Splat { value, .. } => search(exprs, value, target, path),
+            _ => false,
+        }
+    }
+
+    module.constants.iter().find_map(|(handle, constant)| {
+        let mut path = Vec::new();
+        search(&module.const_expressions, constant.init, expr, &mut path).then_some((handle, path))
+    })
+}
+
+/// An informational diagnostic about a valid module.
+///
+/// The validator collects these in [`ModuleInfo::warnings`], when
+/// [`ValidationFlags`] asks for them.
+#[derive(Clone, Debug, thiserror::Error)]
+#[cfg_attr(test, derive(PartialEq))]
+pub enum ValidationWarning {
+    #[error(
+        "Expression {handle:?} indexes a function-local {composite} with a runtime index, \
+         which some backends can only do by copying it to memory or emitting a switch; \
+         consider using an array instead"
+    )]
+    DynamicIndex {
+        handle: Handle<crate::Expression>,
+        /// `"vector"` or `"matrix"`.
+        composite: &'static str,
+    },
+    /// A `derivative_uniformity` diagnostic whose severity a diagnostic
+    /// filter lowered to `warning` or `info`.
+    #[error("Derivative {handle:?} is computed in non-uniform control flow because of {cause:?}")]
+    NonUniformDerivative {
+        handle: Handle<crate::Expression>,
+        cause: UniformityDisruptor,
+    },
 }
 
 #[derive(Clone, Debug, thiserror::Error)]
@@ -223,8 +371,17 @@ pub enum ValidationError {
         name: String,
         source: EntryPointError,
     },
+    #[error("Enabled extensions {0:?} are not supported")]
+    UnsupportedExtensions(crate::EnableExtensions),
+    #[error("Enabled extensions {extensions:?} require the {capability:?} capability")]
+    MissingExtensionCapability {
+        extensions: crate::EnableExtensions,
+        capability: Capabilities,
+    },
     #[error("Module is corrupted")]
     Corrupted,
+    #[error("The validator's information is not for this module")]
+    StaleModuleInfo,
 }
 
 impl crate::TypeInner {
@@ -291,6 +448,9 @@ impl Validator {
             switch_values: FastHashSet::default(),
             valid_expression_list: Vec::new(),
             valid_expression_set: BitSet::new(),
+            emitted_expression_set: BitSet::new(),
+            #[cfg(feature = "telemetry")]
+            statistics: ValidationStatistics::default(),
         }
     }
 
@@ -303,6 +463,17 @@ impl Validator {
         self.switch_values.clear();
         self.valid_expression_list.clear();
         self.valid_expression_set.clear();
+        self.emitted_expression_set.clear();
+        #[cfg(feature = "telemetry")]
+        {
+            self.statistics = ValidationStatistics::default();
+        }
+    }
+
+    /// Return statistics about the last module validated.
+    #[cfg(feature = "telemetry")]
+    pub const fn statistics(&self) -> &ValidationStatistics {
+        &self.statistics
     }
 
     fn validate_constant(
@@ -327,16 +498,65 @@ impl Validator {
         Ok(())
     }
 
+    /// Check that every extension `extensions` enables is allowed by our
+    /// capabilities.
+    fn validate_enable_extensions(
+        &self,
+        extensions: crate::EnableExtensions,
+    ) -> Result<(), ValidationError> {
+        use crate::EnableExtensions as Ee;
+
+        // Naga has no IR representation for `f16` yet.
+        let unsupported = extensions & Ee::F16;
+        if !unsupported.is_empty() {
+            return Err(ValidationError::UnsupportedExtensions(unsupported));
+        }
+
+        for (extension, capability) in [
+            (Ee::DUAL_SOURCE_BLENDING, Capabilities::DUAL_SOURCE_BLENDING),
+            (Ee::CLIP_DISTANCES, Capabilities::CLIP_DISTANCE),
+            (Ee::NAGA_MESH_SHADER, Capabilities::MESH_SHADER),
+        ] {
+            if extensions.contains(extension) && !self.capabilities.contains(capability) {
+                return Err(ValidationError::MissingExtensionCapability {
+                    extensions: extension,
+                    capability,
+                });
+            }
+        }
+        Ok(())
+    }
+
     /// Check the given module to be valid.
     pub fn validate(
         &mut self,
         module: &crate::Module,
+    ) -> Result<ModuleInfo, WithSpan<ValidationError>> {
+        #[cfg(feature = "telemetry")]
+        let stopwatch = crate::telemetry::Stopwatch::start();
+
+        let result = self.validate_module(module);
+        #[cfg(feature = "telemetry")]
+        {
+            self.statistics.total = stopwatch.total();
+        }
+        result
+    }
+
+    fn validate_module(
+        &mut self,
+        module: &crate::Module,
     ) -> Result<ModuleInfo, WithSpan<ValidationError>> {
         self.reset();
         self.reset_types(module.types.len());
+        #[cfg(feature = "telemetry")]
+        let mut stopwatch = crate::telemetry::Stopwatch::start();
 
         Self::validate_module_handles(module).map_err(|e| e.with_span())?;
 
+        self.validate_enable_extensions(module.enable_extensions)
+            .map_err(|e| e.with_span())?;
+
         self.layouter.update(module.to_ctx()).map_err(|e| {
             let handle = e.ty;
             ValidationError::from(e).with_span_handle(handle, &module.types)
@@ -370,6 +590,10 @@ impl Validator {
             mod_info.type_flags.push(ty_info.flags);
             self.types[handle.index()] = ty_info;
         }
+        #[cfg(feature = "telemetry")]
+        {
+            self.statistics.types = stopwatch.lap();
+        }
 
         {
             let t = crate::Arena::new();
@@ -388,8 +612,22 @@ impl Validator {
             for (handle, _) in module.const_expressions.iter() {
                 self.validate_const_expression(handle, module.to_ctx(), &mod_info)
                     .map_err(|source| {
-                        ValidationError::ConstExpression { handle, source }
-                            .with_span_handle(handle, &module.const_expressions)
+                        // Blame the constant being initialized, if there is one.
+                        match find_constant_component(module, handle) {
+                            Some((constant, path)) => ValidationError::Constant {
+                                handle: constant,
+                                name: module.constants[constant].name.clone().unwrap_or_default(),
+                                source: ConstantError::Initializer {
+                                    expression: handle,
+                                    path,
+                                    source: Box::new(source),
+                                },
+                            }
+                            .with_span_handle(constant, &module.constants)
+                            .with_handle(handle, &module.const_expressions),
+                            None => ValidationError::ConstExpression { handle, source }
+                                .with_span_handle(handle, &module.const_expressions),
+                        }
                     })?
             }
 
@@ -405,6 +643,10 @@ impl Validator {
                     })?
             }
         }
+        #[cfg(feature = "telemetry")]
+        {
+            self.statistics.constants = stopwatch.lap();
+        }
 
         for (var_handle, var) in module.global_variables.iter() {
             self.validate_global_var(var, module.to_ctx(), &mod_info)
@@ -417,51 +659,181 @@ impl Validator {
                     .with_span_handle(var_handle, &module.global_variables)
                 })?;
         }
+        #[cfg(feature = "telemetry")]
+        {
+            self.statistics.global_variables = stopwatch.lap();
+        }
+
+        #[cfg(not(feature = "parallel"))]
+        self.validate_functions(module, &mut mod_info)?;
+        #[cfg(feature = "parallel")]
+        self.validate_functions_parallel(module, &mut mod_info)?;
+        #[cfg(feature = "telemetry")]
+        {
+            self.statistics.functions = stopwatch.lap();
+        }
+
+        #[cfg(not(feature = "parallel"))]
+        self.validate_entry_points(module, &mut mod_info)?;
+        #[cfg(feature = "parallel")]
+        self.validate_entry_points_parallel(module, &mut mod_info)?;
+        #[cfg(feature = "telemetry")]
+        {
+            self.statistics.entry_points = stopwatch.lap();
+        }
 
+        Ok(mod_info)
+    }
+
+    /// Validate `module`'s functions in order, appending their information
+    /// to `mod_info`.
+    fn validate_functions(
+        &mut self,
+        module: &crate::Module,
+        mod_info: &mut ModuleInfo,
+    ) -> Result<(), WithSpan<ValidationError>> {
         for (handle, fun) in module.functions.iter() {
-            match self.validate_function(fun, module, &mod_info, false) {
-                Ok(info) => mod_info.functions.push(info),
-                Err(error) => {
-                    return Err(error.and_then(|source| {
-                        ValidationError::Function {
-                            handle,
-                            name: fun.name.clone().unwrap_or_default(),
-                            source,
-                        }
-                        .with_span_handle(handle, &module.functions)
-                    }))
-                }
-            }
+            let info = self
+                .validate_function_impl(fun, module, mod_info, &mod_info.functions, false)
+                .map_err(|error| function_error(module, handle, error))?;
+            mod_info.functions.push(info);
         }
+        Ok(())
+    }
 
+    /// Validate `module`'s entry points in order, appending their
+    /// information to `mod_info`.
+    fn validate_entry_points(
+        &mut self,
+        module: &crate::Module,
+        mod_info: &mut ModuleInfo,
+    ) -> Result<(), WithSpan<ValidationError>> {
         let mut ep_map = FastHashSet::default();
         for ep in module.entry_points.iter() {
-            if !ep_map.insert((ep.stage, &ep.name)) {
-                return Err(ValidationError::EntryPoint {
-                    stage: ep.stage,
-                    name: ep.name.clone(),
-                    source: EntryPointError::Conflict,
-                }
-                .with_span()); // TODO: keep some EP span information?
-            }
+            check_entry_point_conflict(&mut ep_map, ep)?;
+            let info = self
+                .validate_entry_point(ep, module, mod_info)
+                .map_err(|error| entry_point_error(ep, error))?;
+            mod_info.entry_points.push(info);
+        }
+        Ok(())
+    }
 
-            match self.validate_entry_point(ep, module, &mod_info) {
-                Ok(info) => mod_info.entry_points.push(info),
-                Err(error) => {
-                    return Err(error.and_then(|source| {
-                        ValidationError::EntryPoint {
-                            stage: ep.stage,
-                            name: ep.name.clone(),
-                            source,
-                        }
-                        .with_span()
-                    }));
-                }
-            }
+    /// Revalidate a single function of a module that has already been
+    /// validated, and return its new [`FunctionInfo`].
+    ///
+    /// This is much cheaper than calling [`validate`] again, because it reuses
+    /// what `self` learned about the module's types, layouts, constants and
+    /// global variables during that call. For that to be sound:
+    ///
+    /// - `self` must be the validator that produced `mod_info`, and must not
+    ///   have validated any other module since. If they plainly describe a
+    ///   different module, this returns [`ValidationError::StaleModuleInfo`].
+    ///
+    /// - Since then, `module` may only have changed inside `function`'s
+    ///   local variables, expressions, named expressions and body. Changes
+    ///   to types, constants, constant expressions, global variables, entry
+    ///   points, or any function's arguments or result require full
+    ///   revalidation.
+    ///
+    /// The returned information is not stored in `mod_info`; use
+    /// [`ModuleInfo::replace_function`] for that. Callers of `function`, and
+    /// entry points that reach it, have information derived from the old
+    /// one, such as their available shader stages and uniformity
+    /// requirements. If those parts of the function's information change,
+    /// the module as a whole needs to be revalidated.
+    ///
+    /// [`validate`]: Validator::validate
+    pub fn validate_function(
+        &mut self,
+        module: &crate::Module,
+        handle: Handle<crate::Function>,
+        mod_info: &ModuleInfo,
+    ) -> Result<FunctionInfo, WithSpan<ValidationError>> {
+        if self.types.len() != module.types.len()
+            || mod_info.type_flags.len() != module.types.len()
+            || mod_info.functions.len() != module.functions.len()
+        {
+            return Err(ValidationError::StaleModuleInfo.with_span());
         }
+        let fun = &module.functions[handle];
 
-        Ok(mod_info)
+        Self::validate_function_handles(module, Some(handle), fun)
+            .map_err(|e| ValidationError::from(e).with_span())?;
+
+        // Like `validate`, only show `fun` the functions it may call.
+        let functions = &mod_info.functions[..handle.index()];
+        self.validate_function_impl(fun, module, mod_info, functions, false)
+            .map_err(|error| function_error(module, handle, error))
+    }
+}
+
+fn function_error(
+    module: &crate::Module,
+    handle: Handle<crate::Function>,
+    error: WithSpan<FunctionError>,
+) -> WithSpan<ValidationError> {
+    error.and_then(|source| {
+        ValidationError::Function {
+            handle,
+            name: module.functions[handle].name.clone().unwrap_or_default(),
+            source,
+        }
+        .with_span_handle(handle, &module.functions)
+    })
+}
+
+fn entry_point_error(
+    ep: &crate::EntryPoint,
+    error: WithSpan<EntryPointError>,
+) -> WithSpan<ValidationError> {
+    error.and_then(|source| {
+        ValidationError::EntryPoint {
+            stage: ep.stage,
+            name: ep.name.clone(),
+            source,
+        }
+        .with_span()
+    })
+}
+
+/// Record `ep` in `ep_map`, failing if an entry point with the same stage
+/// and name is already there.
+fn check_entry_point_conflict<'a>(
+    ep_map: &mut FastHashSet<(crate::ShaderStage, &'a String)>,
+    ep: &'a crate::EntryPoint,
+) -> Result<(), WithSpan<ValidationError>> {
+    if !ep_map.insert((ep.stage, &ep.name)) {
+        return Err(ValidationError::EntryPoint {
+            stage: ep.stage,
+            name: ep.name.clone(),
+            source: EntryPointError::Conflict,
+        }
+        .with_span()); // TODO: keep some EP span information?
+    }
+    Ok(())
+}
+
+/// Return the smallest set of [`Capabilities`] `module` needs to validate.
+///
+/// Each capability check in the validator depends on a single capability
+/// bit, so a bit is required exactly when withholding it alone makes
+/// validation fail. If `module` doesn't validate even with
+/// [`Capabilities::all`], return that error instead.
+pub fn required_capabilities(
+    module: &crate::Module,
+) -> Result<Capabilities, WithSpan<ValidationError>> {
+    let mut validator = Validator::new(ValidationFlags::all(), Capabilities::all());
+    validator.validate(module)?;
+
+    let mut required = Capabilities::empty();
+    for capability in Capabilities::all().iter() {
+        validator.capabilities = Capabilities::all() - capability;
+        if validator.validate(module).is_err() {
+            required |= capability;
+        }
     }
+    Ok(required)
 }
 
 fn validate_atomic_compare_exchange_struct(
@@ -0,0 +1,212 @@
+/*!
+Validating functions and entry points on multiple threads.
+
+Validating a function only reads module-level information, plus the
+[`FunctionInfo`]s of the functions it calls. Since a function may only call
+functions that precede it in the arena, we split [`Module::functions`] into
+consecutive batches in which no function calls another member of its batch,
+and validate each batch's functions in parallel once the batches before it are
+done. Entry points may call any function, but not each other, so they form a
+single batch.
+
+Each worker validates with its own clone of the [`Validator`], so that they
+don't contend for its scratch space.
+
+Results are collected in arena order, and the first error by handle index is
+reported, so the outcome is the same as validating on one thread.
+
+[`FunctionInfo`]: super::FunctionInfo
+[`Module::functions`]: crate::Module::functions
+*/
+
+use super::{
+    check_entry_point_conflict, entry_point_error, function_error, ModuleInfo, ValidationError,
+    Validator,
+};
+use crate::{span::WithSpan, FastHashSet};
+use rayon::prelude::*;
+
+impl Validator {
+    /// Like [`validate_functions`], but validate independent functions on
+    /// rayon's thread pool.
+    ///
+    /// [`validate_functions`]: Validator::validate_functions
+    pub(super) fn validate_functions_parallel(
+        &mut self,
+        module: &crate::Module,
+        mod_info: &mut ModuleInfo,
+    ) -> Result<(), WithSpan<ValidationError>> {
+        if rayon::current_num_threads() == 1 {
+            return self.validate_functions(module, mod_info);
+        }
+
+        let functions: Vec<_> = module.functions.iter().collect();
+        let mut start = 0;
+        while start < functions.len() {
+            let end = start
+                + 1
+                + functions[start + 1..]
+                    .iter()
+                    .take_while(|&&(_, fun)| !calls_any_from(&fun.body, start))
+                    .count();
+            let batch = &functions[start..end];
+
+            if let [(handle, fun)] = *batch {
+                let info = self
+                    .validate_function_impl(fun, module, mod_info, &mod_info.functions, false)
+                    .map_err(|error| function_error(module, handle, error))?;
+                mod_info.functions.push(info);
+            } else {
+                let results: Vec<_> = batch
+                    .par_iter()
+                    .map_init(
+                        || self.clone(),
+                        |validator, &(handle, fun)| {
+                            validator
+                                .validate_function_impl(
+                                    fun,
+                                    module,
+                                    mod_info,
+                                    &mod_info.functions,
+                                    false,
+                                )
+                                .map_err(|error| function_error(module, handle, error))
+                        },
+                    )
+                    .collect();
+                for result in results {
+                    mod_info.functions.push(result?);
+                }
+            }
+
+            start = end;
+        }
+        Ok(())
+    }
+
+    /// Like [`validate_entry_points`], but validate them on rayon's thread
+    /// pool.
+    ///
+    /// [`validate_entry_points`]: Validator::validate_entry_points
+    pub(super) fn validate_entry_points_parallel(
+        &mut self,
+        module: &crate::Module,
+        mod_info: &mut ModuleInfo,
+    ) -> Result<(), WithSpan<ValidationError>> {
+        if rayon::current_num_threads() == 1 || module.entry_points.len() < 2 {
+            return self.validate_entry_points(module, mod_info);
+        }
+
+        let results: Vec<_> = module
+            .entry_points
+            .par_iter()
+            .map_init(
+                || self.clone(),
+                |validator, ep| validator.validate_entry_point(ep, module, mod_info),
+            )
+            .collect();
+
+        let mut ep_map = FastHashSet::default();
+        for (ep, result) in module.entry_points.iter().zip(results) {
+            check_entry_point_conflict(&mut ep_map, ep)?;
+            let info = result.map_err(|error| entry_point_error(ep, error))?;
+            mod_info.entry_points.push(info);
+        }
+        Ok(())
+    }
+}
+
+/// Return true if `block` calls a function whose handle index is `first` or
+/// greater.
+fn calls_any_from(block: &crate::Block, first: usize) -> bool {
+    use crate::Statement as S;
+
+    block.iter().any(|statement| match *statement {
+        S::Call { function, .. } => function.index() >= first,
+        S::Block(ref block) => calls_any_from(block, first),
+        S::If {
+            ref accept,
+            ref reject,
+            ..
+        } => calls_any_from(accept, first) || calls_any_from(reject, first),
+        S::Switch { ref cases, .. } => cases.iter().any(|case| calls_any_from(&case.body, first)),
+        S::Loop {
+            ref body,
+            ref continuing,
+            ..
+        } => calls_any_from(body, first) || calls_any_from(continuing, first),
+        _ => false,
+    })
+}
+
+#[cfg(all(test, feature = "wgsl-in"))]
+mod tests {
+    use super::super::{Capabilities, ValidationFlags, Validator};
+
+    /// Validate `module` on a thread pool with `threads` threads, and return
+    /// the error as a string.
+    fn error_with_threads(module: &crate::Module, threads: usize) -> String {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .unwrap();
+        pool.install(|| {
+            let mut validator = Validator::new(ValidationFlags::all(), Capabilities::empty());
+            format!("{:?}", validator.validate(module).unwrap_err())
+        })
+    }
+
+    #[test]
+    fn same_errors_on_any_number_of_threads() {
+        let mut module = crate::front::wgsl::parse_str(
+            "
+            fn leaf(x: f32) -> f32 { return x; }
+            fn a(x: f32) -> f32 { return leaf(x); }
+            fn b(x: f32) -> f32 { return leaf(x) * 2.0; }
+            fn c(x: f32) -> f32 { return leaf(x) + 1.0; }
+            fn d(x: f32) -> f32 { return c(x); }
+
+            @compute @workgroup_size(1)
+            fn main() { _ = d(1.0); }
+            ",
+        )
+        .unwrap();
+
+        let mut validator = Validator::new(ValidationFlags::all(), Capabilities::empty());
+        validator.validate(&module).unwrap();
+
+        // `a`, `b` and `c` share a batch. Break `b` and `c`: the error
+        // should always be the one for `b`.
+        for (_, fun) in module.functions.iter_mut() {
+            if matches!(fun.name.as_deref(), Some("b" | "c")) {
+                fun.result.as_mut().unwrap().binding = Some(crate::Binding::Location {
+                    location: 0,
+                    second_blend_source: false,
+                    interpolation: None,
+                    sampling: None,
+                });
+            }
+        }
+        let serial = error_with_threads(&module, 1);
+        assert!(serial.contains("name: \"b\""), "{serial}");
+        for threads in [2, 4] {
+            assert_eq!(error_with_threads(&module, threads), serial);
+        }
+
+        // Entry point conflicts are reported in order, too.
+        let mut module = crate::front::wgsl::parse_str(
+            "
+            @compute @workgroup_size(1) fn main() {}
+            @compute @workgroup_size(1) fn other() {}
+            @compute @workgroup_size(1) fn third() {}
+            ",
+        )
+        .unwrap();
+        module.entry_points[1].name = "main".to_string();
+        let serial = error_with_threads(&module, 1);
+        assert!(serial.contains("Conflict"), "{serial}");
+        for threads in [2, 4] {
+            assert_eq!(error_with_threads(&module, threads), serial);
+        }
+    }
+}
@@ -62,6 +62,51 @@ bitflags::bitflags! {
     }
 }
 
+/// How WGSL classifies a type, for reporting where it can't be used.
+///
+/// See the WGSL spec's [type classification][tc].
+///
+/// [tc]: https://gpuweb.github.io/gpuweb/wgsl/#types
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+pub enum TypeClass {
+    /// Scalars, vectors, matrices, and fixed-size arrays and structs of
+    /// constructible types. See [`TypeFlags::CONSTRUCTIBLE`].
+    Constructible,
+    /// A plain type that isn't constructible: an atomic, a runtime-sized
+    /// array, or a composite containing one.
+    Plain,
+    /// An opaque handle, like a texture, sampler, or acceleration structure.
+    Handle,
+    /// A pointer.
+    Pointer,
+}
+
+impl TypeClass {
+    pub(super) const fn classify(flags: TypeFlags, inner: &crate::TypeInner) -> Self {
+        match *inner {
+            crate::TypeInner::Pointer { .. } | crate::TypeInner::ValuePointer { .. } => {
+                Self::Pointer
+            }
+            _ if flags.contains(TypeFlags::CONSTRUCTIBLE) => Self::Constructible,
+            _ if flags.contains(TypeFlags::DATA) => Self::Plain,
+            _ => Self::Handle,
+        }
+    }
+}
+
+impl std::fmt::Display for TypeClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match *self {
+            Self::Constructible => "a constructible type",
+            Self::Plain => "a plain type that isn't constructible",
+            Self::Handle => "a handle type",
+            Self::Pointer => "a pointer type",
+        })
+    }
+}
+
 #[derive(Clone, Copy, Debug, thiserror::Error)]
 pub enum Disalignment {
     #[error("The array stride {stride} is not a multiple of the required alignment {alignment}")]
@@ -99,14 +144,18 @@ pub enum TypeError {
         base: Handle<crate::Type>,
         space: crate::AddressSpace,
     },
-    #[error("Expected data type, found {0:?}")]
-    InvalidData(Handle<crate::Type>),
+    #[error("Expected data type, found {0:?}, which is {1}")]
+    InvalidData(Handle<crate::Type>, TypeClass),
     #[error("Base type {0:?} for the array is invalid")]
     InvalidArrayBaseType(Handle<crate::Type>),
     #[error("Matrix elements must always be floating-point types")]
     MatrixElementNotFloat,
-    #[error("The constant {0:?} is specialized, and cannot be used as an array size")]
-    UnsupportedSpecializedArrayLength(Handle<crate::Constant>),
+    #[error(
+        "The constant {0:?} is not an integer override, and cannot be used as a pending array size"
+    )]
+    InvalidPendingArrayLength(Handle<crate::Constant>),
+    #[error("Binding arrays can't be sized by an override")]
+    PendingBindingArraySize,
     #[error("{} of dimensionality {dim:?} and class {class:?} are not supported", if *.arrayed {"Arrayed images"} else {"Images"})]
     UnsupportedImageType {
         dim: crate::ImageDimension,
@@ -461,6 +510,23 @@ impl super::Validator {
                             | TypeFlags::ARGUMENT
                             | TypeFlags::CONSTRUCTIBLE
                     }
+                    crate::ArraySize::Pending(size_constant) => {
+                        let constant = &gctx.constants[size_constant];
+                        let is_integer = matches!(
+                            gctx.types[constant.ty].inner,
+                            Ti::Scalar(crate::Scalar {
+                                kind: crate::ScalarKind::Sint | crate::ScalarKind::Uint,
+                                ..
+                            })
+                        );
+                        if constant.r#override == crate::Override::None || !is_integer {
+                            return Err(TypeError::InvalidPendingArrayLength(size_constant));
+                        }
+                        // The size isn't known until pipeline creation, so these
+                        // arrays are not SIZED. Workgroup variables make an exception
+                        // for them; see `Validator::validate_global_var`.
+                        TypeFlags::DATA | TypeFlags::COPY
+                    }
                     crate::ArraySize::Dynamic => {
                         // Non-SIZED types may only appear as the last element of a structure.
                         // This is enforced by checks for SIZED-ness for all compound types,
@@ -499,7 +565,9 @@ impl super::Validator {
                 for (i, member) in members.iter().enumerate() {
                     let base_info = &self.types[member.ty.index()];
                     if !base_info.flags.contains(TypeFlags::DATA) {
-                        return Err(TypeError::InvalidData(member.ty));
+                        let class =
+                            TypeClass::classify(base_info.flags, &gctx.types[member.ty].inner);
+                        return Err(TypeError::InvalidData(member.ty, class));
                     }
                     if !base_info.flags.contains(TypeFlags::HOST_SHAREABLE) {
                         if ti.uniform_layout.is_ok() {
@@ -604,7 +672,10 @@ impl super::Validator {
                 arrayed,
                 class,
             } => {
-                if arrayed && matches!(dim, crate::ImageDimension::D3) {
+                let external = class == crate::ImageClass::External;
+                if (arrayed && matches!(dim, crate::ImageDimension::D3))
+                    || (external && (arrayed || dim != crate::ImageDimension::D2))
+                {
                     return Err(TypeError::UnsupportedImageType {
                         dim,
                         arrayed,
@@ -614,6 +685,20 @@ impl super::Validator {
                 if arrayed && matches!(dim, crate::ImageDimension::Cube) {
                     self.require_type_capability(Capabilities::CUBE_ARRAY_TEXTURES)?;
                 }
+                if let crate::ImageClass::Sampled { kind, .. } = class {
+                    if !matches!(
+                        kind,
+                        crate::ScalarKind::Float
+                            | crate::ScalarKind::Sint
+                            | crate::ScalarKind::Uint
+                    ) {
+                        return Err(TypeError::UnsupportedImageType {
+                            dim,
+                            arrayed,
+                            class,
+                        });
+                    }
+                }
                 TypeInfo::new(TypeFlags::ARGUMENT, Alignment::ONE)
             }
             Ti::Sampler { .. } => TypeInfo::new(TypeFlags::ARGUMENT, Alignment::ONE),
@@ -634,6 +719,7 @@ impl super::Validator {
                 }
                 let type_info_mask = match size {
                     crate::ArraySize::Constant(_) => TypeFlags::SIZED | TypeFlags::HOST_SHAREABLE,
+                    crate::ArraySize::Pending(_) => return Err(TypeError::PendingBindingArraySize),
                     crate::ArraySize::Dynamic => {
                         // Final type is non-sized
                         TypeFlags::HOST_SHAREABLE
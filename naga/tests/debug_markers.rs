@@ -0,0 +1,91 @@
+/*!
+Test that `DebugMarker` statements from WGSL comments reach textual output in
+order, and have no effect on validation or SPIR-V.
+*/
+
+#![cfg(all(feature = "wgsl-in", feature = "msl-out", feature = "spv-out"))]
+
+use naga::back::{msl, spv};
+use naga::valid;
+
+const SOURCE: &str = "
+@fragment
+fn main(@location(0) x: f32) -> @location(0) vec4<f32> {
+    //@marker first
+    var v = x;
+    if x > 0.5 {
+        //@marker in accept
+        v *= 2.0;
+    } else {
+        // not a marker
+        //@markers are not markers either
+    }
+    //@marker   last, with spaces  
+    return vec4(v);
+    //@marker unreachable
+}
+";
+
+fn markers(block: &naga::Block, out: &mut Vec<String>) {
+    for statement in block.iter() {
+        match *statement {
+            naga::Statement::DebugMarker(ref text) => out.push(text.clone()),
+            naga::Statement::Block(ref block) => markers(block, out),
+            naga::Statement::If {
+                ref accept,
+                ref reject,
+                ..
+            } => {
+                markers(accept, out);
+                markers(reject, out);
+            }
+            _ => {}
+        }
+    }
+}
+
+const EXPECTED: [&str; 4] = ["first", "in accept", "last, with spaces", "unreachable"];
+
+#[test]
+fn markers_reach_msl_in_order() {
+    let module = naga::front::wgsl::parse_str(SOURCE).unwrap();
+    let mut found = Vec::new();
+    markers(&module.entry_points[0].function.body, &mut found);
+    assert_eq!(found, EXPECTED);
+
+    let info = valid::Validator::new(valid::ValidationFlags::all(), valid::Capabilities::all())
+        .validate(&module)
+        .expect("markers after `return` should be allowed");
+    let (source, _) = msl::write_string(
+        &module,
+        &info,
+        &msl::Options::default(),
+        &msl::PipelineOptions::default(),
+    )
+    .unwrap();
+
+    let comments: Vec<_> = source
+        .lines()
+        .skip_while(|line| !line.starts_with("fragment"))
+        .filter_map(|line| line.trim().strip_prefix("// "))
+        .collect();
+    assert_eq!(comments, EXPECTED, "{source}");
+}
+
+#[test]
+fn markers_do_not_affect_spirv() {
+    let write = |source: &str| {
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+        let info = valid::Validator::new(valid::ValidationFlags::all(), valid::Capabilities::all())
+            .validate(&module)
+            .unwrap();
+        spv::write_vec(&module, &info, &spv::Options::default(), None).unwrap()
+    };
+
+    let without: String = SOURCE
+        .lines()
+        .filter(|line| !line.trim().starts_with("//@marker "))
+        .map(|line| format!("{line}\n"))
+        .collect();
+    assert_eq!(write(SOURCE), write(&without));
+}
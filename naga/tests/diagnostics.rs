@@ -0,0 +1,151 @@
+/*!
+Tests for [`naga::error::Diagnostic`] conversions and rendering.
+*/
+
+use naga::error::Diagnostic;
+#[cfg(feature = "json")]
+use serde_json::json;
+
+#[cfg(feature = "json")]
+fn to_json(diagnostic: &Diagnostic, source: Option<&str>) -> serde_json::Value {
+    serde_json::from_str(&diagnostic.emit_to_json(source)).unwrap()
+}
+
+#[cfg(all(feature = "json", feature = "wgsl-in"))]
+#[test]
+fn parse_error_json() {
+    let source = "fn () {}";
+    let error = naga::front::wgsl::parse_str(source).expect_err("expected parser error");
+    assert_eq!(
+        to_json(&Diagnostic::from(&error), Some(source)),
+        json!({
+            "severity": "error",
+            "code": "wgsl::parse",
+            "message": "expected identifier, found '('",
+            "labels": [{
+                "start": 3,
+                "end": 4,
+                "line": 1,
+                "column": 4,
+                "message": "expected identifier",
+            }],
+            "notes": [],
+        })
+    );
+}
+
+#[cfg(all(feature = "json", feature = "wgsl-in"))]
+#[test]
+fn validation_error_json() {
+    let source = "fn f() -> i32 { return 1u; }";
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+    let error = naga::valid::Validator::new(
+        naga::valid::ValidationFlags::all(),
+        naga::valid::Capabilities::empty(),
+    )
+    .validate(&module)
+    .expect_err("expected validation error");
+    assert_eq!(
+        to_json(&Diagnostic::from(&error), Some(source)),
+        json!({
+            "severity": "error",
+            "code": "validation",
+            "message": "Function [1] 'f' is invalid",
+            "labels": [
+                {
+                    "start": 0,
+                    "end": 26,
+                    "line": 1,
+                    "column": 1,
+                    "message": "naga::Function [1]",
+                },
+                {
+                    "start": 23,
+                    "end": 25,
+                    "line": 1,
+                    "column": 24,
+                    "message": "naga::Expression [1]",
+                },
+            ],
+            "notes": ["The `return` value Some([1]) does not match the function return value"],
+        })
+    );
+}
+
+#[cfg(all(feature = "json", feature = "glsl-in"))]
+#[test]
+fn glsl_parse_error_json() {
+    let source = "#version 450\nvoid main() { x = 1; }\n";
+    let error = naga::front::glsl::Frontend::default()
+        .parse(
+            &naga::front::glsl::Options::from(naga::ShaderStage::Vertex),
+            source,
+        )
+        .expect_err("expected parser error");
+    assert_eq!(
+        to_json(&Diagnostic::from(&error.errors[0]), Some(source)),
+        json!({
+            "severity": "error",
+            "code": "glsl::parse",
+            "message": "Unknown variable: x",
+            "labels": [{
+                "start": 27,
+                "end": 28,
+                "line": 2,
+                "column": 15,
+                "message": "",
+            }],
+            "notes": [],
+        })
+    );
+}
+
+#[cfg(all(feature = "json", feature = "wgsl-in", feature = "spv-out"))]
+#[test]
+fn backend_error_json() {
+    let module = naga::front::wgsl::parse_str("@compute @workgroup_size(1) fn main() {}").unwrap();
+    let info = naga::valid::Validator::new(
+        naga::valid::ValidationFlags::all(),
+        naga::valid::Capabilities::all(),
+    )
+    .validate(&module)
+    .unwrap();
+    let error = naga::back::spv::write_vec(
+        &module,
+        &info,
+        &naga::back::spv::Options::default(),
+        Some(&naga::back::spv::PipelineOptions {
+            shader_stage: naga::ShaderStage::Compute,
+            entry_point: "missing".to_string(),
+        }),
+    )
+    .expect_err("expected writer error");
+    assert_eq!(
+        to_json(&Diagnostic::from(&error), None),
+        json!({
+            "severity": "error",
+            "code": "spv::write",
+            "message": "The requested Compute entry point `missing` couldn't be found; \
+                        the module's entry points are: `main` (Compute)",
+            "labels": [],
+            "notes": [],
+        })
+    );
+}
+
+#[cfg(feature = "wgsl-in")]
+#[test]
+fn parse_error_text() {
+    let source = "fn () {}";
+    let error = naga::front::wgsl::parse_str(source).expect_err("expected parser error");
+    assert_eq!(
+        Diagnostic::from(&error).emit_to_string_with_path(source, "wgsl"),
+        r###"error[wgsl::parse]: expected identifier, found '('
+  ┌─ wgsl:1:4
+  │
+1 │ fn () {}
+  │    ^ expected identifier
+
+"###
+    );
+}
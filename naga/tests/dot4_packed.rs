@@ -0,0 +1,136 @@
+/*!
+Test `dot4I8Packed` and `dot4U8Packed`: their constant folding against
+reference values, and their validation.
+*/
+
+#![cfg(feature = "wgsl-in")]
+
+use naga::{valid, Literal};
+
+/// Operands that exercise the extremes of each byte, and sign extension.
+const CASES: &[(u32, u32)] = &[
+    (0, 0),
+    (0x0102_0304, 0x0506_0708),
+    (0x8080_8080, 0x8080_8080),
+    (0x7f7f_7f7f, 0x8080_8080),
+    (0xffff_ffff, 0xffff_ffff),
+    (0xffff_ffff, 0x0102_0304),
+    (0x0000_00ff, 0x0000_00ff),
+    (0xff00_0000, 0x0100_0000),
+    (0x7f80_ff01, 0x807f_01ff),
+];
+
+fn reference_i8(a: u32, b: u32) -> i32 {
+    (0..4)
+        .map(|byte| {
+            let a = (a >> (byte * 8)) as u8 as i8;
+            let b = (b >> (byte * 8)) as u8 as i8;
+            a as i32 * b as i32
+        })
+        .sum()
+}
+
+fn reference_u8(a: u32, b: u32) -> u32 {
+    (0..4)
+        .map(|byte| ((a >> (byte * 8)) & 0xff) * ((b >> (byte * 8)) & 0xff))
+        .sum()
+}
+
+/// Return the value of the constant `name` in `module`.
+fn constant(module: &naga::Module, name: &str) -> Literal {
+    let (_, constant) = module
+        .constants
+        .iter()
+        .find(|&(_, constant)| constant.name.as_deref() == Some(name))
+        .unwrap();
+    match module.const_expressions[constant.init] {
+        naga::Expression::Literal(literal) => literal,
+        ref other => panic!("{name} was not folded: {other:?}"),
+    }
+}
+
+fn fold(source: &str) -> (naga::Module, valid::ModuleInfo) {
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+    let info = valid::Validator::new(
+        valid::ValidationFlags::all(),
+        valid::Capabilities::default(),
+    )
+    .validate(&module)
+    .expect("validation failed");
+    (module, info)
+}
+
+#[test]
+fn constant_folding() {
+    for &(a, b) in CASES {
+        let (module, _) = fold(&format!(
+            "
+            const A = {a}u;
+            const B = {b}u;
+            const SIGNED = dot4I8Packed(A, B);
+            const UNSIGNED = dot4U8Packed(A, B);
+            "
+        ));
+        let expected = Literal::I32(reference_i8(a, b));
+        assert_eq!(constant(&module, "SIGNED"), expected, "{a:#x}, {b:#x}");
+        let expected = Literal::U32(reference_u8(a, b));
+        assert_eq!(constant(&module, "UNSIGNED"), expected, "{a:#x}, {b:#x}");
+    }
+}
+
+/// The folded values must survive being written out and parsed again, so
+/// that every backend sees the same constants.
+#[cfg(feature = "wgsl-out")]
+#[test]
+fn folded_round_trip() {
+    let (module, info) = fold(
+        "
+        const SIGNED = dot4I8Packed(0x80808080, 0xffffffff);
+        const UNSIGNED = dot4U8Packed(0xffffffff, 0x04030201);
+        ",
+    );
+    assert_eq!(constant(&module, "SIGNED"), Literal::I32(512));
+    assert_eq!(constant(&module, "UNSIGNED"), Literal::U32(2550));
+
+    let output =
+        naga::back::wgsl::write_string(&module, &info, naga::back::wgsl::WriterFlags::empty())
+            .unwrap();
+    let module = naga::front::wgsl::parse_str(&output).unwrap();
+    assert_eq!(constant(&module, "SIGNED"), Literal::I32(512));
+    assert_eq!(constant(&module, "UNSIGNED"), Literal::U32(2550));
+}
+
+#[test]
+fn operands_must_be_u32() {
+    let module = naga::front::wgsl::parse_str(
+        "
+        fn f(x: i32) -> i32 {
+            return dot4I8Packed(x, 1u);
+        }
+        ",
+    )
+    .unwrap();
+    let error = valid::Validator::new(
+        valid::ValidationFlags::all(),
+        valid::Capabilities::default(),
+    )
+    .validate(&module)
+    .expect_err("an `i32` operand should be rejected");
+    assert!(
+        matches!(
+            *error.as_inner(),
+            valid::ValidationError::Function {
+                source: valid::FunctionError::Expression {
+                    source: valid::ExpressionError::InvalidArgumentType(
+                        naga::MathFunction::Dot4I8Packed,
+                        0,
+                        _
+                    ),
+                    ..
+                },
+                ..
+            }
+        ),
+        "unexpected error: {error:?}"
+    );
+}
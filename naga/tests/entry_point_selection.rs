@@ -0,0 +1,123 @@
+/*!
+Tests for how backends select a single entry point to write.
+*/
+
+#![cfg(all(feature = "wgsl-in", feature = "spv-out", feature = "glsl-out"))]
+
+use naga::back::{glsl, spv, EntryPointNames};
+use naga::valid::{Capabilities, ModuleInfo, ValidationFlags, Validator};
+use naga::ShaderStage;
+
+const SOURCE: &str = "
+@vertex
+fn main() -> @builtin(position) vec4<f32> {
+    return vec4<f32>(0.0);
+}
+
+@compute @workgroup_size(1)
+fn main_cs() {}
+";
+
+fn parse() -> (naga::Module, ModuleInfo) {
+    let module = naga::front::wgsl::parse_str(SOURCE).unwrap();
+    let info = Validator::new(ValidationFlags::all(), Capabilities::default())
+        .validate(&module)
+        .unwrap();
+    (module, info)
+}
+
+fn write_spv(
+    module: &naga::Module,
+    info: &ModuleInfo,
+    stage: ShaderStage,
+    name: &str,
+) -> Result<Vec<u32>, spv::Error> {
+    let pipeline_options = spv::PipelineOptions {
+        shader_stage: stage,
+        entry_point: name.to_string(),
+    };
+    spv::write_vec(
+        module,
+        info,
+        &spv::Options::default(),
+        Some(&pipeline_options),
+    )
+}
+
+fn write_glsl(
+    module: &naga::Module,
+    info: &ModuleInfo,
+    stage: ShaderStage,
+    name: &str,
+) -> Result<String, glsl::Error> {
+    let pipeline_options = glsl::PipelineOptions {
+        shader_stage: stage,
+        entry_point: name.to_string(),
+        multiview: None,
+    };
+    let options = glsl::Options::default();
+    let mut buffer = String::new();
+    let mut writer = glsl::Writer::new(
+        &mut buffer,
+        module,
+        info,
+        &options,
+        &pipeline_options,
+        naga::proc::BoundsCheckPolicies::default(),
+    )?;
+    writer.write()?;
+    Ok(buffer)
+}
+
+#[test]
+fn not_found() {
+    let (module, info) = parse();
+    let available = EntryPointNames(vec![
+        (ShaderStage::Vertex, "main".to_string()),
+        (ShaderStage::Compute, "main_cs".to_string()),
+    ]);
+
+    // The name exists, but not for this stage.
+    match write_spv(&module, &info, ShaderStage::Fragment, "main") {
+        Err(spv::Error::EntryPointNotFound {
+            stage: ShaderStage::Fragment,
+            ref name,
+            available: ref names,
+        }) => {
+            assert_eq!(name, "main");
+            assert_eq!(*names, available);
+        }
+        other => panic!("unexpected result: {other:?}"),
+    }
+
+    let error = write_glsl(&module, &info, ShaderStage::Vertex, "missing").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "The requested Vertex entry point `missing` couldn't be found; \
+         the module's entry points are: `main` (Vertex), `main_cs` (Compute)"
+    );
+}
+
+#[test]
+fn ambiguous() {
+    let (mut module, info) = parse();
+
+    // Validation would reject this, so change the module afterwards.
+    let duplicate = module.entry_points[0].clone();
+    module.entry_points.push(duplicate);
+
+    match write_spv(&module, &info, ShaderStage::Vertex, "main") {
+        Err(spv::Error::AmbiguousEntryPoint {
+            stage: ShaderStage::Vertex,
+            ref name,
+        }) => assert_eq!(name, "main"),
+        other => panic!("unexpected result: {other:?}"),
+    }
+    match write_glsl(&module, &info, ShaderStage::Vertex, "main") {
+        Err(glsl::Error::AmbiguousEntryPoint {
+            stage: ShaderStage::Vertex,
+            ref name,
+        }) => assert_eq!(name, "main"),
+        other => panic!("unexpected result: {other:?}"),
+    }
+}
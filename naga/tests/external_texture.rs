@@ -0,0 +1,156 @@
+/*!
+Test lowering `texture_external` to ordinary bindings.
+*/
+
+#![cfg(all(feature = "wgsl-in", feature = "wgsl-out", feature = "spv-out"))]
+
+use naga::external_texture::{self, ExternalTextureBindings, LoweredTexture};
+use naga::ResourceBinding;
+use rspirv::spirv::Op;
+
+const SHADER: &str = "
+    @group(0) @binding(0) var t: texture_external;
+    @group(0) @binding(1) var s: sampler;
+
+    @fragment
+    fn main(@builtin(position) position: vec4<f32>) -> @location(0) vec4<f32> {
+        let size = textureDimensions(t);
+        let uv = position.xy / vec2<f32>(size);
+        return textureSampleBaseClampToEdge(t, s, uv) + textureLoad(t, vec2<u32>(position.xy));
+    }
+";
+
+fn binding(group: u32, binding: u32) -> ResourceBinding {
+    ResourceBinding { group, binding }
+}
+
+fn validate(module: &naga::Module) -> naga::valid::ModuleInfo {
+    naga::valid::Validator::new(
+        naga::valid::ValidationFlags::all(),
+        naga::valid::Capabilities::default(),
+    )
+    .validate(module)
+    .unwrap()
+}
+
+/// Lower `SHADER`'s external texture to `bindings`, and return the lowered
+/// module's WGSL and SPIR-V.
+fn lower(bindings: ExternalTextureBindings) -> (Vec<LoweredTexture>, String, Vec<Op>) {
+    let mut module = naga::front::wgsl::parse_str(SHADER).unwrap();
+    validate(&module);
+
+    let binding_map = [(binding(0, 0), bindings)].into_iter().collect();
+    let lowered = external_texture::lower(&mut module, &binding_map).unwrap();
+    assert!(!external_texture::has_external_textures(&module));
+    let info = validate(&module);
+
+    let wgsl =
+        naga::back::wgsl::write_string(&module, &info, naga::back::wgsl::WriterFlags::empty())
+            .unwrap();
+    let words =
+        naga::back::spv::write_vec(&module, &info, &naga::back::spv::Options::default(), None)
+            .unwrap();
+    let spirv = rspirv::dr::load_words(words).unwrap();
+    let ops = spirv
+        .all_inst_iter()
+        .map(|instruction| instruction.class.opcode)
+        .collect();
+    (lowered, wgsl, ops)
+}
+
+fn count(ops: &[Op], op: Op) -> usize {
+    ops.iter().filter(|&&other| other == op).count()
+}
+
+#[test]
+fn external_texture_round_trips() {
+    let module = naga::front::wgsl::parse_str(SHADER).unwrap();
+    let info = validate(&module);
+    let wgsl =
+        naga::back::wgsl::write_string(&module, &info, naga::back::wgsl::WriterFlags::empty())
+            .unwrap();
+    assert!(wgsl.contains("var t: texture_external;"));
+    assert!(wgsl.contains("textureSampleBaseClampToEdge(t, s, "));
+}
+
+#[test]
+fn single_plane() {
+    let (lowered, wgsl, ops) = lower(ExternalTextureBindings::SinglePlane {
+        plane: binding(1, 0),
+    });
+
+    assert_eq!(
+        lowered,
+        [LoweredTexture {
+            binding: binding(0, 0),
+            plane: lowered[0].plane,
+            chroma: None,
+            params: None,
+        }]
+    );
+    assert!(!wgsl.contains("texture_external"));
+    assert!(wgsl.contains("@group(1) @binding(0) \nvar t: texture_2d<f32>;"));
+    assert!(wgsl.contains("textureSampleLevel(t, s, clamp("));
+    assert!(!wgsl.contains("yuv_conversion_matrix"));
+
+    assert_eq!(count(&ops, Op::ImageSampleExplicitLod), 1);
+    assert_eq!(count(&ops, Op::ImageFetch), 1);
+    assert_eq!(count(&ops, Op::MatrixTimesVector), 0);
+}
+
+#[test]
+fn two_plane() {
+    let (lowered, wgsl, ops) = lower(ExternalTextureBindings::TwoPlane {
+        luma: binding(1, 0),
+        chroma: binding(1, 1),
+        params: binding(1, 2),
+    });
+
+    assert_eq!(lowered.len(), 1);
+    assert_eq!(lowered[0].binding, binding(0, 0));
+    assert!(lowered[0].chroma.is_some());
+    assert!(lowered[0].params.is_some());
+
+    assert!(wgsl.contains("@group(1) @binding(0) \nvar t: texture_2d<f32>;"));
+    assert!(wgsl.contains("@group(1) @binding(1) \nvar t_chroma: texture_2d<f32>;"));
+    assert!(
+        wgsl.contains("@group(1) @binding(2) \nvar<uniform> t_params: NagaExternalTextureParams;")
+    );
+    assert!(wgsl.contains("textureSampleLevel(t_chroma, s, clamp("));
+    // The chroma plane has half the luma plane's resolution.
+    assert!(wgsl.contains(">> vec2(1u)"));
+
+    // Both the sample and the load are converted to RGB.
+    assert_eq!(count(&ops, Op::ImageSampleExplicitLod), 2);
+    assert_eq!(count(&ops, Op::ImageFetch), 2);
+    assert_eq!(count(&ops, Op::MatrixTimesVector), 2);
+}
+
+#[test]
+fn missing_bindings() {
+    let mut module = naga::front::wgsl::parse_str(SHADER).unwrap();
+    let binding_map = [(
+        binding(0, 1),
+        ExternalTextureBindings::SinglePlane {
+            plane: binding(1, 0),
+        },
+    )]
+    .into_iter()
+    .collect();
+    assert_eq!(
+        external_texture::lower(&mut module, &binding_map),
+        Err(external_texture::Error::MissingBindings(binding(0, 0)))
+    );
+}
+
+#[test]
+fn backends_require_lowering() {
+    let module = naga::front::wgsl::parse_str(SHADER).unwrap();
+    let info = validate(&module);
+    assert!(matches!(
+        naga::back::spv::write_vec(&module, &info, &naga::back::spv::Options::default(), None),
+        Err(naga::back::spv::Error::FeatureNotImplemented(
+            "external textures"
+        ))
+    ));
+}
@@ -0,0 +1,183 @@
+/*!
+Tests for folding accesses to constants with `naga::proc::fold_constants`.
+*/
+
+#![cfg(feature = "spv-out")]
+
+use naga::{Expression, Literal, Span, SwizzleComponent as Sc};
+
+/// Build a module with a constant `V = vec3(1.0, 2.0, 3.0)` and a function
+/// returning `V.zy[1] + V[0]`, as a frontend that doesn't evaluate constant
+/// expressions would.
+///
+/// Return the module and the handles of `V.zy`, `V.zy[1]` and `V[0]`.
+fn build() -> (naga::Module, [naga::Handle<Expression>; 3]) {
+    let mut module = naga::Module::default();
+    let f32_ty = module.types.insert(
+        naga::Type {
+            name: None,
+            inner: naga::TypeInner::Scalar(naga::Scalar::F32),
+        },
+        Span::UNDEFINED,
+    );
+    let vec3_ty = module.types.insert(
+        naga::Type {
+            name: None,
+            inner: naga::TypeInner::Vector {
+                size: naga::VectorSize::Tri,
+                scalar: naga::Scalar::F32,
+            },
+        },
+        Span::UNDEFINED,
+    );
+    let components = [1.0, 2.0, 3.0]
+        .into_iter()
+        .map(|value| {
+            module
+                .const_expressions
+                .append(Expression::Literal(Literal::F32(value)), Span::UNDEFINED)
+        })
+        .collect();
+    let init = module.const_expressions.append(
+        Expression::Compose {
+            ty: vec3_ty,
+            components,
+        },
+        Span::UNDEFINED,
+    );
+    let v = module.constants.append(
+        naga::Constant {
+            name: Some("V".to_string()),
+            r#override: naga::Override::None,
+            ty: vec3_ty,
+            init,
+        },
+        Span::UNDEFINED,
+    );
+
+    let mut function = naga::Function {
+        name: Some("main".to_string()),
+        result: Some(naga::FunctionResult {
+            ty: f32_ty,
+            binding: None,
+        }),
+        ..Default::default()
+    };
+    let expressions = &mut function.expressions;
+    let constant = expressions.append(Expression::Constant(v), Span::UNDEFINED);
+    let swizzle = expressions.append(
+        Expression::Swizzle {
+            size: naga::VectorSize::Bi,
+            vector: constant,
+            pattern: [Sc::Z, Sc::Y, Sc::X, Sc::X],
+        },
+        Span::UNDEFINED,
+    );
+    let access_index = expressions.append(
+        Expression::AccessIndex {
+            base: swizzle,
+            index: 1,
+        },
+        Span::UNDEFINED,
+    );
+    let index = expressions.append(Expression::Literal(Literal::I32(0)), Span::UNDEFINED);
+    let access = expressions.append(
+        Expression::Access {
+            base: constant,
+            index,
+        },
+        Span::UNDEFINED,
+    );
+    let sum = expressions.append(
+        Expression::Binary {
+            op: naga::BinaryOperator::Add,
+            left: access_index,
+            right: access,
+        },
+        Span::UNDEFINED,
+    );
+    function.body.push(
+        naga::Statement::Return { value: Some(sum) },
+        Span::UNDEFINED,
+    );
+    naga::proc::ensure_emits(&mut function);
+    module.functions.append(function, Span::UNDEFINED);
+
+    (module, [swizzle, access_index, access])
+}
+
+/// Validate `module` and return the number of instructions in the bodies of
+/// its SPIR-V functions, and how many of those were `OpVectorShuffle`.
+fn spv_instructions(module: &naga::Module) -> (usize, usize) {
+    use naga::valid::{Capabilities, ValidationFlags, Validator};
+    use rspirv::spirv::Op;
+
+    let info = Validator::new(ValidationFlags::all(), Capabilities::default())
+        .validate(module)
+        .expect("module should validate");
+    let words = naga::back::spv::write_vec(module, &info, &Default::default(), None).unwrap();
+    let spv = rspirv::dr::load_words(words).unwrap();
+
+    let instructions: Vec<_> = spv
+        .functions
+        .iter()
+        .flat_map(|function| &function.blocks)
+        .flat_map(|block| &block.instructions)
+        .collect();
+    let shuffles = instructions
+        .iter()
+        .filter(|inst| inst.class.opcode == Op::VectorShuffle)
+        .count();
+    (instructions.len(), shuffles)
+}
+
+#[test]
+fn fold_accesses() {
+    let (mut module, [swizzle, access_index, access]) = build();
+    let (before, shuffles) = spv_instructions(&module);
+    assert_eq!(shuffles, 1);
+
+    naga::proc::fold_constants(&mut module);
+    let function = &module.functions.iter().next().unwrap().1;
+
+    // Scalar results become literals...
+    assert_eq!(
+        function.expressions[access_index],
+        Expression::Literal(Literal::F32(2.0))
+    );
+    assert_eq!(
+        function.expressions[access],
+        Expression::Literal(Literal::F32(1.0))
+    );
+
+    // ...and composite results become constants.
+    let Expression::Constant(folded) = function.expressions[swizzle] else {
+        panic!(
+            "swizzle was not folded: {:?}",
+            function.expressions[swizzle]
+        );
+    };
+    let Expression::Compose { ref components, .. } =
+        module.const_expressions[module.constants[folded].init]
+    else {
+        panic!("unexpected folded value");
+    };
+    let values: Vec<_> = components
+        .iter()
+        .map(|&component| module.const_expressions[component].clone())
+        .collect();
+    assert_eq!(
+        values,
+        [
+            Expression::Literal(Literal::F32(3.0)),
+            Expression::Literal(Literal::F32(2.0)),
+        ]
+    );
+
+    let (after, shuffles) = spv_instructions(&module);
+    assert_eq!(shuffles, 0);
+    assert!(
+        after < before,
+        "{after} instructions, {before} before folding"
+    );
+}
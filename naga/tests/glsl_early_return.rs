@@ -0,0 +1,152 @@
+/*!
+Test that outputs written before an early `return` from a GLSL `main` still
+reach the entry point's result.
+*/
+
+#![cfg(all(feature = "glsl-in", feature = "spv-out", feature = "msl-out"))]
+
+use naga::{back::msl, back::spv, valid, Statement};
+use rspirv::spirv::{Op, StorageClass};
+
+const SOURCE: &str = "
+#version 450
+
+layout(location = 0) in vec2 uv;
+layout(location = 0) out vec4 color;
+
+void main() {
+    color = vec4(uv, 0.0, 1.0);
+    if (uv.x > 0.5) {
+        return;
+    }
+    color.b = 1.0;
+}
+";
+
+fn parse() -> (naga::Module, valid::ModuleInfo) {
+    let module = naga::front::glsl::Frontend::default()
+        .parse(
+            &naga::front::glsl::Options::from(naga::ShaderStage::Fragment),
+            SOURCE,
+        )
+        .unwrap();
+    let info = valid::Validator::new(valid::ValidationFlags::all(), valid::Capabilities::all())
+        .validate(&module)
+        .unwrap();
+    (module, info)
+}
+
+/// Return true if `block` contains a `return`, within an `if` if `nested`.
+fn has_return(block: &naga::Block, nested: bool) -> bool {
+    block.iter().any(|statement| match *statement {
+        Statement::Return { .. } => !nested,
+        Statement::Block(ref block) => has_return(block, nested),
+        Statement::If {
+            ref accept,
+            ref reject,
+            ..
+        } => has_return(accept, false) || has_return(reject, false),
+        _ => false,
+    })
+}
+
+#[test]
+fn ir() {
+    let (module, _) = parse();
+
+    // The GLSL `main` keeps its early return.
+    let (main, _) = module
+        .functions
+        .iter()
+        .find(|&(_, function)| function.name.as_deref() == Some("main"))
+        .unwrap();
+    assert!(has_return(&module.functions[main].body, true));
+
+    // The entry point calls it, then returns the outputs.
+    let body = &module.entry_points[0].function.body;
+    let call = body
+        .iter()
+        .position(
+            |statement| matches!(*statement, Statement::Call { function, .. } if function == main),
+        )
+        .unwrap();
+    let returns: Vec<_> = body
+        .iter()
+        .enumerate()
+        .filter(|&(_, statement)| matches!(*statement, Statement::Return { .. }))
+        .collect();
+    assert_eq!(returns.len(), 1);
+    let (index, statement) = returns[0];
+    assert!(index > call);
+    assert!(matches!(*statement, Statement::Return { value: Some(_) }));
+}
+
+#[test]
+fn spirv() {
+    let (module, info) = parse();
+    let words = spv::write_vec(&module, &info, &spv::Options::default(), None).unwrap();
+    let spirv = rspirv::dr::load_words(words).unwrap();
+
+    let outputs: Vec<_> = spirv
+        .types_global_values
+        .iter()
+        .filter(|inst| {
+            inst.class.opcode == Op::Variable
+                && inst.operands[0] == rspirv::dr::Operand::StorageClass(StorageClass::Output)
+        })
+        .filter_map(|inst| inst.result_id)
+        .collect();
+    assert_eq!(outputs.len(), 1);
+
+    let entry_point = spirv.entry_points[0].operands[1].unwrap_id_ref();
+    let function = spirv
+        .functions
+        .iter()
+        .find(|function| function.def_id() == Some(entry_point))
+        .unwrap();
+    let instructions: Vec<_> = function
+        .blocks
+        .iter()
+        .flat_map(|block| block.instructions.iter())
+        .collect();
+
+    // The entry point stores the output after `main` returns, however it
+    // returned, and only then returns itself.
+    let call = instructions
+        .iter()
+        .position(|inst| inst.class.opcode == Op::FunctionCall)
+        .unwrap();
+    let store = instructions
+        .iter()
+        .rposition(|inst| {
+            inst.class.opcode == Op::Store && inst.operands[0].unwrap_id_ref() == outputs[0]
+        })
+        .unwrap();
+    let returns: Vec<_> = instructions
+        .iter()
+        .enumerate()
+        .filter(|&(_, inst)| inst.class.opcode == Op::Return)
+        .map(|(index, _)| index)
+        .collect();
+    assert!(call < store);
+    assert_eq!(returns, [instructions.len() - 1]);
+    assert!(store < returns[0]);
+}
+
+#[test]
+fn msl() {
+    let (module, info) = parse();
+    let (source, _) = msl::write_string(
+        &module,
+        &info,
+        &msl::Options::default(),
+        &msl::PipelineOptions::default(),
+    )
+    .unwrap();
+
+    let entry_point = &source[source.find("fragment main_Output main_(").unwrap()..];
+    let call = entry_point.find("main_1(").unwrap();
+    let result = entry_point.find("return main_Output {").unwrap();
+    assert!(call < result);
+    assert_eq!(entry_point.matches("return").count(), 1);
+}
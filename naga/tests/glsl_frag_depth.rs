@@ -0,0 +1,180 @@
+/*!
+Test how the GLSL backend writes `gl_FragDepth`: its conservative depth
+layout, and its value on paths that don't write it.
+*/
+
+#![cfg(all(feature = "wgsl-in", feature = "glsl-out"))]
+
+use naga::back::glsl;
+use naga::valid;
+
+fn write_module(module: &naga::Module, version: glsl::Version) -> Result<String, glsl::Error> {
+    let info = valid::Validator::new(valid::ValidationFlags::all(), valid::Capabilities::all())
+        .validate(module)
+        .expect("validation failed");
+    let options = glsl::Options {
+        version,
+        ..Default::default()
+    };
+    let pipeline_options = glsl::PipelineOptions {
+        shader_stage: naga::ShaderStage::Fragment,
+        entry_point: "main".to_string(),
+        multiview: None,
+    };
+    let mut buffer = String::new();
+    glsl::Writer::new(
+        &mut buffer,
+        module,
+        &info,
+        &options,
+        &pipeline_options,
+        naga::proc::BoundsCheckPolicies::default(),
+    )?
+    .write()?;
+    Ok(buffer)
+}
+
+fn write(source: &str, version: glsl::Version) -> Result<String, glsl::Error> {
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+    write_module(&module, version)
+}
+
+/// A fragment shader with the given `@early_depth_test` argument, that
+/// writes `FragDepth`.
+fn depth_shader(mode: &str) -> String {
+    format!(
+        "
+        @fragment @early_depth_test({mode})
+        fn main(@builtin(position) position: vec4<f32>) -> @builtin(frag_depth) f32 {{
+            return position.z;
+        }}
+        "
+    )
+}
+
+const DEFAULT_DEPTH: &str = "gl_FragDepth = gl_FragCoord.z;";
+
+#[test]
+fn conservative_depth_modes() {
+    for (mode, layout) in [
+        (
+            "greater_equal",
+            "layout(depth_greater) out float gl_FragDepth;",
+        ),
+        ("less_equal", "layout(depth_less) out float gl_FragDepth;"),
+        (
+            "unchanged",
+            "layout(depth_unchanged) out float gl_FragDepth;",
+        ),
+    ] {
+        let source = write(&depth_shader(mode), glsl::Version::Desktop(450)).unwrap();
+        assert!(source.contains(layout), "{mode}:\n{source}");
+        assert!(!source.contains("#extension"), "{mode}:\n{source}");
+        // Forcing early tests would discard the written depth.
+        assert!(
+            !source.contains("early_fragment_tests"),
+            "{mode}:\n{source}"
+        );
+    }
+}
+
+#[test]
+fn conservative_depth_extensions() {
+    let shader = depth_shader("greater_equal");
+
+    let source = write(&shader, glsl::Version::Desktop(330)).unwrap();
+    assert!(source.contains("#extension GL_ARB_conservative_depth : require"));
+    assert!(source.contains("layout(depth_greater) out float gl_FragDepth;"));
+
+    let source = write(&shader, glsl::Version::new_gles(300)).unwrap();
+    assert!(source.contains("#extension GL_EXT_conservative_depth : require"));
+    assert!(source.contains("layout(depth_greater) out float gl_FragDepth;"));
+
+    // Conservative depth is core from GLSL 4.20.
+    let source = write(&shader, glsl::Version::Desktop(420)).unwrap();
+    assert!(!source.contains("conservative_depth"));
+    assert!(source.contains("layout(depth_greater) out float gl_FragDepth;"));
+}
+
+#[test]
+fn unchanged_without_depth_write() {
+    let source = write(
+        "
+        @fragment @early_depth_test(unchanged)
+        fn main() -> @location(0) vec4<f32> {
+            return vec4<f32>(1.0);
+        }
+        ",
+        glsl::Version::Desktop(450),
+    )
+    .unwrap();
+    assert!(!source.contains("gl_FragDepth"), "{source}");
+}
+
+#[test]
+fn forced_early_tests() {
+    let source = write(
+        "
+        @fragment @early_depth_test
+        fn main() -> @location(0) vec4<f32> {
+            return vec4<f32>(1.0);
+        }
+        ",
+        glsl::Version::Desktop(450),
+    )
+    .unwrap();
+    assert!(
+        source.contains("layout(early_fragment_tests) in;"),
+        "{source}"
+    );
+}
+
+#[test]
+fn depth_written_on_all_paths() {
+    let source = write(
+        "
+        @fragment
+        fn main(@builtin(position) position: vec4<f32>) -> @builtin(frag_depth) f32 {
+            if position.x > 0.5 {
+                discard;
+            }
+            if position.y > 0.5 {
+                return 0.25;
+            } else {
+                return position.z;
+            }
+        }
+        ",
+        glsl::Version::Desktop(450),
+    )
+    .unwrap();
+    assert!(!source.contains(DEFAULT_DEPTH), "{source}");
+}
+
+#[test]
+fn depth_written_on_some_paths() {
+    let mut module = naga::front::wgsl::parse_str(
+        "
+        @fragment
+        fn main(@builtin(position) position: vec4<f32>) -> @builtin(frag_depth) f32 {
+            if position.x > 0.5 {
+                return 0.25;
+            }
+            return position.z;
+        }
+        ",
+    )
+    .unwrap();
+
+    // WGSL requires a final `return`, but the IR doesn't. Without it, only
+    // the path through the `if` writes the depth.
+    let body = &mut module.entry_points[0].function.body;
+    let last = body.len() - 1;
+    assert!(matches!(body[last], naga::Statement::Return { .. }));
+    body.cull(last..);
+
+    let source = write_module(&module, glsl::Version::Desktop(450)).unwrap();
+    let default = source.find(DEFAULT_DEPTH).expect(&source);
+    let written = source.find("gl_FragDepth = 0.25").expect(&source);
+    assert!(default < written, "{source}");
+}
@@ -0,0 +1,117 @@
+/*!
+Test how the GLSL backend accounts for the first instance when computing
+`instance_index`, and that it reports the approach it took.
+*/
+
+#![cfg(all(feature = "wgsl-in", feature = "glsl-out"))]
+
+use naga::back::glsl;
+use naga::valid;
+
+const SOURCE: &str = "
+@vertex
+fn with_instance(@builtin(instance_index) instance: u32) -> @builtin(position) vec4<f32> {
+    return vec4<f32>(f32(instance));
+}
+
+@vertex
+fn without_instance(@builtin(vertex_index) vertex: u32) -> @builtin(position) vec4<f32> {
+    return vec4<f32>(f32(vertex));
+}
+";
+
+fn write(
+    entry_point: &str,
+    version: glsl::Version,
+    writer_flags: glsl::WriterFlags,
+) -> (String, glsl::ReflectionInfo) {
+    let module = naga::front::wgsl::parse_str(SOURCE).unwrap();
+    let info = valid::Validator::new(valid::ValidationFlags::all(), valid::Capabilities::all())
+        .validate(&module)
+        .expect("validation failed");
+    let options = glsl::Options {
+        version,
+        writer_flags,
+        ..Default::default()
+    };
+    let pipeline_options = glsl::PipelineOptions {
+        shader_stage: naga::ShaderStage::Vertex,
+        entry_point: entry_point.to_string(),
+        multiview: None,
+    };
+    let mut buffer = String::new();
+    let reflection = glsl::Writer::new(
+        &mut buffer,
+        &module,
+        &info,
+        &options,
+        &pipeline_options,
+        naga::proc::BoundsCheckPolicies::default(),
+    )
+    .unwrap()
+    .write()
+    .unwrap();
+    (buffer, reflection)
+}
+
+#[test]
+fn uniform() {
+    let (source, reflection) = write(
+        "with_instance",
+        glsl::Version::new_gles(310),
+        glsl::WriterFlags::empty(),
+    );
+    assert_eq!(
+        reflection.instance_index,
+        Some(glsl::InstanceIndexSource::Uniform)
+    );
+    assert!(source.contains(&format!("uniform uint {};", glsl::FIRST_INSTANCE_BINDING)));
+    assert!(source.contains("(uint(gl_InstanceID) + naga_vs_first_instance)"));
+}
+
+#[test]
+fn draw_parameters() {
+    let (source, reflection) = write(
+        "with_instance",
+        glsl::Version::Desktop(450),
+        glsl::WriterFlags::DRAW_PARAMETERS,
+    );
+    assert_eq!(
+        reflection.instance_index,
+        Some(glsl::InstanceIndexSource::DrawParameters)
+    );
+    assert!(source.contains("#extension GL_ARB_shader_draw_parameters : require"));
+    assert!(source.contains("(uint(gl_InstanceID) + uint(gl_BaseInstanceARB))"));
+    assert!(!source.contains(glsl::FIRST_INSTANCE_BINDING));
+}
+
+#[test]
+fn base_instance() {
+    // GLSL 4.60 has `gl_BaseInstance` in core, so neither the uniform nor
+    // the extension is needed, even when the extension is available.
+    for writer_flags in [
+        glsl::WriterFlags::empty(),
+        glsl::WriterFlags::DRAW_PARAMETERS,
+    ] {
+        let (source, reflection) =
+            write("with_instance", glsl::Version::Desktop(460), writer_flags);
+        assert_eq!(
+            reflection.instance_index,
+            Some(glsl::InstanceIndexSource::BaseInstance)
+        );
+        assert!(!source.contains("#extension GL_ARB_shader_draw_parameters"));
+        assert!(source.contains("(uint(gl_InstanceID) + uint(gl_BaseInstance))"));
+        assert!(!source.contains(glsl::FIRST_INSTANCE_BINDING));
+    }
+}
+
+#[test]
+fn unused() {
+    let (source, reflection) = write(
+        "without_instance",
+        glsl::Version::new_gles(310),
+        glsl::WriterFlags::empty(),
+    );
+    assert_eq!(reflection.instance_index, None);
+    assert!(!source.contains(glsl::FIRST_INSTANCE_BINDING));
+}
@@ -0,0 +1,144 @@
+/*!
+Test where the GLSL backend writes extensions requested in the options and the
+custom prologue, relative to its own directives.
+*/
+
+#![cfg(all(feature = "wgsl-in", feature = "glsl-out"))]
+
+use naga::back::glsl;
+use naga::valid;
+
+const SOURCE: &str = "
+    @group(0) @binding(0)
+    var tex: texture_cube_array<f32>;
+    @group(0) @binding(1)
+    var samp: sampler;
+
+    @fragment
+    fn main() -> @location(0) vec4<f32> {
+        return textureSample(tex, samp, vec3(0.5), 0);
+    }
+";
+
+fn write(extensions: Vec<glsl::Extension>, prologue: Option<&str>) -> String {
+    let module = naga::front::wgsl::parse_str(SOURCE).unwrap();
+    let info = valid::Validator::new(valid::ValidationFlags::all(), valid::Capabilities::all())
+        .validate(&module)
+        .expect("validation failed");
+    let options = glsl::Options {
+        version: glsl::Version::new_gles(310),
+        extensions,
+        prologue: prologue.map(str::to_string),
+        ..Default::default()
+    };
+    let pipeline_options = glsl::PipelineOptions {
+        shader_stage: naga::ShaderStage::Fragment,
+        entry_point: "main".to_string(),
+        multiview: None,
+    };
+    let mut buffer = String::new();
+    glsl::Writer::new(
+        &mut buffer,
+        &module,
+        &info,
+        &options,
+        &pipeline_options,
+        naga::proc::BoundsCheckPolicies::default(),
+    )
+    .unwrap()
+    .write()
+    .unwrap();
+    buffer
+}
+
+fn extension(name: &str, behavior: glsl::ExtensionBehavior) -> glsl::Extension {
+    glsl::Extension {
+        name: name.to_string(),
+        behavior,
+    }
+}
+
+/// Return the index of the line equal to `line`, panicking if there's none.
+fn line_index(source: &str, line: &str) -> usize {
+    source
+        .lines()
+        .position(|l| l == line)
+        .unwrap_or_else(|| panic!("no line `{line}` in:\n{source}"))
+}
+
+#[test]
+fn requested_extensions_follow_required_ones() {
+    let source = write(
+        vec![
+            extension(
+                "GL_EXT_shader_framebuffer_fetch",
+                glsl::ExtensionBehavior::Enable,
+            ),
+            extension("GL_OVR_multiview2", glsl::ExtensionBehavior::Require),
+        ],
+        None,
+    );
+    let required = line_index(
+        &source,
+        "#extension GL_EXT_texture_cube_map_array : require",
+    );
+    let fetch = line_index(
+        &source,
+        "#extension GL_EXT_shader_framebuffer_fetch : enable",
+    );
+    let multiview = line_index(&source, "#extension GL_OVR_multiview2 : require");
+    assert_eq!(line_index(&source, "#version 310 es"), 0);
+    assert!(required < fetch);
+    assert!(fetch < multiview);
+}
+
+#[test]
+fn requested_extensions_are_deduplicated() {
+    let source = write(
+        vec![
+            extension(
+                "GL_EXT_texture_cube_map_array",
+                glsl::ExtensionBehavior::Enable,
+            ),
+            extension("GL_OVR_multiview2", glsl::ExtensionBehavior::Enable),
+            extension("GL_OVR_multiview2", glsl::ExtensionBehavior::Require),
+        ],
+        None,
+    );
+    let count = |name: &str| {
+        source
+            .lines()
+            .filter(|line| line.starts_with(&format!("#extension {name} ")))
+            .count()
+    };
+    assert_eq!(count("GL_EXT_texture_cube_map_array"), 1);
+    assert_eq!(count("GL_OVR_multiview2"), 1);
+    line_index(
+        &source,
+        "#extension GL_EXT_texture_cube_map_array : require",
+    );
+    line_index(&source, "#extension GL_OVR_multiview2 : enable");
+}
+
+#[test]
+fn prologue_comes_before_declarations() {
+    let source = write(
+        vec![extension(
+            "GL_OVR_multiview2",
+            glsl::ExtensionBehavior::Enable,
+        )],
+        Some("#define ENGINE_DEFINE 1\nprecision mediump float;\n"),
+    );
+    let extension = line_index(&source, "#extension GL_OVR_multiview2 : enable");
+    let precision = line_index(&source, "precision highp float;");
+    let define = line_index(&source, "#define ENGINE_DEFINE 1");
+    let override_ = line_index(&source, "precision mediump float;");
+    let sampler = source
+        .lines()
+        .position(|line| line.contains("samplerCubeArray"))
+        .unwrap();
+    assert!(extension < precision);
+    assert!(precision < define);
+    assert!(define < override_);
+    assert!(override_ < sampler);
+}
@@ -0,0 +1,129 @@
+/*!
+Test that the GLSL backend never emits identifiers GLSL reserves: those
+containing `__`, and those starting with `gl_` other than built-ins.
+*/
+
+#![cfg(all(feature = "wgsl-in", feature = "glsl-out"))]
+
+use naga::back::glsl;
+use naga::valid;
+
+const SOURCE: &str = "
+struct gl_Light {
+    gl_color: vec4<f32>,
+    my__intensity: f32,
+}
+
+struct Push__Constants {
+    gl_scale: f32,
+    offset__: f32,
+}
+
+@group(0) @binding(0)
+var<uniform> gl_light: gl_Light;
+
+@group(0) @binding(1)
+var<storage, read> lights__all: array<gl_Light>;
+
+@group(1) @binding(0)
+var gl_texture: texture_2d<f32>;
+
+@group(1) @binding(1)
+var my__sampler: sampler;
+
+var<push_constant> gl_push: Push__Constants;
+
+fn gl_shade(gl_in: vec4<f32>, x__y: f32) -> vec4<f32> {
+    var gl_out = gl_in * x__y;
+    return gl_out;
+}
+
+@fragment
+fn gl__main(@location(0) gl_uv: vec2<f32>) -> @location(0) vec4<f32> {
+    let my__thing = gl_light.my__intensity * gl_push.gl_scale + gl_push.offset__;
+    let sampled = textureSample(gl_texture, my__sampler, gl_uv);
+    return gl_shade(sampled + lights__all[0].gl_color, my__thing) + gl_light.gl_color;
+}
+";
+
+fn write() -> (naga::Module, String, glsl::ReflectionInfo) {
+    let module = naga::front::wgsl::parse_str(SOURCE).unwrap();
+    let info = valid::Validator::new(valid::ValidationFlags::all(), valid::Capabilities::all())
+        .validate(&module)
+        .expect("validation failed");
+    let options = glsl::Options {
+        version: glsl::Version::Desktop(430),
+        ..Default::default()
+    };
+    let pipeline_options = glsl::PipelineOptions {
+        shader_stage: naga::ShaderStage::Fragment,
+        entry_point: "gl__main".to_string(),
+        multiview: None,
+    };
+    let mut buffer = String::new();
+    let reflection = glsl::Writer::new(
+        &mut buffer,
+        &module,
+        &info,
+        &options,
+        &pipeline_options,
+        naga::proc::BoundsCheckPolicies::default(),
+    )
+    .unwrap()
+    .write()
+    .unwrap();
+    (module, buffer, reflection)
+}
+
+/// Return the identifiers in `source`, ignoring preprocessor directives.
+fn identifiers(source: &str) -> impl Iterator<Item = &str> {
+    source
+        .lines()
+        .filter(|line| !line.trim_start().starts_with('#'))
+        .flat_map(|line| line.split(|c: char| !c.is_ascii_alphanumeric() && c != '_'))
+        .filter(|word| word.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_'))
+}
+
+#[test]
+fn no_reserved_identifiers() {
+    let (_, source, _) = write();
+    for identifier in identifiers(&source) {
+        assert!(
+            !identifier.contains("__"),
+            "`{identifier}` contains `__`:\n{source}"
+        );
+        // Built-ins are all `gl_` followed by a capital letter.
+        if let Some(rest) = identifier.strip_prefix("gl_") {
+            assert!(
+                rest.starts_with(|c: char| c.is_ascii_uppercase()),
+                "`{identifier}` starts with `gl_`:\n{source}"
+            );
+        }
+    }
+}
+
+#[test]
+fn reflection_uses_legal_names() {
+    let (module, source, reflection) = write();
+    let declared: Vec<_> = identifiers(&source).collect();
+
+    let gl_light = module
+        .global_variables
+        .iter()
+        .find(|&(_, var)| var.name.as_deref() == Some("gl_light"))
+        .unwrap()
+        .0;
+    assert!(declared.contains(&reflection.uniforms[&gl_light].as_str()));
+
+    assert_eq!(reflection.texture_mapping.len(), 1);
+    for name in reflection.texture_mapping.keys() {
+        assert!(declared.contains(&name.as_str()), "{name}:\n{source}");
+    }
+
+    assert_eq!(reflection.push_constant_items.len(), 2);
+    for item in reflection.push_constant_items.iter() {
+        for part in item.access_path.split('.') {
+            assert!(declared.contains(&part), "{}:\n{source}", item.access_path);
+        }
+    }
+}
@@ -0,0 +1,80 @@
+/*!
+Test that the GLSL backend gates per-sample built-ins and interpolation on
+the target version.
+*/
+
+#![cfg(all(feature = "wgsl-in", feature = "glsl-out"))]
+
+use naga::back::glsl;
+use naga::valid;
+
+const SOURCE: &str = "
+struct FragmentIn {
+    @location(0) @interpolate(perspective, sample) color: vec4<f32>,
+    @builtin(sample_index) sample_index: u32,
+}
+
+@fragment
+fn main(input: FragmentIn) -> @builtin(sample_mask) u32 {
+    return u32(input.color.x) << input.sample_index;
+}
+";
+
+fn write(version: glsl::Version) -> Result<String, glsl::Error> {
+    let module = naga::front::wgsl::parse_str(SOURCE).unwrap();
+    let info = valid::Validator::new(valid::ValidationFlags::all(), valid::Capabilities::all())
+        .validate(&module)
+        .expect("validation failed");
+    let options = glsl::Options {
+        version,
+        ..Default::default()
+    };
+    let pipeline_options = glsl::PipelineOptions {
+        shader_stage: naga::ShaderStage::Fragment,
+        entry_point: "main".to_string(),
+        multiview: None,
+    };
+    let mut buffer = String::new();
+    glsl::Writer::new(
+        &mut buffer,
+        &module,
+        &info,
+        &options,
+        &pipeline_options,
+        naga::proc::BoundsCheckPolicies::default(),
+    )?
+    .write()?;
+    Ok(buffer)
+}
+
+#[test]
+fn embedded() {
+    for version in [300, 310] {
+        let source = write(glsl::Version::new_gles(version)).unwrap();
+        assert!(source.contains("#extension GL_OES_sample_variables : require"));
+        assert!(source.contains("#extension GL_OES_shader_multisample_interpolation : require"));
+        assert!(source.contains("smooth sample in vec4"));
+        assert!(source.contains("uint(gl_SampleID)"));
+        assert!(source.contains("gl_SampleMask[0] = int("));
+    }
+
+    // Both are core in ES 3.2.
+    let source = write(glsl::Version::new_gles(320)).unwrap();
+    assert!(!source.contains("#extension"));
+}
+
+#[test]
+fn desktop() {
+    match write(glsl::Version::Desktop(330)) {
+        Err(glsl::Error::MissingFeatures(missing)) => assert_eq!(
+            missing,
+            glsl::Features::SAMPLE_QUALIFIER | glsl::Features::SAMPLE_VARIABLES
+        ),
+        Err(other) => panic!("unexpected error: {other}"),
+        Ok(_) => panic!("expected an error at GLSL 330"),
+    }
+
+    let source = write(glsl::Version::Desktop(400)).unwrap();
+    assert!(!source.contains("#extension"));
+    assert!(source.contains("gl_SampleMask[0] = int("));
+}
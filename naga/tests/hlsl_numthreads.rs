@@ -0,0 +1,177 @@
+/*!
+Test compute entry points whose workgroup size is an override constant.
+
+The WGSL front end doesn't produce overrides yet, so these tests parse a
+shader using a `const`, and then turn the constant into an override that
+gives the workgroup size.
+*/
+
+#![cfg(feature = "wgsl-in")]
+
+use naga::valid::{Capabilities, ValidationFlags, Validator};
+
+const SOURCE: &str = "
+    const BASE: u32 = 32u;
+    const SIZE_X: u32 = 64u;
+
+    @group(0) @binding(0) var<storage, read_write> output: array<u32>;
+
+    @compute @workgroup_size(SIZE_X, 2)
+    fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+        output[id.x] = id.y;
+    }
+";
+
+/// Parse `SOURCE`, make `SIZE_X` an override with the given `r#override`,
+/// and have it give `main`'s workgroup size along the `x` axis.
+fn module(r#override: naga::Override) -> naga::Module {
+    let mut module = naga::front::wgsl::parse_str(SOURCE).unwrap();
+
+    let (size_x, constant) = module
+        .constants
+        .iter_mut()
+        .find(|(_, constant)| constant.name.as_deref() == Some("SIZE_X"))
+        .unwrap();
+    constant.r#override = r#override;
+    module.entry_points[0].workgroup_size_overrides = Some([Some(size_x), None, None]);
+
+    module
+}
+
+fn validate(
+    module: &naga::Module,
+) -> Result<naga::valid::ModuleInfo, naga::valid::ValidationError> {
+    Validator::new(ValidationFlags::all(), Capabilities::default())
+        .validate(module)
+        .map_err(|error| error.into_inner())
+}
+
+#[test]
+fn requires_override() {
+    let module = module(naga::Override::None);
+    assert!(matches!(
+        validate(&module),
+        Err(naga::valid::ValidationError::EntryPoint {
+            source: naga::valid::EntryPointError::InvalidWorkgroupSizeOverride(_),
+            ..
+        })
+    ));
+}
+
+#[cfg(feature = "hlsl-out")]
+fn hlsl(
+    module: &naga::Module,
+    options: &naga::back::hlsl::Options,
+) -> (String, naga::back::hlsl::ReflectionInfo) {
+    let info = validate(module).unwrap();
+    let mut output = String::new();
+    let reflection_info = naga::back::hlsl::Writer::new(&mut output, options)
+        .write(module, &info)
+        .unwrap();
+    (output, reflection_info)
+}
+
+#[cfg(feature = "hlsl-out")]
+#[test]
+fn hlsl_default_value() {
+    let module = module(naga::Override::ByNameOrId(7));
+    let (output, reflection_info) = hlsl(&module, &Default::default());
+    assert!(output.contains("[numthreads(64, 2, 1)]"), "{output}");
+    assert!(reflection_info.entry_point_names[0].is_ok());
+}
+
+#[cfg(feature = "hlsl-out")]
+#[test]
+fn hlsl_override_values() {
+    let by_id = module(naga::Override::ByNameOrId(7));
+    let options = naga::back::hlsl::Options {
+        override_values: [("7".to_string(), 128)].into_iter().collect(),
+        ..Default::default()
+    };
+    let (output, _) = hlsl(&by_id, &options);
+    assert!(output.contains("[numthreads(128, 2, 1)]"), "{output}");
+
+    let by_name = module(naga::Override::ByName);
+    let options = naga::back::hlsl::Options {
+        override_values: [("SIZE_X".to_string(), 16)].into_iter().collect(),
+        ..Default::default()
+    };
+    let (output, _) = hlsl(&by_name, &options);
+    assert!(output.contains("[numthreads(16, 2, 1)]"), "{output}");
+}
+
+/// Make `BASE` an override too, and initialize `SIZE_X` from it.
+#[cfg(feature = "hlsl-out")]
+fn initialized_from_base() -> naga::Module {
+    let mut module = module(naga::Override::ByName);
+    let (base, constant) = module
+        .constants
+        .iter_mut()
+        .find(|(_, constant)| constant.name.as_deref() == Some("BASE"))
+        .unwrap();
+    constant.r#override = naga::Override::ByName;
+    let init = module
+        .const_expressions
+        .append(naga::Expression::Constant(base), Default::default());
+    let size_x = module.entry_points[0].workgroup_size_overrides.unwrap()[0].unwrap();
+    module.constants[size_x].init = init;
+    module
+}
+
+#[cfg(feature = "hlsl-out")]
+#[test]
+fn hlsl_initialized_from_override() {
+    let module = initialized_from_base();
+    let (output, _) = hlsl(&module, &Default::default());
+    assert!(output.contains("[numthreads(32, 2, 1)]"), "{output}");
+
+    let options = naga::back::hlsl::Options {
+        override_values: [("BASE".to_string(), 8)].into_iter().collect(),
+        ..Default::default()
+    };
+    let (output, _) = hlsl(&module, &options);
+    assert!(output.contains("[numthreads(8, 2, 1)]"), "{output}");
+}
+
+#[cfg(feature = "hlsl-out")]
+#[test]
+fn hlsl_unresolved() {
+    let unresolved = Err(naga::back::hlsl::EntryPointError::UnresolvedOverride(
+        "SIZE_X".to_string(),
+    ));
+
+    // An explicit value of zero isn't a valid workgroup size.
+    let by_name = module(naga::Override::ByName);
+    let options = naga::back::hlsl::Options {
+        override_values: [("SIZE_X".to_string(), 0)].into_iter().collect(),
+        ..Default::default()
+    };
+    let (output, reflection_info) = hlsl(&by_name, &options);
+    assert!(!output.contains("numthreads"), "{output}");
+    assert_eq!(reflection_info.entry_point_names[0], unresolved);
+
+    // Neither is an override whose default is zero.
+    let mut zero = module(naga::Override::ByName);
+    let size_x = zero.entry_points[0].workgroup_size_overrides.unwrap()[0].unwrap();
+    let ty = zero.constants[size_x].ty;
+    zero.constants[size_x].init = zero
+        .const_expressions
+        .append(naga::Expression::ZeroValue(ty), Default::default());
+    let (output, reflection_info) = hlsl(&zero, &Default::default());
+    assert!(!output.contains("numthreads"), "{output}");
+    assert_eq!(reflection_info.entry_point_names[0], unresolved);
+}
+
+#[cfg(feature = "wgsl-out")]
+#[test]
+fn wgsl() {
+    let module = module(naga::Override::ByNameOrId(0));
+    let info = validate(&module).unwrap();
+    let output =
+        naga::back::wgsl::write_string(&module, &info, naga::back::wgsl::WriterFlags::empty())
+            .unwrap();
+    assert!(
+        output.contains("@compute @workgroup_size(SIZE_X, 2, 1) "),
+        "{output}"
+    );
+}
@@ -0,0 +1,222 @@
+/*!
+Test the HLSL backend's register allocation for resource bindings.
+*/
+
+#![cfg(all(feature = "wgsl-in", feature = "hlsl-out"))]
+
+use naga::back::hlsl;
+use naga::ResourceBinding;
+
+const SHADER: &str = "
+    @group(0) @binding(0) var t: texture_2d<f32>;
+    @group(0) @binding(1) var s: sampler;
+    @group(1) @binding(0) var<uniform> u: vec4<f32>;
+    @group(1) @binding(1) var ss: binding_array<sampler, 4>;
+
+    @fragment
+    fn main(@builtin(position) position: vec4<f32>) -> @location(0) vec4<f32> {
+        return textureSample(t, s, position.xy) + textureSample(t, ss[2], position.xy) + u;
+    }
+";
+
+fn write(options: &hlsl::Options) -> Result<(String, hlsl::ReflectionInfo), hlsl::Error> {
+    let module = naga::front::wgsl::parse_str(SHADER).unwrap();
+    let info = naga::valid::Validator::new(
+        naga::valid::ValidationFlags::all(),
+        naga::valid::Capabilities::all(),
+    )
+    .validate(&module)
+    .unwrap();
+
+    let mut output = String::new();
+    let reflection_info = hlsl::Writer::new(&mut output, options).write(&module, &info)?;
+    Ok((output, reflection_info))
+}
+
+fn target(space: u8, register: u32) -> hlsl::BindTarget {
+    hlsl::BindTarget {
+        space,
+        register,
+        binding_array_size: None,
+    }
+}
+
+fn binding(group: u32, binding: u32) -> ResourceBinding {
+    ResourceBinding { group, binding }
+}
+
+/// Return the class, target and count reported for each global, by name.
+fn allocations(
+    reflection_info: &hlsl::ReflectionInfo,
+) -> Vec<(
+    &'static str,
+    hlsl::RegisterClass,
+    hlsl::BindTarget,
+    Option<u32>,
+)> {
+    // The globals appear in the arena in declaration order.
+    const NAMES: [&str; 4] = ["t", "s", "u", "ss"];
+    reflection_info
+        .resource_bindings
+        .iter()
+        .map(|binding| {
+            (
+                NAMES[binding.global.index()],
+                binding.class,
+                binding.target.clone(),
+                binding.count,
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn explicit_mapping() {
+    use hlsl::RegisterClass as Rc;
+
+    let options = hlsl::Options {
+        binding_map: [
+            (binding(0, 0), target(0, 5)),
+            (binding(0, 1), target(2, 3)),
+            (binding(1, 0), target(0, 0)),
+            (binding(1, 1), target(2, 4)),
+        ]
+        .into_iter()
+        .collect(),
+        fake_missing_bindings: false,
+        ..Default::default()
+    };
+    let (output, reflection_info) = write(&options).unwrap();
+
+    assert!(output.contains("Texture2D<float4> t : register(t5);"));
+    assert!(output.contains("SamplerState s : register(s3, space2);"));
+    assert!(output.contains("SamplerState ss[4] : register(s4, space2);"));
+    assert_eq!(
+        allocations(&reflection_info),
+        [
+            ("t", Rc::ShaderResource, target(0, 5), Some(1)),
+            ("s", Rc::Sampler, target(2, 3), Some(1)),
+            ("u", Rc::ConstantBuffer, target(0, 0), Some(1)),
+            ("ss", Rc::Sampler, target(2, 4), Some(4)),
+        ]
+    );
+}
+
+#[test]
+fn unmapped_resource() {
+    // Without fake bindings, a resource missing from the map fails the entry
+    // points that use it, and isn't allocated.
+    let options = hlsl::Options {
+        binding_map: [
+            (binding(0, 0), target(0, 0)),
+            (binding(1, 0), target(0, 0)),
+            (binding(1, 1), target(0, 0)),
+        ]
+        .into_iter()
+        .collect(),
+        fake_missing_bindings: false,
+        ..Default::default()
+    };
+    let (_, reflection_info) = write(&options).unwrap();
+
+    assert!(matches!(
+        reflection_info.entry_point_names[0],
+        Err(hlsl::EntryPointError::MissingBinding(ResourceBinding {
+            group: 0,
+            binding: 1
+        }))
+    ));
+    let names: Vec<_> = allocations(&reflection_info)
+        .into_iter()
+        .map(|(name, ..)| name)
+        .collect();
+    assert_eq!(names, ["t", "u", "ss"]);
+}
+
+#[test]
+fn fallback_allocation() {
+    use hlsl::RegisterClass as Rc;
+
+    // Unmapped resources use their group as the space and their binding as
+    // the register.
+    let options = hlsl::Options {
+        binding_map: [(binding(0, 0), target(0, 7))].into_iter().collect(),
+        ..Default::default()
+    };
+    let (output, reflection_info) = write(&options).unwrap();
+
+    assert!(output.contains("SamplerState s : register(s1);"));
+    assert!(output.contains("cbuffer u : register(b0, space1)"));
+    assert_eq!(
+        allocations(&reflection_info),
+        [
+            ("t", Rc::ShaderResource, target(0, 7), Some(1)),
+            ("s", Rc::Sampler, target(0, 1), Some(1)),
+            ("u", Rc::ConstantBuffer, target(1, 0), Some(1)),
+            ("ss", Rc::Sampler, target(1, 1), Some(4)),
+        ]
+    );
+}
+
+#[test]
+fn collision() {
+    // `s` lands on the third sampler of the array `ss`.
+    let options = hlsl::Options {
+        binding_map: [(binding(0, 1), target(0, 6)), (binding(1, 1), target(0, 4))]
+            .into_iter()
+            .collect(),
+        ..Default::default()
+    };
+    match write(&options) {
+        Err(hlsl::Error::RegisterCollision {
+            first,
+            second,
+            class: hlsl::RegisterClass::Sampler,
+            space: 0,
+            register: 6,
+        }) => {
+            assert_eq!(first.index(), 1);
+            assert_eq!(second.index(), 3);
+        }
+        Err(other) => panic!("expected a register collision, got {other:?}"),
+        Ok(_) => panic!("expected a register collision"),
+    }
+}
+
+#[test]
+fn aliasing_across_entry_points() {
+    // Entry points may reuse registers, as long as neither uses both globals.
+    let module = naga::front::wgsl::parse_str(
+        "
+        @group(0) @binding(0) var a: texture_2d<f32>;
+        @group(0) @binding(0) var b: texture_2d<f32>;
+
+        @fragment
+        fn first() -> @location(0) vec4<f32> {
+            return textureLoad(a, vec2(0), 0);
+        }
+
+        @fragment
+        fn second() -> @location(0) vec4<f32> {
+            return textureLoad(b, vec2(0), 0);
+        }
+        ",
+    )
+    .unwrap();
+    let info = naga::valid::Validator::new(
+        naga::valid::ValidationFlags::all(),
+        naga::valid::Capabilities::all(),
+    )
+    .validate(&module)
+    .unwrap();
+
+    let mut output = String::new();
+    let reflection_info = hlsl::Writer::new(&mut output, &hlsl::Options::default())
+        .write(&module, &info)
+        .unwrap();
+    assert_eq!(reflection_info.resource_bindings.len(), 2);
+    assert!(reflection_info
+        .entry_point_names
+        .iter()
+        .all(|name| name.is_ok()));
+}
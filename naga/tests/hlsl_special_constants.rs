@@ -0,0 +1,128 @@
+/*!
+Test the HLSL backend's use of the special constants buffer.
+*/
+
+#![cfg(all(feature = "wgsl-in", feature = "hlsl-out"))]
+
+use naga::back::hlsl;
+
+const NUM_WORKGROUPS: &str = "
+    @group(0) @binding(0) var<storage, read_write> out: vec3<u32>;
+
+    @compute @workgroup_size(1)
+    fn reads(@builtin(num_workgroups) count: vec3<u32>) {
+        out = count;
+    }
+
+    @compute @workgroup_size(1)
+    fn ignores(@builtin(workgroup_id) id: vec3<u32>) {
+        out = id;
+    }
+";
+
+const INDICES: &str = "
+    struct Output {
+        @builtin(position) position: vec4<f32>,
+        @location(0) @interpolate(flat) indices: vec2<u32>,
+    }
+
+    @vertex
+    fn main(
+        @builtin(vertex_index) vertex: u32,
+        @builtin(instance_index) instance: u32,
+    ) -> Output {
+        return Output(vec4(f32(vertex), f32(instance), 0.0, 1.0), vec2(vertex, instance));
+    }
+";
+
+fn write(source: &str, options: &hlsl::Options) -> (String, hlsl::ReflectionInfo) {
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+    let info = naga::valid::Validator::new(
+        naga::valid::ValidationFlags::all(),
+        naga::valid::Capabilities::default(),
+    )
+    .validate(&module)
+    .unwrap();
+
+    let mut output = String::new();
+    let reflection_info = hlsl::Writer::new(&mut output, options)
+        .write(&module, &info)
+        .unwrap();
+    (output, reflection_info)
+}
+
+#[test]
+fn num_workgroups() {
+    let binding = hlsl::BindTarget {
+        space: 1,
+        register: 0,
+        binding_array_size: None,
+    };
+    let options = hlsl::Options {
+        special_constants_binding: Some(binding.clone()),
+        ..Default::default()
+    };
+    let (output, reflection_info) = write(NUM_WORKGROUPS, &options);
+
+    assert!(output.contains("ConstantBuffer<NagaConstants> _NagaConstants: register(b0, space1);"));
+    assert!(output.contains(
+        "uint3(_NagaConstants.first_vertex, _NagaConstants.first_instance, _NagaConstants.other)"
+    ));
+    assert!(output.contains("uint3 id : SV_GroupID"));
+    assert_eq!(reflection_info.special_constants_binding, Some(binding));
+    assert_eq!(reflection_info.uses_num_workgroups, [true, false]);
+    assert!(reflection_info.entry_point_names.iter().all(Result::is_ok));
+}
+
+#[test]
+fn num_workgroups_without_special_constants() {
+    let (_, reflection_info) = write(NUM_WORKGROUPS, &hlsl::Options::default());
+
+    assert_eq!(reflection_info.special_constants_binding, None);
+    assert_eq!(
+        reflection_info.entry_point_names[0],
+        Err(hlsl::EntryPointError::MissingSpecialConstants(
+            naga::BuiltIn::NumWorkGroups
+        ))
+    );
+    assert!(reflection_info.entry_point_names[1].is_ok());
+}
+
+#[test]
+fn vertex_and_instance_index() {
+    let binding = hlsl::BindTarget {
+        space: 2,
+        register: 3,
+        binding_array_size: None,
+    };
+    let options = hlsl::Options {
+        special_constants_binding: Some(binding.clone()),
+        ..Default::default()
+    };
+    let (output, reflection_info) = write(INDICES, &options);
+
+    assert!(output.contains("ConstantBuffer<NagaConstants> _NagaConstants: register(b3, space2);"));
+    assert!(output.contains("uint vertex : SV_VertexID, uint instance : SV_InstanceID"));
+    assert!(output.contains("(_NagaConstants.first_vertex + vertex)"));
+    assert!(output.contains("(_NagaConstants.first_instance + instance)"));
+    assert_eq!(reflection_info.special_constants_binding, Some(binding));
+    assert_eq!(
+        reflection_info.special_constants_layout,
+        Some(hlsl::SpecialConstantsLayout {
+            first_vertex: 0,
+            first_instance: 4,
+            other: 8,
+            size: 12,
+        })
+    );
+}
+
+#[test]
+fn vertex_and_instance_index_without_special_constants() {
+    let (output, reflection_info) = write(INDICES, &hlsl::Options::default());
+
+    assert!(!output.contains("_NagaConstants"));
+    assert_eq!(reflection_info.special_constants_binding, None);
+    assert_eq!(reflection_info.special_constants_layout, None);
+    assert!(reflection_info.entry_point_names[0].is_ok());
+}
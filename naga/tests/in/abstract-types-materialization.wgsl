@@ -0,0 +1,66 @@
+// Abstract values are converted to the type their use site expects.
+
+fn takes_f32(x: f32) -> f32 {
+    return x;
+}
+
+fn takes_u32_vec(x: vec2<u32>, y: u32) -> u32 {
+    return x.x + y;
+}
+
+fn return_f32() -> f32 {
+    return 1;
+}
+
+fn return_u32_vec() -> vec2<u32> {
+    return vec2(1, 2);
+}
+
+fn return_f32_array() -> array<f32, 2> {
+    return array(1, 2.5);
+}
+
+var<workgroup> counter: atomic<u32>;
+
+@compute @workgroup_size(1)
+fn main() {
+    // Typed `let` declarations.
+    let a: f32 = 1;
+    let b: u32 = 4294967295;
+    let c: vec3<f32> = vec3(1, 2.5, 3);
+    let d: array<u32, 3> = array(1, 2, 3);
+
+    // Inferred types follow the conversion rank rules.
+    let e = 1 + 1.5;
+    let f = array(1, 2.0, 3);
+    let g = vec2(1, 2) + vec2(0.5);
+
+    // Assignments.
+    var h: f32;
+    h = 2;
+    h += 1;
+    h *= 2;
+    var i: vec2<u32>;
+    i = vec2(1, 2);
+    i.x = 3;
+    i[1] = 4;
+    i <<= vec2(1);
+    var j: array<f32, 2>;
+    j = array(1, 2);
+
+    // Function call arguments.
+    let k = takes_f32(1);
+    let l = takes_u32_vec(vec2(1, 2), 3);
+
+    // Built-in function arguments.
+    let m = max(1, 2.5);
+    let n = clamp(1u, 0, 2);
+    let o = sin(1);
+    let p = extractBits(5u, 0, 8);
+    let q = select(1, 2.0, true);
+    let r = dot(vec2(1.0, 2), vec2(3, 4));
+
+    // Atomic operands.
+    atomicAdd(&counter, 1);
+    atomicStore(&counter, 2);
+}
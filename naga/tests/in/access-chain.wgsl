@@ -0,0 +1,22 @@
+// Nested accesses through a pointer become a single `OpAccessChain`, with
+// each dynamic index restricted separately.
+
+struct Inner {
+    c: array<f32, 4>,
+}
+
+struct Middle {
+    b: array<Inner, 4>,
+}
+
+struct Outer {
+    a: array<Middle, 4>,
+}
+
+@group(0) @binding(0)
+var<storage, read_write> outer: Outer;
+
+@compute @workgroup_size(1)
+fn main(@builtin(local_invocation_index) i: u32) {
+    outer.a[i].b[i + 1u].c[2] = outer.a[2].b[i].c[i];
+}
@@ -32,3 +32,10 @@ fn test_atomic_compare_exchange_u32() {
         }
     }
 }
+
+@compute @workgroup_size(1)
+fn test_atomic_compare_exchange_computed() {
+    var expected = 1u;
+    let result = atomicCompareExchangeWeak(&arr_u32[0], expected * 2u, 0u);
+    expected = result.old_value;
+}
@@ -0,0 +1,32 @@
+// Atomics on struct members at dynamic offsets within storage buffers.
+
+struct Item {
+    value: u32,
+    counter: atomic<u32>,
+    balance: atomic<i32>,
+}
+
+@group(0) @binding(0)
+var<storage, read_write> items: array<Item>;
+
+var<workgroup> workgroup_counter: atomic<u32>;
+
+@compute @workgroup_size(64)
+fn cs_main(@builtin(local_invocation_index) i: u32) {
+    atomicStore(&items[i].counter, 1u);
+    let loaded = atomicLoad(&items[i].counter);
+
+    let add = atomicAdd(&items[i].counter, 1u);
+    let sub = atomicSub(&items[i].balance, 1);
+    let and = atomicAnd(&items[i].counter, 1u);
+    let or = atomicOr(&items[i].balance, 1);
+    let xor = atomicXor(&items[i].counter, 1u);
+    let min = atomicMin(&items[i].balance, 1);
+    let max = atomicMax(&items[i].counter, 1u);
+    let exchange = atomicExchange(&items[i].balance, 1);
+    let compare_exchange = atomicCompareExchangeWeak(&items[i].counter, loaded, 2u);
+
+    let workgroup = atomicCompareExchangeWeak(&workgroup_counter, 0u, 1u);
+    items[i].value = add + u32(sub) + and + u32(or) + xor + u32(min) + max + u32(exchange)
+        + compare_exchange.old_value + workgroup.old_value;
+}
@@ -8,6 +8,20 @@ var<storage, read> storage_array: binding_array<Foo, 1>;
 @group(0) @binding(10)
 var<uniform> uni: UniformIndex;
 
+// `Foo` is also used outside of binding arrays, which must not see their
+// `Block` decoration.
+struct Bar {
+    foo: Foo,
+    y: u32,
+}
+@group(0) @binding(11)
+var<uniform> uniform_foo: Foo;
+@group(0) @binding(12)
+var<storage, read_write> storage_foo: Foo;
+@group(0) @binding(13)
+var<storage, read_write> storage_array_rw: binding_array<Foo, 1>;
+var<private> bar: Bar;
+
 struct FragmentIn {
     @location(0) index: u32,
 }
@@ -23,5 +37,11 @@ fn main(fragment_in: FragmentIn) -> @location(0) u32 {
     u1 += storage_array[uniform_index].x;
     u1 += storage_array[non_uniform_index].x;
 
+    let foo = storage_array[uniform_index];
+    bar.foo = foo;
+    storage_foo = uniform_foo;
+    storage_array_rw[0] = bar.foo;
+    u1 += bar.foo.x + storage_foo.x;
+
     return u1;
 }
@@ -37,6 +37,10 @@ fn test_textureLoad_multisampled_2d(coords: vec2<i32>, _sample: i32) -> vec4<f32
    return textureLoad(image_multisampled_2d, coords, _sample);
 }
 
+fn test_textureLoad_multisampled_2d_u(coords: vec2<i32>, _sample: u32) -> vec4<f32> {
+   return textureLoad(image_multisampled_2d, coords, _sample);
+}
+
 @group(0) @binding(5)
 var image_depth_2d: texture_depth_2d;
 
@@ -62,6 +66,10 @@ fn test_textureLoad_depth_multisampled_2d(coords: vec2<i32>, _sample: i32) -> f3
    return textureLoad(image_depth_multisampled_2d, coords, _sample);
 }
 
+fn test_textureLoad_depth_multisampled_2d_u(coords: vec2<i32>, _sample: u32) -> f32 {
+   return textureLoad(image_depth_multisampled_2d, coords, _sample);
+}
+
 @group(0) @binding(8)
 var image_storage_1d: texture_storage_1d<rgba8unorm, write>;
 
@@ -104,11 +112,13 @@ fn fragment_shader() -> @location(0) vec4<f32> {
     test_textureLoad_2d_array_s(vec2<i32>(), 0, 0);
     test_textureLoad_3d(vec3<i32>(), 0);
     test_textureLoad_multisampled_2d(vec2<i32>(), 0);
+    test_textureLoad_multisampled_2d_u(vec2<i32>(), 0u);
+    test_textureLoad_depth_multisampled_2d(vec2<i32>(), 0);
+    test_textureLoad_depth_multisampled_2d_u(vec2<i32>(), 0u);
     // Not yet implemented for GLSL:
     // test_textureLoad_depth_2d(vec2<i32>(), 0);
     // test_textureLoad_depth_2d_array_u(vec2<i32>(), 0u, 0);
     // test_textureLoad_depth_2d_array_s(vec2<i32>(), 0, 0);
-    // test_textureLoad_depth_multisampled_2d(vec2<i32>(), 0);
     test_textureStore_1d(0, vec4<f32>());
     test_textureStore_2d(vec2<i32>(), vec4<f32>());
     test_textureStore_2d_array_u(vec2<i32>(), 0u, vec4<f32>());
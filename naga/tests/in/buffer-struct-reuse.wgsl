@@ -0,0 +1,41 @@
+// The same structs used as uniform and storage buffer roots, as members of
+// other structs, and as plain values. Only the buffer roots' types may be
+// decorated with `Block`.
+
+struct Light {
+    color: vec4<f32>,
+    intensity: f32,
+}
+
+struct Scene {
+    sun: Light,
+    ambient: vec4<f32>,
+}
+
+struct Lights {
+    count: u32,
+    items: array<Light>,
+}
+
+@group(0) @binding(0)
+var<uniform> sun_uniform: Light;
+@group(0) @binding(1)
+var<storage, read> sun_storage: Light;
+@group(0) @binding(2)
+var<storage, read_write> scene: Scene;
+@group(0) @binding(3)
+var<storage, read> lights: Lights;
+@group(0) @binding(4)
+var<storage, read_write> lights_out: Lights;
+
+var<private> fallback: Light;
+var<workgroup> shared_light: Light;
+
+@compute @workgroup_size(1)
+fn main() {
+    fallback = sun_uniform;
+    shared_light = sun_storage;
+    scene.sun = lights.items[0];
+    scene.ambient = fallback.color + shared_light.color * f32(lights.count);
+    lights_out.items[0] = scene.sun;
+}
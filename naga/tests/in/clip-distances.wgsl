@@ -0,0 +1,36 @@
+enable clip_distances;
+
+struct One {
+    @builtin(position) position: vec4<f32>,
+    @builtin(clip_distances) clip_distances: array<f32, 1>,
+}
+
+struct Four {
+    @builtin(position) position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+    @builtin(clip_distances) clip_distances: array<f32, 4>,
+}
+
+struct Five {
+    @builtin(position) position: vec4<f32>,
+    @builtin(clip_distances) clip_distances: array<f32, 5>,
+}
+
+@vertex
+fn vs_one() -> One {
+    var out: One;
+    out.position = vec4(0.0);
+    out.clip_distances[0] = 1.0;
+    return out;
+}
+
+@vertex
+fn vs_four(@location(0) plane: vec4<f32>) -> Four {
+    return Four(vec4(0.0), plane, array(plane.x, plane.y, plane.z, plane.w));
+}
+
+@vertex
+fn vs_five(@builtin(vertex_index) index: u32) -> Five {
+    let d = f32(index);
+    return Five(vec4(d), array(d, -d, 2.0 * d, -2.0 * d, 0.5));
+}
@@ -0,0 +1,26 @@
+// Conservative depth modes, declared alongside depth replacement.
+
+@fragment
+@early_depth_test(greater_equal)
+fn fs_greater(@builtin(position) position: vec4<f32>) -> @builtin(frag_depth) f32 {
+    return position.z + 0.1;
+}
+
+@fragment
+@early_depth_test(less_equal)
+fn fs_less(@builtin(position) position: vec4<f32>) -> @builtin(frag_depth) f32 {
+    return position.z - 0.1;
+}
+
+@fragment
+@early_depth_test(unchanged)
+fn fs_unchanged(@builtin(position) position: vec4<f32>) -> @builtin(frag_depth) f32 {
+    return position.z;
+}
+
+// Without a depth write, `unchanged` has nothing to describe.
+@fragment
+@early_depth_test(unchanged)
+fn fs_no_write(@builtin(position) position: vec4<f32>) -> @location(0) vec4<f32> {
+    return position;
+}
@@ -0,0 +1,64 @@
+// Branches whose conditions are known at translation time.
+
+const SELECTOR: i32 = 2;
+const ENABLED: bool = false;
+
+var<private> x: i32;
+
+fn constant_if() {
+    if true {
+        x = 1;
+    } else {
+        x = 2;
+    }
+
+    if ENABLED {
+        x = 3;
+    }
+
+    if false {
+        x = 4;
+    } else {
+        x = 5;
+    }
+}
+
+fn constant_switch() {
+    switch SELECTOR {
+        case 1: {
+            x = 1;
+        }
+        case 2, 3: {
+            x = 2;
+            break;
+        }
+        default: {
+            x = 3;
+        }
+    }
+
+    switch 7u {
+        case 0u: {
+            x = 4;
+        }
+        default: {
+            x = 5;
+        }
+    }
+}
+
+fn constant_if_in_loop() {
+    loop {
+        if true {
+            break;
+        }
+        x += 1;
+    }
+}
+
+@compute @workgroup_size(1)
+fn main() {
+    constant_if();
+    constant_switch();
+    constant_if_in_loop();
+}
@@ -0,0 +1,56 @@
+// Naga-specific hints about how to implement loops and branches.
+enable naga_control_flow_hints;
+
+@group(0) @binding(0)
+var<storage, read_write> values: array<f32, 16>;
+
+@compute @workgroup_size(1)
+fn main(@builtin(local_invocation_index) index: u32) {
+    var sum = 0.0;
+
+    @unroll
+    for (var i = 0u; i < 4u; i++) {
+        sum += values[i];
+    }
+
+    @dont_unroll
+    for (var i = 0u; i < index; i++) {
+        sum += values[i];
+    }
+
+    var j = 0u;
+    @unroll while j < 4u {
+        sum *= values[j];
+        j++;
+    }
+
+    @dont_unroll loop {
+        if sum > 100.0 {
+            break;
+        }
+        sum *= 2.0;
+        continuing {
+            break if sum == 0.0;
+        }
+    }
+
+    @branch if index == 0u {
+        sum = values[15];
+    } else if index == 1u {
+        sum = values[14];
+    }
+
+    @flatten if sum < 0.0 {
+        sum = -sum;
+    } else {
+        sum = sum * 0.5;
+    }
+
+    if sum > 1.0 {
+        @flatten if sum > 2.0 {
+            sum = 2.0;
+        }
+    }
+
+    values[index] = sum;
+}
@@ -88,3 +88,66 @@ fn loop_switch_continue(x: i32) {
         }
     }
 }
+
+fn loop_switch_break(x: i32) -> i32 {
+    var i = 0;
+    loop {
+        switch x {
+            case 1: {
+                break;
+            }
+            default: {}
+        }
+        i += 1;
+        if i > 4 {
+            break;
+        }
+    }
+    return i;
+}
+
+fn loop_switch_continue_if(x: i32) {
+    var i = 0;
+    loop {
+        switch x {
+            case 1: {
+                if i == 2 {
+                    continue;
+                }
+                break;
+            }
+            default: {}
+        }
+        continuing {
+            i += 1;
+            break if i > 4;
+        }
+    }
+}
+
+fn switch_loop_break(x: i32) {
+    switch x {
+        case 1: {
+            loop {
+                break;
+            }
+        }
+        default: {}
+    }
+}
+
+fn switch_loop_continue(x: i32) {
+    var i = 0;
+    switch x {
+        case 1: {
+            loop {
+                i += 1;
+                if i < 4 {
+                    continue;
+                }
+                break;
+            }
+        }
+        default: {}
+    }
+}
@@ -0,0 +1,35 @@
+// Whole struct and array assignments become `OpCopyMemory` in SPIR-V.
+
+struct Particle {
+    position: vec3<f32>,
+    velocity: vec3<f32>,
+    history: array<vec4<f32>, 8>,
+}
+
+@group(0) @binding(0)
+var<storage, read_write> particles: array<Particle, 4>;
+
+var<private> saved: Particle;
+
+@compute @workgroup_size(1)
+fn main() {
+    // Struct copies, between storage and private memory.
+    saved = particles[0];
+    particles[1] = saved;
+
+    // Fixed-size array copies, through access chains and between locals.
+    particles[2].history = particles[3].history;
+    var history = saved.history;
+    var other: array<vec4<f32>, 8>;
+    other = history;
+
+    // A loaded value with other uses is still loaded.
+    let first = particles[0];
+    particles[3] = first;
+    saved.position = first.position;
+
+    // Scalars and vectors are loaded and stored as before.
+    var speed = particles[0].velocity.x;
+    saved.velocity = particles[1].velocity;
+    speed = saved.position.y;
+}
@@ -0,0 +1,30 @@
+// Comments of the form `//@marker text` become `DebugMarker` statements,
+// which text backends write out as comments.
+
+@group(0) @binding(0)
+var<storage, read_write> data: array<u32>;
+
+@compute @workgroup_size(1)
+fn main() {
+    //@marker setup
+    var sum = 0u;
+    // an ordinary comment is dropped
+    //@marker accumulate
+    loop {
+        //@marker loop body
+        if sum > 10u {
+            break;
+        }
+        sum += data[sum];
+        continuing {
+            //@marker continuing
+            sum += 1u;
+        }
+    }
+    // a trailing backslash must not comment out the next line in C-like output
+    //@marker path C:\temp\
+    data[0] = sum;
+    // empty markers are kept
+    //@marker
+    //@marker done
+}
@@ -0,0 +1,17 @@
+// `textureLoad` on depth textures returns a scalar `f32`.
+
+@group(0) @binding(0)
+var depth_2d: texture_depth_2d;
+@group(0) @binding(1)
+var depth_2d_array: texture_depth_2d_array;
+@group(0) @binding(2)
+var depth_multisampled_2d: texture_depth_multisampled_2d;
+
+@fragment
+fn main(@builtin(position) position: vec4<f32>) -> @location(0) vec4<f32> {
+    let coords = vec2<i32>(position.xy);
+    let a: f32 = textureLoad(depth_2d, coords, 0);
+    let b: f32 = textureLoad(depth_2d_array, coords, 1, 0);
+    let c: f32 = textureLoad(depth_multisampled_2d, coords, 2);
+    return vec4<f32>(a, b, c, 1.0);
+}
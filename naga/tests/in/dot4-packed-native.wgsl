@@ -0,0 +1,13 @@
+// Packed dot products, with the native HLSL intrinsics of shader model 6.4.
+// The SPIR-V backend's instructions are checked in `spirv_capabilities.rs`.
+
+@group(0) @binding(0)
+var<storage, read_write> values: array<u32, 4>;
+
+@compute @workgroup_size(1)
+fn main() {
+    let a = values[0];
+    let b = values[1];
+    values[2] = bitcast<u32>(dot4I8Packed(a, b));
+    values[3] = dot4U8Packed(a, b);
+}
@@ -0,0 +1,15 @@
+// Dot products of vectors of four 8-bit integers, packed into `u32`s.
+
+@group(0) @binding(0)
+var<storage, read_write> values: array<u32, 5>;
+
+@compute @workgroup_size(1)
+fn main() {
+    let a = values[0];
+    let b = values[1];
+    values[2] = bitcast<u32>(dot4I8Packed(a, b));
+    values[3] = dot4U8Packed(a + 1u, b);
+
+    // Folded to constants: -128 * -1 * 4 and 255 * (1 + 2 + 3 + 4).
+    values[4] = bitcast<u32>(dot4I8Packed(0x80808080, 0xffffffff)) + dot4U8Packed(0xffffffffu, 0x04030201u);
+}
@@ -0,0 +1,42 @@
+// Loops written with `FORCE_BOUNDED_LOOPS`, capped at `max_loop_iterations`.
+
+fn loop_break_if(n: u32) -> u32 {
+    var i = 0u;
+    loop {
+        i += 1u;
+        continuing {
+            break if i >= n;
+        }
+    }
+    return i;
+}
+
+fn while_loop(n: u32) -> u32 {
+    var sum = 0u;
+    var i = 0u;
+    while i < n {
+        sum += i;
+        i += 1u;
+    }
+    return sum;
+}
+
+fn for_loop_continue(n: u32) -> u32 {
+    var sum = 0u;
+    for (var i = 0u; i < n; i++) {
+        if i % 2u == 0u {
+            continue;
+        }
+        for (var j = 0u; j < i; j++) {
+            sum += j;
+        }
+    }
+    return sum;
+}
+
+@compute @workgroup_size(1)
+fn main() {
+    _ = loop_break_if(4u);
+    _ = while_loop(4u);
+    _ = for_loop_continue(4u);
+}
@@ -0,0 +1,73 @@
+// Loops the GLSL backend writes with a native condition, and ones it can't.
+
+var<private> calls: u32;
+
+fn next(i: ptr<function, u32>) -> bool {
+    calls += 1u;
+    *i += 1u;
+    return *i < 8u;
+}
+
+// Leading `break`: `while(cond)`.
+fn while_loop(n: u32) -> u32 {
+    var sum = 0u;
+    var i = 0u;
+    while i < n {
+        sum += i;
+        i += 1u;
+    }
+    return sum;
+}
+
+// Leading `break` on a true condition: `while(!cond)`.
+fn until_loop(n: u32) -> u32 {
+    var i = 0u;
+    loop {
+        if i >= n {
+            break;
+        }
+        i += 2u;
+    }
+    return i;
+}
+
+// Trailing `break if`: `do { } while(!cond)`.
+fn do_while(n: u32) -> u32 {
+    var i = 0u;
+    loop {
+        i += 1u;
+        if i == 3u {
+            continue;
+        }
+        continuing {
+            break if i >= n;
+        }
+    }
+    return i;
+}
+
+// The condition calls a function, which mustn't be evaluated twice.
+fn side_effects() -> u32 {
+    var i = 0u;
+    while next(&i) {
+    }
+    return i;
+}
+
+// The continuing block has side effects.
+fn for_loop(n: u32) -> u32 {
+    var sum = 0u;
+    for (var i = 0u; i < n; i++) {
+        sum += i;
+    }
+    return sum;
+}
+
+@compute @workgroup_size(1)
+fn main() {
+    _ = while_loop(4u);
+    _ = until_loop(4u);
+    _ = do_while(4u);
+    _ = side_effects();
+    _ = for_loop(4u);
+}
@@ -0,0 +1,24 @@
+// Size queries exclude the layer count, and only sampled images take a level.
+
+@group(0) @binding(0)
+var storage_2d_array: texture_storage_2d_array<rgba8unorm, write>;
+@group(0) @binding(1)
+var sampled_cube: texture_cube<f32>;
+
+@group(0) @binding(2)
+var<storage, read_write> out: array<u32, 6>;
+
+@compute @workgroup_size(1)
+fn main() {
+    let storage_size = textureDimensions(storage_2d_array);
+    let storage_layers = textureNumLayers(storage_2d_array);
+    let cube_size = textureDimensions(sampled_cube);
+    let cube_size_lod = textureDimensions(sampled_cube, 1);
+
+    out[0] = storage_size.x;
+    out[1] = storage_size.y;
+    out[2] = storage_layers;
+    out[3] = cube_size.x;
+    out[4] = cube_size.y;
+    out[5] = cube_size_lod.x;
+}
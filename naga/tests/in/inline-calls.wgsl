@@ -0,0 +1,49 @@
+// Every call here is inlined before the module is written, so the output
+// should compute the same values without any calls.
+
+struct Particle {
+    position: vec2<f32>,
+    velocity: vec2<f32>,
+}
+
+@group(0) @binding(0)
+var<storage, read_write> particles: array<Particle>;
+
+fn square(x: f32) -> f32 {
+    return x * x;
+}
+
+fn length_squared(v: vec2<f32>) -> f32 {
+    return square(v.x) + square(v.y);
+}
+
+// Returns early from inside a loop.
+fn first_negative(v: vec4<f32>) -> i32 {
+    for (var i = 0; i < 4; i++) {
+        if v[i] < 0.0 {
+            return i;
+        }
+    }
+    return -1;
+}
+
+// Takes a pointer, and has no result.
+fn step(p: ptr<function, Particle>, dt: f32) {
+    (*p).position += (*p).velocity * dt;
+    if length_squared((*p).velocity) > 1.0 {
+        (*p).velocity = normalize((*p).velocity);
+    }
+}
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    var total = 0.0;
+    for (var i = 0u; i < 2u; i++) {
+        var particle = particles[id.x + i];
+        step(&particle, 0.5);
+        particles[id.x + i] = particle;
+        // A call in a loop, whose locals must be reinitialized each time.
+        total += f32(first_negative(vec4(particles[id.x].position, -1.0, 2.0)));
+    }
+    particles[id.x].velocity = vec2(total, square(total));
+}
@@ -0,0 +1,11 @@
+// Note: this is mirrored in `instance-index-gl460.wgsl`, which targets a GLSL
+// version with `gl_BaseInstance` instead of emulating it with a uniform.
+
+@vertex
+fn main(
+    @builtin(instance_index) instance: u32,
+    @location(0) position: vec2<f32>,
+) -> @builtin(position) vec4<f32> {
+    let offset = f32(instance) * 0.1;
+    return vec4<f32>(position + vec2<f32>(offset, 0.0), 0.0, 1.0);
+}
@@ -0,0 +1,51 @@
+// Integer overloads of the math builtins, for both signed and unsigned
+// scalars and vectors.
+@compute @workgroup_size(1)
+fn main() {
+    var i = 0;
+    var i3 = vec3<i32>(0);
+    var u = 0u;
+    var u3 = vec3<u32>(0u);
+    i = abs(i);
+    i3 = abs(i3);
+    u = abs(u);
+    u3 = abs(u3);
+    i = sign(i);
+    i3 = sign(i3);
+    i = min(i, i);
+    i3 = min(i3, i3);
+    u = min(u, u);
+    u3 = min(u3, u3);
+    i = max(i, i);
+    i3 = max(i3, i3);
+    u = max(u, u);
+    u3 = max(u3, u3);
+    i = clamp(i, i, i);
+    i3 = clamp(i3, i3, i3);
+    u = clamp(u, u, u);
+    u3 = clamp(u3, u3, u3);
+    i = countOneBits(i);
+    i3 = countOneBits(i3);
+    u = countOneBits(u);
+    u3 = countOneBits(u3);
+    i = reverseBits(i);
+    i3 = reverseBits(i3);
+    u = reverseBits(u);
+    u3 = reverseBits(u3);
+    i = firstLeadingBit(i);
+    i3 = firstLeadingBit(i3);
+    u = firstLeadingBit(u);
+    u3 = firstLeadingBit(u3);
+    i = firstTrailingBit(i);
+    i3 = firstTrailingBit(i3);
+    u = firstTrailingBit(u);
+    u3 = firstTrailingBit(u3);
+    i = countLeadingZeros(i);
+    i3 = countLeadingZeros(i3);
+    u = countLeadingZeros(u);
+    u3 = countLeadingZeros(u3);
+    i = countTrailingZeros(i);
+    i3 = countTrailingZeros(i3);
+    u = countTrailingZeros(u);
+    u3 = countTrailingZeros(u3);
+}
@@ -45,4 +45,9 @@ fn main() {
     let frexp_b = frexp(1.5).fract;
     let frexp_c: i32 = frexp(1.5).exp;
     let frexp_d: i32 = frexp(vec4(1.5, 1.5, 1.5, 1.5)).exp.x;
+    let round_a = round(v);
+    let fract_a = fract(v);
+    let trunc_a = trunc(v);
+    let floor_a = floor(v);
+    let ceil_a = ceil(v);
 }
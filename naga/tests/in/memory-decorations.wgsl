@@ -0,0 +1,28 @@
+// Coherent and volatile storage buffers
+enable naga_memory_decorations;
+
+struct Queue {
+    head: atomic<u32>,
+    items: array<u32, 64>,
+}
+
+@group(0) @binding(0) @coherent
+var<storage, read_write> queue: Queue;
+
+@group(0) @binding(1) @volatile
+var<storage, read_write> flags: array<vec4<u32>, 4>;
+
+@group(0) @binding(2) @coherent @volatile
+var<storage, read_write> mailbox: u32;
+
+@group(0) @binding(3)
+var<storage, read_write> plain: u32;
+
+@compute @workgroup_size(64)
+fn main(@builtin(local_invocation_index) index: u32) {
+    let slot = atomicAdd(&queue.head, 1u);
+    queue.items[slot % 64u] = index;
+    flags[index % 4u].y = queue.items[index];
+    mailbox = mailbox + plain;
+    plain = index;
+}
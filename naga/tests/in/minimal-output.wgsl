@@ -0,0 +1,36 @@
+// Written with the SPIR-V backend's `MINIMAL_OUTPUT` flag, which drops
+// debug names and regroups declarations.
+
+struct Globals {
+    transform: mat4x4<f32>,
+    tint: vec4<f32>,
+    time: f32,
+}
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+    @location(1) @interpolate(flat) layer: u32,
+}
+
+const SCALE: f32 = 0.5;
+const CORNER = vec2(-1.0, -1.0);
+const LAYERS: u32 = 4u;
+
+@group(0) @binding(0) var<uniform> globals: Globals;
+@group(0) @binding(1) var texture: texture_2d_array<f32>;
+@group(0) @binding(2) var texture_sampler: sampler;
+
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32, @builtin(instance_index) instance: u32) -> VertexOutput {
+    let offset = CORNER + 4.0 * vec2(f32(index & 1u), f32(index >> 1u));
+    let position = globals.transform * vec4(offset * SCALE, 0.0, 1.0);
+    return VertexOutput(position, offset * 0.5 + 0.5, instance % LAYERS);
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let color = textureSample(texture, texture_sampler, in.uv, in.layer);
+    let pulse = 0.5 + 0.5 * sin(globals.time);
+    return color * globals.tint * pulse;
+}
@@ -0,0 +1,35 @@
+// Conservative depth modes and depth clamping in the MSL backend.
+
+struct FragmentOutput {
+    @location(0) color: vec4<f32>,
+    @builtin(frag_depth) depth: f32,
+}
+
+@fragment
+@early_depth_test(greater_equal)
+fn fs_greater(@builtin(position) position: vec4<f32>) -> @builtin(frag_depth) f32 {
+    return position.z + 0.1;
+}
+
+@fragment
+@early_depth_test(less_equal)
+fn fs_less(@builtin(position) position: vec4<f32>) -> FragmentOutput {
+    return FragmentOutput(vec4(1.0), position.z - 0.1);
+}
+
+@fragment
+@early_depth_test(unchanged)
+fn fs_unchanged(@builtin(position) position: vec4<f32>) -> @builtin(frag_depth) f32 {
+    return position.z;
+}
+
+@fragment
+fn fs_any(@builtin(position) position: vec4<f32>) -> @builtin(frag_depth) f32 {
+    return position.z * 2.0;
+}
+
+// `msl-frag-depth.param.ron` overrides this entry point's mode.
+@fragment
+fn fs_override(@builtin(position) position: vec4<f32>) -> @builtin(frag_depth) f32 {
+    return position.z + 0.5;
+}
@@ -0,0 +1,27 @@
+// A tiled reduction, summing `TILE_SIZE` elements of `input` per workgroup.
+// `tests/override_arrays.rs` makes `TILE_SIZE` an override that sizes `tile`.
+
+const TILE_SIZE: u32 = 64u;
+
+@group(0) @binding(0) var<storage, read> input: array<f32>;
+@group(0) @binding(1) var<storage, read_write> output: array<f32>;
+
+var<workgroup> tile: array<f32, TILE_SIZE>;
+
+@compute @workgroup_size(64)
+fn main(
+    @builtin(local_invocation_index) index: u32,
+    @builtin(workgroup_id) group: vec3<u32>,
+) {
+    tile[index] = input[group.x * TILE_SIZE + index];
+    workgroupBarrier();
+    for (var stride = 32u; stride > 0u; stride /= 2u) {
+        if index < stride {
+            tile[index] += tile[index + stride];
+        }
+        workgroupBarrier();
+    }
+    if index == 0u {
+        output[group.x] = tile[0];
+    }
+}
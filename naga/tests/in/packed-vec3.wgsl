@@ -0,0 +1,35 @@
+// Three-component vectors followed by a scalar are tightly packed in storage
+// buffers, so the MSL backend must declare them with packed vector types.
+
+struct Packed {
+	v: vec3<f32>, // offset 0, size 12
+	f: f32, // offset 12: packs into the vector's padding
+	iv: vec3<i32>, // offset 16, size 12
+	u: u32, // offset 28
+	uv: vec3<u32>, // offset 32, size 12; padded to the struct's alignment
+}
+
+@group(0) @binding(0)
+var<storage, read_write> packed: Packed;
+
+fn length_of(v: vec3<f32>) -> f32 {
+	return length(v);
+}
+
+@compute @workgroup_size(1)
+fn main() {
+	// loads
+	let whole = packed.v;
+	let scaled = packed.v * 2.0;
+	let swizzled = packed.v.zyx;
+	let component = packed.v.y;
+	let passed = length_of(packed.v);
+	let transformed = mat3x3<f32>() * packed.v;
+
+	// stores
+	packed.v = vec3(1.0) + whole;
+	packed.v.x = 4.0;
+	packed.iv = -packed.iv;
+	packed.uv = packed.uv.zxy + vec3(packed.u);
+	packed.f = scaled.x + swizzled.x + component + passed + transformed.x;
+}
@@ -0,0 +1,43 @@
+// Calls that pass pointers into variables. Without variable pointers, the
+// SPIR-V backend inlines the ones whose arguments are access chains.
+
+struct Particle {
+    position: vec4<f32>,
+    velocity: vec4<f32>,
+}
+
+@group(0) @binding(0) var<storage, read_write> out: array<f32>;
+
+fn push(p: ptr<function, vec4<f32>>, amount: f32) -> f32 {
+    var steps = 2;
+    (*p).x += amount;
+    if amount > 2.0 {
+        return (*p).y;
+    }
+    for (var i = 0; i < steps; i++) {
+        if (*p).z > 10.0 {
+            return (*p).z;
+        }
+        (*p).z += 1.0;
+    }
+    return (*p).x;
+}
+
+fn step(particle: ptr<function, Particle>) -> f32 {
+    return push(&(*particle).velocity, 0.5) + push(&(*particle).position, 1.0);
+}
+
+fn nudge(p: ptr<function, vec4<f32>>) {
+    push(p, 3.0);
+}
+
+@compute @workgroup_size(1)
+fn main() {
+    var particle: Particle;
+    var total = 0.0;
+    for (var i = 0; i < 4; i++) {
+        total += step(&particle);
+        nudge(&particle.velocity);
+    }
+    out[0] = total + particle.position.x;
+}
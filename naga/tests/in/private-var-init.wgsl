@@ -0,0 +1,26 @@
+// Module-scope `var<private>` declarations with initializers
+
+const K = 3;
+
+struct S {
+    position: vec3<f32>,
+    weights: array<f32, 2>,
+    count: u32,
+}
+
+var<private> table = array(1, 2, K);
+var<private> nested = array(array(1.0, 2.0), array<f32, 2>());
+var<private> grid: array<array<i32, 2>, 2> = array(array(1, 2), array(3, 4));
+var<private> zero_vec = vec3<f32>();
+var<private> zero_struct = S();
+var<private> s = S(vec3(1.0), array(0.5, f32(K)), 4u);
+var<private> counter: u32;
+var<private> origin: S;
+
+@compute @workgroup_size(1)
+fn main() {
+    counter += u32(table[2]) + s.count;
+    origin.position = zero_vec + zero_struct.position + s.position;
+    origin.weights = nested[0];
+    origin.count = u32(grid[1][1]);
+}
@@ -0,0 +1,19 @@
+// `quantizeToF16` through GL_AMD_gpu_shader_half_float's `float16_t`.
+
+struct Values {
+    scalar: f32,
+    pair: vec2<f32>,
+    triple: vec3<f32>,
+    quad: vec4<f32>,
+}
+
+@group(0) @binding(0)
+var<storage, read_write> values: Values;
+
+@compute @workgroup_size(1)
+fn main() {
+    values.scalar = quantizeToF16(values.scalar);
+    values.pair = quantizeToF16(values.pair);
+    values.triple = quantizeToF16(values.triple);
+    values.quad = quantizeToF16(values.quad);
+}
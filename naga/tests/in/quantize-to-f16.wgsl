@@ -0,0 +1,22 @@
+struct Values {
+    scalar: f32,
+    pair: vec2<f32>,
+    triple: vec3<f32>,
+    quad: vec4<f32>,
+    rounded: vec2<f32>,
+}
+
+// Evaluated when the shader is created, to `vec2(1.0, 65504.0)`.
+const ROUNDED = quantizeToF16(vec2(1.0004883, 65519.0));
+
+@group(0) @binding(0)
+var<storage, read_write> values: Values;
+
+@compute @workgroup_size(1)
+fn main() {
+    values.scalar = quantizeToF16(values.scalar);
+    values.pair = quantizeToF16(values.pair);
+    values.triple = quantizeToF16(values.triple);
+    values.quad = quantizeToF16(values.quad);
+    values.rounded = ROUNDED;
+}
@@ -0,0 +1,60 @@
+// `all`, `any` and component-wise `select` at every vector size.
+
+struct Inputs {
+    a: vec4<f32>,
+    b: vec4<f32>,
+    i: vec4<i32>,
+    u: vec4<u32>,
+}
+
+@group(0) @binding(0)
+var<uniform> inputs: Inputs;
+
+fn all_any(c2: vec2<bool>, c3: vec3<bool>, c4: vec4<bool>) -> bool {
+    // Of a single boolean, `all` and `any` are the identity.
+    return all(c2) && any(c2) && all(c3) && any(c3) && all(c4) && any(c4)
+        && all(c4.x) && any(c4.y);
+}
+
+fn select_f32(c2: vec2<bool>, c3: vec3<bool>, c4: vec4<bool>) -> vec4<f32> {
+    let f2 = select(inputs.a.xy, inputs.b.xy, c2);
+    let f3 = select(inputs.a.xyz, inputs.b.xyz, c3);
+    let f4 = select(inputs.a, inputs.b, c4);
+    return vec4(f2, f2) + vec4(f3, 1.0) + f4;
+}
+
+fn select_i32(c2: vec2<bool>, c3: vec3<bool>, c4: vec4<bool>) -> vec4<i32> {
+    let i2 = select(inputs.i.xy, -inputs.i.yx, c2);
+    let i3 = select(inputs.i.xyz, -inputs.i.zyx, c3);
+    let i4 = select(inputs.i, -inputs.i, c4);
+    return vec4(i2, i2) + vec4(i3, 1) + i4;
+}
+
+fn select_u32(c2: vec2<bool>, c3: vec3<bool>, c4: vec4<bool>) -> vec4<u32> {
+    let u2 = select(inputs.u.xy, inputs.u.yx, c2);
+    let u3 = select(inputs.u.xyz, inputs.u.zyx, c3);
+    let u4 = select(inputs.u, inputs.u.wzyx, c4);
+    return vec4(u2, u2) + vec4(u3, 1u) + u4;
+}
+
+fn select_bool(c2: vec2<bool>, c3: vec3<bool>, c4: vec4<bool>) -> bool {
+    let b2 = select(c2, !c2, c2.yx);
+    let b3 = select(c3, !c3, c3.zxy);
+    let b4 = select(c4, !c4, c4.wzyx);
+    return any(b2) && any(b3) && any(b4);
+}
+
+@fragment
+fn main() -> @location(0) vec4<f32> {
+    let c4 = inputs.a < inputs.b;
+    let c3 = c4.xyz;
+    let c2 = c4.xy;
+
+    var color = select_f32(c2, c3, c4);
+    color += vec4<f32>(select_i32(c2, c3, c4));
+    color += vec4<f32>(select_u32(c2, c3, c4));
+    if all_any(c2, c3, c4) || select_bool(c2, c3, c4) {
+        color.w = 0.0;
+    }
+    return color;
+}
@@ -0,0 +1,19 @@
+// Sampling array textures with signed and unsigned layer indices.
+// The same as `sample-array-index`, with the `Restrict` policy.
+
+@group(0) @binding(0)
+var image_2d_array: texture_2d_array<f32>;
+@group(0) @binding(1)
+var image_cube_array: texture_cube_array<f32>;
+@group(0) @binding(2)
+var samp: sampler;
+
+@fragment
+fn main(@location(0) uv: vec2<f32>, @location(1) @interpolate(flat) layer: i32) -> @location(0) vec4<f32> {
+    let dir = vec3<f32>(uv, 1.0);
+    let a = textureSample(image_2d_array, samp, uv, layer);
+    let b = textureSample(image_2d_array, samp, uv, u32(layer));
+    let c = textureSampleLevel(image_cube_array, samp, dir, layer, 0.0);
+    let d = textureSampleLevel(image_cube_array, samp, dir, u32(layer), 0.0);
+    return a + b + c + d;
+}
@@ -0,0 +1,18 @@
+// Per-sample built-ins and interpolation in fragment shaders.
+
+struct FragmentIn {
+    @location(0) @interpolate(perspective, sample) color: vec4<f32>,
+    @builtin(sample_index) sample_index: u32,
+    @builtin(sample_mask) sample_mask: u32,
+}
+
+struct FragmentOut {
+    @location(0) color: vec4<f32>,
+    @builtin(sample_mask) sample_mask: u32,
+}
+
+@fragment
+fn main(input: FragmentIn) -> FragmentOut {
+    let mask = input.sample_mask & (1u << input.sample_index);
+    return FragmentOut(input.color, mask);
+}
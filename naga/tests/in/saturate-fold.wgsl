@@ -0,0 +1,15 @@
+// `clamp(x, 0.0, 1.0)` written as `saturate(x)` by `fold_clamp_to_saturate`.
+
+const ONE: f32 = 1.0;
+
+@fragment
+fn main(@location(0) x: f32, @location(1) v: vec3<f32>) -> @location(0) vec4<f32> {
+    let a = clamp(x, 0.0, 1.0);
+    let b = clamp(v, vec3(0.0), vec3(1.0));
+    let c = clamp(x, 0.0, ONE);
+    let d = saturate(x);
+    // Not a unit range, so left alone.
+    let e = clamp(x, 0.0, 2.0);
+    let f = clamp(v, vec3(0.0), vec3(1.0, 1.0, 0.5));
+    return vec4(b + f, a + c + d + e);
+}
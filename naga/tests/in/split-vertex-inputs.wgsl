@@ -0,0 +1,26 @@
+// Vertex inputs that take several locations: one per column of a matrix,
+// or per element of an array.
+
+struct Instance {
+    @location(1) transform: mat4x4<f32>,
+    @location(5) weights: array<f32, 2>,
+    @location(7) color: vec4<f32>,
+}
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+}
+
+@vertex
+fn main(
+    @location(0) position: vec3<f32>,
+    instance: Instance,
+    @location(8) normal_matrix: mat3x3<f32>,
+    @location(11) joints: array<vec2<u32>, 2>,
+) -> VertexOutput {
+    let normal = normal_matrix * position;
+    let world = instance.transform * vec4(position + normal, 1.0);
+    let weight = instance.weights[0] * f32(joints[0].x) + instance.weights[1] * f32(joints[1].y);
+    return VertexOutput(world, instance.color * weight);
+}
@@ -0,0 +1,23 @@
+// Storage buffers and textures with each access mode
+
+@group(0) @binding(0)
+var<storage, read> weights: array<f32>;
+@group(0) @binding(1)
+var<storage, read_write> totals: array<f32>;
+
+@group(0) @binding(2)
+var source: texture_storage_2d<r32float, read>;
+@group(0) @binding(3)
+var destination: texture_storage_2d<rgba8unorm, write>;
+@group(0) @binding(4)
+var accumulator: texture_storage_2d<r32float, read_write>;
+
+@compute @workgroup_size(8, 8)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    let weight = weights[id.x];
+    let value = textureLoad(source, id.xy).x * weight;
+    totals[id.x] += value;
+    textureStore(destination, id.xy, vec4(value));
+    let sum = textureLoad(accumulator, id.xy) + value;
+    textureStore(accumulator, id.xy, sum);
+}
@@ -0,0 +1,28 @@
+// Type aliases kept by `WriterFlags::USE_TYPE_ALIASES`.
+
+alias Scalar = f32;
+alias Vec = vec4<Scalar>;
+alias Pair = array<Vec, 2>;
+
+struct Particle {
+    position: Vec,
+    history: Pair,
+}
+
+alias Particles = array<Particle, 4>;
+
+@group(0) @binding(0)
+var<storage, read_write> particles: Particles;
+
+fn advance(particle: Particle, step: Scalar) -> Vec {
+    let last: Vec = particle.history[1];
+    return particle.position + (particle.position - last) * step;
+}
+
+@compute @workgroup_size(4)
+fn main(@builtin(local_invocation_index) index: u32) {
+    let particle = particles[index];
+    let position = advance(particle, 0.5);
+    particles[index].history = Pair(particle.position, position);
+    particles[index].position = position;
+}
@@ -10,3 +10,21 @@ fn test_workgroupUniformLoad(@builtin(workgroup_id) workgroup_id: vec3<u32>) {
         workgroupBarrier();
     }
 }
+
+struct Params {
+    count: u32,
+    scale: f32,
+}
+
+var<workgroup> params: Params;
+
+@compute @workgroup_size(4)
+fn test_workgroupUniformLoad_struct(@builtin(local_invocation_index) index: u32) {
+    if index == 0u {
+        params = Params(4u, 2.0);
+    }
+    let loaded = workgroupUniformLoad(&params);
+    for (var i = 0u; i < loaded.count; i++) {
+        workgroupBarrier();
+    }
+}
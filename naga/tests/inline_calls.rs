@@ -0,0 +1,143 @@
+/*!
+Test inlining every call with `naga::proc::inline_all_calls`.
+*/
+
+#![cfg(feature = "wgsl-in")]
+
+use naga::{Block, Expression, Span, Statement};
+
+const SOURCE: &str = include_str!("in/inline-calls.wgsl");
+
+fn count_calls(block: &Block) -> usize {
+    block
+        .iter()
+        .map(|statement| match *statement {
+            Statement::Call { .. } => 1,
+            Statement::Block(ref block) => count_calls(block),
+            Statement::If {
+                ref accept,
+                ref reject,
+                ..
+            } => count_calls(accept) + count_calls(reject),
+            Statement::Switch { ref cases, .. } => {
+                cases.iter().map(|case| count_calls(&case.body)).sum()
+            }
+            Statement::Loop {
+                ref body,
+                ref continuing,
+                ..
+            } => count_calls(body) + count_calls(continuing),
+            _ => 0,
+        })
+        .sum()
+}
+
+fn functions(module: &naga::Module) -> impl Iterator<Item = &naga::Function> {
+    module
+        .functions
+        .iter()
+        .map(|(_, function)| function)
+        .chain(module.entry_points.iter().map(|ep| &ep.function))
+}
+
+#[test]
+fn no_calls_remain() {
+    let mut module = naga::front::wgsl::parse_str(SOURCE).unwrap();
+    assert!(functions(&module).any(|function| count_calls(&function.body) > 0));
+
+    naga::proc::inline_all_calls(&mut module);
+    for function in functions(&module) {
+        assert_eq!(count_calls(&function.body), 0, "{:?}", function.name);
+    }
+    validate(&module);
+}
+
+fn validate(module: &naga::Module) {
+    naga::valid::Validator::new(
+        naga::valid::ValidationFlags::all(),
+        naga::valid::Capabilities::default(),
+    )
+    .validate(module)
+    .unwrap();
+}
+
+/// Front ends put a call's arguments before its result in the expression
+/// arena, but nothing requires that.
+#[test]
+fn arguments_after_result() {
+    let mut module =
+        naga::front::wgsl::parse_str("fn double(x: f32) -> f32 { return x * 2.0; }").unwrap();
+    let (double, _) = module.functions.iter().next().unwrap();
+    let result = module.functions[double].result.clone();
+
+    // fn caller() -> f32 { return double(3.0 + 3.0) * 3.0; }
+    let mut caller = naga::Function {
+        name: Some("caller".to_string()),
+        result,
+        ..Default::default()
+    };
+    let expressions = &mut caller.expressions;
+    let doubled = expressions.append(Expression::CallResult(double), Span::UNDEFINED);
+    let three = expressions.append(
+        Expression::Literal(naga::Literal::F32(3.0)),
+        Span::UNDEFINED,
+    );
+    let sum = expressions.append(
+        Expression::Binary {
+            op: naga::BinaryOperator::Add,
+            left: three,
+            right: three,
+        },
+        Span::UNDEFINED,
+    );
+    let product = expressions.append(
+        Expression::Binary {
+            op: naga::BinaryOperator::Multiply,
+            left: doubled,
+            right: three,
+        },
+        Span::UNDEFINED,
+    );
+    caller.body = Block::from_vec(vec![
+        Statement::Emit(naga::Range::new_from_bounds(sum, sum)),
+        Statement::Call {
+            function: double,
+            arguments: vec![sum],
+            result: Some(doubled),
+        },
+        Statement::Emit(naga::Range::new_from_bounds(product, product)),
+        Statement::Return {
+            value: Some(product),
+        },
+    ]);
+    let caller = module.functions.append(caller, Span::UNDEFINED);
+    validate(&module);
+
+    naga::proc::inline_all_calls(&mut module);
+    assert_eq!(count_calls(&module.functions[caller].body), 0);
+    validate(&module);
+}
+
+#[test]
+fn spans_point_at_callee() {
+    let mut module = naga::front::wgsl::parse_str(SOURCE).unwrap();
+    naga::proc::inline_all_calls(&mut module);
+
+    // `square` is inlined into `main`, so its multiplication should be there
+    // with the span of `x * x`.
+    let main = &module.entry_points[0].function;
+    let squares = main
+        .expressions
+        .iter()
+        .filter(|&(handle, expr)| {
+            matches!(*expr, Expression::Binary { .. })
+                && main
+                    .expressions
+                    .get_span(handle)
+                    .to_range()
+                    .map(|range| &SOURCE[range])
+                    == Some("x * x")
+        })
+        .count();
+    assert!(squares > 0);
+}
@@ -0,0 +1,172 @@
+/*!
+Tests for merging modules with `naga::link`.
+*/
+
+#![cfg(all(feature = "link", feature = "wgsl-in"))]
+
+use naga::link::{self, LinkError};
+use naga::valid::{Capabilities, ValidationFlags, Validator};
+
+fn parse(source: &str) -> naga::Module {
+    naga::front::wgsl::parse_str(source).unwrap_or_else(|e| panic!("{}", e.emit_to_string(source)))
+}
+
+fn validate(module: &naga::Module) {
+    Validator::new(ValidationFlags::all(), Capabilities::default())
+        .validate(module)
+        .expect("merged module should validate");
+}
+
+/// Shared source that both modules below are parsed with, since WGSL can't
+/// declare functions without defining them.
+const UTILITY: &str = "
+struct Light {
+    direction: vec3<f32>,
+    intensity: f32,
+}
+
+const AMBIENT: f32 = 0.1;
+
+@group(0) @binding(0)
+var<uniform> light: Light;
+
+fn shade(normal: vec3<f32>) -> f32 {
+    return max(dot(normal, light.direction), 0.0) * light.intensity + AMBIENT;
+}
+";
+
+fn material(entry_point: &str, scale: f32) -> naga::Module {
+    parse(&format!(
+        "{UTILITY}
+        @fragment
+        fn {entry_point}(@location(0) normal: vec3<f32>) -> @location(0) vec4<f32> {{
+            return vec4<f32>(shade(normal) * {scale:?});
+        }}"
+    ))
+}
+
+#[test]
+fn merge_shared_library() {
+    let mut dst = material("first", 1.0);
+    let src = material("second", 2.0);
+    let options = link::Options {
+        deduplicate_functions: true,
+    };
+    let remap = link::merge_with_options(&mut dst, src, &options).unwrap();
+    validate(&dst);
+
+    // The shared definitions were merged...
+    assert_eq!(dst.functions.len(), 1);
+    assert_eq!(dst.global_variables.len(), 1);
+    let named_types = dst.types.iter().filter(|(_, ty)| ty.name.is_some());
+    assert_eq!(named_types.count(), 1);
+    let named_constants = dst.constants.iter().filter(|(_, c)| c.name.is_some());
+    assert_eq!(named_constants.count(), 1);
+
+    // ...and the second entry point now calls the first module's function.
+    let (shade, _) = dst.functions.iter().next().unwrap();
+    assert_eq!(remap.function(shade), shade);
+    let second = &dst.entry_points[1];
+    assert_eq!(second.name, "second");
+    assert!(second
+        .function
+        .expressions
+        .iter()
+        .any(|(_, expr)| matches!(*expr, naga::Expression::CallResult(f) if f == shade)));
+}
+
+#[test]
+fn merge_without_deduplication() {
+    let mut dst = material("first", 1.0);
+    let src = material("second", 2.0);
+    let (src_shade, _) = src.functions.iter().next().unwrap();
+    let remap = link::merge(&mut dst, src).unwrap();
+    validate(&dst);
+
+    assert_eq!(dst.functions.len(), 2);
+    assert_ne!(remap.function(src_shade), src_shade);
+    assert_eq!(dst.entry_points.len(), 2);
+}
+
+#[test]
+fn merge_disjoint_modules() {
+    let mut dst = parse(
+        "
+        const SCALE: i32 = 2;
+        var<workgroup> shared_value: i32;
+        fn double(x: i32) -> i32 { return x * SCALE; }
+        @compute @workgroup_size(1)
+        fn first() { shared_value = double(1); }
+        ",
+    );
+    let src = parse(
+        "
+        const OFFSETS: array<u32, 2> = array<u32, 2>(1u, 2u);
+        var<private> offsets: array<u32, 2> = OFFSETS;
+        @group(0) @binding(0)
+        var<storage, read_write> output: array<u32>;
+        fn offset(i: u32) -> u32 { return output[i] + offsets[i]; }
+        @compute @workgroup_size(64)
+        fn second(@builtin(local_invocation_index) i: u32) { output[i] = offset(i % 2u); }
+        ",
+    );
+    link::merge(&mut dst, src).unwrap();
+    validate(&dst);
+
+    assert_eq!(dst.functions.len(), 2);
+    assert_eq!(dst.global_variables.len(), 3);
+    let names: Vec<_> = dst.entry_points.iter().map(|ep| ep.name.as_str()).collect();
+    assert_eq!(names, ["first", "second"]);
+}
+
+#[test]
+fn conflicts() {
+    fn check(dst: &str, src: &str, expected: LinkError) {
+        let mut dst = parse(dst);
+        match link::merge(&mut dst, parse(src)) {
+            Err(error) => assert_eq!(error, expected),
+            Ok(_) => panic!("expected {expected:?}"),
+        }
+    }
+
+    check(
+        "struct S { a: f32 }",
+        "struct S { a: i32 }",
+        LinkError::ConflictingType("S".to_string()),
+    );
+    check(
+        "const C: u32 = 1u;",
+        "const C: u32 = 2u;",
+        LinkError::ConflictingConstant("C".to_string()),
+    );
+    check(
+        "@group(0) @binding(0) var<uniform> g: vec4<f32>;",
+        "@group(0) @binding(1) var<uniform> g: vec4<f32>;",
+        LinkError::ConflictingGlobalVariable("g".to_string()),
+    );
+    check(
+        "@group(0) @binding(0) var<uniform> a: vec4<f32>;",
+        "@group(0) @binding(0) var<uniform> b: vec4<f32>;",
+        LinkError::ConflictingBinding {
+            first: "a".to_string(),
+            second: "b".to_string(),
+            binding: naga::ResourceBinding {
+                group: 0,
+                binding: 0,
+            },
+        },
+    );
+    check(
+        "fn f() -> i32 { return 1; }",
+        "fn f() -> i32 { return 2; }",
+        LinkError::ConflictingFunction("f".to_string()),
+    );
+    check(
+        "@compute @workgroup_size(1) fn main() {}",
+        "@compute @workgroup_size(2) fn main() {}",
+        LinkError::ConflictingEntryPoint {
+            name: "main".to_string(),
+            stage: naga::ShaderStage::Compute,
+        },
+    );
+}
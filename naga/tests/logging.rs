@@ -0,0 +1,73 @@
+/*!
+Test the debug logs that explain translation decisions.
+*/
+
+#![cfg(all(feature = "wgsl-in", feature = "spv-out"))]
+
+use std::cell::RefCell;
+
+thread_local! {
+    /// The `(target, message)` pairs logged on this thread.
+    static RECORDS: RefCell<Vec<(String, String)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// A logger that records messages per thread, so that tests running in
+/// parallel only see their own.
+struct Capture;
+
+impl log::Log for Capture {
+    fn enabled(&self, _metadata: &log::Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &log::Record) {
+        let entry = (record.target().to_string(), record.args().to_string());
+        RECORDS.with(|records| records.borrow_mut().push(entry));
+    }
+
+    fn flush(&self) {}
+}
+
+/// Run `f`, and return what it logged.
+fn capture(f: impl FnOnce()) -> Vec<(String, String)> {
+    static INIT: std::sync::Once = std::sync::Once::new();
+    INIT.call_once(|| {
+        log::set_logger(&Capture).unwrap();
+        log::set_max_level(log::LevelFilter::Debug);
+    });
+
+    RECORDS.with(|records| records.borrow_mut().clear());
+    f();
+    RECORDS.with(|records| records.take())
+}
+
+#[test]
+fn capability_selection() {
+    use naga::back::spv;
+    use naga::valid;
+
+    let source = "
+        @group(0) @binding(0)
+        var image_1d: texture_1d<f32>;
+
+        fn f() -> vec4<f32> {
+            return textureLoad(image_1d, 0, 0);
+        }
+    ";
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+    let info = valid::Validator::new(valid::ValidationFlags::all(), valid::Capabilities::all())
+        .validate(&module)
+        .unwrap();
+
+    let records = capture(|| {
+        spv::write_vec(&module, &info, &spv::Options::default(), None).unwrap();
+    });
+
+    assert!(
+        records.iter().any(|(target, message)| {
+            target.starts_with("naga::back::spv")
+                && message.starts_with("selected capability Sampled1D for sampled 1D images")
+        }),
+        "{records:#?}"
+    );
+}
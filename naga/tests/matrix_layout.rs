@@ -0,0 +1,142 @@
+/*!
+Test that the backends lay out matrices in buffers the way the `Layouter` does.
+
+WGSL stores matrices column by column, and the columns of a `matCx3` are
+padded to 16 bytes, like `vec4`s.
+*/
+
+#![cfg(feature = "wgsl-in")]
+
+const SOURCE: &str = "
+    struct Matrices {
+        a: mat2x2<f32>,
+        b: mat3x3<f32>,
+        c: mat4x3<f32>,
+    }
+
+    @group(0) @binding(0) var<uniform> input: Matrices;
+    @group(0) @binding(1) var<storage, read_write> output: Matrices;
+
+    @compute @workgroup_size(1)
+    fn main() {
+        let loaded = output;
+        output = input;
+        output.b = loaded.b * 2.0;
+    }
+";
+
+/// The offsets of the members of `Matrices`, and its size.
+const OFFSETS: [u32; 3] = [0, 16, 64];
+const SPAN: u32 = 128;
+
+fn parse() -> (naga::Module, naga::valid::ModuleInfo) {
+    let module = naga::front::wgsl::parse_str(SOURCE).unwrap();
+    let info = naga::valid::Validator::new(
+        naga::valid::ValidationFlags::all(),
+        naga::valid::Capabilities::default(),
+    )
+    .validate(&module)
+    .unwrap();
+    (module, info)
+}
+
+fn matrices(module: &naga::Module) -> naga::Handle<naga::Type> {
+    module
+        .types
+        .iter()
+        .find(|&(_, ty)| ty.name.as_deref() == Some("Matrices"))
+        .unwrap()
+        .0
+}
+
+#[test]
+fn layouter() {
+    let (module, _) = parse();
+    let mut layouter = naga::proc::Layouter::default();
+    layouter.update(module.to_ctx()).unwrap();
+
+    let handle = matrices(&module);
+    let naga::TypeInner::Struct { ref members, span } = module.types[handle].inner else {
+        unreachable!();
+    };
+    let offsets: Vec<u32> = members.iter().map(|member| member.offset).collect();
+    assert_eq!(offsets, OFFSETS);
+    assert_eq!(span, SPAN);
+    assert_eq!(layouter[handle].size, SPAN);
+
+    // A `mat3x3<f32>` has three 16-byte columns.
+    assert_eq!(layouter[members[1].ty].size, 48);
+    assert_eq!(layouter[members[2].ty].size, 64);
+}
+
+/// Storage buffers load each column from its own offset, so the columns of
+/// `b` and `c` must be 16 bytes apart.
+#[cfg(feature = "hlsl-out")]
+#[test]
+fn hlsl() {
+    let (module, info) = parse();
+    let mut output = String::new();
+    naga::back::hlsl::Writer::new(&mut output, &Default::default())
+        .write(&module, &info)
+        .unwrap();
+
+    // Uniform buffers declare the matrices transposed; see the HLSL backend's
+    // module documentation.
+    assert!(output.contains("float2 a_0; float2 a_1;"), "{output}");
+    assert!(output.contains("row_major float3x3 b;"), "{output}");
+    assert!(output.contains("row_major float4x3 c;"), "{output}");
+
+    // The struct's members, as their offset, column count, row count, and
+    // column stride.
+    let members = [
+        (OFFSETS[0], 2, 2, 8),
+        (OFFSETS[1], 3, 3, 16),
+        (OFFSETS[2], 4, 3, 16),
+    ];
+    for (offset, columns, rows, stride) in members {
+        for column in 0..columns {
+            let address = format!("{offset}+{}", column * stride);
+            let load = format!("asfloat(output.Load{rows}({address}))");
+            assert!(output.contains(&load), "missing `{load}`:\n{output}");
+            let store = format!("output.Store{rows}({address}, asuint(");
+            assert!(output.contains(&store), "missing `{store}`:\n{output}");
+        }
+    }
+
+    // Structs hold `a` as separate columns, which stores reassemble.
+    assert!(
+        output.contains("float2x2(_value2.a_0, _value2.a_1)"),
+        "{output}"
+    );
+}
+
+#[cfg(feature = "spv-out")]
+#[test]
+fn spirv() {
+    use rspirv::dr::Operand;
+    use rspirv::spirv::Decoration;
+
+    let (module, info) = parse();
+    let words =
+        naga::back::spv::write_vec(&module, &info, &naga::back::spv::Options::default(), None)
+            .unwrap();
+    let spv = rspirv::dr::load_words(words).unwrap();
+
+    let member_decorations = |decoration: Decoration| -> Vec<u32> {
+        spv.annotations
+            .iter()
+            .filter(|inst| {
+                inst.class.opcode == rspirv::spirv::Op::MemberDecorate
+                    && inst.operands[2] == Operand::Decoration(decoration)
+            })
+            .map(|inst| inst.operands[3].unwrap_literal_int32())
+            .collect()
+    };
+    for offset in OFFSETS {
+        assert!(member_decorations(Decoration::Offset).contains(&offset));
+    }
+    // `a` has 8-byte columns, and `b` and `c` have 16-byte columns.
+    let strides = member_decorations(Decoration::MatrixStride);
+    assert!(strides.contains(&8));
+    assert!(strides.contains(&16));
+}
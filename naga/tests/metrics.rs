@@ -0,0 +1,60 @@
+/*!
+Tests for `naga::metrics`. The snapshot tests check the metrics of their
+inputs against the baselines in `tests/out/metrics`.
+*/
+
+#![cfg(feature = "metrics")]
+
+use naga::metrics::Metrics;
+
+#[test]
+fn regressions() {
+    let baseline = Metrics::parse("# comment\na.spv.words 100\na.msl.lines 10\n").unwrap();
+    assert_eq!(baseline.to_string(), "a.msl.lines 10\na.spv.words 100\n");
+
+    let mut current = baseline.clone();
+    current.insert("a.spv.words", 102);
+    current.insert("b.spv.words", 1000);
+    assert_eq!(baseline.regressions(&current, 2), []);
+
+    current.insert("a.spv.words", 103);
+    let regressions = baseline.regressions(&current, 2);
+    assert_eq!(regressions.len(), 1);
+    assert_eq!(regressions[0].to_string(), "a.spv.words: 100 -> 103");
+
+    let current = Metrics::parse("a.spv.words 90\n").unwrap();
+    let regressions = baseline.regressions(&current, 2);
+    assert_eq!(regressions.len(), 1);
+    assert_eq!(regressions[0].to_string(), "a.msl.lines: 10 -> missing");
+
+    assert_eq!(Metrics::parse("a.spv.words x").unwrap_err().line, 1);
+}
+
+#[test]
+fn spirv_classes() {
+    // The header, then `OpCapability Shader`, `OpTypeVoid %1`, and
+    // `OpTypeFunction %2 %1`.
+    let words = [
+        0x0723_0203,
+        0x0001_0000,
+        0,
+        3,
+        0,
+        0x0002_0011,
+        1,
+        0x0002_0013,
+        1,
+        0x0003_0021,
+        2,
+        1,
+    ];
+    let mut metrics = Metrics::new();
+    metrics.measure_spirv("spv", &words);
+    assert_eq!(
+        metrics.to_string(),
+        "spv.instructions 3\n\
+         spv.instructions.mode 1\n\
+         spv.instructions.type 2\n\
+         spv.words 12\n"
+    );
+}
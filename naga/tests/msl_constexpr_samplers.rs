@@ -0,0 +1,159 @@
+/*!
+Test writing samplers listed in `Options::constexpr_samplers` as `constexpr`
+declarations in MSL.
+*/
+
+#![cfg(all(feature = "wgsl-in", feature = "msl-out"))]
+
+use naga::back::msl;
+
+const SOURCE: &str = "
+    @group(0) @binding(0) var tex: texture_2d<f32>;
+    @group(0) @binding(1) var fixed_sampler: sampler;
+    @group(0) @binding(2) var dynamic_sampler: sampler;
+
+    @fragment
+    fn main(@location(0) uv: vec2<f32>) -> @location(0) vec4<f32> {
+        return textureSample(tex, fixed_sampler, uv) + textureSample(tex, dynamic_sampler, uv);
+    }
+";
+
+const FIXED: naga::ResourceBinding = naga::ResourceBinding {
+    group: 0,
+    binding: 1,
+};
+
+fn options(fixed: msl::sampler::InlineSampler) -> msl::Options {
+    let resources = msl::BindingMap::from([
+        (
+            naga::ResourceBinding {
+                group: 0,
+                binding: 0,
+            },
+            msl::BindTarget {
+                texture: Some(0),
+                ..Default::default()
+            },
+        ),
+        (
+            naga::ResourceBinding {
+                group: 0,
+                binding: 2,
+            },
+            msl::BindTarget {
+                sampler: Some(msl::BindSamplerTarget::Resource(0)),
+                ..Default::default()
+            },
+        ),
+    ]);
+    msl::Options {
+        lang_version: (2, 0),
+        per_entry_point_map: msl::EntryPointResourceMap::from([(
+            "main".to_string(),
+            msl::EntryPointResources {
+                resources,
+                ..Default::default()
+            },
+        )]),
+        constexpr_samplers: msl::ConstexprSamplerMap::from([(FIXED, fixed)]),
+        fake_missing_bindings: false,
+        ..Default::default()
+    }
+}
+
+fn write(options: &msl::Options) -> Result<(String, msl::TranslationInfo), msl::Error> {
+    let module = naga::front::wgsl::parse_str(SOURCE).unwrap();
+    let info = naga::valid::Validator::new(
+        naga::valid::ValidationFlags::all(),
+        naga::valid::Capabilities::default(),
+    )
+    .validate(&module)
+    .unwrap();
+    msl::write_string(&module, &info, options, &msl::PipelineOptions::default())
+}
+
+#[test]
+fn mapped_and_unmapped() {
+    let (source, info) = write(&options(msl::sampler::InlineSampler {
+        address: [msl::sampler::Address::Repeat; 3],
+        mag_filter: msl::sampler::Filter::Linear,
+        min_filter: msl::sampler::Filter::Linear,
+        ..Default::default()
+    }))
+    .unwrap();
+
+    assert_eq!(info.entry_point_names[0].as_deref(), Ok("main_"));
+    assert_eq!(info.entry_point_constexpr_samplers, [vec![FIXED]]);
+
+    // The mapped sampler is declared in the entry point, and takes no slot.
+    assert!(
+        source.contains("constexpr metal::sampler fixed_sampler(\n"),
+        "{source}"
+    );
+    assert!(source.contains("metal::s_address::repeat,"), "{source}");
+    assert!(source.contains("metal::mag_filter::linear,"), "{source}");
+    assert!(!source.contains("fixed_sampler [["), "{source}");
+
+    // The other one is still an argument.
+    assert!(
+        source.contains("metal::sampler dynamic_sampler [[sampler(0)]]"),
+        "{source}"
+    );
+}
+
+#[test]
+fn unsupported() {
+    let anisotropic = options(msl::sampler::InlineSampler {
+        max_anisotropy: std::num::NonZeroU32::new(16),
+        ..Default::default()
+    });
+    assert!(matches!(
+        write(&anisotropic),
+        Err(msl::Error::UnsupportedConstexprSampler(
+            FIXED,
+            "anisotropic filtering"
+        ))
+    ));
+
+    let lod_clamped = options(msl::sampler::InlineSampler {
+        lod_clamp: Some(0.0..4.0),
+        ..Default::default()
+    });
+    assert!(matches!(
+        write(&lod_clamped),
+        Err(msl::Error::UnsupportedConstexprSampler(
+            FIXED,
+            "LOD clamping"
+        ))
+    ));
+
+    // A texture can't be written as a sampler.
+    let mut texture = options(Default::default());
+    let sampler = texture.constexpr_samplers.remove(&FIXED).unwrap();
+    texture.constexpr_samplers.insert(
+        naga::ResourceBinding {
+            group: 0,
+            binding: 0,
+        },
+        sampler,
+    );
+    texture
+        .per_entry_point_map
+        .get_mut("main")
+        .unwrap()
+        .resources
+        .insert(
+            FIXED,
+            msl::BindTarget {
+                sampler: Some(msl::BindSamplerTarget::Resource(1)),
+                ..Default::default()
+            },
+        );
+    assert!(matches!(
+        write(&texture),
+        Err(msl::Error::UnsupportedConstexprSampler(
+            _,
+            "the global is not a sampler"
+        ))
+    ));
+}
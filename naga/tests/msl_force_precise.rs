@@ -0,0 +1,97 @@
+/*!
+Test which entry points the MSL backend reports as needing fast math
+disabled, with and without `Options::force_precise`.
+*/
+
+#![cfg(feature = "msl-out")]
+
+use naga::back::msl;
+use naga::valid;
+
+fn write(module: &naga::Module, options: &msl::Options) -> (String, Vec<bool>) {
+    let info = valid::Validator::new(valid::ValidationFlags::all(), valid::Capabilities::all())
+        .validate(module)
+        .expect("validation failed");
+    let (source, info) =
+        msl::write_string(module, &info, options, &msl::PipelineOptions::default()).unwrap();
+    (source, info.entry_point_needs_precise)
+}
+
+#[cfg(feature = "wgsl-in")]
+#[test]
+fn invariant_position() {
+    let module = naga::front::wgsl::parse_str(
+        "
+        @vertex
+        fn plain() -> @builtin(position) vec4<f32> {
+            return vec4(0.0);
+        }
+
+        struct Output {
+            @location(0) color: vec4<f32>,
+            @invariant @builtin(position) position: vec4<f32>,
+        }
+
+        @vertex
+        fn invariant() -> Output {
+            return Output(vec4(1.0), vec4(0.0));
+        }
+        ",
+    )
+    .unwrap();
+
+    for force_precise in [false, true] {
+        let options = msl::Options {
+            lang_version: (2, 1),
+            force_precise,
+            ..Default::default()
+        };
+        let (source, needs_precise) = write(&module, &options);
+        assert!(source.contains("[[position, invariant]]"), "{source}");
+        // Rewriting NaN checks can't make invariance work under fast math.
+        assert_eq!(
+            needs_precise,
+            [false, true],
+            "force_precise: {force_precise}"
+        );
+    }
+}
+
+#[cfg(feature = "glsl-in")]
+#[test]
+fn nan_checks() {
+    let module = naga::front::glsl::Frontend::default()
+        .parse(
+            &naga::front::glsl::Options::from(naga::ShaderStage::Fragment),
+            "
+            #version 450
+            layout(location = 0) in vec4 v;
+            layout(location = 0) out vec4 o;
+
+            bool is_special(float x) {
+                return isnan(x) || isinf(x);
+            }
+
+            void main() {
+                o = is_special(v.x) ? vec4(0.0) : v;
+            }
+            ",
+        )
+        .unwrap();
+
+    let (source, needs_precise) = write(&module, &msl::Options::default());
+    assert!(source.contains("metal::isnan("), "{source}");
+    assert_eq!(needs_precise, [true]);
+
+    let options = msl::Options {
+        force_precise: true,
+        ..Default::default()
+    };
+    let (source, needs_precise) = write(&module, &options);
+    assert!(
+        !source.contains("isnan") && !source.contains("isinf"),
+        "{source}"
+    );
+    assert!(source.contains("0x7F800000u"), "{source}");
+    assert_eq!(needs_precise, [false]);
+}
@@ -0,0 +1,281 @@
+/*!
+Test that the MSL backend respects `Options::lang_version`.
+*/
+
+#![cfg(all(feature = "wgsl-in", feature = "msl-out"))]
+
+use naga::back::msl;
+use naga::valid;
+
+fn write(module: &naga::Module, lang_version: (u8, u8)) -> Result<String, msl::Error> {
+    let info = valid::Validator::new(valid::ValidationFlags::all(), valid::Capabilities::all())
+        .validate(module)
+        .expect("validation failed");
+    let options = msl::Options {
+        lang_version,
+        ..Default::default()
+    };
+    msl::write_string(module, &info, &options, &msl::PipelineOptions::default())
+        .map(|(source, _)| source)
+}
+
+/// Check that `module` can be written for `lang_version` if and only if
+/// it is at least `needed`.
+fn check(name: &str, module: &naga::Module, needed: (u8, u8), lang_version: (u8, u8)) {
+    match write(module, lang_version) {
+        Ok(_) if lang_version >= needed => {}
+        Ok(_) => panic!("{name}: expected an error at MSL {lang_version:?}"),
+        Err(msl::Error::UnsupportedMslVersion {
+            needed: reported,
+            requested,
+            ..
+        }) if lang_version < needed => {
+            assert_eq!(reported, needed, "{name}");
+            assert_eq!(requested, lang_version, "{name}");
+        }
+        Err(other) => panic!("{name}: unexpected error at MSL {lang_version:?}: {other}"),
+    }
+}
+
+const FEATURES: &[(&str, (u8, u8), &str)] = &[
+    (
+        "compute",
+        (1, 0),
+        "
+        @compute @workgroup_size(1)
+        fn main() {}
+        ",
+    ),
+    (
+        "instance_index",
+        (1, 2),
+        "
+        @vertex
+        fn main(@builtin(instance_index) i: u32) -> @builtin(position) vec4<f32> {
+            return vec4(f32(i));
+        }
+        ",
+    ),
+    (
+        "reverseBits",
+        (1, 2),
+        "
+        @compute @workgroup_size(1)
+        fn main() {
+            var x = 1u;
+            x = reverseBits(x);
+        }
+        ",
+    ),
+    (
+        "read_write storage texture",
+        (1, 2),
+        "
+        @group(0) @binding(0)
+        var t: texture_storage_2d<r32float, read_write>;
+
+        @compute @workgroup_size(1)
+        fn main() {
+            textureStore(t, vec2(0), textureLoad(t, vec2(0)));
+        }
+        ",
+    ),
+    (
+        "32-bit atomics",
+        (1, 0),
+        "
+        @group(0) @binding(0)
+        var<storage, read_write> total: atomic<u32>;
+        var<workgroup> largest: atomic<i32>;
+
+        @compute @workgroup_size(1)
+        fn main() {
+            atomicAdd(&total, 1u);
+            atomicMax(&largest, atomicLoad(&largest) - 1);
+            atomicStore(&total, atomicExchange(&total, 0u));
+        }
+        ",
+    ),
+    (
+        "binding array of textures",
+        (2, 0),
+        "
+        @group(0) @binding(0)
+        var t: binding_array<texture_2d<f32>, 4>;
+        var<private> v: vec4<f32>;
+
+        @compute @workgroup_size(1)
+        fn main() {
+            v = textureLoad(t[0], vec2(0), 0);
+        }
+        ",
+    ),
+    (
+        "invariant",
+        (2, 1),
+        "
+        @vertex
+        fn main() -> @invariant @builtin(position) vec4<f32> {
+            return vec4(0.0);
+        }
+        ",
+    ),
+    (
+        "primitive_index",
+        (2, 2),
+        "
+        @fragment
+        fn main(@builtin(primitive_index) i: u32) -> @location(0) vec4<f32> {
+            return vec4(f32(i));
+        }
+        ",
+    ),
+    (
+        "primitive_index in struct",
+        (2, 2),
+        "
+        struct FragmentIn {
+            @location(0) color: vec4<f32>,
+            @builtin(primitive_index) index: u32,
+        }
+
+        @fragment
+        fn main(in: FragmentIn) -> @location(0) vec4<f32> {
+            return in.color * f32(in.index);
+        }
+        ",
+    ),
+    (
+        "ray query",
+        (2, 4),
+        "
+        @group(0) @binding(0)
+        var acc: acceleration_structure;
+
+        @compute @workgroup_size(1)
+        fn main() {
+            var rq: ray_query;
+            rayQueryInitialize(&rq, acc, RayDesc(0u, 0xFFu, 0.1, 100.0, vec3(0.0), vec3(0.0, 1.0, 0.0)));
+            rayQueryProceed(&rq);
+        }
+        ",
+    ),
+];
+
+const VERSIONS: &[(u8, u8)] = &[(1, 2), (2, 0), (2, 4)];
+
+#[test]
+fn feature_matrix() {
+    for &(name, needed, source) in FEATURES {
+        let module = naga::front::wgsl::parse_str(source).unwrap_or_else(|e| {
+            panic!(
+                "{name}: expected WGSL to parse successfully:\n{}",
+                e.emit_to_string(source)
+            )
+        });
+        for &lang_version in VERSIONS {
+            check(name, &module, needed, lang_version);
+        }
+    }
+}
+
+#[test]
+fn primitive_id_needs_family_support() {
+    let module = naga::front::wgsl::parse_str(
+        "
+        @fragment
+        fn main(@builtin(primitive_index) i: u32) -> @location(0) vec4<f32> {
+            return vec4(f32(i));
+        }
+        ",
+    )
+    .unwrap();
+    let info = valid::Validator::new(valid::ValidationFlags::all(), valid::Capabilities::all())
+        .validate(&module)
+        .expect("validation failed");
+    let write = |supports_primitive_id| {
+        let options = msl::Options {
+            lang_version: (2, 4),
+            supports_primitive_id,
+            ..Default::default()
+        };
+        msl::write_string(&module, &info, &options, &msl::PipelineOptions::default())
+            .map(|(source, _)| source)
+    };
+
+    let source = write(true).unwrap();
+    assert!(source.contains("[[primitive_id]]"), "{source}");
+    match write(false) {
+        Err(msl::Error::UnsupportedBuiltIn(naga::BuiltIn::PrimitiveIndex)) => {}
+        other => panic!("expected an unsupported built-in error, got {other:?}"),
+    }
+}
+
+#[test]
+fn per_sample_interpolation() {
+    let module = naga::front::wgsl::parse_str(
+        "
+        @fragment
+        fn main(@location(0) @interpolate(linear, sample) x: f32) -> @location(0) vec4<f32> {
+            return vec4(x);
+        }
+        ",
+    )
+    .unwrap();
+    for lang_version in [(1, 0), (1, 1), (1, 2)] {
+        check("per-sample interpolation", &module, (1, 1), lang_version);
+    }
+
+    // Centroid interpolation needs nothing newer than MSL 1.0.
+    let module = naga::front::wgsl::parse_str(
+        "
+        @fragment
+        fn main(@location(0) @interpolate(linear, centroid) x: f32) -> @location(0) vec4<f32> {
+            return vec4(x);
+        }
+        ",
+    )
+    .unwrap();
+    check("centroid interpolation", &module, (1, 0), (1, 0));
+}
+
+#[test]
+fn multisampled_array_textures() {
+    // WGSL has no multisampled array textures, so patch one in.
+    let mut module = naga::front::wgsl::parse_str(
+        "
+        @group(0) @binding(0)
+        var t: texture_multisampled_2d<f32>;
+        var<private> n: u32;
+
+        @compute @workgroup_size(1)
+        fn main() {
+            n = textureNumSamples(t);
+        }
+        ",
+    )
+    .unwrap();
+    let (handle, _) = module
+        .types
+        .iter()
+        .find(|&(_, ty)| matches!(ty.inner, naga::TypeInner::Image { .. }))
+        .unwrap();
+    module.types.replace(
+        handle,
+        naga::Type {
+            name: None,
+            inner: naga::TypeInner::Image {
+                dim: naga::ImageDimension::D2,
+                arrayed: true,
+                class: naga::ImageClass::Sampled {
+                    kind: naga::ScalarKind::Float,
+                    multi: true,
+                },
+            },
+        },
+    );
+
+    for &lang_version in VERSIONS {
+        check("multisampled array", &module, (2, 0), lang_version);
+    }
+}
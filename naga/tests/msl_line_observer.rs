@@ -0,0 +1,127 @@
+/*!
+Test that `msl::Writer::set_line_observer` accounts for every line of the
+function bodies the MSL backend writes.
+*/
+
+#![cfg(all(feature = "wgsl-in", feature = "msl-out"))]
+
+use std::{
+    ops::Range,
+    sync::{Arc, Mutex},
+};
+
+use naga::back::msl;
+use naga::valid;
+
+const SOURCE: &str = "
+@group(0) @binding(0)
+var<storage, read_write> data: array<u32, 64>;
+
+fn step(x: u32) -> u32 {
+    if x % 2u == 0u {
+        return x / 2u;
+    }
+    return 3u * x + 1u;
+}
+
+@compute @workgroup_size(64)
+fn main() {
+    loop {
+        let x = data[0];
+        if x <= 1u {
+            break;
+        }
+        data[0] = step(x);
+        continuing {
+            data[1] += 1u;
+        }
+    }
+    switch data[2] {
+        case 0u: {
+            data[2] = 1u;
+        }
+        default: {
+            workgroupBarrier();
+        }
+    }
+}
+";
+
+fn write(source: &str) -> (String, Vec<(naga::Span, Range<usize>)>) {
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+    let info = valid::Validator::new(valid::ValidationFlags::all(), valid::Capabilities::all())
+        .validate(&module)
+        .unwrap();
+
+    let reports = Arc::new(Mutex::new(Vec::new()));
+    let mut writer = msl::Writer::new(String::new());
+    writer.set_line_observer({
+        let reports = Arc::clone(&reports);
+        Box::new(move |span, lines| reports.lock().unwrap().push((span, lines)))
+    });
+    writer
+        .write(
+            &module,
+            &info,
+            &msl::Options::default(),
+            &msl::PipelineOptions::default(),
+        )
+        .unwrap();
+
+    let output = writer.finish();
+    let reports = std::mem::take(&mut *reports.lock().unwrap());
+    (output, reports)
+}
+
+#[test]
+fn covers_function_bodies() {
+    let (output, reports) = write(SOURCE);
+    let lines: Vec<&str> = output.lines().collect();
+
+    for (_, range) in reports.iter() {
+        assert!(range.end <= lines.len(), "range {range:?} is out of bounds");
+    }
+
+    // The shader has no locals, so every line strictly inside a function
+    // body should belong to some statement with a known position in the
+    // WGSL source.
+    let mut body_lines = 0;
+    let mut in_body = false;
+    for (number, line) in lines.iter().enumerate() {
+        if *line == "}" {
+            in_body = false;
+            continue;
+        }
+        if in_body && !line.trim().is_empty() {
+            body_lines += 1;
+            assert!(
+                reports
+                    .iter()
+                    .any(|&(span, ref range)| span.is_defined() && range.contains(&number)),
+                "line {number} is not covered by any statement:\n{line}\n\n{output}",
+            );
+        }
+        if line.ends_with(") {") && !line.starts_with(' ') {
+            in_body = true;
+        }
+    }
+    assert!(body_lines > 0, "no function bodies found:\n{output}");
+}
+
+#[test]
+fn nested_ranges() {
+    let (_, reports) = write(SOURCE);
+
+    // Blocks are written before the statement containing them is reported,
+    // so each report's range encloses every earlier report that overlaps it.
+    for (index, (_, outer)) in reports.iter().enumerate() {
+        for (_, inner) in &reports[..index] {
+            if inner.start < outer.end && outer.start < inner.end {
+                assert!(
+                    outer.start <= inner.start && inner.end <= outer.end,
+                    "{inner:?} overlaps but is not inside {outer:?}",
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,251 @@
+/*!
+Test that the MSL backend lays out host-shareable structs the same way the
+SPIR-V backend does, using packed vector types for tightly packed `vec3`s.
+*/
+
+#![cfg(all(feature = "wgsl-in", feature = "msl-out", feature = "spv-out"))]
+
+use std::collections::HashMap;
+
+use naga::back::{msl, spv};
+use naga::valid;
+
+/// The size and alignment of a Metal type.
+#[derive(Clone, Copy, Debug)]
+struct Layout {
+    size: u32,
+    alignment: u32,
+}
+
+/// A member of a Metal struct: its name, offset, and size.
+type Member = (String, u32, u32);
+
+fn align_to(offset: u32, alignment: u32) -> u32 {
+    (offset + alignment - 1) / alignment * alignment
+}
+
+/// Lay out the structs declared in the Metal source `source`, following
+/// Metal's rules for the handful of types the tests below use.
+///
+/// Return each struct's members, by struct name. Padding members are
+/// omitted.
+fn msl_structs(source: &str) -> HashMap<String, Vec<Member>> {
+    let mut layouts: HashMap<String, Layout> = [
+        ("char", 1, 1),
+        ("float", 4, 4),
+        ("int", 4, 4),
+        ("uint", 4, 4),
+        ("metal::packed_float3", 12, 4),
+        ("metal::packed_int3", 12, 4),
+        ("metal::packed_uint3", 12, 4),
+        ("metal::float3", 16, 16),
+        ("metal::int3", 16, 16),
+        ("metal::uint3", 16, 16),
+    ]
+    .into_iter()
+    .map(|(name, size, alignment)| (name.to_string(), Layout { size, alignment }))
+    .collect();
+
+    let mut structs = HashMap::new();
+    let mut lines = source.lines();
+    while let Some(line) = lines.next() {
+        let Some(name) = line
+            .strip_prefix("struct ")
+            .and_then(|rest| rest.strip_suffix(" {"))
+        else {
+            continue;
+        };
+
+        let mut members = Vec::new();
+        let mut offset = 0;
+        let mut alignment = 1;
+        for line in lines.by_ref() {
+            let line = line.trim();
+            if line == "};" {
+                break;
+            }
+            let (ty, declarator) = line.trim_end_matches(';').rsplit_once(' ').unwrap();
+            let (member, count) = match declarator.split_once('[') {
+                Some((member, count)) => (member, count.trim_end_matches(']').parse().unwrap()),
+                None => (declarator, 1),
+            };
+            let layout = layouts[ty];
+            offset = align_to(offset, layout.alignment);
+            alignment = alignment.max(layout.alignment);
+            let size = layout.size * count;
+            if !member.starts_with("_pad") {
+                members.push((member.to_string(), offset, size));
+            }
+            offset += size;
+        }
+
+        let layout = Layout {
+            size: align_to(offset, alignment),
+            alignment,
+        };
+        layouts.insert(name.to_string(), layout);
+        structs.insert(name.to_string(), members);
+    }
+    structs
+}
+
+/// Return the offsets of the members of the SPIR-V struct named `name`, and
+/// the strides of all arrays, in declaration order.
+fn spv_layout(words: Vec<u32>, name: &str) -> (Vec<u32>, Vec<u32>) {
+    use rspirv::dr::Operand;
+    use rspirv::spirv::{Decoration, Op};
+
+    let module = rspirv::dr::load_words(words).unwrap();
+    let id = module
+        .debug_names
+        .iter()
+        .find(|inst| {
+            inst.class.opcode == Op::Name && inst.operands[1] == Operand::LiteralString(name.into())
+        })
+        .map(|inst| inst.operands[0].unwrap_id_ref())
+        .unwrap();
+
+    let mut offsets = Vec::new();
+    let mut strides = Vec::new();
+    for inst in module.annotations.iter() {
+        match inst.class.opcode {
+            Op::MemberDecorate
+                if inst.operands[0].unwrap_id_ref() == id
+                    && inst.operands[2] == Operand::Decoration(Decoration::Offset) =>
+            {
+                offsets.push(inst.operands[3].unwrap_literal_int32());
+            }
+            Op::Decorate if inst.operands[1] == Operand::Decoration(Decoration::ArrayStride) => {
+                strides.push(inst.operands[2].unwrap_literal_int32());
+            }
+            _ => {}
+        }
+    }
+    (offsets, strides)
+}
+
+/// Write `module` as Metal and SPIR-V, validating it with `flags`.
+fn write(module: &naga::Module, flags: valid::ValidationFlags) -> (String, Vec<u32>) {
+    let info = valid::Validator::new(flags, valid::Capabilities::default())
+        .validate(module)
+        .expect("validation failed");
+    let (source, _) = msl::write_string(
+        module,
+        &info,
+        &msl::Options::default(),
+        &msl::PipelineOptions::default(),
+    )
+    .unwrap();
+    let words = spv::write_vec(module, &info, &spv::Options::default(), None).unwrap();
+    (source, words)
+}
+
+#[test]
+fn struct_members() {
+    let module = naga::front::wgsl::parse_str(
+        "
+        struct Packed {
+            v: vec3<f32>,
+            f: f32,
+            iv: vec3<i32>,
+            uv: vec3<u32>,
+            u: u32,
+        }
+
+        @group(0) @binding(0)
+        var<storage, read_write> packed: Packed;
+
+        @compute @workgroup_size(1)
+        fn main() {
+            packed.f = packed.v.y;
+        }
+        ",
+    )
+    .unwrap();
+    let (source, words) = write(&module, valid::ValidationFlags::all());
+
+    let members = &msl_structs(&source)["Packed"];
+    let (offsets, _) = spv_layout(words, "Packed");
+    let msl_offsets: Vec<_> = members.iter().map(|&(_, offset, _)| offset).collect();
+    assert_eq!(msl_offsets, offsets, "{source}");
+    assert_eq!(offsets, [0, 12, 16, 32, 44]);
+}
+
+#[test]
+fn array_elements() {
+    let mut module = naga::front::wgsl::parse_str(
+        "
+        struct Tight {
+            arr: array<vec3<f32>, 4>,
+            f: f32,
+        }
+
+        @group(0) @binding(0)
+        var<storage, read_write> tight: Tight;
+
+        @compute @workgroup_size(1)
+        fn main() {
+            let i = u32(tight.f);
+            tight.f = tight.arr[1].y + tight.arr[i].z + (tight.arr[2] * 2.0).x;
+            tight.arr[3] = tight.arr[0].zyx;
+            tight.arr[i].x = 1.0;
+        }
+        ",
+    )
+    .unwrap();
+
+    // WGSL always gives `vec3` arrays a stride of 16, so squeeze the array
+    // down to a stride of 12 the way other frontends might.
+    let (tight, array) = {
+        let (tight, ty) = module
+            .types
+            .iter()
+            .find(|&(_, ty)| ty.name.as_deref() == Some("Tight"))
+            .unwrap();
+        let naga::TypeInner::Struct { ref members, .. } = ty.inner else {
+            unreachable!();
+        };
+        (tight, members[0].ty)
+    };
+    let naga::TypeInner::Array { base, size, .. } = module.types[array].inner else {
+        unreachable!();
+    };
+    module.types.replace(
+        array,
+        naga::Type {
+            name: None,
+            inner: naga::TypeInner::Array {
+                base,
+                size,
+                stride: 12,
+            },
+        },
+    );
+    let mut ty = module.types[tight].clone();
+    if let naga::TypeInner::Struct {
+        ref mut members,
+        ref mut span,
+    } = ty.inner
+    {
+        members[1].offset = 48;
+        *span = 52;
+    }
+    module.types.replace(tight, ty);
+
+    let (source, words) = write(
+        &module,
+        valid::ValidationFlags::all() - valid::ValidationFlags::STRUCT_LAYOUTS,
+    );
+    assert!(
+        source.contains("metal::packed_float3 inner[4];"),
+        "{source}"
+    );
+
+    let members = &msl_structs(&source)["Tight"];
+    let (offsets, strides) = spv_layout(words, "Tight");
+    let msl_offsets: Vec<_> = members.iter().map(|&(_, offset, _)| offset).collect();
+    assert_eq!(msl_offsets, offsets, "{source}");
+    assert_eq!(offsets, [0, 48]);
+    assert_eq!(strides, [12]);
+    assert_eq!(members[0].2, 4 * strides[0]);
+}
@@ -0,0 +1,64 @@
+#version 310 es
+
+precision highp float;
+precision highp int;
+
+layout(local_size_x = 1, local_size_y = 1, local_size_z = 1) in;
+
+shared uint counter;
+
+
+float takes_f32_(float x) {
+    return x;
+}
+
+uint takes_u32_vec(uvec2 x_1, uint y) {
+    return (x_1.x + y);
+}
+
+float return_f32_() {
+    return 1.0;
+}
+
+uvec2 return_u32_vec() {
+    return uvec2(1u, 2u);
+}
+
+float[2] return_f32_array() {
+    return float[2](1.0, 2.5);
+}
+
+void main() {
+    if (gl_LocalInvocationID == uvec3(0u)) {
+        counter = 0u;
+    }
+    memoryBarrierShared();
+    barrier();
+    float h = 0.0;
+    uvec2 i = uvec2(0u);
+    float j[2] = float[2](0.0, 0.0);
+    vec3 c = vec3(1.0, 2.5, 3.0);
+    uint d[3] = uint[3](1u, 2u, 3u);
+    float f[3] = float[3](1.0, 2.0, 3.0);
+    vec2 g = vec2(1.5, 2.5);
+    h = 2.0;
+    float _e21 = h;
+    h = (_e21 + 1.0);
+    float _e24 = h;
+    h = (_e24 * 2.0);
+    i = uvec2(1u, 2u);
+    i.x = 3u;
+    i.y = 4u;
+    uvec2 _e36 = i;
+    i = (_e36 << uvec2(1u));
+    j = float[2](1.0, 2.0);
+    float _e43 = takes_f32_(1.0);
+    uint _e48 = takes_u32_vec(uvec2(1u, 2u), 3u);
+    uint p = bitfieldExtract(5u, int(0u), int(8u));
+    float q = (true ? 2.0 : 1.0);
+    float r = dot(vec2(1.0, 2.0), vec2(3.0, 4.0));
+    uint _e69 = atomicAdd(counter, 1u);
+    atomicExchange(counter, 2u);
+    return;
+}
+
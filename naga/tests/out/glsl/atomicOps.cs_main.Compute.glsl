@@ -31,24 +31,24 @@ void main() {
     memoryBarrierShared();
     barrier();
     uvec3 id = gl_LocalInvocationID;
-    _group_0_binding_0_cs = 1u;
-    _group_0_binding_1_cs[1] = 1;
-    _group_0_binding_2_cs.atomic_scalar = 1u;
-    _group_0_binding_2_cs.atomic_arr[1] = 1;
-    workgroup_atomic_scalar = 1u;
-    workgroup_atomic_arr[1] = 1;
-    workgroup_struct.atomic_scalar = 1u;
-    workgroup_struct.atomic_arr[1] = 1;
+    atomicExchange(_group_0_binding_0_cs, 1u);
+    atomicExchange(_group_0_binding_1_cs[1], 1);
+    atomicExchange(_group_0_binding_2_cs.atomic_scalar, 1u);
+    atomicExchange(_group_0_binding_2_cs.atomic_arr[1], 1);
+    atomicExchange(workgroup_atomic_scalar, 1u);
+    atomicExchange(workgroup_atomic_arr[1], 1);
+    atomicExchange(workgroup_struct.atomic_scalar, 1u);
+    atomicExchange(workgroup_struct.atomic_arr[1], 1);
     memoryBarrierShared();
     barrier();
-    uint l0_ = _group_0_binding_0_cs;
-    int l1_ = _group_0_binding_1_cs[1];
-    uint l2_ = _group_0_binding_2_cs.atomic_scalar;
-    int l3_ = _group_0_binding_2_cs.atomic_arr[1];
-    uint l4_ = workgroup_atomic_scalar;
-    int l5_ = workgroup_atomic_arr[1];
-    uint l6_ = workgroup_struct.atomic_scalar;
-    int l7_ = workgroup_struct.atomic_arr[1];
+    uint l0_ = atomicOr(_group_0_binding_0_cs, 0u);
+    int l1_ = atomicOr(_group_0_binding_1_cs[1], 0);
+    uint l2_ = atomicOr(_group_0_binding_2_cs.atomic_scalar, 0u);
+    int l3_ = atomicOr(_group_0_binding_2_cs.atomic_arr[1], 0);
+    uint l4_ = atomicOr(workgroup_atomic_scalar, 0u);
+    int l5_ = atomicOr(workgroup_atomic_arr[1], 0);
+    uint l6_ = atomicOr(workgroup_struct.atomic_scalar, 0u);
+    int l7_ = atomicOr(workgroup_struct.atomic_arr[1], 0);
     memoryBarrierShared();
     barrier();
     uint _e51 = atomicAdd(_group_0_binding_0_cs, 1u);
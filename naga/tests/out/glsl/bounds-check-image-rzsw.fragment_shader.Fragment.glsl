@@ -10,6 +10,8 @@ uniform sampler3D _group_0_binding_3_fs;
 
 uniform sampler2DMS _group_0_binding_4_fs;
 
+uniform sampler2DMS _group_0_binding_7_fs;
+
 layout(rgba8) writeonly uniform image1D _group_0_binding_8_fs;
 
 layout(rgba8) writeonly uniform image2D _group_0_binding_9_fs;
@@ -46,32 +48,47 @@ vec4 test_textureLoad_3d(ivec3 coords_4, int level_4) {
 }
 
 vec4 test_textureLoad_multisampled_2d(ivec2 coords_5, int _sample) {
-    vec4 _e3 = (_sample < textureSamples(_group_0_binding_4_fs) && all(lessThan(coords_5, textureSize(_group_0_binding_4_fs))) ? texelFetch(_group_0_binding_4_fs, coords_5, _sample) : vec4(0.0));
+    vec4 _e3 = (uint(_sample) < uint(textureSamples(_group_0_binding_4_fs)) && all(lessThan(coords_5, textureSize(_group_0_binding_4_fs))) ? texelFetch(_group_0_binding_4_fs, coords_5, _sample) : vec4(0.0));
+    return _e3;
+}
+
+vec4 test_textureLoad_multisampled_2d_u(ivec2 coords_6, uint _sample_1) {
+    vec4 _e3 = (uint(_sample_1) < uint(textureSamples(_group_0_binding_4_fs)) && all(lessThan(coords_6, textureSize(_group_0_binding_4_fs))) ? texelFetch(_group_0_binding_4_fs, coords_6, int(_sample_1)) : vec4(0.0));
+    return _e3;
+}
+
+float test_textureLoad_depth_multisampled_2d(ivec2 coords_10, int _sample_2) {
+    float _e3 = (uint(_sample_2) < uint(textureSamples(_group_0_binding_7_fs)) && all(lessThan(coords_10, textureSize(_group_0_binding_7_fs))) ? texelFetch(_group_0_binding_7_fs, coords_10, _sample_2).x : 0.0);
+    return _e3;
+}
+
+float test_textureLoad_depth_multisampled_2d_u(ivec2 coords_11, uint _sample_3) {
+    float _e3 = (uint(_sample_3) < uint(textureSamples(_group_0_binding_7_fs)) && all(lessThan(coords_11, textureSize(_group_0_binding_7_fs))) ? texelFetch(_group_0_binding_7_fs, coords_11, int(_sample_3)).x : 0.0);
     return _e3;
 }
 
-void test_textureStore_1d(int coords_10, vec4 value) {
-    imageStore(_group_0_binding_8_fs, coords_10, value);
+void test_textureStore_1d(int coords_12, vec4 value) {
+    imageStore(_group_0_binding_8_fs, coords_12, value);
     return;
 }
 
-void test_textureStore_2d(ivec2 coords_11, vec4 value_1) {
-    imageStore(_group_0_binding_9_fs, coords_11, value_1);
+void test_textureStore_2d(ivec2 coords_13, vec4 value_1) {
+    imageStore(_group_0_binding_9_fs, coords_13, value_1);
     return;
 }
 
-void test_textureStore_2d_array_u(ivec2 coords_12, uint array_index, vec4 value_2) {
-    imageStore(_group_0_binding_10_fs, ivec3(coords_12, array_index), value_2);
+void test_textureStore_2d_array_u(ivec2 coords_14, uint array_index, vec4 value_2) {
+    imageStore(_group_0_binding_10_fs, ivec3(coords_14, array_index), value_2);
     return;
 }
 
-void test_textureStore_2d_array_s(ivec2 coords_13, int array_index_1, vec4 value_3) {
-    imageStore(_group_0_binding_10_fs, ivec3(coords_13, array_index_1), value_3);
+void test_textureStore_2d_array_s(ivec2 coords_15, int array_index_1, vec4 value_3) {
+    imageStore(_group_0_binding_10_fs, ivec3(coords_15, array_index_1), value_3);
     return;
 }
 
-void test_textureStore_3d(ivec3 coords_14, vec4 value_4) {
-    imageStore(_group_0_binding_11_fs, coords_14, value_4);
+void test_textureStore_3d(ivec3 coords_16, vec4 value_4) {
+    imageStore(_group_0_binding_11_fs, coords_16, value_4);
     return;
 }
 
@@ -82,6 +99,9 @@ void main() {
     vec4 _e13 = test_textureLoad_2d_array_s(ivec2(0), 0, 0);
     vec4 _e16 = test_textureLoad_3d(ivec3(0), 0);
     vec4 _e19 = test_textureLoad_multisampled_2d(ivec2(0), 0);
+    vec4 _e22 = test_textureLoad_multisampled_2d_u(ivec2(0), 0u);
+    float _e25 = test_textureLoad_depth_multisampled_2d(ivec2(0), 0);
+    float _e28 = test_textureLoad_depth_multisampled_2d_u(ivec2(0), 0u);
     test_textureStore_1d(0, vec4(0.0));
     test_textureStore_2d(ivec2(0), vec4(0.0));
     test_textureStore_2d_array_u(ivec2(0), 0u, vec4(0.0));
@@ -7,24 +7,17 @@ layout(local_size_x = 1, local_size_y = 1, local_size_z = 1) in;
 
 
 void breakIfEmpty() {
-    bool loop_init = true;
-    while(true) {
-        if (!loop_init) {
-            if (true) {
-                break;
-            }
-        }
-        loop_init = false;
-    }
+    do {
+    } while(!(true));
     return;
 }
 
 void breakIfEmptyBody(bool a) {
     bool b = false;
     bool c = false;
-    bool loop_init_1 = true;
+    bool loop_init = true;
     while(true) {
-        if (!loop_init_1) {
+        if (!loop_init) {
             b = a;
             bool _e2 = b;
             c = (a != _e2);
@@ -33,7 +26,7 @@ void breakIfEmptyBody(bool a) {
                 break;
             }
         }
-        loop_init_1 = false;
+        loop_init = false;
     }
     return;
 }
@@ -41,36 +34,20 @@ void breakIfEmptyBody(bool a) {
 void breakIf(bool a_1) {
     bool d = false;
     bool e = false;
-    bool loop_init_2 = true;
-    while(true) {
-        if (!loop_init_2) {
-            bool _e5 = e;
-            if ((a_1 == _e5)) {
-                break;
-            }
-        }
-        loop_init_2 = false;
+    do {
         d = a_1;
         bool _e2 = d;
         e = (a_1 != _e2);
-    }
+    } while(!((a_1 == e)));
     return;
 }
 
 void breakIfSeparateVariable() {
     uint counter = 0u;
-    bool loop_init_3 = true;
-    while(true) {
-        if (!loop_init_3) {
-            uint _e5 = counter;
-            if ((_e5 == 5u)) {
-                break;
-            }
-        }
-        loop_init_3 = false;
+    do {
         uint _e3 = counter;
         counter = (_e3 + 1u);
-    }
+    } while(!((counter == 5u)));
     return;
 }
 
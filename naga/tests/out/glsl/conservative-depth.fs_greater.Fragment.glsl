@@ -0,0 +1,15 @@
+#version 310 es
+#extension GL_EXT_conservative_depth : require
+
+precision highp float;
+precision highp int;
+
+layout(depth_greater) out float gl_FragDepth;
+
+
+void main() {
+    vec4 position = gl_FragCoord;
+    gl_FragDepth = (position.z + 0.1);
+    return;
+}
+
@@ -0,0 +1,13 @@
+#version 310 es
+
+precision highp float;
+precision highp int;
+
+layout(location = 0) out vec4 _fs2p_location0;
+
+void main() {
+    vec4 position_3 = gl_FragCoord;
+    _fs2p_location0 = position_3;
+    return;
+}
+
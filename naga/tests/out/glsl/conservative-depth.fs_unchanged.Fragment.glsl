@@ -0,0 +1,15 @@
+#version 310 es
+#extension GL_EXT_conservative_depth : require
+
+precision highp float;
+precision highp int;
+
+layout(depth_unchanged) out float gl_FragDepth;
+
+
+void main() {
+    vec4 position_2 = gl_FragCoord;
+    gl_FragDepth = position_2.z;
+    return;
+}
+
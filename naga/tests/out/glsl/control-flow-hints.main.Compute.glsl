@@ -0,0 +1,101 @@
+#version 310 es
+
+precision highp float;
+precision highp int;
+
+layout(local_size_x = 1, local_size_y = 1, local_size_z = 1) in;
+
+layout(std430) buffer type_1_block_0Compute { float _group_0_binding_0_cs[16]; };
+
+
+void main() {
+    uint index = gl_LocalInvocationIndex;
+    float sum = 0.0;
+    uint i = 0u;
+    uint i_1 = 0u;
+    uint j = 0u;
+    bool loop_init = true;
+    while(true) {
+        if (!loop_init) {
+            uint _e15 = i;
+            i = (_e15 + 1u);
+        }
+        loop_init = false;
+        uint _e5 = i;
+        if ((_e5 < 4u)) {
+        } else {
+            break;
+        }
+        {
+            uint _e9 = i;
+            float _e11 = _group_0_binding_0_cs[_e9];
+            float _e12 = sum;
+            sum = (_e12 + _e11);
+        }
+    }
+    bool loop_init_1 = true;
+    while(true) {
+        if (!loop_init_1) {
+            uint _e28 = i_1;
+            i_1 = (_e28 + 1u);
+        }
+        loop_init_1 = false;
+        uint _e19 = i_1;
+        if ((_e19 < index)) {
+        } else {
+            break;
+        }
+        {
+            uint _e22 = i_1;
+            float _e24 = _group_0_binding_0_cs[_e22];
+            float _e25 = sum;
+            sum = (_e25 + _e24);
+        }
+    }
+    while((j < 4u)) {
+        {
+            uint _e36 = j;
+            float _e38 = _group_0_binding_0_cs[_e36];
+            float _e39 = sum;
+            sum = (_e39 * _e38);
+            uint _e42 = j;
+            j = (_e42 + 1u);
+        }
+    }
+    do {
+        float _e44 = sum;
+        if ((_e44 > 100.0)) {
+            break;
+        }
+        float _e48 = sum;
+        sum = (_e48 * 2.0);
+    } while(!((sum == 0.0)));
+    if ((index == 0u)) {
+        float _e57 = _group_0_binding_0_cs[15];
+        sum = _e57;
+    } else {
+        if ((index == 1u)) {
+            float _e62 = _group_0_binding_0_cs[14];
+            sum = _e62;
+        }
+    }
+    float _e63 = sum;
+    if ((_e63 < 0.0)) {
+        float _e66 = sum;
+        sum = -(_e66);
+    } else {
+        float _e68 = sum;
+        sum = (_e68 * 0.5);
+    }
+    float _e71 = sum;
+    if ((_e71 > 1.0)) {
+        float _e74 = sum;
+        if ((_e74 > 2.0)) {
+            sum = 2.0;
+        }
+    }
+    float _e80 = sum;
+    _group_0_binding_0_cs[index] = _e80;
+    return;
+}
+
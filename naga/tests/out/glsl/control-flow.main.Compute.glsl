@@ -40,6 +40,92 @@ void loop_switch_continue(int x) {
     return;
 }
 
+int loop_switch_break(int x_1) {
+    int i_1 = 0;
+    while(true) {
+        switch(x_1) {
+            case 1: {
+                break;
+            }
+            default: {
+                break;
+            }
+        }
+        int _e4 = i_1;
+        i_1 = (_e4 + 1);
+        int _e6 = i_1;
+        if ((_e6 > 4)) {
+            break;
+        }
+    }
+    int _e9 = i_1;
+    return _e9;
+}
+
+void loop_switch_continue_if(int x_2) {
+    int i_2 = 0;
+    bool loop_init = true;
+    while(true) {
+        if (!loop_init) {
+            int _e7 = i_2;
+            i_2 = (_e7 + 1);
+            int _e9 = i_2;
+            if ((_e9 > 4)) {
+                break;
+            }
+        }
+        loop_init = false;
+        switch(x_2) {
+            case 1: {
+                int _e3 = i_2;
+                if ((_e3 == 2)) {
+                    continue;
+                }
+                break;
+            }
+            default: {
+                break;
+            }
+        }
+    }
+    return;
+}
+
+void switch_loop_break(int x_3) {
+    switch(x_3) {
+        case 1: {
+            while(true) {
+                break;
+            }
+            return;
+        }
+        default: {
+            return;
+        }
+    }
+}
+
+void switch_loop_continue(int x_4) {
+    int i_3 = 0;
+    switch(x_4) {
+        case 1: {
+            while(true) {
+                int _e4 = i_3;
+                i_3 = (_e4 + 1);
+                int _e6 = i_3;
+                if ((_e6 < 4)) {
+                    continue;
+                }
+                break;
+            }
+            return;
+        }
+        default: {
+            return;
+        }
+    }
+}
+
 void main() {
     uvec3 global_id = gl_GlobalInvocationID;
     int pos = 0;
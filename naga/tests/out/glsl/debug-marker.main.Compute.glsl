@@ -0,0 +1,40 @@
+#version 310 es
+
+precision highp float;
+precision highp int;
+
+layout(local_size_x = 1, local_size_y = 1, local_size_z = 1) in;
+
+layout(std430) buffer type_1_block_0Compute { uint _group_0_binding_0_cs[]; };
+
+
+void main() {
+    uint sum = 0u;
+    // setup
+    // accumulate
+    bool loop_init = true;
+    while(true) {
+        if (!loop_init) {
+            // continuing
+            uint _e12 = sum;
+            sum = (_e12 + 1u);
+        }
+        loop_init = false;
+        // loop body
+        uint _e2 = sum;
+        if ((_e2 > 10u)) {
+            break;
+        }
+        uint _e6 = sum;
+        uint _e8 = _group_0_binding_0_cs[_e6];
+        uint _e9 = sum;
+        sum = (_e9 + _e8);
+    }
+    // path C:\temp
+    uint _e16 = sum;
+    _group_0_binding_0_cs[0] = _e16;
+    //
+    // done
+    return;
+}
+
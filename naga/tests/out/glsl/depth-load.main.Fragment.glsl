@@ -0,0 +1,23 @@
+#version 310 es
+
+precision highp float;
+precision highp int;
+
+uniform highp sampler2D _group_0_binding_0_fs;
+
+uniform highp sampler2DArray _group_0_binding_1_fs;
+
+uniform highp sampler2DMS _group_0_binding_2_fs;
+
+layout(location = 0) out vec4 _fs2p_location0;
+
+void main() {
+    vec4 position = gl_FragCoord;
+    ivec2 coords = ivec2(position.xy);
+    float a = texelFetch(_group_0_binding_0_fs, coords, 0).x;
+    float b = texelFetch(_group_0_binding_1_fs, ivec3(coords, 1), 0).x;
+    float c = texelFetch(_group_0_binding_2_fs, coords, 2).x;
+    _fs2p_location0 = vec4(a, b, c, 1.0);
+    return;
+}
+
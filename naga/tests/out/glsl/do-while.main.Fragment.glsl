@@ -5,17 +5,9 @@ precision highp int;
 
 
 void fb1_(inout bool cond) {
-    bool loop_init = true;
-    while(true) {
-        if (!loop_init) {
-            bool _e1 = cond;
-            if (!(_e1)) {
-                break;
-            }
-        }
-        loop_init = false;
+    do {
         continue;
-    }
+    } while(cond);
     return;
 }
 
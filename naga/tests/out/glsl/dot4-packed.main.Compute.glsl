@@ -0,0 +1,20 @@
+#version 310 es
+
+precision highp float;
+precision highp int;
+
+layout(local_size_x = 1, local_size_y = 1, local_size_z = 1) in;
+
+layout(std430) buffer type_1_block_0Compute { uint _group_0_binding_0_cs[5]; };
+
+
+void main() {
+    uint a = _group_0_binding_0_cs[0];
+    uint b = _group_0_binding_0_cs[1];
+    _group_0_binding_0_cs[2] = uint(((int(a) << 24 >> 24) * (int(b) << 24 >> 24) + (int(a) << 16 >> 24) * (int(b) << 16 >> 24) + (int(a) << 8 >> 24) * (int(b) << 8 >> 24) + (int(a) >> 24) * (int(b) >> 24)));
+    uint _e13 = (a + 1u);
+    _group_0_binding_0_cs[3] = ((_e13 & 0xFFu) * (b & 0xFFu) + (_e13 >> 8u & 0xFFu) * (b >> 8u & 0xFFu) + (_e13 >> 16u & 0xFFu) * (b >> 16u & 0xFFu) + (_e13 >> 24u) * (b >> 24u));
+    _group_0_binding_0_cs[4] = (uint(512) + 2550u);
+    return;
+}
+
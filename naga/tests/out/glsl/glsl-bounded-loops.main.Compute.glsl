@@ -0,0 +1,98 @@
+#version 430 core
+#extension GL_ARB_compute_shader : require
+layout(local_size_x = 1, local_size_y = 1, local_size_z = 1) in;
+
+
+uint loop_break_if(uint n) {
+    uint i = 0u;
+    bool loop_init = true;
+    for (uint naga_i = 0u; naga_i < 1024u; ++naga_i) {
+        if (!loop_init) {
+            uint _e6 = i;
+            if ((_e6 >= n)) {
+                break;
+            }
+        }
+        loop_init = false;
+        uint _e4 = i;
+        i = (_e4 + 1u);
+    }
+    uint _e8 = i;
+    return _e8;
+}
+
+uint while_loop(uint n_1) {
+    uint sum = 0u;
+    uint i_1 = 0u;
+    for (uint naga_i_1 = 0u; naga_i_1 < 1024u; ++naga_i_1) {
+        uint _e5 = i_1;
+        if ((_e5 < n_1)) {
+        } else {
+            break;
+        }
+        {
+            uint _e7 = i_1;
+            uint _e8 = sum;
+            sum = (_e8 + _e7);
+            uint _e11 = i_1;
+            i_1 = (_e11 + 1u);
+        }
+    }
+    uint _e13 = sum;
+    return _e13;
+}
+
+uint for_loop_continue(uint n_2) {
+    uint sum_1 = 0u;
+    uint i_2 = 0u;
+    uint j = 0u;
+    bool loop_init_1 = true;
+    for (uint naga_i_2 = 0u; naga_i_2 < 1024u; ++naga_i_2) {
+        if (!loop_init_1) {
+            uint _e24 = i_2;
+            i_2 = (_e24 + 1u);
+        }
+        loop_init_1 = false;
+        uint _e5 = i_2;
+        if ((_e5 < n_2)) {
+        } else {
+            break;
+        }
+        {
+            uint _e7 = i_2;
+            if (((_e7 % 2u) == 0u)) {
+                continue;
+            }
+            j = 0u;
+            bool loop_init_2 = true;
+            for (uint naga_i_3 = 0u; naga_i_3 < 1024u; ++naga_i_3) {
+                if (!loop_init_2) {
+                    uint _e21 = j;
+                    j = (_e21 + 1u);
+                }
+                loop_init_2 = false;
+                uint _e14 = j;
+                uint _e15 = i_2;
+                if ((_e14 < _e15)) {
+                } else {
+                    break;
+                }
+                {
+                    uint _e17 = j;
+                    uint _e18 = sum_1;
+                    sum_1 = (_e18 + _e17);
+                }
+            }
+        }
+    }
+    uint _e26 = sum_1;
+    return _e26;
+}
+
+void main() {
+    uint _e1 = loop_break_if(4u);
+    uint _e3 = while_loop(4u);
+    uint _e5 = for_loop_continue(4u);
+    return;
+}
+
@@ -0,0 +1,108 @@
+#version 310 es
+
+precision highp float;
+precision highp int;
+
+layout(local_size_x = 1, local_size_y = 1, local_size_z = 1) in;
+
+uint calls = 0u;
+
+
+bool next(inout uint i) {
+    uint _e3 = calls;
+    calls = (_e3 + 1u);
+    uint _e6 = i;
+    i = (_e6 + 1u);
+    uint _e8 = i;
+    return (_e8 < 8u);
+}
+
+uint while_loop(uint n) {
+    uint sum = 0u;
+    uint i_1 = 0u;
+    while((i_1 < n)) {
+        {
+            uint _e7 = i_1;
+            uint _e8 = sum;
+            sum = (_e8 + _e7);
+            uint _e11 = i_1;
+            i_1 = (_e11 + 1u);
+        }
+    }
+    uint _e13 = sum;
+    return _e13;
+}
+
+uint until_loop(uint n_1) {
+    uint i_2 = 0u;
+    while(!((i_2 >= n_1))) {
+        uint _e6 = i_2;
+        i_2 = (_e6 + 2u);
+    }
+    uint _e8 = i_2;
+    return _e8;
+}
+
+uint do_while(uint n_2) {
+    uint i_3 = 0u;
+    do {
+        uint _e4 = i_3;
+        i_3 = (_e4 + 1u);
+        uint _e6 = i_3;
+        if ((_e6 == 3u)) {
+            continue;
+        }
+    } while(!((i_3 >= n_2)));
+    uint _e11 = i_3;
+    return _e11;
+}
+
+uint side_effects() {
+    uint i_4 = 0u;
+    while(true) {
+        bool _e2 = next(i_4);
+        if (_e2) {
+        } else {
+            break;
+        }
+        {
+        }
+    }
+    uint _e3 = i_4;
+    return _e3;
+}
+
+uint for_loop(uint n_3) {
+    uint sum_1 = 0u;
+    uint i_5 = 0u;
+    bool loop_init = true;
+    while(true) {
+        if (!loop_init) {
+            uint _e11 = i_5;
+            i_5 = (_e11 + 1u);
+        }
+        loop_init = false;
+        uint _e5 = i_5;
+        if ((_e5 < n_3)) {
+        } else {
+            break;
+        }
+        {
+            uint _e7 = i_5;
+            uint _e8 = sum_1;
+            sum_1 = (_e8 + _e7);
+        }
+    }
+    uint _e13 = sum_1;
+    return _e13;
+}
+
+void main() {
+    uint _e1 = while_loop(4u);
+    uint _e3 = until_loop(4u);
+    uint _e5 = do_while(4u);
+    uint _e6 = side_effects();
+    uint _e8 = for_loop(4u);
+    return;
+}
+
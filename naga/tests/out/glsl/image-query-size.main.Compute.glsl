@@ -0,0 +1,28 @@
+#version 310 es
+
+precision highp float;
+precision highp int;
+
+layout(local_size_x = 1, local_size_y = 1, local_size_z = 1) in;
+
+layout(rgba8) writeonly uniform highp image2DArray _group_0_binding_0_cs;
+
+uniform highp samplerCube _group_0_binding_1_cs;
+
+layout(std430) buffer type_3_block_0Compute { uint _group_0_binding_2_cs[6]; };
+
+
+void main() {
+    uvec2 storage_size = uvec2(imageSize(_group_0_binding_0_cs).xy);
+    uint storage_layers = uint(imageSize(_group_0_binding_0_cs).z);
+    uvec2 cube_size = uvec2(textureSize(_group_0_binding_1_cs, 0).xy);
+    uvec2 cube_size_lod = uvec2(textureSize(_group_0_binding_1_cs, 1).xy);
+    _group_0_binding_2_cs[0] = storage_size.x;
+    _group_0_binding_2_cs[1] = storage_size.y;
+    _group_0_binding_2_cs[2] = storage_layers;
+    _group_0_binding_2_cs[3] = cube_size.x;
+    _group_0_binding_2_cs[4] = cube_size.y;
+    _group_0_binding_2_cs[5] = cube_size_lod.x;
+    return;
+}
+
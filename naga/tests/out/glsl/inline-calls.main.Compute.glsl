@@ -0,0 +1,216 @@
+#version 310 es
+
+precision highp float;
+precision highp int;
+
+layout(local_size_x = 64, local_size_y = 1, local_size_z = 1) in;
+
+struct Particle {
+    vec2 position;
+    vec2 velocity;
+};
+layout(std430) buffer type_1_block_0Compute { Particle _group_0_binding_0_cs[]; };
+
+
+float square(float x) {
+    return (x * x);
+}
+
+float length_squared(vec2 v) {
+    float square_result_3 = 0.0;
+    float square_result_4 = 0.0;
+    {
+        {
+            square_result_3 = (v.x * v.x);
+        }
+    }
+    float _e4 = square_result_3;
+    {
+        {
+            square_result_4 = (v.y * v.y);
+        }
+    }
+    float _e8 = square_result_4;
+    return (_e4 + _e8);
+}
+
+int first_negative(vec4 v_1) {
+    int i_2 = 0;
+    bool loop_init = true;
+    while(true) {
+        if (!loop_init) {
+            int _e12 = i_2;
+            i_2 = (_e12 + 1);
+        }
+        loop_init = false;
+        int _e3 = i_2;
+        if ((_e3 < 4)) {
+        } else {
+            break;
+        }
+        {
+            int _e6 = i_2;
+            if ((v_1[_e6] < 0.0)) {
+                int _e10 = i_2;
+                return _e10;
+            }
+        }
+    }
+    return -1;
+}
+
+void step_(inout Particle p, float dt) {
+    float square_result_5 = 0.0;
+    float square_result_6 = 0.0;
+    float length_squared_result_1 = 0.0;
+    vec2 _e4 = p.velocity;
+    vec2 _e6 = p.position;
+    p.position = (_e6 + (_e4 * dt));
+    vec2 _e9 = p.velocity;
+    {
+        {
+            {
+                {
+                    square_result_5 = (_e9.x * _e9.x);
+                }
+            }
+            float _e13 = square_result_5;
+            {
+                {
+                    square_result_6 = (_e9.y * _e9.y);
+                }
+            }
+            float _e17 = square_result_6;
+            length_squared_result_1 = (_e13 + _e17);
+        }
+    }
+    float _e20 = length_squared_result_1;
+    if ((_e20 > 1.0)) {
+        vec2 _e25 = p.velocity;
+        p.velocity = normalize(_e25);
+        return;
+    } else {
+        return;
+    }
+}
+
+void main() {
+    uvec3 id = gl_GlobalInvocationID;
+    float total = 0.0;
+    uint i = 0u;
+    Particle particle = Particle(vec2(0.0), vec2(0.0));
+    int i_1 = 0;
+    int first_negative_result = 0;
+    bool returned = false;
+    float square_result = 0.0;
+    float square_result_1 = 0.0;
+    float square_result_2 = 0.0;
+    float length_squared_result = 0.0;
+    bool loop_init_1 = true;
+    while(true) {
+        if (!loop_init_1) {
+            uint _e56 = i;
+            i = (_e56 + 1u);
+        }
+        loop_init_1 = false;
+        uint _e5 = i;
+        if ((_e5 < 2u)) {
+        } else {
+            break;
+        }
+        {
+            uint _e10 = i;
+            Particle _e13 = _group_0_binding_0_cs[(id.x + _e10)];
+            particle = _e13;
+            {
+                square_result_1 = 0.0;
+                square_result_2 = 0.0;
+                length_squared_result = 0.0;
+                while(true) {
+                    vec2 _e70 = particle.velocity;
+                    vec2 _e72 = particle.position;
+                    particle.position = (_e72 + (_e70 * 0.5));
+                    vec2 _e75 = particle.velocity;
+                    {
+                        {
+                            {
+                                {
+                                    square_result_1 = (_e75.x * _e75.x);
+                                }
+                            }
+                            float _e79 = square_result_1;
+                            {
+                                {
+                                    square_result_2 = (_e75.y * _e75.y);
+                                }
+                            }
+                            float _e83 = square_result_2;
+                            length_squared_result = (_e79 + _e83);
+                        }
+                    }
+                    float _e86 = length_squared_result;
+                    if ((_e86 > 1.0)) {
+                        vec2 _e91 = particle.velocity;
+                        particle.velocity = normalize(_e91);
+                        break;
+                    } else {
+                        break;
+                    }
+                    break;
+                }
+            }
+            uint _e18 = i;
+            Particle _e21 = particle;
+            _group_0_binding_0_cs[(id.x + _e18)] = _e21;
+            vec2 _e26 = _group_0_binding_0_cs[id.x].position;
+            {
+                i_1 = 0;
+                returned = false;
+                while(true) {
+                    bool loop_init_2 = true;
+                    while(true) {
+                        if (!loop_init_2) {
+                            int _e41 = i_1;
+                            i_1 = (_e41 + 1);
+                        }
+                        loop_init_2 = false;
+                        int _e32 = i_1;
+                        if ((_e32 < 4)) {
+                        } else {
+                            break;
+                        }
+                        {
+                            int _e35 = i_1;
+                            if ((vec4(_e26, -1.0, 2.0)[_e35] < 0.0)) {
+                                int _e39 = i_1;
+                                first_negative_result = _e39;
+                                returned = true;
+                                break;
+                            }
+                        }
+                    }
+                    bool _e50 = returned;
+                    if (_e50) {
+                        break;
+                    }
+                    first_negative_result = -1;
+                    break;
+                }
+            }
+            int _e51 = first_negative_result;
+            float _e53 = total;
+            total = (_e53 + float(_e51));
+        }
+    }
+    float _e62 = total;
+    float _e63 = total;
+    {
+        {
+            square_result = (_e63 * _e63);
+        }
+    }
+    float _e66 = square_result;
+    _group_0_binding_0_cs[id.x].velocity = vec2(_e62, _e66);
+    return;
+}
+
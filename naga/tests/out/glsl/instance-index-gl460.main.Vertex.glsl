@@ -0,0 +1,11 @@
+#version 460 core
+layout(location = 0) in vec2 _p2vs_location0;
+
+void main() {
+    uint instance = (uint(gl_InstanceID) + uint(gl_BaseInstance));
+    vec2 position = _p2vs_location0;
+    float offset = (float(instance) * 0.1);
+    gl_Position = vec4((position + vec2(offset, 0.0)), 0.0, 1.0);
+    return;
+}
+
@@ -0,0 +1,18 @@
+#version 310 es
+
+precision highp float;
+precision highp int;
+
+uniform uint naga_vs_first_instance;
+
+layout(location = 0) in vec2 _p2vs_location0;
+
+void main() {
+    uint instance = (uint(gl_InstanceID) + naga_vs_first_instance);
+    vec2 position = _p2vs_location0;
+    float offset = (float(instance) * 0.1);
+    gl_Position = vec4((position + vec2(offset, 0.0)), 0.0, 1.0);
+    gl_Position.yz = vec2(-gl_Position.y, gl_Position.z * 2.0 - gl_Position.w);
+    return;
+}
+
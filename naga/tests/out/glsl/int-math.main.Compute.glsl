@@ -0,0 +1,116 @@
+#version 310 es
+
+precision highp float;
+precision highp int;
+
+layout(local_size_x = 1, local_size_y = 1, local_size_z = 1) in;
+
+
+void main() {
+    int i = 0;
+    ivec3 i3_ = ivec3(0);
+    uint u = 0u;
+    uvec3 u3_ = uvec3(0u);
+    int _e10 = i;
+    i = abs(_e10);
+    ivec3 _e12 = i3_;
+    i3_ = abs(_e12);
+    uint _e14 = u;
+    u = uint(abs(int(_e14)));
+    uvec3 _e16 = u3_;
+    u3_ = uvec3(abs(ivec3(_e16)));
+    int _e18 = i;
+    i = sign(_e18);
+    ivec3 _e20 = i3_;
+    i3_ = sign(_e20);
+    int _e22 = i;
+    int _e23 = i;
+    i = min(_e22, _e23);
+    ivec3 _e25 = i3_;
+    ivec3 _e26 = i3_;
+    i3_ = min(_e25, _e26);
+    uint _e28 = u;
+    uint _e29 = u;
+    u = min(_e28, _e29);
+    uvec3 _e31 = u3_;
+    uvec3 _e32 = u3_;
+    u3_ = min(_e31, _e32);
+    int _e34 = i;
+    int _e35 = i;
+    i = max(_e34, _e35);
+    ivec3 _e37 = i3_;
+    ivec3 _e38 = i3_;
+    i3_ = max(_e37, _e38);
+    uint _e40 = u;
+    uint _e41 = u;
+    u = max(_e40, _e41);
+    uvec3 _e43 = u3_;
+    uvec3 _e44 = u3_;
+    u3_ = max(_e43, _e44);
+    int _e46 = i;
+    int _e47 = i;
+    int _e48 = i;
+    i = clamp(_e46, _e47, _e48);
+    ivec3 _e50 = i3_;
+    ivec3 _e51 = i3_;
+    ivec3 _e52 = i3_;
+    i3_ = clamp(_e50, _e51, _e52);
+    uint _e54 = u;
+    uint _e55 = u;
+    uint _e56 = u;
+    u = clamp(_e54, _e55, _e56);
+    uvec3 _e58 = u3_;
+    uvec3 _e59 = u3_;
+    uvec3 _e60 = u3_;
+    u3_ = clamp(_e58, _e59, _e60);
+    int _e62 = i;
+    i = bitCount(_e62);
+    ivec3 _e64 = i3_;
+    i3_ = bitCount(_e64);
+    uint _e66 = u;
+    u = uint(bitCount(_e66));
+    uvec3 _e68 = u3_;
+    u3_ = uvec3(bitCount(_e68));
+    int _e70 = i;
+    i = bitfieldReverse(_e70);
+    ivec3 _e72 = i3_;
+    i3_ = bitfieldReverse(_e72);
+    uint _e74 = u;
+    u = bitfieldReverse(_e74);
+    uvec3 _e76 = u3_;
+    u3_ = bitfieldReverse(_e76);
+    int _e78 = i;
+    i = findMSB(_e78);
+    ivec3 _e80 = i3_;
+    i3_ = findMSB(_e80);
+    uint _e82 = u;
+    u = uint(findMSB(_e82));
+    uvec3 _e84 = u3_;
+    u3_ = uvec3(findMSB(_e84));
+    int _e86 = i;
+    i = findLSB(_e86);
+    ivec3 _e88 = i3_;
+    i3_ = findLSB(_e88);
+    uint _e90 = u;
+    u = uint(findLSB(_e90));
+    uvec3 _e92 = u3_;
+    u3_ = uvec3(findLSB(_e92));
+    int _e94 = i;
+    i = (_e94 < 0 ? 0 : 31 - findMSB(_e94));
+    ivec3 _e96 = i3_;
+    i3_ = mix(ivec3(31) - findMSB(_e96), ivec3(0), lessThan(_e96, ivec3(0)));
+    uint _e98 = u;
+    u = uint(31 - findMSB(_e98));
+    uvec3 _e100 = u3_;
+    u3_ = uvec3(ivec3(31) - findMSB(_e100));
+    int _e102 = i;
+    i = int(min(uint(findLSB(_e102)), 32u));
+    ivec3 _e104 = i3_;
+    i3_ = ivec3(min(uvec3(findLSB(_e104)), uvec3(32u)));
+    uint _e106 = u;
+    u = min(uint(findLSB(_e106)), 32u);
+    uvec3 _e108 = u3_;
+    u3_ = min(uvec3(findLSB(_e108)), uvec3(32u));
+    return;
+}
+
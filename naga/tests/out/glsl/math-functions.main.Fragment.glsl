@@ -91,5 +91,10 @@ void main() {
     float frexp_b = naga_frexp(1.5).fract_;
     int frexp_c = naga_frexp(1.5).exp_;
     int frexp_d = naga_frexp(vec4(1.5, 1.5, 1.5, 1.5)).exp_.x;
+    vec4 round_a = roundEven(v);
+    vec4 fract_a = fract(v);
+    vec4 trunc_a = trunc(v);
+    vec4 floor_a = floor(v);
+    vec4 ceil_a = ceil(v);
 }
 
@@ -0,0 +1,33 @@
+#version 310 es
+
+precision highp float;
+precision highp int;
+
+layout(local_size_x = 64, local_size_y = 1, local_size_z = 1) in;
+
+struct Queue {
+    uint head;
+    uint items[64];
+};
+layout(std430) coherent buffer Queue_block_0Compute { Queue _group_0_binding_0_cs; };
+
+layout(std430) volatile buffer type_4_block_1Compute { uvec4 _group_0_binding_1_cs[4]; };
+
+layout(std430) coherent volatile buffer type_1_block_2Compute { uint _group_0_binding_2_cs; };
+
+layout(std430) buffer type_1_block_3Compute { uint _group_0_binding_3_cs; };
+
+
+void main() {
+    uint index = gl_LocalInvocationIndex;
+    uint _e4 = atomicAdd(_group_0_binding_0_cs.head, 1u);
+    _group_0_binding_0_cs.items[(_e4 % 64u)] = index;
+    uint _e18 = _group_0_binding_0_cs.items[index];
+    _group_0_binding_1_cs[(index % 4u)].y = _e18;
+    uint _e21 = _group_0_binding_2_cs;
+    uint _e23 = _group_0_binding_3_cs;
+    _group_0_binding_2_cs = (_e21 + _e23);
+    _group_0_binding_3_cs = index;
+    return;
+}
+
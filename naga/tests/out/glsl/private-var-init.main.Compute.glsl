@@ -0,0 +1,47 @@
+#version 310 es
+
+precision highp float;
+precision highp int;
+
+layout(local_size_x = 1, local_size_y = 1, local_size_z = 1) in;
+
+struct S {
+    vec3 position;
+    float weights[2];
+    uint count;
+};
+const int K = 3;
+
+int table[3] = int[3](1, 2, 3);
+
+float nested[2][2] = float[2][2](float[2](1.0, 2.0), float[2](0.0, 0.0));
+
+int grid[2][2] = int[2][2](int[2](1, 2), int[2](3, 4));
+
+vec3 zero_vec = vec3(0.0);
+
+S zero_struct = S(vec3(0.0), float[2](0.0, 0.0), 0u);
+
+S s = S(vec3(1.0), float[2](0.5, 3.0), 4u);
+
+uint counter = 0u;
+
+S origin = S(vec3(0.0), float[2](0.0, 0.0), 0u);
+
+
+void main() {
+    int _e3 = table[2];
+    uint _e7 = s.count;
+    uint _e9 = counter;
+    counter = (_e9 + (uint(_e3) + _e7));
+    vec3 _e14 = zero_vec;
+    vec3 _e17 = zero_struct.position;
+    vec3 _e21 = s.position;
+    origin.position = ((_e14 + _e17) + _e21);
+    float _e27[2] = nested[0];
+    origin.weights = _e27;
+    int _e33 = grid[1][1];
+    origin.count = uint(_e33);
+    return;
+}
+
@@ -0,0 +1,27 @@
+#version 450 core
+#extension GL_ARB_compute_shader : require
+#extension GL_ARB_shader_storage_buffer_object : require
+#extension GL_AMD_gpu_shader_half_float : require
+layout(local_size_x = 1, local_size_y = 1, local_size_z = 1) in;
+
+struct Values {
+    float scalar;
+    vec2 pair;
+    vec3 triple;
+    vec4 quad;
+};
+layout(std430) buffer Values_block_0Compute { Values _group_0_binding_0_cs; };
+
+
+void main() {
+    float _e4 = _group_0_binding_0_cs.scalar;
+    _group_0_binding_0_cs.scalar = float(float16_t(_e4));
+    vec2 _e10 = _group_0_binding_0_cs.pair;
+    _group_0_binding_0_cs.pair = vec2(f16vec2(_e10));
+    vec3 _e16 = _group_0_binding_0_cs.triple;
+    _group_0_binding_0_cs.triple = vec3(f16vec3(_e16));
+    vec4 _e22 = _group_0_binding_0_cs.quad;
+    _group_0_binding_0_cs.quad = vec4(f16vec4(_e22));
+    return;
+}
+
@@ -0,0 +1,32 @@
+#version 310 es
+
+precision highp float;
+precision highp int;
+
+layout(local_size_x = 1, local_size_y = 1, local_size_z = 1) in;
+
+struct Values {
+    float scalar;
+    vec2 pair;
+    vec3 triple;
+    vec4 quad;
+    vec2 rounded;
+};
+const vec2 ROUNDED = vec2(1.0, 65504.0);
+
+layout(std430) buffer Values_block_0Compute { Values _group_0_binding_0_cs; };
+
+
+void main() {
+    float _e4 = _group_0_binding_0_cs.scalar;
+    _group_0_binding_0_cs.scalar = unpackHalf2x16(packHalf2x16(vec2(_e4, 0.0))).x;
+    vec2 _e10 = _group_0_binding_0_cs.pair;
+    _group_0_binding_0_cs.pair = unpackHalf2x16(packHalf2x16(_e10));
+    vec3 _e16 = _group_0_binding_0_cs.triple;
+    _group_0_binding_0_cs.triple = vec3(unpackHalf2x16(packHalf2x16(_e16.xy)), unpackHalf2x16(packHalf2x16(_e16.zz)).x);
+    vec4 _e22 = _group_0_binding_0_cs.quad;
+    _group_0_binding_0_cs.quad = vec4(unpackHalf2x16(packHalf2x16(_e22.xy)), unpackHalf2x16(packHalf2x16(_e22.zw)));
+    _group_0_binding_0_cs.rounded = ROUNDED;
+    return;
+}
+
@@ -0,0 +1,106 @@
+#version 300 es
+
+precision highp float;
+precision highp int;
+
+struct Inputs {
+    vec4 a;
+    vec4 b;
+    ivec4 i;
+    uvec4 u;
+};
+uniform Inputs_block_0Fragment { Inputs _group_0_binding_0_fs; };
+
+layout(location = 0) out vec4 _fs2p_location0;
+
+bool all_any(bvec2 c2_, bvec3 c3_, bvec4 c4_) {
+    return (((((((all(c2_) && any(c2_)) && all(c3_)) && any(c3_)) && all(c4_)) && any(c4_)) && c4_.x) && c4_.y);
+}
+
+vec4 select_f32_(bvec2 c2_1, bvec3 c3_1, bvec4 c4_1) {
+    vec4 _e5 = _group_0_binding_0_fs.a;
+    vec4 _e9 = _group_0_binding_0_fs.b;
+    vec2 f2_ = mix(_e5.xy, _e9.xy, c2_1);
+    vec4 _e14 = _group_0_binding_0_fs.a;
+    vec4 _e18 = _group_0_binding_0_fs.b;
+    vec3 f3_ = mix(_e14.xyz, _e18.xyz, c3_1);
+    vec4 _e23 = _group_0_binding_0_fs.a;
+    vec4 _e26 = _group_0_binding_0_fs.b;
+    vec4 f4_ = mix(_e23, _e26, c4_1);
+    return ((vec4(f2_, f2_) + vec4(f3_, 1.0)) + f4_);
+}
+
+ivec4 select_i32_(bvec2 c2_2, bvec3 c3_2, bvec4 c4_2) {
+    ivec4 _e5 = _group_0_binding_0_fs.i;
+    ivec2 _e6 = _e5.xy;
+    ivec4 _e9 = _group_0_binding_0_fs.i;
+    ivec2 _e11 = -(_e9.yx);
+    ivec2 i2_ = ivec2(c2_2.x ? _e11.x : _e6.x, c2_2.y ? _e11.y : _e6.y);
+    ivec4 _e15 = _group_0_binding_0_fs.i;
+    ivec3 _e16 = _e15.xyz;
+    ivec4 _e19 = _group_0_binding_0_fs.i;
+    ivec3 _e21 = -(_e19.zyx);
+    ivec3 i3_ = ivec3(c3_2.x ? _e21.x : _e16.x, c3_2.y ? _e21.y : _e16.y, c3_2.z ? _e21.z : _e16.z);
+    ivec4 _e25 = _group_0_binding_0_fs.i;
+    ivec4 _e28 = _group_0_binding_0_fs.i;
+    ivec4 _e29 = -(_e28);
+    ivec4 i4_ = ivec4(c4_2.x ? _e29.x : _e25.x, c4_2.y ? _e29.y : _e25.y, c4_2.z ? _e29.z : _e25.z, c4_2.w ? _e29.w : _e25.w);
+    return ((ivec4(i2_, i2_) + ivec4(i3_, 1)) + i4_);
+}
+
+uvec4 select_u32_(bvec2 c2_3, bvec3 c3_3, bvec4 c4_3) {
+    uvec4 _e5 = _group_0_binding_0_fs.u;
+    uvec2 _e6 = _e5.xy;
+    uvec4 _e9 = _group_0_binding_0_fs.u;
+    uvec2 _e10 = _e9.yx;
+    uvec2 u2_ = uvec2(c2_3.x ? _e10.x : _e6.x, c2_3.y ? _e10.y : _e6.y);
+    uvec4 _e14 = _group_0_binding_0_fs.u;
+    uvec3 _e15 = _e14.xyz;
+    uvec4 _e18 = _group_0_binding_0_fs.u;
+    uvec3 _e19 = _e18.zyx;
+    uvec3 u3_ = uvec3(c3_3.x ? _e19.x : _e15.x, c3_3.y ? _e19.y : _e15.y, c3_3.z ? _e19.z : _e15.z);
+    uvec4 _e23 = _group_0_binding_0_fs.u;
+    uvec4 _e26 = _group_0_binding_0_fs.u;
+    uvec4 _e27 = _e26.wzyx;
+    uvec4 u4_ = uvec4(c4_3.x ? _e27.x : _e23.x, c4_3.y ? _e27.y : _e23.y, c4_3.z ? _e27.z : _e23.z, c4_3.w ? _e27.w : _e23.w);
+    return ((uvec4(u2_, u2_) + uvec4(u3_, 1u)) + u4_);
+}
+
+bool select_bool(bvec2 c2_4, bvec3 c3_4, bvec4 c4_4) {
+    bvec2 _e3 = not(c2_4);
+    bvec2 _e4 = c2_4.yx;
+    bvec2 b2_ = bvec2(_e4.x ? _e3.x : c2_4.x, _e4.y ? _e3.y : c2_4.y);
+    bvec3 _e6 = not(c3_4);
+    bvec3 _e7 = c3_4.zxy;
+    bvec3 b3_ = bvec3(_e7.x ? _e6.x : c3_4.x, _e7.y ? _e6.y : c3_4.y, _e7.z ? _e6.z : c3_4.z);
+    bvec4 _e9 = not(c4_4);
+    bvec4 _e10 = c4_4.wzyx;
+    bvec4 b4_ = bvec4(_e10.x ? _e9.x : c4_4.x, _e10.y ? _e9.y : c4_4.y, _e10.z ? _e9.z : c4_4.z, _e10.w ? _e9.w : c4_4.w);
+    return ((any(b2_) && any(b3_)) && any(b4_));
+}
+
+void main() {
+    vec4 color = vec4(0.0);
+    vec4 _e2 = _group_0_binding_0_fs.a;
+    vec4 _e5 = _group_0_binding_0_fs.b;
+    bvec4 c4_5 = lessThan(_e2, _e5);
+    bvec3 c3_5 = c4_5.xyz;
+    bvec2 c2_5 = c4_5.xy;
+    vec4 _e9 = select_f32_(c2_5, c3_5, c4_5);
+    color = _e9;
+    ivec4 _e11 = select_i32_(c2_5, c3_5, c4_5);
+    vec4 _e13 = color;
+    color = (_e13 + vec4(_e11));
+    uvec4 _e15 = select_u32_(c2_5, c3_5, c4_5);
+    vec4 _e17 = color;
+    color = (_e17 + vec4(_e15));
+    bool _e19 = all_any(c2_5, c3_5, c4_5);
+    bool _e20 = select_bool(c2_5, c3_5, c4_5);
+    if ((_e19 || _e20)) {
+        color.w = 0.0;
+    }
+    vec4 _e24 = color;
+    _fs2p_location0 = _e24;
+    return;
+}
+
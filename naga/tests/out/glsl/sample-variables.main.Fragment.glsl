@@ -0,0 +1,28 @@
+#version 310 es
+#extension GL_OES_sample_variables : require
+#extension GL_OES_shader_multisample_interpolation : require
+
+precision highp float;
+precision highp int;
+
+struct FragmentIn {
+    vec4 color;
+    uint sample_index;
+    uint sample_mask;
+};
+struct FragmentOut {
+    vec4 color;
+    uint sample_mask;
+};
+layout(location = 0) smooth sample in vec4 _vs2fs_location0;
+layout(location = 0) out vec4 _fs2p_location0;
+
+void main() {
+    FragmentIn input_ = FragmentIn(_vs2fs_location0, uint(gl_SampleID), uint(gl_SampleMaskIn[0]));
+    uint mask = (input_.sample_mask & (1u << input_.sample_index));
+    FragmentOut _tmp_return = FragmentOut(input_.color, mask);
+    _fs2p_location0 = _tmp_return.color;
+    gl_SampleMask[0] = int(_tmp_return.sample_mask);
+    return;
+}
+
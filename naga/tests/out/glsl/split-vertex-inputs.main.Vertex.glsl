@@ -0,0 +1,44 @@
+#version 310 es
+
+precision highp float;
+precision highp int;
+
+struct Instance {
+    mat4x4 transform;
+    float weights[2];
+    vec4 color;
+};
+struct VertexOutput {
+    vec4 position;
+    vec4 color;
+};
+layout(location = 0) in vec3 _p2vs_location0;
+layout(location = 1) in vec4 _p2vs_location1;
+layout(location = 2) in vec4 _p2vs_location2;
+layout(location = 3) in vec4 _p2vs_location3;
+layout(location = 4) in vec4 _p2vs_location4;
+layout(location = 5) in float _p2vs_location5;
+layout(location = 6) in float _p2vs_location6;
+layout(location = 7) in vec4 _p2vs_location7;
+layout(location = 8) in vec3 _p2vs_location8;
+layout(location = 9) in vec3 _p2vs_location9;
+layout(location = 10) in vec3 _p2vs_location10;
+layout(location = 11) in uvec2 _p2vs_location11;
+layout(location = 12) in uvec2 _p2vs_location12;
+layout(location = 0) smooth out vec4 _vs2fs_location0;
+
+void main() {
+    vec3 position = _p2vs_location0;
+    Instance instance = Instance(mat4x4(_p2vs_location1, _p2vs_location2, _p2vs_location3, _p2vs_location4), float[2](_p2vs_location5, _p2vs_location6), _p2vs_location7);
+    mat3x3 normal_matrix = mat3x3(_p2vs_location8, _p2vs_location9, _p2vs_location10);
+    uvec2 joints[2] = uvec2[2](_p2vs_location11, _p2vs_location12);
+    vec3 normal = (normal_matrix * position);
+    vec4 world = (instance.transform * vec4((position + normal), 1.0));
+    float weight = ((instance.weights[0] * float(joints[0].x)) + (instance.weights[1] * float(joints[1].y)));
+    VertexOutput _tmp_return = VertexOutput(world, (instance.color * weight));
+    gl_Position = _tmp_return.position;
+    _vs2fs_location0 = _tmp_return.color;
+    gl_Position.yz = vec2(-gl_Position.y, gl_Position.z * 2.0 - gl_Position.w);
+    return;
+}
+
@@ -0,0 +1,32 @@
+#version 310 es
+
+precision highp float;
+precision highp int;
+
+layout(local_size_x = 8, local_size_y = 8, local_size_z = 1) in;
+
+layout(std430) readonly buffer type_1_block_0Compute { float _group_0_binding_0_cs[]; };
+
+layout(std430) buffer type_1_block_1Compute { float _group_0_binding_1_cs[]; };
+
+layout(r32f) readonly uniform highp image2D _group_0_binding_2_cs;
+
+layout(rgba8) writeonly uniform highp image2D _group_0_binding_3_cs;
+
+layout(r32f) uniform highp image2D _group_0_binding_4_cs;
+
+
+void main() {
+    uvec3 id = gl_GlobalInvocationID;
+    float weight = _group_0_binding_0_cs[id.x];
+    vec4 _e7 = imageLoad(_group_0_binding_2_cs, ivec2(id.xy));
+    float value = (_e7.x * weight);
+    float _e13 = _group_0_binding_1_cs[id.x];
+    _group_0_binding_1_cs[id.x] = (_e13 + value);
+    imageStore(_group_0_binding_3_cs, ivec2(id.xy), vec4(value));
+    vec4 _e20 = imageLoad(_group_0_binding_4_cs, ivec2(id.xy));
+    vec4 sum = (_e20 + vec4(value));
+    imageStore(_group_0_binding_4_cs, ivec2(id.xy), sum);
+    return;
+}
+
@@ -5,6 +5,10 @@ precision highp int;
 
 layout(local_size_x = 4, local_size_y = 1, local_size_z = 1) in;
 
+struct Params {
+    uint count;
+    float scale;
+};
 const uint SIZE = 128u;
 
 shared int arr_i32_[128];
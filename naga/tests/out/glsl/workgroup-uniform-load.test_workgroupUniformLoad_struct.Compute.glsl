@@ -0,0 +1,52 @@
+#version 310 es
+
+precision highp float;
+precision highp int;
+
+layout(local_size_x = 4, local_size_y = 1, local_size_z = 1) in;
+
+struct Params {
+    uint count;
+    float scale;
+};
+const uint SIZE = 128u;
+
+shared Params params;
+
+
+void main() {
+    if (gl_LocalInvocationID == uvec3(0u)) {
+        params = Params(0u, 0.0);
+    }
+    memoryBarrierShared();
+    barrier();
+    uint index = gl_LocalInvocationIndex;
+    uint i = 0u;
+    if ((index == 0u)) {
+        params = Params(4u, 2.0);
+    }
+    memoryBarrierShared();
+    barrier();
+    Params _e8 = params;
+    memoryBarrierShared();
+    barrier();
+    bool loop_init = true;
+    while(true) {
+        if (!loop_init) {
+            uint _e15 = i;
+            i = (_e15 + 1u);
+        }
+        loop_init = false;
+        uint _e11 = i;
+        if ((_e11 < _e8.count)) {
+        } else {
+            break;
+        }
+        {
+            memoryBarrierShared();
+            barrier();
+        }
+    }
+    return;
+}
+
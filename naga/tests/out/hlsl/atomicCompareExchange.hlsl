@@ -0,0 +1,119 @@
+struct _atomic_compare_exchange_resultSint4_ {
+    int old_value;
+    bool exchanged;
+};
+
+struct _atomic_compare_exchange_resultUint4_ {
+    uint old_value;
+    bool exchanged;
+};
+
+static const uint SIZE = 128u;
+
+RWByteAddressBuffer arr_i32_ : register(u0);
+RWByteAddressBuffer arr_u32_ : register(u1);
+
+[numthreads(1, 1, 1)]
+void test_atomic_compare_exchange_i32_()
+{
+    uint i = 0u;
+    int old = (int)0;
+    bool exchanged = (bool)0;
+
+    bool loop_init = true;
+    while(true) {
+        if (!loop_init) {
+            uint _expr27 = i;
+            i = (_expr27 + 1u);
+        }
+        loop_init = false;
+        uint _expr2 = i;
+        if ((_expr2 < SIZE)) {
+        } else {
+            break;
+        }
+        {
+            uint _expr6 = i;
+            int _expr8; arr_i32_.InterlockedOr(_expr6*4, 0, _expr8);
+            old = _expr8;
+            exchanged = false;
+            while(true) {
+                bool _expr12 = exchanged;
+                if (!(_expr12)) {
+                } else {
+                    break;
+                }
+                {
+                    int _expr14 = old;
+                    int new_ = asint((asfloat(_expr14) + 1.0));
+                    uint _expr20 = i;
+                    int _expr22 = old;
+                    _atomic_compare_exchange_resultSint4_ _e23; arr_i32_.InterlockedCompareExchange(_expr20*4, _expr22, new_, _e23.old_value);
+                    _e23.exchanged = (_e23.old_value == _expr22);
+                    old = _e23.old_value;
+                    exchanged = _e23.exchanged;
+                }
+            }
+        }
+    }
+    return;
+}
+
+[numthreads(1, 1, 1)]
+void test_atomic_compare_exchange_u32_()
+{
+    uint i_1 = 0u;
+    uint old_1 = (uint)0;
+    bool exchanged_1 = (bool)0;
+
+    bool loop_init_1 = true;
+    while(true) {
+        if (!loop_init_1) {
+            uint _expr27 = i_1;
+            i_1 = (_expr27 + 1u);
+        }
+        loop_init_1 = false;
+        uint _expr2 = i_1;
+        if ((_expr2 < SIZE)) {
+        } else {
+            break;
+        }
+        {
+            uint _expr6 = i_1;
+            uint _expr8; arr_u32_.InterlockedOr(_expr6*4, 0, _expr8);
+            old_1 = _expr8;
+            exchanged_1 = false;
+            while(true) {
+                bool _expr12 = exchanged_1;
+                if (!(_expr12)) {
+                } else {
+                    break;
+                }
+                {
+                    uint _expr14 = old_1;
+                    uint new_1 = asuint((asfloat(_expr14) + 1.0));
+                    uint _expr20 = i_1;
+                    uint _expr22 = old_1;
+                    _atomic_compare_exchange_resultUint4_ _e23; arr_u32_.InterlockedCompareExchange(_expr20*4, _expr22, new_1, _e23.old_value);
+                    _e23.exchanged = (_e23.old_value == _expr22);
+                    old_1 = _e23.old_value;
+                    exchanged_1 = _e23.exchanged;
+                }
+            }
+        }
+    }
+    return;
+}
+
+[numthreads(1, 1, 1)]
+void test_atomic_compare_exchange_computed()
+{
+    uint expected = 1u;
+
+    uint _expr4 = expected;
+    uint _e6 = (_expr4 * 2u);
+    _atomic_compare_exchange_resultUint4_ _e8; arr_u32_.InterlockedCompareExchange(0, _e6, 0u, _e8.old_value);
+    _e8.exchanged = (_e8.old_value == _e6);
+    expected = _e8.old_value;
+    return;
+}
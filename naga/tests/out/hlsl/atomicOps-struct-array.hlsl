@@ -0,0 +1,38 @@
+struct Item {
+    uint value;
+    uint counter;
+    int balance;
+};
+
+struct _atomic_compare_exchange_resultUint4_ {
+    uint old_value;
+    bool exchanged;
+};
+
+RWByteAddressBuffer items : register(u0);
+groupshared uint workgroup_counter;
+
+[numthreads(64, 1, 1)]
+void cs_main(uint i : SV_GroupIndex, uint3 __local_invocation_id : SV_GroupThreadID)
+{
+    if (all(__local_invocation_id == uint3(0u, 0u, 0u))) {
+        workgroup_counter = (uint)0;
+    }
+    GroupMemoryBarrierWithGroupSync();
+    uint _atomic_old; items.InterlockedExchange(4+i*12, 1u, _atomic_old);
+    uint loaded; items.InterlockedOr(4+i*12, 0, loaded);
+    uint _e13; items.InterlockedAdd(4+i*12, 1u, _e13);
+    int _e18; items.InterlockedAdd(8+i*12, -1, _e18);
+    uint _e23; items.InterlockedAnd(4+i*12, 1u, _e23);
+    int _e28; items.InterlockedOr(8+i*12, 1, _e28);
+    uint _e33; items.InterlockedXor(4+i*12, 1u, _e33);
+    int _e38; items.InterlockedMin(8+i*12, 1, _e38);
+    uint _e43; items.InterlockedMax(4+i*12, 1u, _e43);
+    int _e48; items.InterlockedExchange(8+i*12, 1, _e48);
+    _atomic_compare_exchange_resultUint4_ _e53; items.InterlockedCompareExchange(4+i*12, loaded, 2u, _e53.old_value);
+    _e53.exchanged = (_e53.old_value == loaded);
+    _atomic_compare_exchange_resultUint4_ _e57; InterlockedCompareExchange(workgroup_counter, 0u, 1u, _e57.old_value);
+    _e57.exchanged = (_e57.old_value == 0u);
+    items.Store(0+i*12, asuint((((((((((_e13 + uint(_e18)) + _e23) + uint(_e28)) + _e33) + uint(_e38)) + _e43) + uint(_e48)) + _e53.old_value) + _e57.old_value)));
+    return;
+}
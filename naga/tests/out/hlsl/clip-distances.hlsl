@@ -0,0 +1,86 @@
+struct One {
+    float4 position : SV_Position;
+    float clip_distances[1] : SV_ClipDistance;
+};
+
+struct Four {
+    float4 position : SV_Position;
+    float4 color : LOC0;
+    float clip_distances[4] : SV_ClipDistance;
+};
+
+struct Five {
+    float4 position : SV_Position;
+    float clip_distances[5] : SV_ClipDistance;
+};
+
+struct VertexOutput_vs_one {
+    float4 position : SV_Position;
+    float clip_distances : SV_ClipDistance0;
+};
+
+struct VertexOutput_vs_four {
+    float4 color : LOC0;
+    float4 position_1 : SV_Position;
+    float4 clip_distances_1 : SV_ClipDistance0;
+};
+
+struct VertexOutput_vs_five {
+    float4 position_2 : SV_Position;
+    float4 clip_distances_2 : SV_ClipDistance0;
+    float clip_distances_2_ : SV_ClipDistance1;
+};
+
+VertexOutput_vs_one vs_one()
+{
+    One out_ = (One)0;
+
+    out_.position = (0.0).xxxx;
+    out_.clip_distances[0] = 1.0;
+    One _expr7 = out_;
+    const One one = _expr7;
+    const VertexOutput_vs_one one_1 = { one.position, float(one.clip_distances[0]) };
+    return one_1;
+}
+
+typedef float ret_Constructarray4_float_[4];
+ret_Constructarray4_float_ Constructarray4_float_(float arg0, float arg1, float arg2, float arg3) {
+    float ret[4] = { arg0, arg1, arg2, arg3 };
+    return ret;
+}
+
+Four ConstructFour(float4 arg0, float4 arg1, float arg2[4]) {
+    Four ret = (Four)0;
+    ret.position = arg0;
+    ret.color = arg1;
+    ret.clip_distances = arg2;
+    return ret;
+}
+
+VertexOutput_vs_four vs_four(float4 plane : LOC0)
+{
+    const Four four = ConstructFour((0.0).xxxx, plane, Constructarray4_float_(plane.x, plane.y, plane.z, plane.w));
+    const VertexOutput_vs_four four_1 = { four.color, four.position, float4(four.clip_distances[0], four.clip_distances[1], four.clip_distances[2], four.clip_distances[3]) };
+    return four_1;
+}
+
+typedef float ret_Constructarray5_float_[5];
+ret_Constructarray5_float_ Constructarray5_float_(float arg0, float arg1, float arg2, float arg3, float arg4) {
+    float ret[5] = { arg0, arg1, arg2, arg3, arg4 };
+    return ret;
+}
+
+Five ConstructFive(float4 arg0, float arg1[5]) {
+    Five ret = (Five)0;
+    ret.position = arg0;
+    ret.clip_distances = arg1;
+    return ret;
+}
+
+VertexOutput_vs_five vs_five(uint index : SV_VertexID)
+{
+    float d = float(index);
+    const Five five = ConstructFive((d).xxxx, Constructarray5_float_(d, -(d), (2.0 * d), (-2.0 * d), 0.5));
+    const VertexOutput_vs_five five_1 = { five.position, float4(five.clip_distances[0], five.clip_distances[1], five.clip_distances[2], five.clip_distances[3]), float(five.clip_distances[4]) };
+    return five_1;
+}
@@ -0,0 +1,107 @@
+RWByteAddressBuffer values : register(u0);
+
+[numthreads(1, 1, 1)]
+void main(uint index : SV_GroupIndex)
+{
+    float sum = 0.0;
+    uint i = 0u;
+    uint i_1 = 0u;
+    uint j = 0u;
+
+    bool loop_init = true;
+    [unroll] while(true) {
+        if (!loop_init) {
+            uint _expr15 = i;
+            i = (_expr15 + 1u);
+        }
+        loop_init = false;
+        uint _expr5 = i;
+        if ((_expr5 < 4u)) {
+        } else {
+            break;
+        }
+        {
+            uint _expr9 = i;
+            float _expr11 = asfloat(values.Load(_expr9*4));
+            float _expr12 = sum;
+            sum = (_expr12 + _expr11);
+        }
+    }
+    bool loop_init_1 = true;
+    [loop] while(true) {
+        if (!loop_init_1) {
+            uint _expr28 = i_1;
+            i_1 = (_expr28 + 1u);
+        }
+        loop_init_1 = false;
+        uint _expr19 = i_1;
+        if ((_expr19 < index)) {
+        } else {
+            break;
+        }
+        {
+            uint _expr22 = i_1;
+            float _expr24 = asfloat(values.Load(_expr22*4));
+            float _expr25 = sum;
+            sum = (_expr25 + _expr24);
+        }
+    }
+    [unroll] while(true) {
+        uint _expr32 = j;
+        if ((_expr32 < 4u)) {
+        } else {
+            break;
+        }
+        {
+            uint _expr36 = j;
+            float _expr38 = asfloat(values.Load(_expr36*4));
+            float _expr39 = sum;
+            sum = (_expr39 * _expr38);
+            uint _expr42 = j;
+            j = (_expr42 + 1u);
+        }
+    }
+    bool loop_init_2 = true;
+    [loop] while(true) {
+        if (!loop_init_2) {
+            float _expr50 = sum;
+            if ((_expr50 == 0.0)) {
+                break;
+            }
+        }
+        loop_init_2 = false;
+        float _expr44 = sum;
+        if ((_expr44 > 100.0)) {
+            break;
+        }
+        float _expr48 = sum;
+        sum = (_expr48 * 2.0);
+    }
+    [branch] if ((index == 0u)) {
+        float _expr57 = asfloat(values.Load(60));
+        sum = _expr57;
+    } else {
+        if ((index == 1u)) {
+            float _expr62 = asfloat(values.Load(56));
+            sum = _expr62;
+        }
+    }
+    float _expr63 = sum;
+    [flatten] if ((_expr63 < 0.0)) {
+        float _expr66 = sum;
+        sum = -(_expr66);
+    } else {
+        float _expr68 = sum;
+        sum = (_expr68 * 0.5);
+    }
+    float _expr71 = sum;
+    if ((_expr71 > 1.0)) {
+        float _expr74 = sum;
+        [flatten] if ((_expr74 > 2.0)) {
+            sum = 2.0;
+        }
+    }
+    float _expr80 = sum;
+    values.Store(index*4, asuint(_expr80));
+    return;
+}
@@ -35,6 +35,99 @@ void loop_switch_continue(int x)
     return;
 }
 
+int loop_switch_break(int x_1)
+{
+    int i_1 = 0;
+
+    while(true) {
+        switch(x_1) {
+            case 1: {
+                break;
+            }
+            default: {
+                break;
+            }
+        }
+        int _expr4 = i_1;
+        i_1 = (_expr4 + 1);
+        int _expr6 = i_1;
+        if ((_expr6 > 4)) {
+            break;
+        }
+    }
+    int _expr9 = i_1;
+    return _expr9;
+}
+
+void loop_switch_continue_if(int x_2)
+{
+    int i_2 = 0;
+
+    bool loop_init = true;
+    while(true) {
+        if (!loop_init) {
+            int _expr7 = i_2;
+            i_2 = (_expr7 + 1);
+            int _expr9 = i_2;
+            if ((_expr9 > 4)) {
+                break;
+            }
+        }
+        loop_init = false;
+        switch(x_2) {
+            case 1: {
+                int _expr3 = i_2;
+                if ((_expr3 == 2)) {
+                    continue;
+                }
+                break;
+            }
+            default: {
+                break;
+            }
+        }
+    }
+    return;
+}
+
+void switch_loop_break(int x_3)
+{
+    switch(x_3) {
+        case 1: {
+            while(true) {
+                break;
+            }
+            return;
+        }
+        default: {
+            return;
+        }
+    }
+}
+
+void switch_loop_continue(int x_4)
+{
+    int i_3 = 0;
+
+    switch(x_4) {
+        case 1: {
+            while(true) {
+                int _expr4 = i_3;
+                i_3 = (_expr4 + 1);
+                int _expr6 = i_3;
+                if ((_expr6 < 4)) {
+                    continue;
+                }
+                break;
+            }
+            return;
+        }
+        default: {
+            return;
+        }
+    }
+}
+
 [numthreads(1, 1, 1)]
 void main(uint3 global_id : SV_DispatchThreadID)
 {
@@ -0,0 +1,34 @@
+RWByteAddressBuffer data : register(u0);
+
+[numthreads(1, 1, 1)]
+void main()
+{
+    uint sum = 0u;
+
+    // setup
+    // accumulate
+    bool loop_init = true;
+    while(true) {
+        if (!loop_init) {
+            // continuing
+            uint _expr12 = sum;
+            sum = (_expr12 + 1u);
+        }
+        loop_init = false;
+        // loop body
+        uint _expr2 = sum;
+        if ((_expr2 > 10u)) {
+            break;
+        }
+        uint _expr6 = sum;
+        uint _expr8 = asuint(data.Load(_expr6*4));
+        uint _expr9 = sum;
+        sum = (_expr9 + _expr8);
+    }
+    // path C:\temp
+    uint _expr16 = sum;
+    data.Store(0, asuint(_expr16));
+    //
+    // done
+    return;
+}
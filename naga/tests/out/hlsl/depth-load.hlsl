@@ -0,0 +1,17 @@
+Texture2D<float> depth_2d : register(t0);
+Texture2DArray<float> depth_2d_array : register(t1);
+Texture2DMS<float> depth_multisampled_2d : register(t2);
+
+struct FragmentInput_main {
+    float4 position_1 : SV_Position;
+};
+
+float4 main(FragmentInput_main fragmentinput_main) : SV_Target0
+{
+    float4 position = fragmentinput_main.position_1;
+    int2 coords = int2(position.xy);
+    float a = depth_2d.Load(int3(coords, 0)).x;
+    float b = depth_2d_array.Load(int4(coords, 1, 0)).x;
+    float c = depth_multisampled_2d.Load(coords, 2).x;
+    return float4(a, b, c, 1.0);
+}
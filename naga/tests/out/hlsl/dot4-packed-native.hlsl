@@ -0,0 +1,11 @@
+RWByteAddressBuffer values : register(u0);
+
+[numthreads(1, 1, 1)]
+void main()
+{
+    uint a = asuint(values.Load(0));
+    uint b = asuint(values.Load(4));
+    values.Store(8, asuint(asuint(dot4add_i8packed(a, b, 0))));
+    values.Store(12, asuint(dot4add_u8packed(a, b, 0u)));
+    return;
+}
@@ -0,0 +1,13 @@
+RWByteAddressBuffer values : register(u0);
+
+[numthreads(1, 1, 1)]
+void main()
+{
+    uint a = asuint(values.Load(0));
+    uint b = asuint(values.Load(4));
+    values.Store(8, asuint(asuint(dot(int4(a << 24, a << 16, a << 8, a) >> 24, int4(b << 24, b << 16, b << 8, b) >> 24))));
+    uint _expr13 = (a + 1u);
+    values.Store(12, asuint(dot(uint4(_expr13, _expr13 >> 8, _expr13 >> 16, _expr13 >> 24) & 0xFF, uint4(b, b >> 8, b >> 16, b >> 24) & 0xFF)));
+    values.Store(16, asuint((asuint(512) + 2550u)));
+    return;
+}
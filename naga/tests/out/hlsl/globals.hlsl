@@ -132,6 +132,6 @@ void main(uint3 __local_invocation_id : SV_GroupThreadID)
     wg[2] = _expr43;
     alignment.Store(12, asuint(4.0));
     wg[1] = float(((NagaBufferLength(dummy) - 0) / 8));
-    at_1 = 2u;
+    uint _atomic_old; InterlockedExchange(at_1, 2u, _atomic_old);
     return;
 }
@@ -0,0 +1,47 @@
+RWTexture2DArray<unorm float4> storage_2d_array : register(u0);
+TextureCube<float4> sampled_cube : register(t1);
+RWByteAddressBuffer out_ : register(u2);
+
+uint2 NagaRWDimensions2DArray(RWTexture2DArray<unorm float4> tex)
+{
+    uint4 ret;
+    tex.GetDimensions(ret.x, ret.y, ret.z);
+    return ret.xy;
+}
+
+uint NagaRWNumLayers2DArray(RWTexture2DArray<unorm float4> tex)
+{
+    uint4 ret;
+    tex.GetDimensions(ret.x, ret.y, ret.z);
+    return ret.z;
+}
+
+uint2 NagaDimensionsCube(TextureCube<float4> tex)
+{
+    uint4 ret;
+    tex.GetDimensions(0, ret.x, ret.y, ret.z);
+    return ret.xy;
+}
+
+uint2 NagaMipDimensionsCube(TextureCube<float4> tex, uint mip_level)
+{
+    uint4 ret;
+    tex.GetDimensions(mip_level, ret.x, ret.y, ret.z);
+    return ret.xy;
+}
+
+[numthreads(1, 1, 1)]
+void main()
+{
+    uint2 storage_size = NagaRWDimensions2DArray(storage_2d_array);
+    uint storage_layers = NagaRWNumLayers2DArray(storage_2d_array);
+    uint2 cube_size = NagaDimensionsCube(sampled_cube);
+    uint2 cube_size_lod = NagaMipDimensionsCube(sampled_cube, 1);
+    out_.Store(0, asuint(storage_size.x));
+    out_.Store(4, asuint(storage_size.y));
+    out_.Store(8, asuint(storage_layers));
+    out_.Store(12, asuint(cube_size.x));
+    out_.Store(16, asuint(cube_size.y));
+    out_.Store(20, asuint(cube_size_lod.x));
+    return;
+}
@@ -0,0 +1,228 @@
+struct Particle {
+    float2 position;
+    float2 velocity;
+};
+
+RWByteAddressBuffer particles : register(u0);
+
+float square(float x)
+{
+    return (x * x);
+}
+
+float length_squared(float2 v)
+{
+    float square_result_3 = (float)0;
+    float square_result_4 = (float)0;
+
+    {
+        {
+            square_result_3 = (v.x * v.x);
+        }
+    }
+    float _expr4 = square_result_3;
+    {
+        {
+            square_result_4 = (v.y * v.y);
+        }
+    }
+    float _expr8 = square_result_4;
+    return (_expr4 + _expr8);
+}
+
+int first_negative(float4 v_1)
+{
+    int i_2 = 0;
+
+    bool loop_init = true;
+    while(true) {
+        if (!loop_init) {
+            int _expr12 = i_2;
+            i_2 = (_expr12 + 1);
+        }
+        loop_init = false;
+        int _expr3 = i_2;
+        if ((_expr3 < 4)) {
+        } else {
+            break;
+        }
+        {
+            int _expr6 = i_2;
+            if ((v_1[_expr6] < 0.0)) {
+                int _expr10 = i_2;
+                return _expr10;
+            }
+        }
+    }
+    return -1;
+}
+
+void step_(inout Particle p, float dt)
+{
+    float square_result_5 = (float)0;
+    float square_result_6 = (float)0;
+    float length_squared_result_1 = (float)0;
+
+    float2 _expr4 = p.velocity;
+    float2 _expr6 = p.position;
+    p.position = (_expr6 + (_expr4 * dt));
+    float2 _expr9 = p.velocity;
+    {
+        {
+            {
+                {
+                    square_result_5 = (_expr9.x * _expr9.x);
+                }
+            }
+            float _expr13 = square_result_5;
+            {
+                {
+                    square_result_6 = (_expr9.y * _expr9.y);
+                }
+            }
+            float _expr17 = square_result_6;
+            length_squared_result_1 = (_expr13 + _expr17);
+        }
+    }
+    float _expr20 = length_squared_result_1;
+    if ((_expr20 > 1.0)) {
+        float2 _expr25 = p.velocity;
+        p.velocity = normalize(_expr25);
+        return;
+    } else {
+        return;
+    }
+}
+
+Particle ConstructParticle(float2 arg0, float2 arg1) {
+    Particle ret = (Particle)0;
+    ret.position = arg0;
+    ret.velocity = arg1;
+    return ret;
+}
+
+[numthreads(64, 1, 1)]
+void main(uint3 id : SV_DispatchThreadID)
+{
+    float total = 0.0;
+    uint i = 0u;
+    Particle particle = (Particle)0;
+    int i_1 = 0;
+    int first_negative_result = (int)0;
+    bool returned = (bool)0;
+    float square_result = (float)0;
+    float square_result_1 = (float)0;
+    float square_result_2 = (float)0;
+    float length_squared_result = (float)0;
+
+    bool loop_init_1 = true;
+    while(true) {
+        if (!loop_init_1) {
+            uint _expr56 = i;
+            i = (_expr56 + 1u);
+        }
+        loop_init_1 = false;
+        uint _expr5 = i;
+        if ((_expr5 < 2u)) {
+        } else {
+            break;
+        }
+        {
+            uint _expr10 = i;
+            Particle _expr13 = ConstructParticle(asfloat(particles.Load2((id.x + _expr10)*16+0)), asfloat(particles.Load2((id.x + _expr10)*16+8)));
+            particle = _expr13;
+            {
+                square_result_1 = (float)0;
+                square_result_2 = (float)0;
+                length_squared_result = (float)0;
+                while(true) {
+                    float2 _expr70 = particle.velocity;
+                    float2 _expr72 = particle.position;
+                    particle.position = (_expr72 + (_expr70 * 0.5));
+                    float2 _expr75 = particle.velocity;
+                    {
+                        {
+                            {
+                                {
+                                    square_result_1 = (_expr75.x * _expr75.x);
+                                }
+                            }
+                            float _expr79 = square_result_1;
+                            {
+                                {
+                                    square_result_2 = (_expr75.y * _expr75.y);
+                                }
+                            }
+                            float _expr83 = square_result_2;
+                            length_squared_result = (_expr79 + _expr83);
+                        }
+                    }
+                    float _expr86 = length_squared_result;
+                    if ((_expr86 > 1.0)) {
+                        float2 _expr91 = particle.velocity;
+                        particle.velocity = normalize(_expr91);
+                        break;
+                    } else {
+                        break;
+                    }
+                    break;
+                }
+            }
+            uint _expr18 = i;
+            Particle _expr21 = particle;
+            {
+                Particle _value4 = _expr21;
+                particles.Store2((id.x + _expr18)*16+0, asuint(_value4.position));
+                particles.Store2((id.x + _expr18)*16+8, asuint(_value4.velocity));
+            }
+            float2 _expr26 = asfloat(particles.Load2(0+id.x*16));
+            {
+                i_1 = 0;
+                returned = false;
+                while(true) {
+                    bool loop_init_2 = true;
+                    while(true) {
+                        if (!loop_init_2) {
+                            int _expr41 = i_1;
+                            i_1 = (_expr41 + 1);
+                        }
+                        loop_init_2 = false;
+                        int _expr32 = i_1;
+                        if ((_expr32 < 4)) {
+                        } else {
+                            break;
+                        }
+                        {
+                            int _expr35 = i_1;
+                            if ((float4(_expr26, -1.0, 2.0)[_expr35] < 0.0)) {
+                                int _expr39 = i_1;
+                                first_negative_result = _expr39;
+                                returned = true;
+                                break;
+                            }
+                        }
+                    }
+                    bool _expr50 = returned;
+                    if (_expr50) {
+                        break;
+                    }
+                    first_negative_result = -1;
+                    break;
+                }
+            }
+            int _expr51 = first_negative_result;
+            float _expr53 = total;
+            total = (_expr53 + float(_expr51));
+        }
+    }
+    float _expr62 = total;
+    float _expr63 = total;
+    {
+        {
+            square_result = (_expr63 * _expr63);
+        }
+    }
+    float _expr66 = square_result;
+    particles.Store2(8+id.x*16, asuint(float2(_expr62, _expr66)));
+    return;
+}
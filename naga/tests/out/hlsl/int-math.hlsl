@@ -0,0 +1,110 @@
+[numthreads(1, 1, 1)]
+void main()
+{
+    int i = 0;
+    int3 i3_ = (0).xxx;
+    uint u = 0u;
+    uint3 u3_ = (0u).xxx;
+
+    int _expr10 = i;
+    i = abs(_expr10);
+    int3 _expr12 = i3_;
+    i3_ = abs(_expr12);
+    uint _expr14 = u;
+    u = (_expr14);
+    uint3 _expr16 = u3_;
+    u3_ = (_expr16);
+    int _expr18 = i;
+    i = sign(_expr18);
+    int3 _expr20 = i3_;
+    i3_ = sign(_expr20);
+    int _expr22 = i;
+    int _expr23 = i;
+    i = min(_expr22, _expr23);
+    int3 _expr25 = i3_;
+    int3 _expr26 = i3_;
+    i3_ = min(_expr25, _expr26);
+    uint _expr28 = u;
+    uint _expr29 = u;
+    u = min(_expr28, _expr29);
+    uint3 _expr31 = u3_;
+    uint3 _expr32 = u3_;
+    u3_ = min(_expr31, _expr32);
+    int _expr34 = i;
+    int _expr35 = i;
+    i = max(_expr34, _expr35);
+    int3 _expr37 = i3_;
+    int3 _expr38 = i3_;
+    i3_ = max(_expr37, _expr38);
+    uint _expr40 = u;
+    uint _expr41 = u;
+    u = max(_expr40, _expr41);
+    uint3 _expr43 = u3_;
+    uint3 _expr44 = u3_;
+    u3_ = max(_expr43, _expr44);
+    int _expr46 = i;
+    int _expr47 = i;
+    int _expr48 = i;
+    i = clamp(_expr46, _expr47, _expr48);
+    int3 _expr50 = i3_;
+    int3 _expr51 = i3_;
+    int3 _expr52 = i3_;
+    i3_ = clamp(_expr50, _expr51, _expr52);
+    uint _expr54 = u;
+    uint _expr55 = u;
+    uint _expr56 = u;
+    u = clamp(_expr54, _expr55, _expr56);
+    uint3 _expr58 = u3_;
+    uint3 _expr59 = u3_;
+    uint3 _expr60 = u3_;
+    u3_ = clamp(_expr58, _expr59, _expr60);
+    int _expr62 = i;
+    i = asint(countbits(asuint(_expr62)));
+    int3 _expr64 = i3_;
+    i3_ = asint(countbits(asuint(_expr64)));
+    uint _expr66 = u;
+    u = countbits(_expr66);
+    uint3 _expr68 = u3_;
+    u3_ = countbits(_expr68);
+    int _expr70 = i;
+    i = asint(reversebits(asuint(_expr70)));
+    int3 _expr72 = i3_;
+    i3_ = asint(reversebits(asuint(_expr72)));
+    uint _expr74 = u;
+    u = reversebits(_expr74);
+    uint3 _expr76 = u3_;
+    u3_ = reversebits(_expr76);
+    int _expr78 = i;
+    i = asint(firstbithigh(_expr78));
+    int3 _expr80 = i3_;
+    i3_ = asint(firstbithigh(_expr80));
+    uint _expr82 = u;
+    u = firstbithigh(_expr82);
+    uint3 _expr84 = u3_;
+    u3_ = firstbithigh(_expr84);
+    int _expr86 = i;
+    i = asint(firstbitlow(_expr86));
+    int3 _expr88 = i3_;
+    i3_ = asint(firstbitlow(_expr88));
+    uint _expr90 = u;
+    u = firstbitlow(_expr90);
+    uint3 _expr92 = u3_;
+    u3_ = firstbitlow(_expr92);
+    int _expr94 = i;
+    i = (_expr94 < 0 ? 0 : 31 - asint(firstbithigh(_expr94)));
+    int3 _expr96 = i3_;
+    i3_ = (_expr96 < (0).xxx ? (0).xxx : (31).xxx - asint(firstbithigh(_expr96)));
+    uint _expr98 = u;
+    u = (31u - firstbithigh(_expr98));
+    uint3 _expr100 = u3_;
+    u3_ = ((31u).xxx - firstbithigh(_expr100));
+    int _expr102 = i;
+    i = asint(min(32u, firstbitlow(_expr102)));
+    int3 _expr104 = i3_;
+    i3_ = asint(min((32u).xxx, firstbitlow(_expr104)));
+    uint _expr106 = u;
+    u = min(32u, firstbitlow(_expr106));
+    uint3 _expr108 = u3_;
+    u3_ = min((32u).xxx, firstbitlow(_expr108));
+    return;
+}
@@ -101,4 +101,9 @@ void main()
     float frexp_b = naga_frexp(1.5).fract;
     int frexp_c = naga_frexp(1.5).exp_;
     int frexp_d = naga_frexp(float4(1.5, 1.5, 1.5, 1.5)).exp_.x;
+    float4 round_a = round(v);
+    float4 fract_a = frac(v);
+    float4 trunc_a = trunc(v);
+    float4 floor_a = floor(v);
+    float4 ceil_a = ceil(v);
 }
@@ -0,0 +1,23 @@
+struct Queue {
+    uint head;
+    uint items[64];
+};
+
+globallycoherent RWByteAddressBuffer queue : register(u0);
+globallycoherent RWByteAddressBuffer flags : register(u1);
+globallycoherent RWByteAddressBuffer mailbox : register(u2);
+RWByteAddressBuffer plain : register(u3);
+
+[numthreads(64, 1, 1)]
+void main(uint index : SV_GroupIndex)
+{
+    uint _e4; queue.InterlockedAdd(0, 1u, _e4);
+    queue.Store((_e4 % 64u)*4+4, asuint(index));
+    uint _expr18 = asuint(queue.Load(index*4+4));
+    flags.Store(4+(index % 4u)*16, asuint(_expr18));
+    uint _expr21 = asuint(mailbox.Load(0));
+    uint _expr23 = asuint(plain.Load(0));
+    mailbox.Store(0, asuint((_expr21 + _expr23)));
+    plain.Store(0, asuint(index));
+    return;
+}
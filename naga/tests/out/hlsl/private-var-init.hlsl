@@ -0,0 +1,74 @@
+struct S {
+    float3 position;
+    float weights[2];
+    uint count;
+    int _end_pad_0;
+    int _end_pad_1;
+};
+
+typedef int ret_Constructarray3_int_[3];
+ret_Constructarray3_int_ Constructarray3_int_(int arg0, int arg1, int arg2) {
+    int ret[3] = { arg0, arg1, arg2 };
+    return ret;
+}
+
+typedef float ret_Constructarray2_float_[2];
+ret_Constructarray2_float_ Constructarray2_float_(float arg0, float arg1) {
+    float ret[2] = { arg0, arg1 };
+    return ret;
+}
+
+typedef float ret_Constructarray2_array2_float__[2][2];
+ret_Constructarray2_array2_float__ Constructarray2_array2_float__(float arg0[2], float arg1[2]) {
+    float ret[2][2] = { arg0, arg1 };
+    return ret;
+}
+
+typedef int ret_Constructarray2_int_[2];
+ret_Constructarray2_int_ Constructarray2_int_(int arg0, int arg1) {
+    int ret[2] = { arg0, arg1 };
+    return ret;
+}
+
+typedef int ret_Constructarray2_array2_int__[2][2];
+ret_Constructarray2_array2_int__ Constructarray2_array2_int__(int arg0[2], int arg1[2]) {
+    int ret[2][2] = { arg0, arg1 };
+    return ret;
+}
+
+S ConstructS(float3 arg0, float arg1[2], uint arg2) {
+    S ret = (S)0;
+    ret.position = arg0;
+    ret.weights = arg1;
+    ret.count = arg2;
+    return ret;
+}
+
+static const int K = 3;
+
+static int table[3] = Constructarray3_int_(1, 2, 3);
+static float nested[2][2] = Constructarray2_array2_float__(Constructarray2_float_(1.0, 2.0), (float[2])0);
+static int grid[2][2] = Constructarray2_array2_int__(Constructarray2_int_(1, 2), Constructarray2_int_(3, 4));
+static float3 zero_vec = (float3)0;
+static S zero_struct = (S)0;
+static S s = ConstructS((1.0).xxx, Constructarray2_float_(0.5, 3.0), 4u);
+static uint counter = (uint)0;
+static S origin = (S)0;
+
+[numthreads(1, 1, 1)]
+void main()
+{
+    int _expr3 = table[2];
+    uint _expr7 = s.count;
+    uint _expr9 = counter;
+    counter = (_expr9 + (uint(_expr3) + _expr7));
+    float3 _expr14 = zero_vec;
+    float3 _expr17 = zero_struct.position;
+    float3 _expr21 = s.position;
+    origin.position = ((_expr14 + _expr17) + _expr21);
+    float _expr27[2] = nested[0];
+    origin.weights = _expr27;
+    int _expr33 = grid[1][1];
+    origin.count = uint(_expr33);
+    return;
+}
@@ -0,0 +1,30 @@
+struct Values {
+    float scalar;
+    int _pad1_0;
+    float2 pair;
+    float3 triple;
+    int _pad3_0;
+    float4 quad;
+    float2 rounded;
+    int _end_pad_0;
+    int _end_pad_1;
+};
+
+static const float2 ROUNDED = float2(1.0, 65504.0);
+
+RWByteAddressBuffer values : register(u0);
+
+[numthreads(1, 1, 1)]
+void main()
+{
+    float _expr4 = asfloat(values.Load(0));
+    values.Store(0, asuint(f16tof32(f32tof16(_expr4))));
+    float2 _expr10 = asfloat(values.Load2(8));
+    values.Store2(8, asuint(f16tof32(f32tof16(_expr10))));
+    float3 _expr16 = asfloat(values.Load3(16));
+    values.Store3(16, asuint(f16tof32(f32tof16(_expr16))));
+    float4 _expr22 = asfloat(values.Load4(32));
+    values.Store4(32, asuint(f16tof32(f32tof16(_expr22))));
+    values.Store2(48, asuint(ROUNDED));
+    return;
+}
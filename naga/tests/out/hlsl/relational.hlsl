@@ -0,0 +1,89 @@
+struct Inputs {
+    float4 a;
+    float4 b;
+    int4 i;
+    uint4 u;
+};
+
+cbuffer inputs : register(b0) { Inputs inputs; }
+
+bool all_any(bool2 c2_, bool3 c3_, bool4 c4_)
+{
+    return (((((((all(c2_) && any(c2_)) && all(c3_)) && any(c3_)) && all(c4_)) && any(c4_)) && c4_.x) && c4_.y);
+}
+
+float4 select_f32_(bool2 c2_1, bool3 c3_1, bool4 c4_1)
+{
+    float4 _expr5 = inputs.a;
+    float4 _expr9 = inputs.b;
+    float2 f2_ = (c2_1 ? _expr9.xy : _expr5.xy);
+    float4 _expr14 = inputs.a;
+    float4 _expr18 = inputs.b;
+    float3 f3_ = (c3_1 ? _expr18.xyz : _expr14.xyz);
+    float4 _expr23 = inputs.a;
+    float4 _expr26 = inputs.b;
+    float4 f4_ = (c4_1 ? _expr26 : _expr23);
+    return ((float4(f2_, f2_) + float4(f3_, 1.0)) + f4_);
+}
+
+int4 select_i32_(bool2 c2_2, bool3 c3_2, bool4 c4_2)
+{
+    int4 _expr5 = inputs.i;
+    int4 _expr9 = inputs.i;
+    int2 i2_ = (c2_2 ? -(_expr9.yx) : _expr5.xy);
+    int4 _expr15 = inputs.i;
+    int4 _expr19 = inputs.i;
+    int3 i3_ = (c3_2 ? -(_expr19.zyx) : _expr15.xyz);
+    int4 _expr25 = inputs.i;
+    int4 _expr28 = inputs.i;
+    int4 i4_ = (c4_2 ? -(_expr28) : _expr25);
+    return ((int4(i2_, i2_) + int4(i3_, 1)) + i4_);
+}
+
+uint4 select_u32_(bool2 c2_3, bool3 c3_3, bool4 c4_3)
+{
+    uint4 _expr5 = inputs.u;
+    uint4 _expr9 = inputs.u;
+    uint2 u2_ = (c2_3 ? _expr9.yx : _expr5.xy);
+    uint4 _expr14 = inputs.u;
+    uint4 _expr18 = inputs.u;
+    uint3 u3_ = (c3_3 ? _expr18.zyx : _expr14.xyz);
+    uint4 _expr23 = inputs.u;
+    uint4 _expr26 = inputs.u;
+    uint4 u4_ = (c4_3 ? _expr26.wzyx : _expr23);
+    return ((uint4(u2_, u2_) + uint4(u3_, 1u)) + u4_);
+}
+
+bool select_bool(bool2 c2_4, bool3 c3_4, bool4 c4_4)
+{
+    bool2 b2_ = (c2_4.yx ? !(c2_4) : c2_4);
+    bool3 b3_ = (c3_4.zxy ? !(c3_4) : c3_4);
+    bool4 b4_ = (c4_4.wzyx ? !(c4_4) : c4_4);
+    return ((any(b2_) && any(b3_)) && any(b4_));
+}
+
+float4 main() : SV_Target0
+{
+    float4 color = (float4)0;
+
+    float4 _expr2 = inputs.a;
+    float4 _expr5 = inputs.b;
+    bool4 c4_5 = (_expr2 < _expr5);
+    bool3 c3_5 = c4_5.xyz;
+    bool2 c2_5 = c4_5.xy;
+    const float4 _e9 = select_f32_(c2_5, c3_5, c4_5);
+    color = _e9;
+    const int4 _e11 = select_i32_(c2_5, c3_5, c4_5);
+    float4 _expr13 = color;
+    color = (_expr13 + float4(_e11));
+    const uint4 _e15 = select_u32_(c2_5, c3_5, c4_5);
+    float4 _expr17 = color;
+    color = (_expr17 + float4(_e15));
+    const bool _e19 = all_any(c2_5, c3_5, c4_5);
+    const bool _e20 = select_bool(c2_5, c3_5, c4_5);
+    if ((_e19 || _e20)) {
+        color.w = 0.0;
+    }
+    float4 _expr24 = color;
+    return _expr24;
+}
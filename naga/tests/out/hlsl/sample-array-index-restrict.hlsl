@@ -0,0 +1,34 @@
+Texture2DArray<float4> image_2d_array : register(t0);
+TextureCubeArray<float4> image_cube_array : register(t1);
+SamplerState samp : register(s2);
+
+struct FragmentInput_main {
+    float2 uv_1 : LOC0;
+    nointerpolation int layer_1 : LOC1;
+};
+
+uint NagaNumLayers2DArray(Texture2DArray<float4> tex)
+{
+    uint4 ret;
+    tex.GetDimensions(0, ret.x, ret.y, ret.z, ret.w);
+    return ret.z;
+}
+
+uint NagaNumLayersCubeArray(TextureCubeArray<float4> tex)
+{
+    uint4 ret;
+    tex.GetDimensions(0, ret.x, ret.y, ret.z, ret.w);
+    return ret.z;
+}
+
+float4 main(FragmentInput_main fragmentinput_main) : SV_Target0
+{
+    float2 uv = fragmentinput_main.uv_1;
+    int layer = fragmentinput_main.layer_1;
+    float3 dir = float3(uv, 1.0);
+    float4 a = image_2d_array.Sample(samp, float3(uv, clamp(layer, 0, int(NagaNumLayers2DArray(image_2d_array)) - 1)));
+    float4 b = image_2d_array.Sample(samp, float3(uv, min(uint(layer), NagaNumLayers2DArray(image_2d_array) - 1)));
+    float4 c = image_cube_array.SampleLevel(samp, float4(dir, clamp(layer, 0, int(NagaNumLayersCubeArray(image_cube_array)) - 1)), 0.0);
+    float4 d = image_cube_array.SampleLevel(samp, float4(dir, min(uint(layer), NagaNumLayersCubeArray(image_cube_array) - 1)), 0.0);
+    return (((a + b) + c) + d);
+}
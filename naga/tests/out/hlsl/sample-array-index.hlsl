@@ -0,0 +1,20 @@
+Texture2DArray<float4> image_2d_array : register(t0);
+TextureCubeArray<float4> image_cube_array : register(t1);
+SamplerState samp : register(s2);
+
+struct FragmentInput_main {
+    float2 uv_1 : LOC0;
+    nointerpolation int layer_1 : LOC1;
+};
+
+float4 main(FragmentInput_main fragmentinput_main) : SV_Target0
+{
+    float2 uv = fragmentinput_main.uv_1;
+    int layer = fragmentinput_main.layer_1;
+    float3 dir = float3(uv, 1.0);
+    float4 a = image_2d_array.Sample(samp, float3(uv, layer));
+    float4 b = image_2d_array.Sample(samp, float3(uv, uint(layer)));
+    float4 c = image_cube_array.SampleLevel(samp, float4(dir, layer), 0.0);
+    float4 d = image_cube_array.SampleLevel(samp, float4(dir, uint(layer)), 0.0);
+    return (((a + b) + c) + d);
+}
@@ -0,0 +1,19 @@
+static const float ONE = 1.0;
+
+struct FragmentInput_main {
+    float x_1 : LOC0;
+    float3 v_1 : LOC1;
+};
+
+float4 main(FragmentInput_main fragmentinput_main) : SV_Target0
+{
+    float x = fragmentinput_main.x_1;
+    float3 v = fragmentinput_main.v_1;
+    float a = saturate(x);
+    float3 b = saturate(v);
+    float c = saturate(x);
+    float d = saturate(x);
+    float e = clamp(x, 0.0, 2.0);
+    float3 f = clamp(v, (0.0).xxx, float3(1.0, 1.0, 0.5));
+    return float4((b + f), (((a + c) + d) + e));
+}
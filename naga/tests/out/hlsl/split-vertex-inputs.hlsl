@@ -0,0 +1,52 @@
+struct Instance {
+    row_major float4x4 transform : LOC1;
+    float weights[2] : LOC5;
+    float4 color : LOC7;
+};
+
+struct VertexOutput {
+    float4 position : SV_Position;
+    float4 color : LOC0;
+};
+
+struct VertexInput_main {
+    float3 position_1 : LOC0;
+    float4 transform_0_ : LOC1;
+    float4 transform_1_ : LOC2;
+    float4 transform_2_ : LOC3;
+    float4 transform_3_ : LOC4;
+    float weights_0_ : LOC5;
+    float weights_1_ : LOC6;
+    float4 color : LOC7;
+    float3 normal_matrix_1_0_ : LOC8;
+    float3 normal_matrix_1_1_ : LOC9;
+    float3 normal_matrix_1_2_ : LOC10;
+    uint2 joints_1_0_ : LOC11;
+    uint2 joints_1_1_ : LOC12;
+};
+
+struct VertexOutput_main {
+    float4 color_1 : LOC0;
+    float4 position_2 : SV_Position;
+};
+
+VertexOutput ConstructVertexOutput(float4 arg0, float4 arg1) {
+    VertexOutput ret = (VertexOutput)0;
+    ret.position = arg0;
+    ret.color = arg1;
+    return ret;
+}
+
+VertexOutput_main main(VertexInput_main vertexinput_main)
+{
+    float3 position = vertexinput_main.position_1;
+    Instance instance = { float4x4(vertexinput_main.transform_0_, vertexinput_main.transform_1_, vertexinput_main.transform_2_, vertexinput_main.transform_3_), { vertexinput_main.weights_0_, vertexinput_main.weights_1_ }, vertexinput_main.color };
+    float3x3 normal_matrix = float3x3(vertexinput_main.normal_matrix_1_0_, vertexinput_main.normal_matrix_1_1_, vertexinput_main.normal_matrix_1_2_);
+    uint2 joints[2] = { vertexinput_main.joints_1_0_, vertexinput_main.joints_1_1_ };
+    float3 normal = mul(position, normal_matrix);
+    float4 world = mul(float4((position + normal), 1.0), instance.transform);
+    float weight = ((instance.weights[0] * float(joints[0].x)) + (instance.weights[1] * float(joints[1].y)));
+    const VertexOutput vertexoutput = ConstructVertexOutput(world, (instance.color * weight));
+    const VertexOutput_main vertexoutput_1 = { vertexoutput.color, vertexoutput.position };
+    return vertexoutput_1;
+}
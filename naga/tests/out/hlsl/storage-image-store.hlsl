@@ -0,0 +1,21 @@
+RWTexture2D<unorm float4> rgba8unorm : register(u0);
+RWTexture2D<uint4> rgba8uint : register(u1);
+RWTexture2D<float3> rg11b10float : register(u2);
+RWTexture3D<float4> r32float_3d : register(u3);
+RWTexture2DArray<float2> rg16float_array : register(u4);
+RWTexture1D<uint> r16uint_1d : register(u5);
+
+[numthreads(1, 1, 1)]
+void main(uint3 id : SV_DispatchThreadID)
+{
+    float4 color = float4(0.25, 0.5, 0.75, 1.0);
+    rgba8unorm[id.xy] = color;
+    rgba8uint[id.xy] = uint4(id, 255u);
+    rg11b10float[id.xy] = color.xyz;
+    r32float_3d[id] = (color.x).xxxx;
+    rg16float_array[int3(id.xy, id.z)] = color.xy;
+    r16uint_1d[id.x] = ((id.x).xxxx).x;
+    rg16float_array[int3(id.xy, 0)] = ((float4)0).xy;
+    rg11b10float[id.xy] = ((color * 2.0)).xyz;
+    return;
+}
@@ -1,6 +1,12 @@
+struct Params {
+    uint count;
+    float scale;
+};
+
 static const uint SIZE = 128u;
 
 groupshared int arr_i32_[128];
+groupshared Params params;
 
 [numthreads(4, 1, 1)]
 void test_workgroupUniformLoad(uint3 workgroup_id : SV_GroupID, uint3 __local_invocation_id : SV_GroupThreadID)
@@ -19,3 +25,44 @@ void test_workgroupUniformLoad(uint3 workgroup_id : SV_GroupID, uint3 __local_in
         return;
     }
 }
+
+Params ConstructParams(uint arg0, float arg1) {
+    Params ret = (Params)0;
+    ret.count = arg0;
+    ret.scale = arg1;
+    return ret;
+}
+
+[numthreads(4, 1, 1)]
+void test_workgroupUniformLoad_struct(uint index : SV_GroupIndex, uint3 __local_invocation_id : SV_GroupThreadID)
+{
+    if (all(__local_invocation_id == uint3(0u, 0u, 0u))) {
+        params = (Params)0;
+    }
+    GroupMemoryBarrierWithGroupSync();
+    uint i = 0u;
+
+    if ((index == 0u)) {
+        params = ConstructParams(4u, 2.0);
+    }
+    GroupMemoryBarrierWithGroupSync();
+    Params _expr8 = params;
+    GroupMemoryBarrierWithGroupSync();
+    bool loop_init = true;
+    while(true) {
+        if (!loop_init) {
+            uint _expr15 = i;
+            i = (_expr15 + 1u);
+        }
+        loop_init = false;
+        uint _expr11 = i;
+        if ((_expr11 < _expr8.count)) {
+        } else {
+            break;
+        }
+        {
+            GroupMemoryBarrierWithGroupSync();
+        }
+    }
+    return;
+}
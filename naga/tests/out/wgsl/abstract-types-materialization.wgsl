@@ -0,0 +1,52 @@
+var<workgroup> counter: atomic<u32>;
+
+fn takes_f32_(x: f32) -> f32 {
+    return x;
+}
+
+fn takes_u32_vec(x_1: vec2<u32>, y: u32) -> u32 {
+    return (x_1.x + y);
+}
+
+fn return_f32_() -> f32 {
+    return 1f;
+}
+
+fn return_u32_vec() -> vec2<u32> {
+    return vec2<u32>(1u, 2u);
+}
+
+fn return_f32_array() -> array<f32, 2> {
+    return array<f32, 2>(1f, 2.5f);
+}
+
+@compute @workgroup_size(1, 1, 1) 
+fn main() {
+    var h: f32;
+    var i: vec2<u32>;
+    var j: array<f32, 2>;
+
+    let c = vec3<f32>(1f, 2.5f, 3f);
+    let d = array<u32, 3>(1u, 2u, 3u);
+    let f = array<f32, 3>(1f, 2f, 3f);
+    let g = vec2<f32>(1.5f, 2.5f);
+    h = 2f;
+    let _e21 = h;
+    h = (_e21 + 1f);
+    let _e24 = h;
+    h = (_e24 * 2f);
+    i = vec2<u32>(1u, 2u);
+    i.x = 3u;
+    i.y = 4u;
+    let _e36 = i;
+    i = (_e36 << vec2(1u));
+    j = array<f32, 2>(1f, 2f);
+    let _e43 = takes_f32_(1f);
+    let _e48 = takes_u32_vec(vec2<u32>(1u, 2u), 3u);
+    let p = extractBits(5u, 0u, 8u);
+    let q = select(1f, 2f, true);
+    let r = dot(vec2<f32>(1f, 2f), vec2<f32>(3f, 4f));
+    let _e69 = atomicAdd((&counter), 1u);
+    atomicStore((&counter), 2u);
+    return;
+}
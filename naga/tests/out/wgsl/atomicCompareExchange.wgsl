@@ -88,3 +88,13 @@ fn test_atomic_compare_exchange_u32_() {
     }
     return;
 }
+
+@compute @workgroup_size(1, 1, 1) 
+fn test_atomic_compare_exchange_computed() {
+    var expected: u32 = 1u;
+
+    let _e4 = expected;
+    let _e8 = atomicCompareExchangeWeak((&arr_u32_[0]), (_e4 * 2u), 0u);
+    expected = _e8.old_value;
+    return;
+}
@@ -0,0 +1,27 @@
+struct Item {
+    value: u32,
+    counter: atomic<u32>,
+    balance: atomic<i32>,
+}
+
+@group(0) @binding(0) 
+var<storage, read_write> items: array<Item>;
+var<workgroup> workgroup_counter: atomic<u32>;
+
+@compute @workgroup_size(64, 1, 1) 
+fn cs_main(@builtin(local_invocation_index) i: u32) {
+    atomicStore((&items[i].counter), 1u);
+    let loaded = atomicLoad((&items[i].counter));
+    let _e13 = atomicAdd((&items[i].counter), 1u);
+    let _e18 = atomicSub((&items[i].balance), 1i);
+    let _e23 = atomicAnd((&items[i].counter), 1u);
+    let _e28 = atomicOr((&items[i].balance), 1i);
+    let _e33 = atomicXor((&items[i].counter), 1u);
+    let _e38 = atomicMin((&items[i].balance), 1i);
+    let _e43 = atomicMax((&items[i].counter), 1u);
+    let _e48 = atomicExchange((&items[i].balance), 1i);
+    let _e53 = atomicCompareExchangeWeak((&items[i].counter), loaded, 2u);
+    let _e57 = atomicCompareExchangeWeak((&workgroup_counter), 0u, 1u);
+    items[i].value = (((((((((_e13 + u32(_e18)) + _e23) + u32(_e28)) + _e33) + u32(_e38)) + _e43) + u32(_e48)) + _e53.old_value) + _e57.old_value);
+    return;
+}
@@ -6,6 +6,11 @@ struct Foo {
     x: u32,
 }
 
+struct Bar {
+    foo: Foo,
+    y: u32,
+}
+
 struct FragmentIn {
     @location(0) @interpolate(flat) index: u32,
 }
@@ -14,6 +19,13 @@ struct FragmentIn {
 var<storage> storage_array: binding_array<Foo, 1>;
 @group(0) @binding(10) 
 var<uniform> uni: UniformIndex;
+@group(0) @binding(11) 
+var<uniform> uniform_foo: Foo;
+@group(0) @binding(12) 
+var<storage, read_write> storage_foo: Foo;
+@group(0) @binding(13) 
+var<storage, read_write> storage_array_rw: binding_array<Foo, 1>;
+var<private> bar: Bar;
 
 @fragment 
 fn main(fragment_in: FragmentIn) -> @location(0) @interpolate(flat) u32 {
@@ -30,6 +42,16 @@ fn main(fragment_in: FragmentIn) -> @location(0) @interpolate(flat) u32 {
     let _e22 = storage_array[non_uniform_index].x;
     let _e23 = u1_;
     u1_ = (_e23 + _e22);
-    let _e25 = u1_;
-    return _e25;
+    let foo = storage_array[uniform_index];
+    bar.foo = foo;
+    let _e32 = uniform_foo;
+    storage_foo = _e32;
+    let _e37 = bar.foo;
+    storage_array_rw[0] = _e37;
+    let _e41 = bar.foo.x;
+    let _e44 = storage_foo.x;
+    let _e46 = u1_;
+    u1_ = (_e46 + (_e41 + _e44));
+    let _e48 = u1_;
+    return _e48;
 }
@@ -0,0 +1,37 @@
+struct Pair {
+    v: vec4<f32>,
+    s: f32,
+}
+
+var<private> invarVALUE_1: vec4<f32>;
+var<private> outvarSV_Target: vec4<f32>;
+
+fn main_1() {
+    var local: Pair;
+    var local_1: array<f32, 2>;
+
+    let _e5 = invarVALUE_1;
+    let _e7 = (_e5.x > 0f);
+    if _e7 {
+        local = Pair(_e5, _e5.x);
+    } else {
+        local = Pair(vec4<f32>(0f, 0f, 0f, 0f), 1f);
+    }
+    let _e11 = local;
+    if _e7 {
+        local_1 = array<f32, 2>(_e5.x, 1f);
+    } else {
+        local_1 = array<f32, 2>(1f, _e5.x);
+    }
+    let _e15 = local_1;
+    outvarSV_Target = ((_e11.v.wxyx + vec4<f32>(_e11.v.x, _e5.y, _e11.v.x, _e5.z)) + vec4<f32>(_e11.s, _e15[1], _e11.s, _e15[1]));
+    return;
+}
+
+@fragment 
+fn main(@location(0) invarVALUE: vec4<f32>) -> @location(0) vec4<f32> {
+    invarVALUE_1 = invarVALUE;
+    main_1();
+    let _e3 = outvarSV_Target;
+    return _e3;
+}
@@ -0,0 +1,19 @@
+@fragment 
+fn fs_greater(@builtin(position) position: vec4<f32>) -> @builtin(frag_depth) f32 {
+    return (position.z + 0.1f);
+}
+
+@fragment 
+fn fs_less(@builtin(position) position_1: vec4<f32>) -> @builtin(frag_depth) f32 {
+    return (position_1.z - 0.1f);
+}
+
+@fragment 
+fn fs_unchanged(@builtin(position) position_2: vec4<f32>) -> @builtin(frag_depth) f32 {
+    return position_2.z;
+}
+
+@fragment 
+fn fs_no_write(@builtin(position) position_3: vec4<f32>) -> @location(0) vec4<f32> {
+    return position_3;
+}
@@ -0,0 +1,66 @@
+const SELECTOR: i32 = 2i;
+const ENABLED: bool = false;
+
+var<private> x: i32;
+
+fn constant_if() {
+    if true {
+        x = 1i;
+    } else {
+        x = 2i;
+    }
+    if ENABLED {
+        x = 3i;
+    }
+    if false {
+        x = 4i;
+        return;
+    } else {
+        x = 5i;
+        return;
+    }
+}
+
+fn constant_switch() {
+    switch SELECTOR {
+        case 1: {
+            x = 1i;
+        }
+        case 2, 3: {
+            x = 2i;
+            break;
+        }
+        default: {
+            x = 3i;
+        }
+    }
+    switch 7u {
+        case 0u: {
+            x = 4i;
+            return;
+        }
+        default: {
+            x = 5i;
+            return;
+        }
+    }
+}
+
+fn constant_if_in_loop() {
+    loop {
+        if true {
+            break;
+        }
+        let _e3 = x;
+        x = (_e3 + 1i);
+    }
+    return;
+}
+
+@compute @workgroup_size(1, 1, 1) 
+fn main() {
+    constant_if();
+    constant_switch();
+    constant_if_in_loop();
+    return;
+}
@@ -0,0 +1,101 @@
+enable naga_control_flow_hints;
+
+@group(0) @binding(0) 
+var<storage, read_write> values: array<f32, 16>;
+
+@compute @workgroup_size(1, 1, 1) 
+fn main(@builtin(local_invocation_index) index: u32) {
+    var sum: f32 = 0f;
+    var i: u32 = 0u;
+    var i_1: u32 = 0u;
+    var j: u32 = 0u;
+
+    @unroll loop {
+        let _e5 = i;
+        if (_e5 < 4u) {
+        } else {
+            break;
+        }
+        {
+            let _e9 = i;
+            let _e11 = values[_e9];
+            let _e12 = sum;
+            sum = (_e12 + _e11);
+        }
+        continuing {
+            let _e15 = i;
+            i = (_e15 + 1u);
+        }
+    }
+    @dont_unroll loop {
+        let _e19 = i_1;
+        if (_e19 < index) {
+        } else {
+            break;
+        }
+        {
+            let _e22 = i_1;
+            let _e24 = values[_e22];
+            let _e25 = sum;
+            sum = (_e25 + _e24);
+        }
+        continuing {
+            let _e28 = i_1;
+            i_1 = (_e28 + 1u);
+        }
+    }
+    @unroll loop {
+        let _e32 = j;
+        if (_e32 < 4u) {
+        } else {
+            break;
+        }
+        {
+            let _e36 = j;
+            let _e38 = values[_e36];
+            let _e39 = sum;
+            sum = (_e39 * _e38);
+            let _e42 = j;
+            j = (_e42 + 1u);
+        }
+    }
+    @dont_unroll loop {
+        let _e44 = sum;
+        if (_e44 > 100f) {
+            break;
+        }
+        let _e48 = sum;
+        sum = (_e48 * 2f);
+        continuing {
+            let _e50 = sum;
+            break if (_e50 == 0f);
+        }
+    }
+    @branch if (index == 0u) {
+        let _e57 = values[15];
+        sum = _e57;
+    } else {
+        if (index == 1u) {
+            let _e62 = values[14];
+            sum = _e62;
+        }
+    }
+    let _e63 = sum;
+    @flatten if (_e63 < 0f) {
+        let _e66 = sum;
+        sum = -(_e66);
+    } else {
+        let _e68 = sum;
+        sum = (_e68 * 0.5f);
+    }
+    let _e71 = sum;
+    if (_e71 > 1f) {
+        let _e74 = sum;
+        @flatten if (_e74 > 2f) {
+            sum = 2f;
+        }
+    }
+    let _e80 = sum;
+    values[index] = _e80;
+    return;
+}
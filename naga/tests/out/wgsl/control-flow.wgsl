@@ -30,6 +30,89 @@ fn loop_switch_continue(x: i32) {
     return;
 }
 
+fn loop_switch_break(x_1: i32) -> i32 {
+    var i_1: i32 = 0i;
+
+    loop {
+        switch x_1 {
+            case 1: {
+                break;
+            }
+            default: {
+            }
+        }
+        let _e4 = i_1;
+        i_1 = (_e4 + 1i);
+        let _e6 = i_1;
+        if (_e6 > 4i) {
+            break;
+        }
+    }
+    let _e9 = i_1;
+    return _e9;
+}
+
+fn loop_switch_continue_if(x_2: i32) {
+    var i_2: i32 = 0i;
+
+    loop {
+        switch x_2 {
+            case 1: {
+                let _e3 = i_2;
+                if (_e3 == 2i) {
+                    continue;
+                }
+                break;
+            }
+            default: {
+            }
+        }
+        continuing {
+            let _e7 = i_2;
+            i_2 = (_e7 + 1i);
+            let _e9 = i_2;
+            break if (_e9 > 4i);
+        }
+    }
+    return;
+}
+
+fn switch_loop_break(x_3: i32) {
+    switch x_3 {
+        case 1: {
+            loop {
+                break;
+            }
+            return;
+        }
+        default: {
+            return;
+        }
+    }
+}
+
+fn switch_loop_continue(x_4: i32) {
+    var i_3: i32 = 0i;
+
+    switch x_4 {
+        case 1: {
+            loop {
+                let _e4 = i_3;
+                i_3 = (_e4 + 1i);
+                let _e6 = i_3;
+                if (_e6 < 4i) {
+                    continue;
+                }
+                break;
+            }
+            return;
+        }
+        default: {
+            return;
+        }
+    }
+}
+
 @compute @workgroup_size(1, 1, 1) 
 fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
     var pos: i32;
@@ -0,0 +1,32 @@
+@group(0) @binding(0) 
+var<storage, read_write> data: array<u32>;
+
+@compute @workgroup_size(1, 1, 1) 
+fn main() {
+    var sum: u32 = 0u;
+
+    //@marker setup
+    //@marker accumulate
+    loop {
+        //@marker loop body
+        let _e2 = sum;
+        if (_e2 > 10u) {
+            break;
+        }
+        let _e6 = sum;
+        let _e8 = data[_e6];
+        let _e9 = sum;
+        sum = (_e9 + _e8);
+        continuing {
+            //@marker continuing
+            let _e12 = sum;
+            sum = (_e12 + 1u);
+        }
+    }
+    //@marker path C:\temp\
+    let _e16 = sum;
+    data[0] = _e16;
+    //@marker
+    //@marker done
+    return;
+}
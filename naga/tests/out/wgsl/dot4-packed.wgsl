@@ -0,0 +1,12 @@
+@group(0) @binding(0) 
+var<storage, read_write> values: array<u32, 5>;
+
+@compute @workgroup_size(1, 1, 1) 
+fn main() {
+    let a = values[0];
+    let b = values[1];
+    values[2] = bitcast<u32>(dot4I8Packed(a, b));
+    values[3] = dot4U8Packed((a + 1u), b);
+    values[4] = (bitcast<u32>(512i) + 2550u);
+    return;
+}
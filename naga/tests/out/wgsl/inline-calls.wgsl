@@ -0,0 +1,207 @@
+struct Particle {
+    position: vec2<f32>,
+    velocity: vec2<f32>,
+}
+
+@group(0) @binding(0) 
+var<storage, read_write> particles: array<Particle>;
+
+fn square(x: f32) -> f32 {
+    return (x * x);
+}
+
+fn length_squared(v: vec2<f32>) -> f32 {
+    var square_result_3: f32;
+    var square_result_4: f32;
+
+    {
+        {
+            square_result_3 = (v.x * v.x);
+        }
+    }
+    let _e4 = square_result_3;
+    {
+        {
+            square_result_4 = (v.y * v.y);
+        }
+    }
+    let _e8 = square_result_4;
+    return (_e4 + _e8);
+}
+
+fn first_negative(v_1: vec4<f32>) -> i32 {
+    var i_2: i32 = 0i;
+
+    loop {
+        let _e3 = i_2;
+        if (_e3 < 4i) {
+        } else {
+            break;
+        }
+        {
+            let _e6 = i_2;
+            if (v_1[_e6] < 0f) {
+                let _e10 = i_2;
+                return _e10;
+            }
+        }
+        continuing {
+            let _e12 = i_2;
+            i_2 = (_e12 + 1i);
+        }
+    }
+    return -1i;
+}
+
+fn step(p: ptr<function, Particle>, dt: f32) {
+    var square_result_5: f32;
+    var square_result_6: f32;
+    var length_squared_result_1: f32;
+
+    let _e4 = (*p).velocity;
+    let _e6 = (*p).position;
+    (*p).position = (_e6 + (_e4 * dt));
+    let _e9 = (*p).velocity;
+    {
+        {
+            {
+                {
+                    square_result_5 = (_e9.x * _e9.x);
+                }
+            }
+            let _e13 = square_result_5;
+            {
+                {
+                    square_result_6 = (_e9.y * _e9.y);
+                }
+            }
+            let _e17 = square_result_6;
+            length_squared_result_1 = (_e13 + _e17);
+        }
+    }
+    let _e20 = length_squared_result_1;
+    if (_e20 > 1f) {
+        let _e25 = (*p).velocity;
+        (*p).velocity = normalize(_e25);
+        return;
+    } else {
+        return;
+    }
+}
+
+@compute @workgroup_size(64, 1, 1) 
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    var total: f32 = 0f;
+    var i: u32 = 0u;
+    var particle: Particle;
+    var i_1: i32 = 0i;
+    var first_negative_result: i32;
+    var returned: bool;
+    var square_result: f32;
+    var square_result_1: f32;
+    var square_result_2: f32;
+    var length_squared_result: f32;
+
+    loop {
+        let _e5 = i;
+        if (_e5 < 2u) {
+        } else {
+            break;
+        }
+        {
+            let _e10 = i;
+            let _e13 = particles[(id.x + _e10)];
+            particle = _e13;
+            {
+                square_result_1 = f32();
+                square_result_2 = f32();
+                length_squared_result = f32();
+                loop {
+                    let _e70 = particle.velocity;
+                    let _e72 = particle.position;
+                    particle.position = (_e72 + (_e70 * 0.5f));
+                    let _e75 = particle.velocity;
+                    {
+                        {
+                            {
+                                {
+                                    square_result_1 = (_e75.x * _e75.x);
+                                }
+                            }
+                            let _e79 = square_result_1;
+                            {
+                                {
+                                    square_result_2 = (_e75.y * _e75.y);
+                                }
+                            }
+                            let _e83 = square_result_2;
+                            length_squared_result = (_e79 + _e83);
+                        }
+                    }
+                    let _e86 = length_squared_result;
+                    if (_e86 > 1f) {
+                        let _e91 = particle.velocity;
+                        particle.velocity = normalize(_e91);
+                        break;
+                    } else {
+                        break;
+                    }
+                    break;
+                }
+            }
+            let _e18 = i;
+            let _e21 = particle;
+            particles[(id.x + _e18)] = _e21;
+            let _e26 = particles[id.x].position;
+            {
+                i_1 = 0i;
+                returned = false;
+                loop {
+                    loop {
+                        let _e32 = i_1;
+                        if (_e32 < 4i) {
+                        } else {
+                            break;
+                        }
+                        {
+                            let _e35 = i_1;
+                            if (vec4<f32>(_e26, -1f, 2f)[_e35] < 0f) {
+                                let _e39 = i_1;
+                                first_negative_result = _e39;
+                                returned = true;
+                                break;
+                            }
+                        }
+                        continuing {
+                            let _e41 = i_1;
+                            i_1 = (_e41 + 1i);
+                        }
+                    }
+                    let _e50 = returned;
+                    if _e50 {
+                        break;
+                    }
+                    first_negative_result = -1i;
+                    break;
+                }
+            }
+            let _e51 = first_negative_result;
+            let _e53 = total;
+            total = (_e53 + f32(_e51));
+        }
+        continuing {
+            let _e56 = i;
+            i = (_e56 + 1u);
+        }
+    }
+    let _e62 = total;
+    let _e63 = total;
+    {
+        {
+            square_result = (_e63 * _e63);
+        }
+    }
+    let _e66 = square_result;
+    particles[id.x].velocity = vec2<f32>(_e62, _e66);
+    return;
+}
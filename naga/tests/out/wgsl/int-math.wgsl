@@ -0,0 +1,109 @@
+@compute @workgroup_size(1, 1, 1) 
+fn main() {
+    var i: i32 = 0i;
+    var i3_: vec3<i32> = vec3(0i);
+    var u: u32 = 0u;
+    var u3_: vec3<u32> = vec3(0u);
+
+    let _e10 = i;
+    i = abs(_e10);
+    let _e12 = i3_;
+    i3_ = abs(_e12);
+    let _e14 = u;
+    u = abs(_e14);
+    let _e16 = u3_;
+    u3_ = abs(_e16);
+    let _e18 = i;
+    i = sign(_e18);
+    let _e20 = i3_;
+    i3_ = sign(_e20);
+    let _e22 = i;
+    let _e23 = i;
+    i = min(_e22, _e23);
+    let _e25 = i3_;
+    let _e26 = i3_;
+    i3_ = min(_e25, _e26);
+    let _e28 = u;
+    let _e29 = u;
+    u = min(_e28, _e29);
+    let _e31 = u3_;
+    let _e32 = u3_;
+    u3_ = min(_e31, _e32);
+    let _e34 = i;
+    let _e35 = i;
+    i = max(_e34, _e35);
+    let _e37 = i3_;
+    let _e38 = i3_;
+    i3_ = max(_e37, _e38);
+    let _e40 = u;
+    let _e41 = u;
+    u = max(_e40, _e41);
+    let _e43 = u3_;
+    let _e44 = u3_;
+    u3_ = max(_e43, _e44);
+    let _e46 = i;
+    let _e47 = i;
+    let _e48 = i;
+    i = clamp(_e46, _e47, _e48);
+    let _e50 = i3_;
+    let _e51 = i3_;
+    let _e52 = i3_;
+    i3_ = clamp(_e50, _e51, _e52);
+    let _e54 = u;
+    let _e55 = u;
+    let _e56 = u;
+    u = clamp(_e54, _e55, _e56);
+    let _e58 = u3_;
+    let _e59 = u3_;
+    let _e60 = u3_;
+    u3_ = clamp(_e58, _e59, _e60);
+    let _e62 = i;
+    i = countOneBits(_e62);
+    let _e64 = i3_;
+    i3_ = countOneBits(_e64);
+    let _e66 = u;
+    u = countOneBits(_e66);
+    let _e68 = u3_;
+    u3_ = countOneBits(_e68);
+    let _e70 = i;
+    i = reverseBits(_e70);
+    let _e72 = i3_;
+    i3_ = reverseBits(_e72);
+    let _e74 = u;
+    u = reverseBits(_e74);
+    let _e76 = u3_;
+    u3_ = reverseBits(_e76);
+    let _e78 = i;
+    i = firstLeadingBit(_e78);
+    let _e80 = i3_;
+    i3_ = firstLeadingBit(_e80);
+    let _e82 = u;
+    u = firstLeadingBit(_e82);
+    let _e84 = u3_;
+    u3_ = firstLeadingBit(_e84);
+    let _e86 = i;
+    i = firstTrailingBit(_e86);
+    let _e88 = i3_;
+    i3_ = firstTrailingBit(_e88);
+    let _e90 = u;
+    u = firstTrailingBit(_e90);
+    let _e92 = u3_;
+    u3_ = firstTrailingBit(_e92);
+    let _e94 = i;
+    i = countLeadingZeros(_e94);
+    let _e96 = i3_;
+    i3_ = countLeadingZeros(_e96);
+    let _e98 = u;
+    u = countLeadingZeros(_e98);
+    let _e100 = u3_;
+    u3_ = countLeadingZeros(_e100);
+    let _e102 = i;
+    i = countTrailingZeros(_e102);
+    let _e104 = i3_;
+    i3_ = countTrailingZeros(_e104);
+    let _e106 = u;
+    u = countTrailingZeros(_e106);
+    let _e108 = u3_;
+    u3_ = countTrailingZeros(_e108);
+    return;
+}
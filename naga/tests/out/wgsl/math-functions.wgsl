@@ -36,4 +36,9 @@ fn main() {
     let frexp_b = frexp(1.5f).fract;
     let frexp_c = frexp(1.5f).exp;
     let frexp_d = frexp(vec4<f32>(1.5f, 1.5f, 1.5f, 1.5f)).exp.x;
+    let round_a = round(v);
+    let fract_a = fract(v);
+    let trunc_a = trunc(v);
+    let floor_a = floor(v);
+    let ceil_a = ceil(v);
 }
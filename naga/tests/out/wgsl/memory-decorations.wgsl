@@ -0,0 +1,31 @@
+enable naga_memory_decorations;
+
+struct Queue {
+    head: atomic<u32>,
+    items: array<u32, 64>,
+}
+
+@group(0) @binding(0) 
+@coherent 
+var<storage, read_write> queue: Queue;
+@group(0) @binding(1) 
+@volatile 
+var<storage, read_write> flags: array<vec4<u32>, 4>;
+@group(0) @binding(2) 
+@coherent @volatile 
+var<storage, read_write> mailbox: u32;
+@group(0) @binding(3) 
+var<storage, read_write> plain: u32;
+
+@compute @workgroup_size(64, 1, 1) 
+fn main(@builtin(local_invocation_index) index: u32) {
+    let _e4 = atomicAdd((&queue.head), 1u);
+    queue.items[(_e4 % 64u)] = index;
+    let _e18 = queue.items[index];
+    flags[(index % 4u)].y = _e18;
+    let _e21 = mailbox;
+    let _e23 = plain;
+    mailbox = (_e21 + _e23);
+    plain = index;
+    return;
+}
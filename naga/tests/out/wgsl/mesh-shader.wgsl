@@ -0,0 +1,34 @@
+enable naga_mesh_shader;
+
+struct TaskOutput {
+    @builtin(mesh_task_size) size: vec3<u32>,
+}
+
+struct Vertex {
+    @builtin(position) position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+}
+
+struct Primitive {
+    @builtin(triangle_indices) indices: vec3<u32>,
+    @builtin(cull_primitive) cull: bool,
+    @location(1) @interpolate(flat) id: u32,
+}
+
+@task @workgroup_size(1, 1, 1) 
+fn ts_main(@builtin(workgroup_id) id: vec3<u32>) -> TaskOutput {
+    return TaskOutput(vec3<u32>((id.x + 1u), 1u, 1u));
+}
+
+@mesh(triangles, Vertex, 3, Primitive, 1) @workgroup_size(3, 1, 1) 
+fn ms_main(@builtin(local_invocation_index) index: u32) {
+    setMeshOutputs(3u, 1u);
+    let x = (f32(index) - 1f);
+    setVertex(index, Vertex(vec4<f32>(x, f32((index & 1u)), 0f, 1f), vec4<f32>(1f, 0f, 0f, 1f)));
+    if (index == 0u) {
+        setPrimitive(0u, Primitive(vec3<u32>(0u, 1u, 2u), false, 7u));
+        return;
+    } else {
+        return;
+    }
+}
@@ -0,0 +1,33 @@
+struct S {
+    position: vec3<f32>,
+    weights: array<f32, 2>,
+    count: u32,
+}
+
+const K: i32 = 3i;
+
+var<private> table: array<i32, 3> = array<i32, 3>(1i, 2i, 3i);
+var<private> nested: array<array<f32, 2>, 2> = array<array<f32, 2>, 2>(array<f32, 2>(1f, 2f), array<f32, 2>());
+var<private> grid: array<array<i32, 2>, 2> = array<array<i32, 2>, 2>(array<i32, 2>(1i, 2i), array<i32, 2>(3i, 4i));
+var<private> zero_vec: vec3<f32> = vec3<f32>();
+var<private> zero_struct: S = S();
+var<private> s: S = S(vec3(1f), array<f32, 2>(0.5f, 3f), 4u);
+var<private> counter: u32;
+var<private> origin: S;
+
+@compute @workgroup_size(1, 1, 1) 
+fn main() {
+    let _e3 = table[2];
+    let _e7 = s.count;
+    let _e9 = counter;
+    counter = (_e9 + (u32(_e3) + _e7));
+    let _e14 = zero_vec;
+    let _e17 = zero_struct.position;
+    let _e21 = s.position;
+    origin.position = ((_e14 + _e17) + _e21);
+    let _e27 = nested[0];
+    origin.weights = _e27;
+    let _e33 = grid[1][1];
+    origin.count = u32(_e33);
+    return;
+}
@@ -0,0 +1,26 @@
+struct Values {
+    scalar: f32,
+    pair: vec2<f32>,
+    triple: vec3<f32>,
+    quad: vec4<f32>,
+    rounded: vec2<f32>,
+}
+
+const ROUNDED: vec2<f32> = vec2<f32>(1f, 65504f);
+
+@group(0) @binding(0) 
+var<storage, read_write> values: Values;
+
+@compute @workgroup_size(1, 1, 1) 
+fn main() {
+    let _e4 = values.scalar;
+    values.scalar = quantizeToF16(_e4);
+    let _e10 = values.pair;
+    values.pair = quantizeToF16(_e10);
+    let _e16 = values.triple;
+    values.triple = quantizeToF16(_e16);
+    let _e22 = values.quad;
+    values.quad = quantizeToF16(_e22);
+    values.rounded = ROUNDED;
+    return;
+}
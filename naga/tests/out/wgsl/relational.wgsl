@@ -0,0 +1,85 @@
+struct Inputs {
+    a: vec4<f32>,
+    b: vec4<f32>,
+    i: vec4<i32>,
+    u: vec4<u32>,
+}
+
+@group(0) @binding(0) 
+var<uniform> inputs: Inputs;
+
+fn all_any(c2_: vec2<bool>, c3_: vec3<bool>, c4_: vec4<bool>) -> bool {
+    return (((((((all(c2_) && any(c2_)) && all(c3_)) && any(c3_)) && all(c4_)) && any(c4_)) && c4_.x) && c4_.y);
+}
+
+fn select_f32_(c2_1: vec2<bool>, c3_1: vec3<bool>, c4_1: vec4<bool>) -> vec4<f32> {
+    let _e5 = inputs.a;
+    let _e9 = inputs.b;
+    let f2_ = select(_e5.xy, _e9.xy, c2_1);
+    let _e14 = inputs.a;
+    let _e18 = inputs.b;
+    let f3_ = select(_e14.xyz, _e18.xyz, c3_1);
+    let _e23 = inputs.a;
+    let _e26 = inputs.b;
+    let f4_ = select(_e23, _e26, c4_1);
+    return ((vec4<f32>(f2_, f2_) + vec4<f32>(f3_, 1f)) + f4_);
+}
+
+fn select_i32_(c2_2: vec2<bool>, c3_2: vec3<bool>, c4_2: vec4<bool>) -> vec4<i32> {
+    let _e5 = inputs.i;
+    let _e9 = inputs.i;
+    let i2_ = select(_e5.xy, -(_e9.yx), c2_2);
+    let _e15 = inputs.i;
+    let _e19 = inputs.i;
+    let i3_ = select(_e15.xyz, -(_e19.zyx), c3_2);
+    let _e25 = inputs.i;
+    let _e28 = inputs.i;
+    let i4_ = select(_e25, -(_e28), c4_2);
+    return ((vec4<i32>(i2_, i2_) + vec4<i32>(i3_, 1i)) + i4_);
+}
+
+fn select_u32_(c2_3: vec2<bool>, c3_3: vec3<bool>, c4_3: vec4<bool>) -> vec4<u32> {
+    let _e5 = inputs.u;
+    let _e9 = inputs.u;
+    let u2_ = select(_e5.xy, _e9.yx, c2_3);
+    let _e14 = inputs.u;
+    let _e18 = inputs.u;
+    let u3_ = select(_e14.xyz, _e18.zyx, c3_3);
+    let _e23 = inputs.u;
+    let _e26 = inputs.u;
+    let u4_ = select(_e23, _e26.wzyx, c4_3);
+    return ((vec4<u32>(u2_, u2_) + vec4<u32>(u3_, 1u)) + u4_);
+}
+
+fn select_bool(c2_4: vec2<bool>, c3_4: vec3<bool>, c4_4: vec4<bool>) -> bool {
+    let b2_ = select(c2_4, !(c2_4), c2_4.yx);
+    let b3_ = select(c3_4, !(c3_4), c3_4.zxy);
+    let b4_ = select(c4_4, !(c4_4), c4_4.wzyx);
+    return ((any(b2_) && any(b3_)) && any(b4_));
+}
+
+@fragment 
+fn main() -> @location(0) vec4<f32> {
+    var color: vec4<f32>;
+
+    let _e2 = inputs.a;
+    let _e5 = inputs.b;
+    let c4_5 = (_e2 < _e5);
+    let c3_5 = c4_5.xyz;
+    let c2_5 = c4_5.xy;
+    let _e9 = select_f32_(c2_5, c3_5, c4_5);
+    color = _e9;
+    let _e11 = select_i32_(c2_5, c3_5, c4_5);
+    let _e13 = color;
+    color = (_e13 + vec4<f32>(_e11));
+    let _e15 = select_u32_(c2_5, c3_5, c4_5);
+    let _e17 = color;
+    color = (_e17 + vec4<f32>(_e15));
+    let _e19 = all_any(c2_5, c3_5, c4_5);
+    let _e20 = select_bool(c2_5, c3_5, c4_5);
+    if (_e19 || _e20) {
+        color.w = 0f;
+    }
+    let _e24 = color;
+    return _e24;
+}
@@ -0,0 +1,24 @@
+@group(0) @binding(0) 
+var<storage> weights: array<f32>;
+@group(0) @binding(1) 
+var<storage, read_write> totals: array<f32>;
+@group(0) @binding(2) 
+var source: texture_storage_2d<r32float,read>;
+@group(0) @binding(3) 
+var destination: texture_storage_2d<rgba8unorm,write>;
+@group(0) @binding(4) 
+var accumulator: texture_storage_2d<r32float,read_write>;
+
+@compute @workgroup_size(8, 8, 1) 
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    let weight = weights[id.x];
+    let _e7 = textureLoad(source, id.xy);
+    let value = (_e7.x * weight);
+    let _e13 = totals[id.x];
+    totals[id.x] = (_e13 + value);
+    textureStore(destination, id.xy, vec4(value));
+    let _e20 = textureLoad(accumulator, id.xy);
+    let sum = (_e20 + vec4(value));
+    textureStore(accumulator, id.xy, sum);
+    return;
+}
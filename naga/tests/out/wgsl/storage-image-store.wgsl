@@ -0,0 +1,26 @@
+@group(0) @binding(0) 
+var rgba8unorm: texture_storage_2d<rgba8unorm,write>;
+@group(0) @binding(1) 
+var rgba8uint: texture_storage_2d<rgba8uint,write>;
+@group(0) @binding(2) 
+var rg11b10float: texture_storage_2d<rg11b10float,write>;
+@group(0) @binding(3) 
+var r32float_3d: texture_storage_3d<r32float,write>;
+@group(0) @binding(4) 
+var rg16float_array: texture_storage_2d_array<rg16float,write>;
+@group(0) @binding(5) 
+var r16uint_1d: texture_storage_1d<r16uint,write>;
+
+@compute @workgroup_size(1, 1, 1) 
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    let color = vec4<f32>(0.25f, 0.5f, 0.75f, 1f);
+    textureStore(rgba8unorm, id.xy, color);
+    textureStore(rgba8uint, id.xy, vec4<u32>(id, 255u));
+    textureStore(rg11b10float, id.xy, color);
+    textureStore(r32float_3d, id, vec4(color.x));
+    textureStore(rg16float_array, id.xy, id.z, color);
+    textureStore(r16uint_1d, id.x, vec4(id.x));
+    textureStore(rg16float_array, id.xy, 0i, vec4<f32>());
+    textureStore(rg11b10float, id.xy, (color * 2f));
+    return;
+}
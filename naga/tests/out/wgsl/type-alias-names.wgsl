@@ -0,0 +1,27 @@
+alias Scalar = f32;
+alias Vec = vec4<f32>;
+alias Pair = array<Vec, 2>;
+
+struct Particle {
+    position: Vec,
+    history: Pair,
+}
+
+alias Particles = array<Particle, 4>;
+
+@group(0) @binding(0) 
+var<storage, read_write> particles: Particles;
+
+fn advance(particle: Particle, step: Scalar) -> Vec {
+    let last = particle.history[1];
+    return (particle.position + ((particle.position - last) * step));
+}
+
+@compute @workgroup_size(4, 1, 1) 
+fn main(@builtin(local_invocation_index) index: u32) {
+    let particle_1 = particles[index];
+    let _e5 = advance(particle_1, 0.5f);
+    particles[index].history = Pair(particle_1.position, _e5);
+    particles[index].position = _e5;
+    return;
+}
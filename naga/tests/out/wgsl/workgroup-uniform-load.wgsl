@@ -1,6 +1,12 @@
+struct Params {
+    count: u32,
+    scale: f32,
+}
+
 const SIZE: u32 = 128u;
 
 var<workgroup> arr_i32_: array<i32, 128>;
+var<workgroup> params: Params;
 
 @compute @workgroup_size(4, 1, 1) 
 fn test_workgroupUniformLoad(@builtin(workgroup_id) workgroup_id: vec3<u32>) {
@@ -13,3 +19,28 @@ fn test_workgroupUniformLoad(@builtin(workgroup_id) workgroup_id: vec3<u32>) {
         return;
     }
 }
+
+@compute @workgroup_size(4, 1, 1) 
+fn test_workgroupUniformLoad_struct(@builtin(local_invocation_index) index: u32) {
+    var i: u32 = 0u;
+
+    if (index == 0u) {
+        params = Params(4u, 2f);
+    }
+    let _e8 = workgroupUniformLoad((&params));
+    loop {
+        let _e11 = i;
+        if (_e11 < _e8.count) {
+        } else {
+            break;
+        }
+        {
+            workgroupBarrier();
+        }
+        continuing {
+            let _e15 = i;
+            i = (_e15 + 1u);
+        }
+    }
+    return;
+}
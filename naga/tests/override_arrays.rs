@@ -0,0 +1,213 @@
+/*!
+Test workgroup arrays whose length is an override constant.
+
+The WGSL front end doesn't produce overrides yet, so these tests parse a
+shader using a `const`, and then turn the constant into an override that
+sizes the `tile` array.
+
+The resulting module is also the IR snapshot input
+`tests/in/ir/override-arrays.ron`, so CI runs `spirv-val` on its SPIR-V.
+*/
+
+#![cfg(feature = "wgsl-in")]
+
+use naga::valid::{Capabilities, ValidationFlags, Validator};
+
+const SOURCE: &str = include_str!("in/override-arrays.wgsl");
+
+/// Parse `SOURCE`, make `TILE_SIZE` an override with the given `r#override`,
+/// and size `tile` by it, in the address space `space`.
+fn module(r#override: naga::Override, space: naga::AddressSpace) -> naga::Module {
+    let mut module = naga::front::wgsl::parse_str(SOURCE).unwrap();
+
+    let (tile_size, constant) = module
+        .constants
+        .iter_mut()
+        .find(|(_, constant)| constant.name.as_deref() == Some("TILE_SIZE"))
+        .unwrap();
+    constant.r#override = r#override;
+
+    let (_, tile) = module
+        .global_variables
+        .iter_mut()
+        .find(|(_, var)| var.name.as_deref() == Some("tile"))
+        .unwrap();
+    let naga::TypeInner::Array { base, stride, .. } = module.types[tile.ty].inner else {
+        unreachable!();
+    };
+    tile.space = space;
+    tile.ty = module.types.insert(
+        naga::Type {
+            name: None,
+            inner: naga::TypeInner::Array {
+                base,
+                size: naga::ArraySize::Pending(tile_size),
+                stride,
+            },
+        },
+        Default::default(),
+    );
+
+    module
+}
+
+fn validate(
+    module: &naga::Module,
+) -> Result<naga::valid::ModuleInfo, naga::valid::ValidationError> {
+    Validator::new(ValidationFlags::all(), Capabilities::default())
+        .validate(module)
+        .map_err(|error| error.into_inner())
+}
+
+#[test]
+fn workgroup_only() {
+    let workgroup = module(naga::Override::ByNameOrId(0), naga::AddressSpace::WorkGroup);
+    validate(&workgroup).unwrap();
+
+    let private = module(naga::Override::ByNameOrId(0), naga::AddressSpace::Private);
+    assert!(matches!(
+        validate(&private),
+        Err(naga::valid::ValidationError::GlobalVariable {
+            source: naga::valid::GlobalVariableError::MissingTypeFlags { .. },
+            ..
+        })
+    ));
+}
+
+#[test]
+fn requires_override() {
+    let module = module(naga::Override::None, naga::AddressSpace::WorkGroup);
+    assert!(matches!(
+        validate(&module),
+        Err(naga::valid::ValidationError::Type {
+            source: naga::valid::TypeError::InvalidPendingArrayLength(_),
+            ..
+        })
+    ));
+}
+
+#[test]
+fn binding_arrays_rejected() {
+    let mut module = module(naga::Override::ByNameOrId(0), naga::AddressSpace::WorkGroup);
+    let (tile_size, _) = module
+        .constants
+        .iter()
+        .find(|(_, constant)| constant.name.as_deref() == Some("TILE_SIZE"))
+        .unwrap();
+    let sampler = module.types.insert(
+        naga::Type {
+            name: None,
+            inner: naga::TypeInner::Sampler { comparison: false },
+        },
+        Default::default(),
+    );
+    module.types.insert(
+        naga::Type {
+            name: None,
+            inner: naga::TypeInner::BindingArray {
+                base: sampler,
+                size: naga::ArraySize::Pending(tile_size),
+            },
+        },
+        Default::default(),
+    );
+    assert!(matches!(
+        validate(&module),
+        Err(naga::valid::ValidationError::Type {
+            source: naga::valid::TypeError::PendingBindingArraySize,
+            ..
+        })
+    ));
+}
+
+#[cfg(feature = "wgsl-out")]
+#[test]
+fn wgsl() {
+    let module = module(naga::Override::ByNameOrId(0), naga::AddressSpace::WorkGroup);
+    let info = validate(&module).unwrap();
+    let output =
+        naga::back::wgsl::write_string(&module, &info, naga::back::wgsl::WriterFlags::empty())
+            .unwrap();
+    assert!(
+        output.contains("var<workgroup> tile: array<f32, TILE_SIZE>;"),
+        "{output}"
+    );
+}
+
+#[cfg(feature = "spv-out")]
+#[test]
+fn spirv() {
+    use rspirv::dr::Operand;
+    use rspirv::spirv::{Decoration, Op};
+
+    let module = module(naga::Override::ByNameOrId(7), naga::AddressSpace::WorkGroup);
+    let info = validate(&module).unwrap();
+    let options = naga::back::spv::Options {
+        bounds_check_policies: naga::proc::BoundsCheckPolicies {
+            index: naga::proc::BoundsCheckPolicy::Restrict,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let words = naga::back::spv::write_vec(&module, &info, &options, None).unwrap();
+    let spv = rspirv::dr::load_words(words).unwrap();
+
+    // `TILE_SIZE` is a specialization constant, with its default value.
+    let spec_constants: Vec<_> = spv
+        .types_global_values
+        .iter()
+        .filter(|inst| inst.class.opcode == Op::SpecConstant)
+        .collect();
+    assert_eq!(spec_constants.len(), 1);
+    let tile_size = spec_constants[0].result_id.unwrap();
+    assert_eq!(
+        spec_constants[0].operands,
+        [Operand::LiteralInt32(64)],
+        "{:?}",
+        spec_constants[0]
+    );
+    assert!(spv.annotations.iter().any(|inst| {
+        inst.operands
+            == [
+                Operand::IdRef(tile_size),
+                Operand::Decoration(Decoration::SpecId),
+                Operand::LiteralInt32(7),
+            ]
+    }));
+
+    // `tile`'s type uses it as its length.
+    assert!(spv.types_global_values.iter().any(|inst| {
+        inst.class.opcode == Op::TypeArray && inst.operands[1] == Operand::IdRef(tile_size)
+    }));
+
+    // Function bodies use it too, for both `TILE_SIZE` itself and the
+    // bounds checks on `tile`.
+    let uses = spv.functions[0]
+        .all_inst_iter()
+        .filter(|inst| inst.operands.contains(&Operand::IdRef(tile_size)))
+        .count();
+    assert!(uses >= 2, "expected at least 2 uses, found {uses}");
+}
+
+/// Check that `tests/in/ir/override-arrays.ron` is the module this file
+/// tests, or write it if `NAGA_UPDATE_IR=1` is set.
+#[cfg(all(feature = "serialize", feature = "deserialize"))]
+#[test]
+fn ir_snapshot_input() {
+    let module = module(naga::Override::ByNameOrId(0), naga::AddressSpace::WorkGroup);
+    let config = ron::ser::PrettyConfig::default().new_line("\n".to_string());
+    let expected = ron::ser::to_string_pretty(&module, config).unwrap();
+
+    let path = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/in/ir/override-arrays.ron"
+    );
+    if std::env::var_os("NAGA_UPDATE_IR").is_some() {
+        std::fs::write(path, &expected).unwrap();
+    }
+    let actual = std::fs::read_to_string(path).unwrap_or_default();
+    assert!(
+        actual == expected,
+        "`{path}` is out of date; rerun with NAGA_UPDATE_IR=1 to regenerate it"
+    );
+}
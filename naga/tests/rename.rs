@@ -0,0 +1,143 @@
+/*!
+Test renaming entry points and global variables with `naga::proc`, and
+that backends use the new names.
+*/
+
+#![cfg(feature = "wgsl-in")]
+
+use naga::proc::{rename_entry_point, set_global_name, RenameError};
+
+const SOURCE: &str = "
+    @group(0) @binding(0) var tex: texture_2d<f32>;
+    @group(0) @binding(1) var samp: sampler;
+
+    fn helper(uv: vec2<f32>) -> vec4<f32> {
+        return textureSample(tex, samp, uv);
+    }
+
+    @fragment
+    fn fs_main(@location(0) uv: vec2<f32>) -> @location(0) vec4<f32> {
+        return helper(uv);
+    }
+";
+
+/// Parse `SOURCE`, and rename its entry point and texture the way an asset
+/// pipeline might.
+fn renamed() -> (naga::Module, naga::valid::ModuleInfo) {
+    let mut module = naga::front::wgsl::parse_str(SOURCE).unwrap();
+    rename_entry_point(&mut module, "fs_main", "main").unwrap();
+    let (tex, _) = module
+        .global_variables
+        .iter()
+        .find(|(_, var)| var.name.as_deref() == Some("tex"))
+        .unwrap();
+    set_global_name(&mut module, tex, "brick_albedo").unwrap();
+
+    let info = naga::valid::Validator::new(
+        naga::valid::ValidationFlags::all(),
+        naga::valid::Capabilities::default(),
+    )
+    .validate(&module)
+    .unwrap();
+    (module, info)
+}
+
+#[test]
+fn errors() {
+    let mut module = naga::front::wgsl::parse_str(SOURCE).unwrap();
+    let (samp, _) = module
+        .global_variables
+        .iter()
+        .find(|(_, var)| var.name.as_deref() == Some("samp"))
+        .unwrap();
+
+    assert_eq!(
+        rename_entry_point(&mut module, "vs_main", "main").unwrap_err(),
+        RenameError::MissingEntryPoint("vs_main".to_string()),
+    );
+    assert_eq!(
+        rename_entry_point(&mut module, "fs_main", "main()").unwrap_err(),
+        RenameError::InvalidIdentifier("main()".to_string()),
+    );
+    assert_eq!(
+        rename_entry_point(&mut module, "fs_main", "helper").unwrap_err(),
+        RenameError::DuplicateName("helper".to_string()),
+    );
+    assert_eq!(
+        set_global_name(&mut module, samp, "tex").unwrap_err(),
+        RenameError::DuplicateName("tex".to_string()),
+    );
+    assert_eq!(
+        set_global_name(&mut module, samp, "sampler").unwrap_err(),
+        RenameError::Reserved("sampler".to_string()),
+    );
+
+    // Failed renames leave the module unchanged.
+    assert_eq!(module.entry_points[0].name, "fs_main");
+    assert_eq!(module.global_variables[samp].name.as_deref(), Some("samp"));
+
+    // Renaming a global to its own name is fine.
+    set_global_name(&mut module, samp, "samp").unwrap();
+}
+
+#[cfg(feature = "wgsl-out")]
+#[test]
+fn wgsl() {
+    let (module, info) = renamed();
+    let output =
+        naga::back::wgsl::write_string(&module, &info, naga::back::wgsl::WriterFlags::empty())
+            .unwrap();
+    assert!(
+        output.contains("var brick_albedo: texture_2d<f32>;"),
+        "{output}"
+    );
+    assert!(
+        output.contains("textureSample(brick_albedo, samp, uv_1)"),
+        "{output}"
+    );
+    assert!(output.contains("fn main("), "{output}");
+    assert!(
+        !output.contains("tex:") && !output.contains("fs_main"),
+        "{output}"
+    );
+}
+
+#[cfg(feature = "spv-out")]
+#[test]
+fn spirv() {
+    use rspirv::dr::Operand;
+    use rspirv::spirv::Op;
+
+    let (module, info) = renamed();
+    let words = naga::back::spv::write_vec(&module, &info, &Default::default(), None).unwrap();
+    let spv = rspirv::dr::load_words(words).unwrap();
+
+    assert_eq!(spv.entry_points.len(), 1);
+    assert_eq!(
+        spv.entry_points[0].operands[2],
+        Operand::LiteralString("main".to_string())
+    );
+    assert!(spv.debug_names.iter().any(|inst| {
+        inst.class.opcode == Op::Name
+            && inst.operands[1] == Operand::LiteralString("brick_albedo".to_string())
+    }));
+}
+
+#[cfg(feature = "msl-out")]
+#[test]
+fn msl() {
+    let (module, info) = renamed();
+    let (output, translation) =
+        naga::back::msl::write_string(&module, &info, &Default::default(), &Default::default())
+            .unwrap();
+
+    // `main` is reserved in MSL, so the backend adjusts it, and reports the
+    // name it used.
+    let name = translation.entry_point_names[0].as_ref().unwrap();
+    assert_eq!(name, "main_");
+    assert!(
+        output.contains(&format!("fragment {name}Output {name}(")),
+        "{output}"
+    );
+    assert!(output.contains("brick_albedo"), "{output}");
+}
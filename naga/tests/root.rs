@@ -1,4 +1,45 @@
+mod debug_markers;
+mod diagnostics;
+mod dot4_packed;
+mod entry_point_selection;
 mod example_wgsl;
+mod external_texture;
+mod fold_constants;
+mod glsl_early_return;
+mod glsl_frag_depth;
+mod glsl_instance_index;
+mod glsl_prologue;
+mod glsl_reserved_names;
+mod glsl_sample_variables;
+mod hlsl_numthreads;
+mod hlsl_resource_bindings;
+mod hlsl_special_constants;
+mod inline_calls;
+mod link;
+mod logging;
+mod matrix_layout;
+mod metrics;
+mod msl_constexpr_samplers;
+mod msl_force_precise;
+mod msl_lang_version;
+mod msl_line_observer;
+mod msl_packed_layout;
+mod override_arrays;
+mod rename;
 mod snapshots;
+mod spirv_access_chain;
 mod spirv_capabilities;
+mod spirv_interface_blocks;
+mod spirv_local_variables;
+mod spirv_memory_model;
+mod spirv_mesh_shader;
+mod spirv_minimal_output;
+mod spirv_pointer_calls;
+mod spirv_pointer_select;
+mod spirv_resource_bindings;
+mod spirv_storage_buffer;
+mod spirv_switch_in_loop;
+mod spirv_swizzle_store;
+mod telemetry;
+mod validation;
 mod wgsl_errors;
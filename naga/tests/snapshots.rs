@@ -11,6 +11,10 @@ const CRATE_ROOT: &str = env!("CARGO_MANIFEST_DIR");
 const BASE_DIR_IN: &str = "tests/in";
 const BASE_DIR_OUT: &str = "tests/out";
 
+/// How many percent a metric of an input's generated code may grow before
+/// [`Input::check_metrics`] reports it as a regression.
+const METRICS_TOLERANCE: u64 = 2;
+
 bitflags::bitflags! {
     #[derive(Clone, Copy)]
     struct Targets: u32 {
@@ -49,6 +53,12 @@ struct SpirvOutParameters {
     #[serde(default)]
     separate_entry_points: bool,
     #[serde(default)]
+    minimal_output: bool,
+    /// Write the module as a binary `.spv` file instead of disassembling
+    /// it, for instructions `rspirv` doesn't know yet.
+    #[serde(default)]
+    binary: bool,
+    #[serde(default)]
     #[cfg(all(feature = "deserialize", feature = "spv-out"))]
     binding_map: naga::back::spv::BindingMap,
 }
@@ -57,12 +67,17 @@ struct SpirvOutParameters {
 struct WgslOutParameters {
     #[serde(default)]
     explicit_types: bool,
+    #[serde(default)]
+    use_type_aliases: bool,
 }
 
 #[derive(Default, serde::Deserialize)]
 struct Parameters {
     #[serde(default)]
     god_mode: bool,
+    /// Inline every function call before validating and writing output.
+    #[serde(default)]
+    inline_calls: bool,
     #[cfg(feature = "deserialize")]
     #[serde(default)]
     bounds_check_policies: naga::proc::BoundsCheckPolicies,
@@ -108,6 +123,10 @@ struct Input {
     /// like `210-bevy-2d-shader.frag` and just add `.wgsl` to it, producing
     /// `210-bevy-2d-shader.frag.wgsl`.
     keep_input_extension: bool,
+
+    /// The size of the code generated for this input so far.
+    #[cfg(feature = "metrics")]
+    metrics: std::cell::RefCell<naga::metrics::Metrics>,
 }
 
 impl Input {
@@ -126,6 +145,8 @@ impl Input {
             // `with_extension` would do.
             file_name: PathBuf::from(format!("{name}.{extension}")),
             keep_input_extension: false,
+            #[cfg(feature = "metrics")]
+            metrics: Default::default(),
         }
     }
 
@@ -246,6 +267,45 @@ impl Input {
             panic!("Error writing {}: {}", output_path.display(), err);
         }
     }
+
+    /// Record the size of generated code with `measure`, for
+    /// [`Input::check_metrics`].
+    #[cfg(feature = "metrics")]
+    fn measure(&self, measure: impl FnOnce(&mut naga::metrics::Metrics)) {
+        measure(&mut self.metrics.borrow_mut());
+    }
+
+    /// Compare the metrics recorded for this input against its baseline in
+    /// `tests/out/metrics`, and panic if any of them regressed.
+    ///
+    /// Write the baseline instead if there isn't one yet, or if the
+    /// `NAGA_UPDATE_METRICS` environment variable is set.
+    #[cfg(feature = "metrics")]
+    fn check_metrics(&self) {
+        let metrics = self.metrics.take();
+        if metrics == naga::metrics::Metrics::default() {
+            return;
+        }
+
+        let baseline = match fs::read_to_string(self.output_path("metrics", "txt")) {
+            Ok(text) if std::env::var_os("NAGA_UPDATE_METRICS").is_none() => text,
+            _ => {
+                self.write_output_file("metrics", "txt", metrics.to_string());
+                return;
+            }
+        };
+        let baseline = naga::metrics::Metrics::parse(&baseline).unwrap();
+        let regressions = baseline.regressions(&metrics, METRICS_TOLERANCE);
+        if !regressions.is_empty() {
+            let list: Vec<_> = regressions.iter().map(ToString::to_string).collect();
+            panic!(
+                "Code generated for {} grew by more than {METRICS_TOLERANCE}%:\n{}\n\
+                 Rerun with NAGA_UPDATE_METRICS=1 to accept the new sizes.",
+                self.file_name.display(),
+                list.join("\n"),
+            );
+        }
+    }
 }
 
 #[allow(unused_variables)]
@@ -264,6 +324,10 @@ fn check_targets(
         naga::valid::Capabilities::default()
     };
 
+    if params.inline_calls {
+        naga::proc::inline_all_calls(module);
+    }
+
     #[cfg(feature = "serialize")]
     {
         if targets.contains(Targets::IR) {
@@ -377,7 +441,13 @@ fn check_targets(
     #[cfg(all(feature = "deserialize", feature = "hlsl-out"))]
     {
         if targets.contains(Targets::HLSL) {
-            write_output_hlsl(input, module, &info, &params.hlsl);
+            write_output_hlsl(
+                input,
+                module,
+                &info,
+                &params.hlsl,
+                params.bounds_check_policies,
+            );
         }
     }
     #[cfg(all(feature = "deserialize", feature = "wgsl-out"))]
@@ -386,6 +456,8 @@ fn check_targets(
             write_output_wgsl(input, module, &info, &params.wgsl);
         }
     }
+    #[cfg(feature = "metrics")]
+    input.check_metrics();
 }
 
 #[cfg(feature = "spv-out")]
@@ -408,6 +480,7 @@ fn write_output_spv(
     );
     flags.set(spv::WriterFlags::FORCE_POINT_SIZE, params.force_point_size);
     flags.set(spv::WriterFlags::CLAMP_FRAG_DEPTH, params.clamp_frag_depth);
+    flags.set(spv::WriterFlags::MINIMAL_OUTPUT, params.minimal_output);
 
     let options = spv::Options {
         lang_version: (params.version.0, params.version.1),
@@ -436,10 +509,11 @@ fn write_output_spv(
                 &options,
                 Some(&pipeline_options),
                 &format!("{}.spvasm", ep.name),
+                params.binary,
             );
         }
     } else {
-        write_output_spv_inner(input, module, info, &options, None, "spvasm");
+        write_output_spv_inner(input, module, info, &options, None, "spvasm", params.binary);
     }
 }
 
@@ -451,11 +525,26 @@ fn write_output_spv_inner(
     options: &naga::back::spv::Options<'_>,
     pipeline_options: Option<&naga::back::spv::PipelineOptions>,
     extension: &str,
+    binary: bool,
 ) {
     use naga::back::spv;
     use rspirv::binary::Disassemble;
     println!("Generating SPIR-V for {:?}", input.file_name);
     let spv = spv::write_vec(module, info, options, pipeline_options).unwrap();
+    #[cfg(feature = "metrics")]
+    input.measure(|metrics| {
+        let prefix = match extension.strip_suffix(".spvasm") {
+            Some(ep_name) => format!("spv.{ep_name}"),
+            None => "spv".to_string(),
+        };
+        metrics.measure_spirv(&prefix, &spv);
+    });
+    if binary {
+        let bytes: Vec<u8> = spv.iter().flat_map(|word| word.to_le_bytes()).collect();
+        let extension = extension.strip_suffix("asm").unwrap();
+        input.write_output_file("spv", extension, bytes);
+        return;
+    }
     let dis = rspirv::dr::load_words(spv)
         .expect("Produced invalid SPIR-V")
         .disassemble();
@@ -493,6 +582,8 @@ fn write_output_msl(
         }
     }
 
+    #[cfg(feature = "metrics")]
+    input.measure(|metrics| metrics.measure_text("msl", &string));
     input.write_output_file("msl", "msl", string);
 }
 
@@ -530,6 +621,8 @@ fn write_output_glsl(
     .expect("GLSL init failed");
     writer.write().expect("GLSL write failed");
 
+    #[cfg(feature = "metrics")]
+    input.measure(|metrics| metrics.measure_text(&format!("glsl.{ep_name}.{stage:?}"), &buffer));
     let extension = format!("{ep_name}.{stage:?}.glsl");
     input.write_output_file("glsl", &extension, buffer);
 }
@@ -540,16 +633,21 @@ fn write_output_hlsl(
     module: &naga::Module,
     info: &naga::valid::ModuleInfo,
     options: &naga::back::hlsl::Options,
+    bounds_check_policies: naga::proc::BoundsCheckPolicies,
 ) {
     use naga::back::hlsl;
     use std::fmt::Write as _;
 
     println!("generating HLSL");
 
+    let mut options = options.clone();
+    options.bounds_check_policies = bounds_check_policies;
     let mut buffer = String::new();
-    let mut writer = hlsl::Writer::new(&mut buffer, options);
+    let mut writer = hlsl::Writer::new(&mut buffer, &options);
     let reflection_info = writer.write(module, info).expect("HLSL write failed");
 
+    #[cfg(feature = "metrics")]
+    input.measure(|metrics| metrics.measure_text("hlsl", &buffer));
     input.write_output_file("hlsl", "hlsl", buffer);
 
     // We need a config file for validation script
@@ -565,6 +663,7 @@ fn write_output_hlsl(
             naga::ShaderStage::Vertex => &mut config.vertex,
             naga::ShaderStage::Fragment => &mut config.fragment,
             naga::ShaderStage::Compute => &mut config.compute,
+            naga::ShaderStage::Task | naga::ShaderStage::Mesh => unreachable!(),
         }
         .push(hlsl_snapshots::ConfigItem {
             entry_point: name.clone(),
@@ -592,9 +691,12 @@ fn write_output_wgsl(
 
     let mut flags = wgsl::WriterFlags::empty();
     flags.set(wgsl::WriterFlags::EXPLICIT_TYPES, params.explicit_types);
+    flags.set(wgsl::WriterFlags::USE_TYPE_ALIASES, params.use_type_aliases);
 
     let string = wgsl::write_string(module, info, flags).expect("WGSL write failed");
 
+    #[cfg(feature = "metrics")]
+    input.measure(|metrics| metrics.measure_text("wgsl", &string));
     input.write_output_file("wgsl", "wgsl", string);
 }
 
@@ -659,6 +761,10 @@ fn convert_wgsl() {
             "image",
             Targets::SPIRV | Targets::METAL | Targets::HLSL | Targets::WGSL | Targets::GLSL,
         ),
+        (
+            "image-query-size",
+            Targets::SPIRV | Targets::METAL | Targets::HLSL | Targets::WGSL | Targets::GLSL,
+        ),
         ("extra", Targets::SPIRV | Targets::METAL | Targets::WGSL),
         ("push-constants", Targets::GLSL | Targets::HLSL),
         (
@@ -673,6 +779,19 @@ fn convert_wgsl() {
             "fragment-output",
             Targets::SPIRV | Targets::METAL | Targets::GLSL | Targets::HLSL | Targets::WGSL,
         ),
+        ("msl-frag-depth", Targets::METAL),
+        ("msl-bounded-loops", Targets::METAL),
+        ("copy-memory", Targets::SPIRV),
+        (
+            "clip-distances",
+            Targets::SPIRV | Targets::HLSL | Targets::WGSL,
+        ),
+        ("mesh-shader", Targets::SPIRV | Targets::WGSL),
+        (
+            "conservative-depth",
+            Targets::SPIRV | Targets::WGSL | Targets::GLSL,
+        ),
+        ("sample-variables", Targets::GLSL),
         (
             "dualsource",
             Targets::SPIRV | Targets::METAL | Targets::GLSL | Targets::HLSL | Targets::WGSL,
@@ -696,16 +815,26 @@ fn convert_wgsl() {
             "atomicOps",
             Targets::SPIRV | Targets::METAL | Targets::GLSL | Targets::HLSL | Targets::WGSL,
         ),
-        ("atomicCompareExchange", Targets::SPIRV | Targets::WGSL),
+        (
+            "atomicOps-struct-array",
+            Targets::SPIRV | Targets::HLSL | Targets::WGSL,
+        ),
+        (
+            "atomicCompareExchange",
+            Targets::SPIRV | Targets::HLSL | Targets::WGSL,
+        ),
         (
             "padding",
             Targets::SPIRV | Targets::METAL | Targets::GLSL | Targets::HLSL | Targets::WGSL,
         ),
         ("pointers", Targets::SPIRV | Targets::WGSL),
+        ("pointer-calls", Targets::SPIRV),
+        ("minimal-output", Targets::SPIRV),
         (
             "control-flow",
             Targets::SPIRV | Targets::METAL | Targets::GLSL | Targets::HLSL | Targets::WGSL,
         ),
+        ("constant-branches", Targets::SPIRV | Targets::WGSL),
         (
             "standard",
             Targets::SPIRV | Targets::METAL | Targets::GLSL | Targets::HLSL | Targets::WGSL,
@@ -731,6 +860,13 @@ fn convert_wgsl() {
             Targets::SPIRV | Targets::METAL | Targets::GLSL,
         ),
         ("policy-mix", Targets::SPIRV | Targets::METAL),
+        ("access-chain", Targets::SPIRV),
+        (
+            "depth-load",
+            Targets::SPIRV | Targets::METAL | Targets::GLSL | Targets::HLSL | Targets::WGSL,
+        ),
+        ("sample-array-index", Targets::HLSL),
+        ("sample-array-index-restrict", Targets::HLSL),
         (
             "texture-arg",
             Targets::SPIRV | Targets::METAL | Targets::GLSL | Targets::HLSL | Targets::WGSL,
@@ -745,6 +881,19 @@ fn convert_wgsl() {
             "math-functions",
             Targets::SPIRV | Targets::METAL | Targets::GLSL | Targets::HLSL | Targets::WGSL,
         ),
+        (
+            "int-math",
+            Targets::SPIRV | Targets::METAL | Targets::GLSL | Targets::HLSL | Targets::WGSL,
+        ),
+        (
+            "inline-calls",
+            Targets::SPIRV | Targets::METAL | Targets::GLSL | Targets::HLSL | Targets::WGSL,
+        ),
+        (
+            "quantize-to-f16",
+            Targets::SPIRV | Targets::METAL | Targets::GLSL | Targets::HLSL | Targets::WGSL,
+        ),
+        ("quantize-to-f16-half-float", Targets::GLSL),
         (
             "binding-arrays",
             Targets::WGSL | Targets::HLSL | Targets::METAL | Targets::SPIRV,
@@ -753,6 +902,7 @@ fn convert_wgsl() {
             "binding-buffer-arrays",
             Targets::WGSL | Targets::SPIRV, //TODO: more backends, eventually merge into "binding-arrays"
         ),
+        ("buffer-struct-reuse", Targets::SPIRV),
         ("resource-binding-map", Targets::METAL),
         ("multiview", Targets::SPIRV | Targets::GLSL | Targets::WGSL),
         ("multiview_webgl", Targets::GLSL),
@@ -762,6 +912,7 @@ fn convert_wgsl() {
         ),
         ("lexical-scopes", Targets::WGSL),
         ("type-alias", Targets::WGSL),
+        ("type-alias-names", Targets::WGSL),
         ("module-scope", Targets::WGSL),
         (
             "workgroup-var-init",
@@ -775,6 +926,9 @@ fn convert_wgsl() {
         ("sprite", Targets::SPIRV),
         ("force_point_size_vertex_shader_webgl", Targets::GLSL),
         ("invariant", Targets::GLSL),
+        ("glsl-bounded-loops", Targets::GLSL),
+        ("glsl-loop-shapes", Targets::GLSL),
+        ("saturate-fold", Targets::HLSL | Targets::METAL),
         ("ray-query", Targets::SPIRV | Targets::METAL),
         ("hlsl-keyword", Targets::HLSL),
         (
@@ -791,6 +945,26 @@ fn convert_wgsl() {
             "struct-layout",
             Targets::WGSL | Targets::GLSL | Targets::SPIRV | Targets::HLSL | Targets::METAL,
         ),
+        ("packed-vec3", Targets::SPIRV | Targets::METAL),
+        (
+            "debug-marker",
+            Targets::WGSL | Targets::GLSL | Targets::SPIRV | Targets::HLSL | Targets::METAL,
+        ),
+        (
+            "control-flow-hints",
+            Targets::WGSL | Targets::GLSL | Targets::SPIRV | Targets::HLSL | Targets::METAL,
+        ),
+        (
+            "dot4-packed",
+            Targets::WGSL | Targets::GLSL | Targets::SPIRV | Targets::HLSL | Targets::METAL,
+        ),
+        ("dot4-packed-native", Targets::HLSL),
+        (
+            "split-vertex-inputs",
+            Targets::WGSL | Targets::GLSL | Targets::SPIRV | Targets::HLSL | Targets::METAL,
+        ),
+        ("instance-index", Targets::GLSL),
+        ("instance-index-gl460", Targets::GLSL),
         (
             "f64",
             Targets::SPIRV | Targets::GLSL | Targets::HLSL | Targets::WGSL,
@@ -807,6 +981,24 @@ fn convert_wgsl() {
             "abstract-types-operators",
             Targets::SPIRV | Targets::METAL | Targets::GLSL | Targets::WGSL,
         ),
+        (
+            "abstract-types-materialization",
+            Targets::SPIRV | Targets::METAL | Targets::GLSL | Targets::WGSL,
+        ),
+        (
+            "relational",
+            Targets::WGSL | Targets::GLSL | Targets::SPIRV | Targets::HLSL | Targets::METAL,
+        ),
+        (
+            "private-var-init",
+            Targets::WGSL | Targets::GLSL | Targets::SPIRV | Targets::HLSL | Targets::METAL,
+        ),
+        ("storage-image-store", Targets::WGSL | Targets::HLSL),
+        (
+            "memory-decorations",
+            Targets::WGSL | Targets::GLSL | Targets::SPIRV | Targets::HLSL | Targets::METAL,
+        ),
+        ("storage-access", Targets::WGSL | Targets::GLSL),
     ];
 
     for &(name, targets) in inputs.iter() {
@@ -886,6 +1078,30 @@ fn convert_spv_all() {
         true,
         Targets::METAL | Targets::GLSL | Targets::HLSL | Targets::WGSL,
     );
+    convert_spv("msl-force-precise", false, Targets::METAL);
+    convert_spv(
+        "composite-select-undef-shuffle",
+        false,
+        Targets::SPIRV | Targets::WGSL,
+    );
+}
+
+#[cfg(feature = "deserialize")]
+fn convert_ir(name: &str, targets: Targets) {
+    let _ = env_logger::try_init();
+
+    let input = Input::new(Some("ir"), name, "ron");
+    let mut module: naga::Module = ron::de::from_str(&input.read_source())
+        .unwrap_or_else(|err| panic!("Couldn't parse IR input `{name}`: {err}"));
+    check_targets(&input, &mut module, targets, None);
+}
+
+/// Convert modules that no front end can produce yet, stored as IR.
+#[cfg(feature = "deserialize")]
+#[test]
+fn convert_ir_all() {
+    // Generated by `tests/override_arrays.rs`.
+    convert_ir("override-arrays", Targets::SPIRV);
 }
 
 #[cfg(feature = "glsl-in")]
@@ -950,5 +1166,7 @@ fn convert_glsl_folder() {
         {
             write_output_wgsl(&input, &module, &info, &WgslOutParameters::default());
         }
+        #[cfg(feature = "metrics")]
+        input.check_metrics();
     }
 }
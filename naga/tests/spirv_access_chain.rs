@@ -0,0 +1,72 @@
+/*!
+Test that the SPIR-V backend folds nested accesses into one `OpAccessChain`.
+*/
+
+#![cfg(all(feature = "wgsl-in", feature = "spv-out"))]
+
+use naga::proc::{BoundsCheckPolicies, BoundsCheckPolicy};
+
+const SOURCE: &str = "
+struct Inner {
+    c: array<f32, 4>,
+}
+
+struct Middle {
+    b: array<Inner, 4>,
+}
+
+struct Outer {
+    a: array<Middle, 4>,
+}
+
+@group(0) @binding(0)
+var<storage, read_write> outer: Outer;
+
+@compute @workgroup_size(1)
+fn main(@builtin(local_invocation_index) i: u32) {
+    outer.a[i].b[i + 1u].c[2] = outer.a[2].b[i].c[i];
+}
+";
+
+/// Return the number of `OpAccessChain` and `OpExtInst` instructions in the
+/// SPIR-V for `SOURCE`, when buffer accesses use `policy`.
+fn count_instructions(policy: BoundsCheckPolicy) -> (usize, usize) {
+    use naga::back::spv;
+    use naga::valid;
+    use rspirv::spirv::Op;
+
+    let module = naga::front::wgsl::parse_str(SOURCE).unwrap();
+    let info = valid::Validator::new(valid::ValidationFlags::all(), valid::Capabilities::all())
+        .validate(&module)
+        .unwrap();
+    let options = spv::Options {
+        bounds_check_policies: BoundsCheckPolicies {
+            buffer: policy,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let words = spv::write_vec(&module, &info, &options, None).unwrap();
+    let spv = rspirv::dr::load_words(words).unwrap();
+
+    let count = |op| {
+        spv.functions
+            .iter()
+            .flat_map(|function| &function.blocks)
+            .flat_map(|block| &block.instructions)
+            .filter(|inst| inst.class.opcode == op)
+            .count()
+    };
+    (count(Op::AccessChain), count(Op::ExtInst))
+}
+
+#[test]
+fn nested_accesses() {
+    // One chain for the store's pointer, one for the load's, and one taken
+    // once at the start of the function to unwrap the global's block struct.
+    assert_eq!(count_instructions(BoundsCheckPolicy::Unchecked), (3, 0));
+
+    // Each of the four dynamic indices is clamped with `UMin`, but the
+    // chains stay whole.
+    assert_eq!(count_instructions(BoundsCheckPolicy::Restrict), (3, 4));
+}
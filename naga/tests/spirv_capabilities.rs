@@ -176,3 +176,294 @@ fn storage_image_formats() {
         "#,
     );
 }
+
+/// Write `source` with every `u32` replaced by `scalar`, returning the
+/// capabilities and extensions used.
+///
+/// The WGSL front end and validator don't accept 8- or 16-bit integers, so
+/// narrow the types after validation.
+fn write_narrowed(
+    source: &str,
+    scalar: naga::Scalar,
+    capabilities: Option<naga::FastHashSet<Ca>>,
+) -> Result<(naga::FastIndexSet<Ca>, Vec<String>), naga::back::spv::Error> {
+    use naga::back::spv;
+    use naga::valid;
+
+    let mut module = naga::front::wgsl::parse_str(source).unwrap();
+    let info = valid::Validator::new(valid::ValidationFlags::all(), valid::Capabilities::all())
+        .validate(&module)
+        .expect("validation failed");
+
+    let u32_type = naga::Type {
+        name: None,
+        inner: naga::TypeInner::Scalar(naga::Scalar::U32),
+    };
+    let handle = module.types.get(&u32_type).unwrap();
+    module.types.replace(
+        handle,
+        naga::Type {
+            name: None,
+            inner: naga::TypeInner::Scalar(scalar),
+        },
+    );
+
+    let options = spv::Options {
+        capabilities,
+        ..spv::Options::default()
+    };
+    let mut words = vec![];
+    let mut writer = spv::Writer::new(&options).unwrap();
+    writer.write(&module, &info, None, &None, &mut words)?;
+
+    let spirv = rspirv::dr::load_words(words).unwrap();
+    let extensions = spirv
+        .extensions
+        .iter()
+        .map(|inst| match inst.operands[0] {
+            rspirv::dr::Operand::LiteralString(ref name) => name.clone(),
+            ref other => panic!("unexpected extension operand {other:?}"),
+        })
+        .collect();
+    Ok((writer.get_capabilities_used().clone(), extensions))
+}
+
+fn narrow_buffer_source(space: &str) -> String {
+    let binding = match space {
+        "push_constant" => "",
+        _ => "@group(0) @binding(0)",
+    };
+    format!(
+        "
+        struct Data {{ value: u32 }}
+        {binding} var<{space}> data: Data;
+        @compute @workgroup_size(1)
+        fn main() {{ _ = data.value; }}
+        "
+    )
+}
+
+fn require_narrow_storage(space: &str, scalar: naga::Scalar, required: Ca, extension: &str) {
+    let source = narrow_buffer_source(space);
+    let (caps, extensions) = write_narrowed(&source, scalar, None).unwrap();
+    assert!(caps.contains(&required), "{required:?} missing: {caps:?}");
+    assert!(
+        extensions.iter().any(|ext| ext == extension),
+        "{extension} missing: {extensions:?}"
+    );
+}
+
+const U16: naga::Scalar = naga::Scalar {
+    kind: naga::ScalarKind::Uint,
+    width: 2,
+};
+const U8: naga::Scalar = naga::Scalar {
+    kind: naga::ScalarKind::Uint,
+    width: 1,
+};
+
+#[test]
+fn storage_buffer_16bit() {
+    require_narrow_storage(
+        "storage",
+        U16,
+        Ca::StorageBuffer16BitAccess,
+        "SPV_KHR_16bit_storage",
+    );
+}
+
+#[test]
+fn uniform_buffer_16bit() {
+    require_narrow_storage(
+        "uniform",
+        U16,
+        Ca::UniformAndStorageBuffer16BitAccess,
+        "SPV_KHR_16bit_storage",
+    );
+}
+
+#[test]
+fn push_constant_16bit() {
+    require_narrow_storage(
+        "push_constant",
+        U16,
+        Ca::StoragePushConstant16,
+        "SPV_KHR_16bit_storage",
+    );
+}
+
+#[test]
+fn storage_buffer_8bit() {
+    require_narrow_storage(
+        "storage",
+        U8,
+        Ca::StorageBuffer8BitAccess,
+        "SPV_KHR_8bit_storage",
+    );
+}
+
+#[test]
+fn uniform_buffer_8bit() {
+    require_narrow_storage(
+        "uniform",
+        U8,
+        Ca::UniformAndStorageBuffer8BitAccess,
+        "SPV_KHR_8bit_storage",
+    );
+}
+
+#[test]
+fn push_constant_8bit() {
+    require_narrow_storage(
+        "push_constant",
+        U8,
+        Ca::StoragePushConstant8,
+        "SPV_KHR_8bit_storage",
+    );
+}
+
+#[test]
+fn narrow_storage_unavailable() {
+    let source = narrow_buffer_source("uniform");
+    let available = [Ca::Shader, Ca::Int16, Ca::StorageBuffer16BitAccess]
+        .into_iter()
+        .collect();
+    match write_narrowed(&source, U16, Some(available)) {
+        Err(naga::back::spv::Error::MissingCapabilities(_, caps)) => {
+            assert_eq!(caps, [Ca::UniformAndStorageBuffer16BitAccess]);
+        }
+        other => panic!("expected a missing capability error, got {other:?}"),
+    }
+}
+
+const DOT4_PACKED: &str = "
+    @group(0) @binding(0) var<storage, read_write> values: array<u32, 4>;
+    @compute @workgroup_size(1)
+    fn main() {
+        values[2] = bitcast<u32>(dot4I8Packed(values[0], values[1]));
+        values[3] = dot4U8Packed(values[0], values[1]);
+    }
+";
+
+/// Write [`DOT4_PACKED`], returning the capabilities used, and the opcodes
+/// and extensions in the output.
+///
+/// `rspirv` doesn't know the integer dot product instructions yet, so walk
+/// the words by hand.
+fn write_dot4_packed(
+    lang_version: (u8, u8),
+    capabilities: Option<naga::FastHashSet<Ca>>,
+) -> (naga::FastIndexSet<Ca>, Vec<spirv::Op>, Vec<String>) {
+    use naga::back::spv;
+    use naga::valid;
+
+    let module = naga::front::wgsl::parse_str(DOT4_PACKED).unwrap();
+    let info = valid::Validator::new(valid::ValidationFlags::all(), valid::Capabilities::all())
+        .validate(&module)
+        .expect("validation failed");
+
+    let options = spv::Options {
+        lang_version,
+        capabilities,
+        ..spv::Options::default()
+    };
+    let mut words = vec![];
+    let mut writer = spv::Writer::new(&options).unwrap();
+    writer
+        .write(&module, &info, None, &None, &mut words)
+        .unwrap();
+
+    let mut opcodes = vec![];
+    let mut extensions = vec![];
+    let mut rest = &words[5..];
+    while let Some(&first) = rest.first() {
+        let (instruction, next) = rest.split_at((first >> 16) as usize);
+        let op = spirv::Op::from_u32(first & 0xffff).unwrap();
+        if op == spirv::Op::Extension {
+            let bytes: Vec<u8> = instruction[1..]
+                .iter()
+                .flat_map(|word| word.to_le_bytes())
+                .take_while(|&byte| byte != 0)
+                .collect();
+            extensions.push(String::from_utf8(bytes).unwrap());
+        }
+        opcodes.push(op);
+        rest = next;
+    }
+    (writer.get_capabilities_used().clone(), opcodes, extensions)
+}
+
+#[test]
+fn dot4_packed_emulated() {
+    require_and_forbid(
+        &[],
+        &[Ca::DotProduct, Ca::DotProductInput4x8BitPacked],
+        DOT4_PACKED,
+    );
+    let (_, opcodes, _) = write_dot4_packed((1, 0), None);
+    assert!(opcodes.contains(&spirv::Op::BitFieldSExtract));
+    assert!(opcodes.contains(&spirv::Op::BitFieldUExtract));
+    assert!(!opcodes.contains(&spirv::Op::SDot));
+}
+
+#[test]
+fn dot4_packed_extension() {
+    let available = [Ca::Shader, Ca::DotProduct, Ca::DotProductInput4x8BitPacked]
+        .into_iter()
+        .collect();
+    let (caps, opcodes, extensions) = write_dot4_packed((1, 0), Some(available));
+    assert!(caps.contains(&Ca::DotProduct));
+    assert!(caps.contains(&Ca::DotProductInput4x8BitPacked));
+    assert!(extensions.contains(&"SPV_KHR_integer_dot_product".to_string()));
+    assert!(opcodes.contains(&spirv::Op::SDot));
+    assert!(opcodes.contains(&spirv::Op::UDot));
+    assert!(!opcodes.contains(&spirv::Op::BitFieldSExtract));
+}
+
+#[test]
+fn dot4_packed_core() {
+    let (caps, opcodes, extensions) = write_dot4_packed((1, 6), None);
+    assert!(caps.contains(&Ca::DotProductInput4x8BitPacked));
+    assert!(!extensions.contains(&"SPV_KHR_integer_dot_product".to_string()));
+    assert!(opcodes.contains(&spirv::Op::SDot));
+    assert!(opcodes.contains(&spirv::Op::UDot));
+}
+
+#[cfg(feature = "glsl-in")]
+#[test]
+fn float64_from_glsl() {
+    use naga::back::spv;
+    use naga::front::glsl;
+    use naga::valid;
+
+    let source = r#"
+        #version 450
+        layout(std140, set = 0, binding = 0) uniform Block {
+            double a;
+            dmat3 b;
+        };
+        layout(location = 0) out vec4 o;
+        void main() {
+            double x = a * 0.5lf + b[1][2];
+            o = vec4(float(x));
+        }
+    "#;
+
+    let module = glsl::Frontend::default()
+        .parse(&glsl::Options::from(naga::ShaderStage::Fragment), source)
+        .unwrap();
+
+    let validate = |capabilities| {
+        valid::Validator::new(valid::ValidationFlags::all(), capabilities).validate(&module)
+    };
+    validate(valid::Capabilities::default())
+        .expect_err("doubles should require the FLOAT64 capability");
+    let info = validate(valid::Capabilities::all()).expect("validation failed");
+
+    let mut words = vec![];
+    let mut writer = spv::Writer::new(&spv::Options::default()).unwrap();
+    writer
+        .write(&module, &info, None, &None, &mut words)
+        .unwrap();
+    assert!(writer.get_capabilities_used().contains(&Ca::Float64));
+}
@@ -0,0 +1,204 @@
+/*!
+Test that the SPIR-V frontend recovers entry point interfaces declared as
+struct-typed `Input` and `Output` variables with member decorations.
+*/
+
+#![cfg(all(feature = "spv-in", feature = "spv-out"))]
+
+use rspirv::dr::{Builder, Operand};
+use rspirv::spirv;
+
+/// An interface struct member: its type, and its decorations.
+type Member = (spirv::Word, Vec<(spirv::Decoration, Option<Operand>)>);
+
+/// Build a module whose entry point reads or writes a single interface block.
+///
+/// If `stage` is `Vertex`, the block is the entry point's output, and there
+/// are no inputs. If it is `Fragment`, the block is the entry point's input,
+/// and there is a single `vec4` output at location 0.
+fn build(stage: spirv::ExecutionModel) -> Vec<u32> {
+    let mut b = Builder::new();
+    b.set_version(1, 0);
+    b.capability(spirv::Capability::Shader);
+    b.capability(spirv::Capability::SampleRateShading);
+    b.memory_model(spirv::AddressingModel::Logical, spirv::MemoryModel::GLSL450);
+
+    let void = b.type_void();
+    let fn_ty = b.type_function(void, vec![]);
+    let f32_ty = b.type_float(32);
+    let u32_ty = b.type_int(32, 0);
+    let vec4_ty = b.type_vector(f32_ty, 4);
+
+    let built_in = match stage {
+        spirv::ExecutionModel::Vertex => spirv::BuiltIn::Position,
+        _ => spirv::BuiltIn::FragCoord,
+    };
+    let members: Vec<Member> = vec![
+        (
+            vec4_ty,
+            vec![(spirv::Decoration::BuiltIn, Some(Operand::BuiltIn(built_in)))],
+        ),
+        (
+            vec4_ty,
+            vec![
+                (spirv::Decoration::Location, Some(Operand::LiteralInt32(0))),
+                (spirv::Decoration::Centroid, None),
+            ],
+        ),
+        (
+            f32_ty,
+            vec![
+                (spirv::Decoration::Location, Some(Operand::LiteralInt32(1))),
+                (spirv::Decoration::NoPerspective, None),
+                (spirv::Decoration::Sample, None),
+            ],
+        ),
+        (
+            u32_ty,
+            vec![
+                (spirv::Decoration::Location, Some(Operand::LiteralInt32(2))),
+                (spirv::Decoration::Flat, None),
+            ],
+        ),
+    ];
+    let block_ty = b.type_struct(members.iter().map(|&(ty, _)| ty));
+    b.decorate(block_ty, spirv::Decoration::Block, None);
+    for (index, (_, decorations)) in members.into_iter().enumerate() {
+        for (decoration, operand) in decorations {
+            b.member_decorate(block_ty, index as u32, decoration, operand);
+        }
+    }
+
+    let class = match stage {
+        spirv::ExecutionModel::Vertex => spirv::StorageClass::Output,
+        _ => spirv::StorageClass::Input,
+    };
+    let block_ptr = b.type_pointer(None, class, block_ty);
+    let block = b.variable(block_ptr, None, class, None);
+    let mut interface = vec![block];
+    if stage == spirv::ExecutionModel::Fragment {
+        let color_ptr = b.type_pointer(None, spirv::StorageClass::Output, vec4_ty);
+        let color = b.variable(color_ptr, None, spirv::StorageClass::Output, None);
+        b.decorate(
+            color,
+            spirv::Decoration::Location,
+            Some(Operand::LiteralInt32(0)),
+        );
+        interface.push(color);
+    }
+
+    let main = b
+        .begin_function(void, None, spirv::FunctionControl::NONE, fn_ty)
+        .unwrap();
+    b.begin_block(None).unwrap();
+    b.ret().unwrap();
+    b.end_function().unwrap();
+    b.entry_point(stage, main, "main", interface);
+    if stage == spirv::ExecutionModel::Fragment {
+        b.execution_mode(main, spirv::ExecutionMode::OriginUpperLeft, []);
+    }
+
+    use rspirv::binary::Assemble as _;
+    b.module().assemble()
+}
+
+fn parse(words: &[u32]) -> naga::Module {
+    let options = naga::front::spv::Options {
+        adjust_coordinate_space: false,
+        ..Default::default()
+    };
+    naga::front::spv::Frontend::new(words.iter().cloned(), &options)
+        .parse()
+        .unwrap()
+}
+
+fn write(module: &naga::Module) -> Vec<u32> {
+    use naga::back::spv;
+    use naga::valid;
+
+    let info = valid::Validator::new(valid::ValidationFlags::all(), valid::Capabilities::all())
+        .validate(module)
+        .expect("validation failed");
+    let options = spv::Options {
+        flags: spv::WriterFlags::empty(),
+        ..Default::default()
+    };
+    spv::write_vec(module, &info, &options, None).unwrap()
+}
+
+/// Return the bindings of the entry point's inputs or outputs, with structs
+/// flattened, sorted so that the order of the members doesn't matter.
+fn bindings(module: &naga::Module, output: bool) -> Vec<naga::Binding> {
+    fn collect(
+        module: &naga::Module,
+        ty: naga::Handle<naga::Type>,
+        binding: Option<&naga::Binding>,
+        out: &mut Vec<naga::Binding>,
+    ) {
+        match module.types[ty].inner {
+            naga::TypeInner::Struct { ref members, .. } => {
+                for member in members {
+                    collect(module, member.ty, member.binding.as_ref(), out);
+                }
+            }
+            _ => out.push(binding.unwrap().clone()),
+        }
+    }
+
+    let function = &module.entry_points[0].function;
+    let mut out = Vec::new();
+    if output {
+        let result = function.result.as_ref().unwrap();
+        collect(module, result.ty, result.binding.as_ref(), &mut out);
+    } else {
+        for argument in function.arguments.iter() {
+            collect(module, argument.ty, argument.binding.as_ref(), &mut out);
+        }
+    }
+    out.sort_by_key(|binding| format!("{binding:?}"));
+    out
+}
+
+fn expected() -> Vec<naga::Binding> {
+    let mut expected = vec![
+        naga::Binding::BuiltIn(naga::BuiltIn::Position { invariant: false }),
+        naga::Binding::Location {
+            location: 0,
+            interpolation: Some(naga::Interpolation::Perspective),
+            sampling: Some(naga::Sampling::Centroid),
+            second_blend_source: false,
+        },
+        naga::Binding::Location {
+            location: 1,
+            interpolation: Some(naga::Interpolation::Linear),
+            sampling: Some(naga::Sampling::Sample),
+            second_blend_source: false,
+        },
+        naga::Binding::Location {
+            location: 2,
+            interpolation: Some(naga::Interpolation::Flat),
+            sampling: None,
+            second_blend_source: false,
+        },
+    ];
+    expected.sort_by_key(|binding| format!("{binding:?}"));
+    expected
+}
+
+#[test]
+fn output_block() {
+    let module = parse(&build(spirv::ExecutionModel::Vertex));
+    assert_eq!(bindings(&module, true), expected());
+
+    let round_trip = parse(&write(&module));
+    assert_eq!(bindings(&round_trip, true), expected());
+}
+
+#[test]
+fn input_block() {
+    let module = parse(&build(spirv::ExecutionModel::Fragment));
+    assert_eq!(bindings(&module, false), expected());
+
+    let round_trip = parse(&write(&module));
+    assert_eq!(bindings(&round_trip, false), expected());
+}
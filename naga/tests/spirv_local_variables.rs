@@ -0,0 +1,117 @@
+/*!
+Test how the SPIR-V backend initializes function-local variables.
+*/
+
+#![cfg(all(feature = "wgsl-in", feature = "spv-out"))]
+
+use naga::back::spv;
+use rspirv::dr::{Instruction, Operand};
+use rspirv::spirv::Op;
+
+const SHADER: &str = "
+    fn locals(x: f32) -> f32 {
+        var constant = 1.0;
+        var zeroed: f32;
+        var also_zeroed: f32;
+        var runtime = x;
+        for (var i = 0; i < 2; i++) {
+            var in_loop = 2.0;
+            in_loop += x;
+            runtime += in_loop;
+        }
+        return constant + zeroed + also_zeroed + runtime;
+    }
+";
+
+fn write() -> rspirv::dr::Module {
+    let module = naga::front::wgsl::parse_str(SHADER).unwrap();
+    let info = naga::valid::Validator::new(
+        naga::valid::ValidationFlags::all(),
+        naga::valid::Capabilities::all(),
+    )
+    .validate(&module)
+    .unwrap();
+
+    let options = spv::Options {
+        flags: spv::WriterFlags::DEBUG,
+        ..Default::default()
+    };
+    let words = spv::write_vec(&module, &info, &options, None).unwrap();
+    rspirv::dr::load_words(words).unwrap()
+}
+
+fn id_named(spirv: &rspirv::dr::Module, name: &str) -> u32 {
+    spirv
+        .debug_names
+        .iter()
+        .find(|inst| inst.operands[1] == Operand::LiteralString(name.to_string()))
+        .unwrap_or_else(|| panic!("no variable named {name}"))
+        .operands[0]
+        .unwrap_id_ref()
+}
+
+fn definition(spirv: &rspirv::dr::Module, id: u32) -> &Instruction {
+    spirv
+        .all_inst_iter()
+        .find(|inst| inst.result_id == Some(id))
+        .unwrap()
+}
+
+/// Return the initializer of the variable named `name`, and how many times
+/// the function stores to it.
+fn initializer_and_stores<'a>(
+    spirv: &'a rspirv::dr::Module,
+    name: &str,
+) -> (&'a Instruction, usize) {
+    let id = id_named(spirv, name);
+    let variable = definition(spirv, id);
+    assert_eq!(variable.class.opcode, Op::Variable);
+    let initializer = definition(spirv, variable.operands[1].unwrap_id_ref());
+    let stores = spirv
+        .all_inst_iter()
+        .filter(|inst| inst.class.opcode == Op::Store && inst.operands[0] == Operand::IdRef(id))
+        .count();
+    (initializer, stores)
+}
+
+#[test]
+fn constant_initializer() {
+    let spirv = write();
+    let (initializer, stores) = initializer_and_stores(&spirv, "constant");
+    assert_eq!(initializer.class.opcode, Op::Constant);
+    assert_eq!(stores, 0);
+}
+
+#[test]
+fn shared_zero_initializer() {
+    let spirv = write();
+    let (zeroed, stores) = initializer_and_stores(&spirv, "zeroed");
+    assert_eq!(zeroed.class.opcode, Op::ConstantNull);
+    assert_eq!(stores, 0);
+    let (also_zeroed, _) = initializer_and_stores(&spirv, "also_zeroed");
+    assert_eq!(zeroed.result_id, also_zeroed.result_id);
+
+    let nulls = spirv
+        .types_global_values
+        .iter()
+        .filter(|inst| {
+            inst.class.opcode == Op::ConstantNull && inst.result_type == zeroed.result_type
+        })
+        .count();
+    assert_eq!(nulls, 1);
+}
+
+#[test]
+fn runtime_initializers_keep_their_stores() {
+    let spirv = write();
+
+    // The initial value depends on the argument.
+    let (initializer, stores) = initializer_and_stores(&spirv, "runtime");
+    assert_eq!(initializer.class.opcode, Op::ConstantNull);
+    assert_eq!(stores, 2);
+
+    // The initial value is constant, but must be stored on every iteration.
+    let (initializer, stores) = initializer_and_stores(&spirv, "in_loop");
+    assert_eq!(initializer.class.opcode, Op::ConstantNull);
+    assert_eq!(stores, 2);
+}
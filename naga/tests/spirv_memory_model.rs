@@ -0,0 +1,292 @@
+/*!
+Test how the SPIR-V backend writes coherent and volatile storage buffers under
+the GLSL450 and Vulkan memory models.
+*/
+
+#![cfg(all(feature = "wgsl-in", feature = "spv-out"))]
+
+use rspirv::dr::{Module, Operand};
+use rspirv::spirv;
+
+const SOURCE: &str = "
+    enable naga_memory_decorations;
+
+    @group(0) @binding(0) @coherent
+    var<storage, read_write> coherent_buf: u32;
+    @group(0) @binding(1) @volatile
+    var<storage, read_write> volatile_buf: u32;
+    @group(0) @binding(2)
+    var<storage, read_write> plain_buf: u32;
+
+    @compute @workgroup_size(1)
+    fn main() {
+        coherent_buf = volatile_buf + plain_buf;
+        volatile_buf = coherent_buf;
+    }
+";
+
+fn write(source: &str, lang_version: (u8, u8), flags: naga::back::spv::WriterFlags) -> Module {
+    use naga::back::spv;
+    use naga::valid;
+
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+    let info = valid::Validator::new(valid::ValidationFlags::all(), valid::Capabilities::all())
+        .validate(&module)
+        .expect("validation failed");
+
+    let options = spv::Options {
+        lang_version,
+        flags,
+        ..Default::default()
+    };
+    let words = spv::write_vec(&module, &info, &options, None).unwrap();
+    rspirv::dr::load_words(words).unwrap()
+}
+
+/// Return the id of the global variable named `name`.
+fn variable(module: &Module, name: &str) -> spirv::Word {
+    module
+        .debug_names
+        .iter()
+        .find(|inst| inst.operands[1] == Operand::LiteralString(name.to_string()))
+        .unwrap_or_else(|| panic!("no OpName for `{name}`"))
+        .operands[0]
+        .unwrap_id_ref()
+}
+
+fn decorations(module: &Module, id: spirv::Word) -> Vec<spirv::Decoration> {
+    module
+        .annotations
+        .iter()
+        .filter(|inst| {
+            inst.class.opcode == spirv::Op::Decorate && inst.operands[0] == Operand::IdRef(id)
+        })
+        .map(|inst| inst.operands[1].unwrap_decoration())
+        .collect()
+}
+
+/// Return the operands after the pointer and value of every load from, or
+/// store to, the global variable `id`.
+///
+/// The variables are wrapped in structs, so `main` accesses them through an
+/// `OpAccessChain`.
+fn accesses(module: &Module, op: spirv::Op, id: spirv::Word) -> Vec<Vec<Operand>> {
+    let skip = match op {
+        spirv::Op::Load => 1,
+        _ => 2,
+    };
+    let instructions = || {
+        module
+            .functions
+            .iter()
+            .flat_map(|function| function.blocks.iter())
+            .flat_map(|block| block.instructions.iter())
+    };
+    let pointers: Vec<Operand> = instructions()
+        .filter(|inst| {
+            inst.class.opcode == spirv::Op::AccessChain && inst.operands[0] == Operand::IdRef(id)
+        })
+        .map(|inst| Operand::IdRef(inst.result_id.unwrap()))
+        .collect();
+    instructions()
+        .filter(|inst| inst.class.opcode == op && pointers.contains(&inst.operands[0]))
+        .map(|inst| inst.operands[skip..].to_vec())
+        .collect()
+}
+
+fn memory_model(module: &Module) -> spirv::MemoryModel {
+    module.memory_model.as_ref().unwrap().operands[1].unwrap_memory_model()
+}
+
+fn capabilities(module: &Module) -> Vec<spirv::Capability> {
+    module
+        .capabilities
+        .iter()
+        .map(|inst| inst.operands[0].unwrap_capability())
+        .collect()
+}
+
+fn extensions(module: &Module) -> Vec<String> {
+    module
+        .extensions
+        .iter()
+        .map(|inst| inst.operands[0].unwrap_literal_string().to_string())
+        .collect()
+}
+
+/// Return the value of the scope constant `scope`.
+fn scope(module: &Module, scope: &Operand) -> u32 {
+    let id = match *scope {
+        Operand::IdScope(id) => id,
+        ref other => panic!("expected a scope id, got {other:?}"),
+    };
+    let inst = module
+        .types_global_values
+        .iter()
+        .find(|inst| inst.result_id == Some(id))
+        .unwrap();
+    inst.operands[0].unwrap_literal_int32()
+}
+
+#[test]
+fn glsl450_decorates_variables() {
+    use naga::back::spv::WriterFlags;
+
+    let module = write(SOURCE, (1, 0), WriterFlags::DEBUG);
+    assert_eq!(memory_model(&module), spirv::MemoryModel::GLSL450);
+    assert!(!capabilities(&module).contains(&spirv::Capability::VulkanMemoryModel));
+
+    let coherent = variable(&module, "coherent_buf");
+    let volatile = variable(&module, "volatile_buf");
+    let plain = variable(&module, "plain_buf");
+    assert!(decorations(&module, coherent).contains(&spirv::Decoration::Coherent));
+    assert!(!decorations(&module, coherent).contains(&spirv::Decoration::Volatile));
+    assert!(decorations(&module, volatile).contains(&spirv::Decoration::Volatile));
+    assert!(!decorations(&module, volatile).contains(&spirv::Decoration::Coherent));
+    assert!(!decorations(&module, plain).contains(&spirv::Decoration::Coherent));
+
+    // No access has memory operands.
+    for id in [coherent, volatile, plain] {
+        for op in [spirv::Op::Load, spirv::Op::Store] {
+            for operands in accesses(&module, op, id) {
+                assert_eq!(operands, [], "{op:?}");
+            }
+        }
+    }
+}
+
+#[test]
+fn vulkan_uses_memory_operands() {
+    use naga::back::spv::WriterFlags;
+
+    let module = write(
+        SOURCE,
+        (1, 0),
+        WriterFlags::DEBUG | WriterFlags::VULKAN_MEMORY_MODEL,
+    );
+    assert_eq!(memory_model(&module), spirv::MemoryModel::Vulkan);
+    let capabilities = capabilities(&module);
+    assert!(capabilities.contains(&spirv::Capability::VulkanMemoryModel));
+    assert!(capabilities.contains(&spirv::Capability::VulkanMemoryModelDeviceScope));
+    assert!(extensions(&module).contains(&"SPV_KHR_vulkan_memory_model".to_string()));
+
+    let coherent = variable(&module, "coherent_buf");
+    let volatile = variable(&module, "volatile_buf");
+    let plain = variable(&module, "plain_buf");
+    for id in [coherent, volatile] {
+        let decorations = decorations(&module, id);
+        assert!(!decorations.contains(&spirv::Decoration::Coherent));
+        assert!(!decorations.contains(&spirv::Decoration::Volatile));
+    }
+
+    let loads = accesses(&module, spirv::Op::Load, coherent);
+    assert_eq!(loads.len(), 1);
+    assert_eq!(
+        loads[0][0],
+        Operand::MemoryAccess(
+            spirv::MemoryAccess::MAKE_POINTER_VISIBLE | spirv::MemoryAccess::NON_PRIVATE_POINTER
+        )
+    );
+    assert_eq!(scope(&module, &loads[0][1]), spirv::Scope::Device as u32);
+
+    let stores = accesses(&module, spirv::Op::Store, coherent);
+    assert_eq!(stores.len(), 1);
+    assert_eq!(
+        stores[0][0],
+        Operand::MemoryAccess(
+            spirv::MemoryAccess::MAKE_POINTER_AVAILABLE | spirv::MemoryAccess::NON_PRIVATE_POINTER
+        )
+    );
+    assert_eq!(scope(&module, &stores[0][1]), spirv::Scope::Device as u32);
+
+    let expected = Operand::MemoryAccess(
+        spirv::MemoryAccess::VOLATILE | spirv::MemoryAccess::NON_PRIVATE_POINTER,
+    );
+    for op in [spirv::Op::Load, spirv::Op::Store] {
+        let operands = accesses(&module, op, volatile);
+        assert_eq!(operands.len(), 1, "{op:?}");
+        assert_eq!(operands[0], std::slice::from_ref(&expected), "{op:?}");
+        for operands in accesses(&module, op, plain) {
+            assert_eq!(operands, [], "{op:?}");
+        }
+    }
+}
+
+#[test]
+fn vulkan_memory_model_is_core_in_1_5() {
+    use naga::back::spv::WriterFlags;
+
+    let module = write(SOURCE, (1, 5), WriterFlags::VULKAN_MEMORY_MODEL);
+    assert_eq!(memory_model(&module), spirv::MemoryModel::Vulkan);
+    assert!(!extensions(&module).contains(&"SPV_KHR_vulkan_memory_model".to_string()));
+}
+
+/// Composite copies are normally written as a single `OpCopyMemory`, but
+/// that can't carry the memory operands the Vulkan memory model needs.
+#[test]
+fn vulkan_copies_with_memory_operands() {
+    use naga::back::spv::WriterFlags;
+
+    const COPIES: &str = "
+        enable naga_memory_decorations;
+
+        struct Data {
+            values: array<u32, 4>,
+        }
+
+        @group(0) @binding(0) @coherent
+        var<storage, read_write> coherent_buf: Data;
+        @group(0) @binding(1) @volatile
+        var<storage, read_write> volatile_buf: Data;
+        @group(0) @binding(2)
+        var<storage, read_write> plain_buf: Data;
+        @group(0) @binding(3)
+        var<storage, read_write> other_buf: Data;
+
+        @compute @workgroup_size(1)
+        fn main() {
+            plain_buf = coherent_buf;
+            volatile_buf = plain_buf;
+            other_buf = plain_buf;
+        }
+    ";
+
+    let copies = |module: &Module| {
+        module
+            .functions
+            .iter()
+            .flat_map(|function| function.blocks.iter())
+            .flat_map(|block| block.instructions.iter())
+            .filter(|inst| inst.class.opcode == spirv::Op::CopyMemory)
+            .count()
+    };
+
+    let module = write(COPIES, (1, 0), WriterFlags::DEBUG);
+    assert_eq!(copies(&module), 3);
+
+    // Only the copy between undecorated buffers is still an `OpCopyMemory`.
+    let module = write(
+        COPIES,
+        (1, 0),
+        WriterFlags::DEBUG | WriterFlags::VULKAN_MEMORY_MODEL,
+    );
+    assert_eq!(copies(&module), 1);
+
+    let loads = accesses(&module, spirv::Op::Load, variable(&module, "coherent_buf"));
+    assert_eq!(loads.len(), 1);
+    assert_eq!(
+        loads[0][0],
+        Operand::MemoryAccess(
+            spirv::MemoryAccess::MAKE_POINTER_VISIBLE | spirv::MemoryAccess::NON_PRIVATE_POINTER
+        )
+    );
+    assert_eq!(scope(&module, &loads[0][1]), spirv::Scope::Device as u32);
+
+    let stores = accesses(&module, spirv::Op::Store, variable(&module, "volatile_buf"));
+    assert_eq!(
+        stores,
+        [[Operand::MemoryAccess(
+            spirv::MemoryAccess::VOLATILE | spirv::MemoryAccess::NON_PRIVATE_POINTER
+        )]]
+    );
+}
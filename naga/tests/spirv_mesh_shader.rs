@@ -0,0 +1,147 @@
+/*!
+Test how the SPIR-V backend writes task and mesh shaders.
+
+`rspirv` predates `SPV_EXT_mesh_shader`, so these tests walk the words
+directly.
+*/
+
+#![cfg(all(feature = "wgsl-in", feature = "spv-out"))]
+
+use spirv::{BuiltIn, Capability, Decoration, ExecutionMode, ExecutionModel, Op};
+
+const SOURCE: &str = "
+    enable naga_mesh_shader;
+
+    struct TaskOutput {
+        @builtin(mesh_task_size) size: vec3<u32>,
+    }
+
+    struct Vertex {
+        @builtin(position) position: vec4<f32>,
+        @location(0) color: vec4<f32>,
+    }
+
+    struct Primitive {
+        @builtin(triangle_indices) indices: vec3<u32>,
+        @builtin(cull_primitive) cull: bool,
+    }
+
+    @task @workgroup_size(1)
+    fn ts_main(@builtin(workgroup_id) id: vec3<u32>) -> TaskOutput {
+        return TaskOutput(vec3(id.x, 1u, 1u));
+    }
+
+    @mesh(triangles, Vertex, 3, Primitive, 1) @workgroup_size(3)
+    fn ms_main(@builtin(local_invocation_index) index: u32) {
+        setMeshOutputs(3u, 1u);
+        setVertex(index, Vertex(vec4(f32(index)), vec4(1.0)));
+        setPrimitive(0u, Primitive(vec3(0u, 1u, 2u), false));
+    }
+";
+
+fn write(lang_version: (u8, u8)) -> Result<Vec<u32>, naga::back::spv::Error> {
+    use naga::back::spv;
+    use naga::valid;
+
+    let module = naga::front::wgsl::parse_str(SOURCE).unwrap();
+    let info = valid::Validator::new(valid::ValidationFlags::all(), valid::Capabilities::all())
+        .validate(&module)
+        .expect("validation failed");
+
+    let options = spv::Options {
+        lang_version,
+        ..Default::default()
+    };
+    spv::write_vec(&module, &info, &options, None)
+}
+
+/// Split `words` into instructions, as opcodes and operand words.
+fn instructions(words: &[u32]) -> Vec<(u32, &[u32])> {
+    let mut instructions = Vec::new();
+    // Skip the header.
+    let mut rest = &words[5..];
+    while let Some(&first) = rest.first() {
+        let count = (first >> 16) as usize;
+        instructions.push((first & 0xffff, &rest[1..count]));
+        rest = &rest[count..];
+    }
+    instructions
+}
+
+fn operands(words: &[u32], op: Op) -> Vec<&[u32]> {
+    instructions(words)
+        .into_iter()
+        .filter(|&(opcode, _)| opcode == op as u32)
+        .map(|(_, operands)| operands)
+        .collect()
+}
+
+#[test]
+fn mesh_shading_capability() {
+    let words = write((1, 4)).unwrap();
+    assert!(operands(&words, Op::Capability).contains(&&[Capability::MeshShadingEXT as u32][..]));
+    assert_eq!(operands(&words, Op::Extension).len(), 1);
+}
+
+#[test]
+fn entry_points() {
+    let words = write((1, 4)).unwrap();
+    let models: Vec<u32> = operands(&words, Op::EntryPoint)
+        .into_iter()
+        .map(|operands| operands[0])
+        .collect();
+    assert_eq!(
+        models,
+        [
+            ExecutionModel::TaskEXT as u32,
+            ExecutionModel::MeshEXT as u32
+        ]
+    );
+
+    let modes: Vec<&[u32]> = operands(&words, Op::ExecutionMode)
+        .into_iter()
+        .map(|operands| &operands[1..])
+        .collect();
+    assert!(modes.contains(&&[ExecutionMode::OutputVertices as u32, 3][..]));
+    assert!(modes.contains(&&[ExecutionMode::OutputPrimitivesEXT as u32, 1][..]));
+    assert!(modes.contains(&&[ExecutionMode::OutputTrianglesEXT as u32][..]));
+}
+
+#[test]
+fn output_decorations() {
+    let words = write((1, 4)).unwrap();
+    let decorations = operands(&words, Op::Decorate);
+    let per_primitive = decorations
+        .iter()
+        .filter(|operands| operands[1] == Decoration::PerPrimitiveEXT as u32)
+        .count();
+    assert_eq!(per_primitive, 2);
+    for built_in in [
+        BuiltIn::PrimitiveTriangleIndicesEXT,
+        BuiltIn::CullPrimitiveEXT,
+    ] {
+        assert!(decorations
+            .iter()
+            .any(|operands| operands[1..] == [Decoration::BuiltIn as u32, built_in as u32]));
+    }
+}
+
+#[test]
+fn mesh_instructions() {
+    let words = write((1, 4)).unwrap();
+    assert_eq!(operands(&words, Op::SetMeshOutputsEXT).len(), 1);
+    // The task shader ends with `OpEmitMeshTasksEXT` instead of `OpReturn`.
+    assert_eq!(operands(&words, Op::EmitMeshTasksEXT).len(), 1);
+    // One store per member of each output struct.
+    let stores = operands(&words, Op::Store).len();
+    assert!(stores >= 4, "expected at least 4 stores, found {stores}");
+}
+
+#[test]
+fn requires_spirv_1_4() {
+    let error = write((1, 3)).unwrap_err();
+    assert!(matches!(
+        error,
+        naga::back::spv::Error::RequiresVersion(_, 1, 4)
+    ));
+}
@@ -0,0 +1,86 @@
+/*!
+Test the SPIR-V backend's `MINIMAL_OUTPUT` flag.
+
+The `minimal-output` snapshot input is also written with the flag, so CI runs
+`spirv-val` on reordered output.
+*/
+
+#![cfg(all(feature = "wgsl-in", feature = "spv-out"))]
+
+use std::io::Write as _;
+
+use naga::back::spv;
+
+/// Write every WGSL input of the snapshot tests with `flags`, returning the
+/// name and SPIR-V of each.
+fn corpus(flags: spv::WriterFlags) -> Vec<(String, Vec<u32>)> {
+    let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/in");
+    let mut paths: Vec<_> = std::fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension() == Some("wgsl".as_ref()))
+        .collect();
+    paths.sort();
+
+    let options = spv::Options {
+        flags,
+        ..Default::default()
+    };
+    paths
+        .into_iter()
+        .filter_map(|path| {
+            let source = std::fs::read_to_string(&path).unwrap();
+            let module = naga::front::wgsl::parse_str(&source).ok()?;
+            let info = naga::valid::Validator::new(
+                naga::valid::ValidationFlags::all(),
+                naga::valid::Capabilities::all(),
+            )
+            .validate(&module)
+            .ok()?;
+            let words = spv::write_vec(&module, &info, &options, None).ok()?;
+            let name = path.file_stem().unwrap().to_string_lossy().into_owned();
+            Some((name, words))
+        })
+        .collect()
+}
+
+fn deflated_size(words: &[u32]) -> usize {
+    let mut encoder = flate2::write::DeflateEncoder::new(Vec::new(), flate2::Compression::best());
+    for word in words {
+        encoder.write_all(&word.to_le_bytes()).unwrap();
+    }
+    encoder.finish().unwrap().len()
+}
+
+#[test]
+fn smaller() {
+    let flags = spv::WriterFlags::ADJUST_COORDINATE_SPACE | spv::WriterFlags::CLAMP_FRAG_DEPTH;
+    let plain = corpus(flags);
+    let minimal = corpus(flags | spv::WriterFlags::MINIMAL_OUTPUT);
+    let debug = corpus(flags | spv::WriterFlags::DEBUG | spv::WriterFlags::MINIMAL_OUTPUT);
+    assert_eq!(plain.len(), minimal.len());
+    assert!(
+        plain.len() > 50,
+        "only {} shaders in the corpus",
+        plain.len()
+    );
+
+    // `MINIMAL_OUTPUT` takes precedence over `DEBUG`.
+    assert_eq!(minimal, debug);
+
+    let mut plain_size = 0;
+    let mut minimal_size = 0;
+    for ((name, plain), (_, minimal)) in plain.iter().zip(minimal.iter()) {
+        // Nothing is added, and the module is still well formed.
+        assert!(minimal.len() <= plain.len(), "{name} grew");
+        rspirv::dr::load_words(minimal).unwrap();
+
+        plain_size += deflated_size(plain);
+        minimal_size += deflated_size(minimal);
+    }
+
+    assert!(
+        minimal_size < plain_size,
+        "{minimal_size} bytes minimal, {plain_size} bytes plain"
+    );
+}
@@ -0,0 +1,93 @@
+/*!
+Test SPIR-V backend output for calls that pass pointers into variables.
+
+The shader is also a snapshot input, so CI runs `spirv-val` on the inlined
+output.
+*/
+
+#![cfg(all(feature = "wgsl-in", feature = "spv-out"))]
+
+use naga::back::spv;
+use rspirv::dr::Operand;
+use rspirv::spirv::Op;
+use spirv::Capability;
+
+const SHADER: &str = include_str!("in/pointer-calls.wgsl");
+
+/// Write `SHADER` as SPIR-V, with `capabilities` available if given.
+///
+/// Return the module, and the capabilities used.
+fn write(capabilities: Option<&[Capability]>) -> (rspirv::dr::Module, Vec<Capability>) {
+    let module = naga::front::wgsl::parse_str(SHADER).unwrap();
+    let info = naga::valid::Validator::new(
+        naga::valid::ValidationFlags::all(),
+        naga::valid::Capabilities::all(),
+    )
+    .validate(&module)
+    .unwrap();
+
+    let options = spv::Options {
+        capabilities: capabilities.map(|caps| caps.iter().cloned().collect()),
+        ..Default::default()
+    };
+    let mut words = vec![];
+    let mut writer = spv::Writer::new(&options).unwrap();
+    writer
+        .write(&module, &info, None, &None, &mut words)
+        .unwrap();
+
+    let spirv = rspirv::dr::load_words(words).unwrap();
+    let capabilities = writer.get_capabilities_used().iter().cloned().collect();
+    (spirv, capabilities)
+}
+
+/// Return the number of calls in `spirv`, and the number of those that pass
+/// a pointer produced by an access chain.
+fn calls(spirv: &rspirv::dr::Module) -> (usize, usize) {
+    let access_chains: Vec<_> = spirv
+        .all_inst_iter()
+        .filter(|inst| matches!(inst.class.opcode, Op::AccessChain | Op::InBoundsAccessChain))
+        .filter_map(|inst| inst.result_id)
+        .collect();
+    let calls: Vec<_> = spirv
+        .all_inst_iter()
+        .filter(|inst| inst.class.opcode == Op::FunctionCall)
+        .collect();
+    for call in calls.iter() {
+        let callee = spirv
+            .functions
+            .iter()
+            .find(|function| call.operands[0] == Operand::IdRef(function.def_id().unwrap()))
+            .unwrap();
+        assert_eq!(call.operands.len(), 1 + callee.parameters.len());
+    }
+    let with_access_chains = calls
+        .iter()
+        .filter(|call| {
+            call.operands.iter().skip(1).any(|operand| match *operand {
+                Operand::IdRef(id) => access_chains.contains(&id),
+                _ => false,
+            })
+        })
+        .count();
+    (calls.len(), with_access_chains)
+}
+
+#[test]
+fn inline_without_variable_pointers() {
+    let (spirv, capabilities) = write(None);
+
+    // `main` still calls `step`, which takes a whole variable, and `nudge`
+    // still passes its own argument to `push`. The rest are inlined,
+    // including the call to `push` that inlining `nudge` into `main` adds.
+    assert_eq!(calls(&spirv), (2, 0));
+    assert!(!capabilities.contains(&Capability::VariablePointers));
+}
+
+#[test]
+fn keep_calls_with_variable_pointers() {
+    let (spirv, capabilities) = write(Some(&[Capability::Shader, Capability::VariablePointers]));
+
+    assert_eq!(calls(&spirv), (5, 3));
+    assert!(capabilities.contains(&Capability::VariablePointers));
+}
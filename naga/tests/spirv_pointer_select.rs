@@ -0,0 +1,211 @@
+/*!
+Test SPIR-V backend output for `Select` expressions that choose a pointer.
+*/
+
+#![cfg(all(feature = "wgsl-in", feature = "spv-out"))]
+
+use naga::back::spv;
+use naga::{Expression, Span, Statement};
+use rspirv::spirv::Op;
+use spirv::Capability;
+
+const GLOBALS: &str = "
+    @group(0) @binding(0) var<storage, read_write> a: vec4<u32>;
+    @group(0) @binding(1) var<storage, read_write> b: vec4<u32>;
+    @group(0) @binding(2) var<storage, read_write> out: vec4<u32>;
+";
+
+/// Build a compute shader that copies `a` or `b` into `out`, depending on
+/// the invocation index, by loading through `select(&b, &a, index == 0)`.
+///
+/// If `component` is given, copy only that component, by way of an
+/// `AccessIndex` on the selected pointer.
+fn module(component: Option<u32>) -> naga::Module {
+    let mut module = naga::front::wgsl::parse_str(GLOBALS).unwrap();
+    let global = |name: &str| {
+        module
+            .global_variables
+            .iter()
+            .find(|&(_, var)| var.name.as_deref() == Some(name))
+            .unwrap()
+            .0
+    };
+    let (a, b, out) = (global("a"), global("b"), global("out"));
+    let u32_ty = module.types.insert(
+        naga::Type {
+            name: None,
+            inner: naga::TypeInner::Scalar(naga::Scalar::U32),
+        },
+        Span::UNDEFINED,
+    );
+
+    let mut function = naga::Function {
+        arguments: vec![naga::FunctionArgument {
+            name: Some("index".to_string()),
+            ty: u32_ty,
+            binding: Some(naga::Binding::BuiltIn(naga::BuiltIn::LocalInvocationIndex)),
+        }],
+        ..Default::default()
+    };
+    let exprs = &mut function.expressions;
+    let index = exprs.append(Expression::FunctionArgument(0), Span::UNDEFINED);
+    let a = exprs.append(Expression::GlobalVariable(a), Span::UNDEFINED);
+    let b = exprs.append(Expression::GlobalVariable(b), Span::UNDEFINED);
+    let mut target = exprs.append(Expression::GlobalVariable(out), Span::UNDEFINED);
+    let zero = exprs.append(Expression::Literal(naga::Literal::U32(0)), Span::UNDEFINED);
+
+    let start = exprs.len();
+    let condition = exprs.append(
+        Expression::Binary {
+            op: naga::BinaryOperator::Equal,
+            left: index,
+            right: zero,
+        },
+        Span::UNDEFINED,
+    );
+    let mut pointer = exprs.append(
+        Expression::Select {
+            condition,
+            accept: a,
+            reject: b,
+        },
+        Span::UNDEFINED,
+    );
+    if let Some(index) = component {
+        pointer = exprs.append(
+            Expression::AccessIndex {
+                base: pointer,
+                index,
+            },
+            Span::UNDEFINED,
+        );
+        target = exprs.append(
+            Expression::AccessIndex {
+                base: target,
+                index,
+            },
+            Span::UNDEFINED,
+        );
+    }
+    let value = exprs.append(Expression::Load { pointer }, Span::UNDEFINED);
+    let emitted = exprs.range_from(start);
+
+    function
+        .body
+        .push(Statement::Emit(emitted), Span::UNDEFINED);
+    function.body.push(
+        Statement::Store {
+            pointer: target,
+            value,
+        },
+        Span::UNDEFINED,
+    );
+
+    module.entry_points.push(naga::EntryPoint {
+        name: "main".to_string(),
+        stage: naga::ShaderStage::Compute,
+        early_depth_test: None,
+        workgroup_size: [1, 1, 1],
+        workgroup_size_overrides: None,
+        mesh_info: None,
+        function,
+    });
+    module
+}
+
+/// Write `module` as SPIR-V, with `capabilities` available if given.
+///
+/// Return the opcodes of the entry point's body, and the capabilities used.
+fn write(
+    module: &naga::Module,
+    capabilities: Option<&[Capability]>,
+) -> Result<(Vec<Op>, naga::FastIndexSet<Capability>), spv::Error> {
+    use naga::valid;
+
+    let info = valid::Validator::new(valid::ValidationFlags::all(), valid::Capabilities::all())
+        .validate(module)
+        .expect("validation failed");
+
+    let options = spv::Options {
+        capabilities: capabilities.map(|caps| caps.iter().cloned().collect()),
+        ..Default::default()
+    };
+    let mut words = vec![];
+    let mut writer = spv::Writer::new(&options)?;
+    writer.write(module, &info, None, &None, &mut words)?;
+
+    let spirv = rspirv::dr::load_words(words).unwrap();
+    // Skip the first block, which loads the entry point's arguments.
+    let ops = spirv.functions[0]
+        .blocks
+        .iter()
+        .skip(1)
+        .flat_map(|block| block.instructions.iter())
+        .map(|inst| inst.class.opcode)
+        .collect();
+    Ok((ops, writer.get_capabilities_used().clone()))
+}
+
+fn count(ops: &[Op], op: Op) -> usize {
+    ops.iter().filter(|&&o| o == op).count()
+}
+
+#[test]
+fn load_with_variable_pointers() {
+    let (ops, caps) = write(
+        &module(None),
+        Some(&[
+            Capability::Shader,
+            Capability::VariablePointersStorageBuffer,
+        ]),
+    )
+    .unwrap();
+
+    // One select of the two pointers, and a single load through it.
+    assert_eq!(count(&ops, Op::Select), 1, "{ops:?}");
+    assert_eq!(count(&ops, Op::Load), 1, "{ops:?}");
+    assert_eq!(count(&ops, Op::Phi), 0, "{ops:?}");
+    assert!(caps.contains(&Capability::VariablePointersStorageBuffer));
+}
+
+#[test]
+fn load_without_variable_pointers() {
+    let (ops, caps) = write(&module(None), None).unwrap();
+
+    // A branch per pointer, each with its own load, merged by a phi.
+    assert_eq!(count(&ops, Op::Select), 0, "{ops:?}");
+    assert_eq!(count(&ops, Op::SelectionMerge), 1, "{ops:?}");
+    assert_eq!(count(&ops, Op::BranchConditional), 1, "{ops:?}");
+    assert_eq!(count(&ops, Op::Load), 2, "{ops:?}");
+    assert_eq!(count(&ops, Op::Phi), 1, "{ops:?}");
+    assert!(!caps.contains(&Capability::VariablePointersStorageBuffer));
+    assert!(!caps.contains(&Capability::VariablePointers));
+}
+
+#[test]
+fn access_with_variable_pointers() {
+    let (ops, caps) = write(
+        &module(Some(2)),
+        Some(&[Capability::Shader, Capability::VariablePointers]),
+    )
+    .unwrap();
+
+    assert_eq!(count(&ops, Op::Select), 1, "{ops:?}");
+    assert_eq!(count(&ops, Op::Load), 1, "{ops:?}");
+    assert!(caps.contains(&Capability::VariablePointers));
+}
+
+#[test]
+fn access_without_variable_pointers() {
+    // Indexing into the selected pointer can't be split into branches.
+    match write(&module(Some(2)), None) {
+        Err(spv::Error::MissingCapabilities(_, caps)) => assert_eq!(
+            caps,
+            [
+                Capability::VariablePointersStorageBuffer,
+                Capability::VariablePointers
+            ]
+        ),
+        other => panic!("expected missing capabilities, got {other:?}"),
+    }
+}
@@ -0,0 +1,78 @@
+/*!
+Test that the SPIR-V backend refuses to write resources used by an entry point
+without a `ResourceBinding`, unless asked to leave them undecorated.
+*/
+
+#![cfg(all(feature = "wgsl-in", feature = "spv-out"))]
+
+use naga::back::spv;
+use naga::valid;
+
+const SOURCE: &str = "
+    @group(0) @binding(0)
+    var tex: texture_2d<f32>;
+    @group(0) @binding(1)
+    var samp: sampler;
+
+    @fragment
+    fn main() -> @location(0) vec4<f32> {
+        return textureSample(tex, samp, vec2(0.5));
+    }
+";
+
+/// Parse [`SOURCE`] and drop the binding of the sampled texture.
+fn unbound_texture_module() -> naga::Module {
+    let mut module = naga::front::wgsl::parse_str(SOURCE).unwrap();
+    for (_, var) in module.global_variables.iter_mut() {
+        if var.name.as_deref() == Some("tex") {
+            var.binding = None;
+        }
+    }
+    module
+}
+
+fn write(module: &naga::Module, flags: spv::WriterFlags) -> Result<Vec<u32>, spv::Error> {
+    let info = valid::Validator::new(
+        valid::ValidationFlags::all() - valid::ValidationFlags::BINDINGS,
+        valid::Capabilities::all(),
+    )
+    .validate(module)
+    .expect("validation failed");
+
+    let options = spv::Options {
+        flags,
+        ..Default::default()
+    };
+    spv::write_vec(module, &info, &options, None)
+}
+
+#[test]
+fn missing_binding_is_an_error() {
+    let module = unbound_texture_module();
+    match write(&module, spv::WriterFlags::empty()) {
+        Err(spv::Error::MissingBinding(ref name)) if name == "tex" => {}
+        other => panic!("expected a missing binding error for `tex`, got {other:?}"),
+    }
+}
+
+#[test]
+fn missing_binding_allowed_by_flag() {
+    let module = unbound_texture_module();
+    write(&module, spv::WriterFlags::ALLOW_MISSING_BINDINGS).unwrap();
+}
+
+#[test]
+fn missing_binding_rejected_by_validator() {
+    let module = unbound_texture_module();
+    let err = valid::Validator::new(valid::ValidationFlags::all(), valid::Capabilities::all())
+        .validate(&module)
+        .unwrap_err();
+    match *err.as_inner() {
+        valid::ValidationError::GlobalVariable {
+            ref name,
+            source: valid::GlobalVariableError::InvalidBinding,
+            ..
+        } if name == "tex" => {}
+        ref other => panic!("expected an invalid binding error for `tex`, got {other:?}"),
+    }
+}
@@ -0,0 +1,160 @@
+/*!
+Test that the SPIR-V frontend parses the same shader to equivalent IR
+whether it was built for SPIR-V 1.0, which declares storage buffers in the
+`Uniform` storage class with `BufferBlock`, for 1.3, which uses the
+`StorageBuffer` storage class, or for 1.4 and later, whose entry point
+interfaces list every global the entry point uses.
+*/
+
+#![cfg(all(feature = "spv-in", feature = "wgsl-out"))]
+
+use rspirv::dr::{Builder, Operand};
+use rspirv::spirv;
+
+/// Build a compute shader that copies one element from a read-only buffer
+/// named `Input` to a writable buffer named `Output`.
+fn build(major: u8, minor: u8) -> Vec<u32> {
+    let storage_buffer_class = (major, minor) >= (1, 3);
+    let full_interface = (major, minor) >= (1, 4);
+    let (class, block) = if storage_buffer_class {
+        (spirv::StorageClass::StorageBuffer, spirv::Decoration::Block)
+    } else {
+        (spirv::StorageClass::Uniform, spirv::Decoration::BufferBlock)
+    };
+
+    let mut b = Builder::new();
+    b.set_version(major, minor);
+    b.capability(spirv::Capability::Shader);
+    b.memory_model(spirv::AddressingModel::Logical, spirv::MemoryModel::GLSL450);
+
+    let void = b.type_void();
+    let fn_ty = b.type_function(void, vec![]);
+    let u32_ty = b.type_int(32, 0);
+    let vec3u_ty = b.type_vector(u32_ty, 3);
+    let array_ty = b.type_runtime_array(u32_ty);
+    b.decorate(
+        array_ty,
+        spirv::Decoration::ArrayStride,
+        Some(Operand::LiteralInt32(4)),
+    );
+    let zero = b.constant_u32(u32_ty, 0);
+
+    let mut buffers = Vec::new();
+    for (binding, name, writable) in [(0, "Input", false), (1, "Output", true)] {
+        // Pass an id explicitly, so that rspirv doesn't deduplicate the
+        // two structs.
+        let struct_ty = b.id();
+        b.type_struct_id(Some(struct_ty), [array_ty]);
+        b.name(struct_ty, name);
+        b.decorate(struct_ty, block, None);
+        b.member_decorate(
+            struct_ty,
+            0,
+            spirv::Decoration::Offset,
+            Some(Operand::LiteralInt32(0)),
+        );
+        if !writable {
+            b.member_decorate(struct_ty, 0, spirv::Decoration::NonWritable, None);
+        }
+        let ptr_ty = b.type_pointer(None, class, struct_ty);
+        let var = b.variable(ptr_ty, None, class, None);
+        b.name(var, name.to_lowercase());
+        b.decorate(
+            var,
+            spirv::Decoration::DescriptorSet,
+            Some(Operand::LiteralInt32(0)),
+        );
+        b.decorate(
+            var,
+            spirv::Decoration::Binding,
+            Some(Operand::LiteralInt32(binding)),
+        );
+        buffers.push(var);
+    }
+    let element_ptr_ty = b.type_pointer(None, class, u32_ty);
+
+    let id_ptr_ty = b.type_pointer(None, spirv::StorageClass::Input, vec3u_ty);
+    let id = b.variable(id_ptr_ty, None, spirv::StorageClass::Input, None);
+    b.decorate(
+        id,
+        spirv::Decoration::BuiltIn,
+        Some(Operand::BuiltIn(spirv::BuiltIn::GlobalInvocationId)),
+    );
+
+    let main = b
+        .begin_function(void, None, spirv::FunctionControl::NONE, fn_ty)
+        .unwrap();
+    b.begin_block(None).unwrap();
+    let id_value = b.load(vec3u_ty, None, id, None, []).unwrap();
+    let index = b.composite_extract(u32_ty, None, id_value, [0]).unwrap();
+    let source = b
+        .access_chain(element_ptr_ty, None, buffers[0], [zero, index])
+        .unwrap();
+    let value = b.load(u32_ty, None, source, None, []).unwrap();
+    let target = b
+        .access_chain(element_ptr_ty, None, buffers[1], [zero, index])
+        .unwrap();
+    b.store(target, value, None, []).unwrap();
+    b.ret().unwrap();
+    b.end_function().unwrap();
+
+    let mut interface = vec![id];
+    if full_interface {
+        interface.extend(buffers);
+    }
+    b.entry_point(spirv::ExecutionModel::GLCompute, main, "main", interface);
+    b.execution_mode(main, spirv::ExecutionMode::LocalSize, [1, 1, 1]);
+
+    use rspirv::binary::Assemble as _;
+    b.module().assemble()
+}
+
+fn parse(words: &[u32]) -> Result<naga::Module, naga::front::spv::Error> {
+    naga::front::spv::Frontend::new(words.iter().cloned(), &Default::default()).parse()
+}
+
+/// Validate `module` and write it as WGSL.
+fn to_wgsl(module: &naga::Module) -> String {
+    use naga::valid;
+
+    let info = valid::Validator::new(valid::ValidationFlags::all(), valid::Capabilities::all())
+        .validate(module)
+        .expect("validation failed");
+    naga::back::wgsl::write_string(module, &info, naga::back::wgsl::WriterFlags::empty()).unwrap()
+}
+
+fn access(module: &naga::Module, name: &str) -> naga::StorageAccess {
+    let (_, var) = module
+        .global_variables
+        .iter()
+        .find(|&(_, var)| var.name.as_deref() == Some(name))
+        .unwrap();
+    match var.space {
+        naga::AddressSpace::Storage { access } => access,
+        other => panic!("`{name}` is in {other:?}"),
+    }
+}
+
+#[test]
+fn versions_are_equivalent() {
+    let mut outputs = Vec::new();
+    for (major, minor) in [(1, 0), (1, 3), (1, 5)] {
+        let module = parse(&build(major, minor)).unwrap();
+        assert_eq!(access(&module, "input"), naga::StorageAccess::LOAD);
+        assert_eq!(access(&module, "output"), naga::StorageAccess::all());
+        outputs.push(((major, minor), to_wgsl(&module)));
+    }
+
+    let expected = &outputs[0].1;
+    for &(version, ref output) in &outputs[1..] {
+        assert_eq!(output, expected, "SPIR-V {version:?}");
+    }
+}
+
+#[test]
+fn unsupported_version() {
+    match parse(&build(1, 7)) {
+        Err(naga::front::spv::Error::UnsupportedVersion(1, 7)) => {}
+        other => panic!("unexpected result: {other:?}"),
+    }
+}
@@ -0,0 +1,197 @@
+/*!
+Test SPIR-V backend branch targets for `break` and `continue` in `switch`
+statements nested in loops, and loops nested in `switch` statements.
+*/
+
+#![cfg(all(feature = "wgsl-in", feature = "spv-out"))]
+
+use rspirv::dr::{Block, Operand};
+use rspirv::spirv::{Op, Word};
+
+const SOURCE: &str = "
+    fn loop_switch_break(x: i32) {
+        loop {
+            switch x {
+                case 1: {
+                    break;
+                }
+                default: {}
+            }
+            break;
+        }
+    }
+
+    fn loop_switch_continue(x: i32) {
+        var i = 0;
+        loop {
+            switch x {
+                case 1: {
+                    if i == 2 {
+                        continue;
+                    }
+                    break;
+                }
+                default: {}
+            }
+            break;
+            continuing {
+                i += 1;
+            }
+        }
+    }
+
+    fn switch_loop_break(x: i32) {
+        switch x {
+            case 1: {
+                loop {
+                    break;
+                }
+            }
+            default: {}
+        }
+    }
+
+    fn switch_loop_continue(x: i32) {
+        var i = 0;
+        switch x {
+            case 1: {
+                loop {
+                    i += 1;
+                    if i < 4 {
+                        continue;
+                    }
+                    break;
+                }
+            }
+            default: {}
+        }
+    }
+";
+
+/// The blocks of a function, and the structured control flow headers in it.
+struct Function {
+    blocks: Vec<Block>,
+    /// The merge block and continue target of the loop.
+    loop_merge: (Word, Word),
+    /// The merge block of the `switch`, and its target for `case 1`.
+    switch: (Word, Word),
+}
+
+impl Function {
+    fn new(function: &rspirv::dr::Function) -> Self {
+        let instructions = || function.blocks.iter().flat_map(|block| &block.instructions);
+        let find = |op| instructions().find(|inst| inst.class.opcode == op).unwrap();
+        let id = |operand: &Operand| operand.unwrap_id_ref();
+
+        let loop_merge = find(Op::LoopMerge);
+        let switch = find(Op::Switch);
+        let selection_merge = instructions()
+            .zip(instructions().skip(1))
+            .find(|&(_, next)| next.class.opcode == Op::Switch)
+            .unwrap()
+            .0;
+        Function {
+            blocks: function.blocks.clone(),
+            loop_merge: (id(&loop_merge.operands[0]), id(&loop_merge.operands[1])),
+            switch: (id(&selection_merge.operands[0]), id(&switch.operands[3])),
+        }
+    }
+
+    /// Return the targets of the block `label`'s terminator.
+    fn successors(&self, label: Word) -> Vec<Word> {
+        let block = self
+            .blocks
+            .iter()
+            .find(|block| block.label.as_ref().unwrap().result_id == Some(label))
+            .unwrap();
+        let terminator = block.instructions.last().unwrap();
+        match terminator.class.opcode {
+            Op::Branch => vec![terminator.operands[0].unwrap_id_ref()],
+            Op::BranchConditional => terminator.operands[1..3]
+                .iter()
+                .map(Operand::unwrap_id_ref)
+                .collect(),
+            _ => vec![],
+        }
+    }
+
+    /// Return the block the loop header branches to.
+    fn loop_body(&self) -> Word {
+        let header = self
+            .blocks
+            .iter()
+            .find(|block| {
+                block
+                    .instructions
+                    .iter()
+                    .any(|inst| inst.class.opcode == Op::LoopMerge)
+            })
+            .unwrap();
+        header.instructions.last().unwrap().operands[0].unwrap_id_ref()
+    }
+}
+
+fn functions() -> Vec<Function> {
+    let module = naga::front::wgsl::parse_str(SOURCE).unwrap();
+    let info = naga::valid::Validator::new(
+        naga::valid::ValidationFlags::all(),
+        naga::valid::Capabilities::default(),
+    )
+    .validate(&module)
+    .unwrap();
+    let options = naga::back::spv::Options {
+        flags: naga::back::spv::WriterFlags::empty(),
+        ..Default::default()
+    };
+    let words = naga::back::spv::write_vec(&module, &info, &options, None).unwrap();
+    let module = rspirv::dr::load_words(words).unwrap();
+    module.functions.iter().map(Function::new).collect()
+}
+
+#[test]
+fn loop_switch_break() {
+    let function = &functions()[0];
+    let (switch_merge, case) = function.switch;
+    let (loop_merge, _) = function.loop_merge;
+
+    // `break` leaves the `switch`, not the loop.
+    assert_eq!(function.successors(case), [switch_merge]);
+    assert_eq!(function.successors(switch_merge), [loop_merge]);
+}
+
+#[test]
+fn loop_switch_continue() {
+    let function = &functions()[1];
+    let (switch_merge, case) = function.switch;
+    let (_, continue_target) = function.loop_merge;
+
+    // The `if` in the case either continues the loop, or falls through to
+    // the `break`, which leaves the `switch`.
+    let [then, merge] = function.successors(case)[..] else {
+        panic!("expected a conditional branch");
+    };
+    assert_eq!(function.successors(then), [continue_target]);
+    assert_eq!(function.successors(merge), [switch_merge]);
+}
+
+#[test]
+fn switch_loop_break() {
+    let function = &functions()[2];
+    let (loop_merge, _) = function.loop_merge;
+
+    // `break` leaves the loop, not the `switch`.
+    assert_eq!(function.successors(function.loop_body()), [loop_merge]);
+}
+
+#[test]
+fn switch_loop_continue() {
+    let function = &functions()[3];
+    let (loop_merge, continue_target) = function.loop_merge;
+
+    let body = function.loop_body();
+    let [then, merge] = function.successors(body)[..] else {
+        panic!("expected a conditional branch");
+    };
+    assert_eq!(function.successors(then), [continue_target]);
+    assert_eq!(function.successors(merge), [loop_merge]);
+}
@@ -0,0 +1,137 @@
+/*!
+Test SPIR-V backend output for swizzled stores.
+*/
+
+#![cfg(all(feature = "glsl-in", feature = "spv-out"))]
+
+use rspirv::spirv::Op;
+
+/// Compile the GLSL fragment shader `source` and return the instructions of
+/// the body of its `main` function.
+fn main_body(source: &str) -> Vec<rspirv::dr::Instruction> {
+    use naga::back::spv;
+    use naga::valid;
+
+    let module = naga::front::glsl::Frontend::default()
+        .parse(
+            &naga::front::glsl::Options::from(naga::ShaderStage::Fragment),
+            source,
+        )
+        .expect("expected GLSL to parse successfully");
+    let info = valid::Validator::new(valid::ValidationFlags::all(), valid::Capabilities::all())
+        .validate(&module)
+        .expect("validation failed");
+
+    let mut words = vec![];
+    let mut writer = spv::Writer::new(&spv::Options::default()).unwrap();
+    writer
+        .write(&module, &info, None, &None, &mut words)
+        .unwrap();
+
+    // The GLSL front end wraps `main` in an entry point that handles the
+    // inputs and outputs, so look for the function that isn't the wrapper.
+    let spirv = rspirv::dr::load_words(words).unwrap();
+    let entry_point_id = spirv.entry_points[0].operands[1].id_ref_any();
+    let function = spirv
+        .functions
+        .iter()
+        .find(|function| function.def.as_ref().unwrap().result_id != entry_point_id)
+        .expect("`main` function not found");
+    function
+        .blocks
+        .iter()
+        .flat_map(|block| block.instructions.iter().cloned())
+        .collect()
+}
+
+fn main_ops(source: &str) -> Vec<Op> {
+    main_body(source)
+        .iter()
+        .map(|inst| inst.class.opcode)
+        .collect()
+}
+
+fn count(ops: &[Op], op: Op) -> usize {
+    ops.iter().filter(|&&o| o == op).count()
+}
+
+#[test]
+fn two_component_store() {
+    let ops = main_ops(
+        "
+        #version 450
+        layout(location = 0) in vec2 i;
+        layout(location = 0) out vec4 o;
+        void main() {
+            o.xz = i;
+        }
+        ",
+    );
+    // One load of `o`, one shuffle, one store, instead of an extract, an
+    // access chain and a store per component.
+    assert_eq!(count(&ops, Op::VectorShuffle), 1, "{ops:?}");
+    assert_eq!(count(&ops, Op::CompositeExtract), 0, "{ops:?}");
+    assert_eq!(count(&ops, Op::AccessChain), 0, "{ops:?}");
+    assert_eq!(count(&ops, Op::Store), 1, "{ops:?}");
+}
+
+#[test]
+fn three_component_store() {
+    let ops = main_ops(
+        "
+        #version 450
+        layout(location = 0) in vec3 i;
+        layout(location = 0) out vec4 o;
+        void main() {
+            o.wxy = i;
+        }
+        ",
+    );
+    assert_eq!(count(&ops, Op::VectorShuffle), 1, "{ops:?}");
+    assert_eq!(count(&ops, Op::CompositeExtract), 0, "{ops:?}");
+    assert_eq!(count(&ops, Op::AccessChain), 0, "{ops:?}");
+    assert_eq!(count(&ops, Op::Store), 1, "{ops:?}");
+}
+
+#[test]
+fn shuffle_components() {
+    let body = main_body(
+        "
+        #version 450
+        layout(location = 0) in vec3 i;
+        layout(location = 0) out vec4 o;
+        void main() {
+            o.wxy = i;
+        }
+        ",
+    );
+    let shuffle = body
+        .iter()
+        .find(|inst| inst.class.opcode == Op::VectorShuffle)
+        .unwrap();
+    let components: Vec<u32> = shuffle.operands[2..]
+        .iter()
+        .map(|operand| match *operand {
+            rspirv::dr::Operand::LiteralInt32(value) => value,
+            ref other => panic!("unexpected shuffle operand {other:?}"),
+        })
+        .collect();
+    // Components 4.. come from `i`.
+    assert_eq!(components, [5, 6, 2, 4]);
+}
+
+#[test]
+fn single_component_store() {
+    let ops = main_ops(
+        "
+        #version 450
+        layout(location = 0) in vec2 i;
+        layout(location = 0) out vec4 o;
+        void main() {
+            o.y = i.x;
+        }
+        ",
+    );
+    assert_eq!(count(&ops, Op::VectorShuffle), 0, "{ops:?}");
+    assert_eq!(count(&ops, Op::AccessChain), 1, "{ops:?}");
+}
@@ -0,0 +1,109 @@
+/*!
+Tests for the statistics collected with the `telemetry` feature.
+*/
+
+#![cfg(all(feature = "telemetry", feature = "wgsl-in", feature = "spv-out"))]
+
+use naga::back::spv;
+use naga::valid::{Capabilities, ValidationFlags, Validator};
+
+const SOURCE: &str = "
+const SCALE: vec2<f32> = vec2<f32>(2.0, 2.0);
+
+@group(0) @binding(0)
+var<storage, read_write> values: array<vec2<f32>>;
+
+fn scaled(v: vec2<f32>) -> vec2<f32> {
+    return v * SCALE + vec2<f32>(1.0, 1.0);
+}
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    values[id.x] = scaled(values[id.x]) + vec2<f32>(1.0, 1.0);
+}
+";
+
+#[test]
+fn statistics() {
+    let module = naga::front::wgsl::parse_str(SOURCE).unwrap();
+    let mut validator = Validator::new(ValidationFlags::all(), Capabilities::default());
+    let info = validator.validate(&module).unwrap();
+
+    let stats = validator.statistics();
+    let phases = [
+        stats.types,
+        stats.constants,
+        stats.global_variables,
+        stats.functions,
+        stats.entry_points,
+    ];
+    assert!(stats.total >= phases.iter().sum());
+
+    let mut writer = spv::Writer::new(&spv::Options::default()).unwrap();
+    let mut words = Vec::new();
+    writer
+        .write(&module, &info, None, &None, &mut words)
+        .unwrap();
+
+    let stats = writer.statistics().clone();
+    let phases = [
+        stats.types,
+        stats.constants,
+        stats.global_variables,
+        stats.functions,
+        stats.entry_points,
+    ];
+    assert!(stats.total >= phases.iter().sum());
+    assert_ne!(stats.instructions, 0);
+    assert_ne!(stats.constants_created, 0);
+    assert_ne!(stats.constants_cached, 0);
+    assert_eq!(stats.ids_allocated, words[3] - 1);
+
+    // Writing the module again starts the counts over.
+    words.clear();
+    writer
+        .write(&module, &info, None, &None, &mut words)
+        .unwrap();
+    let again = writer.statistics();
+    assert_eq!(again.instructions, stats.instructions);
+    assert_eq!(again.ids_allocated, stats.ids_allocated);
+    assert_eq!(again.constants_created, stats.constants_created);
+    assert_eq!(again.constants_cached, stats.constants_cached);
+}
+
+#[test]
+fn statistics_on_error() {
+    let module = naga::front::wgsl::parse_str(SOURCE).unwrap();
+    let mut validator = Validator::new(ValidationFlags::all(), Capabilities::default());
+    let info = validator.validate(&module).unwrap();
+
+    // A module whose entry point has an invalid workgroup size fails
+    // validation after the other phases are done.
+    let mut invalid = module.clone();
+    invalid.entry_points[0].workgroup_size = [0, 1, 1];
+    validator.validate(&invalid).unwrap_err();
+    let stats = validator.statistics();
+    assert!(stats.total >= stats.types + stats.constants + stats.functions);
+    assert_ne!(stats.total, std::time::Duration::ZERO);
+    assert_eq!(stats.entry_points, std::time::Duration::ZERO);
+
+    // Asking for a missing entry point fails before anything is written.
+    let mut writer = spv::Writer::new(&spv::Options::default()).unwrap();
+    let pipeline_options = spv::PipelineOptions {
+        shader_stage: naga::ShaderStage::Fragment,
+        entry_point: "main".to_string(),
+    };
+    writer
+        .write(
+            &module,
+            &info,
+            Some(&pipeline_options),
+            &None,
+            &mut Vec::new(),
+        )
+        .unwrap_err();
+    let stats = writer.statistics();
+    assert_ne!(stats.total, std::time::Duration::ZERO);
+    assert_eq!(stats.types, std::time::Duration::ZERO);
+    assert_eq!(stats.instructions, 0);
+}
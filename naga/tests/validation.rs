@@ -0,0 +1,1492 @@
+/*!
+Tests for the validator's public API.
+*/
+
+#![cfg(feature = "wgsl-in")]
+
+use naga::valid::{Capabilities, FunctionError, ValidationError, ValidationFlags, Validator};
+use naga::Handle;
+
+#[test]
+fn revalidate_function() {
+    let mut module = naga::front::wgsl::parse_str(
+        "
+        fn first(x: i32) -> i32 {
+            return x + 1;
+        }
+
+        fn second(x: i32) -> i32 {
+            return first(x) * 2;
+        }
+        ",
+    )
+    .unwrap();
+
+    let mut validator = Validator::new(ValidationFlags::all(), Capabilities::default());
+    let mut info = validator.validate(&module).unwrap();
+    let (first, _) = module.functions.iter().next().unwrap();
+
+    // Unchanged functions revalidate cleanly.
+    let function_info = validator.validate_function(&module, first, &info).unwrap();
+    info.replace_function(first, function_info);
+
+    // Return the argument itself, instead of `x + 1`.
+    let function = module.functions.get_mut(first);
+    let argument = function
+        .expressions
+        .iter()
+        .find(|&(_, expr)| matches!(*expr, naga::Expression::FunctionArgument(0)))
+        .unwrap()
+        .0;
+    for statement in function.body.iter_mut() {
+        if let naga::Statement::Return { ref mut value } = *statement {
+            *value = Some(argument);
+        }
+    }
+    validator.validate_function(&module, first, &info).unwrap();
+
+    // Return a value of the wrong type.
+    let function = module.functions.get_mut(first);
+    let literal = function.expressions.append(
+        naga::Expression::Literal(naga::Literal::F32(1.0)),
+        naga::Span::UNDEFINED,
+    );
+    for statement in function.body.iter_mut() {
+        if let naga::Statement::Return { ref mut value } = *statement {
+            *value = Some(literal);
+        }
+    }
+    let error = validator
+        .validate_function(&module, first, &info)
+        .unwrap_err()
+        .into_inner();
+    assert!(matches!(
+        error,
+        ValidationError::Function {
+            handle,
+            source: naga::valid::FunctionError::InvalidReturnType(_),
+            ..
+        } if handle == first
+    ));
+
+    // Full validation agrees.
+    assert!(validator.validate(&module).is_err());
+}
+
+/// Calls without results must also go to earlier functions.
+#[test]
+fn revalidate_void_calls() {
+    let module = naga::front::wgsl::parse_str(
+        "
+        fn first() {}
+
+        fn second() {
+            first();
+        }
+        ",
+    )
+    .unwrap();
+
+    let mut validator = Validator::new(ValidationFlags::all(), Capabilities::default());
+    let info = validator.validate(&module).unwrap();
+    let mut handles = module.functions.iter().map(|(handle, _)| handle);
+    let (first, second) = (handles.next().unwrap(), handles.next().unwrap());
+
+    // A recursive call, and a call to a later function.
+    for callee in [first, second] {
+        let mut module = module.clone();
+        module.functions.get_mut(first).body.push(
+            naga::Statement::Call {
+                function: callee,
+                arguments: Vec::new(),
+                result: None,
+            },
+            naga::Span::UNDEFINED,
+        );
+
+        let error = validator
+            .validate_function(&module, first, &info)
+            .unwrap_err()
+            .into_inner();
+        assert!(
+            matches!(error, ValidationError::InvalidHandle(_)),
+            "{error:?}"
+        );
+
+        // Full validation agrees.
+        let error = validator.validate(&module).unwrap_err().into_inner();
+        assert!(
+            matches!(error, ValidationError::InvalidHandle(_)),
+            "{error:?}"
+        );
+    }
+}
+
+#[test]
+fn revalidate_function_of_other_module() {
+    let module = naga::front::wgsl::parse_str("fn f(x: i32) -> i32 { return x; }").unwrap();
+    let other =
+        naga::front::wgsl::parse_str("fn g(x: f32, v: vec2<u32>) -> f32 { return x; }").unwrap();
+
+    let mut validator = Validator::new(ValidationFlags::all(), Capabilities::default());
+    let info = validator.validate(&module).unwrap();
+    let (g, _) = other.functions.iter().next().unwrap();
+    let error = validator
+        .validate_function(&other, g, &info)
+        .unwrap_err()
+        .into_inner();
+    assert!(matches!(error, ValidationError::StaleModuleInfo));
+}
+
+#[test]
+fn conservative_depth_requires_frag_depth() {
+    fn validate(source: &str) -> Result<naga::valid::ModuleInfo, ValidationError> {
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+        Validator::new(ValidationFlags::all(), Capabilities::EARLY_DEPTH_TEST)
+            .validate(&module)
+            .map_err(|e| e.into_inner())
+    }
+
+    // Replacing depth in the promised direction is fine.
+    validate(
+        "
+        @fragment @early_depth_test(greater_equal)
+        fn main(@builtin(position) position: vec4<f32>) -> @builtin(frag_depth) f32 {
+            return position.z + 0.1;
+        }
+        ",
+    )
+    .unwrap();
+
+    // An unchanged depth doesn't need to be written at all.
+    validate(
+        "
+        @fragment @early_depth_test(unchanged)
+        fn main() -> @location(0) vec4<f32> {
+            return vec4<f32>(1.0);
+        }
+        ",
+    )
+    .unwrap();
+
+    let error = validate(
+        "
+        @fragment @early_depth_test(less_equal)
+        fn main() -> @location(0) vec4<f32> {
+            return vec4<f32>(1.0);
+        }
+        ",
+    )
+    .unwrap_err();
+    assert!(matches!(
+        error,
+        ValidationError::EntryPoint {
+            source: naga::valid::EntryPointError::ConservativeDepthWithoutFragDepth(
+                naga::ConservativeDepth::LessEqual
+            ),
+            ..
+        }
+    ));
+}
+
+#[test]
+fn required_capabilities() {
+    const SHADERS: &[(Capabilities, &str)] = &[
+        (
+            Capabilities::empty(),
+            "
+            @compute @workgroup_size(1)
+            fn main() {}
+            ",
+        ),
+        (
+            Capabilities::PUSH_CONSTANT,
+            "
+            var<push_constant> scale: f32;
+            @compute @workgroup_size(1)
+            fn main() { _ = scale; }
+            ",
+        ),
+        (
+            Capabilities::FLOAT64,
+            "
+            var<private> x: f64;
+            ",
+        ),
+        (
+            Capabilities::PRIMITIVE_INDEX,
+            "
+            @fragment
+            fn main(@builtin(primitive_index) i: u32) -> @location(0) vec4<f32> {
+                return vec4<f32>(f32(i));
+            }
+            ",
+        ),
+        (
+            Capabilities::SAMPLED_TEXTURE_AND_STORAGE_BUFFER_ARRAY_NON_UNIFORM_INDEXING,
+            "
+            @group(0) @binding(0) var textures: binding_array<texture_2d<f32>, 4>;
+            @fragment
+            fn main(@builtin(position) position: vec4<f32>) -> @location(0) vec4<f32> {
+                return textureLoad(textures[u32(position.x)], vec2<i32>(0), 0);
+            }
+            ",
+        ),
+        (
+            Capabilities::UNIFORM_BUFFER_AND_STORAGE_TEXTURE_ARRAY_NON_UNIFORM_INDEXING,
+            "
+            @group(0) @binding(0) var textures: binding_array<texture_storage_2d<rgba8unorm, write>, 4>;
+            @fragment
+            fn main(@builtin(position) position: vec4<f32>) {
+                textureStore(textures[u32(position.x)], vec2<i32>(0), vec4<f32>(1.0));
+            }
+            ",
+        ),
+        (
+            Capabilities::SAMPLER_NON_UNIFORM_INDEXING,
+            "
+            @group(0) @binding(0) var texture: texture_2d<f32>;
+            @group(0) @binding(1) var samplers: binding_array<sampler, 4>;
+            @fragment
+            fn main(@builtin(position) position: vec4<f32>) -> @location(0) vec4<f32> {
+                let i = u32(position.x);
+                return textureSampleLevel(texture, samplers[i], vec2<f32>(0.0), 0.0);
+            }
+            ",
+        ),
+        (
+            Capabilities::CLIP_DISTANCE,
+            "
+            enable clip_distances;
+            struct VertexOutput {
+                @builtin(position) position: vec4<f32>,
+                @builtin(clip_distances) clip_distances: array<f32, 1>,
+            }
+            @vertex
+            fn main() -> VertexOutput {
+                return VertexOutput(vec4<f32>(0.0), array<f32, 1>(0.0));
+            }
+            ",
+        ),
+        (
+            Capabilities::STORAGE_TEXTURE_16BIT_NORM_FORMATS,
+            "
+            @group(0) @binding(0) var texture: texture_storage_2d<r16unorm, write>;
+            ",
+        ),
+        (
+            Capabilities::MULTIVIEW,
+            "
+            @fragment
+            fn main(@builtin(view_index) view: i32) -> @location(0) vec4<f32> {
+                return vec4<f32>(f32(view));
+            }
+            ",
+        ),
+        (
+            Capabilities::EARLY_DEPTH_TEST,
+            "
+            @fragment @early_depth_test
+            fn main() -> @location(0) vec4<f32> {
+                return vec4<f32>(1.0);
+            }
+            ",
+        ),
+        (
+            Capabilities::MULTISAMPLED_SHADING,
+            "
+            @fragment
+            fn main(@builtin(sample_index) i: u32) -> @location(0) vec4<f32> {
+                return vec4<f32>(f32(i));
+            }
+            ",
+        ),
+        (
+            Capabilities::RAY_QUERY,
+            "
+            @group(0) @binding(0) var acc: acceleration_structure;
+            ",
+        ),
+        (
+            Capabilities::DUAL_SOURCE_BLENDING,
+            "
+            enable dual_source_blending;
+            struct FragmentOutput {
+                @location(0) color: vec4<f32>,
+                @location(0) @second_blend_source mask: vec4<f32>,
+            }
+            @fragment
+            fn main() -> FragmentOutput {
+                return FragmentOutput(vec4<f32>(1.0), vec4<f32>(0.5));
+            }
+            ",
+        ),
+        (
+            Capabilities::MESH_SHADER,
+            "
+            enable naga_mesh_shader;
+            @task @workgroup_size(1)
+            fn main() -> @builtin(mesh_task_size) vec3<u32> {
+                return vec3<u32>(1u);
+            }
+            ",
+        ),
+        (
+            Capabilities::CUBE_ARRAY_TEXTURES,
+            "
+            @group(0) @binding(0) var texture: texture_cube_array<f32>;
+            ",
+        ),
+        (
+            Capabilities::FLOAT64.union(Capabilities::MULTISAMPLED_SHADING),
+            "
+            @fragment
+            fn main(@builtin(sample_index) i: u32) -> @location(0) vec4<f32> {
+                let x = f64(i);
+                return vec4<f32>(f32(x));
+            }
+            ",
+        ),
+    ];
+
+    for &(expected, source) in SHADERS {
+        let module = naga::front::wgsl::parse_str(source)
+            .unwrap_or_else(|e| panic!("{}", e.emit_to_string(source)));
+        let required = naga::valid::required_capabilities(&module).unwrap();
+        assert_eq!(required, expected, "{source}");
+    }
+
+    // Modules that are invalid no matter what report their error.
+    let module = naga::front::wgsl::parse_str(
+        "
+        @fragment
+        fn main(@builtin(frag_depth) depth: f32) -> @location(0) vec4<f32> {
+            return vec4<f32>(depth);
+        }
+        ",
+    )
+    .unwrap();
+    assert!(naga::valid::required_capabilities(&module).is_err());
+}
+
+#[test]
+fn duplicate_entry_points() {
+    let mut module = naga::front::wgsl::parse_str(
+        "
+        @vertex
+        fn main() -> @builtin(position) vec4<f32> {
+            return vec4<f32>(0.0);
+        }
+        ",
+    )
+    .unwrap();
+
+    // An entry point with the same name, but for another stage, is fine.
+    let mut fragment = module.entry_points[0].clone();
+    fragment.stage = naga::ShaderStage::Fragment;
+    fragment.function.result = None;
+    fragment.function.body = naga::Block::new();
+    module.entry_points.push(fragment);
+    let mut validator = Validator::new(ValidationFlags::all(), Capabilities::default());
+    validator.validate(&module).unwrap();
+
+    // Another vertex entry point named `main` is not.
+    let vertex = module.entry_points[0].clone();
+    module.entry_points.push(vertex);
+    let error = validator.validate(&module).unwrap_err();
+    match *error.as_inner() {
+        ValidationError::EntryPoint {
+            stage: naga::ShaderStage::Vertex,
+            ref name,
+            source: naga::valid::EntryPointError::Conflict,
+        } => assert_eq!(name, "main"),
+        ref other => panic!("unexpected error: {other:?}"),
+    }
+}
+
+#[test]
+fn clip_distances_limit() {
+    fn validate(count: u32) -> Result<naga::valid::ModuleInfo, ValidationError> {
+        let source = format!(
+            "
+            enable clip_distances;
+            struct Output {{
+                @builtin(position) position: vec4<f32>,
+                @builtin(clip_distances) clip_distances: array<f32, {count}>,
+            }}
+            @vertex
+            fn main() -> Output {{
+                var out: Output;
+                return out;
+            }}
+            "
+        );
+        let module = naga::front::wgsl::parse_str(&source).unwrap();
+        Validator::new(ValidationFlags::all(), Capabilities::CLIP_DISTANCE)
+            .validate(&module)
+            .map_err(|e| e.into_inner())
+    }
+
+    for count in [1, 4, 5, 8] {
+        validate(count).unwrap();
+    }
+
+    let error = validate(9).unwrap_err();
+    assert!(matches!(
+        error,
+        ValidationError::EntryPoint {
+            source: naga::valid::EntryPointError::Result(
+                naga::valid::VaryingError::TooManyClipDistances(9)
+            ),
+            ..
+        }
+    ));
+}
+
+#[test]
+fn mesh_shader_outputs() {
+    fn validate(
+        vertex: &str,
+        primitive: &str,
+        attribute: &str,
+        body: &str,
+    ) -> Result<naga::valid::ModuleInfo, ValidationError> {
+        let source = format!(
+            "
+            enable naga_mesh_shader;
+            struct Vertex {{ {vertex} }}
+            struct Primitive {{ {primitive} }}
+            @mesh({attribute}) @workgroup_size(1)
+            fn main() {{ {body} }}
+            "
+        );
+        let module = naga::front::wgsl::parse_str(&source)
+            .unwrap_or_else(|e| panic!("{}", e.emit_to_string(&source)));
+        Validator::new(ValidationFlags::all(), Capabilities::MESH_SHADER)
+            .validate(&module)
+            .map_err(|e| e.into_inner())
+    }
+
+    const VERTEX: &str = "@builtin(position) position: vec4<f32>,";
+    const TRIANGLE: &str = "@builtin(triangle_indices) indices: vec3<u32>,";
+    const ATTRIBUTE: &str = "triangles, Vertex, 3, Primitive, 1";
+    const BODY: &str = "
+        setMeshOutputs(3u, 1u);
+        setVertex(0u, Vertex(vec4<f32>(1.0)));
+        setPrimitive(0u, Primitive(vec3<u32>(0u, 1u, 2u)));
+    ";
+
+    validate(VERTEX, TRIANGLE, ATTRIBUTE, BODY).unwrap();
+
+    let error = validate(VERTEX, TRIANGLE, "triangles, Vertex, 0, Primitive, 1", "").unwrap_err();
+    assert!(matches!(
+        error,
+        ValidationError::EntryPoint {
+            source: naga::valid::EntryPointError::OutOfRangeMeshOutputs,
+            ..
+        }
+    ));
+
+    let error = validate(VERTEX, TRIANGLE, "lines, Vertex, 3, Primitive, 1", "").unwrap_err();
+    assert!(matches!(
+        error,
+        ValidationError::EntryPoint {
+            source: naga::valid::EntryPointError::MissingMeshPrimitiveIndices(
+                naga::MeshOutputTopology::Lines
+            ),
+            ..
+        }
+    ));
+
+    let error = validate(
+        "@location(0) color: vec4<f32>,",
+        TRIANGLE,
+        "triangles, Vertex, 3, Primitive, 1",
+        "",
+    )
+    .unwrap_err();
+    assert!(matches!(
+        error,
+        ValidationError::EntryPoint {
+            source: naga::valid::EntryPointError::MissingVertexOutputPosition,
+            ..
+        }
+    ));
+
+    // Storing a primitive as a vertex.
+    let error = validate(
+        VERTEX,
+        TRIANGLE,
+        ATTRIBUTE,
+        "setVertex(0u, Primitive(vec3<u32>(0u, 1u, 2u)));",
+    )
+    .unwrap_err();
+    assert!(matches!(
+        error,
+        ValidationError::EntryPoint {
+            source: naga::valid::EntryPointError::InvalidMeshOutputType(_),
+            ..
+        }
+    ));
+
+    // Mesh shaders need the capability.
+    let source = format!(
+        "
+        enable naga_mesh_shader;
+        struct Vertex {{ {VERTEX} }}
+        struct Primitive {{ {TRIANGLE} }}
+        @mesh({ATTRIBUTE}) @workgroup_size(1)
+        fn main() {{}}
+        "
+    );
+    let module = naga::front::wgsl::parse_str(&source).unwrap();
+    assert!(
+        Validator::new(ValidationFlags::all(), Capabilities::empty())
+            .validate(&module)
+            .is_err()
+    );
+}
+
+#[test]
+fn depth_load_is_scalar() {
+    let mut module = naga::front::wgsl::parse_str(
+        "
+        @group(0) @binding(0)
+        var depth: texture_depth_2d;
+
+        fn f() {
+            var v: vec4<f32>;
+            let d = textureLoad(depth, vec2(0), 0);
+            v = vec4(d);
+        }
+        ",
+    )
+    .unwrap();
+
+    let mut validator = Validator::new(ValidationFlags::all(), Capabilities::default());
+    validator.validate(&module).unwrap();
+
+    // Store the loaded value itself, instead of `vec4(d)`.
+    let (handle, function) = module.functions.iter_mut().next().unwrap();
+    let load = function
+        .expressions
+        .iter()
+        .find(|&(_, expr)| matches!(*expr, naga::Expression::ImageLoad { .. }))
+        .unwrap()
+        .0;
+    for statement in function.body.iter_mut() {
+        if let naga::Statement::Store { ref mut value, .. } = *statement {
+            *value = load;
+        }
+    }
+
+    let error = validator.validate(&module).unwrap_err().into_inner();
+    assert!(
+        matches!(
+            error,
+            ValidationError::Function {
+                handle: error_handle,
+                source: naga::valid::FunctionError::InvalidStoreTypes { value, .. },
+                ..
+            } if error_handle == handle && value == load
+        ),
+        "{error:?}"
+    );
+}
+
+#[test]
+fn workgroup_uniform_load_is_uniform() {
+    let module = naga::front::wgsl::parse_str(
+        "
+        var<workgroup> counts: array<u32, 4>;
+
+        @compute @workgroup_size(4)
+        fn main(@builtin(local_invocation_index) index: u32) {
+            counts[index] = index;
+            let uniform = workgroupUniformLoad(&counts[index]);
+            let plain = counts[index];
+        }
+        ",
+    )
+    .unwrap();
+
+    let info = Validator::new(ValidationFlags::all(), Capabilities::default())
+        .validate(&module)
+        .unwrap();
+    let function = &module.entry_points[0].function;
+    let function_info = info.get_entry_point(0);
+    let uniformity_of = |name: &str| {
+        let (&handle, _) = function
+            .named_expressions
+            .iter()
+            .find(|&(_, expr_name)| expr_name == name)
+            .unwrap();
+        function_info[handle].uniformity.non_uniform_result
+    };
+
+    // Indexing with the invocation index makes the load non-uniform, unless
+    // it is done with `workgroupUniformLoad`.
+    assert!(uniformity_of("uniform").is_none());
+    assert!(uniformity_of("plain").is_some());
+}
+
+#[test]
+fn split_vertex_inputs() {
+    let module = naga::front::wgsl::parse_str(
+        "
+        struct Instance {
+            @location(1) transform: mat4x4<f32>,
+            @location(5) color: vec4<f32>,
+        }
+
+        @vertex
+        fn main(
+            @location(0) position: vec4<f32>,
+            instance: Instance,
+            @location(6) weights: array<f32, 3>,
+        ) -> @builtin(position) vec4<f32> {
+            return instance.transform * position * weights[0] + instance.color;
+        }
+        ",
+    )
+    .unwrap();
+
+    let info = Validator::new(ValidationFlags::all(), Capabilities::default())
+        .validate(&module)
+        .unwrap();
+    let f32 = naga::Scalar::F32;
+    assert_eq!(
+        info.get_entry_point(0).split_vertex_inputs,
+        [
+            naga::valid::SplitVertexInput {
+                location: 1,
+                count: 4,
+                ty: naga::TypeInner::Vector {
+                    size: naga::VectorSize::Quad,
+                    scalar: f32,
+                },
+            },
+            naga::valid::SplitVertexInput {
+                location: 6,
+                count: 3,
+                ty: naga::TypeInner::Scalar(f32),
+            },
+        ]
+    );
+}
+
+/// Validate a function whose body `build` returns, built directly on the
+/// IR as a frontend without the WGSL front end's checks might. `build` is
+/// passed an `i32` expression to select on.
+///
+/// On failure, return the function error, and whether any of the spans
+/// attached to it is `span`.
+fn validate_body(
+    build: impl FnOnce(Handle<naga::Expression>) -> naga::Block,
+    span: naga::Span,
+) -> Result<(), (FunctionError, bool)> {
+    let mut function = naga::Function {
+        name: Some("f".to_string()),
+        ..Default::default()
+    };
+    let selector = function.expressions.append(
+        naga::Expression::Literal(naga::Literal::I32(0)),
+        naga::Span::UNDEFINED,
+    );
+    function.body = build(selector);
+    let mut module = naga::Module::default();
+    module.functions.append(function, naga::Span::UNDEFINED);
+
+    let error =
+        match Validator::new(ValidationFlags::all(), Capabilities::default()).validate(&module) {
+            Ok(_) => return Ok(()),
+            Err(error) => error,
+        };
+    let has_span = error.spans().any(|&(error_span, _)| error_span == span);
+    match error.into_inner() {
+        ValidationError::Function { source, .. } => Err((source, has_span)),
+        other => panic!("unexpected error: {other:?}"),
+    }
+}
+
+/// Return a block holding just `statement`, at `span`.
+fn block(statement: naga::Statement, span: naga::Span) -> naga::Block {
+    let mut block = naga::Block::new();
+    block.push(statement, span);
+    block
+}
+
+fn looping(body: naga::Block, continuing: naga::Block) -> naga::Statement {
+    naga::Statement::Loop {
+        body,
+        continuing,
+        break_if: None,
+        hint: None,
+    }
+}
+
+fn switch(selector: Handle<naga::Expression>, body: naga::Block) -> naga::Statement {
+    naga::Statement::Switch {
+        selector,
+        cases: vec![naga::SwitchCase {
+            value: naga::SwitchValue::Default,
+            body,
+            fall_through: false,
+        }],
+    }
+}
+
+const SPAN: naga::Span = naga::Span::new(10, 20);
+const OTHER: naga::Span = naga::Span::new(30, 40);
+
+#[test]
+fn no_return_in_continuing() {
+    let result = validate_body(
+        |_| {
+            let continuing = block(naga::Statement::Return { value: None }, SPAN);
+            block(looping(naga::Block::new(), continuing), OTHER)
+        },
+        SPAN,
+    );
+    assert!(
+        matches!(result, Err((FunctionError::InvalidReturnSpot, true))),
+        "{result:?}"
+    );
+}
+
+#[test]
+fn no_kill_in_continuing() {
+    let result = validate_body(
+        |_| {
+            let continuing = block(naga::Statement::Kill, SPAN);
+            block(looping(naga::Block::new(), continuing), OTHER)
+        },
+        SPAN,
+    );
+    assert!(
+        matches!(result, Err((FunctionError::InvalidKillSpot, true))),
+        "{result:?}"
+    );
+
+    // Nor in a loop or `switch` nested in a `continuing` block.
+    let result = validate_body(
+        |selector| {
+            let inner = looping(block(naga::Statement::Kill, SPAN), naga::Block::new());
+            let continuing = block(switch(selector, block(inner, OTHER)), OTHER);
+            block(looping(naga::Block::new(), continuing), OTHER)
+        },
+        SPAN,
+    );
+    assert!(
+        matches!(result, Err((FunctionError::InvalidKillSpot, true))),
+        "{result:?}"
+    );
+
+    // A loop's body may kill.
+    validate_body(
+        |_| {
+            let body = block(naga::Statement::Kill, SPAN);
+            block(looping(body, naga::Block::new()), OTHER)
+        },
+        SPAN,
+    )
+    .unwrap();
+}
+
+#[test]
+fn break_needs_loop_or_switch() {
+    let result = validate_body(|_| block(naga::Statement::Break, SPAN), SPAN);
+    assert!(
+        matches!(
+            result,
+            Err((FunctionError::BreakOutsideOfLoopOrSwitch, true))
+        ),
+        "{result:?}"
+    );
+
+    // A `continuing` block can only leave its loop with `break_if`.
+    let result = validate_body(
+        |_| {
+            let continuing = block(naga::Statement::Break, SPAN);
+            block(looping(naga::Block::new(), continuing), OTHER)
+        },
+        SPAN,
+    );
+    assert!(
+        matches!(
+            result,
+            Err((FunctionError::BreakOutsideOfLoopOrSwitch, true))
+        ),
+        "{result:?}"
+    );
+
+    // But it may break out of a `switch` of its own.
+    validate_body(
+        |selector| {
+            let continuing = block(switch(selector, block(naga::Statement::Break, SPAN)), OTHER);
+            block(looping(naga::Block::new(), continuing), OTHER)
+        },
+        SPAN,
+    )
+    .unwrap();
+}
+
+#[test]
+fn continue_needs_loop() {
+    let result = validate_body(
+        |selector| {
+            block(
+                switch(selector, block(naga::Statement::Continue, SPAN)),
+                OTHER,
+            )
+        },
+        SPAN,
+    );
+    assert!(
+        matches!(result, Err((FunctionError::ContinueOutsideOfLoop, true))),
+        "{result:?}"
+    );
+
+    // WGSL allows `continue` in a `switch` in a loop, and every backend
+    // lowers it to continue the loop.
+    validate_body(
+        |selector| {
+            let body = block(
+                switch(selector, block(naga::Statement::Continue, SPAN)),
+                OTHER,
+            );
+            block(looping(body, naga::Block::new()), OTHER)
+        },
+        SPAN,
+    )
+    .unwrap();
+}
+
+fn validate(module: &naga::Module) -> Result<naga::valid::ModuleInfo, ValidationError> {
+    Validator::new(ValidationFlags::all(), Capabilities::all())
+        .validate(module)
+        .map_err(|error| error.into_inner())
+}
+
+#[test]
+fn malformed_handles() {
+    // Borrow handles from another module, so they dangle in an empty one.
+    let donor = naga::front::wgsl::parse_str("var<private> x: f32;").unwrap();
+    let (ty, _) = donor.types.iter().next().unwrap();
+    let (global, _) = donor.global_variables.iter().next().unwrap();
+
+    // Constant expressions that the constant expression validator would
+    // reject still have their handles checked first.
+    let mut module = naga::Module::default();
+    module.const_expressions.append(
+        naga::Expression::GlobalVariable(global),
+        naga::Span::UNDEFINED,
+    );
+    assert!(matches!(
+        validate(&module),
+        Err(ValidationError::InvalidHandle(_))
+    ));
+
+    let mut module = naga::Module::default();
+    module.special_types.predeclared_types.insert(
+        naga::PredeclaredType::AtomicCompareExchangeWeakResult(naga::Scalar::U32),
+        ty,
+    );
+    assert!(matches!(
+        validate(&module),
+        Err(ValidationError::InvalidHandle(_))
+    ));
+
+    let mut module = naga::Module::default();
+    let mut function = naga::Function::default();
+    let result = function.expressions.append(
+        naga::Expression::AtomicResult {
+            ty,
+            comparison: false,
+        },
+        naga::Span::UNDEFINED,
+    );
+    function.body.push(
+        naga::Statement::Return {
+            value: Some(result),
+        },
+        naga::Span::UNDEFINED,
+    );
+    module.functions.append(function, naga::Span::UNDEFINED);
+    assert!(matches!(
+        validate(&module),
+        Err(ValidationError::InvalidHandle(_))
+    ));
+}
+
+#[test]
+fn missing_function_argument() {
+    let mut module = naga::Module::default();
+    let mut function = naga::Function::default();
+    let argument = function
+        .expressions
+        .append(naga::Expression::FunctionArgument(3), naga::Span::UNDEFINED);
+    function.body.push(
+        naga::Statement::Emit(naga::Range::new_from_bounds(argument, argument)),
+        naga::Span::UNDEFINED,
+    );
+    module.functions.append(function, naga::Span::UNDEFINED);
+    assert!(matches!(
+        validate(&module),
+        Err(ValidationError::Function { .. })
+    ));
+}
+
+/// Return the ranges of the `Emit` statements directly in `block`.
+fn emitted(block: &naga::Block) -> Vec<(Handle<naga::Expression>, Handle<naga::Expression>)> {
+    block
+        .iter()
+        .filter_map(|statement| match *statement {
+            naga::Statement::Emit(ref range) => range.first_and_last(),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Return the expression named `name` by a `let` in `function`.
+fn named(function: &naga::Function, name: &str) -> Handle<naga::Expression> {
+    let (&handle, _) = function
+        .named_expressions
+        .iter()
+        .find(|&(_, other)| other == name)
+        .unwrap();
+    handle
+}
+
+#[test]
+fn missing_emit() {
+    let mut module = naga::front::wgsl::parse_str("fn f(a: i32) -> i32 { return a + a; }").unwrap();
+    let (handle, _) = module.functions.iter().next().unwrap();
+    let function = module.functions.get_mut(handle);
+    let sum = match function.body[1] {
+        naga::Statement::Return { value: Some(value) } => value,
+        ref other => panic!("unexpected statement {other:?}"),
+    };
+
+    // Drop the `Emit` of `a + a`.
+    function.body.cull(0..1);
+    assert!(matches!(
+        validate(&module),
+        Err(ValidationError::Function {
+            source: FunctionError::Expression {
+                handle,
+                source: naga::valid::ExpressionError::NotInScope,
+            },
+            ..
+        }) if handle == sum
+    ));
+
+    naga::proc::ensure_emits(module.functions.get_mut(handle));
+    validate(&module).unwrap();
+    assert_eq!(emitted(&module.functions[handle].body), [(sum, sum)]);
+}
+
+#[test]
+fn operand_not_emitted() {
+    let mut module = naga::front::wgsl::parse_str(
+        "
+        fn f(a: i32) -> i32 {
+            let sum = a + a;
+            let product = sum * a;
+            if product < a {
+                return sum;
+            } else {
+                return product;
+            }
+        }
+        ",
+    )
+    .unwrap();
+    let (handle, _) = module.functions.iter().next().unwrap();
+    let function = module.functions.get_mut(handle);
+    let sum = named(function, "sum");
+    let product = named(function, "product");
+
+    // Drop the `Emit` of `sum`, so `product` uses it before it's evaluated.
+    function.body.cull(0..1);
+    match validate(&module) {
+        Err(ValidationError::Function {
+            source:
+                FunctionError::OperandNotInScope {
+                    expression,
+                    operand,
+                },
+            ..
+        }) => {
+            assert_eq!(expression, product);
+            assert_eq!(operand, sum);
+        }
+        other => panic!("unexpected result {other:?}"),
+    }
+
+    // `sum` is used in both arms, so it is hoisted above the `if`, along
+    // with `product` and the condition.
+    naga::proc::ensure_emits(module.functions.get_mut(handle));
+    validate(&module).unwrap();
+    let body = &module.functions[handle].body;
+    assert_eq!(emitted(body).len(), 1);
+    assert_eq!(emitted(body)[0].0, sum);
+}
+
+#[test]
+fn emitted_in_sibling_blocks() {
+    let mut module = naga::front::wgsl::parse_str(
+        "
+        fn f(a: i32, c: bool) -> i32 {
+            if c {
+                return a + a;
+            } else {
+                return a;
+            }
+        }
+        ",
+    )
+    .unwrap();
+    let (handle, _) = module.functions.iter().next().unwrap();
+    let function = module.functions.get_mut(handle);
+
+    // Emit `a + a` in the `else` arm as well.
+    let sum = match function.body[0] {
+        naga::Statement::If {
+            ref accept,
+            ref mut reject,
+            ..
+        } => {
+            let &[(sum, _)] = &emitted(accept)[..] else {
+                panic!("expected one emit in {accept:?}");
+            };
+            let emit = naga::Statement::Emit(naga::Range::new_from_bounds(sum, sum));
+            reject.splice(0..0, naga::Block::from_vec(vec![emit]));
+            sum
+        }
+        ref other => panic!("unexpected statement {other:?}"),
+    };
+    assert!(matches!(
+        validate(&module),
+        Err(ValidationError::Function {
+            source: FunctionError::ExpressionAlreadyEmitted(handle),
+            ..
+        }) if handle == sum
+    ));
+
+    // Only the `accept` arm uses `sum`, so that's where it goes.
+    naga::proc::ensure_emits(module.functions.get_mut(handle));
+    validate(&module).unwrap();
+    match module.functions[handle].body[..] {
+        [naga::Statement::If {
+            ref accept,
+            ref reject,
+            ..
+        }] => {
+            assert_eq!(emitted(accept), [(sum, sum)]);
+            assert!(emitted(reject).is_empty());
+        }
+        ref other => panic!("unexpected body {other:?}"),
+    }
+}
+
+#[test]
+fn emits_in_loop_continuing() {
+    let mut module = naga::front::wgsl::parse_str(
+        "
+        fn f(a: i32, c: bool) -> i32 {
+            let sum = a + a;
+            loop {
+                if c {
+                    break;
+                }
+                continuing {
+                    let product = sum * a;
+                    break if product < a;
+                }
+            }
+            return sum;
+        }
+        ",
+    )
+    .unwrap();
+    let (handle, _) = module.functions.iter().next().unwrap();
+    let function = module.functions.get_mut(handle);
+    let sum = named(function, "sum");
+    let product = named(function, "product");
+
+    // Drop the `Emit` of `sum`, which the loop and the `return` both need.
+    function.body.cull(0..1);
+    assert!(validate(&module).is_err());
+
+    // `product` and the `break if` condition are only needed in the
+    // continuing block.
+    naga::proc::ensure_emits(module.functions.get_mut(handle));
+    validate(&module).unwrap();
+    let body = &module.functions[handle].body;
+    assert_eq!(emitted(body), [(sum, sum)]);
+    let continuing = body
+        .iter()
+        .find_map(|statement| match *statement {
+            naga::Statement::Loop { ref continuing, .. } => Some(continuing),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(emitted(continuing).len(), 1);
+    assert_eq!(emitted(continuing)[0].0, product);
+}
+
+#[test]
+fn sampled_image_kind() {
+    for kind in [naga::ScalarKind::Bool, naga::ScalarKind::AbstractFloat] {
+        let mut module = naga::Module::default();
+        module.types.insert(
+            naga::Type {
+                name: None,
+                inner: naga::TypeInner::Image {
+                    dim: naga::ImageDimension::D2,
+                    arrayed: false,
+                    class: naga::ImageClass::Sampled { kind, multi: false },
+                },
+            },
+            naga::Span::UNDEFINED,
+        );
+        assert!(
+            matches!(validate(&module), Err(ValidationError::Type { .. })),
+            "{kind:?}"
+        );
+    }
+}
+
+#[test]
+fn pointer_select() {
+    use naga::{Expression, Span};
+
+    let mut module = naga::Module::default();
+    let ty = module.types.insert(
+        naga::Type {
+            name: None,
+            inner: naga::TypeInner::Scalar(naga::Scalar::U32),
+        },
+        Span::UNDEFINED,
+    );
+    let mut function = naga::Function::default();
+    let mut local = |name: &str| {
+        let var = function.local_variables.append(
+            naga::LocalVariable {
+                name: Some(name.to_string()),
+                ty,
+                init: None,
+            },
+            Span::UNDEFINED,
+        );
+        function
+            .expressions
+            .append(Expression::LocalVariable(var), Span::UNDEFINED)
+    };
+    let (x, y) = (local("x"), local("y"));
+    let exprs = &mut function.expressions;
+    let condition = exprs.append(
+        Expression::Literal(naga::Literal::Bool(true)),
+        Span::UNDEFINED,
+    );
+    let select = exprs.append(
+        Expression::Select {
+            condition,
+            accept: x,
+            reject: y,
+        },
+        Span::UNDEFINED,
+    );
+    let load = exprs.append(Expression::Load { pointer: select }, Span::UNDEFINED);
+    function.body.push(
+        naga::Statement::Emit(naga::Range::new_from_bounds(select, load)),
+        Span::UNDEFINED,
+    );
+    module.functions.append(function, Span::UNDEFINED);
+
+    let validate = |capabilities| {
+        Validator::new(ValidationFlags::all(), capabilities)
+            .validate(&module)
+            .map_err(|error| error.into_inner())
+    };
+    assert!(matches!(
+        validate(Capabilities::default()),
+        Err(ValidationError::Function {
+            source: naga::valid::FunctionError::Expression {
+                source: naga::valid::ExpressionError::MissingCapabilities(
+                    Capabilities::POINTER_SELECT
+                ),
+                ..
+            },
+            ..
+        })
+    ));
+    validate(Capabilities::POINTER_SELECT).unwrap();
+}
+
+#[test]
+fn relational_of_scalar() {
+    let mut module = naga::front::wgsl::parse_str(
+        "
+        fn f(b: bool, i: i32, c: vec2<bool>) -> bool {
+            return all(c) && any(c);
+        }
+        ",
+    )
+    .unwrap();
+
+    // The front end drops `all` and `any` of a scalar, so point them at one
+    // here.
+    let mut with_argument = |index| {
+        let function = module.functions.iter_mut().next().unwrap().1;
+        let argument = function
+            .expressions
+            .iter()
+            .find(|&(_, expr)| matches!(*expr, naga::Expression::FunctionArgument(i) if i == index))
+            .unwrap()
+            .0;
+        for (_, expr) in function.expressions.iter_mut() {
+            if let naga::Expression::Relational {
+                argument: ref mut relational_argument,
+                ..
+            } = *expr
+            {
+                *relational_argument = argument;
+            }
+        }
+        validate(&module)
+    };
+
+    with_argument(0).unwrap();
+    assert!(matches!(
+        with_argument(1),
+        Err(ValidationError::Function {
+            source: FunctionError::Expression {
+                source: naga::valid::ExpressionError::InvalidBooleanVector(_),
+                ..
+            },
+            ..
+        })
+    ));
+}
+
+#[test]
+fn dynamic_indexing_warnings() {
+    use naga::valid::ValidationWarning;
+
+    let source = "
+        var<private> p: vec4<f32>;
+
+        fn f(i: u32) -> f32 {
+            var v: vec4<f32>;
+            var m: mat4x4<f32>;
+            let column = m[i];
+            let value = v.x + v[2] + p[i];
+            return value + v[i] + column.x;
+        }
+    ";
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+
+    // Warnings are opt-in.
+    let info = Validator::new(ValidationFlags::default(), Capabilities::default())
+        .validate(&module)
+        .unwrap();
+    assert_eq!(info.warnings().count(), 0);
+
+    let info = Validator::new(ValidationFlags::all(), Capabilities::default())
+        .validate(&module)
+        .unwrap();
+    let warnings: Vec<_> = info.warnings().collect();
+    let spans: Vec<_> = warnings
+        .iter()
+        .map(|warning| {
+            let ValidationWarning::DynamicIndex { composite, .. } = *warning.as_inner() else {
+                panic!("unexpected warning {warning:?}");
+            };
+            let span = warning.spans().next().unwrap().0;
+            (composite, span.to_range().map(|range| &source[range]))
+        })
+        .collect();
+    // `p` is private, and constant indices are fine.
+    assert_eq!(spans, [("matrix", Some("m[i]")), ("vector", Some("v[i]"))]);
+
+    let diagnostic = naga::error::Diagnostic::from(warnings[0]);
+    assert_eq!(diagnostic.severity, naga::error::Severity::Warning);
+    assert!(diagnostic
+        .message
+        .contains("consider using an array instead"));
+}
+
+/// Arbitrary modules must never panic the validator, and their handles
+/// should mostly be in range so that fuzzing reaches past handle checks.
+#[cfg(feature = "arbitrary")]
+#[test]
+fn arbitrary_modules() {
+    use arbitrary::Arbitrary;
+
+    let mut state = 0x2545_f491_4f6c_dd1d_u64;
+    let mut generated = 0;
+    let mut sound = 0;
+    for _ in 0..2000 {
+        let bytes: Vec<u8> = (0..512)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                state as u8
+            })
+            .collect();
+        let Ok(module) = naga::Module::arbitrary(&mut arbitrary::Unstructured::new(&bytes)) else {
+            continue;
+        };
+        generated += 1;
+        if !matches!(validate(&module), Err(ValidationError::InvalidHandle(_))) {
+            sound += 1;
+        }
+    }
+    assert!(generated > 0);
+    assert!(
+        sound * 4 > generated * 3,
+        "only {sound} of {generated} modules had valid handles"
+    );
+}
+
+/// Parse `source`, and apply `edit` to each of its constant expressions.
+///
+/// The front ends reject mistyped composites before the validator ever sees
+/// them, so the tests below write well-typed constants and then break them.
+fn edit_constants(source: &str, mut edit: impl FnMut(&mut naga::Expression)) -> naga::Module {
+    let mut module = naga::front::wgsl::parse_str(source).unwrap();
+    for (_, expr) in module.const_expressions.iter_mut() {
+        edit(expr);
+    }
+    module
+}
+
+/// Return an edit for [`edit_constants`] that turns the `f32` literal
+/// `value` into an `i32`.
+fn f32_to_i32(value: f32) -> impl FnMut(&mut naga::Expression) {
+    move |expr| {
+        if *expr == naga::Expression::Literal(naga::Literal::F32(value)) {
+            *expr = naga::Expression::Literal(naga::Literal::I32(value as i32));
+        }
+    }
+}
+
+/// Validate `module`, expecting its constant `name` to have a bad initializer.
+fn constant_initializer_error(
+    module: &naga::Module,
+    name: &str,
+) -> (Vec<u32>, naga::valid::ConstExpressionError) {
+    use naga::valid::ConstantError;
+
+    match validate(module) {
+        Err(ValidationError::Constant {
+            name: ref actual,
+            source: ConstantError::Initializer { path, source, .. },
+            ..
+        }) if actual == name => (path, *source),
+        other => panic!("expected a bad initializer for '{name}', got {other:?}"),
+    }
+}
+
+#[test]
+fn constant_component_count() {
+    use naga::valid::{ComposeError, ConstExpressionError};
+
+    let module = edit_constants("const short = vec4(0.0, 1.0, 2.0, 3.0);", |expr| {
+        if let naga::Expression::Compose {
+            ref mut components, ..
+        } = *expr
+        {
+            components.pop();
+        }
+    });
+
+    let (path, source) = constant_initializer_error(&module, "short");
+    assert!(path.is_empty());
+    assert!(matches!(
+        source,
+        ConstExpressionError::Compose(ComposeError::ComponentCount {
+            given: 3,
+            expected: 4
+        })
+    ));
+}
+
+#[test]
+fn constant_component_type() {
+    use naga::valid::{ComposeError, ConstExpressionError};
+
+    let module = edit_constants("const mixed = array(1.0, 2.0);", f32_to_i32(2.0));
+
+    let (path, source) = constant_initializer_error(&module, "mixed");
+    assert!(path.is_empty());
+    assert!(matches!(
+        source,
+        ConstExpressionError::Compose(ComposeError::ComponentType { index: 1 })
+    ));
+}
+
+#[test]
+fn constant_nested_component_type() {
+    use naga::valid::{ComposeError, ConstExpressionError};
+
+    // An `i32` hidden in element [1][0].
+    let module = edit_constants(
+        "const grid = array(
+            array(vec2(0.0, 1.0), vec2(0.0, 1.0)),
+            array(vec2(0.0, 7.0), vec2(0.0, 1.0)),
+        );",
+        f32_to_i32(7.0),
+    );
+
+    let (path, source) = constant_initializer_error(&module, "grid");
+    assert_eq!(path, [1, 0]);
+    assert!(matches!(
+        source,
+        ConstExpressionError::Compose(ComposeError::ComponentType { index: 1 })
+    ));
+
+    let error = validate(&module).unwrap_err();
+    let naga::valid::ValidationError::Constant { source, .. } = error else {
+        unreachable!();
+    };
+    assert_eq!(
+        source.to_string(),
+        "Initializer component [1][0] is invalid"
+    );
+}
+
+#[test]
+fn constant_splat_component_type() {
+    use naga::valid::{ComposeError, ConstExpressionError};
+
+    // An `i32` splat doesn't make a `vec2<f32>` array element.
+    let module = edit_constants("const splatted = array(vec2(3.0));", f32_to_i32(3.0));
+
+    let (path, source) = constant_initializer_error(&module, "splatted");
+    assert!(path.is_empty());
+    assert!(matches!(
+        source,
+        ConstExpressionError::Compose(ComposeError::ComponentType { index: 0 })
+    ));
+}
+
+#[test]
+fn constant_deeply_nested() {
+    // Splats and zero values mixed in.
+    let module = naga::front::wgsl::parse_str(
+        "
+        struct S {
+            grid: array<array<vec2<f32>, 2>, 2>,
+            scale: f32,
+        }
+        const nested = S(array(array(vec2(1.0), vec2<f32>()), array<vec2<f32>, 2>()), 2.0);
+        ",
+    )
+    .unwrap();
+
+    validate(&module).unwrap();
+}
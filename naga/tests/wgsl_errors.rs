@@ -122,6 +122,27 @@ fn unknown_identifier() {
     );
 }
 
+#[test]
+fn clamp_to_edge_texture() {
+    check(
+        r#"
+            @group(0) @binding(0) var t: texture_depth_2d;
+            @group(0) @binding(1) var s: sampler;
+
+            fn f() -> vec4<f32> {
+                return textureSampleBaseClampToEdge(t, s, vec2<f32>());
+            }
+        "#,
+        r#"error: textureSampleBaseClampToEdge requires a texture_2d<f32> or texture_external, but 't' is neither
+  ┌─ wgsl:6:53
+  │
+6 │                 return textureSampleBaseClampToEdge(t, s, vec2<f32>());
+  │                                                     ^ invalid texture
+
+"#,
+    );
+}
+
 #[test]
 fn bad_texture() {
     check(
@@ -544,6 +565,61 @@ fn let_type_mismatch() {
     );
 }
 
+#[test]
+fn abstract_value_not_representable() {
+    check(
+        r#"
+            fn f() {
+                let x: u32 = -1;
+            }
+        "#,
+        r#"error: failed to convert expression to a concrete type: the concrete type `u32` cannot represent the abstract value `-1` accurately
+  ┌─ wgsl:3:30
+  │
+3 │                 let x: u32 = -1;
+  │                              ^^ this expression has type {AbstractInt}
+  │
+  = note: the expression should have been converted to have u32 scalar type
+
+"#,
+    );
+
+    check(
+        r#"
+            fn takes_i32(x: i32) {}
+            fn f() {
+                takes_i32(3000000000);
+            }
+        "#,
+        r#"error: failed to convert expression to a concrete type: the concrete type `i32` cannot represent the abstract value `3000000000` accurately
+  ┌─ wgsl:4:27
+  │
+4 │                 takes_i32(3000000000);
+  │                           ^^^^^^^^^^ this expression has type {AbstractInt}
+  │
+  = note: the expression should have been converted to have i32 scalar type
+
+"#,
+    );
+
+    check(
+        r#"
+            fn f() -> u32 {
+                return 4294967296;
+            }
+        "#,
+        r#"error: failed to convert expression to a concrete type: the concrete type `u32` cannot represent the abstract value `4294967296` accurately
+  ┌─ wgsl:3:24
+  │
+3 │                 return 4294967296;
+  │                        ^^^^^^^^^^ this expression has type {AbstractInt}
+  │
+  = note: the expression should have been converted to have u32 scalar type
+
+"#,
+    );
+}
+
 #[test]
 fn var_type_mismatch() {
     check(
@@ -580,6 +656,22 @@ fn local_var_missing_type() {
     );
 }
 
+#[test]
+fn global_var_missing_type() {
+    check(
+        r#"
+            var<private> x;
+        "#,
+        r#"error: variable `x` needs a type
+  ┌─ wgsl:2:26
+  │
+2 │             var<private> x;
+  │                          ^ definition of `x`
+
+"#,
+    );
+}
+
 #[test]
 fn postfix_pointers() {
     check(
@@ -999,6 +1091,17 @@ fn invalid_arrays() {
 1 │ alias Bad = array<f32, -1>;
   │                        ^^ must be positive
 
+"###,
+    );
+
+    check(
+        "alias Bad = array<vec4<f32>, 1000000000>;",
+        r###"error: array is too large
+  ┌─ wgsl:1:13
+  │
+1 │ alias Bad = array<vec4<f32>, 1000000000>;
+  │             ^^^^^^^^^^^^^^^^^^^^^^^^^^^^ size in bytes must fit in 32 bits
+
 "###,
     );
 }
@@ -1035,13 +1138,69 @@ fn main() -> @builtin(position) vec4<f32> {
     }
 }
 
+#[test]
+fn multisampled_load_sample_index() {
+    for source in [
+        "@group(0) @binding(0) var t: texture_multisampled_2d<f32>;
+        fn f() -> vec4<f32> { return textureLoad(t, vec2<i32>(), 0u); }",
+        "@group(0) @binding(0) var t: texture_depth_multisampled_2d;
+        fn f() -> f32 { return textureLoad(t, vec2<i32>(), 0); }",
+    ] {
+        validation_error(source).unwrap();
+    }
+
+    check_validation! {
+        "@group(0) @binding(0) var t: texture_multisampled_2d<f32>;
+        fn f() -> vec4<f32> { return textureLoad(t, vec2<i32>(), 0.0); }",
+        "@group(0) @binding(0) var t: texture_depth_multisampled_2d;
+        fn f() -> f32 { return textureLoad(t, vec2<i32>(), vec2<i32>()); }":
+        Err(naga::valid::ValidationError::Function {
+            source: naga::valid::FunctionError::Expression {
+                source: naga::valid::ExpressionError::InvalidImageOtherIndexType(_),
+                ..
+            },
+            ..
+        })
+    }
+}
+
+#[test]
+fn external_texture_sample() {
+    for source in [
+        "@group(0) @binding(0) var t: texture_external;
+        @group(0) @binding(1) var s: sampler;
+        fn f() -> vec4<f32> { return textureSampleBaseClampToEdge(t, s, vec2<f32>()); }",
+        "@group(0) @binding(0) var t: texture_2d<f32>;
+        @group(0) @binding(1) var s: sampler;
+        fn f() -> vec4<f32> { return textureSampleBaseClampToEdge(t, s, vec2<f32>()); }",
+    ] {
+        validation_error(source).unwrap();
+    }
+
+    check_validation! {
+        "@group(0) @binding(0) var t: texture_external;
+        @group(0) @binding(1) var s: sampler;
+        fn f() -> vec4<f32> { return textureSampleLevel(t, s, vec2<f32>(), 0.0); }",
+        "@group(0) @binding(0) var t: texture_external;
+        @group(0) @binding(1) var s: sampler;
+        fn f() -> vec4<f32> { return textureSample(t, s, vec2<f32>()); }":
+        Err(naga::valid::ValidationError::Function {
+            source: naga::valid::FunctionError::Expression {
+                source: naga::valid::ExpressionError::InvalidExternalSample,
+                ..
+            },
+            ..
+        })
+    }
+}
+
 #[test]
 fn invalid_structs() {
     check_validation! {
         "struct Bad { data: sampler }",
         "struct Bad { data: texture_2d<f32> }":
         Err(naga::valid::ValidationError::Type {
-            source: naga::valid::TypeError::InvalidData(_),
+            source: naga::valid::TypeError::InvalidData(_, naga::valid::TypeClass::Handle),
             ..
         })
     }
@@ -1153,7 +1312,9 @@ fn invalid_functions() {
         ":
         Err(naga::valid::ValidationError::Function {
             name: function_name,
-            source: naga::valid::FunctionError::NonConstructibleReturnType,
+            source: naga::valid::FunctionError::NonConstructibleReturnType(
+                naga::valid::TypeClass::Pointer
+            ),
             ..
         })
         if function_name == "return_pointer"
@@ -1170,7 +1331,9 @@ fn invalid_functions() {
         ":
         Err(naga::valid::ValidationError::Function {
             name: function_name,
-            source: naga::valid::FunctionError::NonConstructibleReturnType,
+            source: naga::valid::FunctionError::NonConstructibleReturnType(
+                naga::valid::TypeClass::Plain
+            ),
             ..
         })
         if function_name == "return_atomic"
@@ -1303,6 +1466,26 @@ fn missing_bindings2() {
     }
 }
 
+#[test]
+fn frag_depth_input() {
+    check_validation! {
+        "
+        @fragment
+        fn fragment(@builtin(frag_depth) depth: f32) -> @location(0) vec4<f32> {
+           return vec4<f32>(depth);
+        }
+        ":
+        Err(naga::valid::ValidationError::EntryPoint {
+            stage: naga::ShaderStage::Fragment,
+            source: naga::valid::EntryPointError::Argument(
+                0,
+                naga::valid::VaryingError::InvalidBuiltInStage(naga::BuiltIn::FragDepth),
+            ),
+            ..
+        })
+    }
+}
+
 #[test]
 fn invalid_access() {
     check_validation! {
@@ -1381,7 +1564,10 @@ fn invalid_local_vars() {
         Err(naga::valid::ValidationError::Function {
             source: naga::valid::FunctionError::LocalVariable {
                 name: local_var_name,
-                source: naga::valid::LocalVariableError::InvalidType(_),
+                source: naga::valid::LocalVariableError::NonConstructibleType(
+                    _,
+                    naga::valid::TypeClass::Pointer
+                ),
                 ..
             },
             ..
@@ -1398,7 +1584,30 @@ fn invalid_local_vars() {
         Err(naga::valid::ValidationError::Function {
             source: naga::valid::FunctionError::LocalVariable {
                 name: local_var_name,
-                source: naga::valid::LocalVariableError::InvalidType(_),
+                source: naga::valid::LocalVariableError::NonConstructibleType(
+                    _,
+                    naga::valid::TypeClass::Plain
+                ),
+                ..
+            },
+            ..
+        })
+        if local_var_name == "x"
+    }
+
+    check_validation! {
+        "
+        fn f() {
+            var x: array<f32>;
+        }
+        ":
+        Err(naga::valid::ValidationError::Function {
+            source: naga::valid::FunctionError::LocalVariable {
+                name: local_var_name,
+                source: naga::valid::LocalVariableError::NonConstructibleType(
+                    _,
+                    naga::valid::TypeClass::Plain
+                ),
                 ..
             },
             ..
@@ -1407,6 +1616,65 @@ fn invalid_local_vars() {
     }
 }
 
+#[test]
+fn invalid_let_bindings() {
+    check_validation! {
+        "
+        @group(0) @binding(0)
+        var t: texture_2d<f32>;
+
+        fn f() {
+            let x = t;
+        }
+        ":
+        Err(naga::valid::ValidationError::Function {
+            name: function_name,
+            source: naga::valid::FunctionError::NonConstructibleLet {
+                name: let_name,
+                class: naga::valid::TypeClass::Handle,
+            },
+            ..
+        })
+        if function_name == "f" && let_name == "x"
+    }
+
+    check_validation! {
+        "
+        @group(0) @binding(0)
+        var<storage> a: array<f32>;
+
+        fn f() {
+            let p = &a;
+            let x = a[0];
+        }
+        ":
+        Ok(_)
+    }
+}
+
+#[test]
+fn non_constructible_messages() {
+    let error = validation_error(
+        "
+        @group(0) @binding(0)
+        var<storage> atom: atomic<u32>;
+
+        fn return_atomic() -> atomic<u32> {
+           return atom;
+        }
+        ",
+    )
+    .unwrap_err();
+    let naga::valid::ValidationError::Function { source, .. } = error else {
+        panic!("unexpected error: {error:?}");
+    };
+    assert_eq!(
+        source.to_string(),
+        "The function's return type is a plain type that isn't constructible, \
+         but only constructible types can be returned"
+    );
+}
+
 #[test]
 fn dead_code() {
     check_validation! {
@@ -1479,7 +1747,23 @@ fn select() {
             let p = select(&x, &y, which);
             return *p;
         }
-        ",
+        ":
+        Err(
+            naga::valid::ValidationError::Function {
+                name,
+                source: naga::valid::FunctionError::Expression {
+                    source: naga::valid::ExpressionError::MissingCapabilities(
+                        naga::valid::Capabilities::POINTER_SELECT
+                    ),
+                    ..
+                },
+                ..
+            },
+        )
+        if name == "select_pointers"
+    }
+
+    check_validation! {
         "
         fn select_arrays(which: bool) -> i32 {
             var x: array<i32, 4>;
@@ -1562,7 +1846,83 @@ fn wrong_access_mode() {
         Err(
             naga::valid::ValidationError::Function {
                 name,
-                source: naga::valid::FunctionError::InvalidStorePointer(_),
+                source: naga::valid::FunctionError::InvalidStorageAccess {
+                    declared: naga::StorageAccess::LOAD,
+                    used: naga::StorageAccess::STORE,
+                    ..
+                },
+                ..
+            },
+        )
+            if name == "store"
+    }
+}
+
+#[test]
+fn wrong_storage_access() {
+    check_validation! {
+        "
+            @group(0) @binding(0)
+            var<storage, read> counters: array<atomic<u32>, 4>;
+
+            fn increment() {
+                atomicAdd(&counters[1], 1u);
+            }
+        ":
+        Err(
+            naga::valid::ValidationError::Function {
+                name,
+                source: naga::valid::FunctionError::InvalidStorageAccess {
+                    declared: naga::StorageAccess::LOAD,
+                    used,
+                    ..
+                },
+                ..
+            },
+        )
+            if name == "increment" && *used == naga::StorageAccess::all()
+    }
+
+    check_validation! {
+        "
+            @group(0) @binding(0)
+            var image: texture_storage_2d<r32float, write>;
+
+            fn load() -> vec4<f32> {
+                return textureLoad(image, vec2(0));
+            }
+        ":
+        Err(
+            naga::valid::ValidationError::Function {
+                name,
+                source: naga::valid::FunctionError::InvalidStorageAccess {
+                    declared: naga::StorageAccess::STORE,
+                    used: naga::StorageAccess::LOAD,
+                    ..
+                },
+                ..
+            },
+        )
+            if name == "load"
+    }
+
+    check_validation! {
+        "
+            @group(0) @binding(0)
+            var image: texture_storage_2d<r32float, read>;
+
+            fn store() {
+                textureStore(image, vec2(0), vec4(1.0));
+            }
+        ":
+        Err(
+            naga::valid::ValidationError::Function {
+                name,
+                source: naga::valid::FunctionError::InvalidStorageAccess {
+                    declared: naga::StorageAccess::LOAD,
+                    used: naga::StorageAccess::STORE,
+                    ..
+                },
                 ..
             },
         )
@@ -1570,6 +1930,29 @@ fn wrong_access_mode() {
     }
 }
 
+#[test]
+fn entry_point_pointer_argument() {
+    // Entry point arguments are input data, which can't be written to, so
+    // they can't be pointers.
+    check_validation! {
+        "
+            @fragment
+            fn main(@location(0) p: ptr<function, f32>) {
+                *p = 1.0;
+            }
+        ":
+        Err(
+            naga::valid::ValidationError::EntryPoint {
+                source: naga::valid::EntryPointError::Argument(
+                    0,
+                    naga::valid::VaryingError::NotIOShareableType(_),
+                ),
+                ..
+            },
+        )
+    }
+}
+
 #[test]
 fn io_shareable_types() {
     for numeric in "i32 u32 f32".split_whitespace() {
@@ -1584,21 +1967,45 @@ fn io_shareable_types() {
         }
     }
 
-    for ty in "bool
-               vec2<bool> vec3<bool> vec4<bool>
-               array<f32,4>
-               mat2x2<f32>
-               ptr<function,f32>"
-        .split_whitespace()
-    {
+    // Vertex inputs may also be split across several locations.
+    for ty in "array<f32,4> array<vec2<u32>,2> mat2x2<f32> mat4x3<f32>".split_whitespace() {
         check_one_validation! {
             &format!("@vertex
-                          fn f(@location(0) arg: {ty}) -> @builtin(position) vec4<f32>
-                          {{ return vec4<f32>(0.0); }}"),
+                      fn f(@location(0) arg: {ty}) -> @builtin(position) vec4<f32>
+                      {{ return vec4<f32>(0.0); }}"),
+            Ok(_module)
+        }
+        check_one_validation! {
+            &format!("@fragment
+                      fn f(@location(0) arg: {ty}) {{}}"),
             Err(
                 naga::valid::ValidationError::EntryPoint {
-                    stage: naga::ShaderStage::Vertex,
-                    name,
+                    stage: naga::ShaderStage::Fragment,
+                    source: naga::valid::EntryPointError::Argument(
+                        0,
+                        naga::valid::VaryingError::NotIOShareableType(_),
+                    ),
+                    ..
+                },
+            )
+        }
+    }
+
+    for ty in "bool
+               vec2<bool> vec3<bool> vec4<bool>
+               array<bool,4>
+               array<mat2x2<f32>,2>
+               ptr<function,f32>"
+        .split_whitespace()
+    {
+        check_one_validation! {
+            &format!("@vertex
+                          fn f(@location(0) arg: {ty}) -> @builtin(position) vec4<f32>
+                          {{ return vec4<f32>(0.0); }}"),
+            Err(
+                naga::valid::ValidationError::EntryPoint {
+                    stage: naga::ShaderStage::Vertex,
+                    name,
                     source: naga::valid::EntryPointError::Argument(
                         0,
                         naga::valid::VaryingError::NotIOShareableType(
@@ -1612,6 +2019,31 @@ fn io_shareable_types() {
     }
 }
 
+#[test]
+fn split_vertex_input_locations() {
+    // A `mat4x4` takes four locations, so it overlaps `v`.
+    check_validation! {
+        "
+            @vertex
+            fn main(
+                @location(0) m: mat4x4<f32>,
+                @location(3) v: vec4<f32>,
+            ) -> @builtin(position) vec4<f32> {
+                return m * v;
+            }
+        ":
+        Err(
+            naga::valid::ValidationError::EntryPoint {
+                source: naga::valid::EntryPointError::Argument(
+                    1,
+                    naga::valid::VaryingError::BindingCollision { location: 3 },
+                ),
+                ..
+            },
+        )
+    }
+}
+
 #[test]
 fn host_shareable_types() {
     // Host-shareable, constructible types.
@@ -1896,6 +2328,110 @@ fn cyclic_function() {
     );
 }
 
+#[test]
+fn cyclic_const() {
+    check(
+        "
+        const a = b + 1;
+        const b = c * 2;
+        const c = a;
+        ",
+        r###"error: declaration of `a` is cyclic
+  ┌─ wgsl:2:15
+  │
+2 │         const a = b + 1;
+  │               ^   ^ uses `b`
+3 │         const b = c * 2;
+  │               ^   ^ uses `c`
+4 │         const c = a;
+  │               ^   ^ ending the cycle
+
+"###,
+    );
+}
+
+#[test]
+fn cyclic_type() {
+    check(
+        "
+        alias A = array<B, 2>;
+        struct B { a: A }
+        ",
+        r###"error: declaration of `A` is cyclic
+  ┌─ wgsl:2:15
+  │
+2 │         alias A = array<B, 2>;
+  │               ^         ^ uses `B`
+3 │         struct B { a: A }
+  │                ^      ^ ending the cycle
+
+"###,
+    );
+}
+
+#[test]
+fn recursive_alias() {
+    check(
+        "
+        alias A = array<A, 2>;
+        ",
+        r###"error: declaration of `A` is recursive
+  ┌─ wgsl:2:15
+  │
+2 │         alias A = array<A, 2>;
+  │               ^         ^ uses itself here
+
+"###,
+    );
+    check(
+        "
+        alias A = vec2<B>;
+        alias B = A;
+        ",
+        r###"error: declaration of `A` is cyclic
+  ┌─ wgsl:2:15
+  │
+2 │         alias A = vec2<B>;
+  │               ^        ^ uses `B`
+3 │         alias B = A;
+  │               ^   ^ ending the cycle
+
+"###,
+    );
+}
+
+#[test]
+fn bad_alias_component() {
+    check(
+        "
+        alias V = vec2<f32>;
+        var<private> v: vec2<V>;
+        ",
+        r###"error: unknown scalar type: 'V'
+  ┌─ wgsl:3:30
+  │
+3 │         var<private> v: vec2<V>;
+  │                              ^ unknown scalar type
+  │
+  = note: Valid scalar types are f32, f64, i32, u32, bool
+
+"###,
+    );
+    check(
+        "
+        alias I = i32;
+        var<private> m: mat2x2<I>;
+        ",
+        r###"error: matrix scalar type must be floating-point, but found `i32`
+  ┌─ wgsl:3:32
+  │
+3 │         var<private> m: mat2x2<I>;
+  │                                ^ must be floating-point (e.g. `f32`)
+
+"###,
+    );
+}
+
 #[test]
 fn switch_signed_unsigned_mismatch() {
     check(
@@ -2098,3 +2634,588 @@ fn compaction_preserves_spans() {
         panic!("Error message has wrong span:\n\n{err:#?}");
     }
 }
+
+#[test]
+fn enable_extension_not_enabled() {
+    check(
+        "
+        struct FragmentOutput {
+            @location(0) @second_blend_source mask: vec4<f32>,
+        }
+        ",
+        r###"error: the `dual_source_blending` enable-extension is not enabled
+  ┌─ wgsl:3:27
+  │
+3 │             @location(0) @second_blend_source mask: vec4<f32>,
+  │                           ^^^^^^^^^^^^^^^^^^^ the `dual_source_blending` enable-extension is needed for this functionality, but it is not currently enabled
+  │
+  = note: You can enable this extension by adding `enable dual_source_blending;` at the top of the shader.
+
+"###,
+    );
+}
+
+#[test]
+fn unknown_enable_extension() {
+    check(
+        "enable f16, wibble;",
+        r###"error: unknown enable-extension: 'wibble'
+  ┌─ wgsl:1:13
+  │
+1 │ enable f16, wibble;
+  │             ^^^^^^ unknown enable-extension
+  │
+  = note: Supported enable-extensions are f16, dual_source_blending, clip_distances, naga_control_flow_hints, naga_memory_decorations, naga_mesh_shader
+
+"###,
+    );
+}
+
+#[test]
+fn enable_extension_capability() {
+    let source = "
+        enable dual_source_blending;
+
+        struct FragmentOutput {
+            @location(0) color: vec4<f32>,
+            @location(0) @second_blend_source mask: vec4<f32>,
+        }
+
+        @fragment
+        fn main() -> FragmentOutput {
+            return FragmentOutput(vec4(0.0), vec4(1.0));
+        }
+    ";
+    check_one_validation! {
+        source,
+        Err(naga::valid::ValidationError::MissingExtensionCapability {
+            extensions: naga::EnableExtensions::DUAL_SOURCE_BLENDING,
+            capability: naga::valid::Capabilities::DUAL_SOURCE_BLENDING,
+        })
+    }
+}
+
+#[test]
+fn const_assert_failed() {
+    check(
+        "
+        const WORKGROUP_SIZE = 300u;
+        const_assert WORKGROUP_SIZE <= 256;
+        ",
+        r###"error: `const_assert` failure
+  ┌─ wgsl:3:9
+  │
+3 │         const_assert WORKGROUP_SIZE <= 256;
+  │         ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ evaluates to `false`
+  │
+  = note: the left operand is `300u`, and the right operand is `256`
+
+"###,
+    );
+
+    check(
+        "
+        fn f() {
+            const_assert(false);
+        }
+        ",
+        r###"error: `const_assert` failure
+  ┌─ wgsl:3:13
+  │
+3 │             const_assert(false);
+  │             ^^^^^^^^^^^^^^^^^^^^ evaluates to `false`
+
+"###,
+    );
+}
+
+#[test]
+fn const_assert_non_const() {
+    check(
+        "
+        fn f() {
+            let x = 1;
+            const_assert(x > 0);
+        }
+        ",
+        r###"error: this operation is not supported in a const context
+  ┌─ wgsl:4:26
+  │
+4 │             const_assert(x > 0);
+  │                          ^ operation not supported here
+
+"###,
+    );
+
+    check(
+        "
+        const_assert 1;
+        ",
+        r###"error: must be a const-expression that resolves to a `bool`
+  ┌─ wgsl:2:22
+  │
+2 │         const_assert 1;
+  │                      ^ must resolve to a `bool`
+
+"###,
+    );
+}
+
+#[test]
+fn invalid_workgroup_uniform_load() {
+    check(
+        "
+        var<private> x: u32;
+
+        @compute @workgroup_size(4)
+        fn main() {
+            let v = workgroupUniformLoad(&x);
+        }
+        ",
+        r###"error: incorrect type passed to workgroupUniformLoad
+  ┌─ wgsl:6:42
+  │
+6 │             let v = workgroupUniformLoad(&x);
+  │                                          ^^
+  │
+  = note: passed type must be a workgroup pointer
+
+"###,
+    );
+
+    check_validation! {
+        "
+        var<workgroup> x: atomic<u32>;
+
+        @compute @workgroup_size(4)
+        fn main() {
+            let v = workgroupUniformLoad(&x);
+        }
+        ":
+        Err(naga::valid::ValidationError::EntryPoint {
+            source: naga::valid::EntryPointError::Function(
+                naga::valid::FunctionError::Expression {
+                    source: naga::valid::ExpressionError::InvalidWorkGroupUniformLoadResultType(_),
+                    ..
+                }
+            ),
+            ..
+        })
+    }
+}
+
+#[test]
+fn control_flow_hints() {
+    check(
+        "
+        fn f() {
+            @unroll loop { break; }
+        }
+        ",
+        r###"error: the `naga_control_flow_hints` enable-extension is not enabled
+  ┌─ wgsl:3:14
+  │
+3 │             @unroll loop { break; }
+  │              ^^^^^^ the `naga_control_flow_hints` enable-extension is needed for this functionality, but it is not currently enabled
+  │
+  = note: You can enable this extension by adding `enable naga_control_flow_hints;` at the top of the shader.
+
+"###,
+    );
+
+    check(
+        "
+        enable naga_control_flow_hints;
+        fn f() {
+            @branch loop { break; }
+        }
+        ",
+        r###"error: the 'branch' hint can't be applied to this statement
+  ┌─ wgsl:4:14
+  │
+4 │             @branch loop { break; }
+  │              ^^^^^^ hint not allowed here
+  │
+  = note: `@unroll` and `@dont_unroll` apply to loops, and `@branch` and `@flatten` to `if` statements
+
+"###,
+    );
+
+    check(
+        "
+        enable naga_control_flow_hints;
+        fn f() {
+            @unroll var x = 1;
+        }
+        ",
+        r###"error: the 'unroll' hint can't be applied to this statement
+  ┌─ wgsl:4:14
+  │
+4 │             @unroll var x = 1;
+  │              ^^^^^^ hint not allowed here
+  │
+  = note: `@unroll` and `@dont_unroll` apply to loops, and `@branch` and `@flatten` to `if` statements
+
+"###,
+    );
+
+    check(
+        "
+        enable naga_control_flow_hints;
+        fn f() {
+            @unroll @dont_unroll loop { break; }
+        }
+        ",
+        r###"error: repeated attribute: 'dont_unroll'
+  ┌─ wgsl:4:22
+  │
+4 │             @unroll @dont_unroll loop { break; }
+  │                      ^^^^^^^^^^^ repeated attribute
+
+"###,
+    );
+}
+
+#[test]
+fn memory_decorations() {
+    check(
+        "
+        @group(0) @binding(0) @coherent
+        var<storage, read_write> buf: u32;
+        ",
+        r###"error: the `naga_memory_decorations` enable-extension is not enabled
+  ┌─ wgsl:2:32
+  │
+2 │         @group(0) @binding(0) @coherent
+  │                                ^^^^^^^^ the `naga_memory_decorations` enable-extension is needed for this functionality, but it is not currently enabled
+  │
+  = note: You can enable this extension by adding `enable naga_memory_decorations;` at the top of the shader.
+
+"###,
+    );
+
+    check(
+        "
+        enable naga_memory_decorations;
+        @volatile const c = 1;
+        ",
+        r###"error: the 'volatile' attribute can't be applied to this declaration
+  ┌─ wgsl:3:10
+  │
+3 │         @volatile const c = 1;
+  │          ^^^^^^^^ attribute not allowed here
+  │
+  = note: `@coherent` and `@volatile` apply to `var<storage>` declarations
+
+"###,
+    );
+
+    check_validation! {
+        "
+        enable naga_memory_decorations;
+        @coherent var<private> p: u32;
+        ":
+        Err(
+            naga::valid::ValidationError::GlobalVariable {
+                source: naga::valid::GlobalVariableError::MemoryDecorationsNotAllowed(naga::AddressSpace::Private),
+                ..
+            },
+        )
+    }
+}
+
+#[test]
+fn diagnostic_filter_errors() {
+    check(
+        "
+        diagnostic(off, derivative_uniformity);
+        diagnostic(error, derivative_uniformity);
+        ",
+        r###"error: conflicting diagnostic filters for rule 'derivative_uniformity'
+  ┌─ wgsl:2:25
+  │
+2 │         diagnostic(off, derivative_uniformity);
+  │                         ^^^^^^^^^^^^^^^^^^^^^ first filter for this rule
+3 │         diagnostic(error, derivative_uniformity);
+  │                           ^^^^^^^^^^^^^^^^^^^^^ second filter for the same rule
+  │
+  = note: A scope may only have one diagnostic filter for each rule
+
+"###,
+    );
+
+    check(
+        "
+        @diagnostic(loud, derivative_uniformity)
+        fn f() {}
+        ",
+        r###"error: unknown diagnostic severity: 'loud'
+  ┌─ wgsl:2:21
+  │
+2 │         @diagnostic(loud, derivative_uniformity)
+  │                     ^^^^ unknown severity
+  │
+  = note: Severities are error, warning, info and off
+
+"###,
+    );
+
+    check(
+        "
+        @diagnostic(off, derivative_uniformity)
+        var<private> p: f32;
+        ",
+        r###"error: the 'diagnostic' attribute can't be applied to this item
+  ┌─ wgsl:2:10
+  │
+2 │         @diagnostic(off, derivative_uniformity)
+  │          ^^^^^^^^^^ attribute not allowed here
+  │
+  = note: `@diagnostic` applies to functions, compound statements, and `if`, `switch`, `loop`, `for` and `while` statements
+
+"###,
+    );
+
+    check(
+        "
+        fn f() {
+            @diagnostic(off, derivative_uniformity)
+            let x = 1;
+        }
+        ",
+        r###"error: the 'diagnostic' attribute can't be applied to this item
+  ┌─ wgsl:3:14
+  │
+3 │             @diagnostic(off, derivative_uniformity)
+  │              ^^^^^^^^^^ attribute not allowed here
+  │
+  = note: `@diagnostic` applies to functions, compound statements, and `if`, `switch`, `loop`, `for` and `while` statements
+
+"###,
+    );
+}
+
+#[test]
+fn diagnostic_filter_scopes() {
+    // Unknown rule names are only worth a warning, and two-part names belong
+    // to other tools.
+    naga::front::wgsl::parse_str(
+        "
+        diagnostic(off, no_such_rule);
+        @diagnostic(warning, some_tool.some_rule)
+        fn f() {}
+        ",
+    )
+    .unwrap();
+
+    // Naga doesn't report derivatives in non-uniform control flow unless a
+    // filter asks for it.
+    check_validation! {
+        "
+        @fragment
+        fn main(@location(0) x: f32) -> @location(0) vec4<f32> {
+            if x > 0.0 {
+                return vec4(dpdx(x));
+            }
+            return vec4(0.0);
+        }
+        ",
+        "
+        diagnostic(error, derivative_uniformity);
+        @fragment
+        @diagnostic(off, derivative_uniformity)
+        fn main(@location(0) x: f32) -> @location(0) vec4<f32> {
+            if x > 0.0 {
+                return vec4(dpdx(x));
+            }
+            return vec4(0.0);
+        }
+        ",
+        "
+        diagnostic(error, derivative_uniformity);
+        @fragment
+        fn main(@location(0) x: f32) -> @location(0) vec4<f32> {
+            @diagnostic(off, derivative_uniformity)
+            if x > 0.0 {
+                return vec4(dpdx(x));
+            }
+            return vec4(0.0);
+        }
+        ":
+        Ok(ref info) if info.warnings().count() == 0
+    }
+
+    // The innermost filter wins.
+    check_validation! {
+        "
+        diagnostic(error, derivative_uniformity);
+        @fragment
+        fn main(@location(0) x: f32) -> @location(0) vec4<f32> {
+            if x > 0.0 {
+                return vec4(dpdx(x));
+            }
+            return vec4(0.0);
+        }
+        ",
+        "
+        @fragment
+        @diagnostic(error, derivative_uniformity)
+        fn main(@location(0) x: f32) -> @location(0) vec4<f32> {
+            if x > 0.0 {
+                return vec4(dpdx(x));
+            }
+            return vec4(0.0);
+        }
+        ",
+        "
+        @fragment
+        @diagnostic(off, derivative_uniformity)
+        fn main(@location(0) x: f32) -> @location(0) vec4<f32> {
+            @diagnostic(warning, derivative_uniformity)
+            if x > 0.0 {
+                @diagnostic(error, derivative_uniformity) {
+                    return vec4(dpdx(x));
+                }
+            }
+            return vec4(0.0);
+        }
+        ":
+        Err(naga::valid::ValidationError::EntryPoint {
+            source: naga::valid::EntryPointError::Function(
+                naga::valid::FunctionError::NonUniformControlFlow(..)
+            ),
+            ..
+        })
+    }
+
+    let info = validation_error(
+        "
+        @group(0) @binding(0) var t: texture_2d<f32>;
+        @group(0) @binding(1) var s: sampler;
+
+        @fragment
+        fn main(@location(0) x: f32) -> @location(0) vec4<f32> {
+            var color = vec4(0.0);
+            @diagnostic(warning, derivative_uniformity)
+            for (var i = 0; i < 4; i++) {
+                if x > f32(i) {
+                    color += textureSample(t, s, vec2(x));
+                }
+            }
+            return color;
+        }
+        ",
+    )
+    .unwrap();
+    let warnings: Vec<_> = info.warnings().map(|warning| warning.as_inner()).collect();
+    assert!(matches!(
+        warnings[..],
+        [naga::valid::ValidationWarning::NonUniformDerivative { .. }]
+    ));
+}
+
+#[test]
+fn mesh_shader_extension() {
+    check(
+        "
+        @task @workgroup_size(1)
+        fn main() -> @builtin(mesh_task_size) vec3<u32> {
+            return vec3<u32>(1u);
+        }
+        ",
+        r###"error: the `naga_mesh_shader` enable-extension is not enabled
+  ┌─ wgsl:2:10
+  │
+2 │         @task @workgroup_size(1)
+  │          ^^^^ the `naga_mesh_shader` enable-extension is needed for this functionality, but it is not currently enabled
+  │
+  = note: You can enable this extension by adding `enable naga_mesh_shader;` at the top of the shader.
+
+"###,
+    );
+
+    check(
+        "
+        enable naga_mesh_shader;
+        struct V { @builtin(position) p: vec4<f32> }
+        struct P { @builtin(point_index) i: u32 }
+        @mesh(quads, V, 4, P, 4) @workgroup_size(1)
+        fn main() {}
+        ",
+        r###"error: unknown mesh output topology: 'quads'
+  ┌─ wgsl:5:15
+  │
+5 │         @mesh(quads, V, 4, P, 4) @workgroup_size(1)
+  │               ^^^^^ unknown topology
+  │
+  = note: Topologies are points, lines and triangles
+
+"###,
+    );
+}
+
+#[test]
+fn sample_offset_not_const() {
+    check(
+        r#"
+            @group(0) @binding(0) var t: texture_2d<f32>;
+            @group(0) @binding(1) var s: sampler;
+
+            @fragment
+            fn main(@location(0) offset: vec2<i32>) -> @location(0) vec4<f32> {
+                return textureSample(t, s, vec2<f32>(0.0), offset);
+            }
+        "#,
+        r#"error: texture sample offset must be a const-expression
+  ┌─ wgsl:7:60
+  │
+7 │                 return textureSample(t, s, vec2<f32>(0.0), offset);
+  │                                                            ^^^^^^ must be a const-expression
+
+"#,
+    );
+}
+
+#[test]
+fn sample_offset_range() {
+    check_validation! {
+        "
+        @group(0) @binding(0) var t: texture_2d<f32>;
+        @group(0) @binding(1) var s: sampler;
+        const OFFSET = vec2(-8, 7);
+        @fragment
+        fn main() -> @location(0) vec4<f32> {
+            return textureSample(t, s, vec2(0.0), OFFSET) + textureSample(t, s, vec2(0.0), vec2(0));
+        }
+        ":
+        Ok(_)
+    }
+
+    check_validation! {
+        "
+        @group(0) @binding(0) var t: texture_2d<f32>;
+        @group(0) @binding(1) var s: sampler;
+        @fragment
+        fn main() -> @location(0) vec4<f32> {
+            return textureSample(t, s, vec2(0.0), vec2(0, 8));
+        }
+        ",
+        "
+        @group(0) @binding(0) var t: texture_2d<f32>;
+        @group(0) @binding(1) var s: sampler;
+        @fragment
+        fn main() -> @location(0) vec4<f32> {
+            return textureSampleLevel(t, s, vec2(0.0), 0.0, vec2(-9));
+        }
+        ":
+        Err(naga::valid::ValidationError::EntryPoint {
+            source: naga::valid::EntryPointError::Function(
+                naga::valid::FunctionError::Expression {
+                    source: naga::valid::ExpressionError::SampleOffsetOutOfRange(_, 8 | -9),
+                    ..
+                }
+            ),
+            ..
+        })
+    }
+}
@@ -76,9 +76,13 @@ fn collect_validation_jobs(jobs: &mut Vec<Job>, cmd: ValidateSubcommand) -> anyh
             let spirv_val = "spirv-val";
             which(spirv_val)?;
 
-            push_job_for_each_file(snapshots_base_out, "spv/*.spvasm", jobs, |path| {
+            push_job_for_each_file(&snapshots_base_out, "spv/*.spvasm", jobs, |path| {
                 validate_spirv(&path, spirv_as, spirv_val)
             });
+            // Binary snapshots, for instructions the disassembler doesn't know.
+            push_job_for_each_file(snapshots_base_out, "spv/*.spv", jobs, |path| {
+                EasyCommand::new(spirv_val, |cmd| cmd.arg(&path)).success()
+            });
         }
         ValidateSubcommand::Metal => {
             let xcrun = "xcrun";
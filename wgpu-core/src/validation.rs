@@ -895,7 +895,7 @@ impl Interface {
                 naga::TypeInner::Array { stride, size, .. } => {
                     let size = match size {
                         naga::ArraySize::Constant(size) => size.get() * stride,
-                        naga::ArraySize::Dynamic => stride,
+                        naga::ArraySize::Pending(_) | naga::ArraySize::Dynamic => stride,
                     };
                     ResourceType::Buffer {
                         size: wgt::BufferSize::new(size as u64).unwrap(),
@@ -925,6 +925,31 @@ impl Interface {
             for arg in entry_point.function.arguments.iter() {
                 Self::populate(&mut ep.inputs, arg.binding.as_ref(), arg.ty, &module.types);
             }
+            // Matrix and array vertex inputs are fed one column or element
+            // per location.
+            for split in info.split_vertex_inputs.iter() {
+                let dim = match split.ty {
+                    naga::TypeInner::Scalar(_) => NumericDimension::Scalar,
+                    naga::TypeInner::Vector { size, .. } => NumericDimension::Vector(size),
+                    _ => continue,
+                };
+                let Some(scalar) = split.ty.scalar() else {
+                    continue;
+                };
+                ep.inputs.retain(|varying| {
+                    !matches!(*varying, Varying::Local { location, .. } if location == split.location)
+                });
+                for location in split.location..split.location + split.count {
+                    ep.inputs.push(Varying::Local {
+                        location,
+                        iv: InterfaceVar {
+                            ty: NumericType { dim, scalar },
+                            interpolation: None,
+                            sampling: None,
+                        },
+                    });
+                }
+            }
             if let Some(ref result) = entry_point.function.result {
                 Self::populate(
                     &mut ep.outputs,
@@ -1152,7 +1177,9 @@ impl Interface {
                                             iv.ty.dim.num_components(),
                                         )
                                     }
-                                    naga::ShaderStage::Compute => (false, 0),
+                                    naga::ShaderStage::Compute
+                                    | naga::ShaderStage::Task
+                                    | naga::ShaderStage::Mesh => (false, 0),
                                 };
                                 if compatible {
                                     Ok(num_components)
@@ -1075,6 +1075,9 @@ impl crate::Device<super::Api> for super::Device {
                 special_constants_binding,
                 push_constants_target,
                 zero_initialize_workgroup_memory: true,
+                fold_clamp_to_saturate: false,
+                bounds_check_policies: naga::proc::BoundsCheckPolicies::default(),
+                override_values: Default::default(),
             },
         })
     }
@@ -99,7 +99,7 @@ impl CompilationContext<'_> {
                     assert_eq!(location.index, 0);
                     unsafe { gl.bind_frag_data_location(program, location.location, &name) }
                 }
-                naga::ShaderStage::Compute => {}
+                naga::ShaderStage::Compute | naga::ShaderStage::Task | naga::ShaderStage::Mesh => {}
             }
         }
 
@@ -173,6 +173,9 @@ impl super::Device {
             naga::ShaderStage::Vertex => glow::VERTEX_SHADER,
             naga::ShaderStage::Fragment => glow::FRAGMENT_SHADER,
             naga::ShaderStage::Compute => glow::COMPUTE_SHADER,
+            naga::ShaderStage::Task | naga::ShaderStage::Mesh => {
+                return Err(crate::PipelineError::EntryPoint(naga_stage));
+            }
         };
 
         let raw = unsafe { gl.create_shader(target) }.unwrap();
@@ -1166,6 +1169,9 @@ impl crate::Device<super::Api> for super::Device {
                 writer_flags,
                 binding_map,
                 zero_initialize_workgroup_memory: true,
+                max_loop_iterations: 1 << 31,
+                extensions: Vec::new(),
+                prologue: None,
             },
         })
     }
@@ -149,7 +149,7 @@ impl super::CommandState {
         stage: naga::ShaderStage,
         result_sizes: &'a mut Vec<u32>,
     ) -> Option<(u32, &'a [u32])> {
-        let stage_info = &self.stage_infos[stage];
+        let stage_info = self.stage_infos.get(stage)?;
         let slot = stage_info.sizes_slot?;
 
         result_sizes.clear();
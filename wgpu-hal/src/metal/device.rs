@@ -70,7 +70,10 @@ impl super::Device {
         let stage_bit = map_naga_stage(naga_stage);
 
         let module = &stage.module.naga.module;
-        let ep_resources = &layout.per_stage_map[naga_stage];
+        let ep_resources = layout
+            .per_stage_map
+            .get(naga_stage)
+            .ok_or(crate::PipelineError::EntryPoint(naga_stage))?;
 
         let bounds_check_policy = if stage.module.runtime_checks {
             naga::proc::BoundsCheckPolicy::ReadZeroSkipWrite
@@ -90,6 +93,7 @@ impl super::Device {
                 metal::MTLLanguageVersion::V2_4 => (2, 4),
             },
             inline_samplers: Default::default(),
+            constexpr_samplers: Default::default(),
             spirv_cross_compatibility: false,
             fake_missing_bindings: false,
             per_entry_point_map: naga::back::msl::EntryPointResourceMap::from([(
@@ -105,6 +109,12 @@ impl super::Device {
                 binding_array: naga::proc::BoundsCheckPolicy::Unchecked,
             },
             zero_initialize_workgroup_memory: true,
+            clamp_frag_depth: false,
+            fold_clamp_to_saturate: false,
+            force_bounded_loops: false,
+            max_loop_iterations: 1 << 31,
+            force_precise: false,
+            supports_primitive_id: self.shared.private_caps.supports_shader_primitive_index,
         };
 
         let pipeline_options = naga::back::msl::PipelineOptions {
@@ -686,6 +696,7 @@ impl crate::Device<super::Api> for super::Device {
                 .sizes_buffer
                 .map(|buffer_index| buffer_index as naga::back::msl::Slot),
             resources: info.resources,
+            conservative_depth: None,
         });
 
         Ok(super::PipelineLayout {
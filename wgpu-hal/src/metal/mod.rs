@@ -26,7 +26,7 @@ mod surface;
 mod time;
 
 use std::{
-    fmt, iter, ops,
+    fmt, iter,
     ptr::NonNull,
     sync::{atomic, Arc},
     thread,
@@ -527,18 +527,17 @@ const NAGA_STAGES: MultiStageData<naga::ShaderStage> = MultiStageData {
     cs: naga::ShaderStage::Compute,
 };
 
-impl<T> ops::Index<naga::ShaderStage> for MultiStageData<T> {
-    type Output = T;
-    fn index(&self, stage: naga::ShaderStage) -> &T {
+impl<T> MultiStageData<T> {
+    /// Return the data for `stage`, or `None` for task and mesh shaders,
+    /// which aren't supported.
+    fn get(&self, stage: naga::ShaderStage) -> Option<&T> {
         match stage {
-            naga::ShaderStage::Vertex => &self.vs,
-            naga::ShaderStage::Fragment => &self.fs,
-            naga::ShaderStage::Compute => &self.cs,
+            naga::ShaderStage::Vertex => Some(&self.vs),
+            naga::ShaderStage::Fragment => Some(&self.fs),
+            naga::ShaderStage::Compute => Some(&self.cs),
+            naga::ShaderStage::Task | naga::ShaderStage::Mesh => None,
         }
     }
-}
-
-impl<T> MultiStageData<T> {
     fn map_ref<Y>(&self, fun: impl Fn(&T) -> Y) -> MultiStageData<Y> {
         MultiStageData {
             vs: fun(&self.vs),